@@ -0,0 +1,153 @@
+//! Invokes the entry point of a runtime with arguments from the command line, which are converted
+//! to the types of the parameters in the reflected signature of the entry point.
+
+use failure::{err_msg, Error};
+use mun_abi::Reflection;
+use mun_runtime::{invoke_fn, Runtime};
+
+/// The maximum number of arguments that can be passed to an entry point.
+const MAX_ARGUMENTS: usize = 3;
+
+/// An argument of an entry point, converted to the type of its parameter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Argument {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+}
+
+/// Invokes the entry point with the arguments `$args`, and maps an invocation error to an `Error`.
+macro_rules! invoke {
+    ($runtime:ident, $entry_point:ident, ($($arg:expr),*)) => {
+        invoke_fn!(*$runtime, $entry_point $(, $arg)*).map_err(|e| err_msg(format!("{}", e)))
+    };
+}
+
+/// Invokes the entry point with the arguments `$args`, and returns the display of the value it
+/// returns, if it returns one.
+macro_rules! invoke_and_display {
+    ($runtime:ident, $entry_point:ident, $return_type:ident, $args:tt) => {
+        match &$return_type {
+            None => invoke!($runtime, $entry_point, $args).map(|()| None),
+            Some((guid, name)) => invoke_and_display!(
+                @typed $runtime, $entry_point, guid, name, $args,
+                bool, f32, f64, i8, i16, i32, i64, u8, u16, u32, u64
+            ),
+        }
+    };
+    (
+        @typed $runtime:ident, $entry_point:ident, $guid:ident, $name:ident, $args:tt,
+        $($T:ty),+
+    ) => {{
+        $(
+            if *$guid == <$T>::type_guid() {
+                let result: $T = invoke!($runtime, $entry_point, $args)?;
+                return Ok(Some(result.to_string()));
+            }
+        )+
+        Err(format_err!(
+            "Only native Mun return types are supported for entry points. Found: {}",
+            $name
+        ))
+    }};
+}
+
+/// Matches the types of the arguments `$next` and `$rest` one at a time, so the entry point is
+/// invoked with arguments of the matched types.
+macro_rules! dispatch {
+    ($runtime:ident, $entry_point:ident, $return_type:ident, [], ($($matched:expr),*)) => {
+        invoke_and_display!($runtime, $entry_point, $return_type, ($($matched),*))
+    };
+    (
+        $runtime:ident, $entry_point:ident, $return_type:ident,
+        [$next:expr $(, $rest:expr)*], ($($matched:expr),*)
+    ) => {
+        match $next {
+            Argument::Bool(value) => dispatch!(
+                $runtime, $entry_point, $return_type, [$($rest),*], ($($matched,)* value)
+            ),
+            Argument::Int(value) => dispatch!(
+                $runtime, $entry_point, $return_type, [$($rest),*], ($($matched,)* value)
+            ),
+            Argument::Float(value) => dispatch!(
+                $runtime, $entry_point, $return_type, [$($rest),*], ($($matched,)* value)
+            ),
+        }
+    };
+}
+
+/// Invokes the function `entry_point` of `runtime` with the command line arguments `args`. Returns
+/// the display of the value that the function returns, if it returns one. An error is returned if
+/// the arguments don't match the parameters of the function, or if the invocation fails, e.g.
+/// because the Mun code trapped.
+pub fn invoke_entry(
+    runtime: &mut Runtime,
+    entry_point: &str,
+    args: &[&str],
+) -> Result<Option<String>, Error> {
+    let fn_info = runtime
+        .get_function_info(entry_point)
+        .ok_or_else(|| format_err!("Failed to obtain entry point '{}'", entry_point))?;
+    let arguments = convert_arguments(fn_info.signature.arg_types(), entry_point, args)?;
+    let return_type = fn_info
+        .signature
+        .return_type()
+        .map(|ty| (ty.guid, ty.name().to_string()));
+
+    match arguments.as_slice() {
+        [] => dispatch!(runtime, entry_point, return_type, [], ()),
+        [a] => dispatch!(runtime, entry_point, return_type, [*a], ()),
+        [a, b] => dispatch!(runtime, entry_point, return_type, [*a, *b], ()),
+        [a, b, c] => dispatch!(runtime, entry_point, return_type, [*a, *b, *c], ()),
+        _ => Err(format_err!(
+            "Entry points with more than {} parameters are not supported",
+            MAX_ARGUMENTS
+        )),
+    }
+}
+
+/// Converts the command line arguments `args` of the entry point `entry_point` to the types of its
+/// parameters, `arg_types`. Only `bool`, `int` and `float` parameters are supported.
+fn convert_arguments(
+    arg_types: &[mun_abi::TypeInfo],
+    entry_point: &str,
+    args: &[&str],
+) -> Result<Vec<Argument>, Error> {
+    if arg_types.len() != args.len() {
+        return Err(format_err!(
+            "'{}' takes {} argument(s), but {} were given",
+            entry_point,
+            arg_types.len(),
+            args.len()
+        ));
+    }
+
+    arg_types
+        .iter()
+        .zip(args)
+        .enumerate()
+        .map(|(idx, (ty, arg))| {
+            let argument = if ty.guid == bool::type_guid() {
+                arg.parse().ok().map(Argument::Bool)
+            } else if ty.guid == i64::type_guid() {
+                arg.parse().ok().map(Argument::Int)
+            } else if ty.guid == f64::type_guid() {
+                arg.parse().ok().map(Argument::Float)
+            } else {
+                return Err(format_err!(
+                    "Only bool, int and float parameters are supported for entry points. Found: {}",
+                    ty.name()
+                ));
+            };
+            argument.ok_or_else(|| {
+                format_err!(
+                    "Argument {} of '{}' must be a {}, found '{}'",
+                    idx + 1,
+                    entry_point,
+                    ty.name(),
+                    arg
+                )
+            })
+        })
+        .collect()
+}
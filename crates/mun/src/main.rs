@@ -1,14 +1,76 @@
 #[macro_use]
 extern crate failure;
 
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
 use std::time::Duration;
 
 use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
-use mun_abi::Reflection;
-use mun_compiler::{host_triple, Config, PathOrInline, Target};
-use mun_runtime::{invoke_fn, Runtime, RuntimeBuilder};
+use mun_compiler::{
+    host_triple, stderr_color_choice, Artifact, BuildResult, Config, FloatSemantics,
+    IrVerification, LintLevel, LintLevels, Manifest, MessageFormat, PassPipeline, PathOrInline,
+    StandardStream, Target, MANIFEST_FILE_NAME,
+};
+use mun_compiler_daemon::CompilerDaemon;
+use mun_repl::{Evaluation, Session};
+use mun_runtime::{Runtime, RuntimeBuilder};
+use notify::{RecommendedWatcher, Watcher};
+
+mod entry;
+mod test_runner;
 
 fn main() -> Result<(), failure::Error> {
+    let input = Arg::with_name("INPUT")
+        .help("Sets the input file, package directory or `mun.toml` manifest to use (defaults to the manifest in the current directory)")
+        .index(1);
+    let target = Arg::with_name("target")
+        .long("target")
+        .takes_value(true)
+        .help("target triple for which code is compiled");
+    let dead_code_elimination = Arg::with_name("dead-code-elimination")
+        .long("dead-code-elimination")
+        .takes_value(true)
+        .possible_values(&["on", "off"])
+        .help("only compile the private functions that are reachable from the `export` functions (defaults to on)");
+    let tail_call_notes = Arg::with_name("tail-call-notes")
+        .long("tail-call-notes")
+        .takes_value(true)
+        .possible_values(&["on", "off"])
+        .help("report recursive calls that are not in tail position, and thus are not turned into a loop (defaults to off)");
+    let message_format = Arg::with_name("message-format")
+        .long("message-format")
+        .takes_value(true)
+        .possible_values(&["human", "json"])
+        .help("`human` writes diagnostics and the numbers of warnings and errors as text to stderr, `json` writes a JSON object per diagnostic and a final build status with the numbers to stdout, one per line (defaults to human)");
+    let allow = Arg::with_name("allow")
+        .short("A")
+        .long("allow")
+        .takes_value(true)
+        .multiple(true)
+        .number_of_values(1)
+        .value_name("LINT")
+        .help("do not report the lint, or any lint with `warnings`");
+    let warn = Arg::with_name("warn")
+        .short("W")
+        .long("warn")
+        .takes_value(true)
+        .multiple(true)
+        .number_of_values(1)
+        .value_name("LINT")
+        .help("report the lint as a warning, or every lint with `warnings`");
+    let deny = Arg::with_name("deny")
+        .short("D")
+        .long("deny")
+        .takes_value(true)
+        .multiple(true)
+        .number_of_values(1)
+        .value_name("LINT")
+        .help("report the lint as an error that fails the build, or every lint with `warnings`, e.g. `-D warnings`");
+    let deny_warnings = Arg::with_name("deny-warnings")
+        .long("deny-warnings")
+        .help("exit with a non-zero status if any warning is reported, the warnings are still reported as warnings");
+
     let matches = App::new("mun")
         .version(env!("CARGO_PKG_VERSION"))
         .author("The Mun Project Developers")
@@ -16,16 +78,16 @@ fn main() -> Result<(), failure::Error> {
         .setting(AppSettings::SubcommandRequiredElseHelp)
         .subcommand(
             SubCommand::with_name("build")
-                .arg(
-                    Arg::with_name("INPUT")
-                        .help("Sets the input file to use")
-                        .required(true)
-                        .index(1),
-                )
+                .arg(input.clone())
                 .arg(Arg::with_name("watch").long("watch").help(
                     "Run the compiler in watch mode.
                     Watch input files and trigger recompilation on changes.",
                 ))
+                .arg(
+                    Arg::with_name("timings")
+                        .long("timings")
+                        .help("report the duration of every phase of a build, and the number of files and functions that it processed again"),
+                )
                 .arg(
                     Arg::with_name("opt-level")
                         .short("O")
@@ -34,12 +96,178 @@ fn main() -> Result<(), failure::Error> {
                         .help("optimize with possible levels 0-3"),
                 )
                 .arg(
-                    Arg::with_name("target")
-                        .long("target")
+                    Arg::with_name("passes")
+                        .long("passes")
+                        .takes_value(true)
+                        .help("comma separated list of LLVM passes to run instead of the passes of the optimization level, e.g. `mem2reg,instcombine` (defaults to `default`)"),
+                )
+                .arg(
+                    Arg::with_name("overflow-checks")
+                        .long("overflow-checks")
+                        .takes_value(true)
+                        .possible_values(&["on", "off"])
+                        .help("trap when integer arithmetic overflows (defaults to on with opt-level 0, and off otherwise)"),
+                )
+                .arg(
+                    Arg::with_name("division-checks")
+                        .long("division-checks")
+                        .takes_value(true)
+                        .possible_values(&["on", "off"])
+                        .help("trap when an integer is divided by zero, without the checks dividing by zero has undefined behavior (defaults to on)"),
+                )
+                .arg(
+                    Arg::with_name("bounds-checks")
+                        .long("bounds-checks")
+                        .takes_value(true)
+                        .possible_values(&["on", "off"])
+                        .help("trap when an array is indexed out of bounds (defaults to on with opt-level 0, and off otherwise)"),
+                )
+                .arg(
+                    Arg::with_name("assertions")
+                        .long("assertions")
+                        .takes_value(true)
+                        .possible_values(&["on", "off"])
+                        .help("trap when an `assert` or `assert_eq` fails, without assertions they and their operands are compiled out, e.g. of release builds (defaults to on)"),
+                )
+                .arg(
+                    Arg::with_name("float-semantics")
+                        .long("float-semantics")
+                        .takes_value(true)
+                        .possible_values(&["strict", "fast"])
+                        .help("`strict` follows IEEE 754 for reproducible results, `fast` allows optimizations that assume floats are neither NaN nor infinite, after which operations on NaN or infinity have unspecified results (defaults to strict)"),
+                )
+                .arg(
+                    Arg::with_name("debug-info")
+                        .long("debug-info")
+                        .takes_value(true)
+                        .possible_values(&["on", "off"])
+                        .help("generate DWARF debug info, so breakpoints can be set by file and line in a native debugger (defaults to on with opt-level 0, and off otherwise)"),
+                )
+                .arg(
+                    Arg::with_name("verify-ir")
+                        .long("verify-ir")
+                        .takes_value(true)
+                        .possible_values(&["on", "off", "dump"])
+                        .help("report invalid generated IR as an internal compiler error, `dump` also writes the IR next to the module (defaults to on in debug builds of the compiler)"),
+                )
+                .arg(
+                    Arg::with_name("codegen-units")
+                        .long("codegen-units")
+                        .takes_value(true)
+                        .help("maximum number of units that are optimized and compiled to machine code in parallel, only applies when no other artifact than the library is emitted (defaults to 1)"),
+                )
+                .arg(
+                    Arg::with_name("emit")
+                        .long("emit")
+                        .takes_value(true)
+                        .multiple(true)
+                        .use_delimiter(true)
+                        .possible_values(&["lib", "obj", "asm", "ir", "ir-unopt", "metadata"])
+                        .help("comma separated list of artifacts to write next to the module (defaults to lib), `metadata` writes the exports of the module as JSON to `<name>.mun.json`"),
+                )
+                .arg(target.clone())
+                .arg(dead_code_elimination.clone())
+                .arg(tail_call_notes.clone())
+                .arg(message_format.clone())
+                .arg(allow.clone())
+                .arg(warn.clone())
+                .arg(deny.clone())
+                .arg(deny_warnings.clone())
+                .about("Compiles a local Mun file or package into a module. A package with a `mun.toml` manifest is built after its dependencies, into its `target` directory"),
+        )
+        .subcommand(
+            SubCommand::with_name("run")
+                .arg(input.clone())
+                .arg(Arg::with_name("watch").long("watch").help(
+                    "recompile the input on every change, and invoke the entry point again after the runtime reloaded it",
+                ))
+                .arg(
+                    Arg::with_name("entry")
+                        .long("entry")
+                        .takes_value(true)
+                        .help("the function entry point to invoke (defaults to `main`)"),
+                )
+                .arg(
+                    Arg::with_name("ARGS")
+                        .help("Sets the arguments of the entry point, which are converted to the types of its parameters")
+                        .multiple(true)
+                        .last(true),
+                )
+                .about("Compiles a local Mun file or package, invokes its entry point and prints the value it returns. Exits with a non-zero status if the input has errors or the invocation fails, e.g. because the code trapped"),
+        )
+        .subcommand(
+            SubCommand::with_name("test")
+                .arg(input.clone())
+                .arg(
+                    Arg::with_name("FILTER")
+                        .help("Only runs the tests of which the qualified name contains the filter")
+                        .index(2),
+                )
+                .arg(target.clone())
+                .arg(allow.clone())
+                .arg(warn.clone())
+                .arg(deny.clone())
+                .about("Compiles a local Mun file or package with its `#[test]` functions, and runs the tests one after another. Exits with a non-zero status if the input has errors or a test failed, e.g. because it trapped"),
+        )
+        .subcommand(
+            SubCommand::with_name("check")
+                .arg(input)
+                .arg(target)
+                .arg(dead_code_elimination)
+                .arg(tail_call_notes)
+                .arg(message_format)
+                .arg(allow)
+                .arg(warn)
+                .arg(deny)
+                .arg(deny_warnings)
+                .about("Checks a local Mun file or package and the packages it depends on for errors, without generating code. Exits with a non-zero status if there are errors, or warnings with `--deny-warnings`"),
+        )
+        .subcommand(
+            SubCommand::with_name("fmt")
+                .arg(
+                    Arg::with_name("INPUT")
+                        .help("Sets the files, package directories or `mun.toml` manifests to format (defaults to the manifest in the current directory)")
+                        .multiple(true)
+                        .index(1),
+                )
+                .arg(Arg::with_name("check").long("check").help(
+                    "only report the files that are not formatted instead of rewriting them, and exit with a non-zero status if there are any",
+                ))
+                .about("Formats local Mun files or the source files of packages in the canonical style. Files with syntax errors are left untouched"),
+        )
+        .subcommand(
+            SubCommand::with_name("eval")
+                .arg(
+                    Arg::with_name("EXPR")
+                        .help("Sets the expression to evaluate (defaults to reading inputs from stdin, one after another)")
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("file")
+                        .long("file")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("a Mun file of which the items are defined before the first input"),
+                )
+                .about("Evaluates Mun expressions and prints their values. Inputs that define items, e.g. functions, keep them available to later inputs"),
+        )
+        .subcommand(
+            SubCommand::with_name("dump")
+                .arg(
+                    Arg::with_name("INPUT")
+                        .help("Sets the Mun file to dump")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("stage")
+                        .long("stage")
                         .takes_value(true)
-                        .help("target triple for which code is compiled"),
+                        .possible_values(&["ast", "hir", "types"])
+                        .help("`ast` dumps the syntax tree with the range of every node, `hir` the lowered bodies of the items, and `types` the lowered bodies with the inferred type of every expression (defaults to ast)"),
                 )
-                .about("Compiles a local Mun file into a module"),
+                .about("Prints a compiler stage of a local Mun file as a readable tree, to debug the compiler. Nodes that could not be parsed are marked as `ERROR`"),
         )
         .subcommand(
             SubCommand::with_name("start")
@@ -66,6 +294,12 @@ fn main() -> Result<(), failure::Error> {
 
     match matches.subcommand() {
         ("build", Some(matches)) => build(matches)?,
+        ("run", Some(matches)) => run(matches)?,
+        ("test", Some(matches)) => test(matches)?,
+        ("check", Some(matches)) => check(matches)?,
+        ("fmt", Some(matches)) => fmt(matches)?,
+        ("eval", Some(matches)) => eval(matches)?,
+        ("dump", Some(matches)) => dump(matches)?,
         ("start", Some(matches)) => start(matches)?,
         _ => unreachable!(),
     }
@@ -73,77 +307,458 @@ fn main() -> Result<(), failure::Error> {
     Ok(())
 }
 
-/// Build the source file specified
+/// Build the source file specified, and exits with a non-zero status if it has errors
 fn build(matches: &ArgMatches) -> Result<(), failure::Error> {
-    let options = compiler_options(matches)?;
-    if matches.is_present("watch") {
-        mun_compiler_daemon::main(options)
+    let manifest = manifest(matches)?;
+    let options = compiler_options(matches, manifest.as_ref())?;
+    if options.config.message_format == MessageFormat::Json && matches.is_present("watch") {
+        return Err(format_err!(
+            "The JSON message format is not supported in watch mode"
+        ));
+    }
+    if let Some(manifest) = manifest {
+        if matches.is_present("watch") {
+            return Err(format_err!(
+                "Watch mode is not supported for packages with a manifest"
+            ));
+        }
+        let result = mun_compiler::build_package(&manifest, options.config)?;
+        report_timings(matches, &result);
+        exit_on_failure(&result);
+        Ok(())
+    } else if matches.is_present("watch") {
+        mun_compiler_daemon::main(options, matches.is_present("timings"))
     } else {
-        mun_compiler::main(options).map(|_| {})
+        let result = mun_compiler::main(options)?;
+        report_timings(matches, &result);
+        exit_on_failure(&result);
+        Ok(())
+    }
+}
+
+/// Exits with a non-zero status if the build `result` failed, e.g. because a lint is denied.
+fn exit_on_failure(result: &BuildResult) {
+    if !result.success {
+        std::process::exit(1);
+    }
+}
+
+/// Writes the duration of every phase of the build `result` to stderr, if timings were requested.
+fn report_timings(matches: &ArgMatches, result: &BuildResult) {
+    if matches.is_present("timings") {
+        for phase in result.phases.iter() {
+            eprintln!("  {}", phase);
+        }
     }
 }
 
+/// Compiles the input and invokes its entry point with the arguments that follow `--`. In watch
+/// mode the input is recompiled on every change, and the entry point is invoked again after every
+/// reload of the assembly.
+fn run(matches: &ArgMatches) -> Result<(), failure::Error> {
+    let manifest = manifest(matches)?;
+    let options = compiler_options(matches, manifest.as_ref())?;
+    let entry_point = matches.value_of("entry").unwrap_or("main");
+    let args: Vec<&str> = matches
+        .values_of("ARGS")
+        .map(|values| values.collect())
+        .unwrap_or_default();
+
+    if matches.is_present("watch") {
+        if manifest.is_some() {
+            return Err(format_err!(
+                "Watch mode is not supported for packages with a manifest"
+            ));
+        }
+        return run_watch(options, entry_point, &args);
+    }
+
+    let result = match manifest {
+        Some(manifest) => mun_compiler::build_package(&manifest, options.config)?,
+        None => mun_compiler::main(options)?,
+    };
+    // Without an assembly the errors of the input have been reported
+    let assembly_path = match result.library_path() {
+        Some(assembly_path) => assembly_path,
+        None => std::process::exit(1),
+    };
+
+    let mut runtime = RuntimeBuilder::new(assembly_path).spawn()?;
+    if let Some(value) = entry::invoke_entry(&mut runtime, entry_point, &args)? {
+        println!("{}", value);
+    }
+    Ok(())
+}
+
+/// Compiles the input on every change, and invokes its entry point after every reload of the
+/// assembly by the runtime. A failed invocation is reported, but doesn't stop watching.
+fn run_watch(
+    options: mun_compiler::CompilerOptions,
+    entry_point: &str,
+    args: &[&str],
+) -> Result<(), failure::Error> {
+    let mut daemon = CompilerDaemon::new(options)?;
+    let mut writer = StandardStream::stderr(stderr_color_choice());
+    if !daemon.build(&mut writer)? {
+        std::process::exit(1);
+    }
+
+    let mut runtime = RuntimeBuilder::new(daemon.assembly_path()).spawn()?;
+    let invoke = |runtime: &mut Runtime| match entry::invoke_entry(runtime, entry_point, args) {
+        Ok(Some(value)) => println!("{}", value),
+        Ok(None) => {}
+        Err(e) => eprintln!("error: {}", e),
+    };
+    invoke(&mut runtime);
+
+    let (tx, rx) = channel();
+    let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_millis(10))?;
+    watcher.watch(daemon.input_path(), daemon.recursive_mode())?;
+    loop {
+        match rx.recv_timeout(Duration::from_millis(10)) {
+            Ok(event) => {
+                if daemon.handle_event(&event)? {
+                    daemon.build(&mut writer)?;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => {
+                return Err(format_err!("The file watcher stopped"));
+            }
+        }
+        if runtime.update() {
+            invoke(&mut runtime);
+        }
+    }
+}
+
+/// Compiles the input with its tests into a temporary directory, and runs the tests of which the
+/// name contains the filter. Exits with a non-zero status if the input has errors or a test failed.
+fn test(matches: &ArgMatches) -> Result<(), failure::Error> {
+    let manifest = manifest(matches)?;
+    let mut options = compiler_options(matches, manifest.as_ref())?;
+    if let Some(manifest) = manifest {
+        if manifest.dependency_names().next().is_some() {
+            return Err(format_err!(
+                "Testing packages with dependencies is not supported"
+            ));
+        }
+        options.input = PathOrInline::Path(manifest.source_dir());
+    }
+
+    // The test build doesn't replace the assembly of a regular build
+    let out_dir = tempfile::tempdir()?;
+    options.config.out_dir = Some(out_dir.path().to_path_buf());
+    let (result, tests) = mun_compiler::build_tests(options)?;
+    let passed = match result.library_path() {
+        Some(assembly_path) => {
+            let mut runtime = RuntimeBuilder::new(assembly_path).spawn()?;
+            test_runner::run_tests(&mut runtime, &tests, matches.value_of("FILTER"))
+        }
+        // Without an assembly the errors of the input have been reported
+        None => false,
+    };
+
+    out_dir.close()?;
+    if !passed {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Checks the source file specified for errors, and exits with a non-zero status if there are any
+fn check(matches: &ArgMatches) -> Result<(), failure::Error> {
+    let manifest = manifest(matches)?;
+    let options = compiler_options(matches, manifest.as_ref())?;
+    let success = match manifest {
+        Some(manifest) => mun_compiler::check_package(&manifest, options.config)?,
+        None => mun_compiler::check(options)?,
+    };
+    if !success {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Formats the source files specified, and exits with a non-zero status if a file could not be
+/// formatted or, in check mode, is not formatted
+fn fmt(matches: &ArgMatches) -> Result<(), failure::Error> {
+    // The source files of a package are formatted, but not those of its dependencies
+    let paths = match matches.values_of("INPUT") {
+        Some(inputs) => inputs
+            .map(|input| -> Result<PathBuf, failure::Error> {
+                let input = Path::new(input);
+                if input.file_name().and_then(|name| name.to_str()) == Some(MANIFEST_FILE_NAME) {
+                    Ok(Manifest::from_file(input)?.source_dir())
+                } else if input.join(MANIFEST_FILE_NAME).is_file() {
+                    Ok(Manifest::from_dir(input)?.source_dir())
+                } else {
+                    Ok(input.to_path_buf())
+                }
+            })
+            .collect::<Result<_, _>>()?,
+        None => vec![Manifest::from_dir(&std::env::current_dir()?)?.source_dir()],
+    };
+    if !mun_compiler::format(&paths, matches.is_present("check"))? {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Evaluates the expression that is specified, or the inputs that are read from stdin until its
+/// end. An input continues on the next line until all of its delimiters are closed.
+fn eval(matches: &ArgMatches) -> Result<(), failure::Error> {
+    let mut session = Session::new()?;
+    let mut writer = StandardStream::stderr(stderr_color_choice());
+    if let Some(files) = matches.values_of("file") {
+        for file in files {
+            let text = std::fs::read_to_string(file)?;
+            if session.eval(&text, &mut writer)? != Evaluation::Defined {
+                return Err(format_err!("Failed to define the items of '{}'", file));
+            }
+        }
+    }
+
+    // Prints the value of an evaluation, if there is one
+    let print_value = |evaluation: &Evaluation| {
+        if let Evaluation::Value { value, ty } = evaluation {
+            println!("{}: {}", value, ty);
+        }
+    };
+
+    if let Some(expr) = matches.value_of("EXPR") {
+        let evaluation = session.eval(expr, &mut writer)?;
+        print_value(&evaluation);
+        if evaluation == Evaluation::Failed {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let stdin = io::stdin();
+    let mut input = String::new();
+    loop {
+        print!("{}", if input.is_empty() { "> " } else { ". " });
+        io::stdout().flush()?;
+        if stdin.lock().read_line(&mut input)? == 0 {
+            return Ok(());
+        }
+        if !mun_repl::is_complete(&input) {
+            continue;
+        }
+
+        if !input.trim().is_empty() {
+            // An input that fails doesn't end the session
+            match session.eval(input.trim(), &mut writer) {
+                Ok(evaluation) => print_value(&evaluation),
+                Err(e) => eprintln!("error: {}", e),
+            }
+        }
+        input.clear();
+    }
+}
+
+/// Prints the compiler stage that is specified of the input file, even if the file has errors
+fn dump(matches: &ArgMatches) -> Result<(), failure::Error> {
+    let input = PathOrInline::Path(matches.value_of("INPUT").unwrap().into());
+    let (driver, file_id) = mun_compiler::Driver::with_file(Config::default(), input)?;
+    let dump = match matches.value_of("stage") {
+        Some("hir") => driver.dump_hir(file_id),
+        Some("types") => driver.dump_types(file_id),
+        _ => driver.dump_ast(file_id),
+    };
+    print!("{}", dump);
+    Ok(())
+}
+
 /// Starts the runtime with the specified library and invokes function `entry`.
 fn start(matches: &ArgMatches) -> Result<(), failure::Error> {
     let mut runtime = runtime(matches)?;
 
     let entry_point = matches.value_of("entry").unwrap_or("main");
-    let fn_info = runtime.get_function_info(entry_point).ok_or_else(|| {
-        std::io::Error::new(
-            std::io::ErrorKind::InvalidInput,
-            format!("Failed to obtain entry point '{}'", entry_point),
-        )
-    })?;
-
-    if let Some(ret_type) = fn_info.signature.return_type() {
-        let type_guid = ret_type.guid;
-        if type_guid == bool::type_guid() {
-            let result: bool =
-                invoke_fn!(runtime, entry_point).map_err(|e| failure::err_msg(format!("{}", e)))?;
-
-            println!("{}", result)
-        } else if type_guid == f64::type_guid() {
-            let result: f64 =
-                invoke_fn!(runtime, entry_point).map_err(|e| failure::err_msg(format!("{}", e)))?;
-
-            println!("{}", result)
-        } else if type_guid == i64::type_guid() {
-            let result: i64 =
-                invoke_fn!(runtime, entry_point).map_err(|e| failure::err_msg(format!("{}", e)))?;
-
-            println!("{}", result)
-        } else {
-            return Err(failure::err_msg(format!(
-                "Only native Mun return types are supported for entry points. Found: {}",
-                ret_type.name()
-            )));
-        };
-        Ok(())
+    if let Some(value) = entry::invoke_entry(&mut runtime, entry_point, &[])? {
+        println!("{}", value);
+    }
+    Ok(())
+}
+
+/// Returns the manifest of the package that is built, if the input is a `mun.toml` manifest or a
+/// directory that contains one. Without an input, the manifest in the current directory is built.
+fn manifest(matches: &ArgMatches) -> Result<Option<Manifest>, failure::Error> {
+    let input = match matches.value_of("INPUT") {
+        Some(input) => Path::new(input),
+        None => return Ok(Some(Manifest::from_dir(&std::env::current_dir()?)?)),
+    };
+    if input.file_name().and_then(|name| name.to_str()) == Some(MANIFEST_FILE_NAME) {
+        Ok(Some(Manifest::from_file(input)?))
+    } else if input.join(MANIFEST_FILE_NAME).is_file() {
+        Ok(Some(Manifest::from_dir(input)?))
     } else {
-        #[allow(clippy::unit_arg)]
-        invoke_fn!(runtime, entry_point).map_err(|e| failure::err_msg(format!("{}", e)))
+        Ok(None)
     }
 }
 
-fn compiler_options(matches: &ArgMatches) -> Result<mun_compiler::CompilerOptions, failure::Error> {
+/// Constructs the compiler options from the command line arguments. The build settings of the
+/// `manifest` apply to the options that are not specified on the command line.
+fn compiler_options(
+    matches: &ArgMatches,
+    manifest: Option<&Manifest>,
+) -> Result<mun_compiler::CompilerOptions, failure::Error> {
     let optimization_lvl = match matches.value_of("opt-level") {
         Some("0") => mun_compiler::OptimizationLevel::None,
         Some("1") => mun_compiler::OptimizationLevel::Less,
-        None | Some("2") => mun_compiler::OptimizationLevel::Default,
+        Some("2") => mun_compiler::OptimizationLevel::Default,
         Some("3") => mun_compiler::OptimizationLevel::Aggressive,
+        None => manifest
+            .and_then(Manifest::optimization_lvl)
+            .unwrap_or(mun_compiler::OptimizationLevel::Default),
         _ => return Err(format_err!("Only optimization levels 0-3 are supported")),
     };
 
+    let pass_pipeline = match matches.value_of("passes") {
+        Some(passes) => passes.parse::<PassPipeline>()?,
+        None => PassPipeline::Default,
+    };
+
+    // Debug builds trap on overflow, whereas optimized builds wrap around
+    let overflow_checks = match matches.value_of("overflow-checks") {
+        Some("on") => true,
+        Some("off") => false,
+        _ => optimization_lvl == mun_compiler::OptimizationLevel::None,
+    };
+
+    let division_checks = matches.value_of("division-checks") != Some("off");
+
+    // Like overflow checks, bounds checks are only enabled in debug builds by default
+    let bounds_checks = match matches.value_of("bounds-checks") {
+        Some("on") => true,
+        Some("off") => false,
+        _ => optimization_lvl == mun_compiler::OptimizationLevel::None,
+    };
+
+    let assertions = matches.value_of("assertions") != Some("off");
+
+    let float_semantics = match matches.value_of("float-semantics") {
+        Some("fast") => FloatSemantics::Fast,
+        _ => FloatSemantics::Strict,
+    };
+
+    // Debug info is generated for debug builds by default, whose code maps directly to the source
+    let debug_info = match matches.value_of("debug-info") {
+        Some("on") => Some(true),
+        Some("off") => Some(false),
+        _ => None,
+    };
+
+    let ir_verification = match matches.value_of("verify-ir") {
+        Some("on") => IrVerification::On,
+        Some("off") => IrVerification::Off,
+        Some("dump") => IrVerification::Dump,
+        _ => Config::default().ir_verification,
+    };
+
+    let codegen_units = match matches.value_of("codegen-units") {
+        Some(units) => units
+            .parse::<usize>()
+            .ok()
+            .filter(|units| *units > 0)
+            .ok_or_else(|| format_err!("The number of codegen units must be a positive integer"))?,
+        None => 1,
+    };
+
+    let dead_code_elimination = matches.value_of("dead-code-elimination") != Some("off");
+
+    let tail_call_notes = matches.value_of("tail-call-notes") == Some("on");
+
+    let emit = match matches.values_of("emit") {
+        Some(values) => values
+            .map(|value| match value {
+                "lib" => Artifact::Lib,
+                "obj" => Artifact::Obj,
+                "asm" => Artifact::Asm,
+                "ir" => Artifact::Ir,
+                "ir-unopt" => Artifact::UnoptimizedIr,
+                "metadata" => Artifact::Metadata,
+                _ => unreachable!("clap only accepts the possible values"),
+            })
+            .collect(),
+        None => vec![Artifact::Lib],
+    };
+
+    let message_format = match matches.value_of("message-format") {
+        Some("json") => MessageFormat::Json,
+        _ => MessageFormat::Human,
+    };
+
+    let target = matches
+        .value_of("target")
+        .or_else(|| manifest.and_then(Manifest::target))
+        .unwrap_or_else(|| host_triple());
+
+    let input = match manifest {
+        Some(manifest) => manifest.dir().to_path_buf(),
+        None => matches
+            .value_of("INPUT")
+            .ok_or_else(|| format_err!("No input file or package directory specified"))?
+            .into(),
+    };
+
     Ok(mun_compiler::CompilerOptions {
-        input: PathOrInline::Path(matches.value_of("INPUT").unwrap().into()), // Safe because its a required arg
+        input: PathOrInline::Path(input),
         config: Config {
-            target: Target::search(matches.value_of("target").unwrap_or_else(|| host_triple()))?,
+            target: Target::search(target)?,
             optimization_lvl,
+            pass_pipeline,
+            call_mode: mun_compiler::CallMode::Dispatch,
+            overflow_checks,
+            division_checks,
+            bounds_checks,
+            assertions,
+            float_semantics,
+            debug_info,
+            ir_verification,
+            codegen_units,
+            dead_code_elimination,
+            tail_call_notes,
+            lint_levels: lint_levels(matches),
+            deny_warnings: matches.is_present("deny-warnings"),
+            test_build: false,
             out_dir: None,
+            emit,
+            dependencies: Vec::new(),
+            message_format,
         },
     })
 }
 
+/// Returns the lint levels that are set with `--allow`, `--warn` and `--deny`. A later flag
+/// overrides an earlier one for the same lint, e.g. `-D warnings -A dead_code` reports all lints
+/// except `dead_code` as errors. Unknown lints are reported as warnings.
+fn lint_levels(matches: &ArgMatches) -> LintLevels {
+    let mut flags = Vec::new();
+    for (arg, level) in [
+        ("allow", LintLevel::Allow),
+        ("warn", LintLevel::Warn),
+        ("deny", LintLevel::Deny),
+    ]
+    .iter()
+    {
+        if let (Some(indices), Some(names)) = (matches.indices_of(arg), matches.values_of(arg)) {
+            let named = names.map(|name| (*level, name));
+            flags.extend(indices.zip(named));
+        }
+    }
+    flags.sort_by_key(|(index, _)| *index);
+
+    let mut levels = LintLevels::default();
+    for (_, (level, name)) in flags {
+        if !levels.set_by_name(name, level) {
+            eprintln!("warning: unknown lint `{}`", name);
+        }
+    }
+    levels
+}
+
 fn runtime(matches: &ArgMatches) -> Result<Runtime, failure::Error> {
     let mut builder = RuntimeBuilder::new(
         matches.value_of("LIBRARY").unwrap(), // Safe because its a required arg
@@ -0,0 +1,58 @@
+//! Runs the `#[test]` functions of a test build one after another, and reports their outcomes in
+//! the style of `cargo test`. A test fails if invoking it returns an error, e.g. because it
+//! trapped.
+
+use mun_runtime::{invoke_fn, Runtime};
+use std::time::Instant;
+
+/// Runs the tests of which the qualified name contains `filter`, or all tests without a filter,
+/// and prints the outcome of every test and a summary to stdout. Returns whether all tests that
+/// ran passed.
+pub fn run_tests(runtime: &mut Runtime, tests: &[String], filter: Option<&str>) -> bool {
+    let selected: Vec<&String> = tests
+        .iter()
+        .filter(|name| filter.map_or(true, |filter| name.contains(filter)))
+        .collect();
+    let filtered_out = tests.len() - selected.len();
+
+    println!();
+    println!(
+        "running {} test{}",
+        selected.len(),
+        if selected.len() == 1 { "" } else { "s" }
+    );
+
+    let start = Instant::now();
+    let mut failures = Vec::new();
+    for name in selected.iter() {
+        let test_start = Instant::now();
+        let result: Result<(), _> = invoke_fn!(*runtime, name.as_str());
+        match result {
+            Ok(()) => println!("test {} ... ok ({:.2?})", name, test_start.elapsed()),
+            Err(e) => {
+                println!("test {} ... FAILED", name);
+                failures.push((name, e.to_string()));
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        println!();
+        println!("failures:");
+        for (name, message) in failures.iter() {
+            println!("    {}: {}", name, message);
+        }
+    }
+
+    let passed = selected.len() - failures.len();
+    println!();
+    println!(
+        "test result: {}. {} passed; {} failed; {} filtered out; finished in {:.2?}",
+        if failures.is_empty() { "ok" } else { "FAILED" },
+        passed,
+        failures.len(),
+        filtered_out,
+        start.elapsed()
+    );
+    failures.is_empty()
+}
@@ -0,0 +1,15 @@
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn dump_types() {
+    let output = Command::new(env!("CARGO_BIN_EXE_mun"))
+        .args(&["dump", "--stage", "types"])
+        .arg(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/main.mun"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("fn main [0; 31)\n"));
+    assert!(stdout.contains("  Block [22; 31): int\n    Literal 42 [28; 30): int\n"));
+}
@@ -0,0 +1,112 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Returns the path of the fixture `name`.
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+/// Runs the subcommand `subcommand` on the fixture `name` with the arguments `args`, in a
+/// temporary directory.
+fn mun(subcommand: &str, name: &str, args: &[&str]) -> Output {
+    let cwd = tempfile::tempdir().unwrap();
+    Command::new(env!("CARGO_BIN_EXE_mun"))
+        .arg(subcommand)
+        .arg(fixture(name))
+        .args(args)
+        .current_dir(cwd.path())
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn warnings_dont_fail_a_check() {
+    let output = mun("check", "warnings.mun", &[]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(stderr.matches("warning[W0003]").count(), 1, "{}", stderr);
+    assert!(
+        stderr.ends_with("finished with 1 warning, 0 errors\n"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn denied_warnings_fail_the_build() {
+    for subcommand in ["check", "build"].iter() {
+        let output = mun(subcommand, "warnings.mun", &["-D", "warnings"]);
+        assert_eq!(output.status.code(), Some(1));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // The attribute of `allowed` overrides the command line
+        assert_eq!(stderr.matches("error[W0003]").count(), 1, "{}", stderr);
+        assert!(!stderr.contains("warning["), "{}", stderr);
+        assert!(
+            stderr.ends_with("finished with 0 warnings, 1 error\n"),
+            "{}",
+            stderr
+        );
+    }
+}
+
+#[test]
+fn deny_warnings_fails_on_warnings() {
+    for subcommand in ["check", "build"].iter() {
+        let output = mun(subcommand, "warnings.mun", &["--deny-warnings"]);
+        assert_eq!(output.status.code(), Some(1));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // The warnings are still reported as warnings, except for the allowed one
+        assert_eq!(stderr.matches("warning[W0003]").count(), 1, "{}", stderr);
+        assert!(
+            stderr.ends_with("finished with 1 warning, 0 errors\n"),
+            "{}",
+            stderr
+        );
+    }
+}
+
+#[test]
+fn json_summary() {
+    for (args, success) in [
+        (&["--message-format", "json"][..], true),
+        (&["--message-format", "json", "--deny-warnings"][..], false),
+    ]
+    .iter()
+    {
+        let output = mun("check", "warnings.mun", args);
+        assert_eq!(output.status.success(), *success);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let summary = format!(
+            r#"{{"type":"build_finished","success":{},"artifact":null,"warnings":1,"errors":0}}"#,
+            success
+        );
+        assert_eq!(stdout.lines().last(), Some(summary.as_str()), "{}", stdout);
+    }
+}
+
+#[test]
+fn later_lint_flags_override_earlier_ones() {
+    let output = mun(
+        "check",
+        "warnings.mun",
+        &["--deny", "warnings", "--allow", "unused_variables"],
+    );
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn unknown_lint_flags_are_warnings() {
+    let output = mun(
+        "check",
+        "warnings.mun",
+        &["-A", "unused_variables", "-D", "unused"],
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "warning: unknown lint `unused`\n"
+    );
+}
@@ -0,0 +1,63 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Returns the path of the fixture `name`.
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+/// Runs `mun run` on the fixture `name` with the arguments `args`, in a temporary directory to
+/// which the assembly is written.
+fn mun_run(name: &str, args: &[&str]) -> Output {
+    let out_dir = tempfile::tempdir().unwrap();
+    Command::new(env!("CARGO_BIN_EXE_mun"))
+        .arg("run")
+        .arg(fixture(name))
+        .args(args)
+        .current_dir(out_dir.path())
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn run_main() {
+    let output = mun_run("main.mun", &[]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "42\n");
+}
+
+#[test]
+fn run_entry_with_arguments() {
+    let output = mun_run("main.mun", &["--entry", "scale", "--", "4", "0.5", "true"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "2\n");
+}
+
+#[test]
+fn run_entry_that_traps() {
+    let output = mun_run("main.mun", &["--entry", "divide", "--", "1", "0"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("trapped"));
+}
+
+#[test]
+fn run_entry_with_invalid_arguments() {
+    let output = mun_run("main.mun", &["--entry", "scale", "--", "4", "half", "true"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Argument 2 of 'scale' must be a @core::float, found 'half'"));
+
+    let output = mun_run("main.mun", &["--entry", "divide", "--", "1"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("'divide' takes 2 argument(s), but 1 were given"));
+}
+
+#[test]
+fn run_with_compile_errors() {
+    let output = mun_run("broken.mun", &[]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+}
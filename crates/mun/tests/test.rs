@@ -0,0 +1,67 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Returns the path of the fixture `name`.
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+/// Runs `mun test` on the fixture `name` with the arguments `args`, in a temporary directory.
+fn mun_test(name: &str, args: &[&str]) -> Output {
+    let cwd = tempfile::tempdir().unwrap();
+    Command::new(env!("CARGO_BIN_EXE_mun"))
+        .arg("test")
+        .arg(fixture(name))
+        .args(args)
+        .current_dir(cwd.path())
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_reports_failed_tests() {
+    let output = mun_test("tests.mun", &[]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("running 2 tests"), "{}", stdout);
+    assert!(stdout.contains("test adds_numbers ... ok"), "{}", stdout);
+    assert!(
+        stdout.contains("test divides_by_zero ... FAILED"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains(
+            "divides_by_zero: Script trapped while invoking 'divides_by_zero': division by zero in 'divide' at tests.mun:2"
+        ),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("test result: FAILED. 1 passed; 1 failed; 0 filtered out"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_with_filter() {
+    let output = mun_test("tests.mun", &["adds"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("running 1 test\n"), "{}", stdout);
+    assert!(
+        stdout.contains("test result: ok. 1 passed; 0 failed; 1 filtered out"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_with_compile_errors() {
+    let output = mun_test("broken.mun", &[]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+}
@@ -1,11 +1,26 @@
-use std::env;
-use std::path::PathBuf;
+//! `src/autogen.rs` is generated from `include/mun_abi.h` and committed alongside it, so the
+//! bindings are only regenerated, which requires libclang, when the `regenerate-bindings` feature
+//! is enabled:
+//!
+//! ```text
+//! cargo build -p mun_abi --features regenerate-bindings
+//! ```
 
+#[cfg(feature = "regenerate-bindings")]
+use std::{env, path::PathBuf};
+
+#[cfg(feature = "regenerate-bindings")]
 use bindgen::{self, callbacks::EnumVariantValue, callbacks::ParseCallbacks};
 
+/// The header that the bindings are generated from, relative to the manifest directory.
+#[cfg(feature = "regenerate-bindings")]
+const HEADER: &str = "include/mun_abi.h";
+
+#[cfg(feature = "regenerate-bindings")]
 #[derive(Debug)]
 struct RemoveVendorName;
 
+#[cfg(feature = "regenerate-bindings")]
 impl ParseCallbacks for RemoveVendorName {
     fn enum_variant_name(
         &self,
@@ -21,29 +36,45 @@ impl ParseCallbacks for RemoveVendorName {
     }
 
     fn item_name(&self, original_item_name: &str) -> Option<String> {
-        if original_item_name == "MunPrivacy_t" {
-            Some("Privacy".to_string())
-        } else {
-            Some(original_item_name.trim_start_matches("Mun").to_string())
-        }
+        // The `_t` aliases of the enums refer to the enums that are defined in `lib.rs`
+        Some(
+            original_item_name
+                .trim_start_matches("Mun")
+                .trim_end_matches("_t")
+                .to_string(),
+        )
     }
 }
 
+#[cfg(not(feature = "regenerate-bindings"))]
 fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+}
+
+#[cfg(feature = "regenerate-bindings")]
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={}", HEADER);
+
     let bindings = bindgen::Builder::default()
-        .header("c/include/mun_abi.h")
+        .header(HEADER)
         .whitelist_type("Mun.*")
         .blacklist_type("MunPrivacy.*")
+        .blacklist_type("MunMemoryKind.*")
+        .blacklist_type("MunReturnConvention.*")
+        .blacklist_type("MunFloatSemantics.*")
+        .blacklist_type("MunTrapKind.*")
         // Remove type aliasing on Linux
         .blacklist_type("__uint8_t")
         .blacklist_type("__uint16_t")
         .blacklist_type("__uint32_t")
+        .blacklist_type("__uint64_t")
         .parse_callbacks(Box::new(RemoveVendorName))
         // FIXME: Prevent double derivation of Copy and Debug attributes on Windows
         .derive_copy(false)
         .derive_debug(false)
         .raw_line("#![allow(non_snake_case, non_camel_case_types, non_upper_case_globals)]")
-        .raw_line("use crate::Privacy;")
+        .raw_line("use crate::{FloatSemantics, MemoryKind, Privacy, ReturnConvention, TrapKind};")
         .generate()
         .expect("Unable to generate bindings for 'mun_abi.h'");
 
@@ -0,0 +1,332 @@
+#ifndef MUN_ABI_H_
+#define MUN_ABI_H_
+
+#include <stdint.h>
+
+/**
+ * Defines the privacy level of modules, functions, or variables.
+ */
+enum MunPrivacy
+#ifdef __cplusplus
+    : uint8_t
+#endif // __cplusplus
+{
+    /** Publicly (and privately) accessible */
+    MunPrivacyPublic = 0,
+    /** Privately accessible */
+    MunPrivacyPrivate = 1
+};
+
+typedef uint8_t MunPrivacy_t;
+
+/**
+ * Defines how the values of a type are stored in memory.
+ */
+enum MunMemoryKind
+#ifdef __cplusplus
+    : uint8_t
+#endif // __cplusplus
+{
+    /** Values are stored in place and passed by value */
+    MunMemoryKindValue = 0,
+    /** Values are allocated by the runtime and passed by pointer */
+    MunMemoryKindGC = 1
+};
+
+typedef uint8_t MunMemoryKind_t;
+
+/**
+ * Defines how a function returns its value to the caller, as determined by the C ABI of the
+ * target.
+ */
+enum MunReturnConvention
+#ifdef __cplusplus
+    : uint8_t
+#endif // __cplusplus
+{
+    /** The value is returned directly, e.g. in registers */
+    MunReturnConventionDirect = 0,
+    /**
+     * The value is stored in memory that is allocated by the caller, a pointer to which is passed
+     * as a hidden first argument
+     */
+    MunReturnConventionPointer = 1
+};
+
+typedef uint8_t MunReturnConvention_t;
+
+/**
+ * Defines the floating point semantics with which an assembly was compiled.
+ */
+enum MunFloatSemantics
+#ifdef __cplusplus
+    : uint8_t
+#endif // __cplusplus
+{
+    /** Floating point operations strictly follow IEEE 754 */
+    MunFloatSemanticsStrict = 0,
+    /**
+     * Floating point operations may be reordered and may assume that their operands and results
+     * are neither NaN nor infinite
+     */
+    MunFloatSemanticsFast = 1
+};
+
+typedef uint8_t MunFloatSemantics_t;
+
+/**
+ * Defines the check that caused a trap.
+ */
+enum MunTrapKind
+#ifdef __cplusplus
+    : uint8_t
+#endif // __cplusplus
+{
+    /** An integer operation overflowed while overflow checks were enabled */
+    MunTrapKindOverflow = 0,
+    /** An integer was divided by zero */
+    MunTrapKindDivisionByZero = 1,
+    /** An array was indexed out of its bounds while bounds checks were enabled */
+    MunTrapKindIndexOutOfBounds = 2,
+    /** An `assert` or `assert_eq` failed */
+    MunTrapKindAssertionFailed = 3
+};
+
+typedef uint8_t MunTrapKind_t;
+
+/**
+ * Represents a globally unique identifier (GUID).
+ *
+ * GUIDs are generated by taking the MD5 hash of a type's name.
+ *
+ * <div rustbindgen derive="Clone" derive="Copy" derive="Debug" derive="PartialEq" derive="Eq"></div>
+ */
+typedef struct
+{
+    /** 16-byte MD5 hash */
+    uint8_t b[16];
+} MunGuid;
+
+/**
+ * Represents a string, i.e. a pointer to a sequence of UTF-8 encoded bytes and its length.
+ *
+ * The bytes of a string literal are stored in the library that contains it and are followed by a
+ * nul terminator that is not included in `len`. Hot reloading unloads the library, which
+ * invalidates the string. Copy the string to retain it across hot reloads.
+ *
+ * <div rustbindgen derive="Clone" derive="Copy" derive="Debug"></div>
+ */
+typedef struct
+{
+    /** Pointer to the bytes of the string */
+    const uint8_t *ptr;
+    /** Number of bytes in the string */
+    uint64_t len;
+} MunStr;
+
+typedef struct MunTypeInfo MunTypeInfo;
+
+/**
+ * Represents the variants of an enum type. Values of the enum are represented by the integer type
+ * `repr`.
+ *
+ * <div rustbindgen derive="Debug"></div>
+ */
+typedef struct
+{
+    /** Variant names */
+    const char *const *variant_names;
+    /** Variant values, i.e. the bit patterns of the discriminants of the variants */
+    const uint64_t *variant_values;
+    /** The integer type that represents values of the enum */
+    const MunTypeInfo *repr;
+    /** Number of variants */
+    uint16_t num_variants;
+} MunEnumInfo;
+
+/**
+ * Represents the element type and the length of a fixed-size array type. Arrays are passed to
+ * functions by pointer to their first element.
+ *
+ * <div rustbindgen derive="Debug"></div>
+ */
+typedef struct
+{
+    /** The type of the elements */
+    const MunTypeInfo *element_type;
+    /** Number of elements */
+    uint64_t length;
+} MunArrayInfo;
+
+/**
+ * Represents the type declaration for a value type.
+ *
+ * TODO: add support for polymorphism, type parameters, generic type definitions, and constructed generic types.
+ *
+ * <div rustbindgen derive="Debug"></div>
+ */
+struct MunTypeInfo
+{
+    /** Type GUID */
+    MunGuid guid;
+    /** Type name */
+    const char *name;
+    /** Type size in bits */
+    uint32_t size_in_bits;
+    /** Type alignment (in bytes) */
+    uint8_t alignment;
+    /** Whether values of the type are stored by value or allocated by the runtime */
+    MunMemoryKind_t memory_kind;
+    /** Enum variants, or null if the type is not an enum */
+    const MunEnumInfo *enum_info;
+    /** Element type and length, or null if the type is not an array */
+    const MunArrayInfo *array_info;
+};
+
+/**
+ * Represents a function signature.
+ *
+ * <div rustbindgen derive="Clone" derive="Debug"></div>
+ */
+typedef struct
+{
+    /** Function name */
+    const char *name;
+    /** Argument types */
+    const MunTypeInfo *arg_types;
+    /** Optional argument names */
+    const char *const *arg_names;
+    /** Optional return type */
+    const MunTypeInfo *return_type;
+    /** Number of argument types */
+    uint16_t num_arg_types;
+    /** Function accessibility level */
+    MunPrivacy_t privacy;
+    /** How the function returns its value */
+    MunReturnConvention_t return_convention;
+} MunFunctionSignature;
+
+/**
+ * Represents a function declaration.
+ *
+ * `fn_ptr` can be used to call the declared function.
+ *
+ * <div rustbindgen derive="Clone" derive="Debug"></div>
+ */
+typedef struct
+{
+    /** Function signature */
+    MunFunctionSignature signature;
+    /** Function pointer */
+    const void *fn_ptr;
+    /** Optional documentation string */
+    const char *docs;
+} MunFunctionInfo;
+
+/**
+ * Represents a mutable global variable declaration.
+ *
+ * `ptr` points to the memory that holds the value of the global, which is initialized when the
+ * assembly is loaded. Access to the value is not synchronized.
+ *
+ * <div rustbindgen derive="Debug"></div>
+ */
+typedef struct
+{
+    /** Global name */
+    const char *name;
+    /** Type of the global */
+    const MunTypeInfo *type_info;
+    /** Pointer to the value of the global */
+    void *ptr;
+} MunGlobalInfo;
+
+/**
+ * Represents a module declaration.
+ *
+ * <div rustbindgen derive="Debug"></div>
+ */
+typedef struct MunModuleInfo
+{
+    /** Module path */
+    const char *path;
+    /** Module functions */
+    const MunFunctionInfo *functions;
+    /** Child modules */
+    const struct MunModuleInfo *modules;
+    /** Module globals */
+    const MunGlobalInfo *globals;
+    /** Number of module functions */
+    uint32_t num_functions;
+    /** Number of child modules */
+    uint32_t num_modules;
+    /** Number of module globals */
+    uint32_t num_globals;
+} MunModuleInfo;
+
+/**
+ * Represents a function dispatch table. This is used for runtime linking.
+ *
+ * Function signatures and pointers are stored separately for cache efficiency.
+ *
+ * <div rustbindgen derive="Debug"></div>
+ */
+typedef struct
+{
+    /** Function signatures */
+    const MunFunctionSignature *signatures;
+    /** Function pointers */
+    const void **fn_ptrs;
+    /** Hash of the paths and signatures of the functions, or zero if unknown */
+    uint64_t hash;
+    /** Number of functions */
+    uint32_t num_entries;
+} MunDispatchTable;
+
+/**
+ * Represents an assembly declaration.
+ *
+ * <div rustbindgen derive="Debug"></div>
+ */
+typedef struct
+{
+    /** Symbols of the top-level module */
+    MunModuleInfo symbols;
+    /** Dispatch table */
+    MunDispatchTable dispatch_table;
+    /** Paths to assembly dependencies */
+    const char *const *dependencies;
+    /** Number of dependencies */
+    uint32_t num_dependencies;
+    /**
+     * Slot in which the runtime stores the handle of its allocator, or null if the assembly
+     * does not allocate memory
+     */
+    void **allocator_handle;
+    /** Floating point semantics with which the assembly was compiled */
+    MunFloatSemantics_t float_semantics;
+    /** Target triple for which the assembly was compiled, e.g. `x86_64-unknown-linux-gnu` */
+    const char *target;
+} MunAssemblyInfo;
+
+/**
+ * Represents a location in the source of an assembly at which the generated code can trap.
+ *
+ * The code of every function embeds a table of its trap sites. When a check fails, the code
+ * passes the table and the index of the site to the trap handler of the runtime.
+ *
+ * <div rustbindgen derive="Debug"></div>
+ */
+typedef struct
+{
+    /** Qualified name of the function that contains the site */
+    const char *function;
+    /** Path of the source file relative to its source root */
+    const char *file;
+    /** One-based line number of the site in the source file */
+    uint32_t line;
+    /** Check that traps at the site */
+    MunTrapKind_t kind;
+} MunTrapSite;
+
+#endif /* MUN_ABI_H_ */
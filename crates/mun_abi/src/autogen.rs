@@ -1,15 +1,15 @@
 /* automatically generated by rust-bindgen */
 
 #![allow(non_snake_case, non_camel_case_types, non_upper_case_globals)]
-use crate::Privacy;
+use crate::{FloatSemantics, MemoryKind, Privacy, ReturnConvention, TrapKind};
 
 #[doc = " Represents a globally unique identifier (GUID)."]
 #[doc = ""]
 #[doc = " GUIDs are generated by taking the MD5 hash of a type's name."]
 #[doc = ""]
-#[doc = " <div rustbindgen derive=\"Clone\" derive=\"Copy\" derive=\"Debug\" derive=\"PartialEq\"></div>"]
+#[doc = " <div rustbindgen derive=\"Clone\" derive=\"Copy\" derive=\"Debug\" derive=\"PartialEq\" derive=\"Eq\"></div>"]
 #[repr(C)]
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Guid {
     #[doc = " 16-byte MD5 hash"]
     pub b: [u8; 16usize],
@@ -32,9 +32,161 @@ fn bindgen_test_layout_Guid() {
         concat!("Offset of field: ", stringify!(Guid), "::", stringify!(b))
     );
 }
+#[doc = " Represents a string, i.e. a pointer to a sequence of UTF-8 encoded bytes and its length."]
+#[doc = ""]
+#[doc = " The bytes of a string literal are stored in the library that contains it and are followed by a"]
+#[doc = " nul terminator that is not included in `len`. Hot reloading unloads the library, which"]
+#[doc = " invalidates the string. Copy the string to retain it across hot reloads."]
+#[doc = ""]
+#[doc = " <div rustbindgen derive=\"Clone\" derive=\"Copy\" derive=\"Debug\"></div>"]
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct Str {
+    #[doc = " Pointer to the bytes of the string"]
+    pub ptr: *const u8,
+    #[doc = " Number of bytes in the string"]
+    pub len: u64,
+}
+#[test]
+fn bindgen_test_layout_Str() {
+    assert_eq!(
+        ::std::mem::size_of::<Str>(),
+        16usize,
+        concat!("Size of: ", stringify!(Str))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<Str>(),
+        8usize,
+        concat!("Alignment of ", stringify!(Str))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<Str>())).ptr as *const _ as usize },
+        0usize,
+        concat!("Offset of field: ", stringify!(Str), "::", stringify!(ptr))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<Str>())).len as *const _ as usize },
+        8usize,
+        concat!("Offset of field: ", stringify!(Str), "::", stringify!(len))
+    );
+}
+#[doc = " Represents the variants of an enum type. Values of the enum are represented by the integer type"]
+#[doc = " `repr`."]
+#[doc = ""]
+#[doc = " <div rustbindgen derive=\"Debug\"></div>"]
+#[repr(C)]
+#[derive(Debug)]
+pub struct EnumInfo {
+    #[doc = " Variant names"]
+    pub variant_names: *const *const ::std::os::raw::c_char,
+    #[doc = " Variant values, i.e. the bit patterns of the discriminants of the variants"]
+    pub variant_values: *const u64,
+    #[doc = " The integer type that represents values of the enum"]
+    pub repr: *const TypeInfo,
+    #[doc = " Number of variants"]
+    pub num_variants: u16,
+}
+#[test]
+fn bindgen_test_layout_EnumInfo() {
+    assert_eq!(
+        ::std::mem::size_of::<EnumInfo>(),
+        32usize,
+        concat!("Size of: ", stringify!(EnumInfo))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<EnumInfo>(),
+        8usize,
+        concat!("Alignment of ", stringify!(EnumInfo))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<EnumInfo>())).variant_names as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(EnumInfo),
+            "::",
+            stringify!(variant_names)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<EnumInfo>())).variant_values as *const _ as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(EnumInfo),
+            "::",
+            stringify!(variant_values)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<EnumInfo>())).repr as *const _ as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(EnumInfo),
+            "::",
+            stringify!(repr)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<EnumInfo>())).num_variants as *const _ as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(EnumInfo),
+            "::",
+            stringify!(num_variants)
+        )
+    );
+}
+#[doc = " Represents the element type and the length of a fixed-size array type. Arrays are passed to"]
+#[doc = " functions by pointer to their first element."]
+#[doc = ""]
+#[doc = " <div rustbindgen derive=\"Debug\"></div>"]
+#[repr(C)]
+#[derive(Debug)]
+pub struct ArrayInfo {
+    #[doc = " The type of the elements"]
+    pub element_type: *const TypeInfo,
+    #[doc = " Number of elements"]
+    pub length: u64,
+}
+#[test]
+fn bindgen_test_layout_ArrayInfo() {
+    assert_eq!(
+        ::std::mem::size_of::<ArrayInfo>(),
+        16usize,
+        concat!("Size of: ", stringify!(ArrayInfo))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<ArrayInfo>(),
+        8usize,
+        concat!("Alignment of ", stringify!(ArrayInfo))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<ArrayInfo>())).element_type as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(ArrayInfo),
+            "::",
+            stringify!(element_type)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<ArrayInfo>())).length as *const _ as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(ArrayInfo),
+            "::",
+            stringify!(length)
+        )
+    );
+}
 #[doc = " Represents the type declaration for a value type."]
 #[doc = ""]
-#[doc = " TODO: add support for structs, polymorphism, enumerations, type parameters, generic type definitions, and constructed generic types."]
+#[doc = " TODO: add support for polymorphism, type parameters, generic type definitions, and constructed generic types."]
 #[doc = ""]
 #[doc = " <div rustbindgen derive=\"Debug\"></div>"]
 #[repr(C)]
@@ -44,12 +196,22 @@ pub struct TypeInfo {
     pub guid: Guid,
     #[doc = " Type name"]
     pub name: *const ::std::os::raw::c_char,
+    #[doc = " Type size in bits"]
+    pub size_in_bits: u32,
+    #[doc = " Type alignment (in bytes)"]
+    pub alignment: u8,
+    #[doc = " Whether values of the type are stored by value or allocated by the runtime"]
+    pub memory_kind: MemoryKind,
+    #[doc = " Enum variants, or null if the type is not an enum"]
+    pub enum_info: *const EnumInfo,
+    #[doc = " Element type and length, or null if the type is not an array"]
+    pub array_info: *const ArrayInfo,
 }
 #[test]
 fn bindgen_test_layout_TypeInfo() {
     assert_eq!(
         ::std::mem::size_of::<TypeInfo>(),
-        24usize,
+        48usize,
         concat!("Size of: ", stringify!(TypeInfo))
     );
     assert_eq!(
@@ -77,6 +239,56 @@ fn bindgen_test_layout_TypeInfo() {
             stringify!(name)
         )
     );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<TypeInfo>())).size_in_bits as *const _ as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(TypeInfo),
+            "::",
+            stringify!(size_in_bits)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<TypeInfo>())).alignment as *const _ as usize },
+        28usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(TypeInfo),
+            "::",
+            stringify!(alignment)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<TypeInfo>())).memory_kind as *const _ as usize },
+        29usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(TypeInfo),
+            "::",
+            stringify!(memory_kind)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<TypeInfo>())).enum_info as *const _ as usize },
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(TypeInfo),
+            "::",
+            stringify!(enum_info)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<TypeInfo>())).array_info as *const _ as usize },
+        40usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(TypeInfo),
+            "::",
+            stringify!(array_info)
+        )
+    );
 }
 #[doc = " Represents a function signature."]
 #[doc = ""]
@@ -88,18 +300,22 @@ pub struct FunctionSignature {
     pub name: *const ::std::os::raw::c_char,
     #[doc = " Argument types"]
     pub arg_types: *const TypeInfo,
+    #[doc = " Optional argument names"]
+    pub arg_names: *const *const ::std::os::raw::c_char,
     #[doc = " Optional return type"]
     pub return_type: *const TypeInfo,
     #[doc = " Number of argument types"]
     pub num_arg_types: u16,
     #[doc = " Function accessibility level"]
     pub privacy: Privacy,
+    #[doc = " How the function returns its value"]
+    pub return_convention: ReturnConvention,
 }
 #[test]
 fn bindgen_test_layout_FunctionSignature() {
     assert_eq!(
         ::std::mem::size_of::<FunctionSignature>(),
-        32usize,
+        40usize,
         concat!("Size of: ", stringify!(FunctionSignature))
     );
     assert_eq!(
@@ -128,8 +344,18 @@ fn bindgen_test_layout_FunctionSignature() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<FunctionSignature>())).return_type as *const _ as usize },
+        unsafe { &(*(::std::ptr::null::<FunctionSignature>())).arg_names as *const _ as usize },
         16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(FunctionSignature),
+            "::",
+            stringify!(arg_names)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<FunctionSignature>())).return_type as *const _ as usize },
+        24usize,
         concat!(
             "Offset of field: ",
             stringify!(FunctionSignature),
@@ -139,7 +365,7 @@ fn bindgen_test_layout_FunctionSignature() {
     );
     assert_eq!(
         unsafe { &(*(::std::ptr::null::<FunctionSignature>())).num_arg_types as *const _ as usize },
-        24usize,
+        32usize,
         concat!(
             "Offset of field: ",
             stringify!(FunctionSignature),
@@ -149,7 +375,7 @@ fn bindgen_test_layout_FunctionSignature() {
     );
     assert_eq!(
         unsafe { &(*(::std::ptr::null::<FunctionSignature>())).privacy as *const _ as usize },
-        26usize,
+        34usize,
         concat!(
             "Offset of field: ",
             stringify!(FunctionSignature),
@@ -157,6 +383,18 @@ fn bindgen_test_layout_FunctionSignature() {
             stringify!(privacy)
         )
     );
+    assert_eq!(
+        unsafe {
+            &(*(::std::ptr::null::<FunctionSignature>())).return_convention as *const _ as usize
+        },
+        35usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(FunctionSignature),
+            "::",
+            stringify!(return_convention)
+        )
+    );
 }
 #[doc = " Represents a function declaration."]
 #[doc = ""]
@@ -170,12 +408,14 @@ pub struct FunctionInfo {
     pub signature: FunctionSignature,
     #[doc = " Function pointer"]
     pub fn_ptr: *const ::std::os::raw::c_void,
+    #[doc = " Optional documentation string"]
+    pub docs: *const ::std::os::raw::c_char,
 }
 #[test]
 fn bindgen_test_layout_FunctionInfo() {
     assert_eq!(
         ::std::mem::size_of::<FunctionInfo>(),
-        40usize,
+        56usize,
         concat!("Size of: ", stringify!(FunctionInfo))
     );
     assert_eq!(
@@ -195,7 +435,7 @@ fn bindgen_test_layout_FunctionInfo() {
     );
     assert_eq!(
         unsafe { &(*(::std::ptr::null::<FunctionInfo>())).fn_ptr as *const _ as usize },
-        32usize,
+        40usize,
         concat!(
             "Offset of field: ",
             stringify!(FunctionInfo),
@@ -203,6 +443,75 @@ fn bindgen_test_layout_FunctionInfo() {
             stringify!(fn_ptr)
         )
     );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<FunctionInfo>())).docs as *const _ as usize },
+        48usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(FunctionInfo),
+            "::",
+            stringify!(docs)
+        )
+    );
+}
+#[doc = " Represents a mutable global variable declaration."]
+#[doc = ""]
+#[doc = " `ptr` points to the memory that holds the value of the global, which is initialized when the"]
+#[doc = " assembly is loaded. Access to the value is not synchronized."]
+#[doc = ""]
+#[doc = " <div rustbindgen derive=\"Debug\"></div>"]
+#[repr(C)]
+#[derive(Debug)]
+pub struct GlobalInfo {
+    #[doc = " Global name"]
+    pub name: *const ::std::os::raw::c_char,
+    #[doc = " Type of the global"]
+    pub type_info: *const TypeInfo,
+    #[doc = " Pointer to the value of the global"]
+    pub ptr: *mut ::std::os::raw::c_void,
+}
+#[test]
+fn bindgen_test_layout_GlobalInfo() {
+    assert_eq!(
+        ::std::mem::size_of::<GlobalInfo>(),
+        24usize,
+        concat!("Size of: ", stringify!(GlobalInfo))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<GlobalInfo>(),
+        8usize,
+        concat!("Alignment of ", stringify!(GlobalInfo))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<GlobalInfo>())).name as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(GlobalInfo),
+            "::",
+            stringify!(name)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<GlobalInfo>())).type_info as *const _ as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(GlobalInfo),
+            "::",
+            stringify!(type_info)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<GlobalInfo>())).ptr as *const _ as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(GlobalInfo),
+            "::",
+            stringify!(ptr)
+        )
+    );
 }
 #[doc = " Represents a module declaration."]
 #[doc = ""]
@@ -214,14 +523,22 @@ pub struct ModuleInfo {
     pub path: *const ::std::os::raw::c_char,
     #[doc = " Module functions"]
     pub functions: *const FunctionInfo,
+    #[doc = " Child modules"]
+    pub modules: *const ModuleInfo,
+    #[doc = " Module globals"]
+    pub globals: *const GlobalInfo,
     #[doc = " Number of module functions"]
     pub num_functions: u32,
+    #[doc = " Number of child modules"]
+    pub num_modules: u32,
+    #[doc = " Number of module globals"]
+    pub num_globals: u32,
 }
 #[test]
 fn bindgen_test_layout_ModuleInfo() {
     assert_eq!(
         ::std::mem::size_of::<ModuleInfo>(),
-        24usize,
+        48usize,
         concat!("Size of: ", stringify!(ModuleInfo))
     );
     assert_eq!(
@@ -250,8 +567,28 @@ fn bindgen_test_layout_ModuleInfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<ModuleInfo>())).num_functions as *const _ as usize },
+        unsafe { &(*(::std::ptr::null::<ModuleInfo>())).modules as *const _ as usize },
         16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(ModuleInfo),
+            "::",
+            stringify!(modules)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<ModuleInfo>())).globals as *const _ as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(ModuleInfo),
+            "::",
+            stringify!(globals)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<ModuleInfo>())).num_functions as *const _ as usize },
+        32usize,
         concat!(
             "Offset of field: ",
             stringify!(ModuleInfo),
@@ -259,6 +596,26 @@ fn bindgen_test_layout_ModuleInfo() {
             stringify!(num_functions)
         )
     );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<ModuleInfo>())).num_modules as *const _ as usize },
+        36usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(ModuleInfo),
+            "::",
+            stringify!(num_modules)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<ModuleInfo>())).num_globals as *const _ as usize },
+        40usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(ModuleInfo),
+            "::",
+            stringify!(num_globals)
+        )
+    );
 }
 #[doc = " Represents a function dispatch table. This is used for runtime linking."]
 #[doc = ""]
@@ -272,6 +629,8 @@ pub struct DispatchTable {
     pub signatures: *const FunctionSignature,
     #[doc = " Function pointers"]
     pub fn_ptrs: *mut *const ::std::os::raw::c_void,
+    #[doc = " Hash of the paths and signatures of the functions, or zero if unknown"]
+    pub hash: u64,
     #[doc = " Number of functions"]
     pub num_entries: u32,
 }
@@ -279,7 +638,7 @@ pub struct DispatchTable {
 fn bindgen_test_layout_DispatchTable() {
     assert_eq!(
         ::std::mem::size_of::<DispatchTable>(),
-        24usize,
+        32usize,
         concat!("Size of: ", stringify!(DispatchTable))
     );
     assert_eq!(
@@ -308,8 +667,18 @@ fn bindgen_test_layout_DispatchTable() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<DispatchTable>())).num_entries as *const _ as usize },
+        unsafe { &(*(::std::ptr::null::<DispatchTable>())).hash as *const _ as usize },
         16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(DispatchTable),
+            "::",
+            stringify!(hash)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<DispatchTable>())).num_entries as *const _ as usize },
+        24usize,
         concat!(
             "Offset of field: ",
             stringify!(DispatchTable),
@@ -332,12 +701,19 @@ pub struct AssemblyInfo {
     pub dependencies: *const *const ::std::os::raw::c_char,
     #[doc = " Number of dependencies"]
     pub num_dependencies: u32,
+    #[doc = " Slot in which the runtime stores the handle of its allocator, or null if the assembly"]
+    #[doc = " does not allocate memory"]
+    pub allocator_handle: *mut *mut ::std::os::raw::c_void,
+    #[doc = " Floating point semantics with which the assembly was compiled"]
+    pub float_semantics: FloatSemantics,
+    #[doc = " Target triple for which the assembly was compiled, e.g. `x86_64-unknown-linux-gnu`"]
+    pub target: *const ::std::os::raw::c_char,
 }
 #[test]
 fn bindgen_test_layout_AssemblyInfo() {
     assert_eq!(
         ::std::mem::size_of::<AssemblyInfo>(),
-        64usize,
+        120usize,
         concat!("Size of: ", stringify!(AssemblyInfo))
     );
     assert_eq!(
@@ -357,7 +733,7 @@ fn bindgen_test_layout_AssemblyInfo() {
     );
     assert_eq!(
         unsafe { &(*(::std::ptr::null::<AssemblyInfo>())).dispatch_table as *const _ as usize },
-        24usize,
+        48usize,
         concat!(
             "Offset of field: ",
             stringify!(AssemblyInfo),
@@ -367,7 +743,7 @@ fn bindgen_test_layout_AssemblyInfo() {
     );
     assert_eq!(
         unsafe { &(*(::std::ptr::null::<AssemblyInfo>())).dependencies as *const _ as usize },
-        48usize,
+        80usize,
         concat!(
             "Offset of field: ",
             stringify!(AssemblyInfo),
@@ -377,7 +753,7 @@ fn bindgen_test_layout_AssemblyInfo() {
     );
     assert_eq!(
         unsafe { &(*(::std::ptr::null::<AssemblyInfo>())).num_dependencies as *const _ as usize },
-        56usize,
+        88usize,
         concat!(
             "Offset of field: ",
             stringify!(AssemblyInfo),
@@ -385,4 +761,105 @@ fn bindgen_test_layout_AssemblyInfo() {
             stringify!(num_dependencies)
         )
     );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<AssemblyInfo>())).allocator_handle as *const _ as usize },
+        96usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(AssemblyInfo),
+            "::",
+            stringify!(allocator_handle)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<AssemblyInfo>())).float_semantics as *const _ as usize },
+        104usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(AssemblyInfo),
+            "::",
+            stringify!(float_semantics)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<AssemblyInfo>())).target as *const _ as usize },
+        112usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(AssemblyInfo),
+            "::",
+            stringify!(target)
+        )
+    );
+}
+#[doc = " Represents a location in the source of an assembly at which the generated code can trap."]
+#[doc = ""]
+#[doc = " The code of every function embeds a table of its trap sites. When a check fails, the code"]
+#[doc = " passes the table and the index of the site to the trap handler of the runtime."]
+#[doc = ""]
+#[doc = " <div rustbindgen derive=\"Debug\"></div>"]
+#[repr(C)]
+#[derive(Debug)]
+pub struct TrapSite {
+    #[doc = " Qualified name of the function that contains the site"]
+    pub function: *const ::std::os::raw::c_char,
+    #[doc = " Path of the source file relative to its source root"]
+    pub file: *const ::std::os::raw::c_char,
+    #[doc = " One-based line number of the site in the source file"]
+    pub line: u32,
+    #[doc = " Check that traps at the site"]
+    pub kind: TrapKind,
+}
+#[test]
+fn bindgen_test_layout_TrapSite() {
+    assert_eq!(
+        ::std::mem::size_of::<TrapSite>(),
+        24usize,
+        concat!("Size of: ", stringify!(TrapSite))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<TrapSite>(),
+        8usize,
+        concat!("Alignment of ", stringify!(TrapSite))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<TrapSite>())).function as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(TrapSite),
+            "::",
+            stringify!(function)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<TrapSite>())).file as *const _ as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(TrapSite),
+            "::",
+            stringify!(file)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<TrapSite>())).line as *const _ as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(TrapSite),
+            "::",
+            stringify!(line)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<TrapSite>())).kind as *const _ as usize },
+        20usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(TrapSite),
+            "::",
+            stringify!(kind)
+        )
+    );
 }
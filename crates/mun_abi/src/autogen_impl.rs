@@ -1,9 +1,35 @@
 use crate::prelude::*;
 
 use std::ffi::{c_void, CStr};
+use std::fmt;
 use std::marker::{Send, Sync};
 use std::slice;
 
+impl Str {
+    /// Returns the bytes of the string.
+    pub fn as_bytes(&self) -> &[u8] {
+        if self.len == 0 {
+            &[]
+        } else {
+            unsafe { slice::from_raw_parts(self.ptr, self.len as usize) }
+        }
+    }
+
+    /// Returns the string as a `&str`.
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(self.as_bytes()).expect("String contains invalid UTF8")
+    }
+}
+
+impl fmt::Display for Str {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+unsafe impl Send for Str {}
+unsafe impl Sync for Str {}
+
 impl TypeInfo {
     /// Returns the type's name.
     pub fn name(&self) -> &str {
@@ -11,6 +37,21 @@ impl TypeInfo {
             .to_str()
             .expect("Type name contains invalid UTF8")
     }
+
+    /// Returns the size of the type in bytes, rounded up.
+    pub fn size_in_bytes(&self) -> usize {
+        ((self.size_in_bits + 7) / 8) as usize
+    }
+
+    /// Returns the variants of the type if it is an enum.
+    pub fn enum_info(&self) -> Option<&EnumInfo> {
+        unsafe { self.enum_info.as_ref() }
+    }
+
+    /// Returns the element type and the length of the type if it is an array.
+    pub fn array_info(&self) -> Option<&ArrayInfo> {
+        unsafe { self.array_info.as_ref() }
+    }
 }
 
 impl PartialEq for TypeInfo {
@@ -22,6 +63,68 @@ impl PartialEq for TypeInfo {
 unsafe impl Send for TypeInfo {}
 unsafe impl Sync for TypeInfo {}
 
+impl EnumInfo {
+    /// Returns the number of variants.
+    pub fn num_variants(&self) -> usize {
+        self.num_variants as usize
+    }
+
+    /// Returns the name of the variant at `idx`, or `None` if the variant is out of bounds.
+    pub fn variant_name(&self, idx: usize) -> Option<&str> {
+        if idx >= self.num_variants() {
+            return None;
+        }
+
+        let name = unsafe { *self.variant_names.add(idx) };
+        Some(
+            unsafe { CStr::from_ptr(name) }
+                .to_str()
+                .expect("Variant name contains invalid UTF8"),
+        )
+    }
+
+    /// Returns the values of the variants, in declaration order.
+    pub fn variant_values(&self) -> &[u64] {
+        if self.num_variants == 0 {
+            &[]
+        } else {
+            unsafe { slice::from_raw_parts(self.variant_values, self.num_variants()) }
+        }
+    }
+
+    /// Returns the name of the variant with the specified `value`, e.g. to display a value of the
+    /// enum. The value is the bit pattern of the value, zero-extended to 64 bits.
+    pub fn variant_name_of(&self, value: u64) -> Option<&str> {
+        self.variant_values()
+            .iter()
+            .position(|v| *v == value)
+            .and_then(|idx| self.variant_name(idx))
+    }
+
+    /// Returns the integer type that represents values of the enum.
+    pub fn repr(&self) -> &TypeInfo {
+        unsafe { &*self.repr }
+    }
+}
+
+unsafe impl Send for EnumInfo {}
+unsafe impl Sync for EnumInfo {}
+
+impl ArrayInfo {
+    /// Returns the type of the elements.
+    pub fn element_type(&self) -> &TypeInfo {
+        unsafe { &*self.element_type }
+    }
+
+    /// Returns the number of elements.
+    pub fn length(&self) -> usize {
+        self.length as usize
+    }
+}
+
+unsafe impl Send for ArrayInfo {}
+unsafe impl Sync for ArrayInfo {}
+
 impl FunctionSignature {
     /// Returns the function's name.
     pub fn name(&self) -> &str {
@@ -35,6 +138,11 @@ impl FunctionSignature {
         self.privacy
     }
 
+    /// Returns how the function returns its value.
+    pub fn return_convention(&self) -> ReturnConvention {
+        self.return_convention
+    }
+
     /// Returns the function's arguments' types.
     pub fn arg_types(&self) -> &[TypeInfo] {
         if self.num_arg_types == 0 {
@@ -44,18 +152,89 @@ impl FunctionSignature {
         }
     }
 
+    /// Returns the name of the argument at `idx`, or `None` if the argument is out of bounds or
+    /// has no name (e.g. because it was compiled with an older compiler).
+    pub fn arg_name(&self, idx: usize) -> Option<&str> {
+        if self.arg_names.is_null() || idx >= self.num_arg_types as usize {
+            return None;
+        }
+
+        let name = unsafe { *self.arg_names.add(idx) };
+        if name.is_null() {
+            None
+        } else {
+            Some(
+                unsafe { CStr::from_ptr(name) }
+                    .to_str()
+                    .expect("Argument name contains invalid UTF8"),
+            )
+        }
+    }
+
     /// Returns the function's return type
     pub fn return_type(&self) -> Option<&TypeInfo> {
         unsafe { self.return_type.as_ref() }
     }
 }
 
+impl fmt::Display for FunctionSignature {
+    /// Formats the signature using Mun syntax, e.g. `fn add(a: @core::int): @core::int`. Arguments
+    /// without a name are displayed with a positional placeholder, e.g. `arg0`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "fn {}(", self.name())?;
+        for (idx, arg_type) in self.arg_types().iter().enumerate() {
+            if idx > 0 {
+                write!(f, ", ")?;
+            }
+            match self.arg_name(idx) {
+                Some(name) => write!(f, "{}: {}", name, arg_type.name())?,
+                None => write!(f, "arg{}: {}", idx, arg_type.name())?,
+            }
+        }
+        write!(f, ")")?;
+        if let Some(return_type) = self.return_type() {
+            write!(f, ": {}", return_type.name())?;
+        }
+        Ok(())
+    }
+}
+
 unsafe impl Send for FunctionSignature {}
 unsafe impl Sync for FunctionSignature {}
 
+impl FunctionInfo {
+    /// Returns the function's documentation, or an empty string if it has none.
+    pub fn docs(&self) -> &str {
+        if self.docs.is_null() {
+            ""
+        } else {
+            unsafe { CStr::from_ptr(self.docs) }
+                .to_str()
+                .expect("Function documentation contains invalid UTF8")
+        }
+    }
+}
+
 unsafe impl Send for FunctionInfo {}
 unsafe impl Sync for FunctionInfo {}
 
+impl GlobalInfo {
+    /// Returns the global's name.
+    pub fn name(&self) -> &str {
+        unsafe { CStr::from_ptr(self.name) }
+            .to_str()
+            .expect("Global name contains invalid UTF8")
+    }
+
+    /// Returns the global's type.
+    pub fn type_info(&self) -> &TypeInfo {
+        unsafe { &*self.type_info }
+    }
+}
+
+unsafe impl Send for GlobalInfo {}
+unsafe impl Sync for GlobalInfo {}
+
 impl ModuleInfo {
     /// Returns the module's full path.
     pub fn path(&self) -> &str {
@@ -64,6 +243,12 @@ impl ModuleInfo {
             .expect("Module path contains invalid UTF8")
     }
 
+    /// Returns the module's name, i.e. the last segment of its path.
+    pub fn name(&self) -> &str {
+        let path = self.path();
+        path.rsplit("::").next().unwrap_or(path)
+    }
+
     // /// Finds the type's fields that match `filter`.
     // pub fn find_fields(&self, filter: fn(&&FieldInfo) -> bool) -> impl Iterator<Item = &FieldInfo> {
     //     self.fields.iter().map(|f| *f).filter(filter)
@@ -87,6 +272,71 @@ impl ModuleInfo {
             unsafe { slice::from_raw_parts(self.functions, self.num_functions as usize) }
         }
     }
+
+    /// Returns the module's child modules.
+    pub fn modules(&self) -> &[ModuleInfo] {
+        if self.num_modules == 0 {
+            &[]
+        } else {
+            unsafe { slice::from_raw_parts(self.modules, self.num_modules as usize) }
+        }
+    }
+
+    /// Returns the module's globals.
+    pub fn globals(&self) -> &[GlobalInfo] {
+        if self.num_globals == 0 {
+            &[]
+        } else {
+            unsafe { slice::from_raw_parts(self.globals, self.num_globals as usize) }
+        }
+    }
+
+    /// Returns the functions of the module and all of its descendants, paired with their fully
+    /// qualified paths.
+    pub fn qualified_functions(&self) -> Vec<(String, &FunctionInfo)> {
+        let mut functions = Vec::new();
+        self.collect_qualified_functions(&mut functions);
+        functions
+    }
+
+    fn collect_qualified_functions<'a>(&'a self, functions: &mut Vec<(String, &'a FunctionInfo)>) {
+        let path = self.path();
+        for function in self.functions() {
+            let name = function.signature.name();
+            let qualified_name = if path.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}::{}", path, name)
+            };
+            functions.push((qualified_name, function));
+        }
+        for module in self.modules() {
+            module.collect_qualified_functions(functions);
+        }
+    }
+
+    /// Returns the globals of the module and all of its descendants, paired with their fully
+    /// qualified paths.
+    pub fn qualified_globals(&self) -> Vec<(String, &GlobalInfo)> {
+        let mut globals = Vec::new();
+        self.collect_qualified_globals(&mut globals);
+        globals
+    }
+
+    fn collect_qualified_globals<'a>(&'a self, globals: &mut Vec<(String, &'a GlobalInfo)>) {
+        let path = self.path();
+        for global in self.globals() {
+            let qualified_name = if path.is_empty() {
+                global.name().to_string()
+            } else {
+                format!("{}::{}", path, global.name())
+            };
+            globals.push((qualified_name, global));
+        }
+        for module in self.modules() {
+            module.collect_qualified_globals(globals);
+        }
+    }
 }
 
 unsafe impl Send for ModuleInfo {}
@@ -169,6 +419,18 @@ impl DispatchTable {
             None
         }
     }
+
+    /// Returns the hash of the paths and signatures of the dispatch table's functions, or `None`
+    /// if the assembly did not provide one.
+    ///
+    /// Two dispatch tables with the same hash require the same functions in the same slots.
+    pub fn hash(&self) -> Option<u64> {
+        if self.hash == 0 {
+            None
+        } else {
+            Some(self.hash)
+        }
+    }
 }
 
 impl AssemblyInfo {
@@ -186,11 +448,42 @@ impl AssemblyInfo {
                 .expect("dependency path contains invalid UTF8")
         })
     }
+
+    /// Returns the target triple for which the assembly was compiled.
+    pub fn target(&self) -> &str {
+        unsafe { CStr::from_ptr(self.target) }
+            .to_str()
+            .expect("Target triple contains invalid UTF8")
+    }
+
+    /// Stores the `handle` of the runtime's allocator in the assembly, if the assembly allocates
+    /// memory. The handle is passed back to the runtime with every allocation.
+    pub fn set_allocator_handle(&self, handle: *mut c_void) {
+        if let Some(slot) = unsafe { self.allocator_handle.as_mut() } {
+            *slot = handle;
+        }
+    }
 }
 
 unsafe impl Send for AssemblyInfo {}
 unsafe impl Sync for AssemblyInfo {}
 
+impl TrapSite {
+    /// Returns the qualified name of the function that contains the trap site.
+    pub fn function(&self) -> &str {
+        unsafe { CStr::from_ptr(self.function) }
+            .to_str()
+            .expect("Function name contains invalid UTF8")
+    }
+
+    /// Returns the path of the source file that contains the trap site.
+    pub fn file(&self) -> &str {
+        unsafe { CStr::from_ptr(self.file) }
+            .to_str()
+            .expect("File path contains invalid UTF8")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -198,10 +491,33 @@ mod tests {
     use std::os::raw::c_char;
     use std::ptr;
 
+    #[test]
+    fn test_str() {
+        let text = "game_over";
+        let s = Str {
+            ptr: text.as_ptr(),
+            len: text.len() as u64,
+        };
+        assert_eq!(s.as_bytes(), text.as_bytes());
+        assert_eq!(s.as_str(), text);
+        assert_eq!(s.to_string(), text);
+
+        let empty = Str {
+            ptr: ptr::null(),
+            len: 0,
+        };
+        assert_eq!(empty.as_str(), "");
+    }
+
     fn fake_type_info(name: &CStr) -> TypeInfo {
         TypeInfo {
             guid: FAKE_TYPE_GUID,
             name: name.as_ptr(),
+            size_in_bits: FAKE_TYPE_SIZE_IN_BITS,
+            alignment: FAKE_TYPE_ALIGNMENT,
+            memory_kind: MemoryKind::Value,
+            enum_info: ptr::null(),
+            array_info: ptr::null(),
         }
     }
 
@@ -210,6 +526,8 @@ mod tests {
     };
 
     const FAKE_TYPE_NAME: &'static str = "type-name";
+    const FAKE_TYPE_SIZE_IN_BITS: u32 = 12;
+    const FAKE_TYPE_ALIGNMENT: u8 = 2;
 
     #[test]
     fn test_type_info_name() {
@@ -219,6 +537,16 @@ mod tests {
         assert_eq!(type_info.name(), FAKE_TYPE_NAME);
     }
 
+    #[test]
+    fn test_type_info_layout() {
+        let type_name = CString::new(FAKE_TYPE_NAME).expect("Invalid fake type name.");
+        let type_info = fake_type_info(&type_name);
+
+        assert_eq!(type_info.size_in_bits, FAKE_TYPE_SIZE_IN_BITS);
+        assert_eq!(type_info.size_in_bytes(), 2);
+        assert_eq!(type_info.alignment, FAKE_TYPE_ALIGNMENT);
+    }
+
     #[test]
     fn test_type_info_eq() {
         let type_name = CString::new(FAKE_TYPE_NAME).expect("Invalid fake type name.");
@@ -227,6 +555,64 @@ mod tests {
         assert_eq!(type_info, type_info);
     }
 
+    #[test]
+    fn test_type_info_enum_info() {
+        let type_name = CString::new(FAKE_TYPE_NAME).expect("Invalid fake type name.");
+        let type_info = fake_type_info(&type_name);
+        assert!(type_info.enum_info().is_none());
+
+        let repr_name = CString::new("@core::u8").expect("Invalid fake type name.");
+        let repr = fake_type_info(&repr_name);
+        let variant_names = [
+            CString::new("Idle").expect("Invalid fake variant name."),
+            CString::new("Walking").expect("Invalid fake variant name."),
+        ];
+        let variant_name_ptrs: Vec<*const c_char> =
+            variant_names.iter().map(|name| name.as_ptr()).collect();
+        let variant_values = [0u64, 5];
+        let enum_info = EnumInfo {
+            variant_names: variant_name_ptrs.as_ptr(),
+            variant_values: variant_values.as_ptr(),
+            repr: &repr,
+            num_variants: variant_values.len() as u16,
+        };
+        let type_info = TypeInfo {
+            enum_info: &enum_info,
+            ..fake_type_info(&type_name)
+        };
+
+        let enum_info = type_info.enum_info().expect("expected an enum");
+        assert_eq!(enum_info.num_variants(), 2);
+        assert_eq!(enum_info.variant_name(1), Some("Walking"));
+        assert_eq!(enum_info.variant_name(2), None);
+        assert_eq!(enum_info.variant_values(), &variant_values);
+        assert_eq!(enum_info.variant_name_of(5), Some("Walking"));
+        assert_eq!(enum_info.variant_name_of(1), None);
+        assert_eq!(enum_info.repr().name(), "@core::u8");
+    }
+
+    #[test]
+    fn test_type_info_array_info() {
+        let type_name = CString::new(FAKE_TYPE_NAME).expect("Invalid fake type name.");
+        let type_info = fake_type_info(&type_name);
+        assert!(type_info.array_info().is_none());
+
+        let element_name = CString::new("@core::float").expect("Invalid fake type name.");
+        let element_type = fake_type_info(&element_name);
+        let array_info = ArrayInfo {
+            element_type: &element_type,
+            length: 16,
+        };
+        let type_info = TypeInfo {
+            array_info: &array_info,
+            ..fake_type_info(&type_name)
+        };
+
+        let array_info = type_info.array_info().expect("expected an array");
+        assert_eq!(array_info.length(), 16);
+        assert_eq!(array_info.element_type().name(), "@core::float");
+    }
+
     fn fake_fn_signature(
         name: &CStr,
         arg_types: &[TypeInfo],
@@ -236,13 +622,16 @@ mod tests {
         FunctionSignature {
             name: name.as_ptr(),
             arg_types: arg_types.as_ptr(),
+            arg_names: ptr::null(),
             return_type: return_type.map_or(ptr::null(), |t| t as *const TypeInfo),
             num_arg_types: arg_types.len() as u16,
             privacy,
+            return_convention: ReturnConvention::Direct,
         }
     }
 
     const FAKE_FN_NAME: &'static str = "fn-name";
+    const FAKE_ARG_NAME: &'static str = "arg-name";
 
     #[test]
     fn test_fn_signature_name() {
@@ -261,6 +650,17 @@ mod tests {
         assert_eq!(fn_signature.privacy(), privacy);
     }
 
+    #[test]
+    fn test_fn_signature_return_convention() {
+        let fn_name = CString::new(FAKE_FN_NAME).expect("Invalid fake fn name.");
+        let fn_signature = FunctionSignature {
+            return_convention: ReturnConvention::Pointer,
+            ..fake_fn_signature(&fn_name, &[], None, Privacy::Public)
+        };
+
+        assert_eq!(fn_signature.return_convention(), ReturnConvention::Pointer);
+    }
+
     #[test]
     fn test_fn_signature_arg_types_none() {
         let arg_types = &[];
@@ -282,6 +682,49 @@ mod tests {
         assert_eq!(fn_signature.arg_types(), arg_types);
     }
 
+    #[test]
+    fn test_fn_signature_arg_names_none() {
+        let type_name = CString::new(FAKE_TYPE_NAME).expect("Invalid fake type name.");
+        let type_info = fake_type_info(&type_name);
+
+        let arg_types = &[type_info];
+        let fn_name = CString::new(FAKE_FN_NAME).expect("Invalid fake fn name.");
+        let fn_signature = fake_fn_signature(&fn_name, arg_types, None, Privacy::Public);
+
+        assert_eq!(fn_signature.arg_name(0), None);
+        assert_eq!(
+            fn_signature.to_string(),
+            format!("fn {}(arg0: {})", FAKE_FN_NAME, FAKE_TYPE_NAME)
+        );
+    }
+
+    #[test]
+    fn test_fn_signature_arg_names_some() {
+        let type_name = CString::new(FAKE_TYPE_NAME).expect("Invalid fake type name.");
+        let type_info = fake_type_info(&type_name);
+        let return_type_name = CString::new(FAKE_TYPE_NAME).expect("Invalid fake type name.");
+        let return_type = fake_type_info(&return_type_name);
+
+        let arg_name = CString::new(FAKE_ARG_NAME).expect("Invalid fake arg name.");
+        let arg_names = &[arg_name.as_ptr(), ptr::null()];
+        let arg_types = &[fake_type_info(&type_name), type_info];
+        let fn_name = CString::new(FAKE_FN_NAME).expect("Invalid fake fn name.");
+        let mut fn_signature =
+            fake_fn_signature(&fn_name, arg_types, Some(&return_type), Privacy::Public);
+        fn_signature.arg_names = arg_names.as_ptr();
+
+        assert_eq!(fn_signature.arg_name(0), Some(FAKE_ARG_NAME));
+        assert_eq!(fn_signature.arg_name(1), None);
+        assert_eq!(fn_signature.arg_name(2), None);
+        assert_eq!(
+            fn_signature.to_string(),
+            format!(
+                "fn {}({}: {}, arg1: {}): {}",
+                FAKE_FN_NAME, FAKE_ARG_NAME, FAKE_TYPE_NAME, FAKE_TYPE_NAME, FAKE_TYPE_NAME
+            )
+        );
+    }
+
     #[test]
     fn test_fn_signature_return_type_none() {
         let return_type = None;
@@ -303,11 +746,46 @@ mod tests {
         assert_eq!(fn_signature.return_type(), return_type);
     }
 
-    fn fake_module_info(path: &CStr, functions: &[FunctionInfo]) -> ModuleInfo {
+    const FAKE_FN_DOCS: &'static str = "Documentation\nof a function";
+
+    #[test]
+    fn test_fn_info_docs_none() {
+        let fn_name = CString::new(FAKE_FN_NAME).expect("Invalid fake fn name.");
+        let fn_info = FunctionInfo {
+            signature: fake_fn_signature(&fn_name, &[], None, Privacy::Public),
+            fn_ptr: ptr::null(),
+            docs: ptr::null(),
+        };
+
+        assert_eq!(fn_info.docs(), "");
+    }
+
+    #[test]
+    fn test_fn_info_docs_some() {
+        let fn_name = CString::new(FAKE_FN_NAME).expect("Invalid fake fn name.");
+        let fn_docs = CString::new(FAKE_FN_DOCS).expect("Invalid fake fn docs.");
+        let fn_info = FunctionInfo {
+            signature: fake_fn_signature(&fn_name, &[], None, Privacy::Public),
+            fn_ptr: ptr::null(),
+            docs: fn_docs.as_ptr(),
+        };
+
+        assert_eq!(fn_info.docs(), FAKE_FN_DOCS);
+    }
+
+    fn fake_module_info(
+        path: &CStr,
+        functions: &[FunctionInfo],
+        modules: &[ModuleInfo],
+    ) -> ModuleInfo {
         ModuleInfo {
             path: path.as_ptr(),
             functions: functions.as_ptr(),
+            modules: modules.as_ptr(),
+            globals: ptr::null(),
             num_functions: functions.len() as u32,
+            num_modules: modules.len() as u32,
+            num_globals: 0,
         }
     }
 
@@ -316,16 +794,55 @@ mod tests {
     #[test]
     fn test_module_info_path() {
         let module_path = CString::new(FAKE_MODULE_PATH).expect("Invalid fake module path.");
-        let module = fake_module_info(&module_path, &[]);
+        let module = fake_module_info(&module_path, &[], &[]);
 
         assert_eq!(module.path(), FAKE_MODULE_PATH);
+        assert_eq!(module.name(), "module");
+    }
+
+    #[test]
+    fn test_module_info_modules() {
+        let fn_name = CString::new(FAKE_FN_NAME).expect("Invalid fake fn name.");
+        let fn_info = FunctionInfo {
+            signature: fake_fn_signature(&fn_name, &[], None, Privacy::Public),
+            fn_ptr: ptr::null(),
+            docs: ptr::null(),
+        };
+        let functions = &[fn_info];
+
+        // Both the root and the child module contain a function with the same name
+        let child_path = CString::new("child").expect("Invalid fake module path.");
+        let grandchild_path = CString::new("child::grandchild").expect("Invalid fake module path.");
+        let grandchild = fake_module_info(&grandchild_path, functions, &[]);
+        let grandchildren = &[grandchild];
+        let child = fake_module_info(&child_path, &[], grandchildren);
+        let children = &[child];
+        let root_path = CString::new("").expect("Invalid fake module path.");
+        let root = fake_module_info(&root_path, functions, children);
+
+        assert_eq!(root.modules().len(), 1);
+        assert_eq!(root.modules()[0].name(), "child");
+        assert_eq!(root.modules()[0].modules()[0].name(), "grandchild");
+
+        let qualified_names: Vec<String> = root
+            .qualified_functions()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(
+            qualified_names,
+            vec![
+                FAKE_FN_NAME.to_string(),
+                format!("child::grandchild::{}", FAKE_FN_NAME)
+            ]
+        );
     }
 
     #[test]
     fn test_module_info_functions_none() {
         let functions = &[];
         let module_path = CString::new(FAKE_MODULE_PATH).expect("Invalid fake module path.");
-        let module = fake_module_info(&module_path, functions);
+        let module = fake_module_info(&module_path, functions, &[]);
 
         assert_eq!(module.functions().len(), functions.len());
     }
@@ -342,11 +859,12 @@ mod tests {
         let fn_info = FunctionInfo {
             signature: fn_signature,
             fn_ptr: ptr::null(),
+            docs: ptr::null(),
         };
 
         let functions = &[fn_info];
         let module_path = CString::new(FAKE_MODULE_PATH).expect("Invalid fake module path.");
-        let module = fake_module_info(&module_path, functions);
+        let module = fake_module_info(&module_path, functions, &[]);
 
         let result = module.functions();
         assert_eq!(result.len(), functions.len());
@@ -359,6 +877,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_module_info_globals() {
+        let type_name = CString::new(FAKE_TYPE_NAME).expect("Invalid fake type name.");
+        let type_info = fake_type_info(&type_name);
+
+        let mut value = 0u64;
+        let global_name = CString::new("score").expect("Invalid fake global name.");
+        let globals = &[GlobalInfo {
+            name: global_name.as_ptr(),
+            type_info: &type_info,
+            ptr: &mut value as *mut u64 as *mut c_void,
+        }];
+
+        let child_path = CString::new("child").expect("Invalid fake module path.");
+        let child = ModuleInfo {
+            globals: globals.as_ptr(),
+            num_globals: globals.len() as u32,
+            ..fake_module_info(&child_path, &[], &[])
+        };
+        let children = &[child];
+        let root_path = CString::new("").expect("Invalid fake module path.");
+        let root = fake_module_info(&root_path, &[], children);
+
+        assert!(root.globals().is_empty());
+        let child_globals = root.modules()[0].globals();
+        assert_eq!(child_globals.len(), 1);
+        assert_eq!(child_globals[0].name(), "score");
+        assert_eq!(child_globals[0].type_info().name(), FAKE_TYPE_NAME);
+
+        let qualified_names: Vec<String> = root
+            .qualified_globals()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(qualified_names, vec!["child::score".to_string()]);
+    }
+
     fn fake_dispatch_table(
         fn_signatures: &[FunctionSignature],
         fn_ptrs: &mut [*const c_void],
@@ -368,10 +923,20 @@ mod tests {
         DispatchTable {
             signatures: fn_signatures.as_ptr(),
             fn_ptrs: fn_ptrs.as_mut_ptr(),
+            hash: 0,
             num_entries: fn_signatures.len() as u32,
         }
     }
 
+    #[test]
+    fn test_dispatch_table_hash() {
+        let mut dispatch_table = fake_dispatch_table(&[], &mut []);
+        assert_eq!(dispatch_table.hash(), None);
+
+        dispatch_table.hash = 42;
+        assert_eq!(dispatch_table.hash(), Some(42));
+    }
+
     #[test]
     fn test_dispatch_table_iter_mut_none() {
         let signatures = &[];
@@ -559,15 +1124,19 @@ mod tests {
             dispatch_table,
             dependencies: dependencies.as_ptr(),
             num_dependencies: dependencies.len() as u32,
+            allocator_handle: ptr::null_mut(),
+            float_semantics: FloatSemantics::Strict,
+            target: FAKE_TARGET.as_ptr() as *const c_char,
         }
     }
 
     const FAKE_DEPENDENCY: &'static str = "path/to/dependency.dylib";
+    const FAKE_TARGET: &'static [u8] = b"x86_64-unknown-linux-gnu\0";
 
     #[test]
     fn test_assembly_info_dependencies() {
         let module_path = CString::new(FAKE_MODULE_PATH).expect("Invalid fake module path.");
-        let module = fake_module_info(&module_path, &[]);
+        let module = fake_module_info(&module_path, &[], &[]);
 
         let dispatch_table = fake_dispatch_table(&[], &mut []);
 
@@ -580,4 +1149,46 @@ mod tests {
             assert_eq!(lhs, *rhs)
         }
     }
+
+    #[test]
+    fn test_assembly_info_target() {
+        let module_path = CString::new(FAKE_MODULE_PATH).expect("Invalid fake module path.");
+        let module = fake_module_info(&module_path, &[], &[]);
+        let dispatch_table = fake_dispatch_table(&[], &mut []);
+        let assembly = fake_assembly_info(module, dispatch_table, &[]);
+
+        assert_eq!(assembly.target(), "x86_64-unknown-linux-gnu");
+    }
+
+    #[test]
+    fn test_assembly_info_set_allocator_handle() {
+        let module_path = CString::new(FAKE_MODULE_PATH).expect("Invalid fake module path.");
+        let module = fake_module_info(&module_path, &[], &[]);
+        let dispatch_table = fake_dispatch_table(&[], &mut []);
+        let mut assembly = fake_assembly_info(module, dispatch_table, &[]);
+
+        // An assembly without a slot ignores the handle
+        let mut handle = 0u8;
+        assembly.set_allocator_handle(&mut handle as *mut u8 as *mut c_void);
+
+        let mut slot = ptr::null_mut();
+        assembly.allocator_handle = &mut slot;
+        assembly.set_allocator_handle(&mut handle as *mut u8 as *mut c_void);
+        assert_eq!(slot, &mut handle as *mut u8 as *mut c_void);
+    }
+
+    #[test]
+    fn test_trap_site() {
+        let function = CString::new("enemies::update").expect("Invalid fake function name.");
+        let file = CString::new("enemies.mun").expect("Invalid fake file path.");
+        let site = TrapSite {
+            function: function.as_ptr(),
+            file: file.as_ptr(),
+            line: 42,
+            kind: TrapKind::Overflow,
+        };
+
+        assert_eq!(site.function(), "enemies::update");
+        assert_eq!(site.file(), "enemies.mun");
+    }
 }
@@ -0,0 +1,522 @@
+use crate::prelude::*;
+
+use std::ffi::{c_void, CString};
+use std::mem;
+use std::os::raw::c_char;
+use std::ptr;
+
+/// Describes a host-side [`Reflection`] type: its name, GUID, and memory layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TypeDesc {
+    /// Type name
+    pub name: &'static str,
+    /// Type GUID
+    pub guid: Guid,
+    /// Type size in bits
+    pub size_in_bits: u32,
+    /// Type alignment (in bytes)
+    pub alignment: u8,
+    /// Type memory kind
+    pub memory_kind: MemoryKind,
+}
+
+impl TypeDesc {
+    /// Constructs the `TypeDesc` of `T`.
+    pub fn of<T: Reflection>() -> Self {
+        Self::with_layout_of::<T>(T::type_name(), T::type_guid(), T::memory_kind())
+    }
+
+    /// Constructs the `TypeDesc` of `T` as a function argument.
+    pub fn of_argument<T: crate::ArgumentReflection>() -> Self {
+        Self::with_layout_of::<T>(T::type_name(), T::type_guid(), T::memory_kind())
+    }
+
+    fn with_layout_of<T>(name: &'static str, guid: Guid, memory_kind: MemoryKind) -> Self {
+        TypeDesc {
+            name,
+            guid,
+            size_in_bits: (mem::size_of::<T>() * 8) as u32,
+            alignment: mem::align_of::<T>() as u8,
+            memory_kind,
+        }
+    }
+
+    fn as_raw(&self) -> RawTypeDesc<'static> {
+        (
+            self.name,
+            self.guid,
+            self.size_in_bits,
+            self.alignment,
+            self.memory_kind,
+        )
+    }
+}
+
+/// The name, GUID, size in bits, alignment, and memory kind of a type, borrowed from either a
+/// [`TypeDesc`] or a [`TypeInfo`].
+type RawTypeDesc<'a> = (&'a str, Guid, u32, u8, MemoryKind);
+
+fn raw_type_desc(type_info: &TypeInfo) -> RawTypeDesc {
+    (
+        type_info.name(),
+        type_info.guid,
+        type_info.size_in_bits,
+        type_info.alignment,
+        type_info.memory_kind,
+    )
+}
+
+/// A type that can describe the argument types of a function as a tuple of
+/// [`ArgumentReflection`](trait.ArgumentReflection.html) types.
+pub trait ParamTypes {
+    /// Returns the descriptions of the argument types, in order.
+    fn param_types() -> Vec<TypeDesc>;
+}
+
+macro_rules! impl_param_types {
+    ($($T:ident),*) => {
+        impl<$($T: crate::ArgumentReflection,)*> ParamTypes for ($($T,)*) {
+            fn param_types() -> Vec<TypeDesc> {
+                vec![$(TypeDesc::of_argument::<$T>()),*]
+            }
+        }
+    }
+}
+
+impl_param_types!();
+impl_param_types!(A);
+impl_param_types!(A, B);
+impl_param_types!(A, B, C);
+impl_param_types!(A, B, C, D);
+impl_param_types!(A, B, C, D, E);
+impl_param_types!(A, B, C, D, E, F);
+impl_param_types!(A, B, C, D, E, F, G);
+impl_param_types!(A, B, C, D, E, F, G, H);
+impl_param_types!(A, B, C, D, E, F, G, H, I);
+impl_param_types!(A, B, C, D, E, F, G, H, I, J);
+impl_param_types!(A, B, C, D, E, F, G, H, I, J, K);
+impl_param_types!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+/// Owns the memory referenced by a [`FunctionInfo`] that was constructed on the host side.
+///
+/// The `FunctionInfo` returned by [`info`](#method.info) points into heap allocations owned by
+/// the storage, so it remains valid when the storage is moved, but must not outlive it.
+pub struct FunctionInfoStorage {
+    info: FunctionInfo,
+    _name: CString,
+    _type_names: Vec<CString>,
+    _arg_types: Vec<TypeInfo>,
+    _arg_names: Vec<Option<CString>>,
+    _arg_name_ptrs: Vec<*const c_char>,
+    _return_type: Option<Box<TypeInfo>>,
+    _docs: Option<CString>,
+}
+
+impl FunctionInfoStorage {
+    fn new(
+        name: &str,
+        arg_types: &[RawTypeDesc],
+        arg_names: &[Option<&str>],
+        return_type: Option<RawTypeDesc>,
+        privacy: Privacy,
+        fn_ptr: *const c_void,
+        docs: Option<&str>,
+    ) -> Self {
+        let name = CString::new(name).expect("function name contains a nul byte");
+        let docs = docs.map(|docs| CString::new(docs).expect("docs contain a nul byte"));
+        let arg_names: Vec<Option<CString>> = arg_names
+            .iter()
+            .map(|name| {
+                name.map(|name| CString::new(name).expect("argument name contains a nul byte"))
+            })
+            .collect();
+        // Arguments without a name have a null name, as in the information of compiled functions
+        let arg_name_ptrs: Vec<*const c_char> = arg_names
+            .iter()
+            .map(|name| name.as_ref().map_or(ptr::null(), |name| name.as_ptr()))
+            .collect();
+
+        let mut type_names = Vec::with_capacity(arg_types.len() + 1);
+        let mut new_type_info =
+            |(type_name, guid, size_in_bits, alignment, memory_kind): RawTypeDesc| {
+                let type_name = CString::new(type_name).expect("type name contains a nul byte");
+                let type_info = TypeInfo {
+                    guid,
+                    name: type_name.as_ptr(),
+                    size_in_bits,
+                    alignment,
+                    memory_kind,
+                    enum_info: ptr::null(),
+                    array_info: ptr::null(),
+                };
+                type_names.push(type_name);
+                type_info
+            };
+
+        let arg_types: Vec<TypeInfo> = arg_types
+            .iter()
+            .map(|type_desc| new_type_info(*type_desc))
+            .collect();
+        let return_type = return_type.map(|type_desc| Box::new(new_type_info(type_desc)));
+
+        // The pointers below refer to heap memory owned by `name`, `type_names`, `arg_types`,
+        // `arg_names`, `arg_name_ptrs`, `return_type`, and `docs`. Moving the storage does not
+        // move that memory, and the storage never mutates it, so the pointers stay valid for the
+        // lifetime of the storage.
+        let info = FunctionInfo {
+            signature: FunctionSignature {
+                name: name.as_ptr(),
+                arg_types: if arg_types.is_empty() {
+                    ptr::null()
+                } else {
+                    arg_types.as_ptr()
+                },
+                arg_names: if arg_name_ptrs.is_empty() {
+                    ptr::null()
+                } else {
+                    arg_name_ptrs.as_ptr()
+                },
+                return_type: return_type
+                    .as_ref()
+                    .map_or(ptr::null(), |t| t.as_ref() as *const TypeInfo),
+                num_arg_types: arg_types.len() as u16,
+                privacy,
+                // A host function is called through its Rust signature, for which the Rust
+                // compiler applies the C ABI
+                return_convention: ReturnConvention::Direct,
+            },
+            fn_ptr,
+            docs: docs.as_ref().map_or(ptr::null(), |docs| docs.as_ptr()),
+        };
+
+        FunctionInfoStorage {
+            info,
+            _name: name,
+            _type_names: type_names,
+            _arg_types: arg_types,
+            _arg_names: arg_names,
+            _arg_name_ptrs: arg_name_ptrs,
+            _return_type: return_type,
+            _docs: docs,
+        }
+    }
+
+    /// Returns the function information backed by this storage.
+    pub fn info(&self) -> &FunctionInfo {
+        &self.info
+    }
+}
+
+impl Clone for FunctionInfoStorage {
+    fn clone(&self) -> Self {
+        let signature = &self.info.signature;
+        let arg_types: Vec<RawTypeDesc> = signature.arg_types().iter().map(raw_type_desc).collect();
+        let arg_names: Vec<Option<&str>> = if signature.arg_names.is_null() {
+            Vec::new()
+        } else {
+            (0..arg_types.len())
+                .map(|idx| signature.arg_name(idx))
+                .collect()
+        };
+        FunctionInfoStorage::new(
+            signature.name(),
+            &arg_types,
+            &arg_names,
+            signature.return_type().map(raw_type_desc),
+            signature.privacy(),
+            self.info.fn_ptr,
+            if self.info.docs.is_null() {
+                None
+            } else {
+                Some(self.info.docs())
+            },
+        )
+    }
+}
+
+impl std::fmt::Debug for FunctionInfoStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FunctionInfoStorage")
+            .field("info", &self.info)
+            .finish()
+    }
+}
+
+unsafe impl Send for FunctionInfoStorage {}
+unsafe impl Sync for FunctionInfoStorage {}
+
+/// A builder for host-side [`FunctionInfo`]s, e.g.:
+///
+/// ```
+/// # use mun_abi::FunctionInfoBuilder;
+/// extern "C" fn add(a: f64, b: i64) -> f64 {
+///     a + b as f64
+/// }
+///
+/// let storage = FunctionInfoBuilder::new("add")
+///     .params::<(f64, i64)>()
+///     .returns::<f64>()
+///     .fn_ptr(add as *const std::ffi::c_void)
+///     .build();
+/// assert_eq!(storage.info().signature.name(), "add");
+/// ```
+#[derive(Clone, Debug)]
+pub struct FunctionInfoBuilder {
+    name: String,
+    arg_types: Vec<TypeDesc>,
+    arg_names: Vec<String>,
+    return_type: Option<TypeDesc>,
+    privacy: Privacy,
+    fn_ptr: *const c_void,
+    docs: Option<String>,
+}
+
+impl FunctionInfoBuilder {
+    /// Constructs a new `FunctionInfoBuilder` for a public function called `name` without
+    /// arguments or return type.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        FunctionInfoBuilder {
+            name: name.into(),
+            arg_types: Vec::new(),
+            arg_names: Vec::new(),
+            return_type: None,
+            privacy: Privacy::Public,
+            fn_ptr: ptr::null(),
+            docs: None,
+        }
+    }
+
+    /// Sets the argument types to the types of the tuple `T`.
+    pub fn params<T: ParamTypes>(mut self) -> Self {
+        self.arg_types = T::param_types();
+        self
+    }
+
+    /// Sets the names of the arguments. If set, there must be exactly one name per argument.
+    pub fn param_names<S: AsRef<str>>(mut self, names: &[S]) -> Self {
+        self.arg_names = names.iter().map(|n| n.as_ref().to_string()).collect();
+        self
+    }
+
+    /// Sets the return type to `T`. A return type of `()` results in a function without return
+    /// type.
+    pub fn returns<T: Reflection>(mut self) -> Self {
+        self.return_type = if T::type_guid() == <()>::type_guid() {
+            None
+        } else {
+            Some(TypeDesc::of::<T>())
+        };
+        self
+    }
+
+    /// Sets the `privacy` of the function.
+    pub fn privacy(mut self, privacy: Privacy) -> Self {
+        self.privacy = privacy;
+        self
+    }
+
+    /// Sets the documentation of the function.
+    pub fn docs<S: Into<String>>(mut self, docs: S) -> Self {
+        self.docs = Some(docs.into());
+        self
+    }
+
+    /// Sets the function pointer.
+    ///
+    /// The pointer must refer to an `extern "C"` function whose signature matches the argument
+    /// and return types of the builder.
+    pub fn fn_ptr(mut self, fn_ptr: *const c_void) -> Self {
+        self.fn_ptr = fn_ptr;
+        self
+    }
+
+    /// Constructs a [`FunctionInfoStorage`] that owns all memory referenced by its
+    /// [`FunctionInfo`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if no function pointer was set, if the number of argument names does not match the
+    /// number of arguments, or if any of the strings contains a nul byte.
+    pub fn build(self) -> FunctionInfoStorage {
+        assert!(
+            !self.fn_ptr.is_null(),
+            "no function pointer was set for '{}'",
+            self.name
+        );
+        assert!(
+            self.arg_names.is_empty() || self.arg_names.len() == self.arg_types.len(),
+            "expected {} argument names for '{}', found {}",
+            self.arg_types.len(),
+            self.name,
+            self.arg_names.len()
+        );
+        let arg_types: Vec<RawTypeDesc> = self.arg_types.iter().map(TypeDesc::as_raw).collect();
+        let arg_names: Vec<Option<&str>> = self
+            .arg_names
+            .iter()
+            .map(|name| Some(name.as_str()))
+            .collect();
+        FunctionInfoStorage::new(
+            &self.name,
+            &arg_types,
+            &arg_names,
+            self.return_type.as_ref().map(TypeDesc::as_raw),
+            self.privacy,
+            self.fn_ptr,
+            self.docs.as_ref().map(String::as_str),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern "C" fn add(a: f64, b: i64) -> f64 {
+        a + b as f64
+    }
+
+    extern "C" fn nop() {}
+
+    #[test]
+    fn test_function_info_builder() {
+        let storage = FunctionInfoBuilder::new("add")
+            .params::<(f64, i64)>()
+            .param_names(&["a", "b"])
+            .returns::<f64>()
+            .fn_ptr(add as *const c_void)
+            .docs("Adds two numbers")
+            .build();
+
+        let info = storage.info();
+        assert_eq!(info.signature.name(), "add");
+        assert_eq!(info.docs(), "Adds two numbers");
+        assert_eq!(info.signature.privacy(), Privacy::Public);
+        assert_eq!(info.fn_ptr, add as *const c_void);
+
+        let arg_types = info.signature.arg_types();
+        assert_eq!(arg_types.len(), 2);
+        assert_eq!(arg_types[0].name(), f64::type_name());
+        assert_eq!(arg_types[0].guid, f64::type_guid());
+        assert_eq!(arg_types[1].name(), i64::type_name());
+        assert_eq!(arg_types[1].guid, i64::type_guid());
+        assert_eq!(arg_types[1].size_in_bits, 64);
+        assert_eq!(arg_types[1].alignment, 8);
+        assert_eq!(info.signature.arg_name(0), Some("a"));
+        assert_eq!(info.signature.arg_name(1), Some("b"));
+
+        let return_type = info.signature.return_type().unwrap();
+        assert_eq!(return_type.name(), f64::type_name());
+        assert_eq!(return_type.guid, f64::type_guid());
+    }
+
+    #[test]
+    fn test_function_info_builder_empty() {
+        let storage = FunctionInfoBuilder::new("nop")
+            .returns::<()>()
+            .privacy(Privacy::Private)
+            .fn_ptr(nop as *const c_void)
+            .build();
+
+        let info = storage.info();
+        assert_eq!(info.signature.name(), "nop");
+        assert_eq!(info.signature.privacy(), Privacy::Private);
+        assert!(info.signature.arg_types().is_empty());
+        assert!(info.signature.return_type().is_none());
+        assert_eq!(info.docs(), "");
+    }
+
+    #[test]
+    fn test_function_info_storage_move_and_clone() {
+        let storage = FunctionInfoBuilder::new("add")
+            .params::<(f64, i64)>()
+            .param_names(&["a", "b"])
+            .returns::<f64>()
+            .fn_ptr(add as *const c_void)
+            .build();
+
+        // Moving the storage must not invalidate the pointers of its `FunctionInfo`
+        let storages = vec![storage];
+        let cloned = storages[0].clone();
+        drop(storages);
+
+        let info = cloned.info();
+        assert_eq!(info.signature.name(), "add");
+        assert_eq!(info.signature.arg_types()[1].name(), i64::type_name());
+        assert_eq!(info.signature.arg_types()[1].size_in_bits, 64);
+        assert_eq!(info.signature.arg_name(1), Some("b"));
+        assert_eq!(
+            info.signature.return_type().unwrap().name(),
+            f64::type_name()
+        );
+    }
+
+    #[test]
+    fn test_function_info_storage_clone_keeps_unnamed_args() {
+        let arg_types = [
+            TypeDesc::of::<f64>().as_raw(),
+            TypeDesc::of::<i64>().as_raw(),
+        ];
+        let storage = FunctionInfoStorage::new(
+            "add",
+            &arg_types,
+            &[Some("a"), None],
+            None,
+            Privacy::Public,
+            add as *const c_void,
+            None,
+        );
+
+        let cloned = storage.clone();
+        let signature = &cloned.info().signature;
+        assert!(!signature.arg_names.is_null());
+        assert_eq!(signature.arg_name(0), Some("a"));
+        assert!(unsafe { *signature.arg_names.add(1) }.is_null());
+        assert_eq!(signature.arg_name(1), None);
+    }
+
+    #[test]
+    fn test_type_desc_layout() {
+        let bool_desc = TypeDesc::of::<bool>();
+        assert_eq!(bool_desc.name, bool::type_name());
+        assert_eq!(bool_desc.size_in_bits, 8);
+        assert_eq!(bool_desc.alignment, 1);
+
+        let float_desc = TypeDesc::of::<f64>();
+        assert_eq!(float_desc.guid, f64::type_guid());
+        assert_eq!(float_desc.size_in_bits, 64);
+        assert_eq!(float_desc.alignment, 8);
+    }
+
+    #[test]
+    fn test_function_info_builder_reference_params() {
+        extern "C" fn increment(value: *mut i64) {
+            unsafe { *value += 1 };
+        }
+
+        let storage = FunctionInfoBuilder::new("increment")
+            .params::<(&mut i64,)>()
+            .fn_ptr(increment as *const c_void)
+            .build();
+
+        let arg_types = storage.info().signature.arg_types();
+        assert_eq!(arg_types[0].name(), "*mut @core::int");
+        assert_eq!(arg_types[0].size_in_bits, 64);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_function_info_builder_missing_fn_ptr() {
+        FunctionInfoBuilder::new("add").build();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_function_info_builder_param_names_mismatch() {
+        FunctionInfoBuilder::new("add")
+            .params::<(f64, i64)>()
+            .param_names(&["a"])
+            .fn_ptr(add as *const c_void)
+            .build();
+    }
+}
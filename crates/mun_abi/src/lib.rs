@@ -4,15 +4,23 @@
 //! Runtime.
 #![warn(missing_docs)]
 
+use serde::{Deserialize, Serialize};
+
 // Bindings are automatically generated from C on `cargo build`
 mod autogen;
 
 mod autogen_impl;
+mod function_info;
 mod macros;
+mod mangle;
+pub mod metadata;
 mod reflection;
 
 pub use autogen::*;
-pub use reflection::Reflection;
+pub use function_info::{FunctionInfoBuilder, FunctionInfoStorage, ParamTypes, TypeDesc};
+pub use mangle::demangle;
+pub use metadata::ABI_VERSION;
+pub use reflection::{ArgumentReflection, Reflection};
 
 /// The Mun ABI prelude
 ///
@@ -20,15 +28,64 @@ pub use reflection::Reflection;
 pub mod prelude {
     pub use crate::autogen::*;
     pub use crate::reflection::Reflection;
-    pub use crate::Privacy;
+    pub use crate::{FloatSemantics, MemoryKind, Privacy, ReturnConvention, TrapKind};
 }
 
 /// A type that represents the privacy level of modules, functions, or variables.
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Privacy {
     /// Publicly (and privately) accessible
     Public = 0,
     /// Privately accessible
     Private = 1,
 }
+
+/// A type that represents how the values of a type are stored in memory.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoryKind {
+    /// Values are stored in place and passed by value
+    Value = 0,
+    /// Values are allocated by the runtime and passed by pointer
+    GC = 1,
+}
+
+/// A type that represents how a function returns its value to the caller, as determined by the C
+/// ABI of the target.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReturnConvention {
+    /// The value is returned directly, e.g. in registers
+    Direct = 0,
+    /// The value is stored in memory that is allocated by the caller, a pointer to which is passed
+    /// as a hidden first argument
+    Pointer = 1,
+}
+
+/// A type that represents the floating point semantics with which an assembly was compiled.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FloatSemantics {
+    /// Floating point operations strictly follow IEEE 754
+    Strict = 0,
+    /// Floating point operations may be reordered and may assume that their operands and results
+    /// are neither NaN nor infinite
+    Fast = 1,
+}
+
+/// A type that represents the check that caused a trap.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TrapKind {
+    /// An integer operation overflowed while overflow checks were enabled
+    Overflow = 0,
+    /// An integer was divided by zero
+    DivisionByZero = 1,
+    /// An array was indexed out of its bounds while bounds checks were enabled
+    IndexOutOfBounds = 2,
+    /// An `assert` or `assert_eq` failed
+    AssertionFailed = 3,
+}
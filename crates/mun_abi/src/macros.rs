@@ -27,30 +27,87 @@ macro_rules! downcast_fn {
 
         let mut idx = 0;
         $(
-            if arg_types[idx].guid != $T::type_guid() {
+            // An array is accepted by a reference to a Rust array of the same element type and
+            // length, which is checked first to report a mismatching length
+            if let (Some(array_info), Some(length)) = (
+                arg_types[idx].array_info(),
+                <$T as $crate::ArgumentReflection>::array_length(),
+            ) {
+                if array_info.length() != length {
+                    return Err(format!(
+                        "Invalid length of array argument at index {}. Expected: {}. Found: {}.",
+                        idx,
+                        length,
+                        array_info.length(),
+                    ));
+                }
+            }
+            if arg_types[idx].guid != <$T as $crate::ArgumentReflection>::type_guid() {
                 return Err(format!(
-                    "Invalid argument type at index {}. Expected: {}. Found: {}.",
+                    "Invalid argument type at index {}{}. Expected: {}. Found: {}.",
                     idx,
-                    $T::type_name(),
+                    $FunctionInfo
+                        .signature
+                        .arg_name(idx)
+                        .map_or_else(String::new, |name| format!(" ('{}')", name)),
+                    <$T as $crate::ArgumentReflection>::type_name(),
                     arg_types[idx].name(),
                 ));
             }
+            if arg_types[idx].memory_kind != <$T as $crate::ArgumentReflection>::memory_kind() {
+                return Err(format!(
+                    "Invalid memory kind of argument type at index {}. Expected: {:?}. Found: {:?}.",
+                    idx,
+                    <$T as $crate::ArgumentReflection>::memory_kind(),
+                    arg_types[idx].memory_kind,
+                ));
+            }
+            // An enum is accepted by a Rust type of the same name whose size matches the integer
+            // type of the enum, e.g. a `#[repr(u8)]` enum
+            if arg_types[idx].enum_info().is_some()
+                && arg_types[idx].size_in_bits as usize != core::mem::size_of::<$T>() * 8
+            {
+                return Err(format!(
+                    "Invalid size of enum argument type at index {}. Expected: {} bits. Found: {} bits.",
+                    idx,
+                    core::mem::size_of::<$T>() * 8,
+                    arg_types[idx].size_in_bits,
+                ));
+            }
             idx += 1;
         )*
 
         if let Some(return_type) = $FunctionInfo.signature.return_type() {
-            if return_type.guid != Output::type_guid() {
+            if return_type.guid != <$Output as $crate::Reflection>::type_guid() {
                 return Err(format!(
                     "Invalid return type. Expected: {}. Found: {}",
-                    Output::type_name(),
+                    <$Output as $crate::Reflection>::type_name(),
                     return_type.name(),
                 ));
             }
-        } else if <()>::type_guid() != Output::type_guid() {
+            if return_type.memory_kind != <$Output as $crate::Reflection>::memory_kind() {
+                return Err(format!(
+                    "Invalid memory kind of return type. Expected: {:?}. Found: {:?}.",
+                    <$Output as $crate::Reflection>::memory_kind(),
+                    return_type.memory_kind,
+                ));
+            }
+            if return_type.enum_info().is_some()
+                && return_type.size_in_bits as usize != core::mem::size_of::<$Output>() * 8
+            {
+                return Err(format!(
+                    "Invalid size of enum return type. Expected: {} bits. Found: {} bits.",
+                    core::mem::size_of::<$Output>() * 8,
+                    return_type.size_in_bits,
+                ));
+            }
+        } else if <() as $crate::Reflection>::type_guid()
+            != <$Output as $crate::Reflection>::type_guid()
+        {
             return Err(format!(
                 "Invalid return type. Expected: {}. Found: {}",
-                Output::type_name(),
-                <()>::type_name(),
+                <$Output as $crate::Reflection>::type_name(),
+                <() as $crate::Reflection>::type_name(),
             ));
         }
 
@@ -0,0 +1,64 @@
+/// The prefix of the symbol name of a Mun function.
+const PREFIX: &str = "_MN";
+
+/// The suffix of the symbol name of a Mun function.
+const SUFFIX: &str = "E";
+
+/// Converts the symbol name of a Mun function back to the path of the function, e.g.
+/// `_MN4main6updateE` becomes `main::update`. The compiler mangles the names of functions so
+/// functions with the same name in different modules don't collide. Mangled names don't appear in
+/// the ABI itself, which refers to functions by their path, but they do appear in the symbol table
+/// of assemblies, e.g. in backtraces and profilers.
+///
+/// Returns `None` if `symbol` is not the name of a Mun function.
+pub fn demangle(symbol: &str) -> Option<String> {
+    if !symbol.starts_with(PREFIX) {
+        return None;
+    }
+    let mut rest = &symbol[PREFIX.len()..];
+
+    let mut components = Vec::new();
+    while !rest.is_empty() && rest != SUFFIX {
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let len: usize = rest[..digits].parse().ok()?;
+        let component = rest[digits..].get(..len)?;
+        if len == 0 || component.contains("::") {
+            return None;
+        }
+        components.push(component);
+        rest = &rest[digits + len..];
+    }
+
+    if rest != SUFFIX || components.is_empty() {
+        return None;
+    }
+    Some(components.join("::"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::demangle;
+
+    #[test]
+    fn test_demangle() {
+        assert_eq!(
+            demangle("_MN4main6updateE"),
+            Some("main::update".to_owned())
+        );
+        assert_eq!(
+            demangle("_MN3foo3bar10long_name1E"),
+            Some("foo::bar::long_name1".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_demangle_invalid() {
+        assert_eq!(demangle("update"), None);
+        assert_eq!(demangle("_MNE"), None);
+        assert_eq!(demangle("_MN4main6update"), None);
+        assert_eq!(demangle("_MN4main9updateE"), None);
+        assert_eq!(demangle("_MN4mainupdateE"), None);
+        assert_eq!(demangle("_MN0E"), None);
+        assert_eq!(demangle("_ZN4main6updateE"), None);
+    }
+}
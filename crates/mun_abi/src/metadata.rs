@@ -0,0 +1,221 @@
+//! The metadata of an assembly: the information of its ABI in a serializable form, which tools can
+//! read without loading the native code of the assembly. The compiler writes the metadata next to
+//! the assembly, and the runtime extracts it from a loaded assembly, in the same format.
+
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The version of the ABI that the metadata describes. It is incremented whenever the layout of
+/// the types of the ABI changes.
+pub const ABI_VERSION: u32 = 1;
+
+/// The metadata of an assembly.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssemblyMetadata {
+    /// The version of the ABI of the assembly
+    pub abi_version: u32,
+    /// The MD5 hash of the sources of the assembly, in hexadecimal, if known
+    pub source_hash: Option<String>,
+    /// The paths of the assemblies that the assembly depends on
+    pub dependencies: Vec<String>,
+    /// The root module of the assembly
+    pub root: ModuleMetadata,
+}
+
+/// The metadata of a module and its child modules.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleMetadata {
+    /// The path of the module, e.g. `enemies::ai`, which is empty for the root module
+    pub path: String,
+    /// The functions of the module, sorted by name
+    pub functions: Vec<FunctionMetadata>,
+    /// The mutable statics of the module, sorted by name
+    pub globals: Vec<GlobalMetadata>,
+    /// The structs of the module, sorted by name
+    pub structs: Vec<StructMetadata>,
+    /// The child modules of the module, sorted by path
+    pub modules: Vec<ModuleMetadata>,
+}
+
+/// The metadata of a function.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionMetadata {
+    /// The name of the function relative to its module, e.g. `Vector::length`
+    pub name: String,
+    /// The parameters of the function that are passed to it
+    pub params: Vec<ParamMetadata>,
+    /// The name of the return type, or `None` if the function doesn't return a value
+    pub return_type: Option<String>,
+    /// The privacy level of the function
+    pub privacy: Privacy,
+}
+
+/// The metadata of a parameter of a function.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParamMetadata {
+    /// The name of the parameter, or `None` if it is not bound to a name
+    pub name: Option<String>,
+    /// The name of the type of the parameter, e.g. `@core::int`
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+/// The metadata of a mutable static.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GlobalMetadata {
+    /// The name of the static relative to its module
+    pub name: String,
+    /// The name of the type of the static
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+/// The metadata of a struct and the layout of its values.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StructMetadata {
+    /// The name of the type of the struct, e.g. `main::Vector`
+    pub name: String,
+    /// The privacy level of the struct
+    pub privacy: Privacy,
+    /// How values of the struct are stored in memory
+    pub memory_kind: MemoryKind,
+    /// The size of a value of the struct in bytes, including padding. Values of garbage collected
+    /// structs are stored behind a pointer, so this is the size of their allocation.
+    pub size: u64,
+    /// The alignment of a value of the struct in bytes
+    pub alignment: u32,
+    /// The fields of the struct, in declaration order
+    pub fields: Vec<FieldMetadata>,
+}
+
+/// The metadata of a field of a struct.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldMetadata {
+    /// The name of the field
+    pub name: String,
+    /// The name of the type of the field
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// The offset of the field from the start of the struct in bytes
+    pub offset: u64,
+}
+
+impl AssemblyMetadata {
+    /// Extracts the metadata of a loaded assembly from its information. The information doesn't
+    /// describe structs or the sources of the assembly, so the modules have no structs and the
+    /// source hash is `None`.
+    pub fn from_assembly_info(info: &AssemblyInfo) -> Self {
+        AssemblyMetadata {
+            abi_version: ABI_VERSION,
+            source_hash: None,
+            dependencies: info.dependencies().map(ToString::to_string).collect(),
+            root: ModuleMetadata::from_module_info(&info.symbols),
+        }
+    }
+
+    /// Parses metadata from JSON.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Formats the metadata as indented JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("metadata can always be serialized")
+    }
+}
+
+impl ModuleMetadata {
+    /// Constructs the metadata of a module and its descendants from their information.
+    fn from_module_info(info: &ModuleInfo) -> Self {
+        ModuleMetadata {
+            path: info.path().to_string(),
+            functions: info
+                .functions()
+                .iter()
+                .map(|function| FunctionMetadata::from_signature(&function.signature))
+                .collect(),
+            globals: info
+                .globals()
+                .iter()
+                .map(|global| GlobalMetadata {
+                    name: global.name().to_string(),
+                    ty: global.type_info().name().to_string(),
+                })
+                .collect(),
+            structs: Vec::new(),
+            modules: info.modules().iter().map(Self::from_module_info).collect(),
+        }
+    }
+}
+
+impl FunctionMetadata {
+    /// Constructs the metadata of a function from its signature.
+    fn from_signature(signature: &FunctionSignature) -> Self {
+        FunctionMetadata {
+            name: signature.name().to_string(),
+            params: signature
+                .arg_types()
+                .iter()
+                .enumerate()
+                .map(|(idx, ty)| ParamMetadata {
+                    name: signature.arg_name(idx).map(ToString::to_string),
+                    ty: ty.name().to_string(),
+                })
+                .collect(),
+            return_type: signature.return_type().map(|ty| ty.name().to_string()),
+            privacy: signature.privacy(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metadata_json_round_trip() {
+        let metadata = AssemblyMetadata {
+            abi_version: ABI_VERSION,
+            source_hash: Some("d41d8cd98f00b204e9800998ecf8427e".to_string()),
+            dependencies: vec!["core.munlib".to_string()],
+            root: ModuleMetadata {
+                path: String::new(),
+                functions: vec![FunctionMetadata {
+                    name: "add".to_string(),
+                    params: vec![
+                        ParamMetadata {
+                            name: Some("a".to_string()),
+                            ty: "@core::int".to_string(),
+                        },
+                        ParamMetadata {
+                            name: None,
+                            ty: "@core::int".to_string(),
+                        },
+                    ],
+                    return_type: Some("@core::int".to_string()),
+                    privacy: Privacy::Public,
+                }],
+                globals: Vec::new(),
+                structs: vec![StructMetadata {
+                    name: "main::Vector".to_string(),
+                    privacy: Privacy::Private,
+                    memory_kind: MemoryKind::GC,
+                    size: 8,
+                    alignment: 4,
+                    fields: vec![FieldMetadata {
+                        name: "x".to_string(),
+                        ty: "@core::f32".to_string(),
+                        offset: 0,
+                    }],
+                }],
+                modules: Vec::new(),
+            },
+        };
+
+        let json = metadata.to_json();
+        assert!(json.contains("\"type\": \"@core::int\""));
+        assert!(json.contains("\"privacy\": \"public\""));
+        assert!(json.contains("\"memory_kind\": \"gc\""));
+        assert_eq!(AssemblyMetadata::from_json(&json).unwrap(), metadata);
+    }
+}
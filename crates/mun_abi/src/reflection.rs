@@ -2,6 +2,28 @@ use crate::prelude::*;
 use md5;
 
 /// A type to emulate dynamic typing across compilation units for static types.
+///
+/// A Mun enum can be represented by a fieldless Rust enum whose type name is the path of the Mun
+/// enum, e.g. `main::State`, and whose `#[repr]` is the integer type of the Mun enum, which is the
+/// smallest type that fits all discriminants. The variants must have the same discriminants:
+///
+/// ```
+/// # use mun_abi::Reflection;
+/// // enum State { Idle, Walking, Attacking = 5 }
+/// #[repr(u8)]
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// enum State {
+///     Idle,
+///     Walking,
+///     Attacking = 5,
+/// }
+///
+/// impl Reflection for State {
+///     fn type_name() -> &'static str {
+///         "main::State"
+///     }
+/// }
+/// ```
 pub trait Reflection: 'static {
     /// Retrieves the type's `Guid`.
     fn type_guid() -> Guid {
@@ -12,6 +34,12 @@ pub trait Reflection: 'static {
 
     /// Retrieves the type's name.
     fn type_name() -> &'static str;
+
+    /// Retrieves how values of the type are stored in memory. Types that refer to a struct that
+    /// is allocated by the runtime must return `MemoryKind::GC`.
+    fn memory_kind() -> MemoryKind {
+        MemoryKind::Value
+    }
 }
 
 impl Reflection for f64 {
@@ -20,12 +48,61 @@ impl Reflection for f64 {
     }
 }
 
+impl Reflection for f32 {
+    fn type_name() -> &'static str {
+        "@core::f32"
+    }
+}
+
 impl Reflection for i64 {
     fn type_name() -> &'static str {
         "@core::int"
     }
 }
 
+impl Reflection for i8 {
+    fn type_name() -> &'static str {
+        "@core::i8"
+    }
+}
+
+impl Reflection for i16 {
+    fn type_name() -> &'static str {
+        "@core::i16"
+    }
+}
+
+impl Reflection for i32 {
+    fn type_name() -> &'static str {
+        "@core::i32"
+    }
+}
+
+impl Reflection for u8 {
+    fn type_name() -> &'static str {
+        "@core::u8"
+    }
+}
+
+impl Reflection for u16 {
+    fn type_name() -> &'static str {
+        "@core::u16"
+    }
+}
+
+impl Reflection for u32 {
+    fn type_name() -> &'static str {
+        "@core::u32"
+    }
+}
+
+impl Reflection for u64 {
+    fn type_name() -> &'static str {
+        "@core::u64"
+    }
+}
+
+/// A Mun bool is represented by a byte that is either 0 or 1, like a Rust `bool`.
 impl Reflection for bool {
     fn type_name() -> &'static str {
         "@core::bool"
@@ -37,3 +114,148 @@ impl Reflection for () {
         "@core::empty"
     }
 }
+
+impl Reflection for Str {
+    fn type_name() -> &'static str {
+        "@core::str"
+    }
+}
+
+/// Raw byte pointers are returned by functions that hand out memory, e.g. the runtime's allocator.
+impl Reflection for *mut u8 {
+    fn type_name() -> &'static str {
+        "*mut @core::u8"
+    }
+}
+
+/// A type that can be passed as an argument to a function across compilation units.
+///
+/// In addition to all [`Reflection`] types (except `()`), borrowed references to primitives are
+/// supported. They are passed as pointers: `&T` maps to `*const T` and `&mut T` to `*mut T`.
+/// References cannot be returned, because their lifetimes cannot be enforced across the boundary.
+/// Arrays of up to 32 primitives are passed by reference, as `&[T; N]`.
+pub trait ArgumentReflection {
+    /// Retrieves the argument type's `Guid`.
+    fn type_guid() -> Guid {
+        Guid {
+            b: md5::compute(Self::type_name()).0,
+        }
+    }
+
+    /// Retrieves the argument type's name.
+    fn type_name() -> &'static str;
+
+    /// Retrieves how values of the argument type are stored in memory.
+    fn memory_kind() -> MemoryKind {
+        MemoryKind::Value
+    }
+
+    /// Retrieves the number of elements of the array that the argument refers to, if the argument
+    /// is a reference to an array.
+    fn array_length() -> Option<usize> {
+        None
+    }
+}
+
+macro_rules! impl_argument_reflection {
+    ($($T:ty => $name:literal),+) => {
+        $(
+            impl ArgumentReflection for $T {
+                fn type_name() -> &'static str {
+                    $name
+                }
+            }
+
+            impl<'a> ArgumentReflection for &'a $T {
+                fn type_name() -> &'static str {
+                    concat!("*const ", $name)
+                }
+            }
+
+            impl<'a> ArgumentReflection for &'a mut $T {
+                fn type_name() -> &'static str {
+                    concat!("*mut ", $name)
+                }
+            }
+
+            impl_array_argument_reflection!(
+                $T => $name;
+                0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16
+                17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32
+            );
+        )+
+    };
+}
+
+/// Mun arrays are passed by pointer, so an array parameter `[T; N]` is represented by a reference
+/// to a Rust array `&[T; N]` with the same element type and length. The function only reads the
+/// array, unless the parameter is declared `mut`, in which case it works on a copy.
+macro_rules! impl_array_argument_reflection {
+    ($T:ty => $name:literal; $($N:literal)+) => {
+        $(
+            impl<'a> ArgumentReflection for &'a [$T; $N] {
+                fn type_name() -> &'static str {
+                    concat!("[", $name, "; ", $N, "]")
+                }
+
+                fn array_length() -> Option<usize> {
+                    Some($N)
+                }
+            }
+        )+
+    };
+}
+
+impl_argument_reflection!(
+    f32 => "@core::f32",
+    f64 => "@core::float",
+    i8 => "@core::i8",
+    i16 => "@core::i16",
+    i32 => "@core::i32",
+    i64 => "@core::int",
+    u8 => "@core::u8",
+    u16 => "@core::u16",
+    u32 => "@core::u32",
+    u64 => "@core::u64",
+    bool => "@core::bool"
+);
+
+/// Strings are passed by value, as a pointer to their bytes and their length.
+impl ArgumentReflection for Str {
+    fn type_name() -> &'static str {
+        "@core::str"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_argument_reflection_value() {
+        assert_eq!(
+            <i64 as ArgumentReflection>::type_name(),
+            <i64 as Reflection>::type_name()
+        );
+        assert_eq!(
+            <i64 as ArgumentReflection>::type_guid(),
+            <i64 as Reflection>::type_guid()
+        );
+    }
+
+    #[test]
+    fn test_argument_reflection_references() {
+        assert_eq!(<&i64>::type_name(), "*const @core::int");
+        assert_eq!(<&mut i64>::type_name(), "*mut @core::int");
+        assert_ne!(<&i64>::type_guid(), <&mut i64>::type_guid());
+        assert_ne!(<&i64>::type_guid(), <&f64>::type_guid());
+    }
+
+    #[test]
+    fn test_argument_reflection_arrays() {
+        assert_eq!(<&[f64; 16]>::type_name(), "[@core::float; 16]");
+        assert_eq!(<&[f64; 16]>::array_length(), Some(16));
+        assert_eq!(<&f64>::array_length(), None);
+        assert_ne!(<&[f64; 16]>::type_guid(), <&[f64; 15]>::type_guid());
+    }
+}
@@ -1,21 +1,34 @@
 use crate::code_gen::linker::LinkerError;
+use crate::ir::module::package_files;
 use crate::IrDatabase;
 use failure::Fail;
 use inkwell::module::Module;
 use inkwell::passes::{PassManager, PassManagerBuilder};
-use inkwell::targets::{CodeModel, FileType, InitializationConfig, RelocMode, Target};
+use inkwell::targets::{
+    CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetData, TargetMachine,
+};
 use inkwell::OptimizationLevel;
 use mun_hir::FileId;
+use std::fs;
 use std::io::{self, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 mod abi_types;
 mod linker;
+mod metadata;
+mod passes;
+pub(crate) mod units;
+pub(crate) mod verify;
+
+pub use self::passes::{InvalidPassPipeline, Pass, PassPipeline};
+pub use self::verify::{InvalidIrError, IrVerification};
 
 #[derive(Debug, Fail)]
 enum CodeGenerationError {
     #[fail(display = "{}", 0)]
     LinkerError(#[fail(cause)] LinkerError),
+    #[fail(display = "{}", 0)]
+    InvalidIr(#[fail(cause)] InvalidIrError),
     #[fail(display = "error linking modules: {}", 0)]
     ModuleLinkerError(String),
     #[fail(display = "unknown target triple: {}", 0)]
@@ -26,6 +39,49 @@ enum CodeGenerationError {
     CouldNotCreateObjectFile(io::Error),
     #[fail(display = "error generating machine code")]
     CodeGenerationError(String),
+    #[fail(display = "error writing {}: {}", 0, 1)]
+    CouldNotWriteArtifact(String, String),
+}
+
+/// An artifact that can be emitted for a module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Artifact {
+    /// The shared library that is loaded by the runtime
+    Lib,
+    /// The object file that is linked into the shared library
+    Obj,
+    /// The assembly of the target
+    Asm,
+    /// Textual LLVM IR after optimization
+    Ir,
+    /// Textual LLVM IR before optimization
+    UnoptimizedIr,
+    /// The metadata of the shared library as JSON, which describes its exports in the same format
+    /// as the metadata that the runtime extracts from a loaded library
+    Metadata,
+}
+
+impl Artifact {
+    /// Returns the path of the artifact, which is stored next to the shared library at
+    /// `library_path`.
+    pub fn output_path(self, library_path: &Path, target: &mun_target::spec::Target) -> PathBuf {
+        match self {
+            Artifact::Lib => library_path.to_path_buf(),
+            Artifact::Obj if target.options.is_like_windows => library_path.with_extension("obj"),
+            Artifact::Obj => library_path.with_extension("o"),
+            Artifact::Asm => library_path.with_extension("s"),
+            Artifact::Ir => library_path.with_extension("ll"),
+            Artifact::UnoptimizedIr => library_path.with_extension("unopt.ll"),
+            Artifact::Metadata => library_path.with_extension("mun.json"),
+        }
+    }
+}
+
+impl CodeGenerationError {
+    /// Constructs an error for an artifact at `path` that could not be written.
+    fn artifact_error(path: &Path, error: impl ToString) -> Self {
+        CodeGenerationError::CouldNotWriteArtifact(path.display().to_string(), error.to_string())
+    }
 }
 
 impl From<LinkerError> for CodeGenerationError {
@@ -34,21 +90,213 @@ impl From<LinkerError> for CodeGenerationError {
     }
 }
 
+impl From<InvalidIrError> for CodeGenerationError {
+    fn from(e: InvalidIrError) -> Self {
+        CodeGenerationError::InvalidIr(e)
+    }
+}
+
 /// Construct a shared object for the given `hir::FileId` at the specified output file location.
 pub fn write_module_shared_object(
     db: &impl IrDatabase,
     file_id: FileId,
     output_file_path: &Path,
+) -> Result<(), failure::Error> {
+    write_module_artifacts(db, file_id, output_file_path, &[Artifact::Lib])
+}
+
+/// Writes the `artifacts` for the given `hir::FileId`. Every artifact is stored next to the shared
+/// object at `output_file_path`, as determined by `Artifact::output_path`.
+pub fn write_module_artifacts(
+    db: &impl IrDatabase,
+    file_id: FileId,
+    output_file_path: &Path,
+    artifacts: &[Artifact],
 ) -> Result<(), failure::Error> {
     let target = db.target();
+    let emits = |artifact| artifacts.contains(&artifact);
+
+    if emits(Artifact::Metadata) {
+        let path = Artifact::Metadata.output_path(output_file_path, &target);
+        let module = db.module_ir(file_id);
+        let metadata =
+            metadata::gen_metadata(db, file_id, module.functions.keys(), module.globals.keys());
+        fs::write(&path, metadata.to_json())
+            .map_err(|e| CodeGenerationError::artifact_error(&path, e))?;
+    }
 
-    // Construct a module for the assembly
-    let assembly_module = db.context().create_module(
+    let assembly_module = assembly_module(
+        db,
+        file_id,
         output_file_path
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or("unknown"),
-    );
+        Some(&output_file_path.with_extension("invalid.ll")),
+    )?;
+
+    if emits(Artifact::UnoptimizedIr) {
+        let path = Artifact::UnoptimizedIr.output_path(output_file_path, &target);
+        assembly_module
+            .print_to_file(&path)
+            .map_err(|e| CodeGenerationError::artifact_error(&path, e))?;
+    }
+
+    // The other artifacts describe the whole module, so they can only be emitted from a single
+    // codegen unit
+    let optimization_lvl = db.optimization_lvl();
+    let pipeline = db.pass_pipeline();
+    let codegen_units = if emits(Artifact::Ir) || emits(Artifact::Asm) || emits(Artifact::Obj) {
+        1
+    } else {
+        db.codegen_units()
+    };
+
+    let objects = if codegen_units > 1 {
+        units::emit_objects(
+            &assembly_module,
+            codegen_units,
+            &target,
+            optimization_lvl,
+            &pipeline,
+        )?
+    } else {
+        // Optimize the assembly module
+        optimize_module(&assembly_module, optimization_lvl, &pipeline);
+
+        if emits(Artifact::Ir) {
+            let path = Artifact::Ir.output_path(output_file_path, &target);
+            assembly_module
+                .print_to_file(&path)
+                .map_err(|e| CodeGenerationError::artifact_error(&path, e))?;
+        }
+
+        // Construct target machine for machine code generation
+        let target_machine = create_target_machine(&target, optimization_lvl)?;
+
+        if emits(Artifact::Asm) {
+            let path = Artifact::Asm.output_path(output_file_path, &target);
+            target_machine
+                .write_to_file(&assembly_module, FileType::Assembly, &path)
+                .map_err(|e| CodeGenerationError::artifact_error(&path, e))?;
+        }
+
+        if !emits(Artifact::Obj) && !emits(Artifact::Lib) {
+            return Ok(());
+        }
+
+        // Generate object file
+        let obj = target_machine
+            .write_to_memory_buffer(&assembly_module, FileType::Object)
+            .map_err(|e| CodeGenerationError::CodeGenerationError(e.to_string()))?;
+        if emits(Artifact::Obj) {
+            let path = Artifact::Obj.output_path(output_file_path, &target);
+            fs::write(&path, obj.as_slice())
+                .map_err(|e| CodeGenerationError::artifact_error(&path, e))?;
+        }
+        vec![obj.as_slice().to_vec()]
+    };
+
+    if !emits(Artifact::Lib) {
+        return Ok(());
+    }
+
+    // The shared object is linked into a temporary file next to it, which then replaces the
+    // previous shared object at once. The runtime thus never loads a partially written library,
+    // and a failed build keeps the previous library.
+    let output_dir = output_file_path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let temp_path = tempfile::Builder::new()
+        .prefix(".")
+        .suffix(".tmp")
+        .tempfile_in(output_dir)
+        .map_err(|e| CodeGenerationError::artifact_error(output_file_path, e))?
+        .into_temp_path();
+
+    link_shared_object(&target, &objects, &temp_path)?;
+    temp_path
+        .persist(output_file_path)
+        .map_err(|e| CodeGenerationError::artifact_error(output_file_path, e))?;
+    Ok(())
+}
+
+/// The artifacts of a module that were emitted in memory by `emit_module_artifacts`.
+#[derive(Debug, Clone)]
+pub struct ModuleArtifacts {
+    /// The contents of the shared library that is loaded by the runtime
+    pub library: Vec<u8>,
+    /// Textual LLVM IR after optimization, if it was requested
+    pub ir: Option<String>,
+}
+
+/// Emits the shared library of the given `hir::FileId`, and optionally its optimized IR, into
+/// memory rather than next to an output file. The module is named `assembly_name`.
+///
+/// # Limitations
+///
+/// The object files are generated in memory, but the linker only reads its inputs from and writes
+/// its output to files. The objects and the shared library are therefore written to temporary
+/// files that are removed before returning. With `IrVerification::Dump` invalid IR is reported,
+/// but not dumped, because there is no output directory to dump it to.
+pub fn emit_module_artifacts(
+    db: &impl IrDatabase,
+    file_id: FileId,
+    assembly_name: &str,
+    emit_ir: bool,
+) -> Result<ModuleArtifacts, failure::Error> {
+    let target = db.target();
+    let assembly_module = assembly_module(db, file_id, assembly_name, None)?;
+
+    // The IR describes the whole module, so it can only be emitted from a single codegen unit
+    let optimization_lvl = db.optimization_lvl();
+    let pipeline = db.pass_pipeline();
+    let codegen_units = if emit_ir { 1 } else { db.codegen_units() };
+
+    let (objects, ir) = if codegen_units > 1 {
+        let objects = units::emit_objects(
+            &assembly_module,
+            codegen_units,
+            &target,
+            optimization_lvl,
+            &pipeline,
+        )?;
+        (objects, None)
+    } else {
+        optimize_module(&assembly_module, optimization_lvl, &pipeline);
+        let ir = if emit_ir {
+            Some(assembly_module.print_to_string().to_string())
+        } else {
+            None
+        };
+
+        let obj = create_target_machine(&target, optimization_lvl)?
+            .write_to_memory_buffer(&assembly_module, FileType::Object)
+            .map_err(|e| CodeGenerationError::CodeGenerationError(e.to_string()))?;
+        (vec![obj.as_slice().to_vec()], ir)
+    };
+
+    let library_path = tempfile::NamedTempFile::new()
+        .map_err(CodeGenerationError::CouldNotCreateObjectFile)?
+        .into_temp_path();
+    link_shared_object(&target, &objects, &library_path)?;
+    let library = fs::read(&library_path)
+        .map_err(|e| CodeGenerationError::artifact_error(&library_path, e))?;
+    Ok(ModuleArtifacts { library, ir })
+}
+
+/// Constructs the LLVM module of the assembly of the given `hir::FileId`, named `name`. The
+/// module contains the IR of the file and the `get_info` function, and is verified according to
+/// the `IrVerification` of the database. Invalid IR is dumped to `dump_path`, if it is given.
+fn assembly_module(
+    db: &impl IrDatabase,
+    file_id: FileId,
+    name: &str,
+    dump_path: Option<&Path>,
+) -> Result<Module, CodeGenerationError> {
+    let target = db.target();
+    let assembly_module = db.context().create_module(name);
 
     // Generate IR for the module and clone it so that we can modify it without modifying the
     // cached value.
@@ -58,13 +306,23 @@ pub fn write_module_shared_object(
         .map_err(|e| CodeGenerationError::ModuleLinkerError(e.to_string()))?;
 
     // Generate the `get_info` method.
+    let module_paths: Vec<Vec<mun_hir::Name>> = package_files(db, file_id)
+        .into_iter()
+        .map(|file_id| mun_hir::Module::from(file_id).path(db))
+        .collect();
     symbols::gen_reflection_ir(
         db,
+        &module_paths,
         &module.functions,
+        &module.globals,
         &module.dispatch_table,
         &assembly_module,
     );
 
+    // The runtime finds all symbols through `get_info`, so apart from that function only the
+    // `export` functions are exported
+    symbols::hide_internal_symbols(db, &module.functions, &assembly_module);
+
     // Initialize the x86 target
     Target::initialize_x86(&InitializationConfig::default());
 
@@ -72,57 +330,81 @@ pub fn write_module_shared_object(
     let llvm_target = Target::from_triple(&target.llvm_target)
         .map_err(|e| CodeGenerationError::UnknownTargetTriple(e.to_string()))?;
     assembly_module.set_target(&llvm_target);
+    assembly_module.set_data_layout(&TargetData::create(&target.data_layout).get_data_layout());
+
+    // The IR is verified before it is optimized, because LLVM may crash on invalid IR
+    let verification = db.ir_verification();
+    if verification != IrVerification::Off {
+        let dump_path = dump_path.filter(|_| verification == IrVerification::Dump);
+        verify::verify_module(&assembly_module, dump_path).map_err(CodeGenerationError::from)?;
+    }
 
-    // Optimize the assembly module
-    optimize_module(&assembly_module, db.optimization_lvl());
+    Ok(assembly_module)
+}
 
-    // Debug print the IR
-    //println!("{}", assembly_module.print_to_string().to_string());
+/// Links the `objects`, in order, into a shared library at `path`. The linker reads the objects
+/// from files, so they are first written to temporary files.
+fn link_shared_object(
+    target: &mun_target::spec::Target,
+    objects: &[Vec<u8>],
+    path: &Path,
+) -> Result<(), CodeGenerationError> {
+    let obj_files = objects
+        .iter()
+        .map(|obj| {
+            let mut obj_file = tempfile::NamedTempFile::new()?;
+            obj_file.write_all(obj)?;
+            Ok(obj_file)
+        })
+        .collect::<Result<Vec<_>, io::Error>>()
+        .map_err(CodeGenerationError::CouldNotCreateObjectFile)?;
 
-    // Construct target machine for machine code generation
-    let target_machine = llvm_target
+    // Construct a linker for the target, which links the objects in the order of their codegen
+    // units
+    let mut linker = linker::create_with_target(target);
+    for obj_file in &obj_files {
+        linker.add_object(obj_file.path())?;
+    }
+    linker.build_shared_object(path)?;
+    linker.finalize()?;
+    Ok(())
+}
+
+/// Constructs a target machine that generates machine code for `target`.
+fn create_target_machine(
+    target: &mun_target::spec::Target,
+    optimization_lvl: OptimizationLevel,
+) -> Result<TargetMachine, CodeGenerationError> {
+    let llvm_target = Target::from_triple(&target.llvm_target)
+        .map_err(|e| CodeGenerationError::UnknownTargetTriple(e.to_string()))?;
+    llvm_target
         .create_target_machine(
             &target.llvm_target,
             &target.options.cpu,
             &target.options.features,
-            db.optimization_lvl(),
+            optimization_lvl,
             RelocMode::PIC,
             CodeModel::Default,
         )
-        .ok_or(CodeGenerationError::CouldNotCreateTargetMachine)?;
-
-    // Generate object file
-    let obj_file = {
-        let obj = target_machine
-            .write_to_memory_buffer(&assembly_module, FileType::Object)
-            .map_err(|e| CodeGenerationError::CodeGenerationError(e.to_string()))?;
-        let mut obj_file = tempfile::NamedTempFile::new()
-            .map_err(CodeGenerationError::CouldNotCreateObjectFile)?;
-        obj_file
-            .write(obj.as_slice())
-            .map_err(CodeGenerationError::CouldNotCreateObjectFile)?;
-        obj_file
-    };
-
-    // Construct a linker for the target
-    let mut linker = linker::create_with_target(&target);
-    linker.add_object(obj_file.path())?;
-
-    // Link the object
-    linker.build_shared_object(&output_file_path)?;
-    linker.finalize()?;
-
-    Ok(())
+        .ok_or(CodeGenerationError::CouldNotCreateTargetMachine)
 }
 
-/// Optimizes the specified LLVM `Module` using the default passes for the given
-/// `OptimizationLevel`.
-fn optimize_module(module: &Module, optimization_lvl: OptimizationLevel) {
-    let pass_builder = PassManagerBuilder::create();
-    pass_builder.set_optimization_level(optimization_lvl);
-
+/// Optimizes the specified LLVM `Module` using the passes of the `PassPipeline`. The default
+/// pipeline uses the default passes for the given `OptimizationLevel`.
+fn optimize_module(module: &Module, optimization_lvl: OptimizationLevel, pipeline: &PassPipeline) {
     let module_pass_manager = PassManager::create(());
-    pass_builder.populate_module_pass_manager(&module_pass_manager);
+    match pipeline {
+        PassPipeline::Default => {
+            let pass_builder = PassManagerBuilder::create();
+            pass_builder.set_optimization_level(optimization_lvl);
+            pass_builder.populate_module_pass_manager(&module_pass_manager);
+        }
+        PassPipeline::Passes(passes) => {
+            for pass in passes {
+                pass.add_to(&module_pass_manager);
+            }
+        }
+    }
     module_pass_manager.run_on(module);
 }
 
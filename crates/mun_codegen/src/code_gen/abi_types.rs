@@ -6,8 +6,11 @@ pub(super) struct AbiTypes {
     pub guid_type: ArrayType,
     pub privacy_type: IntType,
     pub type_info_type: StructType,
+    pub enum_info_type: StructType,
+    pub array_info_type: StructType,
     pub function_signature_type: StructType,
     pub function_info_type: StructType,
+    pub global_info_type: StructType,
     pub module_info_type: StructType,
     pub dispatch_table_type: StructType,
     pub assembly_info_type: StructType,
@@ -23,12 +26,36 @@ pub(super) fn gen_abi_types(context: ContextRef) -> AbiTypes {
     // Construct the `MunPrivacy` enum
     let privacy_type = context.i8_type();
 
-    // Construct the `MunTypeInfo` struct
+    // Construct the `MunTypeInfo`, `MunEnumInfo` and `MunArrayInfo` structs, which refer to each
+    // other
     let type_info_type = context.opaque_struct_type("struct.MunTypeInfo");
+    let enum_info_type = context.opaque_struct_type("struct.MunEnumInfo");
+    let array_info_type = context.opaque_struct_type("struct.MunArrayInfo");
     type_info_type.set_body(
         &[
-            guid_type.into(), // guid
-            str_type.into(),  // name
+            guid_type.into(),                                     // guid
+            str_type.into(),                                      // name
+            context.i32_type().into(),                            // size_in_bits
+            context.i8_type().into(),                             // alignment
+            context.i8_type().into(),                             // memory_kind
+            enum_info_type.ptr_type(AddressSpace::Const).into(),  // enum_info
+            array_info_type.ptr_type(AddressSpace::Const).into(), // array_info
+        ],
+        false,
+    );
+    enum_info_type.set_body(
+        &[
+            str_type.ptr_type(AddressSpace::Const).into(), // variant_names
+            context.i64_type().ptr_type(AddressSpace::Const).into(), // variant_values
+            type_info_type.ptr_type(AddressSpace::Const).into(), // repr
+            context.i16_type().into(),                     // num_variants
+        ],
+        false,
+    );
+    array_info_type.set_body(
+        &[
+            type_info_type.ptr_type(AddressSpace::Const).into(), // element_type
+            context.i64_type().into(),                           // length
         ],
         false,
     );
@@ -39,9 +66,11 @@ pub(super) fn gen_abi_types(context: ContextRef) -> AbiTypes {
         &[
             str_type.into(),                                     // name
             type_info_type.ptr_type(AddressSpace::Const).into(), // arg_types
+            str_type.ptr_type(AddressSpace::Const).into(),       // arg_names
             type_info_type.ptr_type(AddressSpace::Const).into(), // return_type
             context.i16_type().into(),                           // num_arg_types
             privacy_type.into(),                                 // privacy
+            context.i8_type().into(),                            // return_convention
         ],
         false,
     );
@@ -56,6 +85,18 @@ pub(super) fn gen_abi_types(context: ContextRef) -> AbiTypes {
                 .fn_type(&[], false)
                 .ptr_type(AddressSpace::Const)
                 .into(), // fn_ptr
+            str_type.into(),                // docs
+        ],
+        false,
+    );
+
+    // Construct the `MunGlobalInfo` struct
+    let global_info_type = context.opaque_struct_type("struct.MunGlobalInfo");
+    global_info_type.set_body(
+        &[
+            str_type.into(),                                          // name
+            type_info_type.ptr_type(AddressSpace::Const).into(),      // type_info
+            context.i8_type().ptr_type(AddressSpace::Generic).into(), // ptr
         ],
         false,
     );
@@ -66,7 +107,11 @@ pub(super) fn gen_abi_types(context: ContextRef) -> AbiTypes {
         &[
             str_type.into(),                                         // path
             function_info_type.ptr_type(AddressSpace::Const).into(), // functions
+            module_info_type.ptr_type(AddressSpace::Const).into(),   // modules
+            global_info_type.ptr_type(AddressSpace::Const).into(),   // globals
             context.i32_type().into(),                               // num_functions
+            context.i32_type().into(),                               // num_modules
+            context.i32_type().into(),                               // num_globals
         ],
         false,
     );
@@ -82,6 +127,7 @@ pub(super) fn gen_abi_types(context: ContextRef) -> AbiTypes {
                 .ptr_type(AddressSpace::Generic)
                 .ptr_type(AddressSpace::Const)
                 .into(), // fn_ptrs
+            context.i64_type().into(),                                    // hash
             context.i32_type().into(),                                    // num_entries
         ],
         false,
@@ -95,6 +141,13 @@ pub(super) fn gen_abi_types(context: ContextRef) -> AbiTypes {
             dispatch_table_type.into(),
             str_type.ptr_type(AddressSpace::Const).into(),
             context.i32_type().into(),
+            context
+                .i8_type()
+                .ptr_type(AddressSpace::Generic)
+                .ptr_type(AddressSpace::Generic)
+                .into(), // allocator_handle
+            context.i8_type().into(), // float_semantics
+            str_type.into(),          // target
         ],
         false,
     );
@@ -103,8 +156,11 @@ pub(super) fn gen_abi_types(context: ContextRef) -> AbiTypes {
         guid_type,
         privacy_type,
         type_info_type,
+        enum_info_type,
+        array_info_type,
         function_signature_type,
         function_info_type,
+        global_info_type,
         module_info_type,
         dispatch_table_type,
         assembly_info_type,
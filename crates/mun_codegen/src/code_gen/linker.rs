@@ -156,13 +156,19 @@ impl Linker for MsvcLinker {
             .to_str()
             .ok_or_else(|| LinkerError::PathError(path.to_owned()))?;
 
-        let dll_lib_path_str = path
+        // The import library must not overwrite the DLL itself
+        let dll_lib_path = path.with_extension("dll.lib");
+        let dll_lib_path_str = dll_lib_path
             .to_str()
             .ok_or_else(|| LinkerError::PathError(path.to_owned()))?;
 
         self.args.push("/DLL".to_owned());
         self.args.push("/NOENTRY".to_owned());
-        self.args.push("/EXPORT:get_info".to_owned());
+        // Use a hash of the output instead of a timestamp, so identical inputs produce identical
+        // libraries
+        self.args.push("/Brepro".to_owned());
+        self.args
+            .push(format!("/EXPORT:{}", super::symbols::GET_INFO_FN_NAME));
         self.args.push(format!("/IMPLIB:{}", dll_lib_path_str));
         self.args.push(format!("/OUT:{}", dll_path_str));
         Ok(())
@@ -0,0 +1,174 @@
+use super::symbols::{function_privacy, signature_params};
+use crate::ir::adt::struct_name;
+use crate::ir::module::package_files;
+use crate::IrDatabase;
+use mun_abi::metadata::{
+    AssemblyMetadata, FieldMetadata, FunctionMetadata, GlobalMetadata, ModuleMetadata,
+    ParamMetadata, StructMetadata, ABI_VERSION,
+};
+use mun_abi::{MemoryKind, Privacy};
+use mun_hir::{self as hir, FileId, ModuleDef, StructMemoryKind, Ty, TypeCtor};
+
+/// Constructs the metadata of the assembly of `file_id`, which contains the `functions` and
+/// `statics` of the module IR. The metadata describes the same modules, functions and mutable
+/// statics as the `get_info` function of the assembly, and additionally the structs of the
+/// package and the hash of its sources.
+pub(super) fn gen_metadata<'a>(
+    db: &impl IrDatabase,
+    file_id: FileId,
+    functions: impl Iterator<Item = &'a hir::Function>,
+    statics: impl Iterator<Item = &'a hir::Static>,
+) -> AssemblyMetadata {
+    let functions: Vec<hir::Function> = functions.copied().collect();
+    let statics: Vec<hir::Static> = statics.filter(|s| s.is_mutable(db)).copied().collect();
+    let mut files = package_files(db, file_id);
+    files.sort_by_cached_key(|file_id| db.file_relative_path(*file_id));
+
+    // The hash covers the path and the text of every file, so that moving code to another module
+    // changes it
+    let mut hasher = md5::Context::new();
+    for file_id in files.iter() {
+        hasher.consume(db.file_relative_path(*file_id).as_str());
+        hasher.consume([0u8]);
+        hasher.consume(db.file_text(*file_id).as_bytes());
+    }
+
+    AssemblyMetadata {
+        abi_version: ABI_VERSION,
+        source_hash: Some(format!("{:x}", hasher.compute())),
+        dependencies: db.assembly_dependencies().to_vec(),
+        root: gen_module_metadata(db, &[], &files, &functions, &statics),
+    }
+}
+
+/// Constructs the metadata of the module at `path` and of its descendants, which are the modules
+/// of `files`.
+fn gen_module_metadata(
+    db: &impl IrDatabase,
+    path: &[hir::Name],
+    files: &[FileId],
+    functions: &[hir::Function],
+    statics: &[hir::Static],
+) -> ModuleMetadata {
+    let mut module_functions: Vec<FunctionMetadata> = functions
+        .iter()
+        .filter(|f| f.module(db).path(db) == path)
+        .map(|f| gen_function_metadata(db, *f))
+        .collect();
+    module_functions.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut globals: Vec<GlobalMetadata> = statics
+        .iter()
+        .filter(|s| s.module(db).path(db) == path)
+        .map(|s| GlobalMetadata {
+            name: s.name(db).to_string(),
+            ty: db.type_info(s.ty(db)).name,
+        })
+        .collect();
+    globals.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // Structs with unsupported field types are reported as diagnostics, and have no layout
+    let mut structs: Vec<StructMetadata> = files
+        .iter()
+        .filter(|file_id| hir::Module::from(**file_id).path(db) == path)
+        .flat_map(|file_id| db.module_data(*file_id).definitions().to_vec())
+        .filter_map(|def| match def {
+            ModuleDef::Struct(s) if db.try_type_ir(Ty::simple(TypeCtor::Struct(s))).is_ok() => {
+                Some(gen_struct_metadata(db, s))
+            }
+            _ => None,
+        })
+        .collect();
+    structs.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // The child modules are the next path components of the modules below `path`
+    let mut child_names: Vec<hir::Name> = files
+        .iter()
+        .map(|file_id| hir::Module::from(*file_id).path(db))
+        .filter(|module_path| module_path.len() > path.len() && module_path.starts_with(path))
+        .map(|module_path| module_path[path.len()].clone())
+        .collect();
+    child_names.sort();
+    child_names.dedup();
+    let modules = child_names
+        .into_iter()
+        .map(|name| {
+            let mut child_path = path.to_vec();
+            child_path.push(name);
+            gen_module_metadata(db, &child_path, files, functions, statics)
+        })
+        .collect();
+
+    ModuleMetadata {
+        path: path
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("::"),
+        functions: module_functions,
+        globals,
+        structs,
+        modules,
+    }
+}
+
+/// Constructs the metadata of `function`, with the parameters and the return type of its
+/// signature in the ABI.
+fn gen_function_metadata(db: &impl IrDatabase, function: hir::Function) -> FunctionMetadata {
+    let body = function.body(db);
+    let infer = function.infer(db);
+    let params = signature_params(db, function)
+        .into_iter()
+        .map(|p| ParamMetadata {
+            name: match &body[p] {
+                hir::Pat::Bind { name, .. } => Some(name.to_string()),
+                _ => None,
+            },
+            ty: db.type_info(infer[p].clone()).name,
+        })
+        .collect();
+
+    let ret_type = function.ty(db).callable_sig(db).unwrap().ret().clone();
+    let return_type = if ret_type.is_empty() || ret_type.is_never() {
+        None
+    } else {
+        Some(db.type_info(ret_type).name)
+    };
+
+    FunctionMetadata {
+        name: function.full_name(db),
+        params,
+        return_type,
+        privacy: function_privacy(db, function),
+    }
+}
+
+/// Constructs the metadata of the struct `s`, with the layout of its values on the target.
+fn gen_struct_metadata(db: &impl IrDatabase, s: hir::Struct) -> StructMetadata {
+    let layout = db.struct_layout(s);
+    let fields = s
+        .fields(db)
+        .into_iter()
+        .zip(layout.field_offsets.iter())
+        .map(|(field, offset)| FieldMetadata {
+            name: field.name(db).to_string(),
+            ty: db.type_info(field.ty(db)).name,
+            offset: *offset,
+        })
+        .collect();
+
+    StructMetadata {
+        name: struct_name(db, s),
+        privacy: match s.visibility(db) {
+            hir::Visibility::Public => Privacy::Public,
+            hir::Visibility::Private => Privacy::Private,
+        },
+        memory_kind: match s.memory_kind(db) {
+            StructMemoryKind::GC => MemoryKind::GC,
+            StructMemoryKind::Value => MemoryKind::Value,
+        },
+        size: layout.size,
+        alignment: layout.alignment,
+        fields,
+    }
+}
@@ -0,0 +1,154 @@
+use failure::Fail;
+use inkwell::passes::PassManager;
+use std::fmt;
+use std::str::FromStr;
+
+/// An LLVM pass that can be part of a custom pass pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pass {
+    /// Promotes stack allocations to registers (`mem2reg`)
+    PromoteMemoryToRegister,
+    /// Replaces aggregates on the stack by their scalar fields (`sroa`)
+    ScalarReplAggregates,
+    /// Combines redundant instructions (`instcombine`)
+    InstructionCombining,
+    /// Reassociates commutative expressions to improve constant propagation (`reassociate`)
+    Reassociate,
+    /// Eliminates fully and partially redundant instructions (`gvn`)
+    Gvn,
+    /// Eliminates trivially redundant instructions early (`early-cse`)
+    EarlyCse,
+    /// Simplifies the control flow graph (`simplifycfg`)
+    CfgSimplification,
+    /// Eliminates stores that are never read (`dse`)
+    DeadStoreElimination,
+    /// Eliminates instructions that don't contribute to the result (`adce`)
+    AggressiveDce,
+    /// Hoists loop invariant code out of loops (`licm`)
+    Licm,
+    /// Turns tail recursion into loops (`tailcallelim`)
+    TailCallElimination,
+    /// Inlines functions (`inline`)
+    FunctionInlining,
+    /// Removes unused globals and functions (`globaldce`)
+    GlobalDce,
+}
+
+impl Pass {
+    /// All passes, in the order in which they are listed in error messages.
+    const ALL: [Pass; 13] = [
+        Pass::PromoteMemoryToRegister,
+        Pass::ScalarReplAggregates,
+        Pass::InstructionCombining,
+        Pass::Reassociate,
+        Pass::Gvn,
+        Pass::EarlyCse,
+        Pass::CfgSimplification,
+        Pass::DeadStoreElimination,
+        Pass::AggressiveDce,
+        Pass::Licm,
+        Pass::TailCallElimination,
+        Pass::FunctionInlining,
+        Pass::GlobalDce,
+    ];
+
+    /// Returns the name of the pass, which matches the name that LLVM's `opt` uses.
+    pub fn name(self) -> &'static str {
+        match self {
+            Pass::PromoteMemoryToRegister => "mem2reg",
+            Pass::ScalarReplAggregates => "sroa",
+            Pass::InstructionCombining => "instcombine",
+            Pass::Reassociate => "reassociate",
+            Pass::Gvn => "gvn",
+            Pass::EarlyCse => "early-cse",
+            Pass::CfgSimplification => "simplifycfg",
+            Pass::DeadStoreElimination => "dse",
+            Pass::AggressiveDce => "adce",
+            Pass::Licm => "licm",
+            Pass::TailCallElimination => "tailcallelim",
+            Pass::FunctionInlining => "inline",
+            Pass::GlobalDce => "globaldce",
+        }
+    }
+
+    /// Adds the pass to `pass_manager`.
+    pub(crate) fn add_to<T>(self, pass_manager: &PassManager<T>) {
+        match self {
+            Pass::PromoteMemoryToRegister => pass_manager.add_promote_memory_to_register_pass(),
+            Pass::ScalarReplAggregates => pass_manager.add_scalar_repl_aggregates_pass(),
+            Pass::InstructionCombining => pass_manager.add_instruction_combining_pass(),
+            Pass::Reassociate => pass_manager.add_reassociate_pass(),
+            Pass::Gvn => pass_manager.add_gvn_pass(),
+            Pass::EarlyCse => pass_manager.add_early_cse_pass(),
+            Pass::CfgSimplification => pass_manager.add_cfg_simplification_pass(),
+            Pass::DeadStoreElimination => pass_manager.add_dead_store_elimination_pass(),
+            Pass::AggressiveDce => pass_manager.add_aggressive_dce_pass(),
+            Pass::Licm => pass_manager.add_licm_pass(),
+            Pass::TailCallElimination => pass_manager.add_tail_call_elimination_pass(),
+            Pass::FunctionInlining => pass_manager.add_function_inlining_pass(),
+            Pass::GlobalDce => pass_manager.add_global_dce_pass(),
+        }
+    }
+}
+
+impl fmt::Display for Pass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Determines which LLVM passes are run on a module before machine code is generated.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PassPipeline {
+    /// The passes of the optimization level
+    Default,
+    /// Exactly the specified passes, in order, instead of the passes of the optimization level.
+    /// The optimization level still determines how machine code is generated.
+    Passes(Vec<Pass>),
+}
+
+impl Default for PassPipeline {
+    fn default() -> Self {
+        PassPipeline::Default
+    }
+}
+
+/// An error that is returned when a pass pipeline cannot be parsed.
+#[derive(Debug, Fail, PartialEq, Eq)]
+pub enum InvalidPassPipeline {
+    #[fail(display = "the pass pipeline is empty")]
+    Empty,
+    #[fail(display = "unknown pass `{}`, expected one of: {}", 0, 1)]
+    UnknownPass(String, String),
+}
+
+/// Parses a pipeline, which is either `default` or a comma separated list of passes, e.g.
+/// `mem2reg,instcombine`.
+impl FromStr for PassPipeline {
+    type Err = InvalidPassPipeline;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "default" {
+            return Ok(PassPipeline::Default);
+        }
+        if s.is_empty() {
+            return Err(InvalidPassPipeline::Empty);
+        }
+
+        s.split(',')
+            .map(|name| {
+                let name = name.trim();
+                Pass::ALL
+                    .iter()
+                    .find(|pass| pass.name() == name)
+                    .copied()
+                    .ok_or_else(|| {
+                        let names: Vec<&str> = Pass::ALL.iter().map(|pass| pass.name()).collect();
+                        InvalidPassPipeline::UnknownPass(name.to_string(), names.join(", "))
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(PassPipeline::Passes)
+    }
+}
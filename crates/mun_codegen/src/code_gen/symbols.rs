@@ -1,25 +1,60 @@
 use super::abi_types::{gen_abi_types, AbiTypes};
-use crate::ir::dispatch_table::DispatchTable;
-use crate::ir::function;
+use crate::ir::adt::{enum_name, struct_name};
+use crate::ir::body::ALLOCATOR_HANDLE_NAME;
+use crate::ir::dispatch_table::{DispatchTable, DispatchableFunction, DISPATCH_TABLE_NAME};
+use crate::ir::function::{self, FloatSemantics};
+use crate::ir::intrinsics::Intrinsic;
+use crate::ir::ty::{fn_return_convention, is_zero_sized, ReturnConvention};
+use crate::mangle::function_symbol_name;
 use crate::values::{BasicValue, GlobalValue};
 use crate::IrDatabase;
 use inkwell::attributes::Attribute;
+use inkwell::targets::TargetData;
+use inkwell::types::{AnyType, AnyTypeEnum};
 use inkwell::values::{IntValue, PointerValue, UnnamedAddress};
 use inkwell::{
     module::{Linkage, Module},
     values::{FunctionValue, StructValue},
-    AddressSpace,
+    AddressSpace, GlobalVisibility,
 };
-use mun_hir::{self as hir, Ty, TypeCtor};
-use std::collections::HashMap;
+use mun_abi::Privacy;
+use mun_hir::{self as hir, IntTy, StructMemoryKind, Ty, TypeCtor};
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 
 pub type Guid = [u8; 16];
 
+/// The name of the function through which the runtime obtains the `AssemblyInfo` of a library.
+pub(super) const GET_INFO_FN_NAME: &str = "get_info";
+
 #[derive(Clone, Eq, Ord, PartialOrd, Debug)]
 pub struct TypeInfo {
     pub guid: Guid,
     pub name: String,
+    pub size_in_bits: u32,
+    pub alignment: u32,
+    pub memory_kind: StructMemoryKind,
+    pub enum_info: Option<EnumTypeInfo>,
+    pub array_info: Option<ArrayTypeInfo>,
+}
+
+/// The variants of an enum type.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct EnumTypeInfo {
+    /// The name and the value of every variant, in declaration order. The value is the bit
+    /// pattern of the discriminant in the integer type of the enum, zero-extended to 64 bits.
+    pub variants: Vec<(String, u64)>,
+    /// The integer type that represents values of the enum
+    pub repr: Box<TypeInfo>,
+}
+
+/// The element type and the length of an array type.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct ArrayTypeInfo {
+    /// The type of the elements
+    pub element_type: Box<TypeInfo>,
+    /// The number of elements
+    pub length: u64,
 }
 
 impl Hash for TypeInfo {
@@ -35,28 +70,112 @@ impl PartialEq for TypeInfo {
 }
 
 impl TypeInfo {
-    fn from_name<S: AsRef<str>>(name: S) -> TypeInfo {
+    /// Constructs a `TypeInfo` for the type called `name`, with the size and alignment of the IR
+    /// type `ir_ty` in the specified data layout. Values of the type are stored by value.
+    fn new<S: AsRef<str>>(name: S, ir_ty: AnyTypeEnum, target_data: &TargetData) -> TypeInfo {
         TypeInfo {
             name: name.as_ref().to_string(),
             guid: md5::compute(name.as_ref()).0,
+            size_in_bits: (target_data.get_abi_size(&ir_ty) * 8) as u32,
+            alignment: target_data.get_abi_alignment(&ir_ty),
+            memory_kind: StructMemoryKind::Value,
+            enum_info: None,
+            array_info: None,
         }
     }
 }
 
-pub fn type_info_query(_db: &impl IrDatabase, ty: Ty) -> TypeInfo {
+pub fn type_info_query(db: &impl IrDatabase, ty: Ty) -> TypeInfo {
+    let target_data = TargetData::create(&db.target().data_layout);
+    let ir_ty = db.type_ir(ty.clone());
     match ty {
         Ty::Apply(ctor) => match ctor.ctor {
-            TypeCtor::Float => TypeInfo::from_name("@core::float"),
-            TypeCtor::Int => TypeInfo::from_name("@core::int"),
-            TypeCtor::Bool => TypeInfo::from_name("@core::bool"),
+            TypeCtor::Float(float_ty) => {
+                TypeInfo::new(format!("@core::{}", float_ty), ir_ty, &target_data)
+            }
+            TypeCtor::Int(int_ty) => {
+                TypeInfo::new(format!("@core::{}", int_ty), ir_ty, &target_data)
+            }
+            TypeCtor::Bool => TypeInfo::new("@core::bool", ir_ty, &target_data),
+            TypeCtor::Str => TypeInfo::new("@core::str", ir_ty, &target_data),
+            TypeCtor::Struct(s) => TypeInfo {
+                memory_kind: s.memory_kind(db),
+                ..TypeInfo::new(struct_name(db, s), ir_ty, &target_data)
+            },
+            TypeCtor::Enum(e) => {
+                let repr = e.repr(db);
+                let variants = e
+                    .variants(db)
+                    .into_iter()
+                    .map(|v| {
+                        let value = discriminant_bits(v.discriminant(db), repr);
+                        (v.name(db).to_string(), value)
+                    })
+                    .collect();
+                TypeInfo {
+                    enum_info: Some(EnumTypeInfo {
+                        variants,
+                        repr: Box::new(db.type_info(Ty::simple(TypeCtor::Int(repr)))),
+                    }),
+                    ..TypeInfo::new(enum_name(db, e), ir_ty, &target_data)
+                }
+            }
+            // Tuples are anonymous value structs that are named after their element types
+            TypeCtor::Tuple { .. } => {
+                let elements: Vec<String> = ctor
+                    .parameters
+                    .iter()
+                    .map(|ty| db.type_info(ty.clone()).name)
+                    .collect();
+                let name = if elements.len() == 1 {
+                    format!("({},)", elements[0])
+                } else {
+                    format!("({})", elements.join(", "))
+                };
+                TypeInfo::new(name, ir_ty, &target_data)
+            }
+            TypeCtor::Array { len } => {
+                let element_type = db.type_info(ctor.parameters[0].clone());
+                let name = format!("[{}; {}]", element_type.name, len);
+                TypeInfo {
+                    array_info: Some(ArrayTypeInfo {
+                        element_type: Box::new(element_type),
+                        length: len,
+                    }),
+                    ..TypeInfo::new(name, ir_ty, &target_data)
+                }
+            }
+            // Function pointers are named after the parameters and the return type that are
+            // part of their signature
+            TypeCtor::FnPtr { num_args } => {
+                let (params, ret) = ctor.parameters.split_at(num_args as usize);
+                let params: Vec<String> = params
+                    .iter()
+                    .filter(|ty| !is_zero_sized(db, ty))
+                    .map(|ty| db.type_info(ty.clone()).name)
+                    .collect();
+                let ret = match &ret[0] {
+                    ty if ty.is_never() => ": never".to_string(),
+                    ty if is_zero_sized(db, ty) => String::new(),
+                    ty => format!(": {}", db.type_info(ty.clone()).name),
+                };
+                let name = format!("fn({}){}", params.join(", "), ret);
+                TypeInfo::new(name, ir_ty, &target_data)
+            }
             _ => unreachable!("{:?} unhandled", ctor),
         },
         _ => unreachable!(),
     }
 }
 
+/// Returns the bit pattern of `discriminant` in the integer type `repr`, zero-extended to 64 bits.
+fn discriminant_bits(discriminant: i128, repr: IntTy) -> u64 {
+    let unused_bits = 64 - repr.bitness.bits();
+    (discriminant as u64) << unused_bits >> unused_bits
+}
+
 /// Construct an IR `MunTypeInfo` struct value for the specified `TypeInfo`
-fn type_info_ir(ty: &TypeInfo, module: &Module) -> StructValue {
+fn type_info_ir(ty: &TypeInfo, module: &Module, types: &AbiTypes) -> StructValue {
     let context = module.get_context();
     let guid_values: [IntValue; 16] =
         array_init::array_init(|i| context.i8_type().const_int(u64::from(ty.guid[i]), false));
@@ -64,11 +183,113 @@ fn type_info_ir(ty: &TypeInfo, module: &Module) -> StructValue {
         &[
             context.i8_type().const_array(&guid_values).into(),
             intern_string(module, &ty.name).into(),
+            context
+                .i32_type()
+                .const_int(u64::from(ty.size_in_bits), false)
+                .into(),
+            context
+                .i8_type()
+                .const_int(u64::from(ty.alignment), false)
+                .into(),
+            context
+                .i8_type()
+                .const_int(
+                    match ty.memory_kind {
+                        StructMemoryKind::Value => 0,
+                        StructMemoryKind::GC => 1,
+                    },
+                    false,
+                )
+                .into(),
+            match &ty.enum_info {
+                Some(enum_info) => gen_global(module, &enum_info_ir(enum_info, module, types), "")
+                    .as_pointer_value(),
+                None => types
+                    .enum_info_type
+                    .ptr_type(AddressSpace::Const)
+                    .const_null(),
+            }
+            .into(),
+            match &ty.array_info {
+                Some(array_info) => {
+                    gen_global(module, &array_info_ir(array_info, module, types), "")
+                        .as_pointer_value()
+                }
+                None => types
+                    .array_info_type
+                    .ptr_type(AddressSpace::Const)
+                    .const_null(),
+            }
+            .into(),
         ],
         false,
     )
 }
 
+/// Construct an IR `MunEnumInfo` struct value for the specified `EnumTypeInfo`
+fn enum_info_ir(enum_info: &EnumTypeInfo, module: &Module, types: &AbiTypes) -> StructValue {
+    let context = module.get_context();
+    let str_type = context.i8_type().ptr_type(AddressSpace::Const);
+    let names: Vec<PointerValue> = enum_info
+        .variants
+        .iter()
+        .map(|(name, _)| intern_string(module, name).const_cast(str_type))
+        .collect();
+    let values: Vec<IntValue> = enum_info
+        .variants
+        .iter()
+        .map(|(_, value)| context.i64_type().const_int(*value, false))
+        .collect();
+
+    types.enum_info_type.const_named_struct(&[
+        gen_global(module, &str_type.const_array(&names), "")
+            .as_pointer_value()
+            .into(),
+        gen_global(module, &context.i64_type().const_array(&values), "")
+            .as_pointer_value()
+            .into(),
+        gen_type_info_ptr(module, types, Some(&enum_info.repr)).into(),
+        context
+            .i16_type()
+            .const_int(enum_info.variants.len() as u64, false)
+            .into(),
+    ])
+}
+
+/// Construct an IR `MunArrayInfo` struct value for the specified `ArrayTypeInfo`
+fn array_info_ir(array_info: &ArrayTypeInfo, module: &Module, types: &AbiTypes) -> StructValue {
+    types.array_info_type.const_named_struct(&[
+        gen_type_info_ptr(module, types, Some(&array_info.element_type)).into(),
+        module
+            .get_context()
+            .i64_type()
+            .const_int(array_info.length, false)
+            .into(),
+    ])
+}
+
+/// Returns the `TypeInfo`s of the arguments and of the return type of the intrinsic.
+fn intrinsic_type_infos(
+    db: &impl IrDatabase,
+    module: &Module,
+    intrinsic: Intrinsic,
+) -> (Vec<TypeInfo>, Option<TypeInfo>) {
+    let target_data = TargetData::create(&db.target().data_layout);
+    let ir_type = intrinsic.ir_type(&module.get_context());
+    let arg_types = intrinsic
+        .arg_type_names()
+        .iter()
+        .zip(ir_type.get_param_types())
+        .map(|(name, ty)| TypeInfo::new(name, ty.as_any_type_enum(), &target_data))
+        .collect();
+    let ret_type = intrinsic.ret_type_name().and_then(|name| {
+        ir_type
+            .get_return_type()
+            .map(|ty| TypeInfo::new(name, ty.as_any_type_enum(), &target_data))
+    });
+    (arg_types, ret_type)
+}
+
 /// Intern a string by constructing a global value. Looks something like this:
 /// ```c
 /// const char[] GLOBAL_ = "str";
@@ -78,60 +299,184 @@ fn intern_string(module: &Module, str: &str) -> PointerValue {
     gen_global(module, &value, ".str").as_pointer_value()
 }
 
-/// Construct a `MunFunctionSignature` struct for the specified HIR function.
+/// Construct a `MunFunctionSignature` struct with the name `name` for the specified HIR function.
+/// The name of a function in the info of its module is relative to the module, whereas the
+/// dispatch table refers to functions by their fully qualified paths.
 fn gen_signature_from_function<D: IrDatabase>(
     db: &D,
     module: &Module,
     types: &AbiTypes,
     function: hir::Function,
+    name: &str,
 ) -> StructValue {
-    let name_str = intern_string(&module, &function.name(db).to_string());
+    let name_str = intern_string(&module, name);
     let ret_type_ir = gen_signature_return_type(db, module, types, function);
     let params_type_ir = gen_signature_argument_types(db, module, types, function);
+    let params_name_ir = gen_signature_argument_names(db, module, function);
 
-    let body = function.body(db);
     types.function_signature_type.const_named_struct(&[
         name_str.into(),
         params_type_ir.into(),
+        params_name_ir.into(),
         ret_type_ir.into(),
         module
             .get_context()
             .i16_type()
-            .const_int(body.params().len() as u64, false)
+            .const_int(signature_params(db, function).len() as u64, false)
+            .into(),
+        module
+            .get_context()
+            .i8_type()
+            .const_int(function_privacy(db, function) as u64, false)
+            .into(),
+        module
+            .get_context()
+            .i8_type()
+            .const_int(
+                match fn_return_convention(db, &db.fn_signature(function)) {
+                    ReturnConvention::Direct => 0,
+                    ReturnConvention::Pointer => 1,
+                },
+                false,
+            )
             .into(),
-        module.get_context().i8_type().const_int(0, false).into(),
     ])
 }
 
-/// Given a function, construct a pointer to a `MunTypeInfo[]` global that represents the argument
-/// types of the function; or `null` if the function has no arguments.
-fn gen_signature_argument_types<D: IrDatabase>(
+/// Construct a `MunFunctionSignature` struct for the specified intrinsic.
+fn gen_signature_from_intrinsic<D: IrDatabase>(
     db: &D,
     module: &Module,
     types: &AbiTypes,
-    function: hir::Function,
-) -> PointerValue {
-    let body = function.body(db);
-    let infer = function.infer(db);
-    if body.params().is_empty() {
+    intrinsic: Intrinsic,
+) -> StructValue {
+    let (arg_types, ret_type) = intrinsic_type_infos(db, module, intrinsic);
+    let str_type = module.get_context().i8_type().ptr_type(AddressSpace::Const);
+    let arg_names: Vec<PointerValue> = intrinsic
+        .arg_names()
+        .iter()
+        .map(|name| intern_string(module, name).const_cast(str_type))
+        .collect();
+
+    types.function_signature_type.const_named_struct(&[
+        intern_string(module, intrinsic.name()).into(),
+        gen_type_info_array(module, types, &arg_types).into(),
+        gen_global(module, &str_type.const_array(&arg_names), "")
+            .as_pointer_value()
+            .into(),
+        gen_type_info_ptr(module, types, ret_type.as_ref()).into(),
+        module
+            .get_context()
+            .i16_type()
+            .const_int(arg_types.len() as u64, false)
+            .into(),
+        module.get_context().i8_type().const_int(0, false).into(),
+        // Intrinsics only return primitives, which are returned directly
+        module.get_context().i8_type().const_int(0, false).into(),
+    ])
+}
+
+/// Construct a pointer to a `MunTypeInfo[]` global that contains the specified types; or `null` if
+/// there are no types.
+fn gen_type_info_array(module: &Module, types: &AbiTypes, type_infos: &[TypeInfo]) -> PointerValue {
+    if type_infos.is_empty() {
         types
             .type_info_type
             .ptr_type(AddressSpace::Const)
             .const_null()
     } else {
-        let params_type_array_ir = types.type_info_type.const_array(
-            &body
-                .params()
+        let type_info_array_ir = types.type_info_type.const_array(
+            &type_infos
                 .iter()
-                .map(|(p, _)| type_info_ir(&db.type_info(infer[*p].clone()), &module))
+                .map(|ty| type_info_ir(ty, module, types))
                 .collect::<Vec<StructValue>>(),
         );
-        gen_global(module, &params_type_array_ir, "").as_pointer_value()
+        gen_global(module, &type_info_array_ir, "").as_pointer_value()
+    }
+}
+
+/// Construct a pointer to a `MunTypeInfo` global for the specified type; or `null` if there is no
+/// type.
+fn gen_type_info_ptr(
+    module: &Module,
+    types: &AbiTypes,
+    type_info: Option<&TypeInfo>,
+) -> PointerValue {
+    match type_info {
+        Some(ty) => gen_global(module, &type_info_ir(ty, module, types), "").as_pointer_value(),
+        None => types
+            .type_info_type
+            .ptr_type(AddressSpace::Const)
+            .const_null(),
+    }
+}
+
+/// Returns the privacy level of `function` in the ABI.
+pub(super) fn function_privacy<D: IrDatabase>(db: &D, function: hir::Function) -> Privacy {
+    // Tests are invoked by the test runner, which only retrieves public functions of other modules
+    match function.visibility(db) {
+        hir::Visibility::Public => Privacy::Public,
+        hir::Visibility::Private if db.test_build() && function.is_test(db) => Privacy::Public,
+        hir::Visibility::Private => Privacy::Private,
+    }
+}
+
+/// Returns the parameter patterns of a function that are part of its signature. Zero-sized
+/// parameters are omitted, because they are not passed to the function.
+pub(super) fn signature_params<D: IrDatabase>(db: &D, function: hir::Function) -> Vec<hir::PatId> {
+    let body = function.body(db);
+    body.params()
+        .iter()
+        .zip(function::param_ir_indices(db, &db.fn_signature(function)))
+        .filter_map(|((p, _), idx)| idx.map(|_| *p))
+        .collect()
+}
+
+/// Given a function, construct a pointer to a `MunTypeInfo[]` global that represents the argument
+/// types of the function; or `null` if the function has no arguments.
+fn gen_signature_argument_types<D: IrDatabase>(
+    db: &D,
+    module: &Module,
+    types: &AbiTypes,
+    function: hir::Function,
+) -> PointerValue {
+    let infer = function.infer(db);
+    let arg_types: Vec<TypeInfo> = signature_params(db, function)
+        .into_iter()
+        .map(|p| db.type_info(infer[p].clone()))
+        .collect();
+    gen_type_info_array(module, types, &arg_types)
+}
+
+/// Given a function, construct a pointer to a `const char*[]` global that contains the names of the
+/// arguments of the function; or `null` if the function has no arguments. Arguments that are not
+/// bound to a name are represented by a `null` entry.
+fn gen_signature_argument_names<D: IrDatabase>(
+    db: &D,
+    module: &Module,
+    function: hir::Function,
+) -> PointerValue {
+    let body = function.body(db);
+    let params = signature_params(db, function);
+    let str_type = module.get_context().i8_type().ptr_type(AddressSpace::Const);
+    if params.is_empty() {
+        str_type.ptr_type(AddressSpace::Const).const_null()
+    } else {
+        let names: Vec<PointerValue> = params
+            .into_iter()
+            .map(|p| match &body[p] {
+                hir::Pat::Bind { name, .. } => {
+                    intern_string(module, &name.to_string()).const_cast(str_type)
+                }
+                _ => str_type.const_null(),
+            })
+            .collect();
+        gen_global(module, &str_type.const_array(&names), "").as_pointer_value()
     }
 }
 
 /// Given a function, construct a pointer to a `MunTypeInfo` global that represents the return type
-/// of the function; or `null` if the return type is empty.
+/// of the function; or `null` if the function does not return a value.
 fn gen_signature_return_type<D: IrDatabase>(
     db: &D,
     module: &Module,
@@ -140,15 +485,12 @@ fn gen_signature_return_type<D: IrDatabase>(
 ) -> PointerValue {
     let sig = function.ty(db).callable_sig(db).unwrap();
     let ret_type = sig.ret().clone();
-    if ret_type.is_empty() {
-        types
-            .type_info_type
-            .ptr_type(AddressSpace::Const)
-            .const_null()
+    let ret_type_info = if ret_type.is_empty() || ret_type.is_never() {
+        None
     } else {
-        let ret_type_const = type_info_ir(&db.type_info(ret_type), &module);
-        gen_global(module, &ret_type_const, "").as_pointer_value()
-    }
+        Some(db.type_info(ret_type))
+    };
+    gen_type_info_ptr(module, types, ret_type_info.as_ref())
 }
 
 /// Construct a global that holds a reference to all functions. e.g.:
@@ -161,19 +503,36 @@ fn gen_function_info_array<'a, D: IrDatabase>(
 ) -> GlobalValue {
     let function_infos: Vec<StructValue> = functions
         .map(|(f, value)| {
-            // Get the function from the cloned module and modify the linkage of the function.
+            // Get the function from the cloned module. The runtime reaches functions through their
+            // function info, so only the `export` functions keep a symbol that is visible outside
+            // of the module.
             let value = module
                 .get_function(value.get_name().to_str().unwrap())
                 .unwrap();
-            value.set_linkage(Linkage::Private);
+            if f.visibility(db) != hir::Visibility::Public {
+                value.set_linkage(Linkage::Private);
+            }
 
             // Generate the signature from the function
-            let signature = gen_signature_from_function(db, module, types, *f);
+            let signature = gen_signature_from_function(db, module, types, *f, &f.full_name(db));
+
+            // Generate the documentation string, or a nullptr if there is none
+            let docs = f
+                .documentation(db)
+                .map(|docs| intern_string(module, &docs))
+                .unwrap_or_else(|| {
+                    module
+                        .get_context()
+                        .i8_type()
+                        .ptr_type(AddressSpace::Const)
+                        .const_null()
+                });
 
             // Generate the function info value
             types.function_info_type.const_named_struct(&[
                 signature.into(),
                 value.as_global_value().as_pointer_value().into(),
+                docs.into(),
             ])
         })
         .collect();
@@ -181,6 +540,45 @@ fn gen_function_info_array<'a, D: IrDatabase>(
     gen_global(module, &function_infos, "fn.get_info.functions")
 }
 
+/// Construct a global that holds a reference to the specified statics. e.g.:
+/// MunGlobalInfo[] info = { ... }
+fn gen_global_info_array<D: IrDatabase>(
+    db: &D,
+    types: &AbiTypes,
+    module: &Module,
+    statics: &[mun_hir::Static],
+) -> PointerValue {
+    if statics.is_empty() {
+        return types
+            .global_info_type
+            .ptr_type(AddressSpace::Const)
+            .const_null();
+    }
+
+    let byte_ptr_type = module
+        .get_context()
+        .i8_type()
+        .ptr_type(AddressSpace::Generic);
+    let global_infos: Vec<StructValue> = statics
+        .iter()
+        .map(|s| {
+            // Get the global from the cloned module, the name in the ABI is relative to the module
+            // of the static
+            let name = s.name(db).to_string();
+            let value = module.get_global(&s.qualified_name(db)).unwrap();
+
+            let type_info = db.type_info(s.ty(db));
+            types.global_info_type.const_named_struct(&[
+                intern_string(module, &name).into(),
+                gen_type_info_ptr(module, types, Some(&type_info)).into(),
+                value.as_pointer_value().const_cast(byte_ptr_type).into(),
+            ])
+        })
+        .collect();
+    let global_infos = types.global_info_type.const_array(&global_infos);
+    gen_global(module, &global_infos, "global.get_info.globals").as_pointer_value()
+}
+
 /// Construct a global from the specified value
 fn gen_global(module: &Module, value: &dyn BasicValue, name: &str) -> GlobalValue {
     let global = module.add_global(value.as_basic_value_enum().get_type(), None, name);
@@ -195,6 +593,56 @@ fn gen_global(module: &Module, value: &dyn BasicValue, name: &str) -> GlobalValu
 /// ```c
 /// MunDispatchTable dispatchTable = { ... }
 /// ```
+/// Computes a hash of the paths and signatures of the functions in the dispatch table. The runtime
+/// uses it to detect whether the slots of a reloaded assembly still refer to the same functions,
+/// in which case they can be relinked by index instead of by name.
+fn dispatch_table_hash<D: IrDatabase>(
+    db: &D,
+    module: &Module,
+    dispatch_table: &DispatchTable,
+) -> u64 {
+    let mut layout = String::new();
+    for function in dispatch_table.entries() {
+        let (arg_types, ret_type) = match function {
+            DispatchableFunction::Function(f) => {
+                let sig = f.ty(db).callable_sig(db).unwrap();
+                let arg_types = sig
+                    .params()
+                    .iter()
+                    .map(|param| db.type_info(param.clone()))
+                    .collect();
+                let ret_type = if sig.ret().is_empty() || sig.ret().is_never() {
+                    None
+                } else {
+                    Some(db.type_info(sig.ret().clone()))
+                };
+                (arg_types, ret_type)
+            }
+            DispatchableFunction::Intrinsic(intrinsic) => {
+                intrinsic_type_infos(db, module, *intrinsic)
+            }
+        };
+        layout.push_str(&function.name(db));
+        layout.push('(');
+        for arg_type in arg_types {
+            layout.push_str(&arg_type.name);
+            layout.push(',');
+        }
+        layout.push(')');
+        if let Some(ret_type) = ret_type {
+            layout.push_str(&ret_type.name);
+        }
+        layout.push(';');
+    }
+
+    let digest = md5::compute(layout).0;
+    let mut hash = [0u8; 8];
+    hash.copy_from_slice(&digest[..8]);
+
+    // A hash of zero signifies that no hash is available
+    u64::from_le_bytes(hash).max(1)
+}
+
 fn gen_dispatch_table<D: IrDatabase>(
     db: &D,
     types: &AbiTypes,
@@ -205,7 +653,14 @@ fn gen_dispatch_table<D: IrDatabase>(
     let signatures: Vec<StructValue> = dispatch_table
         .entries()
         .iter()
-        .map(|f| gen_signature_from_function(db, module, types, *f))
+        .map(|f| match f {
+            DispatchableFunction::Function(f) => {
+                gen_signature_from_function(db, module, types, *f, &f.qualified_name(db))
+            }
+            DispatchableFunction::Intrinsic(intrinsic) => {
+                gen_signature_from_intrinsic(db, module, types, *intrinsic)
+            }
+        })
         .collect();
 
     // Construct an IR array from the signatures
@@ -216,27 +671,29 @@ fn gen_dispatch_table<D: IrDatabase>(
     );
 
     // Get the pointer to the global table (or nullptr if no global table was defined).
-    let dispatch_table_ptr = dispatch_table
-        .global_value()
-        .map(|_g|
-            // TODO: This is a hack, the passed module here is a clone of the module with which the
-            // dispatch table was created. Because of this we have to lookup the dispatch table
-            // global again. There is however not a `GlobalValue::get_name` method so I just
-            // hardcoded the name here.
-            module.get_global("dispatchTable").unwrap().as_pointer_value())
-        .unwrap_or_else(|| {
-            module
-                .get_context()
-                .void_type()
-                .fn_type(&[], false)
-                .ptr_type(AddressSpace::Const)
-                .ptr_type(AddressSpace::Generic)
-                .const_null()
-        });
+    let dispatch_table_ptr = if dispatch_table.entries().is_empty() {
+        module
+            .get_context()
+            .void_type()
+            .fn_type(&[], false)
+            .ptr_type(AddressSpace::Const)
+            .ptr_type(AddressSpace::Generic)
+            .const_null()
+    } else {
+        module
+            .get_global(DISPATCH_TABLE_NAME)
+            .expect("the dispatch table is defined when it has entries")
+            .as_pointer_value()
+    };
 
     types.dispatch_table_type.const_named_struct(&[
         signatures.as_pointer_value().into(),
         dispatch_table_ptr.into(),
+        module
+            .get_context()
+            .i64_type()
+            .const_int(dispatch_table_hash(db, module, dispatch_table), false)
+            .into(),
         module
             .get_context()
             .i32_type()
@@ -245,30 +702,32 @@ fn gen_dispatch_table<D: IrDatabase>(
     ])
 }
 
-/// Constructs IR that exposes the types and symbols in the specified module. A function called
+/// Constructs IR that exposes the types and symbols in the specified module, which contains the
+/// modules at `module_paths`. A function called
 /// `get_info` is constructed that returns a struct `MunAssemblyInfo`. See the `mun_abi` crate
 /// for the ABI that `get_info` exposes.
 pub(super) fn gen_reflection_ir(
     db: &impl IrDatabase,
+    module_paths: &[Vec<hir::Name>],
     function_map: &HashMap<mun_hir::Function, FunctionValue>,
+    global_map: &HashMap<mun_hir::Static, GlobalValue>,
     dispatch_table: &DispatchTable,
     module: &Module,
 ) {
     // Get all the types
     let abi_types = gen_abi_types(module.get_context());
 
-    // Construct the module info struct
-    let module_info = abi_types.module_info_type.const_named_struct(&[
-        intern_string(module, "").into(),
-        gen_function_info_array(db, &abi_types, module, function_map.iter())
-            .as_pointer_value()
-            .into(),
-        module
-            .get_context()
-            .i32_type()
-            .const_int(function_map.len() as u64, false)
-            .into(),
-    ]);
+    // Construct the module info struct of the root module, which contains the other modules of the
+    // package as its descendants
+    let module_info = gen_module_tree_info(
+        db,
+        &abi_types,
+        module,
+        &[],
+        module_paths,
+        function_map,
+        global_map,
+    );
 
     // Construct the dispatch table struct
     let dispatch_table = gen_dispatch_table(db, &abi_types, module, dispatch_table);
@@ -277,6 +736,126 @@ pub(super) fn gen_reflection_ir(
     gen_get_info_fn(db, module, &abi_types, module_info, dispatch_table);
 }
 
+/// Construct a `MunModuleInfo` struct for the module at `path` and, recursively, its child modules.
+/// The definitions in `function_map` and `global_map` are assigned to the modules that define them,
+/// `module_paths` are the paths of the modules of the package.
+/// A directory without a module of its own, e.g. `shapes` for `shapes/square.mun`, is still
+/// included as an empty module, so every module path is reachable from the root module.
+fn gen_module_tree_info(
+    db: &impl IrDatabase,
+    abi_types: &AbiTypes,
+    module: &Module,
+    path: &[hir::Name],
+    module_paths: &[Vec<hir::Name>],
+    function_map: &HashMap<mun_hir::Function, FunctionValue>,
+    global_map: &HashMap<mun_hir::Static, GlobalValue>,
+) -> StructValue {
+    let functions: HashMap<_, _> = function_map
+        .iter()
+        .filter(|(f, _)| f.module(db).path(db) == path)
+        .map(|(f, value)| (*f, *value))
+        .collect();
+    let globals: HashMap<_, _> = global_map
+        .iter()
+        .filter(|(s, _)| s.module(db).path(db) == path)
+        .map(|(s, value)| (*s, *value))
+        .collect();
+
+    // The child modules are the next path components of the modules below `path`
+    let mut child_names: Vec<&hir::Name> = module_paths
+        .iter()
+        .filter(|module_path| module_path.len() > path.len() && module_path.starts_with(path))
+        .map(|module_path| &module_path[path.len()])
+        .collect();
+    child_names.sort();
+    child_names.dedup();
+    let modules: Vec<StructValue> = child_names
+        .into_iter()
+        .map(|name| {
+            let mut child_path = path.to_vec();
+            child_path.push(name.clone());
+            gen_module_tree_info(
+                db,
+                abi_types,
+                module,
+                &child_path,
+                module_paths,
+                function_map,
+                global_map,
+            )
+        })
+        .collect();
+
+    let path = path
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("::");
+    gen_module_info(db, abi_types, module, &path, &functions, &globals, &modules)
+}
+
+/// Construct a `MunModuleInfo` struct for the module at `path`, containing the functions in
+/// `function_map`, the mutable statics in `global_map`, and the child modules `modules`.
+fn gen_module_info(
+    db: &impl IrDatabase,
+    abi_types: &AbiTypes,
+    module: &Module,
+    path: &str,
+    function_map: &HashMap<mun_hir::Function, FunctionValue>,
+    global_map: &HashMap<mun_hir::Static, GlobalValue>,
+    modules: &[StructValue],
+) -> StructValue {
+    let context = module.get_context();
+    let modules_ptr = if modules.is_empty() {
+        abi_types
+            .module_info_type
+            .ptr_type(AddressSpace::Const)
+            .const_null()
+    } else {
+        let modules = abi_types.module_info_type.const_array(modules);
+        gen_global(module, &modules, "mod.get_info.modules").as_pointer_value()
+    };
+
+    // The definitions are sorted by name, so the order of their infos in the library doesn't
+    // depend on the iteration order of the maps
+    let mut functions: Vec<_> = function_map.iter().collect();
+    functions.sort_by_cached_key(|(f, _)| f.full_name(db));
+    let mut statics: Vec<_> = global_map.keys().collect();
+    statics.sort_by_cached_key(|s| s.name(db).to_string());
+
+    // The runtime accesses statics through the global info, so they don't need to be exported. The
+    // values of immutable statics never change, so only mutable statics are exposed.
+    let mut globals = Vec::new();
+    for s in statics {
+        let value = module.get_global(&s.qualified_name(db)).unwrap();
+        value.set_linkage(Linkage::Private);
+        if s.is_mutable(db) {
+            globals.push(*s);
+        }
+    }
+
+    abi_types.module_info_type.const_named_struct(&[
+        intern_string(module, path).into(),
+        gen_function_info_array(db, abi_types, module, functions.into_iter())
+            .as_pointer_value()
+            .into(),
+        modules_ptr.into(),
+        gen_global_info_array(db, abi_types, module, &globals).into(),
+        context
+            .i32_type()
+            .const_int(function_map.len() as u64, false)
+            .into(),
+        context
+            .i32_type()
+            .const_int(modules.len() as u64, false)
+            .into(),
+        context
+            .i32_type()
+            .const_int(globals.len() as u64, false)
+            .into(),
+    ])
+}
+
 /// Construct the actual `get_info` function.
 fn gen_get_info_fn(
     db: &impl IrDatabase,
@@ -313,7 +892,7 @@ fn gen_get_info_fn(
     };
 
     let get_symbols_fn =
-        module.add_function("get_info", get_symbols_type, Some(Linkage::DLLExport));
+        module.add_function(GET_INFO_FN_NAME, get_symbols_type, Some(Linkage::DLLExport));
 
     if target.options.is_like_windows {
         get_symbols_fn.add_attribute(
@@ -343,17 +922,60 @@ fn gen_get_info_fn(
     let dependencies_addr = unsafe { builder.build_struct_gep(result_ptr, 2, "dependencies") };
     let num_dependencies_addr =
         unsafe { builder.build_struct_gep(result_ptr, 3, "num_dependencies") };
+    let allocator_handle_addr =
+        unsafe { builder.build_struct_gep(result_ptr, 4, "allocator_handle") };
+    let float_semantics_addr =
+        unsafe { builder.build_struct_gep(result_ptr, 5, "float_semantics") };
+    let target_addr = unsafe { builder.build_struct_gep(result_ptr, 6, "target") };
 
     // Assign the struct values one by one.
     builder.build_store(symbols_addr, module_info);
     builder.build_store(dispatch_table_addr, dispatch_table);
+    let dependencies = db.assembly_dependencies();
+    let dependencies_ptr = if dependencies.is_empty() {
+        str_type.ptr_type(AddressSpace::Const).const_null()
+    } else {
+        let paths: Vec<PointerValue> = dependencies
+            .iter()
+            .map(|path| intern_string(module, path).const_cast(str_type))
+            .collect();
+        gen_global(module, &str_type.const_array(&paths), "dependencies")
+            .as_pointer_value()
+            .const_cast(str_type.ptr_type(AddressSpace::Const))
+    };
+    builder.build_store(dependencies_addr, dependencies_ptr);
+    builder.build_store(
+        num_dependencies_addr,
+        context
+            .i32_type()
+            .const_int(dependencies.len() as u64, false),
+    );
     builder.build_store(
-        dependencies_addr,
-        str_type.ptr_type(AddressSpace::Const).const_null(),
+        allocator_handle_addr,
+        module
+            .get_global(ALLOCATOR_HANDLE_NAME)
+            .map(|g| g.as_pointer_value())
+            .unwrap_or_else(|| {
+                context
+                    .i8_type()
+                    .ptr_type(AddressSpace::Generic)
+                    .ptr_type(AddressSpace::Generic)
+                    .const_null()
+            }),
     );
     builder.build_store(
-        num_dependencies_addr,
-        context.i32_type().const_int(0 as u64, false),
+        float_semantics_addr,
+        context.i8_type().const_int(
+            match db.float_semantics() {
+                FloatSemantics::Strict => 0,
+                FloatSemantics::Fast => 1,
+            },
+            false,
+        ),
+    );
+    builder.build_store(
+        target_addr,
+        intern_string(module, &target.llvm_target).const_cast(str_type),
     );
 
     // Construct the return statement of the function.
@@ -364,5 +986,48 @@ fn gen_get_info_fn(
     }
 
     // Run the function optimizer on the generate function
-    function::create_pass_manager(&module, db.optimization_lvl()).run_on(&get_symbols_fn);
+    function::create_pass_manager(&module, db.optimization_lvl(), &db.pass_pipeline())
+        .run_on(&get_symbols_fn);
+}
+
+/// Hides the symbols that are defined in `module` from the dynamic symbol table of the shared
+/// object, except for the `get_info` function and the `export` functions of `function_map`. The
+/// runtime finds every other function and global through the `AssemblyInfo` that `get_info`
+/// returns, so they don't need to be exported.
+pub(super) fn hide_internal_symbols(
+    db: &impl IrDatabase,
+    function_map: &HashMap<mun_hir::Function, FunctionValue>,
+    module: &Module,
+) {
+    let mut exported: HashSet<String> = function_map
+        .keys()
+        .filter(|f| f.visibility(db) == hir::Visibility::Public)
+        .map(|f| function_symbol_name(db, *f))
+        .collect();
+    exported.insert(GET_INFO_FN_NAME.to_owned());
+
+    let mut next_function = module.get_first_function();
+    while let Some(function) = next_function {
+        let is_exported = function
+            .get_name()
+            .to_str()
+            .map_or(false, |name| exported.contains(name));
+        if function.count_basic_blocks() > 0
+            && function.get_linkage() == Linkage::External
+            && !is_exported
+        {
+            function
+                .as_global_value()
+                .set_visibility(GlobalVisibility::Hidden);
+        }
+        next_function = function.get_next_function();
+    }
+
+    let mut next_global = module.get_first_global();
+    while let Some(global) = next_global {
+        if global.get_initializer().is_some() && global.get_linkage() == Linkage::External {
+            global.set_visibility(GlobalVisibility::Hidden);
+        }
+        next_global = global.get_next_global();
+    }
 }
@@ -0,0 +1,146 @@
+//! Splits a module into codegen units that are optimized and compiled to machine code in parallel.
+//!
+//! An LLVM context cannot be used from multiple threads, so every unit is parsed from the bitcode
+//! of the module into a context of its own. A unit contains the whole module, but it only emits
+//! the definitions it owns. The functions that other units own are marked `available_externally`,
+//! which keeps them available for inlining, while the linker resolves them to the unit that owns
+//! them. The mutable globals that other units own are declarations, so all units share the state
+//! of the owning unit.
+//!
+//! Private functions and mutable globals are referenced across units, so they are turned into
+//! hidden external symbols. Their names are unique in the module, and thus in the shared object.
+
+use super::{create_target_machine, optimize_module, CodeGenerationError, PassPipeline};
+use inkwell::context::Context;
+use inkwell::memory_buffer::MemoryBuffer;
+use inkwell::module::{Linkage, Module};
+use inkwell::targets::FileType;
+use inkwell::values::{AsValueRef, FunctionValue, GlobalValue};
+use inkwell::{GlobalVisibility, OptimizationLevel};
+use llvm_sys::core::LLVMSetInitializer;
+use std::sync::Arc;
+use std::{iter, panic, ptr, thread};
+
+/// Optimizes `module` and compiles it into an object file per codegen unit, using at most `count`
+/// units. The objects are returned in the order of their units, so linking them is deterministic.
+pub(crate) fn emit_objects(
+    module: &Module,
+    count: usize,
+    target: &mun_target::spec::Target,
+    optimization_lvl: OptimizationLevel,
+    pipeline: &PassPipeline,
+) -> Result<Vec<Vec<u8>>, CodeGenerationError> {
+    // A unit without functions would only contain definitions owned by other units
+    let count = count.min(distributed_functions(module).count()).max(1);
+    let bitcode = Arc::new(module.write_bitcode_to_memory().as_slice().to_vec());
+
+    let workers: Vec<_> = (0..count)
+        .map(|unit| {
+            let bitcode = bitcode.clone();
+            let target = target.clone();
+            let pipeline = pipeline.clone();
+            thread::spawn(move || {
+                emit_unit(&bitcode, unit, count, &target, optimization_lvl, &pipeline)
+            })
+        })
+        .collect();
+
+    workers
+        .into_iter()
+        .map(|worker| worker.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+        .collect()
+}
+
+/// Optimizes codegen unit `unit` of `count` units of the module in `bitcode`, and compiles it into
+/// an object file. The functions are distributed over the units in the order of the module,
+/// whereas the mutable globals are all owned by the first unit.
+fn emit_unit(
+    bitcode: &[u8],
+    unit: usize,
+    count: usize,
+    target: &mun_target::spec::Target,
+    optimization_lvl: OptimizationLevel,
+    pipeline: &PassPipeline,
+) -> Result<Vec<u8>, CodeGenerationError> {
+    let context = Context::create();
+    let buffer = MemoryBuffer::create_from_memory_range_copy(bitcode, "unit");
+    let module = context
+        .create_module_from_ir(buffer)
+        .map_err(|e| CodeGenerationError::CodeGenerationError(e.to_string()))?;
+
+    for (idx, function) in distributed_functions(&module).enumerate() {
+        share(function.as_global_value());
+        if idx % count != unit {
+            function.set_linkage(Linkage::AvailableExternally);
+        }
+    }
+    for global in distributed_globals(&module) {
+        share(global);
+        if unit != 0 {
+            if global.is_constant() {
+                global.set_linkage(Linkage::AvailableExternally);
+            } else {
+                // Without an initializer the global is a declaration of the global of the first
+                // unit
+                unsafe { LLVMSetInitializer(global.as_value_ref(), ptr::null_mut()) };
+            }
+        }
+    }
+
+    optimize_module(&module, optimization_lvl, pipeline);
+
+    let target_machine = create_target_machine(target, optimization_lvl)?;
+    let obj = target_machine
+        .write_to_memory_buffer(&module, FileType::Object)
+        .map_err(|e| CodeGenerationError::CodeGenerationError(e.to_string()))?;
+    Ok(obj.as_slice().to_vec())
+}
+
+/// Returns the function definitions of `module` that are distributed over the codegen units, which
+/// are all functions that are defined in the module.
+fn distributed_functions(module: &Module) -> impl Iterator<Item = FunctionValue> {
+    let mut next = module.get_first_function();
+    iter::from_fn(move || {
+        let function = next?;
+        next = function.get_next_function();
+        Some(function)
+    })
+    .filter(|function| {
+        function.count_basic_blocks() > 0 && is_shareable(function.as_global_value())
+    })
+}
+
+/// Returns the global definitions of `module` that are owned by the first codegen unit. Private
+/// constants, e.g. strings, are not distributed; every unit that uses them emits its own copy.
+fn distributed_globals(module: &Module) -> impl Iterator<Item = GlobalValue> {
+    let mut next = module.get_first_global();
+    iter::from_fn(move || {
+        let global = next?;
+        next = global.get_next_global();
+        Some(global)
+    })
+    .filter(|global| {
+        global.get_initializer().is_some()
+            && is_shareable(*global)
+            && (global.get_linkage() == Linkage::External || !global.is_constant())
+    })
+}
+
+/// Returns whether `global` can be referenced from other codegen units once it is shared, i.e. it
+/// is external or a named private definition.
+fn is_shareable(global: GlobalValue) -> bool {
+    match global.get_linkage() {
+        Linkage::External => true,
+        Linkage::Private | Linkage::Internal => !global.get_name().to_bytes().is_empty(),
+        _ => false,
+    }
+}
+
+/// Turns the private definition `global` into a hidden external symbol, so other codegen units can
+/// reference it without exporting it from the shared object.
+fn share(global: GlobalValue) {
+    if global.get_linkage() != Linkage::External {
+        global.set_linkage(Linkage::External);
+        global.set_visibility(GlobalVisibility::Hidden);
+    }
+}
@@ -0,0 +1,96 @@
+use failure::Fail;
+use inkwell::module::Module;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Determines whether the generated IR is verified before machine code is generated. Invalid IR
+/// is a bug in the compiler, which would otherwise crash LLVM or result in a broken library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IrVerification {
+    /// The IR is not verified
+    Off,
+    /// Invalid IR is reported as an internal compiler error
+    On,
+    /// Invalid IR is reported as an internal compiler error, and written to a file next to the
+    /// shared library so it can be attached to a bug report
+    Dump,
+}
+
+/// An internal compiler error that is reported when the LLVM verifier rejects the generated IR.
+#[derive(Debug)]
+pub struct InvalidIrError {
+    /// The name of the module that contains the invalid IR
+    pub module: String,
+    /// The names of the functions that are invalid
+    pub functions: Vec<String>,
+    /// The message of the verifier
+    pub message: String,
+    /// The file that the invalid IR was written to, if it was requested and could be written
+    pub dump_path: Option<PathBuf>,
+}
+
+impl fmt::Display for InvalidIrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "internal compiler error: invalid IR was generated for module `{}`",
+            self.module
+        )?;
+        if !self.functions.is_empty() {
+            let functions: Vec<String> = self
+                .functions
+                .iter()
+                .map(|name| format!("`{}`", name))
+                .collect();
+            write!(f, " in {}", functions.join(", "))?;
+        }
+        writeln!(f, "\n{}", self.message.trim_end())?;
+        match &self.dump_path {
+            Some(path) => write!(
+                f,
+                "The IR was written to '{}', please attach it to a bug report.",
+                path.display()
+            ),
+            None => write!(
+                f,
+                "This is a bug in the compiler, please report it. Compile with `--verify-ir dump` \
+                 to write the IR to a file that can be attached to the report."
+            ),
+        }
+    }
+}
+
+impl Fail for InvalidIrError {}
+
+/// Verifies the IR of `module`. If the IR is invalid, it is written to `dump_path` when specified.
+pub(crate) fn verify_module(
+    module: &Module,
+    dump_path: Option<&Path>,
+) -> Result<(), InvalidIrError> {
+    let message = match module.verify() {
+        Ok(()) => return Ok(()),
+        Err(message) => message.to_string(),
+    };
+
+    // The verifier reports the first problem of the module, verify every function separately to
+    // find all offending functions
+    let mut functions = Vec::new();
+    let mut function = module.get_first_function();
+    while let Some(value) = function {
+        if !value.verify(false) {
+            functions.push(value.get_name().to_string_lossy().into_owned());
+        }
+        function = value.get_next_function();
+    }
+
+    // Failing to write the dump must not hide the verification error
+    let dump_path =
+        dump_path.and_then(|path| module.print_to_file(path).ok().map(|_| path.to_path_buf()));
+
+    Err(InvalidIrError {
+        module: module.get_name().to_string_lossy().into_owned(),
+        functions,
+        message,
+        dump_path,
+    })
+}
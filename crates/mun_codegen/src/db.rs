@@ -2,9 +2,23 @@
 
 use mun_hir as hir;
 
-use crate::{code_gen::symbols::TypeInfo, ir::module::ModuleIR, Context};
-use inkwell::{types::AnyTypeEnum, OptimizationLevel};
+use crate::{
+    code_gen::{symbols::TypeInfo, IrVerification, PassPipeline},
+    ir::{
+        adt::StructLayout,
+        dispatch_table::{CallMode, DispatchTable},
+        function::{FloatSemantics, FunctionIR},
+        module::ModuleIR,
+        ty::UnsupportedTypeError,
+    },
+    Context,
+};
+use inkwell::{
+    types::{AnyTypeEnum, StructType},
+    OptimizationLevel,
+};
 use mun_target::spec::Target;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 /// The `IrDatabase` enables caching of intermediate in the process of LLVM IR generation. It uses
@@ -19,14 +33,119 @@ pub trait IrDatabase: hir::HirDatabase {
     #[salsa::input]
     fn optimization_lvl(&self) -> OptimizationLevel;
 
+    /// Returns the LLVM passes that optimize the generated IR.
+    #[salsa::input]
+    fn pass_pipeline(&self) -> PassPipeline;
+
+    /// Returns how calls between functions of the same assembly are generated.
+    #[salsa::input]
+    fn call_mode(&self) -> CallMode;
+
+    /// Returns whether integer arithmetic that overflows calls the runtime's trap handler, rather
+    /// than silently wrapping around.
+    #[salsa::input]
+    fn overflow_checks(&self) -> bool;
+
+    /// Returns whether integer divisions and remainders call the runtime's trap handler when the
+    /// divisor is zero, rather than having undefined behavior.
+    #[salsa::input]
+    fn division_checks(&self) -> bool;
+
+    /// Returns whether indexing an array with an index that is out of bounds calls the runtime's
+    /// trap handler, rather than accessing memory outside of the array.
+    #[salsa::input]
+    fn bounds_checks(&self) -> bool;
+
+    /// Returns whether calls to `assert` and `assert_eq` are generated. Otherwise neither the
+    /// assertions nor their operands are evaluated.
+    #[salsa::input]
+    fn assertions(&self) -> bool;
+
+    /// Returns whether floating point operations strictly follow IEEE 754, or whether they may be
+    /// optimized as if their operands and results are neither NaN nor infinite.
+    #[salsa::input]
+    fn float_semantics(&self) -> FloatSemantics;
+
+    /// Returns whether the generated IR is verified before machine code is generated.
+    #[salsa::input]
+    fn ir_verification(&self) -> IrVerification;
+
+    /// Returns the maximum number of codegen units into which a module is split, that are
+    /// optimized and compiled to machine code in parallel.
+    #[salsa::input]
+    fn codegen_units(&self) -> usize;
+
+    /// Returns whether code is only generated for the private functions that are reachable from
+    /// the `export` functions of a file.
+    #[salsa::input]
+    fn dead_code_elimination(&self) -> bool;
+
+    /// Returns whether the `#[test]` functions of the package are compiled, so the runtime can
+    /// invoke them. Otherwise they are only compiled if other functions use them.
+    #[salsa::input]
+    fn test_build(&self) -> bool;
+
+    /// Returns whether recursive calls that are not in tail position, and thus are not turned into
+    /// a loop, are reported as notes.
+    #[salsa::input]
+    fn tail_call_notes(&self) -> bool;
+
+    /// Returns the levels of the lints that are set outside of the code, e.g. on the command line.
+    /// The lint attributes of the code override them.
+    #[salsa::input]
+    fn lint_levels(&self) -> Arc<hir::lint::LintLevels>;
+
+    /// Returns the paths of the assemblies that the assembly depends on, relative to the directory
+    /// of the assembly. The runtime loads the dependencies of an assembly before the assembly.
+    #[salsa::input]
+    fn assembly_dependencies(&self) -> Arc<Vec<String>>;
+
     /// Returns the target for code generation.
     #[salsa::input]
     fn target(&self) -> Target;
 
+    /// Returns whether DWARF debug info is generated for the functions.
+    #[salsa::input]
+    fn debug_info(&self) -> bool;
+
     /// Given a type, return the corresponding IR type.
     #[salsa::invoke(crate::ir::ty::ir_query)]
     fn type_ir(&self, ty: hir::Ty) -> AnyTypeEnum;
 
+    /// Given a type, return the corresponding IR type, or the part of the type that is not
+    /// supported in compiled code.
+    #[salsa::invoke(crate::ir::ty::try_ir_query)]
+    fn try_type_ir(&self, ty: hir::Ty) -> Result<AnyTypeEnum, UnsupportedTypeError>;
+
+    /// Given a struct, return the corresponding named IR struct type. The type is created once
+    /// per struct, so every reference to the struct uses the same IR type.
+    #[salsa::invoke(crate::ir::adt::struct_ty_query)]
+    fn struct_ty(&self, s: hir::Struct) -> StructType;
+
+    /// Given a struct, return the offsets of its fields and its size and alignment in the data
+    /// layout of the target.
+    #[salsa::invoke(crate::ir::adt::struct_layout_query)]
+    fn struct_layout(&self, s: hir::Struct) -> Arc<StructLayout>;
+
+    /// Given a `hir::FileId`, return the functions of the file, or of the package of a root file,
+    /// for which code is generated. Unless dead code elimination is disabled, unused private
+    /// functions are omitted.
+    #[salsa::invoke(crate::ir::reachable::reachable_functions_query)]
+    fn reachable_functions(&self, file: hir::FileId) -> Arc<HashSet<hir::Function>>;
+
+    /// Given a `hir::FileId`, return the functions that are called through the dispatch table by
+    /// the functions in the file.
+    #[salsa::invoke(crate::ir::dispatch_table::dispatch_table_query)]
+    fn dispatch_table(&self, file: hir::FileId) -> Arc<DispatchTable>;
+
+    /// Given a `hir::FileId`, return the IR type of the dispatch table of the file.
+    #[salsa::invoke(crate::ir::dispatch_table::dispatch_table_type_query)]
+    fn dispatch_table_type(&self, file: hir::FileId) -> StructType;
+
+    /// Given a `hir::Function`, generate code for the function in a module of its own.
+    #[salsa::invoke(crate::ir::function::ir_query)]
+    fn function_ir(&self, f: hir::Function) -> Arc<FunctionIR>;
+
     /// Given a `hir::FileId` generate code for the module.
     #[salsa::invoke(crate::ir::module::ir_query)]
     fn module_ir(&self, file: hir::FileId) -> Arc<ModuleIR>;
@@ -0,0 +1,170 @@
+//! Diagnostics for definitions that cannot be compiled, because their types are not supported in
+//! compiled code. These are reported after the diagnostics of the HIR, for modules that are
+//! otherwise free of errors.
+
+use crate::ir::module::assembly_file;
+use crate::ir::tail_calls::SelfCalls;
+use crate::ir::ty::{try_basic_ir, UnsupportedTypeError};
+use crate::IrDatabase;
+use mun_hir::diagnostics::{Diagnostic, DiagnosticSink};
+use mun_hir::{FileId, HasSource, HirDisplay, ModuleDef, Ty, TypeCtor};
+use mun_syntax::{AstNode, SyntaxNodePtr};
+use std::any::Any;
+
+#[derive(Debug)]
+pub struct UnsupportedType {
+    pub file: FileId,
+    pub def: SyntaxNodePtr,
+    pub ty: String,
+}
+
+impl Diagnostic for UnsupportedType {
+    fn code(&self) -> &'static str {
+        "E0051"
+    }
+
+    fn message(&self) -> String {
+        format!("type `{}` is not supported in compiled code", self.ty)
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.def
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A call of a function to itself that is not in tail position, so it is not turned into a loop
+/// and every call uses stack space. This is a note that is only reported on request.
+#[derive(Debug)]
+pub struct NonTailRecursion {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+    pub name: String,
+}
+
+impl Diagnostic for NonTailRecursion {
+    fn code(&self) -> &'static str {
+        "N0001"
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "recursive call to `{}` is not in tail position, so it is not turned into a loop",
+            self.name
+        )
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// Reports the definitions in `file_id` with types that are not supported in compiled code and, on
+/// request, the recursive calls that are not in tail position.
+pub fn diagnostics(db: &impl IrDatabase, file_id: FileId, sink: &mut DiagnosticSink) {
+    let reachable_functions = db.reachable_functions(assembly_file(db, file_id));
+    for def in db.module_data(file_id).definitions() {
+        if let ModuleDef::Function(f) = def {
+            if f.is_extern(db) {
+                continue;
+            }
+            if reachable_functions.contains(f) && db.tail_call_notes() {
+                let body = f.body(db);
+                let source_map = f.body_source_map(db);
+                for call in SelfCalls::collect(*f, &body, &f.infer(db)).non_tail {
+                    if let Some(ptr) = source_map.expr_syntax(call) {
+                        sink.push(NonTailRecursion {
+                            file: ptr.file_id,
+                            expr: ptr.ast.syntax_node_ptr(),
+                            name: f.full_name(db),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    let mut report = |file, def, UnsupportedTypeError(ty): UnsupportedTypeError| {
+        sink.push(UnsupportedType {
+            file,
+            def,
+            ty: ty.display(db).to_string(),
+        })
+    };
+
+    for def in db.module_data(file_id).definitions() {
+        match def {
+            ModuleDef::Function(f) => {
+                if let Err(err) = check_fn_signature(db, *f).and_then(|_| check_fn_body(db, *f)) {
+                    let src = f.source(db);
+                    report(src.file_id, SyntaxNodePtr::new(src.ast.syntax()), err);
+                }
+            }
+            ModuleDef::Struct(s) => {
+                if let Err(err) = db.try_type_ir(Ty::simple(TypeCtor::Struct(*s))) {
+                    let src = s.source(db);
+                    report(src.file_id, SyntaxNodePtr::new(src.ast.syntax()), err);
+                }
+            }
+            ModuleDef::Static(s) => {
+                if let Err(err) = check_static(db, *s) {
+                    let src = s.source(db);
+                    report(src.file_id, SyntaxNodePtr::new(src.ast.syntax()), err);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Verifies that the types of the parameters and the return type of `f` are supported.
+pub(crate) fn check_fn_signature(
+    db: &impl IrDatabase,
+    f: mun_hir::Function,
+) -> Result<(), UnsupportedTypeError> {
+    db.try_type_ir(f.ty(db)).map(|_| ())
+}
+
+/// Verifies that the types of all expressions and patterns in the body of `f` are supported.
+/// Extern functions don't have a body.
+pub(crate) fn check_fn_body(
+    db: &impl IrDatabase,
+    f: mun_hir::Function,
+) -> Result<(), UnsupportedTypeError> {
+    if f.is_extern(db) {
+        return Ok(());
+    }
+
+    let body = f.body(db);
+    let infer = f.infer(db);
+    for (expr, _) in body.exprs() {
+        db.try_type_ir(infer[expr].clone())?;
+    }
+    for (pat, _) in body.pats() {
+        db.try_type_ir(infer[pat].clone())?;
+    }
+    Ok(())
+}
+
+/// Verifies that the type of `s` is supported.
+pub(crate) fn check_static(
+    db: &impl IrDatabase,
+    s: mun_hir::Static,
+) -> Result<(), UnsupportedTypeError> {
+    try_basic_ir(db, &s.ty(db)).map(|_| ())
+}
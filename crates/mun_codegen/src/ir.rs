@@ -1,9 +1,16 @@
 use inkwell::types::{AnyTypeEnum, BasicTypeEnum};
+use inkwell::values::BasicValueEnum;
+use mun_hir::ConstValue;
 
+pub mod adt;
 pub mod body;
+pub(crate) mod debug_info;
 pub(crate) mod dispatch_table;
 pub mod function;
+pub(crate) mod intrinsics;
 pub mod module;
+pub(crate) mod reachable;
+pub(crate) mod tail_calls;
 pub mod ty;
 
 /// Try to down cast an `AnyTypeEnum` into a `BasicTypeEnum`.
@@ -18,3 +25,14 @@ fn try_convert_any_to_basic(ty: AnyTypeEnum) -> Option<BasicTypeEnum> {
         _ => None,
     }
 }
+
+/// Generates an IR constant of type `ty` for a value that was evaluated at compile time.
+fn gen_const_value(value: ConstValue, ty: AnyTypeEnum) -> BasicValueEnum {
+    match (value, ty) {
+        // The value of an integer constant is known to fit in its type
+        (ConstValue::Int(v), AnyTypeEnum::IntType(ty)) => ty.const_int(v as u64, false).into(),
+        (ConstValue::Float(v), AnyTypeEnum::FloatType(ty)) => ty.const_float(v).into(),
+        (ConstValue::Bool(v), AnyTypeEnum::IntType(ty)) => ty.const_int(v as u64, false).into(),
+        _ => unreachable!("the type of a constant does not match its value"),
+    }
+}
@@ -0,0 +1,84 @@
+use super::try_convert_any_to_basic;
+use crate::IrDatabase;
+use inkwell::targets::TargetData;
+use inkwell::types::{BasicTypeEnum, StructType};
+use mun_hir as hir;
+use std::sync::Arc;
+
+/// The memory layout of a struct in the target's data layout.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StructLayout {
+    /// The offset in bytes of every field, in declaration order.
+    pub field_offsets: Vec<u64>,
+
+    /// The size of the struct in bytes, including trailing padding.
+    pub size: u64,
+
+    /// The ABI alignment of the struct in bytes.
+    pub alignment: u32,
+}
+
+/// Returns the name of the LLVM struct type of `s`. The name is qualified with the path of the
+/// module that defines the struct so that structs with the same name in different modules don't
+/// collide in the LLVM context, e.g. `Foo` in `main.mun` becomes `main::Foo`.
+pub(crate) fn struct_name(db: &impl IrDatabase, s: hir::Struct) -> String {
+    qualified_name(db, s.module(db), &s.name(db))
+}
+
+/// Returns the name of the enum `e`, which is qualified like the name of a struct, e.g. `State` in
+/// `main.mun` becomes `main::State`.
+pub(crate) fn enum_name(db: &impl IrDatabase, e: hir::Enum) -> String {
+    qualified_name(db, e.module(db), &e.name(db))
+}
+
+/// Returns `name` qualified with the path of `module`.
+fn qualified_name(db: &impl IrDatabase, module: hir::Module, name: &hir::Name) -> String {
+    let module_path = db
+        .file_relative_path(module.file_id())
+        .with_extension("")
+        .as_str()
+        .replace('/', "::");
+    format!("{}::{}", module_path, name)
+}
+
+/// Constructs the named LLVM struct type of `s`, with its fields in declaration order. The type of
+/// a packed struct is a packed LLVM struct. An aligned struct ends with an empty array of byte
+/// vectors of the requested alignment. Vectors are aligned to their size, so the array raises the
+/// alignment of the struct and pads its size, without moving the fields.
+pub(crate) fn struct_ty_query(db: &impl IrDatabase, s: hir::Struct) -> StructType {
+    let struct_type = db.context().opaque_struct_type(&struct_name(db, s));
+    let mut fields: Vec<BasicTypeEnum> = s
+        .fields(db)
+        .into_iter()
+        .map(|field| {
+            try_convert_any_to_basic(db.type_ir(field.ty(db)))
+                .expect("could not convert field type")
+        })
+        .collect();
+    let repr = s.repr(db);
+    if let Some(align) = repr.align {
+        let align_type = db.context().i8_type().vec_type(align).array_type(0);
+        fields.push(align_type.into());
+    }
+    struct_type.set_body(&fields, repr.packed);
+    struct_type
+}
+
+/// Computes the layout of `s` from the data layout of the target.
+pub(crate) fn struct_layout_query(db: &impl IrDatabase, s: hir::Struct) -> Arc<StructLayout> {
+    let target_data = TargetData::create(&db.target().data_layout);
+    let struct_type = db.struct_ty(s);
+    let field_offsets = (0..s.fields(db).len() as u32)
+        .map(|idx| {
+            target_data
+                .offset_of_element(&struct_type, idx)
+                .expect("field index out of bounds")
+        })
+        .collect();
+
+    Arc::new(StructLayout {
+        field_offsets,
+        size: target_data.get_abi_size(&struct_type),
+        alignment: target_data.get_abi_alignment(&struct_type),
+    })
+}
@@ -1,25 +1,59 @@
-use crate::{ir::dispatch_table::DispatchTable, ir::try_convert_any_to_basic, IrDatabase};
+use crate::{
+    ir::debug_info::{DebugInfoBuilder, DebugScope},
+    ir::dispatch_table::{self, DispatchTable},
+    ir::function::{gen_closure_signature, gen_signature, param_ir_indices, signature_attributes},
+    ir::intrinsics::{trap_site_type, Intrinsic, TrapKind},
+    ir::tail_calls::SelfCalls,
+    ir::ty::{fn_return_convention, is_zero_sized, ReturnConvention},
+    ir::{gen_const_value, try_convert_any_to_basic},
+    mangle::function_symbol_name,
+    IrDatabase,
+};
 use inkwell::{
     builder::Builder,
-    module::Module,
-    values::{BasicValueEnum, CallSiteValue, FloatValue, FunctionValue, IntValue},
-    FloatPredicate, IntPredicate,
+    module::{Linkage, Module},
+    types::{AnyTypeEnum, BasicTypeEnum},
+    values::{
+        BasicValue, BasicValueEnum, CallSiteValue, FloatValue, FunctionValue, GlobalValue,
+        IntValue, UnnamedAddress,
+    },
+    AddressSpace, FloatPredicate, IntPredicate,
 };
+use mun_hir::line_index::LineCol;
 use mun_hir::{
-    self as hir, ArithOp, BinaryOp, Body, CmpOp, Expr, ExprId, HirDisplay, InferenceResult,
-    Literal, Ordering, Pat, PatId, Path, Resolution, Resolver, Statement, TypeCtor,
+    self as hir, ArithOp, BinaryOp, Body, BuiltinFunction, CmpOp, ConstValue, Expr, ExprId,
+    HirDisplay, InferenceResult, IntBitness, Literal, LogicOp, MatchArm, ModuleDef, Ordering, Pat,
+    PatId, Path, RecordLitField, Resolution, Resolver, Signedness, Statement, StructMemoryKind, Ty,
+    TypeCtor, UnaryOp,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
 };
-use std::{collections::HashMap, mem, sync::Arc};
 
 use inkwell::basic_block::BasicBlock;
 use inkwell::values::PointerValue;
 
+/// The name of the global that holds the handle of the runtime's allocator.
+pub(crate) const ALLOCATOR_HANDLE_NAME: &str = "allocatorHandle";
+
+/// A call to the runtime's trap handler in `block`, which is generated once the whole body is
+/// generated and the table of its trap sites is known. The site is the location of `expr`.
+struct TrapCall {
+    block: BasicBlock,
+    expr: ExprId,
+    kind: TrapKind,
+    /// The message of a failed assertion
+    message: Option<PointerValue>,
+}
+
 struct LoopInfo {
     break_values: Vec<(
         inkwell::values::BasicValueEnum,
         inkwell::basic_block::BasicBlock,
     )>,
     exit_block: BasicBlock,
+    continue_block: BasicBlock,
 }
 
 pub(crate) struct BodyIrGenerator<'a, 'b, D: IrDatabase> {
@@ -32,10 +66,20 @@ pub(crate) struct BodyIrGenerator<'a, 'b, D: IrDatabase> {
     pat_to_param: HashMap<PatId, inkwell::values::BasicValueEnum>,
     pat_to_local: HashMap<PatId, inkwell::values::PointerValue>,
     pat_to_name: HashMap<PatId, String>,
-    function_map: &'a HashMap<mun_hir::Function, FunctionValue>,
     dispatch_table: &'b DispatchTable,
     active_loop: Option<LoopInfo>,
     hir_function: hir::Function,
+    tail_calls: HashSet<ExprId>,
+    tail_call_block: Option<BasicBlock>,
+    /// The memory of the caller in which the return value is stored, if the function returns its
+    /// value through a pointer
+    return_ptr: Option<PointerValue>,
+    /// The calls to the trap handler that have yet to be generated
+    trap_calls: Vec<TrapCall>,
+    /// The subprogram of the function, if debug info is generated
+    debug_scope: Option<DebugScope<'b>>,
+    /// The location that is attached to the instructions that are generated
+    debug_location: Option<LineCol>,
 }
 
 impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
@@ -44,8 +88,8 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
         module: &'a Module,
         hir_function: hir::Function,
         ir_function: FunctionValue,
-        function_map: &'a HashMap<mun_hir::Function, FunctionValue>,
         dispatch_table: &'b DispatchTable,
+        debug_info: Option<&'b DebugInfoBuilder>,
     ) -> Self {
         // Get the type information from the `hir::Function`
         let body = hir_function.body(db);
@@ -57,7 +101,10 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
         let body_ir = context.append_basic_block(&ir_function, "body");
         builder.position_at_end(&body_ir);
 
-        BodyIrGenerator {
+        let tail_calls = SelfCalls::collect(hir_function, &body, &infer).tail;
+        let return_ptr = return_ptr_param(db, &db.fn_signature(hir_function), ir_function);
+
+        let mut generator = BodyIrGenerator {
             db,
             module,
             body,
@@ -67,24 +114,116 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
             pat_to_param: HashMap::default(),
             pat_to_local: HashMap::default(),
             pat_to_name: HashMap::default(),
-            function_map,
             dispatch_table,
             active_loop: None,
             hir_function,
+            tail_calls,
+            tail_call_block: None,
+            return_ptr,
+            trap_calls: Vec::new(),
+            debug_scope: None,
+            debug_location: None,
+        };
+
+        // The instructions that don't belong to an expression, e.g. the prologue, are attributed to
+        // the start of the body of the function
+        if let Some(debug_info) = debug_info {
+            if let Some((_, location)) = generator.expr_location(generator.body.body_expr()) {
+                let name = hir_function.qualified_name(db);
+                let scope = debug_info.gen_subprogram(ir_function, &name, location.line);
+                generator.debug_scope = Some(scope);
+                generator.debug_location = Some(location);
+            }
+        }
+
+        generator
+    }
+
+    /// Constructs a generator for the body of the closure `expr` in the body of the function. The
+    /// closure shares the HIR body and the inferred types of the function.
+    fn new_closure(&self, expr: ExprId, ir_function: FunctionValue, sig: &hir::FnSig) -> Self {
+        let context = self.module.get_context();
+        let builder = context.create_builder();
+        let body_ir = context.append_basic_block(&ir_function, "body");
+        builder.position_at_end(&body_ir);
+
+        // The closure is a subprogram of its own, that starts where the closure is defined
+        let location = self
+            .expr_location(expr)
+            .map(|(_, location)| location)
+            .or(self.debug_location);
+        let (debug_scope, debug_location) = match (self.debug_scope, location) {
+            (Some(outer), Some(location)) => {
+                let name = ir_function.get_name().to_string_lossy().into_owned();
+                let scope = outer.gen_subprogram(ir_function, &name, location.line);
+                (Some(scope), Some(location))
+            }
+            _ => (None, None),
+        };
+
+        BodyIrGenerator {
+            db: self.db,
+            module: self.module,
+            body: self.body.clone(),
+            infer: self.infer.clone(),
+            builder,
+            fn_value: ir_function,
+            pat_to_param: HashMap::default(),
+            pat_to_local: HashMap::default(),
+            pat_to_name: HashMap::default(),
+            dispatch_table: self.dispatch_table,
+            active_loop: None,
+            hir_function: self.hir_function,
+            tail_calls: HashSet::default(),
+            tail_call_block: None,
+            return_ptr: return_ptr_param(self.db, sig, ir_function),
+            trap_calls: Vec::new(),
+            debug_scope,
+            debug_location,
         }
     }
 
     /// Generates IR for the body of the function.
     pub fn gen_fn_body(&mut self) {
+        let params: Vec<PatId> = self.body.params().iter().map(|(pat, _)| *pat).collect();
+        let sig = self.db.fn_signature(self.hir_function);
+        self.gen_body(&params, self.body.body_expr(), &sig);
+    }
+
+    /// Generates IR for a body that evaluates `body_expr` with the parameters `params`, of a
+    /// function or a closure with the signature `sig`.
+    fn gen_body(&mut self, params: &[PatId], body_expr: ExprId, sig: &hir::FnSig) {
         // Iterate over all parameters and their type and store them so we can reference them
-        // later in code.
-        for (i, (pat, _ty)) in self.body.params().iter().enumerate() {
+        // later in code. Zero-sized parameters are omitted from the IR function.
+        let param_indices = param_ir_indices(self.db, sig);
+        for (pat, param_idx) in params.iter().zip(param_indices) {
             let body = self.body.clone(); // Avoid borrow issues
 
             match &body[*pat] {
-                Pat::Bind { name } => {
+                Pat::Bind { name, is_mutable } => {
                     let name = name.to_string();
-                    let param = self.fn_value.get_nth_param(i as u32).unwrap();
+                    let param = match param_idx {
+                        Some(idx) => self.fn_value.get_nth_param(idx).unwrap(),
+                        None => self.gen_zero_sized_value(&self.infer[*pat].clone()),
+                    };
+                    // Arrays are passed by pointer to memory of the caller, which is only read. A
+                    // parameter that is declared `mut`, or that is assigned by tail calls, is
+                    // copied first, so the array of the caller is never modified.
+                    if param_idx.is_some() && self.infer[*pat].as_array().is_some() {
+                        let array_ptr = param.into_pointer_value();
+                        let array_ptr = if *is_mutable || !self.tail_calls.is_empty() {
+                            let builder = self.new_alloca_builder();
+                            let array = builder.build_load(array_ptr, &name);
+                            let local_ptr = builder.build_alloca(array.get_type(), &name);
+                            builder.build_store(local_ptr, array);
+                            local_ptr
+                        } else {
+                            array_ptr
+                        };
+                        self.pat_to_local.insert(*pat, array_ptr);
+                        self.pat_to_name.insert(*pat, name);
+                        continue;
+                    }
                     let builder = self.new_alloca_builder();
                     let param_ptr = builder.build_alloca(param.get_type(), &name);
                     builder.build_store(param_ptr, param);
@@ -94,8 +233,8 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
                 Pat::Wild => {
                     // Wildcard patterns cannot be referenced from code. So nothing to do.
                 }
-                Pat::Path(_) => unreachable!(
-                    "Path patterns are not supported as parameters, are we missing a diagnostic?"
+                Pat::Path(_) | Pat::Lit(_) => unreachable!(
+                    "Refutable patterns are not supported as parameters, are we missing a diagnostic?"
                 ),
                 Pat::Missing => unreachable!(
                     "found missing Pattern, should not be generating IR for incomplete code"
@@ -103,32 +242,89 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
             }
         }
 
+        // Calls to the function itself in tail position jump back to the start of the body, once
+        // the parameters are stored
+        if !self.tail_calls.is_empty() {
+            let block = self
+                .module
+                .get_context()
+                .append_basic_block(&self.fn_value, "tail_call");
+            self.builder.build_unconditional_branch(&block);
+            self.builder.position_at_end(&block);
+            self.tail_call_block = Some(block);
+        }
+
         // Generate code for the body of the function
-        let ret_value = self.gen_expr(self.body.body_expr());
+        let ret_value = self.gen_expr(body_expr);
 
         // Construct a return statement from the returned value of the body if a return is expected
         // in the first place. If the return type of the body is `never` there is no need to
         // generate a return statement.
-        let block_ret_type = &self.infer[self.body.body_expr()];
-        let fn_ret_type = self
-            .hir_function
-            .ty(self.db)
-            .callable_sig(self.db)
-            .unwrap()
-            .ret()
-            .clone();
+        let block_ret_type = &self.infer[body_expr];
         if !block_ret_type.is_never() {
-            if fn_ret_type.is_empty() {
+            if sig.ret().is_empty() {
                 self.builder.build_return(None);
             } else if let Some(value) = ret_value {
-                self.builder.build_return(Some(&value));
+                self.gen_return_value(Some(value));
             }
+        } else {
+            self.gen_unreachable();
         }
+
+        // The return of the body is attributed to the start of the function, like the prologue
+        self.attach_debug_location();
+        self.gen_trap_calls();
     }
 
     /// Generates IR for the specified expression. Dependending on the type of expression an IR
-    /// value is returned.
+    /// value is returned. A bool is returned as an `i8` that is either 0 or 1, so it can be stored
+    /// and passed as is.
     fn gen_expr(&mut self, expr: ExprId) -> Option<inkwell::values::BasicValueEnum> {
+        let value = self.gen_expr_or_condition(expr)?;
+        match value {
+            BasicValueEnum::IntValue(value) if value.get_type().get_bit_width() == 1 => {
+                let byte_type = self.module.get_context().i8_type();
+                Some(
+                    self.builder
+                        .build_int_z_extend(value, byte_type, "bool")
+                        .into(),
+                )
+            }
+            value => Some(value),
+        }
+    }
+
+    /// Generates IR for a bool expression that is used as a condition. The bool is returned as an
+    /// `i1`, which is what branches and logic operators work with.
+    fn gen_condition(&mut self, expr: ExprId) -> Option<IntValue> {
+        let value = self.gen_expr_or_condition(expr)?.into_int_value();
+        if value.get_type().get_bit_width() == 1 {
+            Some(value)
+        } else {
+            let bool_type = self.module.get_context().bool_type();
+            Some(self.builder.build_int_truncate(value, bool_type, "cond"))
+        }
+    }
+
+    /// Generates IR for the specified expression. A bool is either returned as an `i1`, e.g. the
+    /// result of a comparison, or as an `i8`, e.g. a value that is loaded from memory.
+    fn gen_expr_or_condition(&mut self, expr: ExprId) -> Option<BasicValueEnum> {
+        // The instructions of the expression are attributed to its location, after which the
+        // location of the enclosing expression applies again
+        let outer_location = self.set_debug_location(expr);
+        let value = self.gen_expr_at_location(expr);
+        self.restore_debug_location(outer_location);
+        value
+    }
+
+    /// Generates IR for the specified expression, of which the instructions are attributed to the
+    /// current debug location.
+    fn gen_expr_at_location(&mut self, expr: ExprId) -> Option<BasicValueEnum> {
+        // Expressions whose value is known at compile time are emitted as constants
+        if let Some(value) = self.fold_expr(expr) {
+            return Some(self.gen_const_value(value, expr));
+        }
+
         let body = self.body.clone();
         match &body[expr] {
             Expr::Block {
@@ -139,14 +335,40 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
                 let resolver = mun_hir::resolver_for_expr(self.body.clone(), self.db, expr);
                 Some(self.gen_path_expr(p, expr, &resolver))
             }
-            Expr::Literal(lit) => Some(self.gen_literal(lit)),
+            Expr::Literal(lit) => Some(self.gen_literal(lit, expr)),
             Expr::BinaryOp { lhs, rhs, op } => {
                 self.gen_binary_op(expr, *lhs, *rhs, op.expect("missing op"))
             }
+            Expr::UnaryOp { expr: operand, op } => self.gen_unary_op(expr, *operand, *op),
+            Expr::Cast { expr: operand, .. } => self.gen_cast(expr, *operand),
             Expr::Call {
                 ref callee,
                 ref args,
-            } => self.gen_call(*callee, &args).try_as_basic_value().left(),
+            } => {
+                if let Some(function) = self.infer[*callee].as_builtin_function() {
+                    if function.is_assertion() {
+                        return self.gen_assertion(expr, function, &args);
+                    }
+                    return self.gen_builtin_call(function, &args);
+                }
+                match self.infer[*callee].as_function_def() {
+                    Some(function) => self.gen_call(expr, function, &args),
+                    None => self.gen_fn_ptr_call(expr, *callee, &args),
+                }
+            }
+            Expr::MethodCall {
+                receiver, ref args, ..
+            } => {
+                let method = self
+                    .infer
+                    .method_resolution(expr)
+                    .expect("method calls are resolved before code generation");
+                // The receiver is passed as the `self` argument of the method
+                let args: Vec<ExprId> = std::iter::once(*receiver)
+                    .chain(args.iter().copied())
+                    .collect();
+                self.gen_call(expr, method, &args)
+            }
             Expr::If {
                 condition,
                 then_branch,
@@ -155,38 +377,116 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
             Expr::Return { expr: ret_expr } => self.gen_return(expr, *ret_expr),
             Expr::Loop { body } => self.gen_loop(expr, *body),
             Expr::While { condition, body } => self.gen_while(expr, *condition, *body),
+            Expr::For {
+                pat,
+                start,
+                end,
+                inclusive,
+                body,
+            } => self.gen_for(expr, *pat, *start, *end, *inclusive, *body),
             Expr::Break { expr: break_expr } => self.gen_break(expr, *break_expr),
+            Expr::Continue => self.gen_continue(expr),
+            Expr::Field {
+                expr: receiver_expr,
+                ..
+            } => self.gen_field(expr, *receiver_expr),
+            Expr::RecordLit { fields, .. } => Some(self.gen_record_lit(expr, fields)),
+            Expr::Tuple { exprs } => Some(self.gen_tuple(expr, exprs)),
+            Expr::Array { exprs } => Some(self.gen_array(expr, exprs)),
+            Expr::Index { base, index } => self.gen_index(*base, *index),
+            Expr::Match {
+                expr: scrutinee,
+                arms,
+            } => self.gen_match(expr, *scrutinee, arms),
+            Expr::Closure {
+                args,
+                body: closure_body,
+                ..
+            } => Some(self.gen_closure(expr, args, *closure_body)),
             _ => unimplemented!("unimplemented expr type {:?}", &body[expr]),
         }
     }
 
+    /// Returns the value of `expr` if it can be evaluated at compile time. Only operators and
+    /// references to constants are folded, other expressions are generated as usual.
+    fn fold_expr(&self, expr: ExprId) -> Option<ConstValue> {
+        match &self.body[expr] {
+            Expr::BinaryOp {
+                op: Some(BinaryOp::Assignment { .. }),
+                ..
+            } => None,
+            Expr::BinaryOp { .. } | Expr::UnaryOp { .. } | Expr::Cast { .. } | Expr::Path(_) => {
+                hir::fold_expr(self.db, &self.body, &self.infer, expr)
+            }
+            _ => None,
+        }
+    }
+
+    /// Generates an IR constant for a value that was evaluated at compile time.
+    fn gen_const_value(&self, value: ConstValue, expr: ExprId) -> BasicValueEnum {
+        gen_const_value(value, self.db.type_ir(self.infer[expr].clone()))
+    }
+
+    /// Returns the global that holds the value of the static `s`. The global is declared in the
+    /// module of the function and defined when the modules of the file are linked together.
+    fn static_global(&self, s: hir::Static) -> GlobalValue {
+        let name = s.qualified_name(self.db);
+        self.module.get_global(&name).unwrap_or_else(|| {
+            let ty = try_convert_any_to_basic(self.db.type_ir(s.ty(self.db)))
+                .expect("statics must have a basic type");
+            let global = self.module.add_global(ty, None, &name);
+            global.set_constant(!s.is_mutable(self.db));
+            global
+        })
+    }
+
+    /// Generates an IR value for a string literal. The bytes of the string are stored in a private
+    /// global, followed by a nul terminator that is not included in the length of the string.
+    fn gen_string_literal(&self, value: &str, expr: ExprId) -> BasicValueEnum {
+        let ty = match self.db.type_ir(self.infer[expr].clone()) {
+            AnyTypeEnum::StructType(ty) => ty,
+            _ => unreachable!("expected a struct type for a string literal"),
+        };
+
+        let context = self.module.get_context();
+        let bytes = context.const_string(value, true);
+        let global = self
+            .module
+            .add_global(bytes.as_basic_value_enum().get_type(), None, "str");
+        global.set_linkage(Linkage::Private);
+        global.set_constant(true);
+        global.set_unnamed_address(UnnamedAddress::Global);
+        global.set_initializer(&bytes);
+
+        let ptr = global
+            .as_pointer_value()
+            .const_cast(context.i8_type().ptr_type(AddressSpace::Const));
+        let len = context.i64_type().const_int(value.len() as u64, false);
+        ty.const_named_struct(&[ptr.into(), len.into()]).into()
+    }
+
     /// Generates an IR value that represents the given `Literal`.
-    fn gen_literal(&mut self, lit: &Literal) -> BasicValueEnum {
+    fn gen_literal(&mut self, lit: &Literal, expr: ExprId) -> BasicValueEnum {
         match lit {
-            Literal::Int(v) => self
-                .module
-                .get_context()
-                .i64_type()
-                .const_int(unsafe { mem::transmute::<i64, u64>(*v) }, true)
-                .into(),
+            Literal::Int(v) => match self.db.type_ir(self.infer[expr].clone()) {
+                // The value of the literal is known to fit in the type of the literal
+                AnyTypeEnum::IntType(ty) => ty.const_int(v.value as u64, false).into(),
+                // An integer literal is a floating point value if its type annotation is a float
+                AnyTypeEnum::FloatType(ty) => ty.const_float(v.value as f64).into(),
+                _ => unreachable!("expected a numeric type for an integer literal"),
+            },
 
-            Literal::Float(v) => self
-                .module
-                .get_context()
-                .f64_type()
-                .const_float(*v as f64)
-                .into(),
-
-            Literal::Bool(value) => {
-                let ty = self.module.get_context().bool_type();
-                if *value {
-                    ty.const_all_ones().into()
-                } else {
-                    ty.const_zero().into()
-                }
+            Literal::Float(v) => {
+                let ty = match self.db.type_ir(self.infer[expr].clone()) {
+                    AnyTypeEnum::FloatType(ty) => ty,
+                    _ => unreachable!("expected a floating point type for a float literal"),
+                };
+                ty.const_float(v.value).into()
             }
 
-            Literal::String(_) => unimplemented!("string literals are not implemented yet"),
+            Literal::Bool(value) => self.gen_const_value(ConstValue::Bool(*value), expr),
+
+            Literal::String(value) => self.gen_string_literal(value, expr),
         }
     }
 
@@ -195,6 +495,165 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
         self.module.get_context().const_struct(&[], false).into()
     }
 
+    /// Generates the value of the zero-sized type `ty`, which carries no data.
+    fn gen_zero_sized_value(&self, ty: &Ty) -> BasicValueEnum {
+        match try_convert_any_to_basic(self.db.type_ir(ty.clone())) {
+            Some(BasicTypeEnum::StructType(ty)) => ty.const_zero().into(),
+            Some(BasicTypeEnum::ArrayType(ty)) => ty.const_zero().into(),
+            _ => unreachable!("only aggregates can be zero-sized"),
+        }
+    }
+
+    /// Generates IR for a record literal, e.g. `Foo { a: 1, b: 2.0 }`. A value struct is allocated
+    /// on the stack, whereas a GC struct is allocated by the runtime. Each field is stored in the
+    /// order in which it is specified.
+    fn gen_record_lit(&mut self, type_expr: ExprId, fields: &[RecordLitField]) -> BasicValueEnum {
+        let hir_struct = match self.infer[type_expr].as_simple() {
+            Some(TypeCtor::Struct(s)) => s,
+            _ => unreachable!("a record literal must have a struct type"),
+        };
+        let struct_name = hir_struct.name(self.db).to_string();
+        let packed = hir_struct.repr(self.db).packed;
+        let struct_ptr = match hir_struct.memory_kind(self.db) {
+            StructMemoryKind::GC => self.gen_gc_alloc(hir_struct),
+            StructMemoryKind::Value => self
+                .new_alloca_builder()
+                .build_alloca(self.db.struct_ty(hir_struct), &struct_name),
+        };
+        for field in fields {
+            let value = self.gen_expr(field.expr).expect("expected a field value");
+            let index = hir_struct
+                .field(self.db, &field.name)
+                .expect("unknown field, are we missing a diagnostic?")
+                .index();
+            let field_ptr = unsafe {
+                self.builder
+                    .build_struct_gep(struct_ptr, index, &format!("{}_ptr", field.name))
+            };
+            self.build_place_store(field_ptr, value, packed);
+        }
+        match hir_struct.memory_kind(self.db) {
+            StructMemoryKind::GC => struct_ptr.into(),
+            StructMemoryKind::Value => self.builder.build_load(struct_ptr, &struct_name),
+        }
+    }
+
+    /// Generates IR for a tuple expression, e.g. `(1, 2.0)`. Like a value struct, the tuple is
+    /// allocated on the stack and each element is stored in order.
+    fn gen_tuple(&mut self, expr: ExprId, exprs: &[ExprId]) -> BasicValueEnum {
+        if exprs.is_empty() {
+            return self.gen_empty();
+        }
+        let tuple_ty = match self.db.type_ir(self.infer[expr].clone()) {
+            AnyTypeEnum::StructType(ty) => ty,
+            _ => unreachable!("expected a struct type for a tuple"),
+        };
+        let tuple_ptr = self.new_alloca_builder().build_alloca(tuple_ty, "tuple");
+        for (index, elem) in exprs.iter().enumerate() {
+            let value = self
+                .gen_expr(*elem)
+                .expect("expected a tuple element value");
+            let elem_ptr = unsafe {
+                self.builder.build_struct_gep(
+                    tuple_ptr,
+                    index as u32,
+                    &format!("{}_ptr", tuple_field_name(index)),
+                )
+            };
+            self.builder.build_store(elem_ptr, value);
+        }
+        self.builder.build_load(tuple_ptr, "tuple")
+    }
+
+    /// Generates IR for an array literal, e.g. `[1, 2, 3]`. The array is allocated on the stack and
+    /// each element is stored in order.
+    fn gen_array(&mut self, expr: ExprId, exprs: &[ExprId]) -> BasicValueEnum {
+        let array_ty = match self.db.type_ir(self.infer[expr].clone()) {
+            AnyTypeEnum::ArrayType(ty) => ty,
+            _ => unreachable!("expected an array type for an array literal"),
+        };
+        let array_ptr = self.new_alloca_builder().build_alloca(array_ty, "array");
+        let index_type = self.module.get_context().i64_type();
+        for (index, elem) in exprs.iter().enumerate() {
+            let value = self
+                .gen_expr(*elem)
+                .expect("expected an array element value");
+            let elem_ptr = unsafe {
+                self.builder.build_in_bounds_gep(
+                    array_ptr,
+                    &[
+                        index_type.const_zero(),
+                        index_type.const_int(index as u64, false),
+                    ],
+                    &format!("elem_{}_ptr", index),
+                )
+            };
+            self.builder.build_store(elem_ptr, value);
+        }
+        self.builder.build_load(array_ptr, "array")
+    }
+
+    /// Generates IR that allocates zero-initialized memory for the GC struct `hir_struct` through
+    /// the runtime's allocator, and returns a pointer to the struct.
+    fn gen_gc_alloc(&self, hir_struct: hir::Struct) -> PointerValue {
+        let context = self.module.get_context();
+        let struct_name = hir_struct.name(self.db).to_string();
+        let layout = self.db.struct_layout(hir_struct);
+
+        let alloc_handle = self
+            .builder
+            .build_load(self.allocator_handle().as_pointer_value(), "alloc_handle");
+        let new_ptr = self.dispatch_table.gen_intrinsic_lookup(
+            self.db,
+            self.module,
+            &self.builder,
+            Intrinsic::New,
+        );
+        let ptr = self
+            .builder
+            .build_call(
+                new_ptr,
+                &[
+                    context.i64_type().const_int(layout.size, false).into(),
+                    context
+                        .i32_type()
+                        .const_int(u64::from(layout.alignment), false)
+                        .into(),
+                    alloc_handle,
+                ],
+                &format!("{}_mem", struct_name),
+            )
+            .try_as_basic_value()
+            .left()
+            .expect("the allocation intrinsic must return a pointer");
+        self.builder
+            .build_bitcast(
+                ptr,
+                self.db
+                    .struct_ty(hir_struct)
+                    .ptr_type(AddressSpace::Generic),
+                &struct_name,
+            )
+            .into_pointer_value()
+    }
+
+    /// Returns the global that holds the handle of the runtime's allocator. The runtime sets the
+    /// handle when it loads the assembly. The global is only declared here, it is defined when the
+    /// modules of the file are linked together.
+    fn allocator_handle(&self) -> GlobalValue {
+        self.module
+            .get_global(ALLOCATOR_HANDLE_NAME)
+            .unwrap_or_else(|| {
+                let handle_type = self
+                    .module
+                    .get_context()
+                    .i8_type()
+                    .ptr_type(AddressSpace::Generic);
+                self.module
+                    .add_global(handle_type, None, ALLOCATOR_HANDLE_NAME)
+            })
+    }
+
     /// Generates IR for the specified block expression.
     fn gen_block(
         &mut self,
@@ -207,7 +666,8 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
                 Statement::Let {
                     pat, initializer, ..
                 } => {
-                    self.gen_let_statement(*pat, *initializer);
+                    // No need to generate code after an initializer that has a `never` type.
+                    self.gen_let_statement(*pat, *initializer)?;
                 }
                 Statement::Expr(expr) => {
                     // No need to generate code after a statement that has a `never` return type.
@@ -219,6 +679,15 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
             .or_else(|| Some(self.gen_empty()))
     }
 
+    /// Terminates the current block with an `unreachable` instruction, unless the block is already
+    /// terminated. Used after expressions that diverge.
+    fn gen_unreachable(&mut self) {
+        let block = self.builder.get_insert_block().unwrap();
+        if block.get_terminator().is_none() {
+            self.builder.build_unreachable();
+        }
+    }
+
     /// Constructs a builder that should be used to emit an `alloca` instruction. These instructions
     /// should be at the start of the IR.
     fn new_alloca_builder(&self) -> Builder {
@@ -235,12 +704,19 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
         temp_builder
     }
 
-    /// Generate IR for a let statement: `let a:int = 3`
-    fn gen_let_statement(&mut self, pat: PatId, initializer: Option<ExprId>) {
+    /// Generate IR for a let statement: `let a:int = 3`. Returns `None` if the initializer
+    /// diverges.
+    fn gen_let_statement(&mut self, pat: PatId, initializer: Option<ExprId>) -> Option<()> {
+        if let Some(expr) = initializer {
+            if self.infer[expr].is_never() {
+                self.gen_expr(expr);
+                return None;
+            }
+        }
         let initializer = initializer.and_then(|expr| self.gen_expr(expr));
 
         match &self.body[pat] {
-            Pat::Bind { name } => {
+            Pat::Bind { name, .. } => {
                 let builder = self.new_alloca_builder();
                 let pat_ty = self.infer[pat].clone();
                 let ty = try_convert_any_to_basic(self.db.type_ir(pat_ty.clone()))
@@ -248,6 +724,8 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
                 let ptr = builder.build_alloca(ty, &name.to_string());
                 self.pat_to_local.insert(pat, ptr);
                 self.pat_to_name.insert(pat, name.to_string());
+                // A binding without an initializer is assigned before it is used on every path,
+                // which is verified by the definite initialization analysis
                 if !(pat_ty.is_empty() || pat_ty.is_never()) {
                     if let Some(value) = initializer {
                         self.builder.build_store(ptr, value);
@@ -255,15 +733,16 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
                 }
             }
             Pat::Wild => {}
-            Pat::Missing | Pat::Path(_) => unreachable!(),
+            Pat::Missing | Pat::Path(_) | Pat::Lit(_) => unreachable!(),
         }
+        Some(())
     }
 
     /// Generates IR for looking up a certain path expression.
     fn gen_path_expr(
         &self,
         path: &Path,
-        _expr: ExprId,
+        expr: ExprId,
         resolver: &Resolver,
     ) -> inkwell::values::BasicValueEnum {
         let resolution = resolver
@@ -282,6 +761,26 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
                     unreachable!("could not find the pattern..");
                 }
             }
+            Resolution::Def(ModuleDef::Struct(s)) => match s.memory_kind(self.db) {
+                StructMemoryKind::GC => self.gen_gc_alloc(s).into(),
+                // Unit structs have no fields, so every value is the same
+                StructMemoryKind::Value => self.db.struct_ty(s).const_zero().into(),
+            },
+            Resolution::Def(ModuleDef::Const(c)) => {
+                let value = c
+                    .value(self.db)
+                    .expect("constants are evaluated before code generation");
+                self.gen_const_value(value, expr)
+            }
+            Resolution::Def(ModuleDef::Static(s)) => self.builder.build_load(
+                self.static_global(s).as_pointer_value(),
+                &s.name(self.db).to_string(),
+            ),
+            Resolution::Def(ModuleDef::EnumVariant(v)) => {
+                self.gen_const_value(ConstValue::Int(v.discriminant(self.db)), expr)
+            }
+            // A function is only used as a value when it is coerced to a function pointer
+            Resolution::Def(ModuleDef::Function(f)) => self.gen_fn_ptr(f),
             Resolution::Def(_) => panic!("no support for module definitions"),
         }
     }
@@ -303,6 +802,7 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
                 .pat_to_local
                 .get(&pat)
                 .expect("unresolved local binding"),
+            Resolution::Def(ModuleDef::Static(s)) => self.static_global(s).as_pointer_value(),
             Resolution::Def(_) => panic!("no support for module definitions"),
         }
     }
@@ -310,24 +810,195 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
     /// Generates IR to calculate a binary operation between two expressions.
     fn gen_binary_op(
         &mut self,
-        _tgt_expr: ExprId,
+        tgt_expr: ExprId,
         lhs: ExprId,
         rhs: ExprId,
         op: BinaryOp,
     ) -> Option<BasicValueEnum> {
+        match op {
+            BinaryOp::LogicOp(op) => return self.gen_logic_op(lhs, rhs, op),
+            BinaryOp::Assignment { op } => return self.gen_assignment(tgt_expr, lhs, rhs, op),
+            _ => {}
+        }
         let lhs_type = self.infer[lhs].clone();
         let rhs_type = self.infer[rhs].clone();
         match lhs_type.as_simple() {
-            Some(TypeCtor::Float) => self.gen_binary_op_float(lhs, rhs, op),
-            Some(TypeCtor::Int) => self.gen_binary_op_int(lhs, rhs, op),
-            _ => unimplemented!(
-                "unimplemented operation {0}op{1}",
+            Some(TypeCtor::Float(_)) => self.gen_binary_op_float(lhs, rhs, op),
+            Some(TypeCtor::Int(ty)) => {
+                self.gen_binary_op_int(tgt_expr, lhs, rhs, op, ty.signedness)
+            }
+            // Enums can only be compared for equality, which is the same as comparing their
+            // discriminants
+            Some(TypeCtor::Enum(e)) => {
+                self.gen_binary_op_int(tgt_expr, lhs, rhs, op, e.repr(self.db).signedness)
+            }
+            Some(TypeCtor::Bool) => self.gen_binary_op_bool(lhs, rhs, op),
+            // Invalid operands are reported during type inference
+            _ => unreachable!(
+                "invalid operands {} and {} for {:?}",
                 lhs_type.display(self.db),
-                rhs_type.display(self.db)
+                rhs_type.display(self.db),
+                op
+            ),
+        }
+    }
+
+    /// Generates IR to calculate a unary operation. Negated literals and double negations are
+    /// folded. Negation of integers wraps around, i.e. negating the smallest value of a type
+    /// results in the same value.
+    fn gen_unary_op(
+        &mut self,
+        expr: ExprId,
+        operand: ExprId,
+        op: UnaryOp,
+    ) -> Option<BasicValueEnum> {
+        let body = self.body.clone();
+        match &body[operand] {
+            Expr::UnaryOp {
+                expr: inner,
+                op: inner_op,
+            } if *inner_op == op => return self.gen_expr(*inner),
+            Expr::Literal(lit @ Literal::Int(_)) | Expr::Literal(lit @ Literal::Float(_))
+                if op == UnaryOp::Neg =>
+            {
+                return match self.gen_literal(lit, operand) {
+                    BasicValueEnum::IntValue(value) => Some(value.const_neg().into()),
+                    BasicValueEnum::FloatValue(value) => Some(value.const_neg().into()),
+                    _ => unreachable!("expected a numeric literal"),
+                };
+            }
+            _ => {}
+        }
+
+        if op == UnaryOp::Not {
+            let value = self.gen_condition(operand)?;
+            return Some(self.builder.build_not(value, "not").into());
+        }
+
+        let value = self.gen_expr(operand)?;
+        match (op, self.infer[expr].as_simple()) {
+            (UnaryOp::Neg, Some(TypeCtor::Float(_))) => Some(
+                self.builder
+                    .build_float_neg(value.into_float_value(), "neg")
+                    .into(),
+            ),
+            (UnaryOp::Neg, Some(TypeCtor::Int(_))) => Some(
+                self.builder
+                    .build_int_neg(value.into_int_value(), "neg")
+                    .into(),
+            ),
+            _ => unreachable!(
+                "invalid operand {} for {:?}",
+                self.infer[expr].display(self.db),
+                op
             ),
         }
     }
 
+    /// Generates IR for a cast of an enum or an integer to an integer type. The value is truncated
+    /// if the target type is smaller, or extended according to the signedness of the operand if
+    /// the target type is larger.
+    fn gen_cast(&mut self, expr: ExprId, operand: ExprId) -> Option<BasicValueEnum> {
+        let value = self.gen_expr(operand)?.into_int_value();
+        let signedness = match self.infer[operand].as_simple() {
+            Some(TypeCtor::Int(ty)) => ty.signedness,
+            Some(TypeCtor::Enum(e)) => e.repr(self.db).signedness,
+            _ => unreachable!("expected an integer or enum operand for a cast"),
+        };
+        let ty = match self.db.type_ir(self.infer[expr].clone()) {
+            AnyTypeEnum::IntType(ty) => ty,
+            _ => unreachable!("expected an integer type for a cast"),
+        };
+
+        let from_bits = value.get_type().get_bit_width();
+        let to_bits = ty.get_bit_width();
+        let value = if to_bits < from_bits {
+            self.builder.build_int_truncate(value, ty, "cast")
+        } else if to_bits > from_bits {
+            match signedness {
+                Signedness::Signed => self.builder.build_int_s_extend(value, ty, "cast"),
+                Signedness::Unsigned => self.builder.build_int_z_extend(value, ty, "cast"),
+            }
+        } else {
+            value
+        };
+        Some(value.into())
+    }
+
+    /// Generates IR for a short-circuiting logical operation. The right-hand side is only
+    /// evaluated if the left-hand side does not already determine the result.
+    fn gen_logic_op(&mut self, lhs: ExprId, rhs: ExprId, op: LogicOp) -> Option<BasicValueEnum> {
+        let lhs_ir = self.gen_condition(lhs)?;
+        let lhs_block = self.builder.get_insert_block().unwrap();
+
+        // Generate the code blocks to branch to
+        let context = self.module.get_context();
+        let rhs_block = context.append_basic_block(&self.fn_value, "rhs");
+        let merge_block = context.append_basic_block(&self.fn_value, "logic_merge");
+        rhs_block
+            .move_after(&lhs_block)
+            .expect("programmer error, lhs_block is invalid");
+        let (then_block, else_block) = match op {
+            LogicOp::And => (&rhs_block, &merge_block),
+            LogicOp::Or => (&merge_block, &rhs_block),
+        };
+        self.builder
+            .build_conditional_branch(lhs_ir, then_block, else_block);
+
+        // Fill the rhs block
+        self.builder.position_at_end(&rhs_block);
+        let rhs_ir = self.gen_condition(rhs);
+        if rhs_ir.is_some() {
+            self.builder.build_unconditional_branch(&merge_block);
+        }
+        let rhs_block = self.builder.get_insert_block().unwrap();
+        merge_block.move_after(&rhs_block).unwrap();
+        self.builder.position_at_end(&merge_block);
+
+        // If the rhs diverges, the merge block can only be reached when the lhs determines the
+        // result.
+        match rhs_ir {
+            Some(rhs_ir) => {
+                let phi = self.builder.build_phi(context.bool_type(), "logictmp");
+                phi.add_incoming(&[(&lhs_ir, &lhs_block), (&rhs_ir, &rhs_block)]);
+                Some(phi.as_basic_value())
+            }
+            None => Some(lhs_ir.into()),
+        }
+    }
+
+    /// Generates IR to calculate a binary operation between two boolean values.
+    fn gen_binary_op_bool(
+        &mut self,
+        lhs_expr: ExprId,
+        rhs_expr: ExprId,
+        op: BinaryOp,
+    ) -> Option<BasicValueEnum> {
+        let lhs = self
+            .gen_expr(lhs_expr)
+            .expect("no lhs value")
+            .into_int_value();
+        let rhs = self
+            .gen_expr(rhs_expr)
+            .expect("no rhs value")
+            .into_int_value();
+        match op {
+            BinaryOp::CmpOp(CmpOp::Eq { negated }) => {
+                let (name, predicate) = if negated {
+                    ("neq", IntPredicate::NE)
+                } else {
+                    ("eq", IntPredicate::EQ)
+                };
+                Some(
+                    self.builder
+                        .build_int_compare(predicate, lhs, rhs, name)
+                        .into(),
+                )
+            }
+            _ => unreachable!(format!("Operator {:?} is not implemented for bool", op)),
+        }
+    }
+
     /// Generates IR to calculate a binary operation between two floating point values.
     fn gen_binary_op_float(
         &mut self,
@@ -346,9 +1017,11 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
         match op {
             BinaryOp::ArithOp(op) => Some(self.gen_arith_bin_op_float(lhs, rhs, op).into()),
             BinaryOp::CmpOp(op) => {
+                // Comparisons with NaN are false, except `!=` which is the negation of `==`, as
+                // defined by IEEE 754
                 let (name, predicate) = match op {
                     CmpOp::Eq { negated: false } => ("eq", FloatPredicate::OEQ),
-                    CmpOp::Eq { negated: true } => ("neq", FloatPredicate::ONE),
+                    CmpOp::Eq { negated: true } => ("neq", FloatPredicate::UNE),
                     CmpOp::Ord {
                         ordering: Ordering::Less,
                         strict: false,
@@ -372,26 +1045,22 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
                         .into(),
                 )
             }
-            BinaryOp::Assignment { op } => {
-                let rhs = match op {
-                    Some(op) => self.gen_arith_bin_op_float(lhs, rhs, op),
-                    None => rhs,
-                };
-                let place = self.gen_place_expr(lhs_expr);
-                self.builder.build_store(place, rhs);
-                Some(self.gen_empty())
-            }
-            _ => unimplemented!("Operator {:?} is not implemented for float", op),
+            _ => unreachable!("Operator {:?} is not implemented for float", op),
         }
     }
 
-    /// Generates IR to calculate a binary operation between two integer values.
+    /// Generates IR to calculate the binary operation `expr` between two integer values with the
+    /// specified signedness. Arithmetic wraps around on overflow, unless overflow checks are
+    /// enabled.
     fn gen_binary_op_int(
         &mut self,
+        expr: ExprId,
         lhs_expr: ExprId,
         rhs_expr: ExprId,
         op: BinaryOp,
+        signedness: Signedness,
     ) -> Option<BasicValueEnum> {
+        let signed = signedness == Signedness::Signed;
         let lhs = self
             .gen_expr(lhs_expr)
             .expect("no lhs value")
@@ -400,8 +1069,16 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
             .gen_expr(rhs_expr)
             .expect("no rhs value")
             .into_int_value();
+        let trap_site = if dispatch_table::is_trap_checked(self.db, op, &self.infer[lhs_expr]) {
+            Some(expr)
+        } else {
+            None
+        };
         match op {
-            BinaryOp::ArithOp(op) => Some(self.gen_arith_bin_op_int(lhs, rhs, op).into()),
+            BinaryOp::ArithOp(op) => Some(
+                self.gen_arith_bin_op_int(lhs, rhs, op, signed, trap_site)
+                    .into(),
+            ),
             BinaryOp::CmpOp(op) => {
                 let (name, predicate) = match op {
                     CmpOp::Eq { negated: false } => ("eq", IntPredicate::EQ),
@@ -409,19 +1086,47 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
                     CmpOp::Ord {
                         ordering: Ordering::Less,
                         strict: false,
-                    } => ("lesseq", IntPredicate::SLE),
+                    } => (
+                        "lesseq",
+                        if signed {
+                            IntPredicate::SLE
+                        } else {
+                            IntPredicate::ULE
+                        },
+                    ),
                     CmpOp::Ord {
                         ordering: Ordering::Less,
                         strict: true,
-                    } => ("less", IntPredicate::SLT),
+                    } => (
+                        "less",
+                        if signed {
+                            IntPredicate::SLT
+                        } else {
+                            IntPredicate::ULT
+                        },
+                    ),
                     CmpOp::Ord {
                         ordering: Ordering::Greater,
                         strict: false,
-                    } => ("greatereq", IntPredicate::SGE),
+                    } => (
+                        "greatereq",
+                        if signed {
+                            IntPredicate::SGE
+                        } else {
+                            IntPredicate::UGE
+                        },
+                    ),
                     CmpOp::Ord {
                         ordering: Ordering::Greater,
                         strict: true,
-                    } => ("greater", IntPredicate::SGT),
+                    } => (
+                        "greater",
+                        if signed {
+                            IntPredicate::SGT
+                        } else {
+                            IntPredicate::UGT
+                        },
+                    ),
                 };
                 Some(
                     self.builder
@@ -429,91 +1134,1202 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
                         .into(),
                 )
             }
-            BinaryOp::Assignment { op } => {
-                let rhs = match op {
-                    Some(op) => self.gen_arith_bin_op_int(lhs, rhs, op),
-                    None => rhs,
-                };
-                let place = self.gen_place_expr(lhs_expr);
-                self.builder.build_store(place, rhs);
-                Some(self.gen_empty())
-            }
             _ => unreachable!(format!("Operator {:?} is not implemented for integer", op)),
         }
     }
 
-    fn gen_arith_bin_op_int(&mut self, lhs: IntValue, rhs: IntValue, op: ArithOp) -> IntValue {
+    /// Generates IR for the assignment `expr` of `rhs_expr` to the place `lhs_expr`, or for the
+    /// compound assignment with the arithmetic operator `op`, e.g. `a[i] += 1`. The address of the
+    /// place is computed once, before the right-hand side is evaluated, so side effects of the
+    /// place, e.g. of an index, happen exactly once. A compound assignment loads the current value
+    /// from that address.
+    fn gen_assignment(
+        &mut self,
+        expr: ExprId,
+        lhs_expr: ExprId,
+        rhs_expr: ExprId,
+        op: Option<ArithOp>,
+    ) -> Option<BasicValueEnum> {
+        let place = self.gen_place_expr(lhs_expr);
+        let unaligned = self.is_unaligned_place(lhs_expr);
+        let rhs = self.gen_expr(rhs_expr)?;
+        let value = match op {
+            None => rhs,
+            Some(op) => {
+                let lhs = self.build_place_load(place, "lhs", unaligned);
+                let lhs_type = self.infer[lhs_expr].clone();
+                match lhs_type.as_simple() {
+                    Some(TypeCtor::Float(_)) => self
+                        .gen_arith_bin_op_float(lhs.into_float_value(), rhs.into_float_value(), op)
+                        .into(),
+                    Some(TypeCtor::Int(ty)) => {
+                        let assignment = BinaryOp::Assignment { op: Some(op) };
+                        let trap_site =
+                            if dispatch_table::is_trap_checked(self.db, assignment, &lhs_type) {
+                                Some(expr)
+                            } else {
+                                None
+                            };
+                        self.gen_arith_bin_op_int(
+                            lhs.into_int_value(),
+                            rhs.into_int_value(),
+                            op,
+                            ty.signedness == Signedness::Signed,
+                            trap_site,
+                        )
+                        .into()
+                    }
+                    _ => unreachable!("invalid operand {} for {:?}", lhs_type.display(self.db), op),
+                }
+            }
+        };
+        self.build_place_store(place, value, unaligned);
+        Some(self.gen_empty())
+    }
+
+    /// Generates IR for an arithmetic operation on integers. If the operation is checked, a failed
+    /// check traps at `trap_site`.
+    fn gen_arith_bin_op_int(
+        &mut self,
+        lhs: IntValue,
+        rhs: IntValue,
+        op: ArithOp,
+        signed: bool,
+        trap_site: Option<ExprId>,
+    ) -> IntValue {
+        match (op, trap_site) {
+            (ArithOp::Add, Some(expr))
+            | (ArithOp::Subtract, Some(expr))
+            | (ArithOp::Multiply, Some(expr)) => {
+                self.gen_overflow_checked_int(expr, lhs, rhs, op, signed)
+            }
+            (ArithOp::Divide, Some(expr)) | (ArithOp::Remainder, Some(expr)) => {
+                let rhs = self.gen_zero_divisor_check(expr, rhs);
+                if signed && op == ArithOp::Divide {
+                    self.gen_division_overflow_check(expr, lhs, rhs);
+                }
+                self.gen_unchecked_arith_bin_op_int(lhs, rhs, op, signed)
+            }
+            _ => self.gen_unchecked_arith_bin_op_int(lhs, rhs, op, signed),
+        }
+    }
+
+    /// Generates IR for an arithmetic operation on integers that wraps around on overflow.
+    fn gen_unchecked_arith_bin_op_int(
+        &mut self,
+        lhs: IntValue,
+        rhs: IntValue,
+        op: ArithOp,
+        signed: bool,
+    ) -> IntValue {
         match op {
             ArithOp::Add => self.builder.build_int_add(lhs, rhs, "add"),
             ArithOp::Subtract => self.builder.build_int_sub(lhs, rhs, "sub"),
-            ArithOp::Divide => self.builder.build_int_signed_div(lhs, rhs, "div"),
+            ArithOp::Divide => self.gen_int_div(lhs, rhs, signed),
             ArithOp::Multiply => self.builder.build_int_mul(lhs, rhs, "mul"),
+            ArithOp::Remainder => self.gen_int_rem(lhs, rhs, signed),
+            ArithOp::LeftShift | ArithOp::RightShift => self.gen_int_shift(lhs, rhs, op, signed),
         }
     }
 
-    fn gen_arith_bin_op_float(
+    /// Generates IR for a shift of an integer. To avoid undefined behavior the shift amount is
+    /// taken modulo the number of bits of the type, e.g. `1u8 << 9` results in `2`. A right shift
+    /// of a signed integer preserves its sign.
+    fn gen_int_shift(
         &mut self,
-        lhs: FloatValue,
-        rhs: FloatValue,
+        lhs: IntValue,
+        rhs: IntValue,
         op: ArithOp,
-    ) -> FloatValue {
+        signed: bool,
+    ) -> IntValue {
+        let int_type = rhs.get_type();
+        let mask = int_type.const_int(u64::from(int_type.get_bit_width() - 1), false);
+        let amount = self.builder.build_and(rhs, mask, "amount");
         match op {
-            ArithOp::Add => self.builder.build_float_add(lhs, rhs, "add"),
-            ArithOp::Subtract => self.builder.build_float_sub(lhs, rhs, "sub"),
-            ArithOp::Divide => self.builder.build_float_div(lhs, rhs, "div"),
-            ArithOp::Multiply => self.builder.build_float_mul(lhs, rhs, "mul"),
+            ArithOp::LeftShift => self.builder.build_left_shift(lhs, amount, "shl"),
+            _ => self.builder.build_right_shift(lhs, amount, signed, "shr"),
         }
     }
 
-    /// Given an expression generate code that results in a memory address that can be used for
-    /// other place operations.
-    fn gen_place_expr(&mut self, expr: ExprId) -> PointerValue {
-        let body = self.body.clone();
-        match &body[expr] {
-            Expr::Path(ref p) => {
-                let resolver = mun_hir::resolver_for_expr(self.body.clone(), self.db, expr);
-                self.gen_path_place_expr(p, expr, &resolver)
-            }
-            _ => unreachable!("invalid place expression"),
-        }
-    }
+    /// Generates IR for the addition, subtraction or multiplication of integers `expr` that calls
+    /// the runtime's trap handler when the operation overflows. Execution continues with the
+    /// wrapped result when the handler returns; the runtime reports the trap once the invoked
+    /// function returns.
+    fn gen_overflow_checked_int(
+        &mut self,
+        expr: ExprId,
+        lhs: IntValue,
+        rhs: IntValue,
+        op: ArithOp,
+        signed: bool,
+    ) -> IntValue {
+        let (value, overflow) = self.gen_int_with_overflow(lhs, rhs, op, signed);
 
-    // TODO: Implement me!
-    fn should_use_dispatch_table(&self) -> bool {
-        true
-    }
+        // Call the trap handler on overflow
+        let context = self.module.get_context();
+        let current_block = self.builder.get_insert_block().unwrap();
+        let trap_block = context.append_basic_block(&self.fn_value, "overflow_trap");
+        let continue_block = context.append_basic_block(&self.fn_value, "no_overflow");
+        trap_block.move_after(&current_block).unwrap();
+        continue_block.move_after(&trap_block).unwrap();
+        self.builder
+            .build_conditional_branch(overflow, &trap_block, &continue_block);
 
-    /// Generates IR for a function call.
-    fn gen_call(&mut self, callee: ExprId, args: &[ExprId]) -> CallSiteValue {
-        // Get the function value from the map
-        let function = self.infer[callee]
-            .as_function_def()
-            .expect("expected a function expression");
+        self.builder.position_at_end(&trap_block);
+        self.gen_trap(expr, TrapKind::Overflow, None);
+        self.builder.build_unconditional_branch(&continue_block);
 
-        // Get all the arguments
-        let args: Vec<BasicValueEnum> = args
-            .iter()
-            .map(|expr| self.gen_expr(*expr).expect("expected a value"))
-            .collect();
+        self.builder.position_at_end(&continue_block);
+        value
+    }
 
-        if self.should_use_dispatch_table() {
-            let ptr_value =
-                self.dispatch_table
-                    .gen_function_lookup(self.db, &self.builder, function);
-            self.builder
-                .build_call(ptr_value, &args, &function.name(self.db).to_string())
-        } else {
-            let llvm_function = self
-                .function_map
-                .get(&function)
-                .expect("missing function value for hir function");
+    /// Generates IR that calls the runtime's trap handler when the divisor `rhs` of the integer
+    /// division or remainder `expr` is zero. The check is omitted for divisors that are constant
+    /// and nonzero. Returns the divisor to use for the operation; when the handler returns,
+    /// execution continues with a divisor of `1` instead of zero, because dividing by zero has
+    /// undefined behavior.
+    fn gen_zero_divisor_check(&mut self, expr: ExprId, rhs: IntValue) -> IntValue {
+        let int_type = rhs.get_type();
+        let is_zero =
             self.builder
-                .build_call(*llvm_function, &args, &function.name(self.db).to_string())
+                .build_int_compare(IntPredicate::EQ, rhs, int_type.const_zero(), "is_zero");
+        // The comparison of a constant divisor is folded into a constant
+        if is_zero.get_zero_extended_constant() == Some(0) {
+            return rhs;
         }
-    }
 
-    /// Generates IR for an if statement.
-    fn gen_if(
+        let context = self.module.get_context();
+        let current_block = self.builder.get_insert_block().unwrap();
+        let trap_block = context.append_basic_block(&self.fn_value, "division_by_zero_trap");
+        let continue_block = context.append_basic_block(&self.fn_value, "nonzero_divisor");
+        trap_block.move_after(&current_block).unwrap();
+        continue_block.move_after(&trap_block).unwrap();
+        self.builder
+            .build_conditional_branch(is_zero, &trap_block, &continue_block);
+
+        self.builder.position_at_end(&trap_block);
+        self.gen_trap(expr, TrapKind::DivisionByZero, None);
+        self.builder.build_unconditional_branch(&continue_block);
+
+        self.builder.position_at_end(&continue_block);
+        let divisor = self.builder.build_phi(int_type, "divisor");
+        divisor.add_incoming(&[
+            (&rhs, &current_block),
+            (&int_type.const_int(1, false), &trap_block),
+        ]);
+        divisor.as_basic_value().into_int_value()
+    }
+
+    /// Generates IR that calls the runtime's trap handler when the signed integer division `expr`
+    /// of `lhs` by `rhs` overflows, i.e. when the smallest value of the type is divided by `-1`.
+    /// The check is omitted when either operand is a constant that rules out overflow. When the
+    /// handler returns, execution continues with the wrapped quotient.
+    fn gen_division_overflow_check(&mut self, expr: ExprId, lhs: IntValue, rhs: IntValue) {
+        let overflow = self.gen_is_division_overflow(lhs, rhs);
+        if overflow.get_zero_extended_constant() == Some(0) {
+            return;
+        }
+
+        let context = self.module.get_context();
+        let current_block = self.builder.get_insert_block().unwrap();
+        let trap_block = context.append_basic_block(&self.fn_value, "division_overflow_trap");
+        let continue_block = context.append_basic_block(&self.fn_value, "no_division_overflow");
+        trap_block.move_after(&current_block).unwrap();
+        continue_block.move_after(&trap_block).unwrap();
+        self.builder
+            .build_conditional_branch(overflow, &trap_block, &continue_block);
+
+        self.builder.position_at_end(&trap_block);
+        self.gen_trap(expr, TrapKind::Overflow, None);
+        self.builder.build_unconditional_branch(&continue_block);
+
+        self.builder.position_at_end(&continue_block);
+    }
+
+    /// Generates IR that results in an `i1` that is true if the signed integer division of `lhs`
+    /// by `rhs` overflows, i.e. if `lhs` is the smallest value of the type and `rhs` is `-1`. The
+    /// result is a constant if either operand is a constant that rules out overflow.
+    fn gen_is_division_overflow(&mut self, lhs: IntValue, rhs: IntValue) -> IntValue {
+        let int_type = lhs.get_type();
+        let no_overflow = self.module.get_context().bool_type().const_zero();
+
+        // The comparison of a constant operand is folded into a constant
+        let minus_one = int_type.const_all_ones();
+        let is_minus_one =
+            self.builder
+                .build_int_compare(IntPredicate::EQ, rhs, minus_one, "is_minus_one");
+        if is_minus_one.get_zero_extended_constant() == Some(0) {
+            return no_overflow;
+        }
+        let min = int_type.const_int(1 << (int_type.get_bit_width() - 1), false);
+        let is_min = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, lhs, min, "is_min");
+        if is_min.get_zero_extended_constant() == Some(0) {
+            return no_overflow;
+        }
+        self.builder.build_and(is_min, is_minus_one, "overflow")
+    }
+
+    /// Records a call to the runtime's trap handler at the current position, for a trap of `kind`
+    /// at the location of `expr`. A failed assertion passes its `message` to the handler. The call
+    /// is generated by `gen_trap_calls`; it precedes the instructions that follow in the block.
+    fn gen_trap(&mut self, expr: ExprId, kind: TrapKind, message: Option<PointerValue>) {
+        self.trap_calls.push(TrapCall {
+            block: self.builder.get_insert_block().unwrap(),
+            expr,
+            kind,
+            message,
+        });
+    }
+
+    /// Generates the table of the trap sites of the body in a private global, and the recorded
+    /// calls to the runtime's trap handler, each of which passes the table and the index of its
+    /// site in the table.
+    fn gen_trap_calls(&mut self) {
+        if self.trap_calls.is_empty() {
+            return;
+        }
+
+        let context = self.module.get_context();
+        let site_type = trap_site_type(&context);
+        let function_name = self.gen_c_string(&self.hir_function.qualified_name(self.db));
+        let sites: Vec<_> = self
+            .trap_calls
+            .iter()
+            .map(|call| {
+                let (file_id, line) = match self.expr_location(call.expr) {
+                    Some((file_id, location)) => (file_id, location.line + 1),
+                    None => (self.hir_function.module(self.db).file_id(), 0),
+                };
+                let file = self.gen_c_string(self.db.file_relative_path(file_id).as_str());
+                site_type.const_named_struct(&[
+                    function_name.into(),
+                    file.into(),
+                    context.i32_type().const_int(u64::from(line), false).into(),
+                    context
+                        .i8_type()
+                        .const_int(call.kind.abi_value(), false)
+                        .into(),
+                ])
+            })
+            .collect();
+
+        let table_type = site_type.array_type(sites.len() as u32);
+        let table = self.module.add_global(table_type, None, "trap_sites");
+        table.set_linkage(Linkage::Private);
+        table.set_constant(true);
+        table.set_initializer(&site_type.const_array(&sites));
+        let table_ptr = table
+            .as_pointer_value()
+            .const_cast(context.i8_type().ptr_type(AddressSpace::Generic));
+
+        for (index, call) in std::mem::take(&mut self.trap_calls).into_iter().enumerate() {
+            match call.block.get_first_instruction() {
+                Some(instruction) => self.builder.position_before(&instruction),
+                None => self.builder.position_at_end(&call.block),
+            }
+            let intrinsic = match call.message {
+                Some(_) => Intrinsic::AssertFailed,
+                None => Intrinsic::Trap,
+            };
+            let trap_ptr = self.dispatch_table.gen_intrinsic_lookup(
+                self.db,
+                self.module,
+                &self.builder,
+                intrinsic,
+            );
+            let site = context.i32_type().const_int(index as u64, false);
+            let mut args: Vec<BasicValueEnum> = vec![table_ptr.into(), site.into()];
+            args.extend(call.message.map(BasicValueEnum::from));
+            self.builder.build_call(trap_ptr, &args, "");
+
+            // The call precedes instructions that already have a location, so its location is
+            // attached to the block as a whole
+            let location = self
+                .expr_location(call.expr)
+                .map(|(_, location)| location)
+                .or(self.debug_location);
+            if let (Some(scope), Some(location)) = (self.debug_scope, location) {
+                scope.set_block_location(call.block, location);
+            }
+        }
+    }
+
+    /// Generates IR for an addition or subtraction of integers that results in the smallest or
+    /// largest value of the type when the operation overflows. The saturating intrinsics are not
+    /// available in LLVM 7, so the result is selected based on the overflow flag instead.
+    fn gen_saturating_int(
+        &mut self,
+        lhs: IntValue,
+        rhs: IntValue,
+        op: ArithOp,
+        signed: bool,
+    ) -> IntValue {
+        let (value, overflow) = self.gen_int_with_overflow(lhs, rhs, op, signed);
+
+        let int_type = lhs.get_type();
+        let bits = int_type.get_bit_width();
+        let saturated = if signed {
+            // The wrapped result of a signed overflow has the opposite sign of the exact result
+            let min = int_type.const_int(1 << (bits - 1), false);
+            let max = int_type.const_int((1 << (bits - 1)) - 1, false);
+            let zero = int_type.const_zero();
+            let is_negative =
+                self.builder
+                    .build_int_compare(IntPredicate::SLT, value, zero, "is_negative");
+            self.builder
+                .build_select(is_negative, max, min, "saturated")
+                .into_int_value()
+        } else {
+            match op {
+                ArithOp::Add => int_type.const_all_ones(),
+                _ => int_type.const_zero(),
+            }
+        };
+        self.builder
+            .build_select(overflow, saturated, value, "saturating")
+            .into_int_value()
+    }
+
+    /// Generates a call to the LLVM intrinsic that performs an addition, subtraction or
+    /// multiplication of integers and reports whether it overflows. Returns the wrapped result and
+    /// the `i1` overflow flag.
+    fn gen_int_with_overflow(
+        &mut self,
+        lhs: IntValue,
+        rhs: IntValue,
+        op: ArithOp,
+        signed: bool,
+    ) -> (IntValue, IntValue) {
+        let op_name = match op {
+            ArithOp::Add => "add",
+            ArithOp::Subtract => "sub",
+            ArithOp::Multiply => "mul",
+            _ => unreachable!("{:?} does not overflow", op),
+        };
+
+        // Declare the LLVM intrinsic, e.g. `{ i8, i1 } @llvm.uadd.with.overflow.i8(i8, i8)`
+        let context = self.module.get_context();
+        let int_type = lhs.get_type();
+        let intrinsic_name = format!(
+            "llvm.{}{}.with.overflow.i{}",
+            if signed { "s" } else { "u" },
+            op_name,
+            int_type.get_bit_width()
+        );
+        let intrinsic = self
+            .module
+            .get_function(&intrinsic_name)
+            .unwrap_or_else(|| {
+                let result_type =
+                    context.struct_type(&[int_type.into(), context.bool_type().into()], false);
+                let fn_type = result_type.fn_type(&[int_type.into(), int_type.into()], false);
+                self.module.add_function(&intrinsic_name, fn_type, None)
+            });
+
+        let result = self
+            .builder
+            .build_call(intrinsic, &[lhs.into(), rhs.into()], "checked")
+            .try_as_basic_value()
+            .left()
+            .expect("overflow intrinsics return a value")
+            .into_struct_value();
+        let value = self
+            .builder
+            .build_extract_value(result, 0, op_name)
+            .unwrap()
+            .into_int_value();
+        let overflow = self
+            .builder
+            .build_extract_value(result, 1, "overflow")
+            .unwrap()
+            .into_int_value();
+        (value, overflow)
+    }
+
+    /// Generates IR to calculate the quotient of an integer division, rounded towards zero. To
+    /// avoid undefined behavior the quotient is fully defined: `x / 0` results in `x` and
+    /// `MIN / -1` wraps around to `MIN`.
+    fn gen_int_div(&mut self, lhs: IntValue, rhs: IntValue, signed: bool) -> IntValue {
+        let int_type = rhs.get_type();
+        let one = int_type.const_int(1, false);
+        let is_zero =
+            self.builder
+                .build_int_compare(IntPredicate::EQ, rhs, int_type.const_zero(), "is_zero");
+
+        // Replace divisors that would cause undefined behavior by `1`, which results in the
+        // dividend.
+        let invalid_rhs = if signed {
+            let overflow = self.gen_is_division_overflow(lhs, rhs);
+            self.builder.build_or(is_zero, overflow, "invalid_rhs")
+        } else {
+            is_zero
+        };
+        let safe_rhs = self
+            .builder
+            .build_select(invalid_rhs, one, rhs, "safe_rhs")
+            .into_int_value();
+        if signed {
+            self.builder.build_int_signed_div(lhs, safe_rhs, "div")
+        } else {
+            self.builder.build_int_unsigned_div(lhs, safe_rhs, "div")
+        }
+    }
+
+    /// Generates IR to calculate the remainder of an integer division. The result has the sign of
+    /// the dividend. To avoid undefined behavior the remainder is fully defined: `x % 0` results
+    /// in `x` and `MIN % -1` results in `0`.
+    fn gen_int_rem(&mut self, lhs: IntValue, rhs: IntValue, signed: bool) -> IntValue {
+        let int_type = rhs.get_type();
+        let zero = int_type.const_int(0, false);
+        let one = int_type.const_int(1, false);
+        let is_zero = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, rhs, zero, "is_zero");
+
+        // Replace divisors that would cause undefined behavior by `1`, which results in a
+        // remainder of `0`.
+        let invalid_rhs = if signed {
+            let minus_one = int_type.const_all_ones();
+            let is_minus_one =
+                self.builder
+                    .build_int_compare(IntPredicate::EQ, rhs, minus_one, "is_minus_one");
+            self.builder.build_or(is_zero, is_minus_one, "invalid_rhs")
+        } else {
+            is_zero
+        };
+        let safe_rhs = self
+            .builder
+            .build_select(invalid_rhs, one, rhs, "safe_rhs")
+            .into_int_value();
+        let rem = if signed {
+            self.builder.build_int_signed_rem(lhs, safe_rhs, "rem")
+        } else {
+            self.builder.build_int_unsigned_rem(lhs, safe_rhs, "rem")
+        };
+        self.builder
+            .build_select(is_zero, lhs, rem, "rem")
+            .into_int_value()
+    }
+
+    fn gen_arith_bin_op_float(
+        &mut self,
+        lhs: FloatValue,
+        rhs: FloatValue,
+        op: ArithOp,
+    ) -> FloatValue {
+        match op {
+            ArithOp::Add => self.builder.build_float_add(lhs, rhs, "add"),
+            ArithOp::Subtract => self.builder.build_float_sub(lhs, rhs, "sub"),
+            ArithOp::Divide => self.builder.build_float_div(lhs, rhs, "div"),
+            ArithOp::Multiply => self.builder.build_float_mul(lhs, rhs, "mul"),
+            ArithOp::Remainder => self.builder.build_float_rem(lhs, rhs, "rem"),
+            ArithOp::LeftShift | ArithOp::RightShift => {
+                unreachable!("{:?} is not implemented for floats", op)
+            }
+        }
+    }
+
+    /// Given an expression generate code that results in a memory address that can be used for
+    /// other place operations.
+    fn gen_place_expr(&mut self, expr: ExprId) -> PointerValue {
+        let body = self.body.clone();
+        match &body[expr] {
+            Expr::Path(ref p) => {
+                let resolver = mun_hir::resolver_for_expr(self.body.clone(), self.db, expr);
+                self.gen_path_place_expr(p, expr, &resolver)
+            }
+            Expr::Field {
+                expr: receiver_expr,
+                ..
+            } => self.gen_field_place_expr(expr, *receiver_expr),
+            Expr::Index { base, index } => self.gen_index_place_expr(*base, *index),
+            _ => unreachable!("invalid place expression"),
+        }
+    }
+
+    /// Returns true if the specified expression refers to a memory location. Parameters and local
+    /// bindings are stored on the stack, so paths and fields or elements of paths are place
+    /// expressions.
+    fn is_place_expr(&self, expr: ExprId) -> bool {
+        match &self.body[expr] {
+            Expr::Path(_) => true,
+            Expr::Field {
+                expr: receiver_expr,
+                ..
+            }
+            | Expr::Index {
+                base: receiver_expr,
+                ..
+            } => self.is_place_expr(*receiver_expr),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the specified expression is a path to a local binding that is not declared
+    /// `mut`.
+    fn is_immutable_local(&self, expr: ExprId) -> bool {
+        let path = match &self.body[expr] {
+            Expr::Path(path) => path,
+            _ => return false,
+        };
+        let resolver = mun_hir::resolver_for_expr(self.body.clone(), self.db, expr);
+        match resolver
+            .resolve_path_without_assoc_items(self.db, path)
+            .take_values()
+        {
+            Some(Resolution::LocalBinding(pat)) => match &self.body[pat] {
+                Pat::Bind { is_mutable, .. } => !is_mutable,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Returns true if values of type `ty` are GC structs, which are referred to by pointer.
+    fn is_gc_struct(&self, ty: &Ty) -> bool {
+        match ty.as_simple() {
+            Some(TypeCtor::Struct(s)) => s.memory_kind(self.db) == StructMemoryKind::GC,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the memory location of the place expression `expr` may not be aligned to
+    /// its type, because it is a field of a packed struct or lies within such a field.
+    fn is_unaligned_place(&self, expr: ExprId) -> bool {
+        match &self.body[expr] {
+            Expr::Field {
+                expr: receiver_expr,
+                ..
+            } => {
+                let receiver_ty = &self.infer[*receiver_expr];
+                let packed = match receiver_ty.as_simple() {
+                    Some(TypeCtor::Struct(s)) => s.repr(self.db).packed,
+                    _ => false,
+                };
+                // A GC struct is allocated with the alignment of its own type
+                packed
+                    || (!self.is_gc_struct(receiver_ty) && self.is_unaligned_place(*receiver_expr))
+            }
+            Expr::Index { base, .. } => self.is_unaligned_place(*base),
+            _ => false,
+        }
+    }
+
+    /// Loads the value at `ptr`. If `unaligned` is true, `ptr` may not be aligned to the type of
+    /// the value, so the load makes no assumption about its alignment.
+    fn build_place_load(&self, ptr: PointerValue, name: &str, unaligned: bool) -> BasicValueEnum {
+        let value = self.builder.build_load(ptr, name);
+        if unaligned {
+            value
+                .as_instruction_value()
+                .and_then(|load| load.set_alignment(1).ok())
+                .expect("could not set the alignment of a load");
+        }
+        value
+    }
+
+    /// Stores `value` at `ptr`. If `unaligned` is true, `ptr` may not be aligned to the type of
+    /// the value, so the store makes no assumption about its alignment.
+    fn build_place_store(&self, ptr: PointerValue, value: impl BasicValue, unaligned: bool) {
+        let store = self.builder.build_store(ptr, value);
+        if unaligned {
+            store
+                .set_alignment(1)
+                .expect("could not set the alignment of a store");
+        }
+    }
+
+    /// Generates IR for a field access expression. A field of a place expression or of a GC struct
+    /// is loaded from memory, whereas a field of a temporary value struct is extracted from that
+    /// value.
+    fn gen_field(&mut self, expr: ExprId, receiver_expr: ExprId) -> Option<BasicValueEnum> {
+        let (index, name) = self.field_index_and_name(expr);
+        if self.is_place_expr(receiver_expr) || self.is_gc_struct(&self.infer[receiver_expr]) {
+            let ptr = self.gen_field_place_expr(expr, receiver_expr);
+            Some(self.build_place_load(ptr, &name, self.is_unaligned_place(expr)))
+        } else {
+            let receiver = self.gen_expr(receiver_expr)?.into_struct_value();
+            self.builder.build_extract_value(receiver, index, &name)
+        }
+    }
+
+    /// Returns the index of the field accessed by `expr` within its struct or tuple, and the name
+    /// of the field in the IR.
+    fn field_index_and_name(&self, expr: ExprId) -> (u32, String) {
+        if let Some(field) = self.infer.field_resolution(expr) {
+            return (field.index(), field.name(self.db).to_string());
+        }
+        match &self.body[expr] {
+            Expr::Field { name, .. } => {
+                let index = name
+                    .as_tuple_index()
+                    .expect("unresolved field, are we missing a diagnostic?");
+                (index as u32, tuple_field_name(index))
+            }
+            _ => unreachable!("expected a field expression"),
+        }
+    }
+
+    /// Generates IR that computes the memory address of the field accessed by `expr`. The receiver
+    /// of a GC struct already is a pointer to the struct.
+    fn gen_field_place_expr(&mut self, expr: ExprId, receiver_expr: ExprId) -> PointerValue {
+        let (index, name) = self.field_index_and_name(expr);
+        let receiver_ptr = if self.is_gc_struct(&self.infer[receiver_expr]) {
+            self.gen_expr(receiver_expr)
+                .expect("expected a struct pointer")
+                .into_pointer_value()
+        } else {
+            self.gen_place_expr(receiver_expr)
+        };
+        unsafe {
+            self.builder
+                .build_struct_gep(receiver_ptr, index, &format!("{}_ptr", name))
+        }
+    }
+
+    /// Generates IR for an index expression, e.g. `a[i]`, which loads the element from memory.
+    fn gen_index(&mut self, base: ExprId, index: ExprId) -> Option<BasicValueEnum> {
+        let elem_ptr = self.gen_index_place_expr(base, index);
+        Some(self.build_place_load(elem_ptr, "elem", self.is_unaligned_place(base)))
+    }
+
+    /// Generates IR that computes the memory address of the element `index` of the array `base`.
+    /// An array that is not a place expression is first stored on the stack. With bounds checks,
+    /// an index that may be out of bounds is checked before the address is computed.
+    fn gen_index_place_expr(&mut self, base: ExprId, index: ExprId) -> PointerValue {
+        let array_ptr = if self.is_place_expr(base) {
+            self.gen_place_expr(base)
+        } else {
+            let array = self.gen_expr(base).expect("expected an array value");
+            let array_ptr = self
+                .new_alloca_builder()
+                .build_alloca(array.get_type(), "array");
+            self.builder.build_store(array_ptr, array);
+            array_ptr
+        };
+
+        // Indices of any integer type are extended to 64 bits
+        let index_type = self.module.get_context().i64_type();
+        let index_value = self
+            .gen_expr(index)
+            .expect("expected an index value")
+            .into_int_value();
+        let index_value = match self.infer[index].as_simple() {
+            Some(TypeCtor::Int(ty)) if ty.bitness != IntBitness::X64 => match ty.signedness {
+                Signedness::Signed => {
+                    self.builder
+                        .build_int_s_extend(index_value, index_type, "index")
+                }
+                Signedness::Unsigned => {
+                    self.builder
+                        .build_int_z_extend(index_value, index_type, "index")
+                }
+            },
+            _ => index_value,
+        };
+
+        if dispatch_table::is_bounds_checked(self.db, &self.body, index) {
+            let (elem_ty, len) = self.infer[base]
+                .as_array()
+                .expect("only arrays can be indexed");
+            // The comparison of a constant index is folded into a constant
+            let len = index_type.const_int(len, false);
+            let in_bounds =
+                self.builder
+                    .build_int_compare(IntPredicate::ULT, index_value, len, "in_bounds");
+            if in_bounds.get_zero_extended_constant() != Some(1) {
+                let elem_ty = try_convert_any_to_basic(self.db.type_ir(elem_ty.clone()))
+                    .expect("expected a basic element type");
+                return self.gen_bounds_checked_gep(
+                    index,
+                    array_ptr,
+                    index_value,
+                    in_bounds,
+                    elem_ty,
+                );
+            }
+        }
+
+        unsafe {
+            self.builder.build_in_bounds_gep(
+                array_ptr,
+                &[index_type.const_zero(), index_value],
+                "elem_ptr",
+            )
+        }
+    }
+
+    /// Generates IR that computes the memory address of the element `index` of the array pointed
+    /// to by `array_ptr` if `in_bounds` is true, and that calls the trap intrinsic otherwise, at
+    /// the location of the index expression `index_expr`. After the trap, the out-of-bounds
+    /// access reads or writes a scratch slot of type `elem_ty` on the stack, rather than memory
+    /// outside of the array.
+    fn gen_bounds_checked_gep(
+        &mut self,
+        index_expr: ExprId,
+        array_ptr: PointerValue,
+        index: IntValue,
+        in_bounds: IntValue,
+        elem_ty: BasicTypeEnum,
+    ) -> PointerValue {
+        let context = self.module.get_context();
+        let current_block = self.builder.get_insert_block().unwrap();
+        let in_bounds_block = context.append_basic_block(&self.fn_value, "in_bounds");
+        let trap_block = context.append_basic_block(&self.fn_value, "index_out_of_bounds_trap");
+        let continue_block = context.append_basic_block(&self.fn_value, "index_checked");
+        in_bounds_block.move_after(&current_block).unwrap();
+        trap_block.move_after(&in_bounds_block).unwrap();
+        continue_block.move_after(&trap_block).unwrap();
+        self.builder
+            .build_conditional_branch(in_bounds, &in_bounds_block, &trap_block);
+
+        self.builder.position_at_end(&in_bounds_block);
+        let index_type = context.i64_type();
+        let elem_ptr = unsafe {
+            self.builder.build_in_bounds_gep(
+                array_ptr,
+                &[index_type.const_zero(), index],
+                "elem_ptr",
+            )
+        };
+        self.builder.build_unconditional_branch(&continue_block);
+
+        self.builder.position_at_end(&trap_block);
+        self.gen_trap(index_expr, TrapKind::IndexOutOfBounds, None);
+        let scratch_ptr = self
+            .new_alloca_builder()
+            .build_alloca(elem_ty, "out_of_bounds_elem");
+        self.builder.build_unconditional_branch(&continue_block);
+
+        self.builder.position_at_end(&continue_block);
+        let phi = self.builder.build_phi(elem_ptr.get_type(), "elem_ptr");
+        phi.add_incoming(&[(&elem_ptr, &in_bounds_block), (&scratch_ptr, &trap_block)]);
+        phi.as_basic_value().into_pointer_value()
+    }
+
+    /// Generates IR for a call of `function` with the arguments `args`.
+    fn gen_call(
+        &mut self,
+        expr: ExprId,
+        function: hir::Function,
+        args: &[ExprId],
+    ) -> Option<BasicValueEnum> {
+        if self.tail_calls.contains(&expr) {
+            return self.gen_tail_call(expr, args);
+        }
+
+        let sig = self.db.fn_signature(function);
+        let (args, return_ptr) = self.gen_call_args(expr, &sig, args);
+        let call = if dispatch_table::is_dispatched(self.db, function) {
+            let ptr_value = self.dispatch_table.gen_function_lookup(
+                self.db,
+                self.module,
+                &self.builder,
+                function,
+            );
+            self.builder
+                .build_call(ptr_value, &args, &function.name(self.db).to_string())
+        } else {
+            // The callee is declared in the module of the caller and defined when the modules of
+            // the file are linked together
+            let llvm_function = self.gen_function_decl(function);
+            self.builder
+                .build_call(llvm_function, &args, &function.name(self.db).to_string())
+        };
+        self.gen_call_result(expr, &sig, call, return_ptr)
+    }
+
+    /// Generates IR for a call through the function pointer that `callee` evaluates to. The callee
+    /// is evaluated before the arguments.
+    fn gen_fn_ptr_call(
+        &mut self,
+        expr: ExprId,
+        callee: ExprId,
+        args: &[ExprId],
+    ) -> Option<BasicValueEnum> {
+        let sig = self.infer[callee]
+            .callable_sig(self.db)
+            .expect("expected a function pointer");
+        let fn_ptr = self
+            .gen_expr(callee)
+            .expect("expected a function pointer")
+            .into_pointer_value();
+        let (args, return_ptr) = self.gen_call_args(expr, &sig, args);
+        let call = self.builder.build_call(fn_ptr, &args, "fn_ptr_call");
+        self.gen_call_result(expr, &sig, call, return_ptr)
+    }
+
+    /// Generates the arguments of the call `expr` to a function with the signature `sig`. Arrays
+    /// are passed by pointer, to the memory of an immutable local binding or otherwise to a copy of
+    /// the array. Zero-sized arguments are evaluated, but not passed. If the function returns its
+    /// value through a pointer, memory for the value is allocated and passed as the first
+    /// argument.
+    fn gen_call_args(
+        &mut self,
+        expr: ExprId,
+        sig: &hir::FnSig,
+        args: &[ExprId],
+    ) -> (Vec<BasicValueEnum>, Option<PointerValue>) {
+        let mut arg_values = Vec::with_capacity(args.len());
+        for (arg, param_idx) in args.iter().zip(param_ir_indices(self.db, sig)) {
+            // The memory of an immutable binding cannot change during the call
+            if param_idx.is_some()
+                && self.infer[*arg].as_array().is_some()
+                && self.is_immutable_local(*arg)
+            {
+                arg_values.push(self.gen_place_expr(*arg).into());
+                continue;
+            }
+            let value = self.gen_expr(*arg);
+            if param_idx.is_none() {
+                continue;
+            }
+            let value = value.expect("expected a value");
+            arg_values.push(if self.infer[*arg].as_array().is_some() {
+                let array_ptr = self
+                    .new_alloca_builder()
+                    .build_alloca(value.get_type(), "array_arg");
+                self.builder.build_store(array_ptr, value);
+                array_ptr.into()
+            } else {
+                value
+            });
+        }
+
+        // A value that is returned through a pointer is stored in memory of the caller
+        let return_ptr = match fn_return_convention(self.db, sig) {
+            ReturnConvention::Direct => None,
+            ReturnConvention::Pointer => {
+                let ret_type = try_convert_any_to_basic(self.db.type_ir(self.infer[expr].clone()))
+                    .expect("expected a basic return type");
+                let return_ptr = self.new_alloca_builder().build_alloca(ret_type, "ret");
+                arg_values.insert(0, return_ptr.into());
+                Some(return_ptr)
+            }
+        };
+        (arg_values, return_ptr)
+    }
+
+    /// Adds the attributes of the signature `sig` to `call` and returns the value of the call
+    /// `expr`, which is loaded from `return_ptr` if it is returned through a pointer.
+    fn gen_call_result(
+        &mut self,
+        expr: ExprId,
+        sig: &hir::FnSig,
+        call: CallSiteValue,
+        return_ptr: Option<PointerValue>,
+    ) -> Option<BasicValueEnum> {
+        for (loc, attribute) in signature_attributes(self.db, sig, self.module) {
+            call.add_attribute(loc, attribute);
+        }
+
+        // A call to a function that never returns diverges
+        if self.infer[expr].is_never() {
+            self.gen_unreachable();
+            None
+        } else if let Some(return_ptr) = return_ptr {
+            Some(self.builder.build_load(return_ptr, "ret"))
+        } else if !self.infer[expr].is_empty() && is_zero_sized(self.db, &self.infer[expr]) {
+            // A zero-sized value is returned as `void`
+            Some(self.gen_zero_sized_value(&self.infer[expr].clone()))
+        } else {
+            call.try_as_basic_value().left()
+        }
+    }
+
+    /// Returns the declaration of `function` in the module, which is added if the module doesn't
+    /// declare the function yet.
+    fn gen_function_decl(&self, function: hir::Function) -> FunctionValue {
+        self.module
+            .get_function(&function_symbol_name(self.db, function))
+            .unwrap_or_else(|| gen_signature(self.db, function, self.module))
+    }
+
+    /// Generates a pointer to the code of `function`, which is the value of a function that is
+    /// coerced to a function pointer. A dispatched function is looked up in the dispatch table, so
+    /// the pointer refers to the code of the function at the time the pointer is taken. It is not
+    /// updated when the function is hot reloaded.
+    fn gen_fn_ptr(&self, function: hir::Function) -> BasicValueEnum {
+        if dispatch_table::is_dispatched(self.db, function) {
+            self.dispatch_table
+                .gen_function_lookup(self.db, self.module, &self.builder, function)
+                .into()
+        } else {
+            self.gen_function_decl(function)
+                .as_global_value()
+                .as_pointer_value()
+                .into()
+        }
+    }
+
+    /// Generates IR for a closure. The body of the closure is generated as a separate function,
+    /// the closure evaluates to a pointer to that function.
+    fn gen_closure(&mut self, expr: ExprId, args: &[PatId], body: ExprId) -> BasicValueEnum {
+        let sig = self.infer[expr]
+            .callable_sig(self.db)
+            .expect("a closure is a function pointer");
+        // Closures are numbered in the order of the body, so their names are stable
+        let index = self
+            .body
+            .exprs()
+            .filter_map(|(id, e)| match e {
+                Expr::Closure { .. } => Some(id),
+                _ => None,
+            })
+            .position(|id| id == expr)
+            .unwrap();
+        let function = gen_closure_signature(self.db, self.hir_function, index, &sig, self.module);
+
+        let mut closure_gen = self.new_closure(expr, function, &sig);
+        closure_gen.gen_body(args, body, &sig);
+
+        function.as_global_value().as_pointer_value().into()
+    }
+
+    /// Generates IR for a call of the function to itself in tail position. Instead of calling the
+    /// function, the arguments are stored in the parameters and the body is started again, so
+    /// the recursion doesn't grow the stack.
+    fn gen_tail_call(&mut self, expr: ExprId, args: &[ExprId]) -> Option<BasicValueEnum> {
+        // All arguments are evaluated before a parameter is assigned, because the arguments can
+        // refer to the parameters
+        let args: Vec<Option<BasicValueEnum>> =
+            args.iter().map(|expr| self.gen_expr(*expr)).collect();
+        let body = self.body.clone();
+        for ((pat, _), value) in body.params().iter().zip(args) {
+            // Wildcard parameters are never read, array parameters point to the copy of the
+            // array that is owned by the function. An argument without a value, e.g. the result
+            // of a call that returns nothing, is zero-sized, so there is nothing to store.
+            if let (Some(ptr), Some(value)) = (self.pat_to_local.get(pat), value) {
+                self.builder.build_store(*ptr, value);
+            }
+        }
+        let tail_call_block = self
+            .tail_call_block
+            .expect("the body starts with a block for tail calls");
+        self.builder.build_unconditional_branch(&tail_call_block);
+
+        // The code after the call is never executed, it is generated in a block without
+        // predecessors that yields an undefined result
+        let context = self.module.get_context();
+        let block = context.append_basic_block(&self.fn_value, "after_tail_call");
+        self.builder.position_at_end(&block);
+        let ty = self.infer[expr].clone();
+        if ty.is_never() {
+            self.gen_unreachable();
+            None
+        } else if ty.is_empty() {
+            None
+        } else {
+            let ty = try_convert_any_to_basic(self.db.type_ir(ty)).expect("expected basic type");
+            Some(match ty {
+                BasicTypeEnum::ArrayType(ty) => ty.get_undef().into(),
+                BasicTypeEnum::FloatType(ty) => ty.get_undef().into(),
+                BasicTypeEnum::IntType(ty) => ty.get_undef().into(),
+                BasicTypeEnum::PointerType(ty) => ty.get_undef().into(),
+                BasicTypeEnum::StructType(ty) => ty.get_undef().into(),
+                BasicTypeEnum::VectorType(ty) => ty.get_undef().into(),
+            })
+        }
+    }
+
+    /// Generates IR for a call to a builtin function, which is generated inline. Functions of
+    /// floats call the LLVM intrinsic for the type of their operands, functions of integers are
+    /// generated as comparisons.
+    fn gen_builtin_call(
+        &mut self,
+        function: BuiltinFunction,
+        args: &[ExprId],
+    ) -> Option<BasicValueEnum> {
+        let operand_ty = self.infer[args[0]].as_simple();
+        let args = args
+            .iter()
+            .map(|arg| self.gen_expr(*arg))
+            .collect::<Option<Vec<_>>>()?;
+
+        match operand_ty {
+            Some(TypeCtor::Float(float_ty)) => {
+                let name = match function {
+                    BuiltinFunction::Sqrt => "sqrt",
+                    BuiltinFunction::Abs => "fabs",
+                    BuiltinFunction::Sin => "sin",
+                    BuiltinFunction::Cos => "cos",
+                    BuiltinFunction::Floor => "floor",
+                    BuiltinFunction::Min => "minnum",
+                    BuiltinFunction::Max => "maxnum",
+                    _ => unreachable!("{:?} is not defined for floats", function),
+                };
+                Some(self.gen_float_intrinsic(name, float_ty.bitness.bits(), &args))
+            }
+            Some(TypeCtor::Int(int_ty)) => {
+                let signed = int_ty.signedness == Signedness::Signed;
+                let lhs = args[0].into_int_value();
+                let value = match function {
+                    BuiltinFunction::Abs if !signed => lhs.into(),
+                    // Negation wraps around, so the smallest value of the type is its own
+                    // absolute value
+                    BuiltinFunction::Abs => {
+                        let zero = lhs.get_type().const_zero();
+                        let is_negative = self.builder.build_int_compare(
+                            IntPredicate::SLT,
+                            lhs,
+                            zero,
+                            "is_negative",
+                        );
+                        let neg = self.builder.build_int_neg(lhs, "neg");
+                        self.builder.build_select(is_negative, neg, lhs, "abs")
+                    }
+                    BuiltinFunction::Min | BuiltinFunction::Max => {
+                        let rhs = args[1].into_int_value();
+                        let (predicate, name) = match (function, signed) {
+                            (BuiltinFunction::Min, true) => (IntPredicate::SLT, "min"),
+                            (BuiltinFunction::Min, false) => (IntPredicate::ULT, "min"),
+                            (_, true) => (IntPredicate::SGT, "max"),
+                            (_, false) => (IntPredicate::UGT, "max"),
+                        };
+                        let cmp = self.builder.build_int_compare(predicate, lhs, rhs, "cmp");
+                        self.builder.build_select(cmp, lhs, rhs, name)
+                    }
+                    // The wrapping operations ignore the overflow checks
+                    BuiltinFunction::WrappingAdd
+                    | BuiltinFunction::WrappingSub
+                    | BuiltinFunction::WrappingMul => {
+                        let op = match function {
+                            BuiltinFunction::WrappingAdd => ArithOp::Add,
+                            BuiltinFunction::WrappingSub => ArithOp::Subtract,
+                            _ => ArithOp::Multiply,
+                        };
+                        let rhs = args[1].into_int_value();
+                        self.gen_unchecked_arith_bin_op_int(lhs, rhs, op, signed)
+                            .into()
+                    }
+                    BuiltinFunction::SaturatingAdd | BuiltinFunction::SaturatingSub => {
+                        let op = if function == BuiltinFunction::SaturatingAdd {
+                            ArithOp::Add
+                        } else {
+                            ArithOp::Subtract
+                        };
+                        let rhs = args[1].into_int_value();
+                        self.gen_saturating_int(lhs, rhs, op, signed).into()
+                    }
+                    _ => unreachable!("{:?} is not defined for integers", function),
+                };
+                Some(value)
+            }
+            _ => unreachable!("builtin functions are only defined for integers and floats"),
+        }
+    }
+
+    /// Generates IR for a call to `assert` or `assert_eq`, which calls the runtime's handler of
+    /// failed assertions with a message that describes the assertion if it fails. Like after other
+    /// traps, execution continues when the handler returns. Without assertions, neither the
+    /// assertion nor its operands are generated.
+    fn gen_assertion(
+        &mut self,
+        expr: ExprId,
+        function: BuiltinFunction,
+        args: &[ExprId],
+    ) -> Option<BasicValueEnum> {
+        if !self.db.assertions() {
+            return Some(self.gen_empty());
+        }
+
+        let holds = match function {
+            BuiltinFunction::Assert => self.gen_condition(args[0])?,
+            _ => match (self.gen_expr(args[0])?, self.gen_expr(args[1])?) {
+                (BasicValueEnum::FloatValue(lhs), BasicValueEnum::FloatValue(rhs)) => self
+                    .builder
+                    .build_float_compare(FloatPredicate::OEQ, lhs, rhs, "eq"),
+                (lhs, rhs) => self.builder.build_int_compare(
+                    IntPredicate::EQ,
+                    lhs.into_int_value(),
+                    rhs.into_int_value(),
+                    "eq",
+                ),
+            },
+        };
+
+        let context = self.module.get_context();
+        let current_block = self.builder.get_insert_block().unwrap();
+        let failed_block = context.append_basic_block(&self.fn_value, "assertion_failed");
+        let continue_block = context.append_basic_block(&self.fn_value, "assertion_holds");
+        failed_block.move_after(&current_block).unwrap();
+        continue_block.move_after(&failed_block).unwrap();
+        self.builder
+            .build_conditional_branch(holds, &continue_block, &failed_block);
+
+        self.builder.position_at_end(&failed_block);
+        let message = self.gen_c_string(&self.assertion_message(function, args));
+        self.gen_trap(expr, TrapKind::AssertionFailed, Some(message));
+        self.builder.build_unconditional_branch(&continue_block);
+
+        self.builder.position_at_end(&continue_block);
+        Some(self.gen_empty())
+    }
+
+    /// Returns the message of a failed assertion, which is the source of the condition that it
+    /// checks, e.g. `hp >= 0`. The runtime reports it with the location of the trap site.
+    fn assertion_message(&self, function: BuiltinFunction, args: &[ExprId]) -> String {
+        let source_map = self.hir_function.body_source_map(self.db);
+        let source = |expr: ExprId| match source_map.expr_syntax(expr) {
+            Some(src) => {
+                let text = self.db.file_text(src.file_id);
+                let range = src.ast.syntax_node_ptr().range();
+                text[range.start().to_usize()..range.end().to_usize()].to_string()
+            }
+            None => String::new(),
+        };
+        match function {
+            BuiltinFunction::Assert => source(args[0]),
+            _ => format!("{} == {}", source(args[0]), source(args[1])),
+        }
+    }
+
+    /// Generates a pointer to a constant nul-terminated copy of `value`, which the runtime reads
+    /// as a C string.
+    fn gen_c_string(&self, value: &str) -> PointerValue {
+        let context = self.module.get_context();
+        let bytes = context.const_string(value, true);
+        let global = self
+            .module
+            .add_global(bytes.as_basic_value_enum().get_type(), None, "cstr");
+        global.set_linkage(Linkage::Private);
+        global.set_constant(true);
+        global.set_unnamed_address(UnnamedAddress::Global);
+        global.set_initializer(&bytes);
+        global
+            .as_pointer_value()
+            .const_cast(context.i8_type().ptr_type(AddressSpace::Generic))
+    }
+
+    /// Generates a call to the LLVM intrinsic `name` for floats of `bits` bits, e.g.
+    /// `double @llvm.sqrt.f64(double)`. Each argument and the result have the same type.
+    fn gen_float_intrinsic(
+        &self,
+        name: &str,
+        bits: u32,
+        args: &[BasicValueEnum],
+    ) -> BasicValueEnum {
+        let float_type = args[0].into_float_value().get_type();
+        let intrinsic_name = format!("llvm.{}.f{}", name, bits);
+        let intrinsic = self
+            .module
+            .get_function(&intrinsic_name)
+            .unwrap_or_else(|| {
+                let params: Vec<BasicTypeEnum> = vec![float_type.into(); args.len()];
+                let fn_type = float_type.fn_type(&params, false);
+                self.module.add_function(&intrinsic_name, fn_type, None)
+            });
+        self.builder
+            .build_call(intrinsic, args, name)
+            .try_as_basic_value()
+            .left()
+            .expect("float intrinsics return a value")
+    }
+
+    /// Generates IR for an if statement.
+    fn gen_if(
         &mut self,
         _expr: ExprId,
         condition: ExprId,
@@ -521,7 +2337,7 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
         else_branch: Option<ExprId>,
     ) -> Option<inkwell::values::BasicValueEnum> {
         // Generate IR for the condition
-        let condition_ir = self.gen_expr(condition)?.into_int_value();
+        let condition_ir = self.gen_condition(condition)?;
 
         // Generate the code blocks to branch to
         let context = self.module.get_context();
@@ -571,17 +2387,130 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
         merge_block.move_after(&current_block).unwrap();
         self.builder.position_at_end(&merge_block);
 
-        // Construct phi block if a value was returned
-        if let Some(then_block_ir) = then_block_ir {
-            if let Some((else_block_ir, else_block)) = else_ir_and_block {
+        // Construct phi block if a value was returned. If one of the branches diverges, the value
+        // of the other branch is the result of the if expression.
+        match (then_block_ir, else_ir_and_block) {
+            (Some(then_block_ir), Some((else_block_ir, else_block))) => {
                 let phi = self.builder.build_phi(then_block_ir.get_type(), "iftmp");
                 phi.add_incoming(&[(&then_block_ir, &then_block), (&else_block_ir, &else_block)]);
                 Some(phi.as_basic_value())
-            } else {
-                Some(then_block_ir)
             }
+            (Some(then_block_ir), None) => Some(then_block_ir),
+            (None, Some((else_block_ir, _))) => Some(else_block_ir),
+            (None, None) => Some(self.gen_empty()),
+        }
+    }
+
+    /// Generates IR for a match expression. The arms are lowered to a `switch` on the value of the
+    /// matched expression, arms that can never be reached are not generated.
+    fn gen_match(
+        &mut self,
+        _expr: ExprId,
+        scrutinee: ExprId,
+        arms: &[MatchArm],
+    ) -> Option<BasicValueEnum> {
+        let scrutinee_ir = self.gen_expr(scrutinee)?;
+
+        // Determine the value that each arm matches, arms after a wildcard and arms that match a
+        // value that is already matched are unreachable
+        let context = self.module.get_context();
+        let mut cases = Vec::new();
+        let mut default_expr = None;
+        let mut covered = HashSet::new();
+        for arm in arms {
+            let value = match &self.body[arm.pat] {
+                Pat::Wild => None,
+                Pat::Lit(lit) => Some(self.gen_expr(*lit)?.into_int_value()),
+                Pat::Path(_) => {
+                    let variant = self
+                        .infer
+                        .variant_resolution(arm.pat)
+                        .expect("unresolved enum variant, are we missing a diagnostic?");
+                    let value = ConstValue::Int(variant.discriminant(self.db));
+                    Some(self.gen_const_value(value, scrutinee).into_int_value())
+                }
+                _ => unreachable!("unsupported match pattern, are we missing a diagnostic?"),
+            };
+            match value {
+                Some(value) => {
+                    if covered.insert(value.get_zero_extended_constant()) {
+                        let block = context.append_basic_block(&self.fn_value, "match_arm");
+                        cases.push((value, block, arm.expr));
+                    }
+                }
+                None => {
+                    default_expr = Some(arm.expr);
+                    break;
+                }
+            }
+        }
+
+        // Without a wildcard the arms are exhaustive, so the default block is never reached
+        let default_block = if default_expr.is_some() {
+            context.append_basic_block(&self.fn_value, "match_arm")
         } else {
-            Some(self.gen_empty())
+            let block = context.append_basic_block(&self.fn_value, "match_unreachable");
+            let current_block = self.builder.get_insert_block().unwrap();
+            self.builder.position_at_end(&block);
+            self.builder.build_unreachable();
+            self.builder.position_at_end(&current_block);
+            block
+        };
+        let merge_block = context.append_basic_block(&self.fn_value, "match_merge");
+
+        // Build the actual branching IR. A scrutinee that is matched by a wildcard only, doesn't
+        // have to be an integer.
+        if cases.is_empty() {
+            self.builder.build_unconditional_branch(&default_block);
+        } else {
+            let switch_cases: Vec<_> = cases
+                .iter()
+                .map(|(value, block, _)| (*value, block))
+                .collect();
+            self.builder
+                .build_switch(scrutinee_ir.into_int_value(), &default_block, &switch_cases);
+        }
+
+        // Fill the blocks of the arms
+        let mut incoming = Vec::new();
+        let arm_blocks = cases
+            .iter()
+            .map(|(_, block, expr)| (block, *expr))
+            .chain(default_expr.map(|expr| (&default_block, expr)));
+        for (block, arm_expr) in arm_blocks {
+            let current_block = self.builder.get_insert_block().unwrap();
+            block
+                .move_after(&current_block)
+                .expect("programmer error, current block is invalid");
+            self.builder.position_at_end(block);
+            let value = self.gen_expr(arm_expr);
+            if !self.infer[arm_expr].is_never() {
+                self.builder.build_unconditional_branch(&merge_block);
+                if let Some(value) = value {
+                    incoming.push((value, self.builder.get_insert_block().unwrap()));
+                }
+            }
+        }
+
+        // Create merge block
+        let current_block = self.builder.get_insert_block().unwrap();
+        merge_block.move_after(&current_block).unwrap();
+        if default_expr.is_none() {
+            default_block.move_after(&merge_block).unwrap();
+        }
+        self.builder.position_at_end(&merge_block);
+
+        // Construct phi block if a value was returned. Arms that diverge don't contribute a value.
+        match incoming.len() {
+            0 => Some(self.gen_empty()),
+            1 => Some(incoming[0].0),
+            _ => {
+                let phi = self.builder.build_phi(incoming[0].0.get_type(), "matchtmp");
+                for (ref value, ref block) in incoming {
+                    phi.add_incoming(&[(value, block)]);
+                }
+                Some(phi.as_basic_value())
+            }
         }
     }
 
@@ -589,15 +2518,30 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
         let ret_value = ret_expr.and_then(|expr| self.gen_expr(expr));
 
         // Construct a return statement from the returned value of the body
-        if let Some(value) = ret_value {
-            self.builder.build_return(Some(&value));
-        } else {
-            self.builder.build_return(None);
-        }
+        self.gen_return_value(ret_value);
 
         None
     }
 
+    /// Generates a return of `value` from the function. A value that is returned through a
+    /// pointer is stored in the memory of the caller instead, and a zero-sized value is returned
+    /// as `void`.
+    fn gen_return_value(&mut self, value: Option<BasicValueEnum>) {
+        let returns_void = self.fn_value.get_type().get_return_type().is_none();
+        match (value, self.return_ptr) {
+            (Some(value), Some(return_ptr)) => {
+                self.builder.build_store(return_ptr, value);
+                self.builder.build_return(None);
+            }
+            (Some(value), None) if !returns_void => {
+                self.builder.build_return(Some(&value));
+            }
+            _ => {
+                self.builder.build_return(None);
+            }
+        }
+    }
+
     fn gen_break(&mut self, _expr: ExprId, break_expr: Option<ExprId>) -> Option<BasicValueEnum> {
         let break_value = break_expr.and_then(|expr| self.gen_expr(expr));
         let loop_info = self.active_loop.as_mut().unwrap();
@@ -611,11 +2555,20 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
         None
     }
 
+    fn gen_continue(&mut self, _expr: ExprId) -> Option<BasicValueEnum> {
+        let loop_info = self.active_loop.as_ref().unwrap();
+        self.builder
+            .build_unconditional_branch(&loop_info.continue_block);
+        None
+    }
+
     fn gen_loop_block_expr(
         &mut self,
         block: ExprId,
         exit_block: BasicBlock,
+        continue_block: BasicBlock,
     ) -> (
+        BasicBlock,
         BasicBlock,
         Vec<(BasicValueEnum, BasicBlock)>,
         Option<BasicValueEnum>,
@@ -623,6 +2576,7 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
         // Build a new loop info struct
         let loop_info = LoopInfo {
             exit_block,
+            continue_block,
             break_values: Vec::new(),
         };
 
@@ -634,10 +2588,11 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
 
         let LoopInfo {
             exit_block,
+            continue_block,
             break_values,
         } = std::mem::replace(&mut self.active_loop, prev_loop).unwrap();
 
-        (exit_block, break_values, value)
+        (exit_block, continue_block, break_values, value)
     }
 
     fn gen_while(
@@ -656,13 +2611,10 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
 
         // Generate condition block
         self.builder.position_at_end(&cond_block);
-        let condition_ir = self.gen_expr(condition_expr);
+        let condition_ir = self.gen_condition(condition_expr);
         if let Some(condition_ir) = condition_ir {
-            self.builder.build_conditional_branch(
-                condition_ir.into_int_value(),
-                &loop_block,
-                &exit_block,
-            );
+            self.builder
+                .build_conditional_branch(condition_ir, &loop_block, &exit_block);
         } else {
             // If the condition doesn't return a value, we also immediately return without a value.
             // This can happen if the expression is a `never` expression.
@@ -671,7 +2623,8 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
 
         // Generate loop block
         self.builder.position_at_end(&loop_block);
-        let (exit_block, _, value) = self.gen_loop_block_expr(body_expr, exit_block);
+        let (exit_block, cond_block, _, value) =
+            self.gen_loop_block_expr(body_expr, exit_block, cond_block);
         if value.is_some() {
             self.builder.build_unconditional_branch(&cond_block);
         }
@@ -682,7 +2635,104 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
         Some(self.gen_empty())
     }
 
-    fn gen_loop(&mut self, _expr: ExprId, body_expr: ExprId) -> Option<BasicValueEnum> {
+    fn gen_for(
+        &mut self,
+        _expr: ExprId,
+        pat: PatId,
+        start_expr: ExprId,
+        end_expr: ExprId,
+        inclusive: bool,
+        body_expr: ExprId,
+    ) -> Option<BasicValueEnum> {
+        let signed = match self.infer[pat].as_simple() {
+            Some(TypeCtor::Int(ty)) => ty.signedness == Signedness::Signed,
+            _ => unreachable!("expected an integer type for the loop variable"),
+        };
+
+        // The bounds of the range are only evaluated once
+        let start = self.gen_expr(start_expr)?.into_int_value();
+        let end = self.gen_expr(end_expr)?.into_int_value();
+
+        // The induction variable is kept separate from the loop variable so that assignments to
+        // the loop variable in the body don't affect the number of iterations.
+        let builder = self.new_alloca_builder();
+        let index_ptr = builder.build_alloca(start.get_type(), "index");
+        let pat_ptr = match &self.body[pat] {
+            Pat::Bind { name, .. } => {
+                let ptr = builder.build_alloca(start.get_type(), &name.to_string());
+                self.pat_to_local.insert(pat, ptr);
+                self.pat_to_name.insert(pat, name.to_string());
+                Some(ptr)
+            }
+            _ => None,
+        };
+        self.builder.build_store(index_ptr, start);
+
+        let context = self.module.get_context();
+        let loop_block = context.append_basic_block(&self.fn_value, "for");
+        let step_block = context.append_basic_block(&self.fn_value, "forstep");
+        let exit_block = context.append_basic_block(&self.fn_value, "afterfor");
+
+        // Skip the loop entirely if the range is empty
+        let (less, less_equal) = if signed {
+            (IntPredicate::SLT, IntPredicate::SLE)
+        } else {
+            (IntPredicate::ULT, IntPredicate::ULE)
+        };
+        let not_empty = self.builder.build_int_compare(
+            if inclusive { less_equal } else { less },
+            start,
+            end,
+            "notempty",
+        );
+        self.builder
+            .build_conditional_branch(not_empty, &loop_block, &exit_block);
+
+        // Generate the body of the loop, `continue` jumps to the step block
+        self.builder.position_at_end(&loop_block);
+        if let Some(pat_ptr) = pat_ptr {
+            let index = self.builder.build_load(index_ptr, "index");
+            self.builder.build_store(pat_ptr, index);
+        }
+        let (exit_block, step_block, _, value) =
+            self.gen_loop_block_expr(body_expr, exit_block, step_block);
+        if value.is_some() {
+            self.builder.build_unconditional_branch(&step_block);
+        }
+
+        // Advance the induction variable
+        self.builder.position_at_end(&step_block);
+        let index = self.builder.build_load(index_ptr, "index").into_int_value();
+        let one = index.get_type().const_int(1, false);
+        if inclusive {
+            // The upper bound is checked before incrementing, which prevents overflowing when the
+            // range ends at the maximum value of the type.
+            let next_block = context.append_basic_block(&self.fn_value, "fornext");
+            let done = self
+                .builder
+                .build_int_compare(IntPredicate::EQ, index, end, "done");
+            self.builder
+                .build_conditional_branch(done, &exit_block, &next_block);
+
+            self.builder.position_at_end(&next_block);
+            let next = self.builder.build_int_add(index, one, "next");
+            self.builder.build_store(index_ptr, next);
+            self.builder.build_unconditional_branch(&loop_block);
+        } else {
+            let next = self.builder.build_int_add(index, one, "next");
+            self.builder.build_store(index_ptr, next);
+            let more = self.builder.build_int_compare(less, next, end, "more");
+            self.builder
+                .build_conditional_branch(more, &loop_block, &exit_block);
+        }
+
+        // Generate the exit block
+        self.builder.position_at_end(&exit_block);
+
+        Some(self.gen_empty())
+    }
+
+    fn gen_loop(&mut self, expr: ExprId, body_expr: ExprId) -> Option<BasicValueEnum> {
         let context = self.module.get_context();
         let loop_block = context.append_basic_block(&self.fn_value, "loop");
         let exit_block = context.append_basic_block(&self.fn_value, "exit");
@@ -692,7 +2742,8 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
 
         // Generate the body of the loop
         self.builder.position_at_end(&loop_block);
-        let (exit_block, break_values, value) = self.gen_loop_block_expr(body_expr, exit_block);
+        let (exit_block, loop_block, break_values, value) =
+            self.gen_loop_block_expr(body_expr, exit_block, loop_block);
         if value.is_some() {
             self.builder.build_unconditional_branch(&loop_block);
         }
@@ -707,8 +2758,68 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
                 phi.add_incoming(&[(value, block)])
             }
             Some(phi.as_basic_value())
-        } else {
+        } else if self.infer[expr].is_never() {
+            // The loop is only exited by returning from the function
             None
+        } else {
+            // The loop is exited by a `break` without a value
+            Some(self.gen_empty())
+        }
+    }
+
+    /// Returns the file and the zero-based line and column at which `expr` starts, or `None` if the
+    /// expression has no source, e.g. because it was desugared.
+    fn expr_location(&self, expr: ExprId) -> Option<(hir::FileId, LineCol)> {
+        let src = self
+            .hir_function
+            .body_source_map(self.db)
+            .expr_syntax(expr)?;
+        let start = src.ast.syntax_node_ptr().range().start();
+        Some((src.file_id, self.db.line_index(src.file_id).line_col(start)))
+    }
+
+    /// Attaches the location of `expr` to the instructions that are generated from now on, if
+    /// debug info is generated. Returns the location that applied before.
+    fn set_debug_location(&mut self, expr: ExprId) -> Option<LineCol> {
+        self.debug_scope?;
+        let (_, location) = self.expr_location(expr)?;
+        self.attach_debug_location();
+        std::mem::replace(&mut self.debug_location, Some(location))
+    }
+
+    /// Attaches `location`, that `set_debug_location` returned, to the instructions that are
+    /// generated from now on.
+    fn restore_debug_location(&mut self, location: Option<LineCol>) {
+        if location.is_some() {
+            self.attach_debug_location();
+            self.debug_location = location;
+        }
+    }
+
+    /// Attaches the current debug location to the instructions that were generated since the
+    /// location last changed.
+    fn attach_debug_location(&self) {
+        if let (Some(scope), Some(location)) = (self.debug_scope, self.debug_location) {
+            scope.set_location(self.fn_value, location);
         }
     }
 }
+
+/// Returns the name of the `index`th element of a tuple in the IR. Purely numeric names would be
+/// confused with the numbering of unnamed values.
+fn tuple_field_name(index: usize) -> String {
+    format!("elem_{}", index)
+}
+
+/// Returns the parameter of `function`, which has the signature `sig`, that points to the memory of
+/// the caller in which the return value is stored, if the value is returned through a pointer.
+fn return_ptr_param(
+    db: &impl IrDatabase,
+    sig: &hir::FnSig,
+    function: FunctionValue,
+) -> Option<PointerValue> {
+    match fn_return_convention(db, sig) {
+        ReturnConvention::Direct => None,
+        ReturnConvention::Pointer => Some(function.get_nth_param(0).unwrap().into_pointer_value()),
+    }
+}
@@ -0,0 +1,254 @@
+//! Generates DWARF debug information, so a native debugger can map the machine code of a function
+//! to its source.
+//!
+//! The pinned revision of inkwell doesn't wrap LLVM's `DIBuilder`, so its C API is called through
+//! `llvm-sys`, the bindings that inkwell itself is built on. Inkwell only exposes the raw
+//! references of values, which is why the module is found through its functions and why locations
+//! are attached to the generated instructions, rather than set on the builder that generates them.
+
+use crate::IrDatabase;
+use inkwell::basic_block::BasicBlock;
+use inkwell::module::Module;
+use inkwell::values::{AsValueRef, FunctionValue};
+use inkwell::OptimizationLevel;
+use llvm_sys::core::{
+    LLVMAddModuleFlag, LLVMConstInt, LLVMGetGlobalParent, LLVMGetMDKindIDInContext,
+    LLVMGetMetadata, LLVMGetModuleContext, LLVMGetModuleFlag, LLVMInt32TypeInContext,
+    LLVMMetadataAsValue, LLVMSetMetadata, LLVMValueAsMetadata,
+};
+use llvm_sys::debuginfo::{
+    LLVMCreateDIBuilder, LLVMDIBuilderCreateCompileUnit, LLVMDIBuilderCreateDebugLocation,
+    LLVMDIBuilderCreateFile, LLVMDIBuilderCreateFunction, LLVMDIBuilderCreateSubroutineType,
+    LLVMDIBuilderFinalize, LLVMDIFlagZero, LLVMDWARFEmissionKind, LLVMDWARFSourceLanguage,
+    LLVMDebugMetadataVersion, LLVMDisposeDIBuilder, LLVMSetSubprogram,
+};
+use llvm_sys::prelude::{
+    LLVMContextRef, LLVMDIBuilderRef, LLVMMetadataRef, LLVMModuleRef, LLVMValueRef,
+};
+use llvm_sys::LLVMModuleFlagBehavior;
+use mun_hir::line_index::LineCol;
+use mun_hir::FileId;
+use std::ptr;
+
+/// The name of the compiler that is recorded in the compile unit.
+const PRODUCER: &str = "mun";
+
+/// The DWARF version of the generated debug information.
+const DWARF_VERSION: u64 = 4;
+
+/// Builds the debug information of a source file in a module, which is a single compile unit for
+/// the file and a subprogram for every function in it. A module that contains the code of several
+/// files, e.g. of a package, has a compile unit for every file.
+pub(crate) struct DebugInfoBuilder {
+    builder: LLVMDIBuilderRef,
+    context: LLVMContextRef,
+    file: LLVMMetadataRef,
+    compile_unit: LLVMMetadataRef,
+    dbg_kind_id: u32,
+    is_optimized: bool,
+}
+
+/// The subprogram of a function, to which the locations of its instructions refer.
+#[derive(Clone, Copy)]
+pub(crate) struct DebugScope<'a> {
+    debug_info: &'a DebugInfoBuilder,
+    subprogram: LLVMMetadataRef,
+}
+
+impl DebugInfoBuilder {
+    /// Constructs a builder for the debug information of `module`, which contains the code of the
+    /// file `file_id`. Returns `None` if the module doesn't contain any functions to describe.
+    pub fn new(db: &impl IrDatabase, module: &Module, file_id: FileId) -> Option<Self> {
+        let function = module.get_first_function()?;
+        let module_ref = unsafe { LLVMGetGlobalParent(function.as_value_ref()) };
+        let is_optimized = db.optimization_lvl() != OptimizationLevel::None;
+        let path = db.file_relative_path(file_id);
+        unsafe {
+            let context = LLVMGetModuleContext(module_ref);
+            add_module_flag(
+                module_ref,
+                context,
+                "Debug Info Version",
+                u64::from(LLVMDebugMetadataVersion()),
+            );
+            if db.target().options.is_like_windows {
+                add_module_flag(module_ref, context, "CodeView", 1);
+            } else {
+                add_module_flag(module_ref, context, "Dwarf Version", DWARF_VERSION);
+            }
+
+            let builder = LLVMCreateDIBuilder(module_ref);
+            let file = LLVMDIBuilderCreateFile(
+                builder,
+                path.as_str().as_ptr() as *const _,
+                path.as_str().len(),
+                ptr::null(),
+                0,
+            );
+            // DWARF doesn't define a language for Mun, C is the closest language that debuggers
+            // know how to present
+            let compile_unit = LLVMDIBuilderCreateCompileUnit(
+                builder,
+                LLVMDWARFSourceLanguage::LLVMDWARFSourceLanguageC,
+                file,
+                PRODUCER.as_ptr() as *const _,
+                PRODUCER.len(),
+                is_optimized as i32,
+                ptr::null(),
+                0,
+                0,
+                ptr::null(),
+                0,
+                LLVMDWARFEmissionKind::LLVMDWARFEmissionKindFull,
+                0,
+                0,
+                0,
+            );
+            let dbg_kind_id = LLVMGetMDKindIDInContext(context, "dbg".as_ptr() as *const _, 3);
+
+            Some(DebugInfoBuilder {
+                builder,
+                context,
+                file,
+                compile_unit,
+                dbg_kind_id,
+                is_optimized,
+            })
+        }
+    }
+
+    /// Attaches a subprogram to `function`, for the function `name` that is defined on the
+    /// zero-based `line`.
+    ///
+    /// The subprogram doesn't describe the types of the parameters, nor the local variables of the
+    /// function, so debuggers only know the locations of its instructions.
+    pub fn gen_subprogram(&self, function: FunctionValue, name: &str, line: u32) -> DebugScope {
+        let linkage_name = function.get_name().to_string_lossy().into_owned();
+        unsafe {
+            let subroutine_type = LLVMDIBuilderCreateSubroutineType(
+                self.builder,
+                self.file,
+                ptr::null_mut(),
+                0,
+                LLVMDIFlagZero,
+            );
+            let subprogram = LLVMDIBuilderCreateFunction(
+                self.builder,
+                self.compile_unit,
+                name.as_ptr() as *const _,
+                name.len(),
+                linkage_name.as_ptr() as *const _,
+                linkage_name.len(),
+                self.file,
+                line + 1,
+                subroutine_type,
+                0,
+                1,
+                line + 1,
+                LLVMDIFlagZero,
+                self.is_optimized as i32,
+            );
+            LLVMSetSubprogram(function.as_value_ref(), subprogram);
+            DebugScope {
+                debug_info: self,
+                subprogram,
+            }
+        }
+    }
+
+    /// Resolves the metadata of the module. This must be called before the module is optimized or
+    /// compiled.
+    pub fn finalize(self) {
+        unsafe { LLVMDIBuilderFinalize(self.builder) };
+    }
+}
+
+impl Drop for DebugInfoBuilder {
+    fn drop(&mut self) {
+        unsafe { LLVMDisposeDIBuilder(self.builder) };
+    }
+}
+
+impl<'a> DebugScope<'a> {
+    /// Attaches a subprogram to `function` in the compile unit of the scope, e.g. for a closure in
+    /// the function of the scope.
+    pub fn gen_subprogram(&self, function: FunctionValue, name: &str, line: u32) -> DebugScope<'a> {
+        self.debug_info.gen_subprogram(function, name, line)
+    }
+
+    /// Attaches the zero-based `location` to the instructions of `function` that don't have a
+    /// location yet, i.e. the instructions that were generated since locations were last attached.
+    pub fn set_location(&self, function: FunctionValue, location: LineCol) {
+        let location = self.location_value(location);
+
+        // Instructions are appended to the end of their block, so the instructions without a
+        // location are found by walking back from the end of each block. Allocas that are
+        // inserted at the start of the entry block may be skipped, which is harmless.
+        let mut block = function.get_first_basic_block();
+        while let Some(current_block) = block {
+            let mut instruction = current_block.get_last_instruction();
+            while let Some(current_instruction) = instruction {
+                if !self.attach(current_instruction.as_value_ref(), location) {
+                    break;
+                }
+                instruction = current_instruction.get_previous_instruction();
+            }
+            block = current_block.get_next_basic_block();
+        }
+    }
+
+    /// Attaches the zero-based `location` to all instructions of `block` that don't have a location
+    /// yet, e.g. instructions that were inserted before instructions that already have one.
+    pub fn set_block_location(&self, block: BasicBlock, location: LineCol) {
+        let location = self.location_value(location);
+        let mut instruction = block.get_first_instruction();
+        while let Some(current_instruction) = instruction {
+            self.attach(current_instruction.as_value_ref(), location);
+            instruction = current_instruction.get_next_instruction();
+        }
+    }
+
+    /// Returns the zero-based `location` in the subprogram of the scope, as a metadata value.
+    fn location_value(&self, location: LineCol) -> LLVMValueRef {
+        let context = self.debug_info.context;
+        unsafe {
+            let location = LLVMDIBuilderCreateDebugLocation(
+                context,
+                location.line + 1,
+                location.col + 1,
+                self.subprogram,
+                ptr::null_mut(),
+            );
+            LLVMMetadataAsValue(context, location)
+        }
+    }
+
+    /// Attaches `location` to `instruction`, unless it already has a location. Returns whether the
+    /// location was attached.
+    fn attach(&self, instruction: LLVMValueRef, location: LLVMValueRef) -> bool {
+        let dbg_kind_id = self.debug_info.dbg_kind_id;
+        unsafe {
+            if !LLVMGetMetadata(instruction, dbg_kind_id).is_null() {
+                return false;
+            }
+            LLVMSetMetadata(instruction, dbg_kind_id, location);
+        }
+        true
+    }
+}
+
+/// Adds the integer flag `key` to `module`, unless the compile unit of another file already added
+/// it. Linking modules with different values of the flag reports a warning.
+unsafe fn add_module_flag(module: LLVMModuleRef, context: LLVMContextRef, key: &str, value: u64) {
+    if !LLVMGetModuleFlag(module, key.as_ptr() as *const _, key.len()).is_null() {
+        return;
+    }
+    let value = LLVMConstInt(LLVMInt32TypeInContext(context), value, 0);
+    LLVMAddModuleFlag(
+        module,
+        LLVMModuleFlagBehavior::LLVMModuleFlagBehaviorWarning,
+        key.as_ptr() as *const _,
+        key.len(),
+        LLVMValueAsMetadata(value),
+    );
+}
@@ -1,11 +1,18 @@
+use crate::diagnostics::{check_fn_body, check_fn_signature};
+use crate::ir::intrinsics::Intrinsic;
+use crate::ir::module::package_files;
 use crate::values::FunctionValue;
 use crate::IrDatabase;
 use inkwell::module::Module;
-use inkwell::types::BasicTypeEnum;
+use inkwell::types::{BasicTypeEnum, StructType};
 use inkwell::values::{BasicValueEnum, PointerValue};
 use mun_hir as hir;
-use mun_hir::{Body, Expr, ExprId, InferenceResult};
+use mun_hir::{
+    ArithOp, BinaryOp, Body, BuiltinFunction, Expr, ExprId, FileId, InferenceResult, Literal,
+    ModuleDef, Resolution, StructMemoryKind, Ty, TypeCtor,
+};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// A dispatch table in IR is a struct that contains pointers to all functions that are called from
 /// code. In C terms it looks something like this:
@@ -21,17 +28,104 @@ use std::collections::HashMap;
 /// functions. This basically enables all hot reloading within Mun.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct DispatchTable {
+    // The file whose functions call the functions in the table
+    file_id: FileId,
     // This contains the function that map to the DispatchTable struct fields
-    function_to_idx: HashMap<hir::Function, usize>,
+    function_to_idx: HashMap<DispatchableFunction, usize>,
     // This contains an ordered list of all the function in the dispatch table
-    entries: Vec<hir::Function>,
-    // Contains a reference to the global value containing the DispatchTable
-    table_ref: Option<inkwell::values::GlobalValue>,
+    entries: Vec<DispatchableFunction>,
+}
+
+/// The name of the global that holds the dispatch table.
+pub(crate) const DISPATCH_TABLE_NAME: &str = "dispatchTable";
+
+/// Determines how calls from one Mun function to another Mun function in the same assembly are
+/// generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CallMode {
+    /// All calls go through the dispatch table. Swapping the implementation of a function updates
+    /// all of its callers, including recursive calls, at the cost of an indirect call.
+    Dispatch,
+    /// Calls to functions that are defined in the same assembly are direct calls. Only extern
+    /// functions and intrinsics are called through the dispatch table.
+    Direct,
+}
+
+impl Default for CallMode {
+    fn default() -> Self {
+        CallMode::Dispatch
+    }
+}
+
+/// Returns true if calls to `function` are resolved through the dispatch table, rather than
+/// directly calling its definition.
+pub(crate) fn is_dispatched<D: IrDatabase>(db: &D, function: hir::Function) -> bool {
+    // Extern functions have no definition in the assembly, so they are always resolved by the
+    // runtime
+    function.is_extern(db) || db.call_mode() == CallMode::Dispatch
+}
+
+/// Returns true if the binary operation `op` with a left-hand side of type `ty` checks for
+/// overflow or a zero divisor, and calls the trap intrinsic if the check fails.
+pub(crate) fn is_trap_checked<D: IrDatabase>(db: &D, op: BinaryOp, ty: &Ty) -> bool {
+    let op = match op {
+        BinaryOp::ArithOp(op) | BinaryOp::Assignment { op: Some(op) } => op,
+        _ => return false,
+    };
+    match (op, ty.as_simple()) {
+        (ArithOp::Add, Some(TypeCtor::Int(_)))
+        | (ArithOp::Subtract, Some(TypeCtor::Int(_)))
+        | (ArithOp::Multiply, Some(TypeCtor::Int(_))) => db.overflow_checks(),
+        (ArithOp::Divide, Some(TypeCtor::Int(_)))
+        | (ArithOp::Remainder, Some(TypeCtor::Int(_))) => db.division_checks(),
+        _ => false,
+    }
+}
+
+/// Returns true if indexing an array with the expression `index` checks that the index is in
+/// bounds, and calls the trap intrinsic if it isn't. Literal indices are never checked, because
+/// the type checker already rejects literals that are out of bounds.
+pub(crate) fn is_bounds_checked<D: IrDatabase>(db: &D, body: &Body, index: ExprId) -> bool {
+    match &body[index] {
+        Expr::Literal(Literal::Int(_)) => false,
+        _ => db.bounds_checks(),
+    }
+}
+
+/// Returns true if the callee `callee` is an assertion that is generated, which calls the
+/// intrinsic that reports a failed assertion.
+pub(crate) fn is_assertion_generated<D: IrDatabase>(
+    db: &D,
+    infer: &InferenceResult,
+    callee: ExprId,
+) -> bool {
+    let is_assertion = infer[callee]
+        .as_builtin_function()
+        .map_or(false, BuiltinFunction::is_assertion);
+    is_assertion && db.assertions()
+}
+
+/// A function that can be called through the dispatch table: either a Mun function or an
+/// intrinsic that is provided by the runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DispatchableFunction {
+    Function(hir::Function),
+    Intrinsic(Intrinsic),
+}
+
+impl DispatchableFunction {
+    /// Returns the name of the function, by which the runtime links it.
+    pub fn name<D: IrDatabase>(self, db: &D) -> String {
+        match self {
+            DispatchableFunction::Function(f) => f.qualified_name(db),
+            DispatchableFunction::Intrinsic(intrinsic) => intrinsic.name().to_string(),
+        }
+    }
 }
 
 impl DispatchTable {
     /// Returns a slice containing all the functions in the dispatch table.
-    pub fn entries(&self) -> &[hir::Function] {
+    pub fn entries(&self) -> &[DispatchableFunction] {
         &self.entries
     }
 
@@ -41,10 +135,42 @@ impl DispatchTable {
     pub fn gen_function_lookup<D: IrDatabase>(
         &self,
         db: &D,
+        module: &Module,
         builder: &inkwell::builder::Builder,
         function: hir::Function,
     ) -> PointerValue {
-        let function_name = function.name(db).to_string();
+        self.gen_lookup(
+            db,
+            module,
+            builder,
+            DispatchableFunction::Function(function),
+        )
+    }
+
+    /// Generate a lookup of an intrinsic through the DispatchTable, like `gen_function_lookup`.
+    pub fn gen_intrinsic_lookup<D: IrDatabase>(
+        &self,
+        db: &D,
+        module: &Module,
+        builder: &inkwell::builder::Builder,
+        intrinsic: Intrinsic,
+    ) -> PointerValue {
+        self.gen_lookup(
+            db,
+            module,
+            builder,
+            DispatchableFunction::Intrinsic(intrinsic),
+        )
+    }
+
+    fn gen_lookup<D: IrDatabase>(
+        &self,
+        db: &D,
+        module: &Module,
+        builder: &inkwell::builder::Builder,
+        function: DispatchableFunction,
+    ) -> PointerValue {
+        let function_name = function.name(db);
 
         // Get the index of the function
         let index = *self
@@ -52,8 +178,15 @@ impl DispatchTable {
             .get(&function)
             .expect("unknown function");
 
-        // Get the internal table reference
-        let table_ref = self.table_ref.expect("no dispatch table defined");
+        // Functions are generated in separate modules, the table is declared in every module that
+        // uses it and defined when the modules are linked together
+        let table_ref = module.get_global(DISPATCH_TABLE_NAME).unwrap_or_else(|| {
+            module.add_global(
+                db.dispatch_table_type(self.file_id),
+                None,
+                DISPATCH_TABLE_NAME,
+            )
+        });
 
         // Create an expression that finds the associated field in the table and returns this as a pointer access
         let ptr_to_function_ptr = unsafe {
@@ -69,131 +202,223 @@ impl DispatchTable {
             .into_pointer_value()
     }
 
-    /// Returns the value that represents the dispatch table in IR or `None` if no table was
-    /// generated.
-    pub fn global_value(&self) -> Option<&inkwell::values::GlobalValue> {
-        self.table_ref.as_ref()
+    /// Sets the initial value of the dispatch table global in `module`, which contains the
+    /// pointers to the functions that are defined in the module. The pointers to extern functions
+    /// and intrinsics are filled in by the runtime.
+    /// # Parameters
+    /// * **functions**: Mapping of *defined* Mun functions to their respective IR values.
+    pub(crate) fn gen_initializer<D: IrDatabase>(
+        &self,
+        db: &D,
+        module: &Module,
+        functions: &HashMap<mun_hir::Function, FunctionValue>,
+    ) {
+        if self.entries.is_empty() {
+            return;
+        }
+
+        let table_type = db.dispatch_table_type(self.file_id);
+        let table_ref = module
+            .get_global(DISPATCH_TABLE_NAME)
+            .unwrap_or_else(|| module.add_global(table_type, None, DISPATCH_TABLE_NAME));
+
+        let values: Vec<BasicValueEnum> = self
+            .entries
+            .iter()
+            .zip(table_type.get_field_types())
+            .map(|(f, field_type)| {
+                // Find the associated IR function if it exists
+                let function_value = match f {
+                    DispatchableFunction::Function(f) => functions.get(f),
+                    DispatchableFunction::Intrinsic(_) => None,
+                };
+                match function_value {
+                    // Case external function: Convert to typed null for the given function
+                    None => field_type.into_pointer_type().const_null(),
+                    // Case mun function: Get the function location as the initializer
+                    Some(function_value) => function_value.as_global_value().as_pointer_value(),
+                }
+                .into()
+            })
+            .collect();
+        // Set the initialize for the global value
+        table_ref.set_initializer(&table_type.const_named_struct(&values));
+    }
+}
+
+/// Builds the dispatch table of the file from the bodies of its functions, which for the root file
+/// of a package includes the functions of all its modules. The table only depends on the HIR, so
+/// it is unchanged when a body changes without changing the functions it calls.
+pub(crate) fn dispatch_table_query(db: &impl IrDatabase, file_id: FileId) -> Arc<DispatchTable> {
+    let mut builder = DispatchTableBuilder::new(db, file_id);
+    let reachable_functions = db.reachable_functions(file_id);
+    let definitions = package_files(db, file_id)
+        .into_iter()
+        .flat_map(|file_id| db.module_data(file_id).definitions().to_vec());
+    for def in definitions {
+        if let ModuleDef::Function(f) = &def {
+            // Only the bodies that are generated can call functions
+            if !f.is_extern(db)
+                && reachable_functions.contains(f)
+                && check_fn_signature(db, *f).is_ok()
+                && check_fn_body(db, *f).is_ok()
+            {
+                let body = f.body(db);
+                let infer = f.infer(db);
+                builder.collect_body(&body, &infer);
+            }
+        }
     }
+    Arc::new(builder.finalize())
+}
+
+/// Returns the IR type of the dispatch table of the file. There is a single type per file, so the
+/// declarations of the table in the modules of its functions can be linked together.
+pub(crate) fn dispatch_table_type_query(db: &impl IrDatabase, file_id: FileId) -> StructType {
+    let context = db.context();
+    let table_body: Vec<BasicTypeEnum> = db
+        .dispatch_table(file_id)
+        .entries()
+        .iter()
+        .map(|f| {
+            let function_type = match f {
+                // This returns the associated IR type declaration
+                DispatchableFunction::Function(f) => db.type_ir(f.ty(db)).into_function_type(),
+                DispatchableFunction::Intrinsic(intrinsic) => intrinsic.ir_type(&context),
+            };
+            // This converts it into a function pointer type
+            function_type
+                .ptr_type(inkwell::AddressSpace::Generic)
+                .into()
+        })
+        .collect();
+
+    // We can fill in the DispatchTable body, i.e: struct DispatchTable { <this part> };
+    let table_type = context.opaque_struct_type("DispatchTable");
+    table_type.set_body(&table_body, false);
+    table_type
 }
 
 /// A struct that can be used to build the dispatch table from HIR.
-pub(crate) struct DispatchTableBuilder<'a, D: IrDatabase> {
+struct DispatchTableBuilder<'a, D: IrDatabase> {
     db: &'a D,
-    module: &'a Module,
+    file_id: FileId,
     // This contains the functions that map to the DispatchTable struct fields
-    function_to_idx: HashMap<hir::Function, usize>,
+    function_to_idx: HashMap<DispatchableFunction, usize>,
     // These are *all* called functions in the modules
-    entries: Vec<hir::Function>,
-    // Contains a reference to the global value containing the DispatchTable
-    table_ref: Option<inkwell::values::GlobalValue>,
-    // This is the actual DispatchTable type
-    table_type: inkwell::types::StructType,
+    entries: Vec<DispatchableFunction>,
 }
 
 impl<'a, D: IrDatabase> DispatchTableBuilder<'a, D> {
-    /// Creates a new builder that can generate a dispatch function.
-    pub fn new(db: &'a D, module: &'a Module) -> Self {
+    /// Creates a new builder that can collect the dispatch table of a file.
+    fn new(db: &'a D, file_id: FileId) -> Self {
         DispatchTableBuilder {
             db,
-            module,
+            file_id,
             function_to_idx: Default::default(),
             entries: Default::default(),
-            table_ref: None,
-            table_type: module.get_context().opaque_struct_type("DispatchTable"),
         }
     }
 
-    /// Creates the global dispatch table in the module if it does not exist.
-    fn ensure_table_ref(&mut self) {
-        if self.table_ref.is_none() {
-            self.table_ref = Some(
-                self.module
-                    .add_global(self.table_type, None, "dispatchTable"),
-            )
+    /// Adds the function to the dispatch table if it is not yet contained in it.
+    fn insert(&mut self, function: DispatchableFunction) {
+        if !self.function_to_idx.contains_key(&function) {
+            self.entries.push(function);
+            self.function_to_idx
+                .insert(function, self.function_to_idx.len());
         }
     }
 
-    /// Collects call expression from the given expression and sub expressions.
-    fn collect_expr(&mut self, expr: ExprId, body: &Body, infer: &InferenceResult) {
-        let expr = &body[expr];
-
-        // If this expression is a call, store it in the dispatch table
-        if let Expr::Call { callee, .. } = expr {
-            self.ensure_table_ref();
+    /// Collects call expressions and allocations of GC structs from the given expression and sub
+    /// expressions.
+    fn collect_expr(&mut self, expr_id: ExprId, body: &Arc<Body>, infer: &InferenceResult) {
+        let expr = &body[expr_id];
 
-            // Get the function from the expression
-            let function = &infer[*callee]
-                .as_function_def()
-                .expect("expected a function expression");
-
-            // If the function is not yet contained in the table, add it
-            if !self.function_to_idx.contains_key(function) {
-                self.entries.push(*function);
-                self.function_to_idx
-                    .insert(*function, self.function_to_idx.len());
+        match expr {
+            // Calls to builtin functions are generated inline, failed assertions are reported by
+            // the runtime
+            Expr::Call { callee, .. } if infer[*callee].as_builtin_function().is_some() => {
+                if is_assertion_generated(self.db, infer, *callee) {
+                    self.insert(DispatchableFunction::Intrinsic(Intrinsic::AssertFailed));
+                }
+            }
+            // If this expression is a call, store it in the dispatch table. A call through a
+            // function pointer doesn't refer to a specific function.
+            Expr::Call { callee, .. } => {
+                if let Some(function) = infer[*callee].as_function_def() {
+                    if is_dispatched(self.db, function) {
+                        self.insert(DispatchableFunction::Function(function));
+                    }
+                }
+            }
+            Expr::MethodCall { .. } => {
+                let method = infer
+                    .method_resolution(expr_id)
+                    .expect("method calls are resolved before code generation");
+                if is_dispatched(self.db, method) {
+                    self.insert(DispatchableFunction::Function(method));
+                }
+            }
+            Expr::BinaryOp {
+                lhs, op: Some(op), ..
+            } => {
+                if is_trap_checked(self.db, *op, &infer[*lhs]) {
+                    self.insert(DispatchableFunction::Intrinsic(Intrinsic::Trap));
+                }
             }
+            Expr::Index { index, .. } => {
+                if is_bounds_checked(self.db, body, *index) {
+                    self.insert(DispatchableFunction::Intrinsic(Intrinsic::Trap));
+                }
+            }
+            // Record literals of GC structs, and unit structs used as values, are allocated by
+            // the runtime
+            Expr::RecordLit { .. } => self.collect_struct_alloc(expr_id, infer),
+            Expr::Path(path) => {
+                let resolver = hir::resolver_for_expr(body.clone(), self.db, expr_id);
+                let resolution = resolver
+                    .resolve_path_without_assoc_items(self.db, path)
+                    .take_values();
+                match resolution {
+                    Some(Resolution::Def(ModuleDef::Struct(_))) => {
+                        self.collect_struct_alloc(expr_id, infer)
+                    }
+                    // A function pointer to a dispatched function is looked up in the table
+                    Some(Resolution::Def(ModuleDef::Function(function)))
+                        if is_dispatched(self.db, function) =>
+                    {
+                        self.insert(DispatchableFunction::Function(function))
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
         }
 
         // Recurse further
         expr.walk_child_exprs(|expr_id| self.collect_expr(expr_id, body, infer))
     }
 
-    /// Collect all the call expressions from the specified body with the given type inference
-    /// result.
-    pub fn collect_body(&mut self, body: &Body, infer: &InferenceResult) {
+    /// Adds the allocation intrinsic to the dispatch table if `expr` constructs a GC struct.
+    fn collect_struct_alloc(&mut self, expr: ExprId, infer: &InferenceResult) {
+        if let Some(TypeCtor::Struct(s)) = infer[expr].as_simple() {
+            if s.memory_kind(self.db) == StructMemoryKind::GC {
+                self.insert(DispatchableFunction::Intrinsic(Intrinsic::New));
+            }
+        }
+    }
+
+    /// Collect all the call expressions and allocations from the specified body with the given
+    /// type inference result.
+    fn collect_body(&mut self, body: &Arc<Body>, infer: &InferenceResult) {
         self.collect_expr(body.body_expr(), body, infer);
     }
 
     /// This creates the final DispatchTable with all *called* functions from within the module
-    /// # Parameters
-    /// * **functions**: Mapping of *defined* Mun functions to their respective IR values.
-    pub fn finalize(self, functions: &HashMap<mun_hir::Function, FunctionValue>) -> DispatchTable {
-        // Construct the table body from all the entries in the dispatch table
-        let table_body: Vec<BasicTypeEnum> = self
-            .entries
-            .iter()
-            .map(|f| {
-                // Push the associated IR function value type in the table_body field
-                // So that we can fill the DispatchTable struct intermediately
-                self.db
-                    // This returns the associated IR type declaration
-                    .type_ir(f.ty(self.db))
-                    .into_function_type()
-                    // This converts it into a function pointer type
-                    .ptr_type(inkwell::AddressSpace::Generic)
-                    .into()
-            })
-            .collect();
-
-        // We can fill in the DispatchTable body, i.e: struct DispatchTable { <this part> };
-        self.table_type.set_body(&table_body, false);
-
-        // Create a default initializer for function that are already known
-        if let Some(table_ref) = self.table_ref {
-            let values: Vec<BasicValueEnum> = self
-                .entries
-                .iter()
-                .enumerate()
-                // Maps over all HIR functions
-                .map(|(i, f)| {
-                    let function_type = table_body[i].into_pointer_type();
-                    // Find the associated IR function if it exists
-                    match functions.get(f) {
-                        // Case external function: Convert to typed null for the given function
-                        None => function_type.const_null(),
-                        // Case mun function: Get the function location as the initializer
-                        Some(function_value) => function_value.as_global_value().as_pointer_value(),
-                    }
-                    .into()
-                })
-                .collect();
-            // Set the initialize for the global value
-            table_ref.set_initializer(&self.table_type.const_named_struct(&values));
-        }
-
+    fn finalize(self) -> DispatchTable {
         DispatchTable {
+            file_id: self.file_id,
             function_to_idx: self.function_to_idx,
-            table_ref: self.table_ref,
             entries: self.entries,
         }
     }
@@ -1,22 +1,101 @@
+use crate::diagnostics::check_fn_body;
 use crate::ir::body::BodyIrGenerator;
+use crate::ir::debug_info::DebugInfoBuilder;
 use crate::ir::dispatch_table::DispatchTable;
+use crate::ir::module::assembly_file;
+use crate::ir::ty::{fn_return_convention, is_zero_sized, try_fn_ir, ReturnConvention};
+use crate::mangle::{closure_symbol_name, function_symbol_name};
 use crate::values::FunctionValue;
-use crate::{IrDatabase, Module, OptimizationLevel};
+use crate::{IrDatabase, Module, OptimizationLevel, PassPipeline};
+use inkwell::attributes::{Attribute, AttributeLoc};
+use inkwell::module::Linkage;
 use inkwell::passes::{PassManager, PassManagerBuilder};
 use inkwell::types::AnyTypeEnum;
 use mun_hir as hir;
-use std::collections::HashMap;
+use std::sync::Arc;
 
-/// Constructs a PassManager to optimize functions for the given optimization level.
+/// Determines the semantics of floating point operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FloatSemantics {
+    /// Floating point operations strictly follow IEEE 754, so their results are reproducible
+    /// across builds and optimization levels. Comparisons with NaN are false, except `!=`.
+    Strict,
+    /// Floating point operations may be reordered, contracted and approximated, as if their
+    /// operands and results are neither NaN nor infinite and the sign of zero doesn't matter.
+    /// Operations on NaN or infinity, including comparisons, then have unspecified results.
+    Fast,
+}
+
+impl Default for FloatSemantics {
+    fn default() -> Self {
+        FloatSemantics::Strict
+    }
+}
+
+/// The function attributes that enable fast floating point semantics. The LLVM C API doesn't allow
+/// setting fast-math flags on individual instructions, so they are applied to whole functions.
+const FAST_MATH_ATTRIBUTES: &[&str] = &[
+    "unsafe-fp-math",
+    "no-nans-fp-math",
+    "no-infs-fp-math",
+    "no-signed-zeros-fp-math",
+    "no-trapping-math",
+    "less-precise-fpmad",
+];
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FunctionIR {
+    /// The function for which the IR was generated
+    pub function: hir::Function,
+
+    /// The LLVM module that contains the definition of the function. Everything else that the
+    /// function references, e.g. other functions, statics and the dispatch table, is only
+    /// declared in the module.
+    pub llvm_module: Module,
+}
+
+/// Generates IR for the specified function. Every function is generated in its own module, so a
+/// function whose HIR didn't change is not generated again when another function in the file
+/// changes.
+pub(crate) fn ir_query(db: &impl IrDatabase, f: hir::Function) -> Arc<FunctionIR> {
+    let llvm_module = db.context().create_module(&f.name(db).to_string());
+    let llvm_function = gen_signature(db, f, &llvm_module);
+
+    // With debug info the functions of a file are generated in the module of the file instead
+    let dispatch_table = db.dispatch_table(assembly_file(db, f.module(db).file_id()));
+    gen_body(db, f, llvm_function, &llvm_module, &dispatch_table, None);
+
+    // The closures in the body are defined in the module as well
+    let fn_pass_manager =
+        create_pass_manager(&llvm_module, db.optimization_lvl(), &db.pass_pipeline());
+    let mut next_function = llvm_module.get_first_function();
+    while let Some(function) = next_function {
+        if function.count_basic_blocks() > 0 {
+            fn_pass_manager.run_on(&function);
+        }
+        next_function = function.get_next_function();
+    }
+
+    Arc::new(FunctionIR {
+        function: f,
+        llvm_module,
+    })
+}
+
+/// Constructs a PassManager to optimize functions for the given optimization level. Custom pass
+/// pipelines are run on the whole module before machine code is generated instead, so the
+/// PassManager doesn't optimize functions for a custom pipeline.
 pub(crate) fn create_pass_manager(
     module: &Module,
     optimization_lvl: OptimizationLevel,
+    pipeline: &PassPipeline,
 ) -> PassManager<FunctionValue> {
-    let pass_builder = PassManagerBuilder::create();
-    pass_builder.set_optimization_level(optimization_lvl);
-
     let function_pass_manager = PassManager::create(module);
-    pass_builder.populate_function_pass_manager(&function_pass_manager);
+    if *pipeline == PassPipeline::Default {
+        let pass_builder = PassManagerBuilder::create();
+        pass_builder.set_optimization_level(optimization_lvl);
+        pass_builder.populate_function_pass_manager(&function_pass_manager);
+    }
     function_pass_manager.initialize();
 
     function_pass_manager
@@ -24,19 +103,127 @@ pub(crate) fn create_pass_manager(
 
 /// Generates a `FunctionValue` for a `hir::Function`. This function does not generate a body for
 /// the `hir::Function`. That task is left to the `gen_body` function. The reason this is split
-/// between two functions is that first all signatures are generated and then all bodies. This
-/// allows bodies to reference `FunctionValue` wherever they are declared in the file.
+/// between two functions is that a body also declares the functions it calls directly, which are
+/// defined in the modules of those functions.
 pub(crate) fn gen_signature(
     db: &impl IrDatabase,
     f: hir::Function,
     module: &Module,
 ) -> FunctionValue {
-    let name = f.name(db).to_string();
-    if let AnyTypeEnum::FunctionType(ty) = db.type_ir(f.ty(db)) {
+    let name = function_symbol_name(db, f);
+    let fun = if let AnyTypeEnum::FunctionType(ty) = db.type_ir(f.ty(db)) {
         module.add_function(&name, ty, None)
     } else {
         panic!("not a function type")
+    };
+
+    // Calls through the dispatch table are indirect and can therefore never be inlined. Inline
+    // attributes only affect direct calls, i.e. with `CallMode::Direct`.
+    if let Some(inline) = f.data(db).inline() {
+        let attribute_name = match inline {
+            hir::InlineAttr::Hint => "inlinehint",
+            hir::InlineAttr::Always => "alwaysinline",
+            hir::InlineAttr::Never => "noinline",
+        };
+        let attribute = enum_attribute(module, attribute_name);
+        fun.add_attribute(AttributeLoc::Function, attribute);
+    }
+
+    add_attributes(db, fun, &db.fn_signature(f), module);
+    fun
+}
+
+/// Generates a `FunctionValue` for the closure with the signature `sig` in the body of `f`. Its
+/// body is generated by the `BodyIrGenerator` of `f`. A closure is only referred to by the body it
+/// is defined in, so it is private to the module of `f` and never exported.
+pub(crate) fn gen_closure_signature(
+    db: &impl IrDatabase,
+    f: hir::Function,
+    index: usize,
+    sig: &hir::FnSig,
+    module: &Module,
+) -> FunctionValue {
+    let name = closure_symbol_name(db, f, index);
+    let ty = try_fn_ir(db, sig).expect("the types of closures are checked before generating IR");
+    let fun = module.add_function(&name, ty, Some(Linkage::Private));
+    add_attributes(db, fun, sig, module);
+    fun
+}
+
+/// Adds the attributes of the signature `sig` and of the floating point semantics to `fun`.
+fn add_attributes(db: &impl IrDatabase, fun: FunctionValue, sig: &hir::FnSig, module: &Module) {
+    for (loc, attribute) in signature_attributes(db, sig, module) {
+        fun.add_attribute(loc, attribute);
+    }
+
+    if db.float_semantics() == FloatSemantics::Fast {
+        let context = module.get_context();
+        for attribute_name in FAST_MATH_ATTRIBUTES {
+            let attribute = context.create_string_attribute(attribute_name, "true");
+            fun.add_attribute(AttributeLoc::Function, attribute);
+        }
+    }
+}
+
+/// Returns the index of every parameter of the signature `sig` in the parameters of its IR function.
+/// Zero-sized parameters are omitted from the IR function, as well as from the ABI metadata, so
+/// their index is `None`. A pointer to the return value precedes the parameters.
+pub(crate) fn param_ir_indices(db: &impl IrDatabase, sig: &hir::FnSig) -> Vec<Option<u32>> {
+    let mut next_idx = match fn_return_convention(db, sig) {
+        ReturnConvention::Direct => 0,
+        ReturnConvention::Pointer => 1,
+    };
+    sig.params()
+        .iter()
+        .map(|ty| {
+            if is_zero_sized(db, ty) {
+                None
+            } else {
+                next_idx += 1;
+                Some(next_idx - 1)
+            }
+        })
+        .collect()
+}
+
+/// Returns the attributes of the parameters and the return value of functions with the signature
+/// `sig`. Calls through the dispatch table or a function pointer are indirect, so the attributes are
+/// also added to every call site.
+///
+/// Bools are passed as an `i8` that is marked `zeroext`, which is how the C ABI passes a `bool`. A
+/// value that is returned through a pointer is stored through the first parameter, which is marked
+/// `sret` and `noalias`.
+pub(crate) fn signature_attributes(
+    db: &impl IrDatabase,
+    sig: &hir::FnSig,
+    module: &Module,
+) -> Vec<(AttributeLoc, Attribute)> {
+    let mut attributes = Vec::new();
+    if fn_return_convention(db, sig) == ReturnConvention::Pointer {
+        attributes.push((AttributeLoc::Param(0), enum_attribute(module, "sret")));
+        attributes.push((AttributeLoc::Param(0), enum_attribute(module, "noalias")));
+    }
+
+    let is_bool = |ty: &hir::Ty| ty.as_simple() == Some(hir::TypeCtor::Bool);
+    for (ty, idx) in sig.params().iter().zip(param_ir_indices(db, sig)) {
+        match idx {
+            Some(idx) if is_bool(ty) => {
+                attributes.push((AttributeLoc::Param(idx), enum_attribute(module, "zeroext")))
+            }
+            _ => {}
+        }
     }
+    if is_bool(sig.ret()) {
+        attributes.push((AttributeLoc::Return, enum_attribute(module, "zeroext")));
+    }
+    attributes
+}
+
+/// Constructs the enum attribute `name`, e.g. `zeroext`.
+fn enum_attribute(module: &Module, name: &str) -> Attribute {
+    module
+        .get_context()
+        .create_enum_attribute(Attribute::get_named_enum_kind_id(name), 0)
 }
 
 /// Generates the body of a `hir::Function` for an associated `FunctionValue`.
@@ -45,16 +232,26 @@ pub(crate) fn gen_body<'a, 'b, D: IrDatabase>(
     hir_function: hir::Function,
     llvm_function: FunctionValue,
     module: &'a Module,
-    llvm_functions: &'a HashMap<mun_hir::Function, FunctionValue>,
     dispatch_table: &'b DispatchTable,
+    debug_info: Option<&'b DebugInfoBuilder>,
 ) -> FunctionValue {
+    // A body with types that are not supported is reported as a diagnostic. The function is still
+    // given a body, so the module remains valid.
+    if check_fn_body(db, hir_function).is_err() {
+        let context = module.get_context();
+        let builder = context.create_builder();
+        builder.position_at_end(&context.append_basic_block(&llvm_function, "body"));
+        builder.build_unreachable();
+        return llvm_function;
+    }
+
     let mut code_gen = BodyIrGenerator::new(
         db,
         module,
         hir_function,
         llvm_function,
-        llvm_functions,
         dispatch_table,
+        debug_info,
     );
 
     code_gen.gen_fn_body();
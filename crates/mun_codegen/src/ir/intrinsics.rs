@@ -0,0 +1,128 @@
+use inkwell::context::Context;
+use inkwell::types::{FunctionType, StructType};
+use inkwell::AddressSpace;
+
+/// A function that is implemented by the runtime and called from generated code through the
+/// dispatch table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Intrinsic {
+    /// Allocates memory for a GC struct using the runtime's allocator:
+    /// `fn new(size: u64, alignment: u32, alloc_handle: *mut u8) -> *mut u8`
+    New,
+    /// Reports a trap, e.g. an integer overflow, to the runtime with the table of trap sites of
+    /// the function and the index of the site that trapped: `fn trap(sites: *const u8, site: u32)`
+    Trap,
+    /// Reports a trap of a failed assertion to the runtime, like `Trap`, with a nul-terminated
+    /// message that describes the assertion:
+    /// `fn assert_failed(sites: *const u8, site: u32, message: *const u8)`
+    AssertFailed,
+}
+
+/// The check that causes a trap, which is stored in the table of trap sites as the `u8`
+/// representation of `mun_abi::TrapKind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrapKind {
+    Overflow,
+    DivisionByZero,
+    IndexOutOfBounds,
+    AssertionFailed,
+}
+
+impl TrapKind {
+    /// Returns the value by which the ABI represents the kind.
+    pub fn abi_value(self) -> u64 {
+        match self {
+            TrapKind::Overflow => 0,
+            TrapKind::DivisionByZero => 1,
+            TrapKind::IndexOutOfBounds => 2,
+            TrapKind::AssertionFailed => 3,
+        }
+    }
+}
+
+/// Returns the IR type of a `mun_abi::TrapSite`, an entry of the table of trap sites of a
+/// function: the name of the function, the path of its file, a line number and a `TrapKind`.
+pub fn trap_site_type(context: &Context) -> StructType {
+    let str_type = context.i8_type().ptr_type(AddressSpace::Generic);
+    context.struct_type(
+        &[
+            str_type.into(),
+            str_type.into(),
+            context.i32_type().into(),
+            context.i8_type().into(),
+        ],
+        false,
+    )
+}
+
+impl Intrinsic {
+    /// Returns the name under which the runtime provides the intrinsic.
+    pub fn name(self) -> &'static str {
+        match self {
+            Intrinsic::New => "@core::new",
+            Intrinsic::Trap => "@core::trap",
+            Intrinsic::AssertFailed => "@core::assert_failed",
+        }
+    }
+
+    /// Returns the names of the arguments of the intrinsic.
+    pub fn arg_names(self) -> &'static [&'static str] {
+        match self {
+            Intrinsic::New => &["size", "alignment", "alloc_handle"],
+            Intrinsic::Trap => &["sites", "site"],
+            Intrinsic::AssertFailed => &["sites", "site", "message"],
+        }
+    }
+
+    /// Returns the names of the runtime types of the arguments of the intrinsic.
+    pub fn arg_type_names(self) -> &'static [&'static str] {
+        match self {
+            Intrinsic::New => &["@core::u64", "@core::u32", "*mut @core::u8"],
+            Intrinsic::Trap => &["*const @core::u8", "@core::u32"],
+            Intrinsic::AssertFailed => &["*const @core::u8", "@core::u32", "*const @core::u8"],
+        }
+    }
+
+    /// Returns the name of the runtime type of the return value of the intrinsic, or `None` if
+    /// it doesn't return a value.
+    pub fn ret_type_name(self) -> Option<&'static str> {
+        match self {
+            Intrinsic::New => Some("*mut @core::u8"),
+            Intrinsic::Trap | Intrinsic::AssertFailed => None,
+        }
+    }
+
+    /// Returns the IR type of the intrinsic.
+    pub fn ir_type(self, context: &Context) -> FunctionType {
+        match self {
+            Intrinsic::New => {
+                let byte_ptr_type = context.i8_type().ptr_type(AddressSpace::Generic);
+                byte_ptr_type.fn_type(
+                    &[
+                        context.i64_type().into(),
+                        context.i32_type().into(),
+                        byte_ptr_type.into(),
+                    ],
+                    false,
+                )
+            }
+            Intrinsic::Trap => {
+                let byte_ptr_type = context.i8_type().ptr_type(AddressSpace::Generic);
+                context
+                    .void_type()
+                    .fn_type(&[byte_ptr_type.into(), context.i32_type().into()], false)
+            }
+            Intrinsic::AssertFailed => {
+                let byte_ptr_type = context.i8_type().ptr_type(AddressSpace::Generic);
+                context.void_type().fn_type(
+                    &[
+                        byte_ptr_type.into(),
+                        context.i32_type().into(),
+                        byte_ptr_type.into(),
+                    ],
+                    false,
+                )
+            }
+        }
+    }
+}
@@ -1,9 +1,17 @@
-use crate::ir::dispatch_table::{DispatchTable, DispatchTableBuilder};
-use crate::ir::function;
+use crate::diagnostics::{check_fn_signature, check_static};
+use crate::ir::body::ALLOCATOR_HANDLE_NAME;
+use crate::ir::debug_info::DebugInfoBuilder;
+use crate::ir::dispatch_table::{DispatchTable, DISPATCH_TABLE_NAME};
+use crate::ir::{function, gen_const_value, try_convert_any_to_basic};
+use crate::mangle::function_symbol_name;
 use crate::IrDatabase;
-use inkwell::{module::Module, values::FunctionValue};
+use inkwell::{
+    module::Module,
+    values::{FunctionValue, GlobalValue},
+    AddressSpace,
+};
 use mun_hir::{FileId, ModuleDef};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -17,58 +25,171 @@ pub struct ModuleIR {
     /// A mapping from HIR functions to LLVM IR values
     pub functions: HashMap<mun_hir::Function, FunctionValue>,
 
+    /// A mapping from HIR statics to the LLVM globals that hold their values
+    pub globals: HashMap<mun_hir::Static, GlobalValue>,
+
     /// The dispatch table
     pub dispatch_table: DispatchTable,
 }
 
-/// Generates IR for the specified file
+/// Returns the files whose code is generated in the module of `file_id`. The module of the root
+/// file of a package contains the code of all modules of the package, any other file is generated
+/// on its own.
+pub(crate) fn package_files(db: &impl IrDatabase, file_id: FileId) -> Vec<FileId> {
+    let module_tree = db.module_tree(db.file_source_root(file_id));
+    if module_tree.root() == Some(file_id) {
+        module_tree.files().to_vec()
+    } else {
+        vec![file_id]
+    }
+}
+
+/// Returns the file whose module contains the code of `file_id`, which is the root file of its
+/// package.
+pub(crate) fn assembly_file(db: &impl IrDatabase, file_id: FileId) -> FileId {
+    db.module_tree(db.file_source_root(file_id))
+        .root()
+        .unwrap_or(file_id)
+}
+
+/// Generates IR for the specified file and, for the root file of a package, the other modules of
+/// the package. The IR of every function is generated separately and linked into the module, so
+/// only functions that changed are generated again. With debug info, the functions are generated
+/// in the module itself, see `gen_bodies_with_debug_info`.
 pub(crate) fn ir_query(db: &impl IrDatabase, file_id: FileId) -> Arc<ModuleIR> {
     let llvm_module = db
         .context()
         .create_module(db.file_relative_path(file_id).as_str());
 
-    // Generate all the function signatures
+    // The dispatch table is declared by every function that uses it, declare it up front so it
+    // precedes the other globals that the functions declare
+    let dispatch_table = db.dispatch_table(file_id);
+    if !dispatch_table.entries().is_empty() {
+        llvm_module.add_global(db.dispatch_table_type(file_id), None, DISPATCH_TABLE_NAME);
+    }
+
+    // Generate all the globals and link in the functions
+    let debug_info = db.debug_info();
     let mut functions = HashMap::new();
-    let mut dispatch_table_builder = DispatchTableBuilder::new(db, &llvm_module);
-    for def in db.module_data(file_id).definitions() {
-        // TODO: Remove once we have more ModuleDef variants
-        #[allow(clippy::single_match)]
-        match def {
-            ModuleDef::Function(f) => {
-                // Construct the function signature
+    let mut debug_info_functions = Vec::new();
+    let mut globals = HashMap::new();
+    let reachable_functions = db.reachable_functions(file_id);
+    let definitions = package_files(db, file_id)
+        .into_iter()
+        .flat_map(|file_id| db.module_data(file_id).definitions().to_vec());
+    for def in definitions {
+        match &def {
+            // Extern functions are provided by the host, calls to them are resolved through the
+            // dispatch table by the runtime
+            ModuleDef::Function(f) if f.is_extern(db) => {}
+            // Definitions with types that are not supported in compiled code are reported as
+            // diagnostics, the rest of the module is still generated
+            ModuleDef::Function(f) if check_fn_signature(db, *f).is_err() => {}
+            // Unused private functions are reported as warnings instead
+            ModuleDef::Function(f) if !reachable_functions.contains(f) => {}
+            ModuleDef::Function(f) if debug_info => {
                 let fun = function::gen_signature(db, *f, &llvm_module);
                 functions.insert(*f, fun);
-
-                // Add calls to the dispatch table
-                let body = f.body(db);
-                let infer = f.infer(db);
-                dispatch_table_builder.collect_body(&body, &infer);
+                debug_info_functions.push((*f, fun));
+            }
+            ModuleDef::Function(f) => {
+                // Link a copy of the function's module, the cached module must not be modified
+                llvm_module
+                    .link_in_module(db.function_ir(*f).llvm_module.clone())
+                    .expect("the modules of functions can always be linked");
+                let fun = llvm_module
+                    .get_function(&function_symbol_name(db, *f))
+                    .expect("the linked module defines the function");
+                functions.insert(*f, fun);
+            }
+            ModuleDef::Static(s) if check_static(db, *s).is_err() => {}
+            ModuleDef::Static(s) => {
+                globals.insert(*s, gen_static(db, *s, &llvm_module));
             }
             _ => {}
         }
     }
 
-    // Construct requirements for generating the bodies
-    let dispatch_table = dispatch_table_builder.finalize(&functions);
-    let fn_pass_manager = function::create_pass_manager(&llvm_module, db.optimization_lvl());
+    if debug_info {
+        gen_bodies_with_debug_info(db, file_id, &llvm_module, &debug_info_functions);
+    }
+
+    // The globals that the functions only declare are defined once all functions are linked
+    dispatch_table.gen_initializer(db, &llvm_module, &functions);
+    if let Some(allocator_handle) = llvm_module.get_global(ALLOCATOR_HANDLE_NAME) {
+        let handle_type = db.context().i8_type().ptr_type(AddressSpace::Generic);
+        allocator_handle.set_initializer(&handle_type.const_null());
+    }
 
-    // Generate the function bodies
+    Arc::new(ModuleIR {
+        file_id,
+        llvm_module,
+        functions,
+        globals,
+        dispatch_table: (*dispatch_table).clone(),
+    })
+}
+
+/// Generates the bodies of `functions`, in the order of their definitions, in the module of the
+/// file `file_id` with their debug info.
+///
+/// The debug info of every source file is a single compile unit, which can't be split over the
+/// modules of its functions, because linking them would duplicate the compile unit. The locations
+/// in the debug info also change whenever lines are inserted above a function, so the IR of most
+/// functions would have to be generated again after an edit anyway.
+fn gen_bodies_with_debug_info(
+    db: &impl IrDatabase,
+    file_id: FileId,
+    module: &Module,
+    functions: &[(mun_hir::Function, FunctionValue)],
+) {
+    let dispatch_table = db.dispatch_table(file_id);
+    let mut debug_infos = BTreeMap::new();
     for (hir_function, llvm_function) in functions.iter() {
+        let function_file_id = hir_function.module(db).file_id();
+        let debug_info = debug_infos
+            .entry(function_file_id)
+            .or_insert_with(|| DebugInfoBuilder::new(db, module, function_file_id));
         function::gen_body(
             db,
             *hir_function,
             *llvm_function,
-            &llvm_module,
-            &functions,
+            module,
             &dispatch_table,
+            debug_info.as_ref(),
         );
+    }
+
+    // The debug info must be complete before the functions are optimized
+    debug_infos
+        .into_iter()
+        .filter_map(|(_, debug_info)| debug_info)
+        .for_each(DebugInfoBuilder::finalize);
+    let fn_pass_manager =
+        function::create_pass_manager(module, db.optimization_lvl(), &db.pass_pipeline());
+    for (_, llvm_function) in functions.iter() {
         fn_pass_manager.run_on(llvm_function);
     }
+}
 
-    Arc::new(ModuleIR {
-        file_id,
-        llvm_module,
-        functions,
-        dispatch_table,
-    })
+/// Generates the global that holds the value of the static `s`. The global is initialized with
+/// the initial value of the static, which is evaluated at compile time.
+fn gen_static(db: &impl IrDatabase, s: mun_hir::Static, module: &Module) -> GlobalValue {
+    let ty = db.type_ir(s.ty(db));
+    let value = s
+        .value(db)
+        .expect("statics are evaluated before code generation");
+
+    // Functions that precede the static in the file have already declared its global
+    let name = s.qualified_name(db);
+    let global = module.get_global(&name).unwrap_or_else(|| {
+        module.add_global(
+            try_convert_any_to_basic(ty).expect("statics must have a basic type"),
+            None,
+            &name,
+        )
+    });
+    global.set_initializer(&gen_const_value(value, ty));
+    global.set_constant(!s.is_mutable(db));
+    global
 }
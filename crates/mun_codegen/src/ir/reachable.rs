@@ -0,0 +1,50 @@
+use crate::ir::module::package_files;
+use crate::IrDatabase;
+use mun_hir::{functions_reachable_from, FileId, Function, ModuleDef, Visibility};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Returns the functions of the file, or of the package of a root file, for which code is
+/// generated. With dead code elimination these are the functions that are used by the `export`
+/// functions of the package, directly or indirectly. A package without `export` functions has no
+/// declared interface, so all of its functions are kept. `#[test]` functions and the functions
+/// that only they use are left out, unless this is a test build.
+pub(crate) fn reachable_functions_query(
+    db: &impl IrDatabase,
+    file_id: FileId,
+) -> Arc<HashSet<Function>> {
+    let (tests, functions): (Vec<Function>, Vec<Function>) = db
+        .module_tree(db.file_source_root(file_id))
+        .files()
+        .iter()
+        .flat_map(|file_id| db.module_data(*file_id).definitions().to_vec())
+        .filter_map(|def| match def {
+            ModuleDef::Function(f) => Some(f),
+            _ => None,
+        })
+        .partition(|f| f.is_test(db));
+
+    let has_exports = functions
+        .iter()
+        .any(|f| f.visibility(db) == Visibility::Public);
+    let roots = if db.dead_code_elimination() && has_exports {
+        functions
+            .into_iter()
+            .filter(|f| f.visibility(db) == Visibility::Public)
+            .collect()
+    } else {
+        functions
+    };
+    let mut reachable = functions_reachable_from(db, roots);
+    if db.test_build() {
+        reachable.extend(functions_reachable_from(db, tests));
+    }
+
+    let files = package_files(db, file_id);
+    Arc::new(
+        reachable
+            .into_iter()
+            .filter(|f| files.contains(&f.module(db).file_id()))
+            .collect(),
+    )
+}
@@ -0,0 +1,110 @@
+use mun_hir::{Body, Expr, ExprId, Function, InferenceResult, Statement};
+use std::collections::HashSet;
+
+/// The calls of a function to itself in its body. Calls in tail position are generated as a jump
+/// back to the start of the body, so tail recursion doesn't grow the stack. Mutual recursion is
+/// not taken into account.
+#[derive(Debug, Default)]
+pub(crate) struct SelfCalls {
+    /// The calls whose result is the result of the function
+    pub tail: HashSet<ExprId>,
+    /// The calls whose result is used otherwise, e.g. as an operand, in the order of the body
+    pub non_tail: Vec<ExprId>,
+}
+
+impl SelfCalls {
+    /// Collects the calls of `function` to itself from its `body`.
+    pub(crate) fn collect(function: Function, body: &Body, infer: &InferenceResult) -> Self {
+        let mut collector = SelfCallCollector {
+            function,
+            body,
+            infer,
+            calls: SelfCalls::default(),
+        };
+        collector.collect_expr(body.body_expr(), true);
+        collector.calls
+    }
+}
+
+struct SelfCallCollector<'a> {
+    function: Function,
+    body: &'a Body,
+    infer: &'a InferenceResult,
+    calls: SelfCalls,
+}
+
+impl<'a> SelfCallCollector<'a> {
+    /// Collects the self calls in `expr` and its sub expressions. `is_tail` specifies whether the
+    /// value of `expr` is the result of the function.
+    fn collect_expr(&mut self, expr: ExprId, is_tail: bool) {
+        let body = self.body;
+        match &body[expr] {
+            Expr::Block { statements, tail } => {
+                for statement in statements {
+                    match statement {
+                        Statement::Let { initializer, .. } => {
+                            if let Some(initializer) = initializer {
+                                self.collect_expr(*initializer, false);
+                            }
+                        }
+                        Statement::Expr(expr) => self.collect_expr(*expr, false),
+                    }
+                }
+                if let Some(tail) = tail {
+                    self.collect_expr(*tail, is_tail);
+                }
+            }
+            Expr::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.collect_expr(*condition, false);
+                self.collect_expr(*then_branch, is_tail);
+                if let Some(else_branch) = else_branch {
+                    self.collect_expr(*else_branch, is_tail);
+                }
+            }
+            Expr::Match {
+                expr: scrutinee,
+                arms,
+            } => {
+                self.collect_expr(*scrutinee, false);
+                for arm in arms {
+                    self.collect_expr(arm.expr, is_tail);
+                }
+            }
+            // The value of a return expression is always the result of the function
+            Expr::Return {
+                expr: Some(ret_expr),
+            } => self.collect_expr(*ret_expr, true),
+            Expr::Call { callee, .. } => {
+                if self.infer[*callee].as_function_def() == Some(self.function) {
+                    self.insert(expr, is_tail);
+                }
+                self.collect_child_exprs(expr);
+            }
+            Expr::MethodCall { .. } => {
+                if self.infer.method_resolution(expr) == Some(self.function) {
+                    self.insert(expr, is_tail);
+                }
+                self.collect_child_exprs(expr);
+            }
+            _ => self.collect_child_exprs(expr),
+        }
+    }
+
+    /// Collects the self calls in the sub expressions of `expr`, which are not in tail position.
+    fn collect_child_exprs(&mut self, expr: ExprId) {
+        let body = self.body;
+        body[expr].walk_child_exprs(|child| self.collect_expr(child, false));
+    }
+
+    fn insert(&mut self, call: ExprId, is_tail: bool) {
+        if is_tail {
+            self.calls.tail.insert(call);
+        } else {
+            self.calls.non_tail.push(call);
+        }
+    }
+}
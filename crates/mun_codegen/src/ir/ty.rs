@@ -1,36 +1,222 @@
 use super::try_convert_any_to_basic;
 use crate::IrDatabase;
-use inkwell::types::{AnyTypeEnum, BasicType, BasicTypeEnum};
-use mun_hir::{ApplicationTy, Ty, TypeCtor};
+use inkwell::context::Context;
+use inkwell::targets::TargetData;
+use inkwell::types::{AnyTypeEnum, BasicType, BasicTypeEnum, FunctionType, IntType};
+use inkwell::AddressSpace;
+use mun_hir::{
+    ApplicationTy, FloatBitness, FnSig, IntBitness, IntTy, StructMemoryKind, Ty, TypeCtor,
+};
 
-/// Given a mun type, construct an LLVM IR type
+/// A type that cannot be represented in compiled code, e.g. a type that could not be inferred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedTypeError(pub Ty);
+
+/// How a function returns its value to the caller, as determined by the C ABI of the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReturnConvention {
+    /// The value is returned directly, e.g. in registers
+    Direct,
+    /// The value is stored in memory of the caller, a pointer to which is passed as the first
+    /// parameter of the function. The parameter is marked `sret` and the function returns `void`.
+    Pointer,
+}
+
+/// Given a mun type, construct an LLVM IR type. Unsupported types are reported as diagnostics
+/// before code generation, so this should only be called for types that are supported.
 pub(crate) fn ir_query(db: &impl IrDatabase, ty: Ty) -> AnyTypeEnum {
+    db.try_type_ir(ty)
+        .unwrap_or_else(|UnsupportedTypeError(ty)| panic!("type {:?} is not supported", ty))
+}
+
+/// Given a mun type, construct an LLVM IR type. Returns the (part of the) type that cannot be
+/// represented if the type is not supported.
+pub(crate) fn try_ir_query(
+    db: &impl IrDatabase,
+    ty: Ty,
+) -> Result<AnyTypeEnum, UnsupportedTypeError> {
     let context = db.context();
-    match ty {
+    let ir_ty = match &ty {
         Ty::Empty => AnyTypeEnum::StructType(context.struct_type(&[], false)),
-        Ty::Apply(ApplicationTy { ctor, .. }) => match ctor {
-            TypeCtor::Float => AnyTypeEnum::FloatType(context.f64_type()),
-            TypeCtor::Int => AnyTypeEnum::IntType(context.i64_type()),
-            TypeCtor::Bool => AnyTypeEnum::IntType(context.bool_type()),
-            TypeCtor::FnDef(f) => {
-                let ty = db.fn_signature(f);
-                let params: Vec<BasicTypeEnum> = ty
-                    .params()
+        Ty::Apply(ApplicationTy { ctor, parameters }) => match *ctor {
+            TypeCtor::Float(ty) => AnyTypeEnum::FloatType(match ty.bitness {
+                FloatBitness::X32 => context.f32_type(),
+                FloatBitness::X64 => context.f64_type(),
+            }),
+            TypeCtor::Int(ty) => AnyTypeEnum::IntType(int_ir(&context, ty)),
+            // Enums are represented by the integer type of their discriminants
+            TypeCtor::Enum(e) => AnyTypeEnum::IntType(int_ir(&context, e.repr(db))),
+            // Bools are stored and passed as a byte that is either 0 or 1, like a C `bool`. Only
+            // conditions use `i1` values.
+            TypeCtor::Bool => AnyTypeEnum::IntType(context.i8_type()),
+            // Strings are passed as a pointer to their bytes and the number of bytes
+            TypeCtor::Str => AnyTypeEnum::StructType(context.struct_type(
+                &[
+                    context.i8_type().ptr_type(AddressSpace::Const).into(),
+                    context.i64_type().into(),
+                ],
+                false,
+            )),
+            // Expressions of type `never` diverge and therefore never produce a value
+            TypeCtor::Never => AnyTypeEnum::VoidType(context.void_type()),
+            TypeCtor::Struct(s) => {
+                // The fields are lowered when the struct type is constructed
+                for field in s.fields(db) {
+                    try_basic_ir(db, &field.ty(db))?;
+                }
+                let struct_ty = db.struct_ty(s);
+                match s.memory_kind(db) {
+                    // GC structs live in memory owned by the runtime and are referred to by pointer
+                    StructMemoryKind::GC => {
+                        AnyTypeEnum::PointerType(struct_ty.ptr_type(AddressSpace::Generic))
+                    }
+                    StructMemoryKind::Value => AnyTypeEnum::StructType(struct_ty),
+                }
+            }
+            // Tuples are anonymous structs with a field for every element
+            TypeCtor::Tuple { .. } => {
+                let fields = parameters
                     .iter()
-                    .map(|p| try_convert_any_to_basic(db.type_ir(p.clone())).unwrap())
-                    .collect();
+                    .map(|ty| try_basic_ir(db, ty))
+                    .collect::<Result<Vec<_>, _>>()?;
+                AnyTypeEnum::StructType(context.struct_type(&fields, false))
+            }
+            TypeCtor::Array { len } => {
+                let elem_ty = try_basic_ir(db, &parameters[0])?;
+                AnyTypeEnum::ArrayType(elem_ty.array_type(len as u32))
+            }
+            TypeCtor::FnDef(f) => AnyTypeEnum::FunctionType(try_fn_ir(db, &db.fn_signature(f))?),
+            // A function pointer is the address of the code of a function
+            TypeCtor::FnPtr { .. } => {
+                let sig = ty.callable_sig(db).expect("expected a function pointer");
+                AnyTypeEnum::PointerType(try_fn_ir(db, &sig)?.ptr_type(AddressSpace::Generic))
+            }
+            // A builtin function takes operands of the type of its result, and an assertion
+            // doesn't return a value
+            TypeCtor::BuiltinFn(function) => {
+                let operand_ty = try_basic_ir(db, &parameters[0])?;
+                let params = vec![operand_ty; function.arity()];
+                AnyTypeEnum::FunctionType(if function.is_assertion() {
+                    context.void_type().fn_type(&params, false)
+                } else {
+                    operand_ty.fn_type(&params, false)
+                })
+            }
+        },
+        Ty::Infer(_) | Ty::Unknown => return Err(UnsupportedTypeError(ty.clone())),
+    };
+    Ok(ir_ty)
+}
 
-                let fn_type = match ty.ret() {
-                    Ty::Empty => context.void_type().fn_type(&params, false),
-                    ty => try_convert_any_to_basic(db.type_ir(ty.clone()))
-                        .expect("could not convert return value")
-                        .fn_type(&params, false),
-                };
+/// Constructs the IR type of functions with the signature `sig`.
+pub(crate) fn try_fn_ir(
+    db: &impl IrDatabase,
+    sig: &FnSig,
+) -> Result<FunctionType, UnsupportedTypeError> {
+    let context = db.context();
+    let mut params = Vec::with_capacity(sig.params().len());
+    for p in sig.params() {
+        let param_ty = try_basic_ir(db, p)?;
+        // Zero-sized parameters carry no data, so they are omitted from the signature
+        if ir_size(db, param_ty) == 0 {
+            continue;
+        }
+        // Arrays are passed to functions by pointer. Their length is known from their type,
+        // which the ABI metadata of the function describes.
+        params.push(if p.as_array().is_some() {
+            param_ty.ptr_type(AddressSpace::Generic).into()
+        } else {
+            param_ty
+        });
+    }
 
-                AnyTypeEnum::FunctionType(fn_type)
+    let fn_type = match sig.ret() {
+        ty if ty.is_empty() || ty.is_never() => context.void_type().fn_type(&params, false),
+        ty => {
+            let ret_ty = try_basic_ir(db, ty)?;
+            if ir_size(db, ret_ty) == 0 {
+                // A zero-sized value is returned as `void`
+                context.void_type().fn_type(&params, false)
+            } else {
+                match return_convention(db, ret_ty) {
+                    ReturnConvention::Direct => ret_ty.fn_type(&params, false),
+                    ReturnConvention::Pointer => {
+                        let ret_ptr_ty = ret_ty.ptr_type(AddressSpace::Generic);
+                        params.insert(0, ret_ptr_ty.into());
+                        context.void_type().fn_type(&params, false)
+                    }
+                }
             }
-            _ => unreachable!(),
-        },
-        _ => unreachable!("unknown type can not be converted"),
+        }
+    };
+    Ok(fn_type)
+}
+
+/// Constructs the IR type of the integer type `ty`.
+fn int_ir(context: &Context, ty: IntTy) -> IntType {
+    match ty.bitness {
+        IntBitness::X8 => context.i8_type(),
+        IntBitness::X16 => context.i16_type(),
+        IntBitness::X32 => context.i32_type(),
+        IntBitness::X64 => context.i64_type(),
+    }
+}
+
+/// Constructs the IR type of `ty`, which must be a type of values, e.g. the type of a field or a
+/// parameter.
+pub(crate) fn try_basic_ir(
+    db: &impl IrDatabase,
+    ty: &Ty,
+) -> Result<BasicTypeEnum, UnsupportedTypeError> {
+    try_convert_any_to_basic(db.try_type_ir(ty.clone())?)
+        .ok_or_else(|| UnsupportedTypeError(ty.clone()))
+}
+
+/// Returns how a value of the IR type `ty` is returned from a function on the target. Aggregates
+/// that don't fit in the return registers of the C ABI are returned through memory of the caller.
+/// On Windows these are aggregates with a size other than 1, 2, 4, or 8 bytes, on other platforms
+/// aggregates larger than 16 bytes.
+pub(crate) fn return_convention(db: &impl IrDatabase, ty: BasicTypeEnum) -> ReturnConvention {
+    let is_aggregate = match ty {
+        BasicTypeEnum::StructType(_) | BasicTypeEnum::ArrayType(_) => true,
+        _ => false,
+    };
+    if !is_aggregate {
+        return ReturnConvention::Direct;
+    }
+
+    let size = ir_size(db, ty);
+    let in_memory = if db.target().options.is_like_windows {
+        size > 8 || !size.is_power_of_two()
+    } else {
+        size > 16
+    };
+    if in_memory {
+        ReturnConvention::Pointer
+    } else {
+        ReturnConvention::Direct
     }
 }
+
+/// Returns how a function with the signature `sig` returns its value.
+pub(crate) fn fn_return_convention(db: &impl IrDatabase, sig: &FnSig) -> ReturnConvention {
+    let ret = sig.ret();
+    if ret.is_never() || is_zero_sized(db, ret) {
+        ReturnConvention::Direct
+    } else {
+        let ty = try_basic_ir(db, ret).expect("unsupported return type");
+        return_convention(db, ty)
+    }
+}
+
+/// Returns whether values of `ty` occupy no memory, e.g. `()` or a value struct without fields.
+/// Zero-sized parameters are omitted from the signatures of functions, and zero-sized values are
+/// returned as `void`.
+pub(crate) fn is_zero_sized(db: &impl IrDatabase, ty: &Ty) -> bool {
+    ty.is_empty() || try_basic_ir(db, ty).map_or(false, |ty| ir_size(db, ty) == 0)
+}
+
+/// Returns the size in bytes of the IR type `ty` in the data layout of the target.
+fn ir_size(db: &impl IrDatabase, ty: BasicTypeEnum) -> u64 {
+    TargetData::create(&db.target().data_layout).get_abi_size(&ty)
+}
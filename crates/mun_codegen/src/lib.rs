@@ -1,7 +1,9 @@
 /// This library generates machine code from HIR using inkwell which is a safe wrapper around LLVM.
 mod code_gen;
 mod db;
+pub mod diagnostics;
 mod ir;
+mod mangle;
 pub(crate) mod symbols;
 
 #[cfg(test)]
@@ -12,6 +14,10 @@ mod test;
 pub use inkwell::{builder, context::Context, module::Module, values, OptimizationLevel};
 
 pub use crate::{
-    code_gen::write_module_shared_object,
+    code_gen::{
+        emit_module_artifacts, write_module_artifacts, write_module_shared_object, Artifact,
+        InvalidIrError, InvalidPassPipeline, IrVerification, ModuleArtifacts, Pass, PassPipeline,
+    },
     db::{IrDatabase, IrDatabaseStorage},
+    ir::{dispatch_table::CallMode, function::FloatSemantics},
 };
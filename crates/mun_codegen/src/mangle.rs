@@ -0,0 +1,64 @@
+//! The names of the symbols of functions are mangled, so functions with the same name in different
+//! modules don't collide when the modules are linked together. The scheme is inspired by the
+//! Itanium C++ ABI: the mangled name of `update` in `main.mun` is `_MN4main6updateE`, a prefix
+//! followed by the length-prefixed components of the path of the function. The ABI refers to
+//! functions by their human-readable paths, so mangled names only appear in the symbol tables of
+//! assemblies. `mun_abi::demangle` converts them back.
+
+use crate::IrDatabase;
+use mun_hir as hir;
+use mun_hir::FileId;
+
+/// The prefix of a mangled name.
+const PREFIX: &str = "_MN";
+
+/// The suffix of a mangled name.
+const SUFFIX: &str = "E";
+
+/// Mangles the path that consists of the components in `path`.
+pub(crate) fn mangle<S: AsRef<str>>(path: &[S]) -> String {
+    let mut mangled = PREFIX.to_owned();
+    for component in path {
+        let component = component.as_ref();
+        mangled.push_str(&component.len().to_string());
+        mangled.push_str(component);
+    }
+    mangled.push_str(SUFFIX);
+    mangled
+}
+
+/// Returns the path of the module of `file_id`, which is derived from the path of the file
+/// relative to its source root, e.g. `foo/bar.mun` becomes `foo::bar`.
+fn module_path(db: &impl IrDatabase, file_id: FileId) -> Vec<String> {
+    db.file_relative_path(file_id)
+        .with_extension("")
+        .as_str()
+        .split('/')
+        .filter(|component| !component.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Returns the name of the symbol of the function `f`. The path of a method includes the name of
+/// its struct, e.g. `_MN4main6Vector6lengthE`.
+pub(crate) fn function_symbol_name(db: &impl IrDatabase, f: hir::Function) -> String {
+    mangle(&function_path(db, f))
+}
+
+/// Returns the name of the symbol of the `index`th closure in the body of `f`, e.g.
+/// `_MN4main6update10{closure0}E`. Closures are private to the module of `f`.
+pub(crate) fn closure_symbol_name(db: &impl IrDatabase, f: hir::Function, index: usize) -> String {
+    let mut path = function_path(db, f);
+    path.push(format!("{{closure{}}}", index));
+    mangle(&path)
+}
+
+/// Returns the components of the path of the function `f`.
+fn function_path(db: &impl IrDatabase, f: hir::Function) -> Vec<String> {
+    let mut path = module_path(db, f.module(db).file_id());
+    if let Some(s) = f.impl_block(db).and_then(|it| it.target_struct(db)) {
+        path.push(s.name(db).to_string());
+    }
+    path.push(f.name(db).to_string());
+    path
+}
@@ -1,7 +1,10 @@
-use crate::{IrDatabase, OptimizationLevel};
+use crate::{
+    CallMode, FloatSemantics, IrDatabase, IrVerification, OptimizationLevel, PassPipeline,
+};
 use mun_hir::{FileId, RelativePathBuf};
 use mun_hir::{SourceDatabase, SourceRoot, SourceRootId};
-use std::sync::Arc;
+use mun_target::spec::Target;
+use std::sync::{Arc, Mutex};
 
 /// A mock implementation of the IR database. It can be used to set up a simple test case.
 #[salsa::database(
@@ -13,35 +16,93 @@ use std::sync::Arc;
 #[derive(Default, Debug)]
 pub(crate) struct MockDatabase {
     runtime: salsa::Runtime<MockDatabase>,
+    events: Mutex<Option<Vec<salsa::Event<MockDatabase>>>>,
 }
 
 impl salsa::Database for MockDatabase {
     fn salsa_runtime(&self) -> &salsa::Runtime<MockDatabase> {
         &self.runtime
     }
+
+    fn salsa_event(&self, event: impl Fn() -> salsa::Event<MockDatabase>) {
+        let mut events = self.events.lock().unwrap();
+        if let Some(events) = &mut *events {
+            events.push(event());
+        }
+    }
 }
 
 impl MockDatabase {
     /// Creates a database from the given text.
     pub fn with_single_file(text: &str) -> (MockDatabase, FileId) {
+        let (db, file_ids) = MockDatabase::with_files(&[("main.mun", text)]);
+        (db, file_ids[0])
+    }
+
+    /// Creates a database with a file for every pair of relative path and text.
+    pub fn with_files(files: &[(&str, &str)]) -> (MockDatabase, Vec<FileId>) {
         let mut db: MockDatabase = Default::default();
 
         let mut source_root = SourceRoot::default();
         let source_root_id = SourceRootId(0);
 
-        let text = Arc::new(text.to_owned());
-        let rel_path = RelativePathBuf::from("main.mun");
-        let file_id = FileId(0);
-        db.set_file_relative_path(file_id, rel_path.clone());
-        db.set_file_text(file_id, Arc::new(text.to_string()));
-        db.set_file_source_root(file_id, source_root_id);
-        source_root.insert_file(rel_path, file_id);
+        let mut file_ids = Vec::new();
+        for (idx, (path, text)) in files.iter().enumerate() {
+            let rel_path = RelativePathBuf::from(*path);
+            let file_id = FileId(idx as u32);
+            db.set_file_relative_path(file_id, rel_path.clone());
+            db.set_file_text(file_id, Arc::new(text.to_string()));
+            db.set_file_source_root(file_id, source_root_id);
+            source_root.insert_file(rel_path, file_id);
+            file_ids.push(file_id);
+        }
 
         db.set_source_root(source_root_id, Arc::new(source_root));
         db.set_optimization_lvl(OptimizationLevel::Default);
+        db.set_pass_pipeline(PassPipeline::Default);
+        db.set_call_mode(CallMode::Dispatch);
+        db.set_overflow_checks(false);
+        db.set_division_checks(false);
+        db.set_bounds_checks(false);
+        db.set_assertions(true);
+        db.set_float_semantics(FloatSemantics::Strict);
+        db.set_debug_info(false);
+        db.set_ir_verification(IrVerification::On);
+        db.set_codegen_units(1);
+        db.set_dead_code_elimination(true);
+        db.set_test_build(false);
+        db.set_tail_call_notes(false);
+        db.set_lint_levels(Arc::new(Default::default()));
+        db.set_assembly_dependencies(Arc::new(Vec::new()));
+        // The calling convention depends on the target, so the generated IR is the same on every
+        // host
+        db.set_target(Target::search("x86_64-unknown-linux-gnu").unwrap());
 
         let context = crate::Context::create();
         db.set_context(Arc::new(context));
-        (db, file_id)
+        (db, file_ids)
+    }
+}
+
+impl MockDatabase {
+    pub fn log(&self, f: impl FnOnce()) -> Vec<salsa::Event<MockDatabase>> {
+        *self.events.lock().unwrap() = Some(Vec::new());
+        f();
+        self.events.lock().unwrap().take().unwrap()
+    }
+
+    pub fn log_executed(&self, f: impl FnOnce()) -> Vec<String> {
+        let events = self.log(f);
+        events
+            .into_iter()
+            .filter_map(|e| match e.kind {
+                // This pretty horrible, but `Debug` is the only way to inspect
+                // QueryDescriptor at the moment.
+                salsa::EventKind::WillExecute { database_key } => {
+                    Some(format!("{:?}", database_key))
+                }
+                _ => None,
+            })
+            .collect()
     }
 }
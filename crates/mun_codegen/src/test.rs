@@ -1,9 +1,20 @@
-use crate::{mock::MockDatabase, IrDatabase};
-use mun_hir::diagnostics::DiagnosticSink;
+use crate::code_gen::units;
+use crate::{
+    code_gen::verify::verify_module, diagnostics, mangle::mangle, mock::MockDatabase,
+    write_module_artifacts, Artifact, CallMode, FloatSemantics, InvalidPassPipeline, IrDatabase,
+    OptimizationLevel, Pass, PassPipeline,
+};
+use inkwell::attributes::{Attribute, AttributeLoc};
+use inkwell::targets::TargetData;
+use mun_hir::diagnostics::{
+    DiagnosticSink, FloatLiteralOutOfRange, UnreachableCode, UnusedFunction, UnusedVariable,
+};
 use mun_hir::line_index::LineIndex;
-use mun_hir::Module;
-use mun_hir::SourceDatabase;
+use mun_hir::{DefDatabase, SourceDatabase};
+use mun_hir::{Module, ModuleDef};
+use mun_target::spec::Target;
 use std::cell::RefCell;
+use std::fs;
 use std::sync::Arc;
 
 #[test]
@@ -86,25 +97,25 @@ fn update_operators() {
     test_snapshot(
         r#"
     fn add(a:int, b:int):int {
-      let result = a
+      let mut result = a
       result += b
       result
     }
 
     fn subtract(a:int, b:int):int {
-      let result = a
+      let mut result = a
       result -= b
       result
     }
 
     fn multiply(a:int, b:int):int {
-      let result = a
+      let mut result = a
       result *= b
       result
     }
 
     fn divide(a:int, b:int):int {
-      let result = a
+      let mut result = a
       result /= b
       result
     }
@@ -116,7 +127,7 @@ fn update_operators() {
 fn update_parameter() {
     test_snapshot(
         r#"
-    fn add_three(a:int):int {
+    fn add_three(mut a:int):int {
       a += 3;
       a
     }
@@ -186,6 +197,22 @@ fn if_statement() {
     )
 }
 
+#[test]
+fn if_diverging_branch() {
+    test_snapshot(
+        r#"
+    fn foo(a:int):int {
+        let b = if a > 3 {
+            return 0;
+        } else {
+            a+1
+        }
+        b
+    }
+    "#,
+    )
+}
+
 #[test]
 fn void_return() {
     test_snapshot(
@@ -220,9 +247,9 @@ fn fibonacci_loop() {
     test_snapshot(
         r#"
     fn fibonacci(n:int):int {
-        let a = 0;
-        let b = 1;
-        let i = 1;
+        let mut a = 0;
+        let mut b = 1;
+        let mut i = 1;
         loop {
             if i > n {
                 return a
@@ -316,6 +343,163 @@ fn true_is_true() {
     );
 }
 
+#[test]
+fn bool_abi() {
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    struct(value) Flags {
+        a: bool,
+        b: bool,
+    }
+
+    fn negate(a: bool): bool { !a }
+    fn flags(a: bool): Flags { Flags { a: a, b: negate(a) } }
+    fn both(f: Flags): bool { f.a && f.b }
+    "#,
+    );
+
+    // Bools are stored in struct fields as bytes and passed as zero extended bytes
+    let ir = db
+        .module_ir(file_id)
+        .llvm_module
+        .print_to_string()
+        .to_string();
+    assert!(ir.contains("%\"main::Flags\" = type { i8, i8 }"), "{}", ir);
+    assert!(
+        ir.contains("define zeroext i8 @_MN4main6negateE(i8 zeroext)"),
+        "{}",
+        ir
+    );
+    assert!(ir.contains("= call zeroext i8 "), "{}", ir);
+    assert!(ir.contains("(i8 zeroext %0)"), "{}", ir);
+    assert!(!ir.contains("i1 @"), "{}", ir);
+}
+
+#[test]
+fn sret_returns() {
+    let text = r#"
+    struct(value) Small { a: int, b: float }
+    struct(value) Large { a: int, b: int, c: int }
+
+    fn small(a:int):Small { Small { a: a, b: 1.0 } }
+    fn large(a:int, b:bool):Large { Large { a: a, b: a, c: a } }
+    fn sum():int { let l = large(1, true); l.a + l.b + l.c }
+    "#;
+
+    let ir = |triple| {
+        let (mut db, file_id) = MockDatabase::with_single_file(text);
+        db.set_target(Target::search(triple).unwrap());
+        db.module_ir(file_id)
+            .llvm_module
+            .print_to_string()
+            .to_string()
+    };
+
+    // On x86-64 Linux only aggregates larger than 16 bytes are returned through a pointer
+    let linux_ir = ir("x86_64-unknown-linux-gnu");
+    assert!(
+        linux_ir.contains(r#"define %"main::Small" @_MN4main5smallE(i64)"#),
+        "{}",
+        linux_ir
+    );
+    assert!(
+        linux_ir.contains(
+            r#"define void @_MN4main5largeE(%"main::Large"* noalias sret, i64, i8 zeroext)"#
+        ),
+        "{}",
+        linux_ir
+    );
+    assert!(linux_ir.contains("noalias sret %ret"), "{}", linux_ir);
+
+    // On Windows only aggregates of 1, 2, 4, or 8 bytes are returned directly
+    let windows_ir = ir("x86_64-pc-windows-msvc");
+    assert!(
+        windows_ir.contains(r#"define void @_MN4main5smallE(%"main::Small"* noalias sret, i64)"#),
+        "{}",
+        windows_ir
+    );
+}
+
+#[test]
+fn zero_sized_params() {
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    struct(value) Unit;
+
+    fn nothing() {}
+    fn unit(a: Unit, b: (), c: bool): Unit { a }
+    fn main(): int {
+        unit(Unit, nothing(), true);
+        5
+    }
+    "#,
+    );
+    let ir = db
+        .module_ir(file_id)
+        .llvm_module
+        .print_to_string()
+        .to_string();
+
+    // Zero-sized parameters and return values are omitted from the signature
+    assert!(
+        ir.contains("define void @_MN4main4unitE(i8 zeroext)"),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn fn_pointers() {
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    fn double(a: int): int { a * 2 }
+    fn apply(f: fn(int): int, v: int): int { f(v) }
+    fn main(): int { apply(double, 3) }
+    "#,
+    );
+    let ir = db
+        .module_ir(file_id)
+        .llvm_module
+        .print_to_string()
+        .to_string();
+
+    // A function pointer is passed as a pointer to the code of the function, and called
+    // indirectly
+    assert!(
+        ir.contains("define i64 @_MN4main5applyE(i64 (i64)*, i64)"),
+        "{}",
+        ir
+    );
+    assert!(ir.contains("i64 %0(i64 %1)"), "{}", ir);
+}
+
+#[test]
+fn closures() {
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    fn apply(f: fn(int): int, v: int): int { f(v) }
+    fn main(): int { apply(|x| x * 2, 3) }
+    "#,
+    );
+    let ir = db
+        .module_ir(file_id)
+        .llvm_module
+        .print_to_string()
+        .to_string();
+
+    // The body of a closure is a private function, which is passed by pointer
+    assert!(
+        ir.contains("define private i64 @\"_MN4main4main10{closure0}E\"(i64)"),
+        "{}",
+        ir
+    );
+    assert!(
+        ir.contains("(i64 (i64)* @\"_MN4main4main10{closure0}E\", i64 3)"),
+        "{}",
+        ir
+    );
+}
+
 #[test]
 fn loop_expr() {
     test_snapshot(
@@ -331,7 +515,7 @@ fn loop_expr() {
 fn loop_break_expr() {
     test_snapshot(
         r#"
-    fn foo(n:int):int {
+    fn foo(mut n:int):int {
         loop {
             if n > 5 {
                 break n;
@@ -350,7 +534,7 @@ fn loop_break_expr() {
 fn while_expr() {
     test_snapshot(
         r#"
-    fn foo(n:int) {
+    fn foo(mut n:int) {
         while n<3 {
             n += 1;
         };
@@ -364,23 +548,1591 @@ fn while_expr() {
     )
 }
 
-fn test_snapshot(text: &str) {
-    let text = text.trim().replace("\n    ", "\n");
+#[test]
+fn f32_expressions() {
+    test_snapshot(
+        r#"
+    fn add(a:f32, b:f32):f32 {
+      a+b
+    }
 
-    let (db, file_id) = MockDatabase::with_single_file(&text);
+    fn less(a:f32, b:f32):bool {
+      a<b
+    }
 
-    let line_index: Arc<LineIndex> = db.line_index(file_id);
-    let messages = RefCell::new(Vec::new());
-    let mut sink = DiagnosticSink::new(|diag| {
-        let line_col = line_index.line_col(diag.highlight_range().start());
-        messages.borrow_mut().push(format!(
-            "error {}:{}: {}",
-            line_col.line + 1,
-            line_col.col + 1,
-            diag.message()
-        ));
-    });
-    Module::from(file_id).diagnostics(&db, &mut sink);
+    fn literal():f32 {
+      1.5
+    }
+    "#,
+    );
+}
+
+#[test]
+fn struct_type() {
+    test_snapshot(
+        r#"
+    struct Foo {
+        a: int,
+        b: float,
+        c: bool,
+    }
+    struct Bar;
+
+    fn foo(a:Foo, b:Bar):Foo {
+      a
+    }
+    "#,
+    );
+}
+
+#[test]
+fn field_access() {
+    test_snapshot(
+        r#"
+    struct Foo {
+        a: int,
+        b: float,
+    }
+    struct Bar {
+        foo: Foo,
+        c: bool,
+    }
+
+    fn foo(bar:Bar):float {
+      bar.foo.b
+    }
+    "#,
+    );
+}
+
+#[test]
+fn record_lit() {
+    test_snapshot(
+        r#"
+    struct Foo {
+        a: int,
+        b: float,
+    }
+    struct Unit;
+
+    fn foo(a:int):Foo {
+      Foo { b: 2.0, a: a }
+    }
+
+    fn unit():Unit {
+      Unit
+    }
+    "#,
+    );
+}
+
+#[test]
+fn value_struct() {
+    test_snapshot(
+        r#"
+    struct(value) Foo {
+        a: int,
+        b: float,
+    }
+    struct(value) Bar {
+        foo: Foo,
+        c: bool,
+    }
+    struct(value) Unit;
+
+    fn foo(a:int):Foo {
+      Foo { b: 2.0, a: a }
+    }
+
+    fn get_b(bar:Bar):float {
+      bar.foo.b
+    }
+
+    fn unit():Unit {
+      Unit
+    }
+    "#,
+    );
+}
+
+#[test]
+fn tuples() {
+    test_snapshot(
+        r#"
+    fn pair(a:int, b:float):(int, float) {
+      (a, b)
+    }
+
+    fn swap(t:(int, float)):(float, int) {
+      (t.1, t.0)
+    }
+
+    fn first():int {
+      pair(3, 1.0).0
+    }
+    "#,
+    );
+}
+
+#[test]
+fn arrays() {
+    test_snapshot(
+        r#"
+    fn make(a:float):[float; 2] {
+      [a, 1.0]
+    }
+
+    fn get(a:[float; 2], i:int):float {
+      a[i]
+    }
+    "#,
+    );
+}
+
+#[test]
+fn array_params() {
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    fn first(a:[int; 4]):int { a[0] }
+    fn forward(a:[int; 4]):int { first(a) }
+    fn forward_mut(mut a:[int; 4]):int { a[0] = 1; first(a) }
+    "#,
+    );
+    let ir = db
+        .module_ir(file_id)
+        .llvm_module
+        .print_to_string()
+        .to_string();
+    let function_ir = |name: &str| {
+        let symbol = format!("@_MN4main{}{}E(", name.len(), name);
+        ir.split("\ndefine ")
+            .find(|function| function.contains(&symbol))
+            .unwrap()
+            .to_string()
+    };
+
+    // An immutable array parameter refers to the array of the caller, which it passes on without
+    // a copy. A mutable parameter is copied, so the array of the caller is never modified.
+    assert!(!function_ir("forward").contains("alloca"), "{}", ir);
+    assert!(function_ir("forward_mut").contains("alloca"), "{}", ir);
+}
+
+#[test]
+fn struct_layout() {
+    let (mut db, file_id) = MockDatabase::with_single_file(
+        r#"
+    struct Foo {
+        a: bool,
+        b: int,
+        c: f32,
+    }
+    "#,
+    );
+    db.set_target(Target::search("x86_64-unknown-linux-gnu").unwrap());
+
+    let foo = Module::from(file_id)
+        .declarations(&db)
+        .into_iter()
+        .find_map(|def| match def {
+            ModuleDef::Struct(s) => Some(s),
+            _ => None,
+        })
+        .unwrap();
+
+    let layout = db.struct_layout(foo);
+    let target_data = TargetData::create(&db.target().data_layout);
+    let struct_ty = db.struct_ty(foo);
+    let expected_offsets: Vec<u64> = (0..3)
+        .map(|idx| target_data.offset_of_element(&struct_ty, idx).unwrap())
+        .collect();
+    assert_eq!(layout.field_offsets, expected_offsets);
+    assert_eq!(layout.field_offsets, vec![0, 8, 16]);
+    assert_eq!(layout.size, target_data.get_abi_size(&struct_ty));
+    assert_eq!(layout.size, 24);
+    assert_eq!(layout.alignment, target_data.get_abi_alignment(&struct_ty));
+    assert_eq!(layout.alignment, 8);
+}
+
+#[test]
+fn struct_repr_layout() {
+    #[repr(C, packed)]
+    #[allow(dead_code)]
+    struct Packed {
+        a: u8,
+        b: i64,
+        c: u16,
+    }
+
+    #[repr(C, align(16))]
+    #[allow(dead_code)]
+    struct Aligned {
+        a: u8,
+        b: f32,
+    }
+
+    let (mut db, file_id) = MockDatabase::with_single_file(
+        r#"
+    #[repr(packed)]
+    struct(value) Packed {
+        a: u8,
+        b: int,
+        c: u16,
+    }
+
+    #[repr(C, align(16))]
+    struct(value) Aligned {
+        a: u8,
+        b: f32,
+    }
+
+    struct(value) Outer {
+        a: u8,
+        b: Aligned,
+    }
+    "#,
+    );
+    db.set_target(Target::search("x86_64-unknown-linux-gnu").unwrap());
+
+    let layout = |name: &str| {
+        let s = Module::from(file_id)
+            .declarations(&db)
+            .into_iter()
+            .find_map(|def| match def {
+                ModuleDef::Struct(s) if s.name(&db).to_string() == name => Some(s),
+                _ => None,
+            })
+            .unwrap();
+        db.struct_layout(s)
+    };
+
+    // The layouts match those of the equivalent Rust structs
+    let packed = layout("Packed");
+    assert_eq!(packed.field_offsets, vec![0, 1, 9]);
+    assert_eq!(packed.size, std::mem::size_of::<Packed>() as u64);
+    assert_eq!(packed.alignment, std::mem::align_of::<Packed>() as u32);
+
+    let aligned = layout("Aligned");
+    assert_eq!(aligned.field_offsets, vec![0, 4]);
+    assert_eq!(aligned.size, std::mem::size_of::<Aligned>() as u64);
+    assert_eq!(aligned.alignment, std::mem::align_of::<Aligned>() as u32);
+
+    // An aligned struct is also aligned as a field of another struct
+    let outer = layout("Outer");
+    assert_eq!(outer.field_offsets, vec![0, 16]);
+    assert_eq!(outer.size, 32);
+    assert_eq!(outer.alignment, 16);
+}
+
+#[test]
+fn packed_struct_fields() {
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    #[repr(packed)]
+    struct(value) Packed {
+        a: u8,
+        b: int,
+    }
+
+    fn get(p: Packed): int { p.b }
+    fn set(mut p: Packed): int { p.b = 5; p.b }
+    "#,
+    );
+
+    // The fields of a packed struct are accessed without assuming their alignment
+    let ir = db
+        .module_ir(file_id)
+        .llvm_module
+        .print_to_string()
+        .to_string();
+    assert!(ir.contains("<{ i8, i64 }>"), "{}", ir);
+    assert!(ir.contains("%b = load i64, i64* %b_ptr, align 1"), "{}", ir);
+    assert!(ir.contains("store i64 5, i64* %b_ptr, align 1"), "{}", ir);
+}
+
+#[test]
+fn invalid_repr_attributes() {
+    test_snapshot(
+        r#"
+    #[repr(packed, align(4))]
+    struct A;
+
+    #[repr(align(3))]
+    struct B;
+
+    #[repr(align(256))]
+    struct(value) C;
+
+    #[repr(packed)]
+    #[repr(align(8))]
+    struct D;
+
+    #[repr(transparent)]
+    struct E;
+
+    #[inline]
+    struct F;
+    "#,
+    );
+}
+
+#[test]
+fn optimization_levels() {
+    let text = r#"
+    fn fibonacci(n:int):int {
+        let mut a = 0;
+        let mut b = 1;
+        let mut i = 1;
+        while i <= n {
+            let sum = a + b;
+            a = b;
+            b = sum;
+            i += 1;
+        }
+        a
+    }
+    "#;
+
+    // Counts the instructions in the IR of the module, which are the only indented lines
+    let instruction_count = |optimization_lvl| {
+        let (mut db, file_id) = MockDatabase::with_single_file(text);
+        db.set_optimization_lvl(optimization_lvl);
+        let ir = db.module_ir(file_id);
+        assert!(ir
+            .llvm_module
+            .get_function(&mangle(&["main", "fibonacci"]))
+            .is_some());
+        ir.llvm_module
+            .print_to_string()
+            .to_string()
+            .lines()
+            .filter(|line| line.starts_with("  "))
+            .count()
+    };
+
+    assert!(
+        instruction_count(OptimizationLevel::None) > instruction_count(OptimizationLevel::Default)
+    );
+}
+
+#[test]
+fn debug_info() {
+    let text = r#"
+    fn add(a:int, b:int):int {
+        a + b
+    }
+    fn apply(f: fn(int): int, v: int): int { f(v) }
+    fn main():int {
+        apply(|x| x * 2, add(1, 2))
+    }
+    "#;
+
+    let ir = |debug_info| {
+        let (mut db, file_id) = MockDatabase::with_single_file(text);
+        db.set_debug_info(debug_info);
+        db.set_optimization_lvl(OptimizationLevel::None);
+        db.module_ir(file_id)
+            .llvm_module
+            .print_to_string()
+            .to_string()
+    };
+
+    // The file is a single compile unit, in which every function and closure has a subprogram on
+    // the line of its definition, and the instructions of an expression are attributed to the line
+    // and column at which it starts
+    let ir_with_debug_info = ir(true);
+    for expected in &[
+        "!\"Debug Info Version\"",
+        "!DICompileUnit(language: DW_LANG_C,",
+        "!DIFile(filename: \"main.mun\"",
+        "!DISubprogram(name: \"add\", linkageName: \"_MN4main3addE\"",
+        "!DISubprogram(name: \"main\", linkageName: \"_MN4main4mainE\"",
+        "!DISubprogram(name: \"_MN4main4main10{closure0}E\"",
+        "line: 2, type:",
+        "!DILocation(line: 3, column: 9,",
+        "!DILocation(line: 7, column: 19,",
+    ] {
+        assert!(
+            ir_with_debug_info.contains(expected),
+            "missing {}: {}",
+            expected,
+            ir_with_debug_info
+        );
+    }
+    assert_eq!(
+        ir_with_debug_info.matches("!DICompileUnit(").count(),
+        1,
+        "{}",
+        ir_with_debug_info
+    );
+
+    let ir_without_debug_info = ir(false);
+    assert!(
+        !ir_without_debug_info.contains("!DISubprogram"),
+        "{}",
+        ir_without_debug_info
+    );
+}
+
+#[test]
+fn emit_artifacts() {
+    let (mut db, file_id) = MockDatabase::with_single_file(
+        r#"
+    fn add(a:int, b:int):int {
+        a+b
+    }
+    "#,
+    );
+    db.set_target(Target::search("x86_64-unknown-linux-gnu").unwrap());
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let library_path = temp_dir.path().join("main.so");
+    write_module_artifacts(
+        &db,
+        file_id,
+        &library_path,
+        &[
+            Artifact::Ir,
+            Artifact::UnoptimizedIr,
+            Artifact::Asm,
+            Artifact::Obj,
+            Artifact::Metadata,
+        ],
+    )
+    .unwrap();
+
+    let defines_add = |ir: &str| {
+        ir.lines()
+            .any(|line| line.starts_with("define") && line.contains("@_MN4main3addE("))
+    };
+    let ir = fs::read_to_string(temp_dir.path().join("main.ll")).unwrap();
+    assert!(defines_add(&ir), "missing definition of `add` in:\n{}", ir);
+    let unoptimized_ir = fs::read_to_string(temp_dir.path().join("main.unopt.ll")).unwrap();
+    assert!(defines_add(&unoptimized_ir));
+
+    let asm = fs::read_to_string(temp_dir.path().join("main.s")).unwrap();
+    assert!(asm.contains("_MN4main3addE:"));
+    let obj = fs::read(temp_dir.path().join("main.o")).unwrap();
+    assert!(obj.starts_with(b"\x7fELF"));
+    let metadata = fs::read_to_string(temp_dir.path().join("main.mun.json")).unwrap();
+    let metadata = mun_abi::metadata::AssemblyMetadata::from_json(&metadata).unwrap();
+    assert_eq!(metadata.root.functions[0].name, "add");
+
+    // The shared library was not requested
+    assert!(!library_path.exists());
+}
+
+#[test]
+fn overflow_checks() {
+    let text = r#"
+    fn add(a:u8, b:u8):u8 { a + b }
+    fn multiply(a:int, b:int):int { a * b }
+    fn divide(a:int, b:int):int { a / b }
+    "#;
+
+    let ir = |overflow_checks| {
+        let (mut db, file_id) = MockDatabase::with_single_file(text);
+        db.set_overflow_checks(overflow_checks);
+        db.module_ir(file_id)
+            .llvm_module
+            .print_to_string()
+            .to_string()
+    };
+
+    let checked_ir = ir(true);
+    assert!(checked_ir.contains("@llvm.uadd.with.overflow.i8"));
+    assert!(checked_ir.contains("@llvm.smul.with.overflow.i64"));
+    assert!(!checked_ir.contains("div.with.overflow"));
+
+    let unchecked_ir = ir(false);
+    assert!(!unchecked_ir.contains(".with.overflow."));
+}
+
+#[test]
+fn division_checks() {
+    let text = r#"
+    fn divide(a:int, b:int):int { a / b }
+    fn remainder(a:u8, b:u8):u8 { a % b }
+    fn halve(a:int):int { a / 2 }
+    fn divide_float(a:float, b:float):float { a / b }
+    "#;
+
+    let ir = |division_checks| {
+        let (mut db, file_id) = MockDatabase::with_single_file(text);
+        db.set_division_checks(division_checks);
+        db.module_ir(file_id)
+            .llvm_module
+            .print_to_string()
+            .to_string()
+    };
+
+    // Only the integer operations with a divisor that may be zero are checked
+    let checked_ir = ir(true);
+    assert_eq!(
+        checked_ir.matches("division_by_zero_trap:").count(),
+        2,
+        "{}",
+        checked_ir
+    );
+    assert!(checked_ir.contains("fdiv double"), "{}", checked_ir);
+
+    let unchecked_ir = ir(false);
+    assert!(
+        !unchecked_ir.contains("division_by_zero_trap"),
+        "{}",
+        unchecked_ir
+    );
+}
+
+#[test]
+fn bounds_checks() {
+    let text = r#"
+    fn get(a:[int; 3], i:int):int { a[i] }
+    fn set(a:[int; 3], i:u8):int { a[i] = 1; a[0] }
+    fn first(a:[int; 3]):int { a[0] }
+    "#;
+
+    let ir = |bounds_checks| {
+        let (mut db, file_id) = MockDatabase::with_single_file(text);
+        db.set_bounds_checks(bounds_checks);
+        db.module_ir(file_id)
+            .llvm_module
+            .print_to_string()
+            .to_string()
+    };
+
+    // Reads and writes with a dynamic index are checked, literal indices are not
+    let checked_ir = ir(true);
+    assert_eq!(
+        checked_ir.matches("index_out_of_bounds_trap:").count(),
+        2,
+        "{}",
+        checked_ir
+    );
+    assert!(checked_ir.contains("icmp ult i64"), "{}", checked_ir);
+
+    let unchecked_ir = ir(false);
+    assert!(
+        !unchecked_ir.contains("index_out_of_bounds_trap"),
+        "{}",
+        unchecked_ir
+    );
+}
+
+#[test]
+fn bounds_checked_writes() {
+    let text = r#"
+    fn set(mut a:[int; 3], i:u8) { a[i] = 1; }
+    fn increment(mut a:[int; 3], i:u8) { a[i] += 1; }
+    "#;
+
+    let (mut db, file_id) = MockDatabase::with_single_file(text);
+    db.set_bounds_checks(true);
+    db.set_optimization_lvl(OptimizationLevel::None);
+    let ir = db
+        .module_ir(file_id)
+        .llvm_module
+        .print_to_string()
+        .to_string();
+
+    // The index of a write is checked once, also when the write loads the current value
+    assert_eq!(
+        ir.matches("private constant [1 x { i8*, i8*, i32, i8 }]")
+            .count(),
+        2,
+        "{}",
+        ir
+    );
+    assert_eq!(ir.matches("index_out_of_bounds_trap:").count(), 2, "{}", ir);
+    assert!(!ir.contains("index_out_of_bounds_trap1"), "{}", ir);
+}
+
+#[test]
+fn trap_sites() {
+    let text = r#"
+    fn divide(a:int, b:int):int {
+        let q = a / b;
+        q + a % b
+    }
+    fn add(a:int, b:int):int { a + b }
+    "#;
+
+    let (mut db, file_id) = MockDatabase::with_single_file(text);
+    db.set_division_checks(true);
+    db.set_optimization_lvl(OptimizationLevel::None);
+    let ir = db
+        .module_ir(file_id)
+        .llvm_module
+        .print_to_string()
+        .to_string();
+
+    // The table of a function has an entry for every check, with its line and kind, and every
+    // trap passes the index of its entry
+    assert!(
+        ir.contains("@trap_sites = private constant [2 x { i8*, i8*, i32, i8 }]"),
+        "{}",
+        ir
+    );
+    assert!(ir.contains("i32 3, i8 1 }"), "{}", ir);
+    assert!(ir.contains("i32 4, i8 1 }"), "{}", ir);
+    let trap_calls: Vec<&str> = ir
+        .lines()
+        .filter(|line| line.contains("call void") && line.contains("@trap_sites"))
+        .collect();
+    assert_eq!(trap_calls.len(), 2, "{}", ir);
+    assert!(trap_calls[0].ends_with(", i32 0)"), "{}", ir);
+    assert!(trap_calls[1].ends_with(", i32 1)"), "{}", ir);
+}
+
+#[test]
+fn float_semantics() {
+    let text = r#"
+    fn add(a:float, b:float):float { a + b }
+    fn not_equal(a:f32, b:f32):bool { a != b }
+    "#;
+
+    let ir = |float_semantics| {
+        let (mut db, file_id) = MockDatabase::with_single_file(text);
+        db.set_float_semantics(float_semantics);
+        db.module_ir(file_id)
+            .llvm_module
+            .print_to_string()
+            .to_string()
+    };
+
+    // `!=` is true if either operand is NaN
+    let strict_ir = ir(FloatSemantics::Strict);
+    assert!(strict_ir.contains("fcmp une float"), "{}", strict_ir);
+    assert!(!strict_ir.contains("-fp-math"), "{}", strict_ir);
+
+    let fast_ir = ir(FloatSemantics::Fast);
+    assert!(
+        fast_ir.contains(r#""unsafe-fp-math"="true""#),
+        "{}",
+        fast_ir
+    );
+    assert!(
+        fast_ir.contains(r#""no-nans-fp-math"="true""#),
+        "{}",
+        fast_ir
+    );
+}
+
+#[test]
+fn wrapping_and_saturating_builtins() {
+    let text = r#"
+    fn wrapping(a:u8, b:u8):u8 { wrapping_add(a, b) }
+    fn saturating(a:i16, b:i16):i16 { saturating_sub(a, b) }
+    "#;
+
+    let ir = |overflow_checks| {
+        let (mut db, file_id) = MockDatabase::with_single_file(text);
+        db.set_overflow_checks(overflow_checks);
+        db.module_ir(file_id)
+            .llvm_module
+            .print_to_string()
+            .to_string()
+    };
+
+    // The builtins neither trap nor depend on the overflow checks
+    for overflow_checks in &[true, false] {
+        let ir = ir(*overflow_checks);
+        assert!(!ir.contains("overflow_trap"), "{}", ir);
+        assert!(!ir.contains("@llvm.uadd.with.overflow.i8"), "{}", ir);
+        assert!(ir.contains("@llvm.ssub.with.overflow.i16"), "{}", ir);
+    }
+}
+
+#[test]
+fn inline_attributes() {
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    #[inline]
+    fn hint():int { 1 }
+
+    #[inline(always)]
+    fn always():int { 2 }
+
+    #[inline(never)]
+    fn never():int { 3 }
+
+    fn none():int { 4 }
+    "#,
+    );
+
+    let ir = db.module_ir(file_id);
+    let has_attribute = |function: &str, attribute: &str| {
+        ir.llvm_module
+            .get_function(&mangle(&["main", function]))
+            .unwrap()
+            .get_enum_attribute(
+                AttributeLoc::Function,
+                Attribute::get_named_enum_kind_id(attribute),
+            )
+            .is_some()
+    };
+
+    assert!(has_attribute("hint", "inlinehint"));
+    assert!(has_attribute("always", "alwaysinline"));
+    assert!(has_attribute("never", "noinline"));
+    for attribute in &["inlinehint", "alwaysinline", "noinline"] {
+        assert!(!has_attribute("none", attribute));
+    }
+}
+
+#[test]
+fn invalid_attributes() {
+    test_snapshot(
+        r#"
+    #[foo]
+    fn a() {}
+
+    #[inline(sometimes)]
+    fn b() {}
+
+    #[inline(always, never)]
+    fn c() {}
+    "#,
+    );
+}
+
+#[test]
+fn const_folding() {
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    const SECONDS_PER_MINUTE: int = 60;
+    const SECONDS_PER_HOUR: int = SECONDS_PER_MINUTE * 60;
+    const GRAVITY: float = -9.81;
+
+    fn seconds_per_hour():int { SECONDS_PER_HOUR }
+    fn seconds_per_day():int { 24 * 60 * 60 }
+    fn gravity():float { GRAVITY }
+    "#,
+    );
+
+    let ir = db
+        .module_ir(file_id)
+        .llvm_module
+        .print_to_string()
+        .to_string();
+    assert!(ir.contains("ret i64 3600"), "{}", ir);
+    assert!(ir.contains("ret i64 86400"), "{}", ir);
+    assert!(!ir.contains("mul"), "{}", ir);
+
+    // Constants don't occupy runtime storage
+    assert!(!ir.contains("SECONDS_PER"), "{}", ir);
+    assert!(!ir.contains("GRAVITY"), "{}", ir);
+}
+
+#[test]
+fn invalid_consts() {
+    test_snapshot(
+        r#"
+    const A: int = B + 1;
+    const B: int = A * 2;
+    const C: int = foo();
+    const D: int = 1 / 0;
+    const E: u8 = 255 + 1;
+
+    fn foo():int { 1 }
+    "#,
+    );
+}
+
+#[test]
+fn invalid_type_aliases() {
+    test_snapshot(
+        r#"
+    type A = B;
+    type B = (A, int);
+    type C = Missing;
+    type D = [A; 2];
+
+    fn foo(c: C, d: D) {}
+    "#,
+    );
+}
+
+#[test]
+fn enums() {
+    test_snapshot(
+        r#"
+    enum State { Idle, Walking, Attacking = 5 }
+    enum Offset { Back = -1, Forward = 1 }
+
+    fn is_idle(s: State): bool { s == State::Idle }
+    fn code(s: State): u8 { s as u8 }
+    fn wide_code(s: State): int { s as int }
+    fn offset(o: Offset): int { o as int }
+    fn attacking(): State { State::Attacking }
+    "#,
+    );
+}
+
+#[test]
+fn invalid_enums() {
+    test_snapshot(
+        r#"
+    enum A { X = 1, Y = 1 }
+    enum B { X = foo }
+    enum C { X = 18446744073709551616 }
+    enum D { X = -1, Y = 18446744073709551615 }
+
+    fn bar(a: A): bool { a < a }
+    "#,
+    );
+}
+
+#[test]
+fn invalid_const_contexts() {
+    test_snapshot(
+        r#"
+    fn foo(): int { 1 }
+    const N: int = 4;
+
+    fn a(values: [int; foo()]) {}
+    fn b(values: [int; 2 * N]) {}
+    fn c(values: [int; 2 - 3]) {}
+    fn d(values: [int; 1.5 * 2]) {}
+    fn e(values: [int; 4 / (2 - 2)]) {}
+    fn f(values: [int; 1 << 128]) {}
+    enum E { A = 1 << 4, B = 1 < 2, C = foo() }
+    enum F { A = -1u8, B = 255u8, C = 256u8 }
+    fn g(a: float): float { a << 1 }
+    "#,
+    );
+}
+
+#[test]
+fn match_expr() {
+    test_snapshot(
+        r#"
+    fn foo(a: int, b: int): int {
+        match a {
+            0 => b + 1,
+            -1 => b * 3,
+            _ => b - 1,
+        }
+    }
+    "#,
+    );
+}
+
+#[test]
+fn invalid_match() {
+    test_snapshot(
+        r#"
+    enum State { Idle, Walking, Attacking }
+
+    fn foo(a: int, b: bool, s: State, x: float): int {
+        let c = match a { 0 => 1, 1 => 2 };
+        let d = match b { true => 1 };
+        let e = match s { State::Walking => 1 };
+        let f = match s { State::Idle => 1, _ => 2, State::Walking => 3 };
+        let g = match a { 0 => 1, 0 => 2, _ => 3 };
+        match x { 1.5 => 1, _ => 2 }
+    }
+    "#,
+    );
+}
+
+#[test]
+fn invalid_methods() {
+    test_snapshot(
+        r#"
+    struct Vector { x: float }
+    enum State { Idle }
+
+    impl Vector {
+        fn x(self): float { 0.0 }
+        fn len(self): float { 1.0 }
+        fn len(self): float { 2.0 }
+    }
+
+    impl State {}
+    impl Foo {}
+
+    fn foo(self) {}
+    "#,
+    );
+}
+
+#[test]
+fn statics() {
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    static mut SCORE: int = 10;
+    static LIMIT: int = 60 * 60;
+
+    fn increment() { SCORE += 1; }
+    fn limit():int { LIMIT }
+    "#,
+    );
+
+    let ir = db
+        .module_ir(file_id)
+        .llvm_module
+        .print_to_string()
+        .to_string();
+    assert!(ir.contains("@SCORE = global i64 10"), "{}", ir);
+    assert!(ir.contains("@LIMIT = constant i64 3600"), "{}", ir);
+    assert!(ir.contains("load i64, i64* @SCORE"), "{}", ir);
+    assert!(ir.contains("store i64"), "{}", ir);
+}
+
+#[test]
+fn invalid_statics() {
+    test_snapshot(
+        r#"
+    static A: int = foo();
+    static mut B: int = A;
+    static C: int = 10;
+
+    fn foo():int { 1 }
+    fn bar() { C = 5; }
+    "#,
+    );
+}
+
+#[test]
+fn unsupported_types() {
+    test_snapshot(
+        r#"
+    fn main() {
+        let a;
+    }
+    fn foo(a: never) {}
+    struct Bar { b: never }
+    fn baz():int { 5 }
+    "#,
+    );
+}
+
+#[test]
+fn unsupported_types_are_skipped() {
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    fn main() {
+        let a;
+    }
+    fn foo(a: never) {}
+    fn baz():int { 5 }
+    "#,
+    );
+
+    // The rest of the module is generated without panicking
+    let ir = db
+        .module_ir(file_id)
+        .llvm_module
+        .print_to_string()
+        .to_string();
+    assert!(ir.contains("define i64 @_MN4main3bazE()"), "{}", ir);
+    assert!(ir.contains("unreachable"), "{}", ir);
+    assert!(!ir.contains("@_MN4main3fooE"), "{}", ir);
+}
+
+#[test]
+fn string_literals() {
+    let (db, file_id) = MockDatabase::with_single_file(
+        r#"
+    extern fn log(message: str);
+
+    fn main() { log("game_over"); }
+    fn message():str { "tab\t" }
+    "#,
+    );
+
+    let ir = db
+        .module_ir(file_id)
+        .llvm_module
+        .print_to_string()
+        .to_string();
+    assert!(
+        ir.contains(r#"private unnamed_addr constant [10 x i8] c"game_over\00""#),
+        "{}",
+        ir
+    );
+    assert!(
+        ir.contains(r#"private unnamed_addr constant [5 x i8] c"tab\09\00""#),
+        "{}",
+        ir
+    );
+    assert!(ir.contains("i64 9 }"), "{}", ir);
+    assert!(ir.contains("i64 4 }"), "{}", ir);
+}
+
+#[test]
+fn pass_pipeline() {
+    let main_ir = |pipeline| {
+        let (mut db, file_id) = MockDatabase::with_single_file(
+            r#"
+        fn main(a:int):int {
+            let b = a + 1;
+            b
+        }
+        "#,
+        );
+        db.set_optimization_lvl(OptimizationLevel::None);
+        db.set_pass_pipeline(pipeline);
+        db.set_target(Target::search("x86_64-unknown-linux-gnu").unwrap());
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let library_path = temp_dir.path().join("main.so");
+        write_module_artifacts(&db, file_id, &library_path, &[Artifact::Ir]).unwrap();
+        let ir = fs::read_to_string(temp_dir.path().join("main.ll")).unwrap();
+
+        // Only the definition of `main` is of interest
+        let main_ir = ir
+            .lines()
+            .skip_while(|line| !(line.starts_with("define") && line.contains("@_MN4main4mainE(")))
+            .take_while(|line| *line != "}")
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(
+            !main_ir.is_empty(),
+            "missing definition of `main` in:\n{}",
+            ir
+        );
+        main_ir
+    };
+
+    // Without optimizations, locals are stored on the stack
+    let default_ir = main_ir(PassPipeline::Default);
+    assert!(default_ir.contains("alloca"), "{}", default_ir);
+    let mem2reg_ir = main_ir("mem2reg".parse().unwrap());
+    assert!(!mem2reg_ir.contains("alloca"), "{}", mem2reg_ir);
+}
+
+#[test]
+fn parse_pass_pipeline() {
+    assert_eq!("default".parse(), Ok(PassPipeline::Default));
+    assert_eq!(
+        "mem2reg, instcombine".parse(),
+        Ok(PassPipeline::Passes(vec![
+            Pass::PromoteMemoryToRegister,
+            Pass::InstructionCombining
+        ]))
+    );
+    assert_eq!("".parse::<PassPipeline>(), Err(InvalidPassPipeline::Empty));
+
+    let err = "mem2reg,mem3reg".parse::<PassPipeline>().unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("unknown pass `mem3reg`, expected one of: mem2reg, sroa,"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn parallel_codegen_units() {
+    // Every function does some arithmetic and calls the previous one, which gives the optimizer
+    // and the code generator work to spread over the units
+    let mut text = "fn f0(a:int):int { a }\n".to_owned();
+    for i in 1..500 {
+        text.push_str(&format!(
+            "fn f{}(a:int):int {{ let b = a * {} + 1; if b > 100 {{ f{}(b - 100) }} else {{ b }} }}\n",
+            i,
+            i,
+            i - 1
+        ));
+    }
+    let (mut db, file_id) = MockDatabase::with_single_file(&text);
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let mut library = |codegen_units: usize, name: &str| {
+        db.set_codegen_units(codegen_units);
+        let library_path = temp_dir.path().join(name);
+        write_module_artifacts(&db, file_id, &library_path, &[Artifact::Lib]).unwrap();
+        fs::read(&library_path).unwrap()
+    };
+
+    let serial = library(1, "serial.so");
+    let parallel = library(4, "parallel.so");
+    assert!(serial.starts_with(b"\x7fELF"));
+    assert!(parallel.starts_with(b"\x7fELF"));
+
+    // Splitting the module into units is deterministic
+    let again = library(4, "again.so");
+    assert!(
+        parallel == again,
+        "codegen units emitted different libraries"
+    );
+
+    // Every unit emits a different part of the module
+    let objects = units::emit_objects(
+        &db.module_ir(file_id).llvm_module,
+        4,
+        &db.target(),
+        OptimizationLevel::Default,
+        &PassPipeline::Default,
+    )
+    .unwrap();
+    assert_eq!(objects.len(), 4);
+    for (idx, object) in objects.iter().enumerate() {
+        assert!(
+            !objects[idx + 1..].contains(object),
+            "unit {} emitted the same object as another unit",
+            idx
+        );
+    }
+}
+
+#[test]
+fn reproducible_libraries() {
+    let mut text = r"
+    struct Vector2 { x: float, y: float }
+    struct(value) Size { width: u32, height: u32 }
+    "
+    .to_owned();
+    for i in 0..50 {
+        text.push_str(&format!(
+            "static mut S{0}: int = {0};\nfn f{0}(v:Vector2, s:Size):float {{ S{0} += 1; v.x * {0}.0 }}\n",
+            i
+        ));
+    }
+
+    // Every compilation uses a fresh database, so the iteration order of its maps differs
+    let library_hash = || {
+        let (db, file_id) = MockDatabase::with_single_file(&text);
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let library_path = temp_dir.path().join("main.so");
+        write_module_artifacts(&db, file_id, &library_path, &[Artifact::Lib]).unwrap();
+        md5::compute(fs::read(&library_path).unwrap())
+    };
+    assert_eq!(library_hash(), library_hash());
+}
+
+#[test]
+fn dead_code_elimination() {
+    let text = r"
+    export fn main():int { helper() }
+    fn helper():int { nested() }
+    fn nested():int { 3 }
+    fn unused():int { nested() }
+    ";
+    let (mut db, file_id) = MockDatabase::with_single_file(text);
+
+    let generated = |db: &MockDatabase| {
+        let mut names: Vec<String> = db
+            .module_ir(file_id)
+            .functions
+            .keys()
+            .map(|f| f.name(db).to_string())
+            .collect();
+        names.sort();
+        names
+    };
+    let warnings = |db: &MockDatabase| {
+        let mut messages = Vec::new();
+        let mut sink = DiagnosticSink::new(|diag| messages.push(diag.message()));
+        Module::from(file_id).diagnostics(db, &mut sink);
+        diagnostics::diagnostics(db, file_id, &mut sink);
+        drop(sink);
+        messages
+    };
+
+    // A helper that is only called by another private function is retained
+    assert_eq!(generated(&db), vec!["helper", "main", "nested"]);
+    assert_eq!(warnings(&db), vec!["function `unused` is never used"]);
+    let ir = db
+        .module_ir(file_id)
+        .llvm_module
+        .print_to_string()
+        .to_string();
+    assert!(!ir.contains("@_MN4main6unusedE"), "{}", ir);
+
+    // The function is still unused when its code is generated
+    db.set_dead_code_elimination(false);
+    assert_eq!(generated(&db), vec!["helper", "main", "nested", "unused"]);
+    assert_eq!(warnings(&db), vec!["function `unused` is never used"]);
+
+    // A file without `export` functions keeps all of its functions
+    db.set_dead_code_elimination(true);
+    db.set_file_text(file_id, Arc::new(text.replace("export ", "")));
+    assert_eq!(generated(&db), vec!["helper", "main", "nested", "unused"]);
+    assert!(warnings(&db).is_empty());
+}
+
+#[test]
+fn test_functions() {
+    let text = r"
+    export fn main():int { helper() }
+    fn helper():int { 3 }
+    fn expected():int { 3 }
+    #[test]
+    fn main_returns_three() { let _a = main() == expected(); }
+    ";
+    let (mut db, file_id) = MockDatabase::with_single_file(text);
+
+    let generated = |db: &MockDatabase| {
+        let mut names: Vec<String> = db
+            .module_ir(file_id)
+            .functions
+            .keys()
+            .map(|f| f.name(db).to_string())
+            .collect();
+        names.sort();
+        names
+    };
+
+    // Tests and the functions that only they use are left out of normal builds, without warnings
+    assert_eq!(generated(&db), vec!["helper", "main"]);
+    let mut messages = Vec::new();
+    let mut sink = DiagnosticSink::new(|diag| messages.push(diag.message()));
+    Module::from(file_id).diagnostics(&db, &mut sink);
+    drop(sink);
+    assert!(messages.is_empty(), "{:?}", messages);
+
+    db.set_dead_code_elimination(false);
+    assert_eq!(generated(&db), vec!["expected", "helper", "main"]);
+
+    db.set_test_build(true);
+    assert_eq!(
+        generated(&db),
+        vec!["expected", "helper", "main", "main_returns_three"]
+    );
+    db.set_dead_code_elimination(true);
+    assert_eq!(
+        generated(&db),
+        vec!["expected", "helper", "main", "main_returns_three"]
+    );
+}
+
+#[test]
+fn invalid_test_functions() {
+    test_snapshot(
+        r#"
+    #[test(should_panic)]
+    fn with_arguments() {}
+
+    #[test]
+    fn with_parameters(_a: int) {}
+
+    #[test]
+    fn with_return_type(): int { 0 }
+
+    #[test]
+    export fn exported() {}
+
+    #[test]
+    extern fn external();
+    "#,
+    );
+}
+
+#[test]
+fn tail_calls() {
+    let text = r"
+    fn countdown(n:int):int { if n == 0 { 0 } else { countdown(n - 1) } }
+    fn sum(n:int, acc:int):int {
+        if n == 0 {
+            return acc
+        }
+        return sum(n - 1, acc + n)
+    }
+    fn factorial(n:int):int { if n <= 1 { 1 } else { n * factorial(n - 1) } }
+    ";
+    let (mut db, file_id) = MockDatabase::with_single_file(text);
+
+    // Tail calls jump back to the start of the body, only `factorial` still calls itself
+    let ir = db
+        .module_ir(file_id)
+        .llvm_module
+        .print_to_string()
+        .to_string();
+    assert!(!ir.contains("%countdown = call"), "{}", ir);
+    assert!(!ir.contains("%sum = call"), "{}", ir);
+    assert!(ir.contains("%factorial = call"), "{}", ir);
+    assert!(verify_module(&db.module_ir(file_id).llvm_module, None).is_ok());
+
+    let notes = |db: &MockDatabase| {
+        let mut messages = Vec::new();
+        let mut sink = DiagnosticSink::new(|diag| messages.push(diag.message()));
+        diagnostics::diagnostics(db, file_id, &mut sink);
+        drop(sink);
+        messages
+    };
+    assert!(notes(&db).is_empty());
+    db.set_tail_call_notes(true);
+    assert_eq!(
+        notes(&db),
+        vec!["recursive call to `factorial` is not in tail position, so it is not turned into a loop"]
+    );
+}
+
+#[test]
+fn invalid_ir_is_reported() {
+    let context = crate::Context::create();
+    let module = context.create_module("main.mun");
+    let fn_type = context.void_type().fn_type(&[], false);
+
+    let valid = module.add_function("valid", fn_type, None);
+    let builder = context.create_builder();
+    builder.position_at_end(&context.append_basic_block(&valid, "body"));
+    builder.build_return(None);
+
+    // A basic block must end with a terminator
+    let invalid = module.add_function("invalid", fn_type, None);
+    context.append_basic_block(&invalid, "body");
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let dump_path = temp_dir.path().join("main.invalid.ll");
+    let err = verify_module(&module, Some(&dump_path)).unwrap_err();
+    assert_eq!(err.module, "main.mun");
+    assert_eq!(err.functions, vec!["invalid".to_string()]);
+    assert!(!err.message.is_empty());
+    assert_eq!(err.dump_path, Some(dump_path.clone()));
+    assert!(fs::read_to_string(&dump_path)
+        .unwrap()
+        .contains("define void @invalid()"));
+
+    let message = err.to_string();
+    assert!(
+        message.starts_with(
+            "internal compiler error: invalid IR was generated for module `main.mun` in `invalid`"
+        ),
+        "{}",
+        message
+    );
+    assert!(
+        message.contains(&dump_path.display().to_string()),
+        "{}",
+        message
+    );
+
+    // Without a dump path the IR is not written
+    let err = verify_module(&module, None).unwrap_err();
+    assert_eq!(err.dump_path, None);
+
+    // Generated modules are valid
+    let (db, file_id) = MockDatabase::with_single_file("fn main():int { 5 }");
+    assert!(verify_module(&db.module_ir(file_id).llvm_module, None).is_ok());
+}
+
+/// Returns the function called `name` in the file.
+fn function_by_name(db: &MockDatabase, file_id: mun_hir::FileId, name: &str) -> mun_hir::Function {
+    db.module_data(file_id)
+        .definitions()
+        .iter()
+        .find_map(|def| match def {
+            ModuleDef::Function(f) if f.name(db).to_string() == name => Some(*f),
+            _ => None,
+        })
+        .unwrap()
+}
+
+/// Returns the functions whose IR was generated while running `f`.
+fn executed_function_ir(db: &MockDatabase, f: impl FnOnce()) -> Vec<String> {
+    db.log_executed(f)
+        .into_iter()
+        .filter(|event| event.contains("function_ir("))
+        .collect()
+}
+
+#[test]
+fn incremental_function_ir() {
+    // Every function calls the previous one, so all of them are in the dispatch table
+    let fixture = |changed_body: &str| {
+        let mut text = "fn f0(a:int):int { a }\n".to_owned();
+        for i in 1..200 {
+            let body = if i == 100 {
+                changed_body.to_owned()
+            } else {
+                format!("f{}(a) + 1", i - 1)
+            };
+            text.push_str(&format!("fn f{}(a:int):int {{ {} }}\n", i, body));
+        }
+        text
+    };
+
+    let (mut db, file_id) = MockDatabase::with_single_file(&fixture("f99(a) + 1"));
+    let executed = executed_function_ir(&db, || {
+        db.module_ir(file_id);
+    });
+    assert_eq!(executed.len(), 200);
+
+    // Changing a single body only generates the IR of that function again
+    db.set_file_text(file_id, Arc::new(fixture("f99(a) + 42")));
+    let executed = executed_function_ir(&db, || {
+        db.module_ir(file_id);
+    });
+    let f100 = function_by_name(&db, file_id, "f100");
+    assert_eq!(executed, vec![format!("function_ir({:?})", f100)]);
+
+    let ir = db
+        .function_ir(f100)
+        .llvm_module
+        .print_to_string()
+        .to_string();
+    assert!(ir.contains(", 42"), "{}", ir);
+    assert!(verify_module(&db.module_ir(file_id).llvm_module, None).is_ok());
+}
+
+#[test]
+fn incremental_function_ir_signature_change() {
+    for call_mode in [CallMode::Dispatch, CallMode::Direct].iter() {
+        let (mut db, file_id) = MockDatabase::with_single_file(
+            r#"
+        fn foo(a:u8):u8 { a }
+        fn bar() { foo(1); }
+        fn baz():int { 3 }
+        "#,
+        );
+        db.set_call_mode(*call_mode);
+        db.module_ir(file_id);
+
+        // Changing the signature of `foo` invalidates its caller, but not the other functions
+        db.set_file_text(
+            file_id,
+            Arc::new(
+                r#"
+        fn foo(a:u16):u16 { a }
+        fn bar() { foo(1); }
+        fn baz():int { 3 }
+        "#
+                .to_owned(),
+            ),
+        );
+        let executed = executed_function_ir(&db, || {
+            db.module_ir(file_id);
+        });
+        let bar = function_by_name(&db, file_id, "bar");
+        let baz = function_by_name(&db, file_id, "baz");
+        assert!(
+            executed.contains(&format!("function_ir({:?})", bar)),
+            "{:?}",
+            executed
+        );
+        assert!(
+            !executed.contains(&format!("function_ir({:?})", baz)),
+            "{:?}",
+            executed
+        );
+
+        // The caller uses the new signature
+        let bar_ir = db
+            .function_ir(bar)
+            .llvm_module
+            .print_to_string()
+            .to_string();
+        assert!(bar_ir.contains("i16 (i16)"), "{}", bar_ir);
+        assert!(!bar_ir.contains("i8 (i8)"), "{}", bar_ir);
+        assert!(verify_module(&db.module_ir(file_id).llvm_module, None).is_ok());
+    }
+}
+
+#[test]
+fn mangled_function_names() {
+    let (mut db, file_ids) = MockDatabase::with_files(&[
+        (
+            "main.mun",
+            "fn update():int { 1 }\nfn tick():int { update() + enemy::ai::update() }",
+        ),
+        ("enemy/ai.mun", "export fn update():int { 2 }"),
+    ]);
+    db.set_target(Target::search("x86_64-unknown-linux-gnu").unwrap());
+
+    // The module of the root file contains the functions of all modules of the package, functions
+    // with the same name in different modules don't collide
+    let module = db.module_ir(file_ids[0]).llvm_module.clone();
+    for (symbol, path) in &[
+        ("_MN4main6updateE", "main::update"),
+        ("_MN4main4tickE", "main::tick"),
+        ("_MN5enemy2ai6updateE", "enemy::ai::update"),
+    ] {
+        assert!(module.get_function(symbol).is_some(), "missing {}", symbol);
+        assert_eq!(mun_abi::demangle(symbol), Some(path.to_string()));
+    }
+
+    // The dispatch table and the ABI refer to functions by their path within the assembly
+    let dispatch_keys: Vec<String> = db
+        .dispatch_table(file_ids[0])
+        .entries()
+        .iter()
+        .map(|f| f.name(&db))
+        .collect();
+    assert_eq!(
+        dispatch_keys,
+        vec!["update".to_string(), "enemy::ai::update".to_string()]
+    );
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let library_path = temp_dir.path().join("main.so");
+    write_module_artifacts(&db, file_ids[0], &library_path, &[Artifact::Ir]).unwrap();
+    let ir = fs::read_to_string(temp_dir.path().join("main.ll")).unwrap();
+    assert!(ir.contains("c\"update\\00\""), "{}", ir);
+    assert!(ir.contains("c\"tick\\00\""), "{}", ir);
+    assert!(ir.contains("c\"enemy::ai\\00\""), "{}", ir);
+    assert!(ir.contains("c\"enemy::ai::update\\00\""), "{}", ir);
+}
+
+#[test]
+fn method_symbol_names() {
+    let (mut db, file_id) = MockDatabase::with_single_file(
+        r#"
+    struct Vector { x: float }
+    impl Vector {
+        fn new(): Vector { Vector { x: 3.0 } }
+        fn length(self): float { self.x }
+    }
+    fn length(): float { Vector::new().length() }
+    "#,
+    );
+    db.set_target(Target::search("x86_64-unknown-linux-gnu").unwrap());
+
+    // Methods are mangled with the name of their struct, so they don't collide with functions
+    let llvm_module = db.module_ir(file_id).llvm_module.clone();
+    for (symbol, path) in &[
+        ("_MN4main6Vector3newE", "main::Vector::new"),
+        ("_MN4main6Vector6lengthE", "main::Vector::length"),
+        ("_MN4main6lengthE", "main::length"),
+    ] {
+        assert!(
+            llvm_module.get_function(symbol).is_some(),
+            "missing {}",
+            symbol
+        );
+        assert_eq!(mun_abi::demangle(symbol), Some(path.to_string()));
+    }
+
+    let mut dispatch_keys: Vec<String> = db
+        .dispatch_table(file_id)
+        .entries()
+        .iter()
+        .map(|f| f.name(&db))
+        .collect();
+    dispatch_keys.sort();
+    assert_eq!(
+        dispatch_keys,
+        vec!["Vector::length".to_string(), "Vector::new".to_string()]
+    );
+}
+
+fn test_snapshot(text: &str) {
+    let text = text.trim().replace("\n    ", "\n");
+
+    let (db, file_id) = MockDatabase::with_single_file(&text);
+
+    let line_index: Arc<LineIndex> = db.line_index(file_id);
+    let messages = RefCell::new(Vec::new());
+    let mut sink = DiagnosticSink::new(|diag| {
+        let line_col = line_index.line_col(diag.highlight_range().start());
+        messages.borrow_mut().push(format!(
+            "error {}:{}: {}",
+            line_col.line + 1,
+            line_col.col + 1,
+            diag.message()
+        ));
+    })
+    // Warnings don't prevent generating IR
+    .on::<FloatLiteralOutOfRange, _>(|_| {})
+    .on::<UnreachableCode, _>(|_| {})
+    .on::<UnusedVariable, _>(|_| {})
+    .on::<UnusedFunction, _>(|_| {});
+    Module::from(file_id).diagnostics(&db, &mut sink);
+    if messages.borrow().is_empty() {
+        diagnostics::diagnostics(&db, file_id, &mut sink);
+    }
     drop(sink);
     let messages = messages.into_inner();
 
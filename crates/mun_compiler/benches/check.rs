@@ -0,0 +1,99 @@
+//! Compares checking a medium-sized file for errors with building it, which additionally generates,
+//! optimizes and links machine code. Run with `cargo bench -p mun_compiler`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use mun_compiler::{Config, Driver, FileId, PathOrInline, RelativePathBuf};
+use std::path::Path;
+
+/// The number of times the functions and structs of the fixture are repeated.
+const FIXTURE_REPETITIONS: usize = 50;
+
+/// Generates a file with `repetitions` copies of a few structs, methods and functions.
+fn medium_fixture(repetitions: usize) -> String {
+    (0..repetitions)
+        .map(|i| {
+            format!(
+                r#"
+struct Vector{i} {{ x: float, y: float }}
+
+impl Vector{i} {{
+    fn dot(self, other: Vector{i}): float {{
+        self.x * other.x + self.y * other.y
+    }}
+}}
+
+export fn length_squared{i}(x: float, y: float): float {{
+    let v = Vector{i} {{ x: x, y: y }};
+    v.dot(v)
+}}
+
+export fn fibonacci{i}(n: int): int {{
+    let mut a = 0;
+    let mut b = 1;
+    let mut i = 0;
+    while i < n {{
+        let c = a + b;
+        a = b;
+        b = c;
+        i += 1;
+    }}
+    a
+}}
+
+export fn clamp{i}(value: int, min: int, max: int): int {{
+    if value < min {{
+        min
+    }} else if value > max {{
+        max
+    }} else {{
+        value
+    }}
+}}
+"#,
+                i = i
+            )
+        })
+        .collect()
+}
+
+/// Constructs a driver that compiles `text` into `out_dir`.
+fn driver(text: &str, out_dir: &Path) -> (Driver, FileId) {
+    let config = Config {
+        out_dir: Some(out_dir.to_path_buf()),
+        ..Config::default()
+    };
+    Driver::with_file(
+        config,
+        PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("main.mun"),
+            contents: text.to_owned(),
+        },
+    )
+    .unwrap()
+}
+
+fn check_and_build(c: &mut Criterion) {
+    let text = medium_fixture(FIXTURE_REPETITIONS);
+    let out_dir = tempfile::tempdir().unwrap();
+
+    // Every iteration starts from a new driver, because the database memoizes all results
+    let mut group = c.benchmark_group("medium");
+    group.sample_size(10);
+    group.bench_function("check", |b| {
+        b.iter(|| {
+            let (driver, _) = driver(&text, out_dir.path());
+            black_box(driver.diagnostics());
+        })
+    });
+    group.bench_function("build", |b| {
+        b.iter(|| {
+            let (driver, file_id) = driver(&text, out_dir.path());
+            black_box(driver.diagnostics());
+            driver.write_assembly(file_id).unwrap();
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, check_and_build);
+criterion_main!(benches);
@@ -0,0 +1,64 @@
+//! Compares emitting the shared object of a file with many functions from a single codegen unit
+//! with emitting it from several units in parallel. The IR is generated before the measurement,
+//! so only the optimization, the machine code generation and the linking are timed. Run with
+//! `cargo bench -p mun_compiler --bench codegen_units`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use mun_compiler::{Config, Driver, PathOrInline, RelativePathBuf};
+
+/// The number of functions of the fixture.
+const FIXTURE_FUNCTIONS: usize = 500;
+
+/// The numbers of codegen units that are compared.
+const CODEGEN_UNITS: [usize; 3] = [1, 2, 4];
+
+/// Generates a file of `functions` functions, of which every function does some arithmetic and
+/// calls the previous one, which gives the optimizer and the code generator work to spread over
+/// the units.
+fn many_functions_fixture(functions: usize) -> String {
+    let mut text = "fn f0(a: int): int { a }\n".to_owned();
+    for i in 1..functions {
+        let visibility = if i == functions - 1 { "export " } else { "" };
+        text.push_str(&format!(
+            "{}fn f{}(a: int): int {{ let b = a * {} + 1; if b > 100 {{ f{}(b - 100) }} else {{ b }} }}\n",
+            visibility,
+            i,
+            i,
+            i - 1
+        ));
+    }
+    text
+}
+
+fn serial_and_parallel_codegen(c: &mut Criterion) {
+    let text = many_functions_fixture(FIXTURE_FUNCTIONS);
+    let out_dir = tempfile::tempdir().unwrap();
+
+    let mut group = c.benchmark_group("codegen_units");
+    group.sample_size(10);
+    for &codegen_units in CODEGEN_UNITS.iter() {
+        let config = Config {
+            out_dir: Some(out_dir.path().to_path_buf()),
+            codegen_units,
+            ..Config::default()
+        };
+        let (driver, file_id) = Driver::with_file(
+            config,
+            PathOrInline::Inline {
+                rel_path: RelativePathBuf::from("main.mun"),
+                contents: text.clone(),
+            },
+        )
+        .unwrap();
+
+        // The first build generates the IR, which the database reuses in the measured builds
+        driver.write_assembly(file_id).unwrap();
+        group.bench_function(BenchmarkId::from_parameter(codegen_units), |b| {
+            b.iter(|| driver.write_assembly(file_id).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, serial_and_parallel_codegen);
+criterion_main!(benches);
@@ -0,0 +1,80 @@
+//! Compares building a file with many functions from scratch with rebuilding it after a small
+//! edit, for which the database only checks and generates the changed function again. Run with
+//! `cargo bench -p mun_compiler --bench rebuild`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use mun_compiler::{Config, Driver, FileId, PathOrInline, RelativePathBuf};
+use std::path::Path;
+
+/// The number of functions of the fixture.
+const FIXTURE_FUNCTIONS: usize = 500;
+
+/// Generates a file of `functions` functions, of which every function calls the previous one. The
+/// body of the function in the middle adds `increment` instead of 1.
+fn many_functions_fixture(functions: usize, increment: usize) -> String {
+    let mut text = "fn f0(a: int): int { a }\n".to_owned();
+    for i in 1..functions {
+        let visibility = if i == functions - 1 { "export " } else { "" };
+        let increment = if i == functions / 2 { increment } else { 1 };
+        text.push_str(&format!(
+            "{}fn f{}(a: int): int {{ f{}(a) + {} }}\n",
+            visibility,
+            i,
+            i - 1,
+            increment
+        ));
+    }
+    text
+}
+
+/// Constructs a driver that compiles `text` into `out_dir`.
+fn driver(text: &str, out_dir: &Path) -> (Driver, FileId) {
+    let config = Config {
+        out_dir: Some(out_dir.to_path_buf()),
+        ..Config::default()
+    };
+    Driver::with_file(
+        config,
+        PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("main.mun"),
+            contents: text.to_owned(),
+        },
+    )
+    .unwrap()
+}
+
+fn cold_and_incremental_build(c: &mut Criterion) {
+    let text = many_functions_fixture(FIXTURE_FUNCTIONS, 1);
+    let out_dir = tempfile::tempdir().unwrap();
+
+    let mut group = c.benchmark_group("many_functions");
+    group.sample_size(10);
+    group.bench_function("cold", |b| {
+        b.iter(|| {
+            let (driver, file_id) = driver(&text, out_dir.path());
+            black_box(driver.diagnostics());
+            driver.write_assembly(file_id).unwrap();
+        })
+    });
+
+    // Every iteration changes the body of a single function, and builds the file again with the
+    // database of the previous iteration
+    let (mut driver, file_id) = driver(&text, out_dir.path());
+    driver.write_assembly(file_id).unwrap();
+    let mut increment = 1;
+    group.bench_function("edit", |b| {
+        b.iter(|| {
+            increment += 1;
+            driver.set_file_text(
+                file_id,
+                many_functions_fixture(FIXTURE_FUNCTIONS, increment),
+            );
+            black_box(driver.diagnostics());
+            driver.write_assembly(file_id).unwrap();
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, cold_and_incremental_build);
+criterion_main!(benches);
@@ -0,0 +1,80 @@
+//! Compiles Mun source to an assembly in memory, for tools that compile snippets without a package
+//! on disk, e.g. tests and editor previews.
+
+use crate::diagnostics::json::{JsonDiagnostic, Severity};
+use crate::{Config, Driver, PathOrInline, RelativePathBuf};
+use failure::Fail;
+use mun_codegen::{Artifact, ModuleArtifacts};
+
+/// The path of the file that the source is compiled as, relative to its package.
+const FILE_NAME: &str = "main.mun";
+
+/// An assembly that was compiled in memory.
+#[derive(Debug, Clone)]
+pub struct CompiledArtifact {
+    /// The contents of the shared library, which the runtime loads with
+    /// `RuntimeBuilder::from_bytes`
+    pub library: Vec<u8>,
+    /// The textual LLVM IR after optimization, if the configuration emits `Artifact::Ir`
+    pub ir: Option<String>,
+    /// The warnings and notes of the source
+    pub diagnostics: Vec<JsonDiagnostic>,
+}
+
+/// An error that prevented source from being compiled in memory.
+#[derive(Debug, Fail)]
+pub enum CompileError {
+    /// The source has errors. All diagnostics of the source are included, not just the errors.
+    #[fail(display = "the source has errors")]
+    Diagnostics(Vec<JsonDiagnostic>),
+
+    /// The assembly could not be generated, e.g. because it could not be linked.
+    #[fail(display = "{}", _0)]
+    Build(failure::Error),
+}
+
+impl From<failure::Error> for CompileError {
+    fn from(error: failure::Error) -> Self {
+        CompileError::Build(error)
+    }
+}
+
+/// Compiles `source` as the root module of a package with the settings of `config`. The shared
+/// library and the IR are generated in memory, so the output directory of the configuration is
+/// ignored, and of its artifacts only the IR is kept besides the shared library. The assembly
+/// cannot depend on other assemblies.
+///
+/// # Limitations
+///
+/// The linker only links files, so the objects and the shared library are written to temporary
+/// files while the library is linked. See `mun_codegen::emit_module_artifacts`.
+pub fn compile_str(source: &str, config: Config) -> Result<CompiledArtifact, CompileError> {
+    let emit_ir = config.emit.contains(&Artifact::Ir);
+    let config = Config {
+        out_dir: None,
+        dependencies: Vec::new(),
+        ..config
+    };
+
+    let (driver, file_id) = Driver::with_file(
+        config,
+        PathOrInline::Inline {
+            rel_path: RelativePathBuf::from(FILE_NAME),
+            contents: source.to_string(),
+        },
+    )?;
+    let diagnostics = driver.json_diagnostics();
+    if diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == Severity::Error)
+    {
+        return Err(CompileError::Diagnostics(diagnostics));
+    }
+
+    let ModuleArtifacts { library, ir } = driver.emit_assembly(file_id, emit_ir)?;
+    Ok(CompiledArtifact {
+        library,
+        ir,
+        diagnostics,
+    })
+}
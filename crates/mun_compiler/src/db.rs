@@ -1,4 +1,5 @@
 use mun_hir::salsa;
+use std::sync::Mutex;
 
 #[salsa::database(
     mun_hir::SourceDatabaseStorage,
@@ -9,18 +10,60 @@ use mun_hir::salsa;
 #[derive(Debug)]
 pub(crate) struct CompilerDatabase {
     runtime: salsa::Runtime<CompilerDatabase>,
+    /// The queries that were executed since counting started, if they are counted
+    executed_queries: Mutex<Option<ExecutedQueries>>,
+}
+
+/// The number of queries of the expensive phases of a build that were executed, rather than
+/// reused from an earlier build because their inputs didn't change.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutedQueries {
+    /// The number of files that were parsed
+    pub parsed_files: usize,
+    /// The number of bodies of which the types were inferred
+    pub inferred_bodies: usize,
+    /// The number of functions for which IR was generated
+    pub generated_functions: usize,
 }
 
 impl CompilerDatabase {
     pub fn new() -> Self {
         CompilerDatabase {
             runtime: salsa::Runtime::default(),
+            executed_queries: Mutex::new(None),
         }
     }
+
+    /// Runs `f` and returns its result, along with the queries that it executed.
+    pub fn count_executed_queries<T>(&self, f: impl FnOnce() -> T) -> (T, ExecutedQueries) {
+        *self.executed_queries.lock().unwrap() = Some(ExecutedQueries::default());
+        let result = f();
+        let executed = self.executed_queries.lock().unwrap().take();
+        (result, executed.unwrap_or_default())
+    }
 }
 
 impl salsa::Database for CompilerDatabase {
     fn salsa_runtime(&self) -> &salsa::Runtime<CompilerDatabase> {
         &self.runtime
     }
+
+    fn salsa_event(&self, event: impl Fn() -> salsa::Event<CompilerDatabase>) {
+        let mut executed = self.executed_queries.lock().unwrap();
+        let executed = match &mut *executed {
+            Some(executed) => executed,
+            None => return,
+        };
+        if let salsa::EventKind::WillExecute { database_key } = event().kind {
+            // `Debug` is the only way to inspect the query of a database key
+            let query = format!("{:?}", database_key);
+            if query.starts_with("parse(") {
+                executed.parsed_files += 1;
+            } else if query.starts_with("infer(") {
+                executed.inferred_bodies += 1;
+            } else if query.starts_with("function_ir(") {
+                executed.generated_functions += 1;
+            }
+        }
+    }
 }
@@ -1,38 +1,66 @@
-use mun_hir::diagnostics::{Diagnostic as HirDiagnostic, DiagnosticSink};
-use mun_hir::{FileId, HirDatabase, HirDisplay, Module};
-use mun_syntax::{ast, AstNode, SyntaxKind};
+use mun_codegen::IrDatabase;
+use mun_hir::diagnostics::{Diagnostic as HirDiagnostic, DiagnosticSink, ExpectedTypeSource};
+use mun_hir::lint::LintLevel;
+use mun_hir::{FileId, HirDatabase, HirDisplay, Module, Namespace, Ty, TypeCtor};
+use mun_syntax::{ast, ast::NameOwner, AstNode, SyntaxKind, SyntaxNode, SyntaxNodePtr, TextRange};
 use std::cell::RefCell;
 
 mod emit;
+pub mod json;
+#[cfg(test)]
+mod tests;
 
 pub use emit::Emit;
-use mun_errors::{Diagnostic, Level};
+use mun_errors::{Diagnostic, Label, Level, Suggestion};
 
 /// Constructs diagnostic messages for the given file.
-pub fn diagnostics(db: &impl HirDatabase, file_id: FileId) -> Vec<Diagnostic> {
+pub fn diagnostics(db: &impl IrDatabase, file_id: FileId) -> Vec<Diagnostic> {
     let parse = db.parse(file_id);
+    let line_index = db.line_index(file_id);
     let mut result = Vec::new();
 
     result.extend(parse.errors().iter().map(|err| Diagnostic {
         level: Level::Error,
+        code: None,
         loc: err.location(),
         message: format!("Syntax Error: {}", err),
+        labels: Vec::new(),
+        suggestions: Vec::new(),
     }));
 
     let result = RefCell::new(result);
+    // A warning is dropped if its lint is allowed in its scope, and is an error if it is denied
+    let lint_levels = db.lint_levels();
+    let push_lint = |d: &dyn HirDiagnostic, diagnostic: Diagnostic| {
+        let level = match mun_hir::lint::lint_level(db, &lint_levels, d) {
+            Some(LintLevel::Allow) => return,
+            Some(LintLevel::Deny) => Level::Error,
+            Some(LintLevel::Warn) | None => diagnostic.level,
+        };
+        result.borrow_mut().push(Diagnostic {
+            level,
+            ..diagnostic
+        });
+    };
     let mut sink = DiagnosticSink::new(|d| {
         result.borrow_mut().push(Diagnostic {
             level: Level::Error,
+            code: Some(d.code()),
             loc: d.highlight_range().into(),
             message: d.message(),
+            labels: Vec::new(),
+            suggestions: Vec::new(),
         });
     })
     .on::<mun_hir::diagnostics::UnresolvedValue, _>(|d| {
         let text = d.expr.to_node(&parse.tree().syntax()).text().to_string();
         result.borrow_mut().push(Diagnostic {
             level: Level::Error,
+            code: Some(d.code()),
             loc: d.highlight_range().into(),
             message: format!("could not find value `{}` in this scope", text),
+            labels: Vec::new(),
+            suggestions: Vec::new(),
         });
     })
     .on::<mun_hir::diagnostics::UnresolvedType, _>(|d| {
@@ -44,34 +72,433 @@ pub fn diagnostics(db: &impl HirDatabase, file_id: FileId) -> Vec<Diagnostic> {
             .to_string();
         result.borrow_mut().push(Diagnostic {
             level: Level::Error,
+            code: Some(d.code()),
             loc: d.highlight_range().into(),
             message: format!("could not find type `{}` in this scope", text),
+            labels: Vec::new(),
+            suggestions: Vec::new(),
         });
     })
     .on::<mun_hir::diagnostics::ExpectedFunction, _>(|d| {
         result.borrow_mut().push(Diagnostic {
             level: Level::Error,
+            code: Some(d.code()),
             loc: d.highlight_range().into(),
             message: format!("expected function, found `{}`", d.found.display(db)),
+            labels: Vec::new(),
+            suggestions: Vec::new(),
         });
     })
     .on::<mun_hir::diagnostics::MismatchedType, _>(|d| {
+        let (expected, labels) =
+            expected_type_annotation(db, file_id, d.expected_source.as_ref(), &d.expected);
         result.borrow_mut().push(Diagnostic {
             level: Level::Error,
+            code: Some(d.code()),
+            loc: d.highlight_range().into(),
+            message: mismatched_type_message(db, &expected, &d.expected, &d.found),
+            labels,
+            suggestions: Vec::new(),
+        });
+    })
+    .on::<mun_hir::diagnostics::ConflictingLiteralTypes, _>(|d| {
+        let literal = d.literal.to_node(&parse.tree().syntax()).text().to_string();
+        result.borrow_mut().push(Diagnostic {
+            level: Level::Error,
+            code: Some(d.code()),
             loc: d.highlight_range().into(),
             message: format!(
-                "expected `{}`, found `{}`",
+                "conflicting types for the literal `{}`: this use expects `{}`, but an earlier use \
+                 inferred `{}`",
+                literal,
                 d.expected.display(db),
                 d.found.display(db)
             ),
+            labels: vec![Label {
+                loc: d.literal.range().into(),
+                message: "the type of this literal is inferred from the uses of its value"
+                    .to_string(),
+            }],
+            suggestions: Vec::new(),
+        });
+    })
+    .on::<mun_hir::diagnostics::MissingElseBranch, _>(|d| {
+        result.borrow_mut().push(Diagnostic {
+            level: Level::Error,
+            code: Some(d.code()),
+            loc: d.highlight_range().into(),
+            message: format!(
+                "missing else branch, an `if` without `else` cannot evaluate to `{}`",
+                d.found.display(db)
+            ),
+            labels: Vec::new(),
+            suggestions: Vec::new(),
+        });
+    })
+    .on::<mun_hir::diagnostics::MissingReturnValue, _>(|d| {
+        let (expected, labels) =
+            expected_type_annotation(db, file_id, d.expected_source.as_ref(), &d.expected);
+        result.borrow_mut().push(Diagnostic {
+            level: Level::Error,
+            code: Some(d.code()),
+            loc: d.highlight_range().into(),
+            message: match d.exit.kind() {
+                SyntaxKind::IF_EXPR => {
+                    format!("this `if` is missing an `else` that returns `{}`", expected)
+                }
+                SyntaxKind::BREAK_EXPR => format!(
+                    "this `break` leaves the loop without a value of type `{}`",
+                    expected
+                ),
+                SyntaxKind::WHILE_EXPR | SyntaxKind::FOR_EXPR => format!(
+                    "this loop can end without returning a value of type `{}`",
+                    expected
+                ),
+                _ => format!(
+                    "this block ends without returning a value of type `{}`",
+                    expected
+                ),
+            },
+            labels,
+            suggestions: Vec::new(),
+        });
+    })
+    .on::<mun_hir::diagnostics::AccessUnknownField, _>(|d| {
+        result.borrow_mut().push(Diagnostic {
+            level: Level::Error,
+            code: Some(d.code()),
+            loc: d.highlight_range().into(),
+            message: format!(
+                "no field `{}` on type `{}`",
+                d.name,
+                d.receiver_ty.display(db)
+            ),
+            labels: Vec::new(),
+            suggestions: Vec::new(),
+        });
+    })
+    .on::<mun_hir::diagnostics::UnresolvedMethod, _>(|d| {
+        result.borrow_mut().push(Diagnostic {
+            level: Level::Error,
+            code: Some(d.code()),
+            loc: d.highlight_range().into(),
+            message: format!(
+                "no method `{}` on type `{}`",
+                d.name,
+                d.receiver_ty.display(db)
+            ),
+            labels: Vec::new(),
+            suggestions: Vec::new(),
+        });
+    })
+    .on::<mun_hir::diagnostics::CannotAssignToImmutable, _>(|d| {
+        let binding = line_index.line_col(d.binding.range().start());
+        result.borrow_mut().push(Diagnostic {
+            level: Level::Error,
+            code: Some(d.code()),
+            loc: d.highlight_range().into(),
+            message: format!(
+                "{}, which is defined at {}:{}; consider declaring it as `mut {}`",
+                d.message(),
+                binding.line + 1,
+                binding.col,
+                d.name
+            ),
+            labels: Vec::new(),
+            suggestions: ast::BindPat::cast(d.binding.to_node(&parse.tree().syntax()))
+                .map(|pat| Suggestion {
+                    loc: pat.syntax().text_range().into(),
+                    message: format!("declare it as `mut {}`", d.name),
+                    replacement: format!("mut {}", d.name),
+                })
+                .into_iter()
+                .collect(),
+        });
+    })
+    .on::<mun_hir::diagnostics::PossiblyUninitializedVariable, _>(|d| {
+        result.borrow_mut().push(Diagnostic {
+            level: Level::Error,
+            code: Some(d.code()),
+            loc: d.highlight_range().into(),
+            message: d.message(),
+            labels: vec![Label {
+                loc: d.binding.range().into(),
+                message: format!("`{}` is declared here without a value", d.name),
+            }],
+            suggestions: Vec::new(),
+        });
+    })
+    .on::<mun_hir::diagnostics::CannotIndex, _>(|d| {
+        result.borrow_mut().push(Diagnostic {
+            level: Level::Error,
+            code: Some(d.code()),
+            loc: d.highlight_range().into(),
+            message: format!("cannot index into a value of type `{}`", d.ty.display(db)),
+            labels: Vec::new(),
+            suggestions: Vec::new(),
+        });
+    })
+    .on::<mun_hir::diagnostics::CannotApplyBinaryOp, _>(|d| {
+        let bin_expr = ast::BinExpr::cast(d.expr.to_node(&parse.tree().syntax()));
+        let (lhs, rhs) = match &bin_expr {
+            Some(bin_expr) => (bin_expr.lhs(), bin_expr.rhs()),
+            None => (None, None),
+        };
+        let message = if d.mismatched_operands {
+            format!(
+                "`{}` cannot be applied to `{}` and `{}`",
+                d.op,
+                d.lhs.display(db),
+                d.rhs.display(db)
+            )
+        } else {
+            format!("`{}` cannot be applied to `{}`", d.op, d.lhs.display(db))
+        };
+        let labels = [(lhs, &d.lhs), (rhs.clone(), &d.rhs)]
+            .iter()
+            .filter_map(|(operand, ty)| {
+                operand.as_ref().map(|operand| Label {
+                    loc: operand.syntax().text_range().into(),
+                    message: format!("this operand has type `{}`", ty.display(db)),
+                })
+            })
+            .collect();
+        let suggestions = match rhs {
+            Some(rhs) if d.mismatched_operands => operand_suggestion(db, &rhs, &d.lhs, &d.rhs),
+            _ => None,
+        };
+        result.borrow_mut().push(Diagnostic {
+            level: Level::Error,
+            code: Some(d.code()),
+            loc: d.highlight_range().into(),
+            message,
+            labels,
+            suggestions: suggestions.into_iter().collect(),
+        });
+    })
+    .on::<mun_hir::diagnostics::CannotApplyUnaryOp, _>(|d| {
+        result.borrow_mut().push(Diagnostic {
+            level: Level::Error,
+            code: Some(d.code()),
+            loc: d.highlight_range().into(),
+            message: format!("`{}` cannot be applied to `{}`", d.op, d.ty.display(db)),
+            labels: Vec::new(),
+            suggestions: Vec::new(),
+        });
+    })
+    .on::<mun_hir::diagnostics::CannotApplyBuiltinFunction, _>(|d| {
+        result.borrow_mut().push(Diagnostic {
+            level: Level::Error,
+            code: Some(d.code()),
+            loc: d.highlight_range().into(),
+            message: format!("{} to type `{}`", d.message(), d.ty.display(db)),
+            labels: Vec::new(),
+            suggestions: Vec::new(),
+        });
+    })
+    .on::<mun_hir::diagnostics::UnresolvedStruct, _>(|d| {
+        let path =
+            ast::RecordLit::cast(d.expr.to_node(&parse.tree().syntax())).and_then(|r| r.path());
+        result.borrow_mut().push(Diagnostic {
+            level: Level::Error,
+            code: Some(d.code()),
+            loc: path
+                .as_ref()
+                .map(|p| p.syntax().text_range())
+                .unwrap_or_else(|| d.highlight_range())
+                .into(),
+            message: match path {
+                Some(path) => format!("could not find struct `{}` in this scope", path.syntax()),
+                None => d.message(),
+            },
+            labels: Vec::new(),
+            suggestions: Vec::new(),
+        });
+    })
+    .on::<mun_hir::diagnostics::MissingFields, _>(|d| {
+        result.borrow_mut().push(Diagnostic {
+            level: Level::Error,
+            code: Some(d.code()),
+            loc: ast::RecordLit::cast(d.expr.to_node(&parse.tree().syntax()))
+                .and_then(|r| r.path())
+                .map(|p| p.syntax().text_range())
+                .unwrap_or_else(|| d.highlight_range())
+                .into(),
+            message: format!(
+                "{} in initializer of `{}`",
+                d.message(),
+                d.struct_ty.display(db)
+            ),
+            labels: Vec::new(),
+            suggestions: Vec::new(),
         });
     })
     .on::<mun_hir::diagnostics::DuplicateDefinition, _>(|d| {
+        let root = parse.tree().syntax().clone();
+        let message = match d.namespace {
+            Some(Namespace::Values) => format!(
+                "{}; `{}` must be defined only once in the value namespace of this module",
+                d.message(),
+                d.name
+            ),
+            Some(Namespace::Types) => format!(
+                "{}; `{}` must be defined only once in the type namespace of this module",
+                d.message(),
+                d.name
+            ),
+            None => d.message(),
+        };
+        result.borrow_mut().push(Diagnostic {
+            level: Level::Error,
+            code: Some(d.code()),
+            loc: definition_range(&root, d.definition).into(),
+            message,
+            labels: vec![Label {
+                loc: definition_range(&root, d.first_definition).into(),
+                message: format!("the first definition of `{}` is here", d.name),
+            }],
+            suggestions: Vec::new(),
+        });
+    })
+    .on::<mun_hir::diagnostics::UnreachablePattern, _>(|d| {
+        push_lint(
+            d,
+            Diagnostic {
+                level: Level::Warning,
+                code: Some(d.code()),
+                loc: d.highlight_range().into(),
+                message: d.message(),
+                labels: Vec::new(),
+                suggestions: Vec::new(),
+            },
+        );
+    })
+    .on::<mun_hir::diagnostics::UnreachableCode, _>(|d| {
+        push_lint(
+            d,
+            Diagnostic {
+                level: Level::Warning,
+                code: Some(d.code()),
+                loc: d.highlight_range().into(),
+                message: d.message(),
+                labels: Vec::new(),
+                suggestions: Vec::new(),
+            },
+        );
+    })
+    .on::<mun_hir::diagnostics::UnknownLint, _>(|d| {
+        push_lint(
+            d,
+            Diagnostic {
+                level: Level::Warning,
+                code: Some(d.code()),
+                loc: d.highlight_range().into(),
+                message: d.message(),
+                labels: Vec::new(),
+                suggestions: Vec::new(),
+            },
+        );
+    })
+    .on::<mun_hir::diagnostics::FloatLiteralOutOfRange, _>(|d| {
+        push_lint(
+            d,
+            Diagnostic {
+                level: Level::Warning,
+                code: Some(d.code()),
+                loc: d.highlight_range().into(),
+                message: d.message(),
+                labels: Vec::new(),
+                suggestions: Vec::new(),
+            },
+        );
+    })
+    .on::<mun_hir::diagnostics::UnusedVariable, _>(|d| {
+        let name = ast::BindPat::cast(d.binding.to_node(&parse.tree().syntax()))
+            .and_then(|pat| pat.name());
+        push_lint(
+            d,
+            Diagnostic {
+                level: Level::Warning,
+                code: Some(d.code()),
+                loc: name
+                    .as_ref()
+                    .map(|name| name.syntax().text_range())
+                    .unwrap_or_else(|| d.highlight_range())
+                    .into(),
+                message: format!(
+                    "{}; if this is intentional, rename it to `{}`",
+                    d.message(),
+                    d.suggested_name()
+                ),
+                labels: Vec::new(),
+                suggestions: name
+                    .map(|name| rename_suggestion(&name, d.suggested_name()))
+                    .into_iter()
+                    .collect(),
+            },
+        );
+    })
+    .on::<mun_hir::diagnostics::UnusedFunction, _>(|d| {
+        let name =
+            ast::FunctionDef::cast(d.def.to_node(&parse.tree().syntax())).and_then(|f| f.name());
+        push_lint(
+            d,
+            Diagnostic {
+                level: Level::Warning,
+                code: Some(d.code()),
+                loc: name
+                    .as_ref()
+                    .map(|name| name.syntax().text_range())
+                    .unwrap_or_else(|| d.highlight_range())
+                    .into(),
+                message: format!(
+                    "{}; if this is intentional, rename it to `{}`",
+                    d.message(),
+                    d.suggested_name()
+                ),
+                labels: Vec::new(),
+                suggestions: name
+                    .map(|name| rename_suggestion(&name, d.suggested_name()))
+                    .into_iter()
+                    .collect(),
+            },
+        );
+    })
+    .on::<mun_hir::diagnostics::PrivateItem, _>(|d| {
+        result.borrow_mut().push(Diagnostic {
+            level: Level::Error,
+            code: Some(d.code()),
+            loc: d.highlight_range().into(),
+            message: format!(
+                "{}; it is declared without `export` at {}",
+                d.message(),
+                definition_location(db, d.definition_file, d.definition)
+            ),
+            labels: Vec::new(),
+            suggestions: Vec::new(),
+        });
+    })
+    .on::<mun_hir::diagnostics::PrivateField, _>(|d| {
         result.borrow_mut().push(Diagnostic {
             level: Level::Error,
-            loc: match d.definition.kind() {
+            code: Some(d.code()),
+            loc: d.highlight_range().into(),
+            message: format!(
+                "{}; the struct is declared without `export` at {}",
+                d.message(),
+                definition_location(db, d.definition_file, d.definition)
+            ),
+            labels: Vec::new(),
+            suggestions: Vec::new(),
+        });
+    })
+    .on::<mun_codegen::diagnostics::UnsupportedType, _>(|d| {
+        result.borrow_mut().push(Diagnostic {
+            level: Level::Error,
+            code: Some(d.code()),
+            loc: match d.def.kind() {
                 SyntaxKind::FUNCTION_DEF => {
-                    ast::FunctionDef::cast(d.definition.to_node(&parse.tree().syntax()))
+                    ast::FunctionDef::cast(d.def.to_node(&parse.tree().syntax()))
                         .map(|f| f.signature_range())
                         .unwrap_or_else(|| d.highlight_range())
                         .into()
@@ -79,11 +506,160 @@ pub fn diagnostics(db: &impl HirDatabase, file_id: FileId) -> Vec<Diagnostic> {
                 _ => d.highlight_range().into(),
             },
             message: d.message(),
+            labels: Vec::new(),
+            suggestions: Vec::new(),
+        });
+    })
+    .on::<mun_codegen::diagnostics::NonTailRecursion, _>(|d| {
+        result.borrow_mut().push(Diagnostic {
+            level: Level::Note,
+            code: Some(d.code()),
+            loc: d.highlight_range().into(),
+            message: d.message(),
+            labels: Vec::new(),
+            suggestions: Vec::new(),
         });
     });
 
     Module::from(file_id).diagnostics(db, &mut sink);
 
+    // Types that are not supported in compiled code are only checked for modules without other
+    // errors, because an erroneous module contains types that could not be inferred
+    let has_errors = result.borrow().iter().any(|d| d.level == Level::Error);
+    if !has_errors {
+        mun_codegen::diagnostics::diagnostics(db, file_id, &mut sink);
+    }
+
     drop(sink);
     result.into_inner()
 }
+
+/// Returns a suggestion to rename the definition with the name `name` to `new_name`.
+fn rename_suggestion(name: &ast::Name, new_name: String) -> Suggestion {
+    Suggestion {
+        loc: name.syntax().text_range().into(),
+        message: format!("rename it to `{}`", new_name),
+        replacement: new_name,
+    }
+}
+
+/// Returns a suggestion to convert the right operand `rhs` of a binary operator to the type of the
+/// left operand, if a conversion exists: integers and enums can be cast to integer types, and an
+/// unsuffixed integer literal can be written as a floating point literal.
+fn operand_suggestion(
+    db: &impl HirDatabase,
+    rhs: &ast::Expr,
+    lhs_ty: &Ty,
+    rhs_ty: &Ty,
+) -> Option<Suggestion> {
+    let text = rhs.syntax().text().to_string();
+    match (lhs_ty.as_simple(), rhs_ty.as_simple(), rhs.kind()) {
+        (Some(TypeCtor::Int(_)), Some(TypeCtor::Int(_)), _)
+        | (Some(TypeCtor::Int(_)), Some(TypeCtor::Enum(_)), _) => {
+            // A cast binds tighter than any binary operator
+            let operand = match rhs.kind() {
+                ast::ExprKind::BinExpr(_) | ast::ExprKind::RangeExpr(_) => format!("({})", text),
+                _ => text,
+            };
+            Some(Suggestion {
+                loc: rhs.syntax().text_range().into(),
+                message: format!("cast the right operand to `{}`", lhs_ty.display(db)),
+                replacement: format!("{} as {}", operand, lhs_ty.display(db)),
+            })
+        }
+        (Some(TypeCtor::Float(_)), _, ast::ExprKind::Literal(literal)) => match literal.kind() {
+            ast::LiteralKind::IntNumber { suffix: None } => Some(Suggestion {
+                loc: rhs.syntax().text_range().into(),
+                message: "use a floating point literal".to_string(),
+                replacement: format!("{}.0", text),
+            }),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the range of `definition` that a diagnostic points at: the signature of a function or
+/// the name of another definition.
+fn definition_range(root: &SyntaxNode, definition: SyntaxNodePtr) -> TextRange {
+    let node = definition.to_node(root);
+    match ast::FunctionDef::cast(node.clone()) {
+        Some(function) => function.signature_range(),
+        None => node
+            .children()
+            .find(|child| child.kind() == SyntaxKind::NAME)
+            .map_or_else(|| definition.range(), |name| name.text_range()),
+    }
+}
+
+/// Returns the location of `definition` in `file_id` in the format of the location of a diagnostic,
+/// e.g. `math.mun:3:0`. Labels can only point at the file of their diagnostic, so a definition in
+/// another file is referred to by its location instead.
+fn definition_location(
+    db: &impl HirDatabase,
+    file_id: FileId,
+    definition: SyntaxNodePtr,
+) -> String {
+    let line_col = db.line_index(file_id).line_col(definition.range().start());
+    format!(
+        "{}:{}:{}",
+        db.file_relative_path(file_id).as_str(),
+        line_col.line + 1,
+        line_col.col
+    )
+}
+
+/// Returns the name of the `expected` type and a label that points out the type annotation that
+/// imposed it, if it is in `file_id`. The type is named as it is annotated, so type aliases are
+/// preserved.
+fn expected_type_annotation(
+    db: &impl HirDatabase,
+    file_id: FileId,
+    source: Option<&ExpectedTypeSource>,
+    expected: &Ty,
+) -> (String, Vec<Label>) {
+    let annotation = source.map(|source| {
+        let tree = db.parse(source.file).tree();
+        (source, source.type_ref.to_node(tree.syntax()))
+    });
+    let name = match &annotation {
+        Some((_, type_ref)) => type_ref.syntax().text().to_string(),
+        None => expected.display(db).to_string(),
+    };
+    let labels = annotation
+        .iter()
+        .filter(|(source, _)| source.file == file_id)
+        .map(|(source, type_ref)| Label {
+            loc: type_ref.syntax().text_range().into(),
+            message: if source.is_param {
+                "expected due to the type of this parameter".to_string()
+            } else {
+                "expected due to this type".to_string()
+            },
+        })
+        .collect();
+    (name, labels)
+}
+
+/// Constructs the message of a type mismatch, in which the expected type is called `expected_name`.
+/// Floating point types of different widths are never converted implicitly, which is pointed out
+/// explicitly.
+fn mismatched_type_message(
+    db: &impl HirDatabase,
+    expected_name: &str,
+    expected: &Ty,
+    found: &Ty,
+) -> String {
+    let message = format!(
+        "expected `{}`, found `{}`",
+        expected_name,
+        found.display(db)
+    );
+    match (expected.as_simple(), found.as_simple()) {
+        (Some(TypeCtor::Float(_)), Some(TypeCtor::Float(_))) => format!(
+            "{}; floating point types of different widths are not implicitly converted",
+            message
+        ),
+        _ => message,
+    }
+}
@@ -1,7 +1,16 @@
-use mun_errors::Diagnostic;
+use mun_errors::{Diagnostic, Level, Location};
+use mun_hir::line_index::LineIndex;
 use mun_hir::{FileId, SourceDatabase};
 use std::io;
 use termcolor::{Color, ColorSpec, WriteColor};
+use unicode_width::UnicodeWidthChar;
+
+/// The number of columns in which a tab is rendered.
+const TAB_WIDTH: usize = 4;
+
+/// The maximum number of lines of a multi-line span that are shown. The lines in the middle of a
+/// longer span are left out.
+const MAX_MULTI_LINE_SPAN_LINES: u32 = 5;
 
 pub trait Emit {
     fn emit(
@@ -20,79 +29,210 @@ impl Emit for Diagnostic {
         file_id: FileId,
     ) -> io::Result<()> {
         let line_index = db.line_index(file_id);
-        let text = db.file_text(file_id).to_string();
+        let text = db.file_text(file_id);
         let path = db.file_relative_path(file_id);
         let line_col = line_index.line_col(self.loc.offset());
-        let line_col_end = line_index.line_col(self.loc.end_offset());
 
         let header = ColorSpec::new()
             .set_fg(Some(Color::White))
             .set_bold(true)
             .set_intense(true)
             .clone();
-        let error = header.clone().set_fg(Some(Color::Red)).clone();
-        let snippet_gutter = ColorSpec::new()
-            .set_fg(Some(Color::Cyan))
-            .set_bold(true)
-            .set_intense(true)
-            .clone();
-        let snippet_text = ColorSpec::new();
+        let severity = match self.level {
+            Level::Error => header.clone().set_fg(Some(Color::Red)).clone(),
+            Level::Warning => header.clone().set_fg(Some(Color::Yellow)).clone(),
+            Level::Note => header.clone().set_fg(Some(Color::Green)).clone(),
+        };
 
-        // Write severity name
-        writer.set_color(&error)?;
-        write!(writer, "error")?;
+        // Write severity name and code
+        writer.set_color(&severity)?;
+        match self.level {
+            Level::Error => write!(writer, "error")?,
+            Level::Warning => write!(writer, "warning")?,
+            Level::Note => write!(writer, "note")?,
+        }
+        if let Some(code) = self.code {
+            write!(writer, "[{}]", code)?;
+        }
 
         // Write diagnostic message
         writer.set_color(&header)?;
         writeln!(writer, ": {}", self.message)?;
 
-        if let Some(snippet) = line_index.line_str(line_col.line, &text) {
-            // Determine gutter offset
-            let line_str = format!("{}", line_col.line + 1);
-            let gutter_indent = " ".to_string().repeat(line_str.len());
+        // Determine gutter width, which fits the numbers of all lines that are shown
+        let gutter_width = self
+            .labels
+            .iter()
+            .map(|label| &label.loc)
+            .chain(std::iter::once(&self.loc))
+            .map(|loc| line_index.line_col(loc.end_offset()).line)
+            .map(|line| format!("{}", line + 1).len())
+            .max()
+            .unwrap_or_default();
+        let snippet = Snippet {
+            line_index: &line_index,
+            text: &text,
+            gutter_indent: " ".repeat(gutter_width),
+            gutter: ColorSpec::new()
+                .set_fg(Some(Color::Cyan))
+                .set_bold(true)
+                .set_intense(true)
+                .clone(),
+            source: ColorSpec::new(),
+        };
+
+        writer.set_color(&snippet.gutter)?;
+        write!(writer, "{}-->", snippet.gutter_indent)?;
+        writer.set_color(&snippet.source)?;
+        writeln!(
+            writer,
+            " {}:{}:{}",
+            path.as_str(),
+            line_col.line + 1,
+            line_col.col
+        )?;
 
-            writer.set_color(&snippet_gutter)?;
-            write!(writer, "{}-->", gutter_indent)?;
-            writer.set_color(&snippet_text)?;
-            writeln!(
+        // The span of the diagnostic is underlined with carets, and every label is shown in a
+        // snippet of its own underneath it
+        snippet.write(writer, &self.loc, '^', &severity, None)?;
+        for label in self.labels.iter() {
+            snippet.write(
                 writer,
-                " {}:{}:{}",
-                path.as_str(),
-                line_col.line + 1,
-                line_col.col
+                &label.loc,
+                '-',
+                &snippet.gutter,
+                Some(&label.message),
             )?;
+        }
+
+        writer.reset()?;
+        Ok(())
+    }
+}
+
+/// Writes the source lines of spans of a file, in which the span is underlined.
+struct Snippet<'a> {
+    line_index: &'a LineIndex,
+    text: &'a str,
+    /// The space that is taken up by the line numbers in front of the source lines
+    gutter_indent: String,
+    gutter: ColorSpec,
+    source: ColorSpec,
+}
+
+impl Snippet<'_> {
+    /// Writes the lines of `loc`, in which the span is underlined with `underline` in `color` and
+    /// followed by the `message`, if any. A span of multiple lines is connected from its start to
+    /// its end by a line in the margin of the source lines.
+    fn write(
+        &self,
+        writer: &mut impl WriteColor,
+        loc: &Location,
+        underline: char,
+        color: &ColorSpec,
+        message: Option<&str>,
+    ) -> io::Result<()> {
+        let start = self.line_index.line_col(loc.offset());
+        let end = self.line_index.line_col(loc.end_offset());
+        let start_line = self.line(start.line);
+        let end_line = self.line(end.line);
+        let start_column = display_width(start_line.get(..start.col as usize).unwrap_or(""));
+        let end_column = display_width(end_line.get(..end.col as usize).unwrap_or(end_line));
+
+        writer.set_color(&self.gutter)?;
+        writeln!(writer, "{} |", self.gutter_indent)?;
 
-            // Snippet
-            writer.set_color(&snippet_gutter)?;
-            writeln!(writer, "{} |", gutter_indent)?;
-            write!(writer, "{} | ", line_str)?;
-            writer.set_color(&snippet_text)?;
-            writeln!(writer, "{}", snippet)?;
-
-            writer.set_color(&snippet_gutter)?;
-            write!(writer, "{} |", gutter_indent)?;
-            writer.set_color(&error)?;
-
-            if line_col.line == line_col_end.line {
-                // single-line diagnostic
-                writeln!(
-                    writer,
-                    " {}{}",
-                    " ".to_string().repeat(line_col.col as usize),
-                    "^".to_string()
-                        .repeat((line_col_end.col - line_col.col) as usize)
-                )?;
+        if start.line == end.line {
+            // An empty span is still marked, e.g. the position of a syntax error
+            let width = end_column.saturating_sub(start_column).max(1);
+            self.write_source_line(writer, start.line, None, color)?;
+            let marker = format!(
+                "{}{}",
+                " ".repeat(start_column),
+                underline.to_string().repeat(width)
+            );
+            return self.write_marker_line(writer, &marker, color, message);
+        }
+
+        self.write_source_line(writer, start.line, Some("  "), color)?;
+        let marker = format!(" {}{}", "_".repeat(start_column + 1), underline);
+        self.write_marker_line(writer, &marker, color, None)?;
+
+        // Only the first and last two lines of a long span are shown
+        let elide = end.line - start.line + 1 > MAX_MULTI_LINE_SPAN_LINES;
+        for line in start.line + 1..=end.line {
+            if elide && line > start.line + 1 && line + 1 < end.line {
+                if line == start.line + 2 {
+                    writer.set_color(&self.gutter)?;
+                    writeln!(writer, "...")?;
+                }
+                continue;
             }
+            self.write_source_line(writer, line, Some("| "), color)?;
         }
 
-        //        // Write the start location
-        //        println!("  {} {}:{}:{}",
-        //            "-->".cyan(),
-        //            path.as_str(),
-        //            line_col.line + 1,
-        //            line_col.col);
+        // The underline points at the last character of the span
+        let marker = format!("|{}{}", "_".repeat(end_column.max(1)), underline);
+        self.write_marker_line(writer, &marker, color, message)
+    }
 
-        writer.reset()?;
-        Ok(())
+    /// Returns the text of the line with index `line`, without its line ending.
+    fn line(&self, line: u32) -> &str {
+        self.line_index
+            .line_str(line, self.text)
+            .unwrap_or("")
+            .trim_end_matches('\r')
+    }
+
+    /// Writes the line with index `line` after its line number. The `margin` in front of the line
+    /// shows the spans of multiple lines that contain it.
+    fn write_source_line(
+        &self,
+        writer: &mut impl WriteColor,
+        line: u32,
+        margin: Option<&str>,
+        color: &ColorSpec,
+    ) -> io::Result<()> {
+        writer.set_color(&self.gutter)?;
+        write!(writer, "{:>1$} | ", line + 1, self.gutter_indent.len())?;
+        if let Some(margin) = margin {
+            writer.set_color(color)?;
+            write!(writer, "{}", margin)?;
+        }
+        writer.set_color(&self.source)?;
+        writeln!(
+            writer,
+            "{}",
+            self.line(line).replace('\t', &" ".repeat(TAB_WIDTH))
+        )
+    }
+
+    /// Writes the `marker` that underlines a span, followed by the `message` of the span, if any.
+    fn write_marker_line(
+        &self,
+        writer: &mut impl WriteColor,
+        marker: &str,
+        color: &ColorSpec,
+        message: Option<&str>,
+    ) -> io::Result<()> {
+        writer.set_color(&self.gutter)?;
+        write!(writer, "{} | ", self.gutter_indent)?;
+        writer.set_color(color)?;
+        match message {
+            Some(message) => writeln!(writer, "{} {}", marker, message),
+            None => writeln!(writer, "{}", marker),
+        }
     }
 }
+
+/// Returns the number of columns in which `text` is rendered in a terminal. Tabs are rendered as
+/// `TAB_WIDTH` spaces, and characters that are wider or narrower than a single column, like CJK
+/// characters or combining marks, are taken into account.
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c {
+            '\t' => TAB_WIDTH,
+            c => c.width().unwrap_or(0),
+        })
+        .sum()
+}
@@ -0,0 +1,168 @@
+//! The JSON message format of the compiler, which is meant to be read by other tools, e.g. editors.
+//! Every message is a JSON object on a line of its own, e.g.:
+//!
+//! ```json
+//! {"type":"diagnostic","severity":"error","code":"E0005","message":"...","file":"main.mun","span":{...},"labels":[],"suggestions":[]}
+//! {"type":"build_finished","success":false,"artifact":null,"warnings":0,"errors":1}
+//! ```
+//!
+//! A build or check writes a `diagnostic` message for every diagnostic, followed by a single
+//! `build_finished` message. New fields may be added to messages, but existing fields are neither
+//! renamed nor removed.
+
+use crate::DiagnosticSummary;
+use mun_errors::{Diagnostic, Level, Location};
+use mun_hir::{FileId, SourceDatabase};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A message of the JSON message format.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    Diagnostic(JsonDiagnostic),
+    BuildFinished(BuildFinished),
+}
+
+/// A diagnostic of a file of the package.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonDiagnostic {
+    pub severity: Severity,
+    /// The code that identifies the kind of diagnostic, if it has one
+    pub code: Option<String>,
+    pub message: String,
+    /// The path of the file relative to the source root of the package
+    pub file: String,
+    pub span: Span,
+    /// Secondary messages at related locations in the same file
+    pub labels: Vec<JsonLabel>,
+    /// Changes to the source that resolve the diagnostic
+    pub suggestions: Vec<JsonSuggestion>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+/// A range of source text, from `start` up to `end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// A position in a file. Lines start at 1. Columns start at 1 as well and count UTF-8 bytes, like
+/// `offset`, which is the number of bytes from the start of the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    pub line: u32,
+    pub column: u32,
+    pub offset: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonLabel {
+    pub span: Span,
+    pub message: String,
+}
+
+/// A change to the source that replaces the text at `span` with `replacement`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonSuggestion {
+    pub span: Span,
+    pub message: String,
+    pub replacement: String,
+}
+
+/// The final message of a build.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildFinished {
+    /// Whether the build succeeded, i.e. there were no errors, nor warnings if warnings are denied
+    pub success: bool,
+    /// The path of the assembly that was written, if any
+    pub artifact: Option<String>,
+    /// The number of warnings of all packages of the build
+    pub warnings: usize,
+    /// The number of errors of all packages of the build
+    pub errors: usize,
+}
+
+impl Message {
+    /// Constructs the message of the diagnostic `diagnostic` of the file `file_id`.
+    pub fn diagnostic(db: &impl SourceDatabase, file_id: FileId, diagnostic: &Diagnostic) -> Self {
+        Message::Diagnostic(JsonDiagnostic::new(db, file_id, diagnostic))
+    }
+
+    /// Constructs the final message of a build, which wrote the assembly at `artifact` if it
+    /// succeeded, and reported the warnings and errors of `summary`.
+    pub fn build_finished(
+        success: bool,
+        artifact: Option<&Path>,
+        summary: DiagnosticSummary,
+    ) -> Self {
+        Message::BuildFinished(BuildFinished {
+            success,
+            artifact: artifact.map(|path| path.display().to_string()),
+            warnings: summary.warnings,
+            errors: summary.errors,
+        })
+    }
+
+    /// Returns the message as a single line of JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("messages can always be serialized")
+    }
+}
+
+impl JsonDiagnostic {
+    /// Constructs the JSON representation of the diagnostic `diagnostic` of the file `file_id`.
+    pub fn new(db: &impl SourceDatabase, file_id: FileId, diagnostic: &Diagnostic) -> Self {
+        let line_index = db.line_index(file_id);
+        let span = |loc: &Location| {
+            let position = |offset| {
+                let line_col = line_index.line_col(offset);
+                Position {
+                    line: line_col.line + 1,
+                    column: line_col.col + 1,
+                    offset: offset.to_usize() as u32,
+                }
+            };
+            Span {
+                start: position(loc.offset()),
+                end: position(loc.end_offset()),
+            }
+        };
+        JsonDiagnostic {
+            severity: match diagnostic.level {
+                Level::Error => Severity::Error,
+                Level::Warning => Severity::Warning,
+                Level::Note => Severity::Note,
+            },
+            code: diagnostic.code.map(String::from),
+            message: diagnostic.message.clone(),
+            file: db.file_relative_path(file_id).to_string(),
+            span: span(&diagnostic.loc),
+            labels: diagnostic
+                .labels
+                .iter()
+                .map(|label| JsonLabel {
+                    span: span(&label.loc),
+                    message: label.message.clone(),
+                })
+                .collect(),
+            suggestions: diagnostic
+                .suggestions
+                .iter()
+                .map(|suggestion| JsonSuggestion {
+                    span: span(&suggestion.loc),
+                    message: suggestion.message.clone(),
+                    replacement: suggestion.replacement.clone(),
+                })
+                .collect(),
+        }
+    }
+}
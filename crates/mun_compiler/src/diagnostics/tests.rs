@@ -0,0 +1,856 @@
+use super::json::{JsonDiagnostic, JsonSuggestion, Message, Position, Severity, Span};
+use crate::{
+    Config, DiagnosticSummary, Driver, Lint, LintLevel, LintLevels, PathOrInline, RelativePathBuf,
+};
+use mun_errors::{Diagnostic, Level, Location};
+use mun_syntax::{TextRange, TextUnit};
+use std::path::Path;
+use termcolor::NoColor;
+
+#[test]
+fn mismatched_return_type() {
+    emit_snapshot(
+        r#"
+    type Meters = int;
+
+    fn distance(): Meters {
+        true
+    }
+    "#,
+    )
+}
+
+#[test]
+fn mismatched_argument_type() {
+    emit_snapshot(
+        r#"
+    struct Vector { x: float }
+
+    impl Vector {
+        fn scale(self, _factor: float): Vector { self }
+    }
+
+    fn takes(_a: int, _b: bool) {}
+
+    fn main(v: Vector, f: float) {
+        takes(1, f);
+        v.scale(true);
+        let _c: bool = f;
+    }
+    "#,
+    )
+}
+
+#[test]
+fn unreachable_code() {
+    emit_snapshot(
+        r#"
+    export fn after_return(a: int): int {
+        return a;
+        a + 1
+    }
+
+    export fn constant_condition(a: int): int {
+        if true { a } else { a + 1 }
+    }
+
+    export fn after_break(a: int): int {
+        loop {
+            break a;
+            let _b = a + 1;
+        }
+    }
+    "#,
+    )
+}
+
+#[test]
+fn unreachable_code_is_a_warning() {
+    let text = r#"
+    export fn main(a: int): int {
+        if 2 > 3 {
+            return 0;
+        }
+        return a;
+        let b = a + 1;
+        b
+    }
+    "#;
+    let driver = driver_with_text(text);
+
+    // Only the code after the `return` is unreachable, a condition that is only known after
+    // optimization is not considered
+    let start = text.find("let b").unwrap();
+    let end = text.rfind('b').unwrap() + 1;
+    assert_eq!(
+        driver.diagnostics(),
+        vec![Diagnostic {
+            level: Level::Warning,
+            code: Some("W0002"),
+            loc: TextRange::from_to(TextUnit::from_usize(start), TextUnit::from_usize(end)).into(),
+            message: "unreachable code".to_string(),
+            labels: Vec::new(),
+            suggestions: Vec::new(),
+        }]
+    );
+
+    let mut writer = NoColor::new(Vec::new());
+    assert!(!driver.emit_diagnostics(&mut writer).unwrap());
+}
+
+#[test]
+fn float_literal_out_of_range_is_a_warning() {
+    let text = r#"
+    export fn main(): f32 {
+        1e39
+    }
+    "#;
+    let driver = driver_with_text(text);
+
+    let start = text.find("1e39").unwrap();
+    let end = start + "1e39".len();
+    assert_eq!(
+        driver.diagnostics(),
+        vec![Diagnostic {
+            level: Level::Warning,
+            code: Some("W0005"),
+            loc: TextRange::from_to(TextUnit::from_usize(start), TextUnit::from_usize(end)).into(),
+            message: "literal out of range for `f32`; it is rounded to infinity".to_string(),
+            labels: Vec::new(),
+            suggestions: Vec::new(),
+        }]
+    );
+
+    let mut writer = NoColor::new(Vec::new());
+    assert!(!driver.emit_diagnostics(&mut writer).unwrap());
+}
+
+#[test]
+fn lint_attributes_are_scoped() {
+    let text = r#"
+    #![deny(warnings)]
+
+    export fn denied(): int {
+        let a = 1;
+        2
+    }
+
+    #[allow(unused_variables)]
+    export fn allowed(): int {
+        let b = 1;
+        2
+    }
+
+    #[warn(unused_variables)]
+    export fn warned(): int {
+        let c = 1;
+        2
+    }
+
+    #[allow(dead_code)]
+    fn unused() {}
+    "#;
+    let levels: Vec<(Level, String)> = driver_with_text(text)
+        .diagnostics()
+        .into_iter()
+        .map(|d| {
+            let range = d.loc.offset().to_usize()..d.loc.end_offset().to_usize();
+            (d.level, text[range].to_string())
+        })
+        .collect();
+    // The file denies all lints, of which `warned` only turns `unused_variables` back into a
+    // warning
+    assert_eq!(
+        levels,
+        vec![
+            (Level::Error, "a".to_string()),
+            (Level::Warning, "c".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn command_line_lint_levels() {
+    let text = r#"
+    export fn main() {
+        let a = 1;
+    }
+
+    #[allow(unused_variables)]
+    export fn allowed() {
+        let b = 1;
+    }
+
+    fn unused() {}
+    "#;
+    let mut lint_levels = LintLevels::default();
+    lint_levels.set_by_name("warnings", LintLevel::Deny);
+    lint_levels.set(Lint::DeadCode, LintLevel::Allow);
+    let config = Config {
+        lint_levels,
+        ..Config::default()
+    };
+    let levels: Vec<(Level, Option<&str>)> = driver_with_config(config, text)
+        .diagnostics()
+        .into_iter()
+        .map(|d| (d.level, d.code))
+        .collect();
+    // The attributes of the code override the command line
+    assert_eq!(levels, vec![(Level::Error, Some("W0003"))]);
+}
+
+#[test]
+fn invalid_lint_attributes() {
+    let text = r#"
+    #![inline]
+    #![allow(unused_variable)]
+
+    #[deny(dead_code, 16)]
+    export fn main() {}
+
+    #[warn]
+    export struct Foo;
+    "#;
+    assert_eq!(
+        highlighted_diagnostics(text),
+        vec![
+            (
+                "unknown attribute `inline`".to_string(),
+                "#![inline]".to_string()
+            ),
+            (
+                "unknown lint `unused_variable`".to_string(),
+                "unused_variable".to_string()
+            ),
+            (
+                "malformed `deny` attribute, expected `#[deny(lint, ...)]`".to_string(),
+                "#[deny(dead_code, 16)]".to_string()
+            ),
+            (
+                "malformed `warn` attribute, expected `#[warn(lint, ...)]`".to_string(),
+                "#[warn]".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn unused_variables() {
+    emit_snapshot(
+        r#"
+    export fn attack(strength: int): int {
+        let damage = strength * 2;
+        let mut armor = 3;
+        strength
+    }
+    "#,
+    )
+}
+
+#[test]
+fn unused_parameters() {
+    emit_snapshot(
+        r#"
+    struct Vector { x: float }
+
+    impl Vector {
+        fn scale(self, factor: float): float { self.x }
+    }
+
+    fn apply(f: fn(int):int, v: int): int { f(v) }
+
+    fn attack(strength: int, bonus: int): float {
+        let v = Vector { x: 1.0 };
+        apply(|x| 2, strength);
+        v.scale(2.0)
+    }
+    "#,
+    )
+}
+
+#[test]
+fn unused_functions() {
+    emit_snapshot(
+        r#"
+    struct Vector { x: float }
+
+    impl Vector {
+        fn length(self): float { self.x }
+    }
+
+    extern fn log(message: int);
+
+    export fn attack(): int { damage() }
+    fn damage(): int { 2 }
+    fn heal(): int { damage() }
+    "#,
+    )
+}
+
+#[test]
+fn underscore_suppresses_unused_warnings() {
+    let text = r#"
+    export fn attack(_strength: int): int {
+        let _damage = 2;
+        let apply = |_x: int| 2;
+        apply(1)
+    }
+    fn _heal(): int { 2 }
+    "#;
+    assert!(driver_with_text(text).diagnostics().is_empty());
+}
+
+#[test]
+fn multi_line_spans() {
+    emit_snapshot(
+        r#"
+    fn short(a: bool): int {
+        if a {
+            1
+        }
+    }
+
+    fn long(a: bool): int {
+        if a {
+            let b = 1;
+            let c = b + 1;
+            let d = c + 1;
+            d
+        }
+    }
+    "#,
+    )
+}
+
+#[test]
+fn missing_return_values() {
+    let text = r#"
+    export fn sign(a: int): int {
+        if a > 0 {
+            return 1;
+        }
+    }
+    "#;
+    assert_eq!(
+        highlighted_diagnostics(text),
+        vec![(
+            "this `if` is missing an `else` that returns `int`".to_string(),
+            "if a > 0 {\n            return 1;\n        }".to_string()
+        )]
+    );
+
+    // The end of a block is only reported if it is reachable
+    let text = r#"
+    export fn double(a: int): int {
+        if a > 0 {
+            return 1;
+        }
+        let _b = a * 2;
+    }
+    export fn never(a: int): int {
+        let _b = a * 2;
+        return _b;
+    }
+    "#;
+    assert_eq!(
+        highlighted_diagnostics(text),
+        vec![(
+            "this block ends without returning a value of type `int`".to_string(),
+            "}".to_string()
+        )]
+    );
+}
+
+#[test]
+fn missing_return_values_are_reported_at_the_innermost_exit() {
+    let text = r#"
+    export fn nested(a: int): int {
+        if a > 0 {
+            if a > 1 { 2 }
+        } else {
+            0
+        }
+    }
+    "#;
+    assert_eq!(
+        highlighted_diagnostics(text),
+        vec![(
+            "this `if` is missing an `else` that returns `int`".to_string(),
+            "if a > 1 { 2 }".to_string()
+        )]
+    );
+}
+
+#[test]
+fn missing_return_values_of_loops() {
+    let text = r#"
+    export fn find(a: int): int {
+        let mut i = 0;
+        loop {
+            if i == a {
+                break i;
+            }
+            i += 1;
+        }
+    }
+    "#;
+    assert!(driver_with_text(text).diagnostics().is_empty());
+
+    let text = r#"
+    export fn find(a: int): int {
+        loop {
+            if a > 0 {
+                break;
+            }
+        }
+    }
+    export fn count(a: int): int {
+        let mut i = 0;
+        while i < a {
+            i += 1;
+        }
+    }
+    "#;
+    assert_eq!(
+        highlighted_diagnostics(text),
+        vec![
+            (
+                "this `break` leaves the loop without a value of type `int`".to_string(),
+                "break".to_string()
+            ),
+            (
+                "this loop can end without returning a value of type `int`".to_string(),
+                "while i < a {\n            i += 1;\n        }".to_string()
+            )
+        ]
+    );
+}
+
+#[test]
+fn possibly_uninitialized_variables() {
+    let text = r#"
+    export fn branch(c: bool): int {
+        let x: int;
+        if c {
+            x = 1;
+        }
+        x
+    }
+    "#;
+    let diagnostics = driver_with_text(text).diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "`x` may be uninitialized");
+    assert_eq!(
+        diagnostics[0].loc.offset().to_usize(),
+        text.rfind('x').unwrap()
+    );
+    assert_eq!(
+        diagnostics[0].labels[0].loc.offset().to_usize(),
+        text.find("x: int").unwrap()
+    );
+
+    // A loop body isn't necessarily executed
+    let text = r#"
+    export fn loop_body(n: int): int {
+        let mut x: int;
+        let mut i = 0;
+        while i < n {
+            x = i;
+            i += 1;
+        }
+        x
+    }
+    "#;
+    assert_eq!(
+        highlighted_diagnostics(text),
+        vec![("`x` may be uninitialized".to_string(), "x".to_string())]
+    );
+}
+
+#[test]
+fn initialized_on_every_path() {
+    let text = r#"
+    export fn both_branches(c: bool): int {
+        let x: int;
+        if c {
+            x = 1;
+        } else {
+            x = 2;
+        }
+        x
+    }
+    export fn early_return(c: bool): int {
+        let x: int;
+        if c {
+            x = 1;
+        } else {
+            return 0;
+        }
+        x
+    }
+    export fn before_break(): int {
+        let x: int;
+        loop {
+            x = 3;
+            break;
+        }
+        x
+    }
+    "#;
+    assert!(driver_with_text(text).diagnostics().is_empty());
+}
+
+#[test]
+fn immutable_variables_are_initialized_once() {
+    let text = r#"
+    export fn twice(): int {
+        let x: int;
+        x = 1;
+        x = 2;
+        x
+    }
+    "#;
+    let diagnostics = highlighted_diagnostics(text);
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0]
+        .0
+        .starts_with("cannot assign to immutable variable `x`"));
+    assert_eq!(diagnostics[0].1, "x");
+}
+
+#[test]
+fn duplicate_definitions_point_at_both_definitions() {
+    let text = r#"
+    export fn foo(): int { 1 }
+    export struct Bar { a: int }
+    export fn foo(): int { 2 }
+    export struct Bar { b: float }
+    "#;
+    let range = |start: usize, len: usize| -> Location {
+        TextRange::offset_len(TextUnit::from_usize(start), TextUnit::from_usize(len)).into()
+    };
+    let diagnostics = driver_with_text(text).diagnostics();
+    assert_eq!(diagnostics.len(), 2);
+
+    assert_eq!(
+        diagnostics[0].message,
+        "the name `foo` is defined multiple times; `foo` must be defined only once in the value \
+         namespace of this module"
+    );
+    let signature = "fn foo(): int".len();
+    assert_eq!(
+        diagnostics[0].loc,
+        range(text.rfind("fn foo").unwrap(), signature)
+    );
+    assert_eq!(
+        diagnostics[0].labels[0].loc,
+        range(text.find("fn foo").unwrap(), signature)
+    );
+
+    assert_eq!(
+        diagnostics[1].message,
+        "the name `Bar` is defined multiple times; `Bar` must be defined only once in the type \
+         namespace of this module"
+    );
+    assert_eq!(diagnostics[1].loc, range(text.rfind("Bar").unwrap(), 3));
+    assert_eq!(
+        diagnostics[1].labels[0].loc,
+        range(text.find("Bar").unwrap(), 3)
+    );
+}
+
+#[test]
+fn syntax_errors_dont_hide_later_errors() {
+    let text = r#"
+    export fn broken() {
+        let _a = ;
+    }
+
+    export fn unclosed() {
+        let _b = 1;
+
+    export fn distance(): int {
+        true
+    }
+    "#;
+    let messages: Vec<String> = driver_with_text(text)
+        .diagnostics()
+        .into_iter()
+        .map(|d| d.message)
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Syntax Error: expected expression",
+            "Syntax Error: expected R_CURLY",
+            "expected `int`, found `bool`",
+        ]
+    );
+}
+
+#[test]
+fn tabs_and_wide_characters() {
+    // The tab and the characters that take up two columns precede the spans on the same line
+    emit_snapshot("fn main(f: float) {\n\t/* 変数 */ let _a: bool = f;\n}")
+}
+
+#[test]
+fn json_diagnostics() {
+    let text = "fn main(a: int) {\n    a = a + 1;\n    let _b = c;\n}\n";
+    let driver = driver_with_text(text);
+    let mut output = Vec::new();
+    assert!(driver.emit_json_diagnostics(&mut output).unwrap());
+
+    let mut diagnostics: Vec<JsonDiagnostic> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| match serde_json::from_str(line).unwrap() {
+            Message::Diagnostic(diagnostic) => diagnostic,
+            message => panic!("unexpected message: {:?}", message),
+        })
+        .collect();
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.start.offset);
+
+    let span = |(line, column, offset), len| Span {
+        start: Position {
+            line,
+            column,
+            offset,
+        },
+        end: Position {
+            line,
+            column: column + len,
+            offset: offset + len,
+        },
+    };
+    assert_eq!(
+        diagnostics,
+        vec![
+            JsonDiagnostic {
+                severity: Severity::Error,
+                code: Some("E0043".to_owned()),
+                message: "cannot assign to immutable variable `a`, which is defined at 1:8; \
+                          consider declaring it as `mut a`"
+                    .to_owned(),
+                file: "main.mun".to_owned(),
+                span: span((2, 5, 22), 1),
+                labels: Vec::new(),
+                suggestions: vec![JsonSuggestion {
+                    span: span((1, 9, 8), 1),
+                    message: "declare it as `mut a`".to_owned(),
+                    replacement: "mut a".to_owned(),
+                }],
+            },
+            JsonDiagnostic {
+                severity: Severity::Error,
+                code: Some("E0001".to_owned()),
+                message: "could not find value `c` in this scope".to_owned(),
+                file: "main.mun".to_owned(),
+                span: span((3, 14, 46), 1),
+                labels: Vec::new(),
+                suggestions: Vec::new(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn json_build_finished() {
+    let summary = DiagnosticSummary {
+        warnings: 3,
+        errors: 0,
+    };
+    let message = Message::build_finished(true, Some(Path::new("main.munlib")), summary);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&message.to_json()).unwrap(),
+        serde_json::json!({
+            "type": "build_finished",
+            "success": true,
+            "artifact": "main.munlib",
+            "warnings": 3,
+            "errors": 0,
+        })
+    );
+}
+
+#[test]
+fn conflicting_literal_types() {
+    let text = r#"
+    fn take_u8(_a: u8) {}
+    fn take_i32(_a: i32) {}
+    export fn main() {
+        let x = 0;
+        take_u8(x);
+        take_i32(x);
+    }
+    "#;
+    assert_eq!(
+        highlighted_diagnostics(text),
+        vec![(
+            "conflicting types for the literal `0`: this use expects `i32`, but an earlier use \
+             inferred `u8`"
+                .to_string(),
+            "x".to_string()
+        )]
+    );
+}
+
+#[test]
+fn invalid_operators() {
+    let text = r#"
+    struct Foo;
+
+    export fn main(a: int, b: u8, c: bool, d: float) {
+        let e = Foo;
+        let _f = c + 1;
+        let _g = e < e;
+        let _h = d << 1;
+        let _i = a + d;
+        let _j = c == 1.5;
+        let _k = !a;
+        let _l = -b;
+    }
+    "#;
+    let expected = [
+        ("`+` cannot be applied to `bool`", "+"),
+        ("`<` cannot be applied to `Foo`", "<"),
+        ("`<<` cannot be applied to `float`", "<<"),
+        ("`+` cannot be applied to `int` and `float`", "+"),
+        ("`==` cannot be applied to `bool` and `{float}`", "=="),
+        ("`!` cannot be applied to `int`", "!"),
+        ("`-` cannot be applied to `u8`", "-"),
+    ];
+    assert_eq!(
+        highlighted_diagnostics(text),
+        expected
+            .iter()
+            .map(|(message, text)| (message.to_string(), text.to_string()))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn invalid_operands_suggest_conversions() {
+    let text = r#"
+    export fn main(a: i32, b: u8, d: float) {
+        let _e = a == b;
+        let _f = a < b * b;
+        let _g = d * 2;
+        let _h = d + a;
+    }
+    "#;
+    let diagnostics = driver_with_text(text).diagnostics();
+    let labels: Vec<&str> = diagnostics[0]
+        .labels
+        .iter()
+        .map(|label| label.message.as_str())
+        .collect();
+    assert_eq!(
+        labels,
+        vec!["this operand has type `i32`", "this operand has type `u8`"]
+    );
+    let suggestions: Vec<(&str, &str)> = diagnostics
+        .iter()
+        .flat_map(|diagnostic| diagnostic.suggestions.iter())
+        .map(|s| (s.message.as_str(), s.replacement.as_str()))
+        .collect();
+    assert_eq!(
+        suggestions,
+        vec![
+            ("cast the right operand to `i32`", "b as i32"),
+            ("cast the right operand to `i32`", "(b * b) as i32"),
+            ("use a floating point literal", "2.0"),
+        ]
+    );
+}
+
+#[test]
+fn recursive_value_structs() {
+    let text = r#"
+    export struct(value) Node { value: int, next: Node }
+    "#;
+    assert_eq!(
+        highlighted_diagnostics(text),
+        vec![(
+            "recursive struct `Node` has infinite size, it contains itself by value through \
+             `Node.next`"
+                .to_string(),
+            "next: Node".to_string()
+        )]
+    );
+
+    // Every struct of the cycle is reported, also through arrays and tuples
+    let text = r#"
+    export struct(value) A { b: [B; 2] }
+    export struct(value) B { c: (int, A) }
+    "#;
+    assert_eq!(
+        highlighted_diagnostics(text),
+        vec![
+            (
+                "recursive struct `A` has infinite size, it contains itself by value through \
+                 `A.b` -> `B.c`"
+                    .to_string(),
+                "b: [B; 2]".to_string()
+            ),
+            (
+                "recursive struct `B` has infinite size, it contains itself by value through \
+                 `B.c` -> `A.b`"
+                    .to_string(),
+                "c: (int, A)".to_string()
+            )
+        ]
+    );
+}
+
+#[test]
+fn recursive_gc_structs() {
+    // A garbage collected struct is stored behind a pointer
+    let text = r#"
+    export struct Node { value: int, next: Node }
+    export struct(value) Link { node: Node }
+    export struct(gc) Tree { children: [Tree; 2], link: Link }
+    "#;
+    assert!(driver_with_text(text).diagnostics().is_empty());
+}
+
+fn emit_snapshot(text: &str) {
+    let text = text.trim().replace("\n    ", "\n");
+    insta::assert_snapshot!(insta::_macro_support::AutoName, emit(&text), &text);
+}
+
+/// Returns the diagnostics of the specified text as they are emitted by the driver.
+fn emit(text: &str) -> String {
+    let driver = driver_with_text(text);
+    let mut writer = NoColor::new(Vec::new());
+    driver.emit_diagnostics(&mut writer).unwrap();
+    String::from_utf8(writer.into_inner()).unwrap()
+}
+
+/// Returns the message and the highlighted text of every diagnostic of the specified text.
+fn highlighted_diagnostics(text: &str) -> Vec<(String, String)> {
+    driver_with_text(text)
+        .diagnostics()
+        .into_iter()
+        .map(|d| {
+            let range = d.loc.offset().to_usize()..d.loc.end_offset().to_usize();
+            (d.message, text[range].to_string())
+        })
+        .collect()
+}
+
+fn driver_with_text(text: &str) -> Driver {
+    driver_with_config(Config::default(), text)
+}
+
+fn driver_with_config(config: Config, text: &str) -> Driver {
+    let (driver, _) = Driver::with_file(
+        config,
+        PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("main.mun"),
+            contents: text.to_owned(),
+        },
+    )
+    .unwrap();
+    driver
+}
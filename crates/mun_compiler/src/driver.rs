@@ -2,30 +2,44 @@
 //! from previous compilation.
 
 use crate::{
-    db::CompilerDatabase,
-    diagnostics::{diagnostics, Emit},
-    PathOrInline,
+    db::{CompilerDatabase, ExecutedQueries},
+    diagnostics::{diagnostics, json, Emit},
+    stderr_color_choice, PathOrInline,
 };
-use mun_codegen::IrDatabase;
-use mun_hir::{FileId, RelativePathBuf, SourceDatabase, SourceRoot, SourceRootId};
+use mun_codegen::{Artifact, IrDatabase, ModuleArtifacts};
+use mun_hir::{
+    line_index::LineIndex, DefDatabase, FileId, HirDisplay, Module, ModuleDef, Pat, RelativePath,
+    RelativePathBuf, SourceDatabase, SourceRoot, SourceRootId, Ty,
+};
+use mun_syntax::{Parse, SourceFile};
 use mun_target::spec::Target;
 use std::{
+    io::{self, Write},
     path::{Path, PathBuf},
     sync::Arc,
 };
 
+mod build;
 mod config;
 
-pub use self::config::Config;
+pub use self::build::{BuildResult, DiagnosticSummary, EmittedArtifact, PhaseTiming};
+pub use self::config::{Config, MessageFormat};
 use mun_errors::{Diagnostic, Level};
-use termcolor::WriteColor;
+use termcolor::{StandardStream, WriteColor};
 
 pub const WORKSPACE: SourceRootId = SourceRootId(0);
 
+/// The extension of Mun source files.
+const SOURCE_FILE_EXTENSION: &str = "mun";
+
 #[derive(Debug)]
 pub struct Driver {
     db: CompilerDatabase,
     out_dir: Option<PathBuf>,
+    emit: Vec<Artifact>,
+    message_format: MessageFormat,
+    deny_warnings: bool,
+    next_file_id: u32,
 }
 
 impl Driver {
@@ -34,7 +48,14 @@ impl Driver {
         let mut driver = Driver {
             db: CompilerDatabase::new(),
             out_dir: None,
+            emit: Vec::new(),
+            message_format: MessageFormat::Human,
+            deny_warnings: false,
+            next_file_id: 0,
         };
+        driver
+            .db
+            .set_source_root(WORKSPACE, Arc::new(SourceRoot::default()));
 
         // Move relevant configuration into the database
         driver.db.set_target(config.target);
@@ -42,8 +63,34 @@ impl Driver {
             .db
             .set_context(Arc::new(mun_codegen::Context::create()));
         driver.db.set_optimization_lvl(config.optimization_lvl);
+        driver.db.set_pass_pipeline(config.pass_pipeline);
+        driver.db.set_call_mode(config.call_mode);
+        driver.db.set_overflow_checks(config.overflow_checks);
+        driver.db.set_division_checks(config.division_checks);
+        driver.db.set_bounds_checks(config.bounds_checks);
+        driver.db.set_assertions(config.assertions);
+        driver.db.set_float_semantics(config.float_semantics);
+        driver.db.set_debug_info(
+            config
+                .debug_info
+                .unwrap_or(config.optimization_lvl == mun_codegen::OptimizationLevel::None),
+        );
+        driver.db.set_ir_verification(config.ir_verification);
+        driver.db.set_codegen_units(config.codegen_units);
+        driver
+            .db
+            .set_dead_code_elimination(config.dead_code_elimination);
+        driver.db.set_tail_call_notes(config.tail_call_notes);
+        driver.db.set_lint_levels(Arc::new(config.lint_levels));
+        driver.db.set_test_build(config.test_build);
+        driver
+            .db
+            .set_assembly_dependencies(Arc::new(config.dependencies));
 
         driver.out_dir = config.out_dir;
+        driver.emit = config.emit;
+        driver.message_format = config.message_format;
+        driver.deny_warnings = config.deny_warnings;
 
         driver
     }
@@ -53,11 +100,6 @@ impl Driver {
         config: Config,
         path: PathOrInline,
     ) -> Result<(Driver, FileId), failure::Error> {
-        let mut driver = Driver::with_config(config);
-
-        // Construct a SourceRoot
-        let mut source_root = SourceRoot::default();
-
         // Get the path and contents of the path
         let (rel_path, text) = match path {
             PathOrInline::Path(p) => {
@@ -75,15 +117,204 @@ impl Driver {
             PathOrInline::Inline { rel_path, contents } => (rel_path, contents),
         };
 
-        // Store the file information in the database together with the source root
-        let file_id = FileId(0);
-        driver.db.set_file_relative_path(file_id, rel_path.clone());
-        driver.db.set_file_text(file_id, Arc::new(text));
-        driver.db.set_file_source_root(file_id, WORKSPACE);
+        Driver::with_files(config, vec![(rel_path, text)])
+    }
+
+    /// Constructs a driver with a configuration and the files of a package, given by their paths
+    /// relative to the package and their contents. Returns the driver and the file of the root
+    /// module of the package, for which the assembly of the package is generated.
+    pub fn with_files(
+        config: Config,
+        files: Vec<(RelativePathBuf, String)>,
+    ) -> Result<(Driver, FileId), failure::Error> {
+        let mut driver = Driver::with_config(config);
+        for (rel_path, text) in files {
+            driver.add_file(rel_path, text);
+        }
+
+        let root = driver.db.module_tree(WORKSPACE).root().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "The package has no root module, expected a `main.mun` file.",
+            )
+        })?;
+        Ok((driver, root))
+    }
+
+    /// Constructs a driver with a configuration and the package in the directory `dir`, which
+    /// consists of all Mun files in the directory and its subdirectories.
+    pub fn with_package(config: Config, dir: &Path) -> Result<(Driver, FileId), failure::Error> {
+        let mut files = Vec::new();
+        collect_source_files(dir, dir, &mut files)?;
+        Driver::with_files(config, files)
+    }
+}
+
+/// Adds the path relative to `root` and the contents of every Mun file in `dir` and its
+/// subdirectories to `files`.
+pub(crate) fn collect_source_files(
+    root: &Path,
+    dir: &Path,
+    files: &mut Vec<(RelativePathBuf, String)>,
+) -> Result<(), std::io::Error> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_source_files(root, &path, files)?;
+        } else if path.extension().and_then(|ext| ext.to_str()) == Some(SOURCE_FILE_EXTENSION) {
+            let rel_path = path
+                .strip_prefix(root)
+                .ok()
+                .and_then(|rel_path| RelativePathBuf::from_path(rel_path).ok())
+                .expect("the files of a directory are relative to the directory");
+            files.push((rel_path, std::fs::read_to_string(&path)?));
+        }
+    }
+    Ok(())
+}
+
+impl Driver {
+    /// Returns the file at `rel_path` relative to the package, if the package contains it.
+    pub fn file_id(&self, rel_path: &RelativePath) -> Option<FileId> {
+        self.db
+            .source_root(WORKSPACE)
+            .file_by_relative_path(rel_path)
+    }
+
+    /// Adds a file with the specified contents at `rel_path` relative to the package.
+    pub fn add_file(&mut self, rel_path: RelativePathBuf, text: String) -> FileId {
+        let file_id = FileId(self.next_file_id);
+        self.next_file_id += 1;
+
+        self.db.set_file_relative_path(file_id, rel_path.clone());
+        self.db.set_file_text(file_id, Arc::new(text));
+        self.db.set_file_source_root(file_id, WORKSPACE);
+        let mut source_root = (*self.db.source_root(WORKSPACE)).clone();
         source_root.insert_file(rel_path, file_id);
-        driver.db.set_source_root(WORKSPACE, Arc::new(source_root));
+        self.db.set_source_root(WORKSPACE, Arc::new(source_root));
+        file_id
+    }
+
+    /// Removes the file at `rel_path` relative to the package from the package.
+    pub fn remove_file(&mut self, rel_path: &RelativePath) {
+        let mut source_root = (*self.db.source_root(WORKSPACE)).clone();
+        source_root.remove_file(rel_path);
+        self.db.set_source_root(WORKSPACE, Arc::new(source_root));
+    }
+
+    /// Adds all Mun files in the directory `dir` and its subdirectories to the package, at their
+    /// paths relative to `dir`.
+    pub fn add_source_files(&mut self, dir: &Path) -> Result<(), failure::Error> {
+        let mut files = Vec::new();
+        collect_source_files(dir, dir, &mut files)?;
+        for (rel_path, text) in files {
+            self.add_file(rel_path, text);
+        }
+        Ok(())
+    }
+
+    /// Returns all files of the package.
+    pub fn files(&self) -> Vec<FileId> {
+        self.db.module_tree(WORKSPACE).files().to_vec()
+    }
+
+    /// Returns the path of a specific file relative to the package.
+    pub fn file_relative_path(&self, file_id: FileId) -> RelativePathBuf {
+        self.db.file_relative_path(file_id)
+    }
+
+    /// Returns the contents of a specific file.
+    pub fn file_text(&self, file_id: FileId) -> Arc<String> {
+        self.db.file_text(file_id)
+    }
+
+    /// Returns the line index of a specific file, which maps offsets to lines and columns.
+    pub fn line_index(&self, file_id: FileId) -> Arc<LineIndex> {
+        self.db.line_index(file_id)
+    }
+
+    /// Returns the syntax tree of a specific file.
+    pub fn parse(&self, file_id: FileId) -> Parse<SourceFile> {
+        self.db.parse(file_id)
+    }
+
+    /// Parses the files of the workspace that changed since they were last parsed.
+    pub fn parse_files(&self) {
+        for &file_id in self.db.module_tree(WORKSPACE).files() {
+            self.db.parse(file_id);
+        }
+    }
+
+    /// Runs `f` and returns its result, along with the queries of the driver that it executed
+    /// rather than reused from an earlier build.
+    pub fn count_executed_queries<T>(&self, f: impl FnOnce() -> T) -> (T, ExecutedQueries) {
+        self.db.count_executed_queries(f)
+    }
 
-        Ok((driver, file_id))
+    /// Returns a readable dump of the syntax tree of a specific file, with the range of every node
+    /// and token. Nodes that could not be parsed are `ERROR` nodes, and the syntax errors are
+    /// listed after the tree.
+    pub fn dump_ast(&self, file_id: FileId) -> String {
+        self.db.parse(file_id).debug_dump()
+    }
+
+    /// Returns a readable dump of the lowered bodies of the items of a specific file.
+    pub fn dump_hir(&self, file_id: FileId) -> String {
+        mun_hir::dump::dump_bodies(&self.db, file_id)
+    }
+
+    /// Returns a readable dump of the lowered bodies of the items of a specific file, with the
+    /// inferred type of every expression and pattern.
+    pub fn dump_types(&self, file_id: FileId) -> String {
+        mun_hir::dump::dump_types(&self.db, file_id)
+    }
+
+    /// Returns the qualified names of the `#[test]` functions of the package, e.g.
+    /// `math::adds_numbers`, by which the runtime retrieves them in a test build. The tests of a
+    /// file are in source order, and the files are ordered by their paths.
+    pub fn test_functions(&self) -> Vec<String> {
+        let mut files = self.files();
+        files.sort_by_key(|&file_id| self.file_relative_path(file_id));
+        files
+            .into_iter()
+            .flat_map(|file_id| Module::from(file_id).declarations(&self.db))
+            .filter_map(|def| match def {
+                ModuleDef::Function(function) if function.is_test(&self.db) => {
+                    Some(function.qualified_name(&self.db))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the inferred type of the local variable `name` of the function `fn_name` in a
+    /// specific file, as it is written in Mun, if the function binds the variable and its type is
+    /// known.
+    pub fn local_type(&self, file_id: FileId, fn_name: &str, name: &str) -> Option<String> {
+        let function = Module::from(file_id)
+            .declarations(&self.db)
+            .into_iter()
+            .find_map(|def| match def {
+                ModuleDef::Function(function) if function.name(&self.db).to_string() == fn_name => {
+                    Some(function)
+                }
+                _ => None,
+            })?;
+        let infer = function.infer(&self.db);
+        let ty = function
+            .body(&self.db)
+            .pats()
+            .find_map(|(pat, data)| match data {
+                Pat::Bind { name: binding, .. } if binding.to_string() == name => {
+                    Some(infer[pat].clone())
+                }
+                _ => None,
+            })?;
+        if ty == Ty::Unknown {
+            None
+        } else {
+            Some(ty.display(&self.db).to_string())
+        }
     }
 }
 
@@ -99,18 +330,40 @@ impl Driver {
     /// Returns a vector containing all the diagnostic messages for the project.
     pub fn diagnostics(&self) -> Vec<Diagnostic> {
         self.db
-            .source_root(WORKSPACE)
+            .module_tree(WORKSPACE)
             .files()
-            .map(|f| diagnostics(&self.db, f))
+            .iter()
+            .map(|f| diagnostics(&self.db, *f))
             .flatten()
             .collect()
     }
 
+    /// Returns the diagnostic messages of a specific file.
+    pub fn file_diagnostics(&self, file_id: FileId) -> Vec<Diagnostic> {
+        diagnostics(&self.db, file_id)
+    }
+
+    /// Returns all diagnostic messages of the project as data, with the file and the lines and
+    /// columns of their locations resolved.
+    pub fn json_diagnostics(&self) -> Vec<json::JsonDiagnostic> {
+        self.db
+            .module_tree(WORKSPACE)
+            .files()
+            .iter()
+            .flat_map(|&file_id| {
+                diagnostics(&self.db, file_id)
+                    .iter()
+                    .map(|diagnostic| json::JsonDiagnostic::new(&self.db, file_id, diagnostic))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
     /// Emits all diagnostic messages currently in the database; returns true if errors were
     /// emitted.
     pub fn emit_diagnostics(&self, writer: &mut impl WriteColor) -> Result<bool, failure::Error> {
         let mut has_errors = false;
-        for file_id in self.db.source_root(WORKSPACE).files() {
+        for &file_id in self.db.module_tree(WORKSPACE).files() {
             let diags = diagnostics(&self.db, file_id);
             for diagnostic in diags.iter() {
                 diagnostic.emit(writer, &self.db, file_id)?;
@@ -121,11 +374,65 @@ impl Driver {
         }
         Ok(has_errors)
     }
+
+    /// Emits the error messages currently in the database, but no warnings or notes; returns true
+    /// if errors were emitted.
+    pub fn emit_errors(&self, writer: &mut impl WriteColor) -> Result<bool, failure::Error> {
+        let mut has_errors = false;
+        for &file_id in self.db.module_tree(WORKSPACE).files() {
+            for diagnostic in diagnostics(&self.db, file_id).iter() {
+                if diagnostic.level == Level::Error {
+                    diagnostic.emit(writer, &self.db, file_id)?;
+                    has_errors = true;
+                }
+            }
+        }
+        Ok(has_errors)
+    }
+
+    /// Writes all diagnostic messages currently in the database as JSON messages, one per line;
+    /// returns true if errors were written.
+    pub fn emit_json_diagnostics(&self, writer: &mut impl Write) -> Result<bool, failure::Error> {
+        let mut has_errors = false;
+        for &file_id in self.db.module_tree(WORKSPACE).files() {
+            for diagnostic in diagnostics(&self.db, file_id).iter() {
+                let message = json::Message::diagnostic(&self.db, file_id, diagnostic);
+                writeln!(writer, "{}", message.to_json())?;
+                if diagnostic.level == Level::Error {
+                    has_errors = true;
+                }
+            }
+        }
+        Ok(has_errors)
+    }
+
+    /// Returns the format in which diagnostics are reported.
+    pub fn message_format(&self) -> MessageFormat {
+        self.message_format
+    }
+
+    /// Returns whether a compilation fails if it reports warnings.
+    pub fn deny_warnings(&self) -> bool {
+        self.deny_warnings
+    }
+
+    /// Reports all diagnostic messages currently in the database in the message format of the
+    /// configuration, as text to stderr or as JSON to stdout; returns the numbers of warnings and
+    /// errors that were reported.
+    pub fn report_diagnostics(&self) -> Result<DiagnosticSummary, failure::Error> {
+        match self.message_format {
+            MessageFormat::Human => {
+                self.emit_diagnostics(&mut StandardStream::stderr(stderr_color_choice()))?
+            }
+            MessageFormat::Json => self.emit_json_diagnostics(&mut io::stdout().lock())?,
+        };
+        Ok(DiagnosticSummary::of(&self.json_diagnostics()))
+    }
 }
 
 impl Driver {
     /// Computes the output path for the assembly of the specified file.
-    fn assembly_output_path(&self, file_id: FileId) -> PathBuf {
+    pub fn assembly_output_path(&self, file_id: FileId) -> PathBuf {
         let target: Target = self.db.target();
         let relative_path: RelativePathBuf = self.db.file_relative_path(file_id);
         let original_filename = Path::new(relative_path.file_name().unwrap());
@@ -148,10 +455,34 @@ impl Driver {
         }
     }
 
-    /// Generate an assembly for the given file
+    /// Generates the IR of a specific file, for the functions of which the IR changed since it was
+    /// last generated. The machine code is generated by `write_assembly`.
+    pub fn generate_ir(&self, file_id: FileId) {
+        self.db.module_ir(file_id);
+    }
+
+    /// Generate an assembly for the given file, and the other artifacts of the configuration.
+    /// Returns the path of the assembly, or `None` if the configuration does not emit a shared
+    /// library.
     pub fn write_assembly(&self, file_id: FileId) -> Result<Option<PathBuf>, failure::Error> {
         let output_path = self.assembly_output_path(file_id);
-        mun_codegen::write_module_shared_object(&self.db, file_id, &output_path)?;
-        Ok(Some(output_path))
+        mun_codegen::write_module_artifacts(&self.db, file_id, &output_path, &self.emit)?;
+        if self.emit.contains(&Artifact::Lib) {
+            Ok(Some(output_path))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Generates the shared library of the given file, and its optimized IR if `emit_ir` is set,
+    /// in memory. The artifacts of the configuration and its output directory are ignored. See
+    /// `mun_codegen::emit_module_artifacts` for the files that are still written temporarily.
+    pub fn emit_assembly(
+        &self,
+        file_id: FileId,
+        emit_ir: bool,
+    ) -> Result<ModuleArtifacts, failure::Error> {
+        let relative_path = self.db.file_relative_path(file_id);
+        mun_codegen::emit_module_artifacts(&self.db, file_id, relative_path.as_str(), emit_ir)
     }
 }
@@ -0,0 +1,221 @@
+use super::Driver;
+use crate::db::ExecutedQueries;
+use crate::diagnostics::json::{JsonDiagnostic, Severity};
+use mun_codegen::Artifact;
+use mun_hir::FileId;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::AddAssign;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// The outcome of a build by `Driver::build`, from which the output of the compiler is formatted.
+#[derive(Debug, Clone)]
+pub struct BuildResult {
+    /// Whether the build succeeded, i.e. there were no errors, nor warnings if warnings are denied,
+    /// and the artifacts were written
+    pub success: bool,
+    /// The target triple that the assembly was compiled for
+    pub target_triple: String,
+    /// The artifacts that were written, which is empty if the build failed
+    pub artifacts: Vec<EmittedArtifact>,
+    /// All diagnostics of the package, including warnings and notes
+    pub diagnostics: Vec<JsonDiagnostic>,
+    /// The phases of the build in the order in which they ran
+    pub phases: Vec<PhaseTiming>,
+    /// A hash of the contents of the shared library, if it was written
+    pub library_hash: Option<u64>,
+    /// Whether the contents of the shared library differ from those of the shared library that
+    /// was at its path before the build
+    pub library_changed: bool,
+}
+
+/// An artifact that was written by a build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmittedArtifact {
+    pub kind: Artifact,
+    pub path: PathBuf,
+}
+
+/// The numbers of warnings and errors that a compilation reported, e.g. over all packages of a
+/// build. Warnings of which the lint is allowed are not reported, so they aren't counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiagnosticSummary {
+    pub warnings: usize,
+    pub errors: usize,
+}
+
+/// A phase of a build, which took `duration` and executed the queries `executed`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseTiming {
+    /// The name of the phase: `parse`, `check`, `ir` or `codegen`
+    pub name: &'static str,
+    pub duration: Duration,
+    pub executed: ExecutedQueries,
+}
+
+impl BuildResult {
+    /// Returns the path of the shared library that was written, if any.
+    pub fn library_path(&self) -> Option<&Path> {
+        self.artifacts
+            .iter()
+            .find(|artifact| artifact.kind == Artifact::Lib)
+            .map(|artifact| artifact.path.as_path())
+    }
+
+    /// Returns the numbers of warnings and errors of the build.
+    pub fn summary(&self) -> DiagnosticSummary {
+        DiagnosticSummary::of(&self.diagnostics)
+    }
+}
+
+impl DiagnosticSummary {
+    /// Counts the warnings and errors of `diagnostics`.
+    pub fn of(diagnostics: &[JsonDiagnostic]) -> Self {
+        let count = |severity| {
+            diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.severity == severity)
+                .count()
+        };
+        DiagnosticSummary {
+            warnings: count(Severity::Warning),
+            errors: count(Severity::Error),
+        }
+    }
+
+    /// Returns whether a compilation with these diagnostics fails, which it does if there are
+    /// errors or, if warnings are denied, warnings.
+    pub fn fails(self, deny_warnings: bool) -> bool {
+        self.errors > 0 || (deny_warnings && self.warnings > 0)
+    }
+}
+
+impl AddAssign for DiagnosticSummary {
+    fn add_assign(&mut self, other: Self) {
+        self.warnings += other.warnings;
+        self.errors += other.errors;
+    }
+}
+
+/// Formats the summary as the last line of the output of a compilation, e.g.
+/// `finished with 3 warnings, 0 errors`.
+impl fmt::Display for DiagnosticSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let plural = |count| if count == 1 { "" } else { "s" };
+        write!(
+            f,
+            "finished with {} warning{}, {} error{}",
+            self.warnings,
+            plural(self.warnings),
+            self.errors,
+            plural(self.errors)
+        )
+    }
+}
+
+/// Formats the phase as a line of a timings report, with its duration and the queries that it
+/// executed, e.g. `check       1.20ms, 1 body inferred`.
+impl fmt::Display for PhaseTiming {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:<8} {:>10}",
+            self.name,
+            format!("{:.2?}", self.duration)
+        )?;
+        let counts = [
+            (self.executed.parsed_files, "file parsed", "files parsed"),
+            (
+                self.executed.inferred_bodies,
+                "body inferred",
+                "bodies inferred",
+            ),
+            (
+                self.executed.generated_functions,
+                "function generated",
+                "functions generated",
+            ),
+        ];
+        for (count, singular, plural) in counts.iter() {
+            if *count > 0 {
+                let noun = if *count == 1 { singular } else { plural };
+                write!(f, ", {} {}", count, noun)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Driver {
+    /// Builds the assembly of the given file, and the other artifacts of the configuration. The
+    /// artifacts are only written if the package has no errors, nor warnings if warnings are
+    /// denied. Nothing is reported; the diagnostics are returned as part of the result instead.
+    /// An error is returned if the artifacts could not be written.
+    pub fn build(&self, file_id: FileId) -> Result<BuildResult, failure::Error> {
+        let mut phases = Vec::new();
+        self.phase("parse", &mut phases, || self.parse_files());
+        let diagnostics = self.phase("check", &mut phases, || self.json_diagnostics());
+        let success = !DiagnosticSummary::of(&diagnostics).fails(self.deny_warnings);
+
+        let mut result = BuildResult {
+            success,
+            target_triple: self.db.target().llvm_target,
+            artifacts: Vec::new(),
+            diagnostics,
+            phases: Vec::new(),
+            library_hash: None,
+            library_changed: false,
+        };
+        if success {
+            self.phase("ir", &mut phases, || self.generate_ir(file_id));
+
+            let library_path = self.assembly_output_path(file_id);
+            let previous_hash = content_hash(&library_path);
+            self.phase("codegen", &mut phases, || self.write_assembly(file_id))?;
+
+            let target = self.db.target();
+            result.artifacts = self
+                .emit
+                .iter()
+                .map(|&kind| EmittedArtifact {
+                    kind,
+                    path: kind.output_path(&library_path, &target),
+                })
+                .collect();
+            if self.emit.contains(&Artifact::Lib) {
+                result.library_hash = content_hash(&library_path);
+                result.library_changed = result.library_hash != previous_hash;
+            }
+        }
+        result.phases = phases;
+        Ok(result)
+    }
+
+    /// Runs the phase `name` of a build, and records its duration and the queries that it
+    /// executed in `phases`.
+    fn phase<T>(
+        &self,
+        name: &'static str,
+        phases: &mut Vec<PhaseTiming>,
+        f: impl FnOnce() -> T,
+    ) -> T {
+        let start = Instant::now();
+        let (result, executed) = self.count_executed_queries(f);
+        phases.push(PhaseTiming {
+            name,
+            duration: start.elapsed(),
+            executed,
+        });
+        result
+    }
+}
+
+/// Returns a hash of the contents of the file at `path`, or `None` if it cannot be read.
+fn content_hash(path: &Path) -> Option<u64> {
+    let contents = std::fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    Some(hasher.finish())
+}
@@ -1,5 +1,8 @@
 use crate::host_triple;
-use mun_codegen::OptimizationLevel;
+use mun_codegen::{
+    Artifact, CallMode, FloatSemantics, IrVerification, OptimizationLevel, PassPipeline,
+};
+use mun_hir::lint::LintLevels;
 use mun_target::spec::Target;
 use std::path::PathBuf;
 
@@ -12,9 +15,87 @@ pub struct Config {
     /// The optimization level to use for the IR generation.
     pub optimization_lvl: OptimizationLevel,
 
+    /// The LLVM passes that optimize the generated IR. A custom pipeline replaces the passes of
+    /// the optimization level.
+    pub pass_pipeline: PassPipeline,
+
+    /// How calls between functions of the same assembly are generated.
+    pub call_mode: CallMode,
+
+    /// Whether integer arithmetic that overflows traps, rather than silently wrapping around.
+    pub overflow_checks: bool,
+
+    /// Whether integer division and remainder by zero trap. Without the checks, dividing by zero
+    /// has undefined behavior.
+    pub division_checks: bool,
+
+    /// Whether indexing an array out of bounds traps. Without the checks, an out-of-bounds index
+    /// accesses memory outside of the array.
+    pub bounds_checks: bool,
+
+    /// Whether the `assert` and `assert_eq` builtins are compiled. Otherwise they are compiled out,
+    /// without evaluating their operands.
+    pub assertions: bool,
+
+    /// Whether floating point operations strictly follow IEEE 754, or may be optimized as if
+    /// their operands and results are neither NaN nor infinite.
+    pub float_semantics: FloatSemantics,
+
+    /// Whether DWARF debug info is generated, so native debuggers can map the machine code of
+    /// functions to the lines of their source, e.g. to set breakpoints. If `None`, debug info is
+    /// generated for unoptimized code.
+    pub debug_info: Option<bool>,
+
+    /// Whether the generated IR is verified before machine code is generated.
+    pub ir_verification: IrVerification,
+
+    /// The maximum number of units into which every compiled file is split, that are optimized and
+    /// compiled to machine code in parallel.
+    pub codegen_units: usize,
+
+    /// Whether code is only generated for the private functions that are reachable from the
+    /// `export` functions of a file. Unused functions are reported as warnings.
+    pub dead_code_elimination: bool,
+
+    /// Whether recursive calls that are not in tail position are reported as notes. Only calls in
+    /// tail position are turned into a loop that doesn't grow the stack.
+    pub tail_call_notes: bool,
+
+    /// The levels of the lints, which the lint attributes of the code override, e.g. to turn all
+    /// warnings into errors.
+    pub lint_levels: LintLevels,
+
+    /// Whether a build or check fails if it reports any warning. Unlike denying all lints, the
+    /// warnings are still reported as warnings.
+    pub deny_warnings: bool,
+
+    /// Whether the `#[test]` functions are compiled, so a test runner can invoke them through the
+    /// runtime. Otherwise tests are only compiled if other functions use them.
+    pub test_build: bool,
+
     /// The optional output directory to store all outputs. If no directory is specified all output
     /// is stored in a temporary directory.
     pub out_dir: Option<PathBuf>,
+
+    /// The artifacts that are written for every compiled file. Artifacts other than the shared
+    /// library are stored next to it.
+    pub emit: Vec<Artifact>,
+
+    /// The paths of the assemblies that the assembly depends on, relative to the output directory.
+    /// The runtime loads them before the assembly.
+    pub dependencies: Vec<String>,
+
+    /// How diagnostics are reported.
+    pub message_format: MessageFormat,
+}
+
+/// The format in which diagnostics are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageFormat {
+    /// Human readable text, written to stderr
+    Human,
+    /// A JSON object per line, written to stdout. See `mun_compiler::json` for the schema.
+    Json,
 }
 
 impl Default for Config {
@@ -25,7 +106,30 @@ impl Default for Config {
             // triple.
             target: target.unwrap(),
             optimization_lvl: OptimizationLevel::Default,
+            pass_pipeline: PassPipeline::Default,
+            call_mode: CallMode::Dispatch,
+            overflow_checks: false,
+            division_checks: true,
+            bounds_checks: false,
+            assertions: true,
+            float_semantics: FloatSemantics::Strict,
+            debug_info: None,
+            // Debug builds of the compiler catch invalid IR, release builds skip the verification
+            ir_verification: if cfg!(debug_assertions) {
+                IrVerification::On
+            } else {
+                IrVerification::Off
+            },
+            codegen_units: 1,
+            dead_code_elimination: true,
+            tail_call_notes: false,
+            lint_levels: LintLevels::default(),
+            deny_warnings: false,
+            test_build: false,
             out_dir: None,
+            emit: vec![Artifact::Lib],
+            dependencies: Vec::new(),
+            message_format: MessageFormat::Human,
         }
     }
 }
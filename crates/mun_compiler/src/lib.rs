@@ -1,17 +1,33 @@
 #![allow(clippy::enum_variant_names)] // This is a HACK because we use salsa
 
+mod compile;
 mod db;
 ///! This library contains the code required to go from source code to binaries.
 mod diagnostics;
 mod driver;
+mod manifest;
+#[cfg(test)]
+mod tests;
 
+use mun_hir::line_index::LineIndex;
+pub use mun_hir::lint::{Lint, LintLevel, LintLevels};
 pub use mun_hir::{FileId, RelativePath, RelativePathBuf};
 pub use mun_target::spec::Target;
+use std::collections::HashMap;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 pub use termcolor::{ColorChoice, StandardStream};
 
-pub use crate::driver::{Config, Driver};
-pub use mun_codegen::OptimizationLevel;
+pub use crate::compile::{compile_str, CompileError, CompiledArtifact};
+pub use crate::db::ExecutedQueries;
+pub use crate::diagnostics::json;
+pub use crate::driver::{
+    BuildResult, Config, DiagnosticSummary, Driver, EmittedArtifact, MessageFormat, PhaseTiming,
+};
+pub use crate::manifest::{Manifest, ManifestError, MANIFEST_FILE_NAME};
+pub use mun_codegen::{
+    Artifact, CallMode, FloatSemantics, IrVerification, OptimizationLevel, PassPipeline,
+};
 
 #[derive(Debug, Clone)]
 pub enum PathOrInline {
@@ -66,13 +82,230 @@ pub fn host_triple() -> &'static str {
     (option_env!("CFG_COMPILER_HOST_TRIPLE")).expect("CFG_COMPILER_HOST_TRIPLE")
 }
 
-pub fn main(options: CompilerOptions) -> Result<Option<PathBuf>, failure::Error> {
-    let (driver, file_id) = Driver::with_file(options.config, options.input)?;
-
-    let mut writer = StandardStream::stderr(ColorChoice::Auto);
-    if driver.emit_diagnostics(&mut writer)? {
-        Ok(None)
+/// Returns whether diagnostics that are written to stderr are colored. Colors are only used if
+/// stderr is a terminal and the `NO_COLOR` environment variable is not set, see
+/// https://no-color.org.
+pub fn stderr_color_choice() -> ColorChoice {
+    if std::env::var_os("NO_COLOR").is_some() || !atty::is(atty::Stream::Stderr) {
+        ColorChoice::Never
     } else {
-        driver.write_assembly(file_id)
+        ColorChoice::Auto
+    }
+}
+
+/// Builds the input of `options`, and reports its diagnostics in the message format of the
+/// configuration. The output is formatted from the returned result, which describes the build.
+pub fn main(options: CompilerOptions) -> Result<BuildResult, failure::Error> {
+    let (driver, file_id) = driver_with_input(options)?;
+    build_and_report(&driver, file_id)
+}
+
+/// Builds the input of `options` like `main`, but in a test build, in which the `#[test]`
+/// functions are compiled so the runtime can invoke them. Returns the result of the build and the
+/// qualified names of the tests, in the order in which they should run.
+pub fn build_tests(options: CompilerOptions) -> Result<(BuildResult, Vec<String>), failure::Error> {
+    let options = CompilerOptions {
+        config: Config {
+            test_build: true,
+            ..options.config
+        },
+        ..options
+    };
+    let (driver, file_id) = driver_with_input(options)?;
+    let result = build_and_report(&driver, file_id)?;
+    Ok((result, driver.test_functions()))
+}
+
+/// Builds the assembly of the file `file_id` of `driver`, and reports the diagnostics and the
+/// outcome of the build.
+fn build_and_report(driver: &Driver, file_id: FileId) -> Result<BuildResult, failure::Error> {
+    let result = driver.build(file_id)?;
+    report_build_diagnostics(driver, &result)?;
+    report_build_finished(
+        driver.message_format(),
+        result.success,
+        result.library_path(),
+        result.summary(),
+    )?;
+    Ok(result)
+}
+
+/// Checks the input of `options` for errors without generating code. The diagnostics are reported
+/// like those of `main`, and are the same as the diagnostics of a build. Returns true if there are
+/// no errors, nor warnings if warnings are denied.
+pub fn check(options: CompilerOptions) -> Result<bool, failure::Error> {
+    let (driver, _) = driver_with_input(options)?;
+    let summary = driver.report_diagnostics()?;
+    let success = !summary.fails(driver.deny_warnings());
+    report_build_finished(driver.message_format(), success, None, summary)?;
+    Ok(success)
+}
+
+/// Constructs a driver for the input of `options`, and returns the file of the assembly.
+fn driver_with_input(options: CompilerOptions) -> Result<(Driver, FileId), failure::Error> {
+    match options.input {
+        // A directory is compiled as a package of all the Mun files it contains
+        PathOrInline::Path(ref path) if path.is_dir() => Driver::with_package(options.config, path),
+        input => Driver::with_file(options.config, input),
+    }
+}
+
+/// Reports the diagnostics of the build `result` of `driver` in the message format of the driver,
+/// as text to stderr or as JSON to stdout.
+fn report_build_diagnostics(driver: &Driver, result: &BuildResult) -> Result<(), failure::Error> {
+    match driver.message_format() {
+        MessageFormat::Human => {
+            driver.emit_diagnostics(&mut StandardStream::stderr(stderr_color_choice()))?;
+        }
+        MessageFormat::Json => {
+            let stdout = io::stdout();
+            let mut stdout = stdout.lock();
+            for diagnostic in result.diagnostics.iter() {
+                let message = json::Message::Diagnostic(diagnostic.clone());
+                writeln!(stdout, "{}", message.to_json())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reports the end of a build with the numbers of warnings and errors of `summary`, as the final
+/// JSON message or, if the build reported any diagnostics, as a line of text to stderr.
+fn report_build_finished(
+    message_format: MessageFormat,
+    success: bool,
+    artifact: Option<&Path>,
+    summary: DiagnosticSummary,
+) -> Result<(), failure::Error> {
+    match message_format {
+        MessageFormat::Human => {
+            if summary != DiagnosticSummary::default() {
+                eprintln!("{}", summary);
+            }
+        }
+        MessageFormat::Json => {
+            let message = json::Message::build_finished(success, artifact, summary);
+            writeln!(io::stdout().lock(), "{}", message.to_json())?;
+        }
+    }
+    Ok(())
+}
+
+/// Builds the package of `manifest` after the packages that it depends on, with the settings of
+/// `config`. The assembly of every package is written to a directory of its own in the target
+/// directory of the package, and refers to the assemblies of its dependencies relative to its own
+/// directory, so the runtime loads them. Returns the result of the build of the package, or of the
+/// first package that has errors, after which no other packages are built.
+pub fn build_package(manifest: &Manifest, config: Config) -> Result<BuildResult, failure::Error> {
+    let target_dir = manifest.target_dir();
+    let message_format = config.message_format;
+    let mut assembly_file_names = HashMap::new();
+    let mut summary = DiagnosticSummary::default();
+    let mut result = None;
+    for package in manifest.build_order()? {
+        let out_dir = target_dir.join(package.name());
+        std::fs::create_dir_all(&out_dir)?;
+
+        // The assemblies of dependencies are stored in sibling directories
+        let dependencies = package
+            .dependency_names()
+            .filter_map(|name| {
+                let file_name = assembly_file_names.get(name)?;
+                Some(format!("../{}/{}", name, file_name))
+            })
+            .collect();
+        let config = Config {
+            out_dir: Some(out_dir),
+            dependencies,
+            ..config.clone()
+        };
+
+        let (driver, file_id) = Driver::with_package(config, &package.source_dir())?;
+        let package_result = driver.build(file_id)?;
+        report_build_diagnostics(&driver, &package_result)?;
+        summary += package_result.summary();
+        if !package_result.success {
+            report_build_finished(message_format, false, None, summary)?;
+            return Ok(package_result);
+        }
+        if let Some(file_name) = package_result.library_path().and_then(Path::file_name) {
+            assembly_file_names.insert(
+                package.name().to_string(),
+                file_name.to_string_lossy().into_owned(),
+            );
+        }
+        result = Some(package_result);
+    }
+
+    // The package itself is always the last package of the build order
+    let result = result.expect("a package is built after its dependencies");
+    report_build_finished(message_format, true, result.library_path(), summary)?;
+    Ok(result)
+}
+
+/// Checks the package of `manifest` and the packages that it depends on for errors without
+/// generating code. Unlike a build, the packages after a package with errors are still checked.
+/// Returns true if there are no errors, nor warnings if warnings are denied.
+pub fn check_package(manifest: &Manifest, config: Config) -> Result<bool, failure::Error> {
+    let message_format = config.message_format;
+    let mut summary = DiagnosticSummary::default();
+    for package in manifest.build_order()? {
+        let (driver, _) = Driver::with_package(config.clone(), &package.source_dir())?;
+        summary += driver.report_diagnostics()?;
+    }
+    let success = !summary.fails(config.deny_warnings);
+    report_build_finished(message_format, success, None, summary)?;
+    Ok(success)
+}
+
+/// Formats the Mun files at `paths`, where a directory stands for the Mun files in it and its
+/// subdirectories. In `check` mode the files are left untouched, and the files that are not
+/// formatted are reported instead. Files with syntax errors are never rewritten, their errors are
+/// reported instead. Returns true if all files are formatted.
+pub fn format(paths: &[PathBuf], check: bool) -> Result<bool, failure::Error> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut dir_files = Vec::new();
+            driver::collect_source_files(path, path, &mut dir_files)?;
+            files.extend(
+                dir_files
+                    .into_iter()
+                    .map(|(rel_path, text)| (rel_path.to_path(path), text)),
+            );
+        } else {
+            files.push((path.clone(), std::fs::read_to_string(path)?));
+        }
+    }
+
+    let mut success = true;
+    for (path, text) in files {
+        match mun_syntax::format(&text) {
+            Ok(formatted) if formatted == text => {}
+            Ok(_) if check => {
+                eprintln!("{} is not formatted", path.display());
+                success = false;
+            }
+            Ok(formatted) => std::fs::write(&path, formatted)?,
+            Err(errors) => {
+                let line_index = LineIndex::new(&text);
+                for error in errors {
+                    let line_col = line_index.line_col(error.location().offset());
+                    eprintln!(
+                        "error: {}:{}:{}: {}",
+                        path.display(),
+                        line_col.line + 1,
+                        line_col.col + 1,
+                        error.kind()
+                    );
+                }
+                eprintln!(
+                    "error: {} is not formatted, because it contains syntax errors",
+                    path.display()
+                );
+                success = false;
+            }
+        }
     }
+    Ok(success)
 }
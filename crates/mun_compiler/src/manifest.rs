@@ -0,0 +1,411 @@
+//! A package can be described by a `mun.toml` manifest in its directory, e.g.:
+//!
+//! ```toml
+//! [package]
+//! name = "game"
+//! source = "src"
+//!
+//! [build]
+//! opt-level = 2
+//! target = "x86_64-unknown-linux-gnu"
+//!
+//! [dependencies]
+//! physics = { path = "../physics" }
+//! ```
+//!
+//! The source root of a package defaults to `src`. Dependencies refer to the directories of other
+//! packages by their names, relative to the directory of the manifest. The build settings of a manifest only apply
+//! when its package is built, a dependency is built with the settings of the package that depends
+//! on it.
+
+use failure::Fail;
+use mun_codegen::OptimizationLevel;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The file name of the manifest of a package.
+pub const MANIFEST_FILE_NAME: &str = "mun.toml";
+
+/// The default source root of a package, relative to its manifest.
+const DEFAULT_SOURCE_DIR: &str = "src";
+
+/// The directory of a package in which the artifacts of a build are stored.
+const TARGET_DIR: &str = "target";
+
+/// An error that occurred while reading the manifest of a package or one of its dependencies.
+#[derive(Debug, Fail)]
+pub enum ManifestError {
+    #[fail(display = "could not find `{}` in `{}`", file_name, dir)]
+    NotFound {
+        file_name: &'static str,
+        dir: String,
+    },
+
+    #[fail(display = "failed to read manifest `{}`: {}", path, error)]
+    Io { path: String, error: std::io::Error },
+
+    #[fail(display = "failed to parse manifest `{}`: {}", path, message)]
+    Parse {
+        path: String,
+        /// The line and column at which the error occurred, both starting at 1
+        line_col: Option<(usize, usize)>,
+        message: String,
+    },
+
+    #[fail(
+        display = "dependency `{}` of package `{}` refers to package `{}`",
+        dependency, package, name
+    )]
+    MismatchedDependencyName {
+        package: String,
+        dependency: String,
+        name: String,
+    },
+
+    /// A package that depends on itself, e.g. `game -> physics -> game`
+    #[fail(display = "cyclic dependency between packages: {}", cycle)]
+    CyclicDependency { cycle: String },
+
+    #[fail(
+        display = "package `{}` at `{}` has the same name as the package at `{}`",
+        package, dir, other_dir
+    )]
+    DuplicatePackage {
+        package: String,
+        dir: String,
+        other_dir: String,
+    },
+}
+
+/// The contents of the manifest of a package.
+#[derive(Debug, Clone)]
+pub struct Manifest {
+    /// The directory of the manifest
+    dir: PathBuf,
+    package: PackageSection,
+    build: BuildSection,
+    dependencies: BTreeMap<String, DependencySection>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestToml {
+    package: PackageSection,
+    #[serde(default)]
+    build: BuildSection,
+    #[serde(default)]
+    dependencies: BTreeMap<String, DependencySection>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PackageSection {
+    #[serde(deserialize_with = "deserialize_package_name")]
+    name: String,
+    source: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct BuildSection {
+    #[serde(default, deserialize_with = "deserialize_opt_level")]
+    opt_level: Option<OptimizationLevel>,
+    target: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DependencySection {
+    path: PathBuf,
+}
+
+/// Names of packages are used as directory names, so they may only contain ASCII letters, digits,
+/// `-` and `_`.
+fn deserialize_package_name<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<String, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(serde::de::Error::custom(format!(
+            "invalid package name `{}`, only ASCII letters, digits, `-` and `_` are allowed",
+            name
+        )));
+    }
+    Ok(name)
+}
+
+fn deserialize_opt_level<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<OptimizationLevel>, D::Error> {
+    match u8::deserialize(deserializer)? {
+        0 => Ok(Some(OptimizationLevel::None)),
+        1 => Ok(Some(OptimizationLevel::Less)),
+        2 => Ok(Some(OptimizationLevel::Default)),
+        3 => Ok(Some(OptimizationLevel::Aggressive)),
+        level => Err(serde::de::Error::custom(format!(
+            "invalid optimization level {}, only levels 0-3 are supported",
+            level
+        ))),
+    }
+}
+
+impl Manifest {
+    /// Reads the manifest in the directory `dir`.
+    pub fn from_dir(dir: &Path) -> Result<Manifest, ManifestError> {
+        let path = dir.join(MANIFEST_FILE_NAME);
+        if !path.is_file() {
+            return Err(ManifestError::NotFound {
+                file_name: MANIFEST_FILE_NAME,
+                dir: dir.display().to_string(),
+            });
+        }
+        Manifest::from_file(&path)
+    }
+
+    /// Reads the manifest at `path`.
+    pub fn from_file(path: &Path) -> Result<Manifest, ManifestError> {
+        let text = std::fs::read_to_string(path).map_err(|error| ManifestError::Io {
+            path: path.display().to_string(),
+            error,
+        })?;
+        Manifest::parse(path, &text)
+    }
+
+    /// Parses the contents of the manifest at `path`.
+    fn parse(path: &Path, text: &str) -> Result<Manifest, ManifestError> {
+        let manifest: ManifestToml =
+            toml::from_str(text).map_err(|error| ManifestError::Parse {
+                path: path.display().to_string(),
+                line_col: error.line_col().map(|(line, col)| (line + 1, col + 1)),
+                message: error.to_string(),
+            })?;
+        Ok(Manifest {
+            dir: path.parent().map(Path::to_path_buf).unwrap_or_default(),
+            package: manifest.package,
+            build: manifest.build,
+            dependencies: manifest.dependencies,
+        })
+    }
+
+    /// Returns the name of the package.
+    pub fn name(&self) -> &str {
+        &self.package.name
+    }
+
+    /// Returns the directory of the package, which contains the manifest.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the source root of the package, which contains the files of its modules.
+    pub fn source_dir(&self) -> PathBuf {
+        self.dir.join(
+            self.package
+                .source
+                .as_deref()
+                .unwrap_or_else(|| Path::new(DEFAULT_SOURCE_DIR)),
+        )
+    }
+
+    /// Returns the directory in which the artifacts of a build of the package are stored. The
+    /// assembly of every package of the build is stored in a directory of its own, e.g.
+    /// `target/physics/main.munlib`.
+    pub fn target_dir(&self) -> PathBuf {
+        self.dir.join(TARGET_DIR)
+    }
+
+    /// Returns the optimization level of builds of the package, if the manifest specifies one.
+    pub fn optimization_lvl(&self) -> Option<OptimizationLevel> {
+        self.build.opt_level
+    }
+
+    /// Returns the target triple of builds of the package, if the manifest specifies one.
+    pub fn target(&self) -> Option<&str> {
+        self.build.target.as_deref()
+    }
+
+    /// Returns the names of the packages that the package depends on directly. The name of a
+    /// dependency is the name of its package.
+    pub fn dependency_names(&self) -> impl Iterator<Item = &str> {
+        self.dependencies.keys().map(String::as_str)
+    }
+
+    /// Reads the manifests of all packages that the package depends on, directly or indirectly.
+    /// Every package precedes the packages that depend on it and the package itself is last.
+    pub fn build_order(&self) -> Result<Vec<Manifest>, ManifestError> {
+        let mut order = Vec::new();
+        add_to_build_order(self.clone(), &mut Vec::new(), &mut order)?;
+        Ok(order)
+    }
+}
+
+/// Adds the dependencies of `manifest` and then `manifest` itself to `order`, unless they were
+/// already added. `dependents` are the names of the packages that led to `manifest`, to detect
+/// cyclic dependencies.
+fn add_to_build_order(
+    manifest: Manifest,
+    dependents: &mut Vec<String>,
+    order: &mut Vec<Manifest>,
+) -> Result<(), ManifestError> {
+    if let Some(other) = order.iter().find(|other| other.name() == manifest.name()) {
+        if !is_same_dir(other.dir(), manifest.dir()) {
+            return Err(ManifestError::DuplicatePackage {
+                package: manifest.name().to_string(),
+                dir: manifest.dir().display().to_string(),
+                other_dir: other.dir().display().to_string(),
+            });
+        }
+        return Ok(());
+    }
+
+    dependents.push(manifest.name().to_string());
+    for (name, dependency) in manifest.dependencies.iter() {
+        let dependency = Manifest::from_dir(&manifest.dir.join(&dependency.path))?;
+        if dependency.name() != name {
+            return Err(ManifestError::MismatchedDependencyName {
+                package: manifest.name().to_string(),
+                dependency: name.clone(),
+                name: dependency.name().to_string(),
+            });
+        }
+        if let Some(idx) = dependents
+            .iter()
+            .position(|dependent| dependent == dependency.name())
+        {
+            let mut cycle = dependents[idx..].to_vec();
+            cycle.push(dependency.name().to_string());
+            return Err(ManifestError::CyclicDependency {
+                cycle: cycle.join(" -> "),
+            });
+        }
+        add_to_build_order(dependency, dependents, order)?;
+    }
+    dependents.pop();
+
+    order.push(manifest);
+    Ok(())
+}
+
+/// Returns true if `a` and `b` refer to the same directory.
+fn is_same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Manifest, ManifestError};
+    use std::path::Path;
+
+    fn write_manifest(dir: &Path, text: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join("mun.toml"), text).unwrap();
+    }
+
+    #[test]
+    fn parse_manifest() {
+        let manifest = Manifest::parse(
+            Path::new("game/mun.toml"),
+            r#"
+[package]
+name = "game"
+
+[build]
+opt-level = 3
+target = "x86_64-unknown-linux-gnu"
+
+[dependencies]
+physics = { path = "../physics" }
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.name(), "game");
+        assert_eq!(manifest.source_dir(), Path::new("game/src"));
+        assert_eq!(manifest.target_dir(), Path::new("game/target"));
+        assert_eq!(
+            manifest.optimization_lvl(),
+            Some(mun_codegen::OptimizationLevel::Aggressive)
+        );
+        assert_eq!(manifest.target(), Some("x86_64-unknown-linux-gnu"));
+        assert_eq!(manifest.dependency_names().collect::<Vec<_>>(), ["physics"]);
+    }
+
+    #[test]
+    fn malformed_manifest() {
+        // Returns the line of the parse error of `text`
+        let parse = |text| match Manifest::parse(Path::new("mun.toml"), text) {
+            Err(ManifestError::Parse { line_col, .. }) => line_col.map(|(line, _)| line),
+            result => panic!("expected a parse error, found {:?}", result),
+        };
+
+        assert_eq!(parse("[package]\nname = game\n"), Some(2));
+        assert_eq!(parse("[package]\nname = \"my game\"\n"), Some(2));
+        assert_eq!(
+            parse("[package]\nname = \"game\"\n\n[build]\nopt-level = 4\n"),
+            Some(5)
+        );
+        assert!(parse("[package]\nname = \"game\"\nversion = 1\n").is_some());
+        assert!(parse("[build]\nopt-level = 2\n").is_some());
+    }
+
+    #[test]
+    fn missing_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let error = Manifest::from_dir(dir.path()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("could not find `mun.toml` in `{}`", dir.path().display())
+        );
+    }
+
+    #[test]
+    fn build_order() {
+        let dir = tempfile::tempdir().unwrap();
+        write_manifest(
+            &dir.path().join("game"),
+            "[package]\nname = \"game\"\n[dependencies]\nphysics = { path = \"../physics\" }\nmath = { path = \"../math\" }\n",
+        );
+        write_manifest(
+            &dir.path().join("physics"),
+            "[package]\nname = \"physics\"\n[dependencies]\nmath = { path = \"../math\" }\n",
+        );
+        write_manifest(&dir.path().join("math"), "[package]\nname = \"math\"\n");
+
+        // A dependency that is shared by multiple packages is only built once
+        let manifest = Manifest::from_dir(&dir.path().join("game")).unwrap();
+        let names: Vec<String> = manifest
+            .build_order()
+            .unwrap()
+            .iter()
+            .map(|package| package.name().to_string())
+            .collect();
+        assert_eq!(names, ["math", "physics", "game"]);
+    }
+
+    #[test]
+    fn cyclic_dependencies() {
+        let dir = tempfile::tempdir().unwrap();
+        write_manifest(
+            &dir.path().join("game"),
+            "[package]\nname = \"game\"\n[dependencies]\nphysics = { path = \"../physics\" }\n",
+        );
+        write_manifest(
+            &dir.path().join("physics"),
+            "[package]\nname = \"physics\"\n[dependencies]\ngame = { path = \"../game\" }\n",
+        );
+
+        let manifest = Manifest::from_dir(&dir.path().join("game")).unwrap();
+        assert_eq!(
+            manifest.build_order().unwrap_err().to_string(),
+            "cyclic dependency between packages: game -> physics -> game"
+        );
+    }
+}
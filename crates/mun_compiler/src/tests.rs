@@ -0,0 +1,207 @@
+use crate::json::{Position, Severity};
+use crate::{
+    check, compile_str, format, Artifact, CompileError, CompilerOptions, Config, DiagnosticSummary,
+    Driver, EmittedArtifact, RelativePath,
+};
+
+#[test]
+fn check_does_not_write_assembly() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let options = |text: &str| CompilerOptions {
+        config: Config {
+            out_dir: Some(out_dir.path().to_path_buf()),
+            ..Config::default()
+        },
+        ..CompilerOptions::with_file("main.mun", text)
+    };
+
+    assert!(check(options("export fn main(): int { 1 }")).unwrap());
+    assert!(!check(options("export fn main(): int { true }")).unwrap());
+    assert_eq!(std::fs::read_dir(out_dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn format_leaves_files_with_syntax_errors_untouched() {
+    let dir = tempfile::tempdir().unwrap();
+    let read = |name: &str| std::fs::read_to_string(dir.path().join(name)).unwrap();
+    std::fs::create_dir(dir.path().join("nested")).unwrap();
+    std::fs::write(dir.path().join("formatted.mun"), "fn main() {}\n").unwrap();
+    std::fs::write(dir.path().join("nested/messy.mun"), "fn  main( ){ }").unwrap();
+    std::fs::write(dir.path().join("invalid.mun"), "fn main( {").unwrap();
+    let paths = [dir.path().to_path_buf()];
+
+    // The check mode does not write any files
+    assert!(!format(&paths, true).unwrap());
+    assert_eq!(read("nested/messy.mun"), "fn  main( ){ }");
+
+    assert!(!format(&paths, false).unwrap());
+    assert_eq!(read("formatted.mun"), "fn main() {}\n");
+    assert_eq!(read("nested/messy.mun"), "fn main() {}\n");
+    assert_eq!(read("invalid.mun"), "fn main( {");
+
+    std::fs::remove_file(dir.path().join("invalid.mun")).unwrap();
+    assert!(format(&paths, true).unwrap());
+}
+
+#[test]
+fn compile_str_returns_diagnostics_as_data() {
+    let diagnostics = match compile_str("export fn main(): int {\n    true\n}", Config::default()) {
+        Err(CompileError::Diagnostics(diagnostics)) => diagnostics,
+        result => panic!("expected diagnostics, found {:?}", result),
+    };
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert_eq!(diagnostics[0].code.as_deref(), Some("E0005"));
+    assert_eq!(diagnostics[0].file, "main.mun");
+    assert_eq!(
+        diagnostics[0].span.start,
+        Position {
+            line: 2,
+            column: 5,
+            offset: 28
+        }
+    );
+}
+
+#[test]
+fn compile_str_emits_ir() {
+    let config = Config {
+        emit: vec![Artifact::Lib, Artifact::Ir],
+        ..Config::default()
+    };
+    let artifact = compile_str("export fn main(): int { 1 }", config).unwrap();
+    assert!(!artifact.library.is_empty());
+    assert!(artifact.diagnostics.is_empty());
+    assert!(artifact.ir.unwrap().contains("@_MN4main4mainE"));
+}
+
+#[test]
+fn build_metadata() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let config = Config {
+        out_dir: Some(out_dir.path().to_path_buf()),
+        target: mun_target::spec::Target::search("x86_64-unknown-linux-gnu").unwrap(),
+        emit: vec![Artifact::Metadata],
+        ..Config::default()
+    };
+    let text = include_str!("../../mun/tests/fixtures/main.mun");
+    let (mut driver, file_id) =
+        Driver::with_files(config, vec![("main.mun".into(), text.to_string())]).unwrap();
+
+    let result = driver.build(file_id).unwrap();
+    assert!(result.success);
+    assert_eq!(result.library_path(), None);
+    assert_eq!(
+        result.artifacts[0].path,
+        out_dir.path().join("main.mun.json")
+    );
+    let metadata = std::fs::read_to_string(&result.artifacts[0].path).unwrap();
+    insta::assert_snapshot!(metadata);
+}
+
+#[test]
+fn build_result() {
+    let package_dir = tempfile::tempdir().unwrap();
+    let out_dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        package_dir.path().join("main.mun"),
+        "export fn main(): int {\n    let unused = 1;\n    math::add(1, 2)\n}",
+    )
+    .unwrap();
+    std::fs::write(
+        package_dir.path().join("math.mun"),
+        "export fn add(a: int, b: int): int { a + b }",
+    )
+    .unwrap();
+    let config = Config {
+        out_dir: Some(out_dir.path().to_path_buf()),
+        emit: vec![Artifact::Lib, Artifact::Ir],
+        ..Config::default()
+    };
+    let (mut driver, file_id) = Driver::with_package(config, package_dir.path()).unwrap();
+
+    let result = driver.build(file_id).unwrap();
+    assert!(result.success);
+    assert_eq!(result.target_triple, Config::default().target.llvm_target);
+    let library_path = driver.assembly_output_path(file_id);
+    assert_eq!(result.library_path(), Some(library_path.as_path()));
+    assert_eq!(
+        result.artifacts[1],
+        EmittedArtifact {
+            kind: Artifact::Ir,
+            path: library_path.with_extension("ll"),
+        }
+    );
+    assert!(result
+        .artifacts
+        .iter()
+        .all(|artifact| artifact.path.is_file()));
+    let phases: Vec<&str> = result.phases.iter().map(|phase| phase.name).collect();
+    assert_eq!(phases, ["parse", "check", "ir", "codegen"]);
+    assert_eq!(result.phases[0].executed.parsed_files, 2);
+
+    // Warnings are part of the result, but don't fail the build
+    assert_eq!(result.diagnostics.len(), 1);
+    assert_eq!(result.diagnostics[0].severity, Severity::Warning);
+    assert_eq!(result.diagnostics[0].code.as_deref(), Some("W0003"));
+    assert!(result.library_changed);
+    assert!(result.library_hash.is_some());
+    let library = std::fs::read(&library_path).unwrap();
+
+    // Rebuilding the same source writes the same shared library
+    let rebuild = driver.build(file_id).unwrap();
+    assert!(!rebuild.library_changed);
+    assert_eq!(rebuild.library_hash, result.library_hash);
+
+    // A build with errors writes no artifacts, and keeps the previous shared library
+    let math = driver.file_id(RelativePath::new("math.mun")).unwrap();
+    driver.set_file_text(math, "export fn add(a: int, b: int): int { true }");
+    let failed = driver.build(file_id).unwrap();
+    assert!(!failed.success);
+    assert!(failed.artifacts.is_empty());
+    assert_eq!(failed.phases.len(), 2);
+    let error = failed
+        .diagnostics
+        .iter()
+        .find(|diagnostic| diagnostic.severity == Severity::Error)
+        .unwrap();
+    assert_eq!(error.file, "math.mun");
+    assert_eq!(std::fs::read(&library_path).unwrap(), library);
+}
+
+#[test]
+fn deny_warnings() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let text = "export fn main(): int {\n    let unused = 1;\n    42\n}\n\n\
+                #[allow(unused_variables)]\n\
+                export fn allowed(): int {\n    let unused = 1;\n    42\n}";
+    let build = |deny_warnings| {
+        let config = Config {
+            out_dir: Some(out_dir.path().to_path_buf()),
+            deny_warnings,
+            ..Config::default()
+        };
+        let (driver, file_id) =
+            Driver::with_files(config, vec![("main.mun".into(), text.to_string())]).unwrap();
+        driver.build(file_id).unwrap()
+    };
+
+    // The warning of the allowed lint isn't counted
+    let result = build(false);
+    assert!(result.success);
+    let summary = result.summary();
+    assert_eq!(
+        summary,
+        DiagnosticSummary {
+            warnings: 1,
+            errors: 0
+        }
+    );
+    assert_eq!(summary.to_string(), "finished with 1 warning, 0 errors");
+
+    // Denied warnings fail the build like errors, but are still reported as warnings
+    let denied = build(true);
+    assert!(!denied.success);
+    assert!(denied.artifacts.is_empty());
+    assert_eq!(denied.summary(), summary);
+}
@@ -1,44 +1,209 @@
+//! The compiler daemon compiles a file or package and recompiles it whenever one of its source
+//! files changes. The database of the driver is kept between builds, so a rebuild only recomputes
+//! what depends on the changed files.
+
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::channel;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use failure::Error;
-use mun_compiler::{ColorChoice, CompilerOptions, Driver, PathOrInline, StandardStream};
-use notify::{RecommendedWatcher, RecursiveMode, Watcher};
-
-pub fn main(options: CompilerOptions) -> Result<(), Error> {
-    // Need to canonicalize path to do comparisons
-    let input_path = match &options.input {
-        PathOrInline::Path(path) => path.canonicalize()?,
-        PathOrInline::Inline { .. } => panic!("cannot run compiler with inline path"),
-    };
+use mun_compiler::{
+    stderr_color_choice, CompilerOptions, Driver, FileId, PathOrInline, RelativePathBuf,
+    StandardStream,
+};
+use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use termcolor::WriteColor;
 
-    let (tx, rx) = channel();
+#[cfg(test)]
+mod tests;
 
-    let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_millis(10))?;
-    watcher.watch(&input_path, RecursiveMode::NonRecursive)?;
-    println!("Watching: {}", input_path.display());
+/// Compiles the input of `options` and recompiles it on every change, until the watcher fails.
+/// With `timings`, the duration of every phase of a build is reported.
+pub fn main(options: CompilerOptions, timings: bool) -> Result<(), Error> {
+    let mut daemon = CompilerDaemon::new(options)?;
+    daemon.set_timings(timings);
 
-    let (mut driver, file_id) = Driver::with_file(options.config, options.input)?;
+    let (tx, rx) = channel();
+    let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_millis(10))?;
+    watcher.watch(daemon.input_path(), daemon.recursive_mode())?;
+    println!("Watching: {}", daemon.input_path().display());
 
     // Compile at least once
-    let mut writer = StandardStream::stderr(ColorChoice::Auto);
-    if !driver.emit_diagnostics(&mut writer)? {
-        driver.write_assembly(file_id)?;
-    }
+    let mut writer = StandardStream::stderr(stderr_color_choice());
+    daemon.build(&mut writer)?;
 
     loop {
+        let changed = match rx.recv() {
+            Ok(event) => daemon.handle_event(&event)?,
+            Err(e) => {
+                eprintln!("Watcher error: {:?}", e);
+                false
+            }
+        };
+        if changed {
+            daemon.build(&mut writer)?;
+        }
+    }
+}
+
+/// A driver for a file or package that applies the changes of its source files.
+pub struct CompilerDaemon {
+    driver: Driver,
+    file_id: FileId,
+    input_path: PathBuf,
+    is_package: bool,
+    timings: bool,
+}
+
+impl CompilerDaemon {
+    /// Constructs a daemon for the input of `options`, which must be a path.
+    pub fn new(options: CompilerOptions) -> Result<CompilerDaemon, Error> {
+        // Need to canonicalize path to do comparisons
+        let input_path = match &options.input {
+            PathOrInline::Path(path) => path.canonicalize()?,
+            PathOrInline::Inline { .. } => panic!("cannot run compiler with inline path"),
+        };
+
+        let is_package = input_path.is_dir();
+        let (driver, file_id) = if is_package {
+            Driver::with_package(options.config, &input_path)?
+        } else {
+            Driver::with_file(options.config, options.input)?
+        };
+        Ok(CompilerDaemon {
+            driver,
+            file_id,
+            input_path,
+            is_package,
+            timings: false,
+        })
+    }
+
+    /// Sets whether a build reports the duration of each of its phases, and the number of files
+    /// and functions that the phase processed again.
+    pub fn set_timings(&mut self, timings: bool) {
+        self.timings = timings;
+    }
+
+    /// Returns the path of the file or package directory that is compiled.
+    pub fn input_path(&self) -> &Path {
+        &self.input_path
+    }
+
+    /// Returns the path at which the assembly of the input is written.
+    pub fn assembly_path(&self) -> PathBuf {
+        self.driver.assembly_output_path(self.file_id)
+    }
+
+    /// Returns how the input path is watched. The files of a package are watched in all
+    /// subdirectories of the package.
+    pub fn recursive_mode(&self) -> RecursiveMode {
+        if self.is_package {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        }
+    }
+
+    /// Compiles the input and writes its diagnostics and the duration of the build to `writer`.
+    /// The assembly is only written if there are no errors, otherwise the assembly of the last
+    /// successful build is kept. With timings, the duration of every phase is written as well.
+    /// Returns whether the build succeeded.
+    pub fn build(&self, writer: &mut impl WriteColor) -> Result<bool, Error> {
+        let start = Instant::now();
+        let result = self.driver.build(self.file_id);
+        self.driver.emit_diagnostics(writer)?;
+        let result = match result {
+            Ok(result) => Some(result),
+            Err(e) => {
+                writeln!(writer, "error: {}", e)?;
+                None
+            }
+        };
+
+        let success = result.as_ref().map_or(false, |result| result.success);
+        if success {
+            writeln!(
+                writer,
+                "Successfully compiled {} in {:.2?}",
+                self.input_path.display(),
+                start.elapsed()
+            )?;
+        } else {
+            writeln!(
+                writer,
+                "Failed to compile {} in {:.2?}, the previous assembly is kept",
+                self.input_path.display(),
+                start.elapsed()
+            )?;
+        }
+        if self.timings {
+            for phase in result.iter().flat_map(|result| result.phases.iter()) {
+                writeln!(writer, "  {}", phase)?;
+            }
+        }
+        Ok(success)
+    }
+
+    /// Applies the change of a source file that `event` describes. Only the files that changed are
+    /// updated, the rest of a package is not parsed again. Returns whether the input changed.
+    pub fn handle_event(&mut self, event: &DebouncedEvent) -> Result<bool, Error> {
         use notify::DebouncedEvent::*;
-        match rx.recv() {
-            Ok(Write(ref path)) | Ok(Create(ref path)) if path == &input_path => {
+        match event {
+            Write(path) | Create(path) | Rename(_, path) if path == &self.input_path => {
                 let contents = std::fs::read_to_string(path)?;
-                driver.set_file_text(file_id, &contents);
-                if !driver.emit_diagnostics(&mut writer)? {
-                    driver.write_assembly(file_id)?;
-                }
-                println!("Successfully compiled: {}", path.display())
+                self.driver.set_file_text(self.file_id, &contents);
+                Ok(true)
+            }
+            Write(path) | Create(path) if self.is_package => {
+                update_file(&mut self.driver, &self.input_path, path)
+            }
+            Remove(path) if self.is_package => {
+                Ok(remove_file(&mut self.driver, &self.input_path, path))
+            }
+            Rename(from, to) if self.is_package => {
+                let removed = remove_file(&mut self.driver, &self.input_path, from);
+                Ok(update_file(&mut self.driver, &self.input_path, to)? || removed)
             }
-            Ok(_) => {}
-            Err(e) => eprintln!("Watcher error: {:?}", e),
+            _ => Ok(false),
+        }
+    }
+}
+
+/// Returns the path of the Mun file at `path` relative to the package in `package_dir`, or `None`
+/// if `path` is not a Mun file of the package.
+fn package_file_path(package_dir: &Path, path: &Path) -> Option<RelativePathBuf> {
+    if path.extension().and_then(|ext| ext.to_str()) != Some("mun") {
+        return None;
+    }
+    let rel_path = path.strip_prefix(package_dir).ok()?;
+    RelativePathBuf::from_path(rel_path).ok()
+}
+
+/// Updates the contents of the file at `path` in the package, or adds it to the package if it is
+/// a new file. Returns whether the package changed.
+fn update_file(driver: &mut Driver, package_dir: &Path, path: &Path) -> Result<bool, Error> {
+    let rel_path = match package_file_path(package_dir, path) {
+        Some(rel_path) => rel_path,
+        None => return Ok(false),
+    };
+    let contents = std::fs::read_to_string(path)?;
+    match driver.file_id(&rel_path) {
+        Some(file_id) => driver.set_file_text(file_id, &contents),
+        None => {
+            driver.add_file(rel_path, contents);
+        }
+    }
+    Ok(true)
+}
+
+/// Removes the file at `path` from the package. Returns whether the package changed.
+fn remove_file(driver: &mut Driver, package_dir: &Path, path: &Path) -> bool {
+    match package_file_path(package_dir, path) {
+        Some(ref rel_path) if driver.file_id(rel_path).is_some() => {
+            driver.remove_file(rel_path);
+            true
         }
+        _ => false,
     }
 }
@@ -0,0 +1,252 @@
+use crate::CompilerDaemon;
+use mun_compiler::{CompilerOptions, Config, PathOrInline};
+use notify::DebouncedEvent;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use termcolor::NoColor;
+
+/// Returns a hash of the contents of the file at `path`.
+fn content_hash(path: &Path) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    std::fs::read(path).unwrap().hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn rebuild_on_change() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let source_path = temp_dir.path().join("main.mun");
+    std::fs::write(&source_path, "export fn main(): int { 1 }").unwrap();
+    let mut daemon = CompilerDaemon::new(CompilerOptions {
+        input: PathOrInline::Path(source_path.clone()),
+        config: Config {
+            out_dir: Some(temp_dir.path().to_path_buf()),
+            ..Config::default()
+        },
+    })
+    .unwrap();
+    let source_path = daemon.input_path().to_path_buf();
+    let assembly_path = daemon.assembly_path();
+
+    let mut output = NoColor::new(Vec::new());
+    assert!(daemon.build(&mut output).unwrap());
+    let first_hash = content_hash(&assembly_path);
+
+    // A build with errors keeps the previous assembly
+    std::fs::write(&source_path, "export fn main(): int { true }").unwrap();
+    assert!(daemon
+        .handle_event(&DebouncedEvent::Write(source_path.clone()))
+        .unwrap());
+    assert!(!daemon.build(&mut output).unwrap());
+    assert_eq!(content_hash(&assembly_path), first_hash);
+
+    std::fs::write(&source_path, "export fn main(): int { 2 }").unwrap();
+    assert!(daemon
+        .handle_event(&DebouncedEvent::Write(source_path.clone()))
+        .unwrap());
+    assert!(daemon.build(&mut output).unwrap());
+    assert_ne!(content_hash(&assembly_path), first_hash);
+
+    // The assembly is linked into a temporary file that replaces it, which doesn't remain
+    let temp_files: Vec<_> = std::fs::read_dir(temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .filter(|name| name.to_string_lossy().ends_with(".tmp"))
+        .collect();
+    assert!(temp_files.is_empty(), "{:?}", temp_files);
+
+    let output = String::from_utf8(output.into_inner()).unwrap();
+    let statuses: Vec<&str> = output
+        .lines()
+        .filter_map(|line| line.split(' ').next())
+        .filter(|word| *word == "Successfully" || *word == "Failed")
+        .collect();
+    assert_eq!(statuses, ["Successfully", "Failed", "Successfully"]);
+}
+
+#[test]
+fn unrelated_changes_are_ignored() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        temp_dir.path().join("main.mun"),
+        "export fn main(): int { 1 }",
+    )
+    .unwrap();
+    let mut daemon = CompilerDaemon::new(CompilerOptions {
+        input: PathOrInline::Path(temp_dir.path().to_path_buf()),
+        config: Config {
+            out_dir: Some(temp_dir.path().join("out")),
+            ..Config::default()
+        },
+    })
+    .unwrap();
+
+    let notes_path = daemon.input_path().join("notes.txt");
+    std::fs::write(&notes_path, "").unwrap();
+    assert!(!daemon
+        .handle_event(&DebouncedEvent::Create(notes_path))
+        .unwrap());
+
+    let math_path = daemon.input_path().join("math.mun");
+    std::fs::write(&math_path, "export fn add(a: int, b: int): int { a + b }").unwrap();
+    assert!(daemon
+        .handle_event(&DebouncedEvent::Create(math_path))
+        .unwrap());
+}
+
+/// Constructs a daemon with timings for a file with the contents `text`, in `temp_dir`.
+fn timed_daemon(temp_dir: &Path, text: &str) -> CompilerDaemon {
+    let source_path = temp_dir.join("main.mun");
+    std::fs::write(&source_path, text).unwrap();
+    let mut daemon = CompilerDaemon::new(CompilerOptions {
+        input: PathOrInline::Path(source_path),
+        config: Config {
+            out_dir: Some(temp_dir.to_path_buf()),
+            ..Config::default()
+        },
+    })
+    .unwrap();
+    daemon.set_timings(true);
+    daemon
+}
+
+/// Changes the contents of the input of `daemon` to `text`, and rebuilds it. Returns whether the
+/// build succeeded and its output.
+fn rebuild(daemon: &mut CompilerDaemon, text: &str) -> (bool, String) {
+    let source_path = daemon.input_path().to_path_buf();
+    std::fs::write(&source_path, text).unwrap();
+    assert!(daemon
+        .handle_event(&DebouncedEvent::Write(source_path))
+        .unwrap());
+    let mut output = NoColor::new(Vec::new());
+    let success = daemon.build(&mut output).unwrap();
+    (success, String::from_utf8(output.into_inner()).unwrap())
+}
+
+/// Returns the timings line of the phase `name` in the output of a build.
+fn phase_line<'a>(output: &'a str, name: &str) -> &'a str {
+    output
+        .lines()
+        .find(|line| line.trim_start().starts_with(&format!("{} ", name)))
+        .unwrap_or_else(|| panic!("no timings of phase `{}` in:\n{}", name, output))
+}
+
+#[test]
+fn incremental_rebuild_timings() {
+    // Every function calls the previous one, so all of them are reachable from the last one
+    let fixture = |changed_body: &str| {
+        let mut text = "fn f0(a: int): int { a }\n".to_owned();
+        for i in 1..200 {
+            let body = if i == 100 {
+                changed_body.to_owned()
+            } else {
+                format!("f{}(a) + 1", i - 1)
+            };
+            let visibility = if i == 199 { "export " } else { "" };
+            text.push_str(&format!(
+                "{}fn f{}(a: int): int {{ {} }}\n",
+                visibility, i, body
+            ));
+        }
+        text
+    };
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut daemon = timed_daemon(temp_dir.path(), &fixture("f99(a) + 1"));
+    let mut output = NoColor::new(Vec::new());
+    assert!(daemon.build(&mut output).unwrap());
+    let cold = String::from_utf8(output.into_inner()).unwrap();
+    assert!(
+        phase_line(&cold, "parse").ends_with(", 1 file parsed"),
+        "{}",
+        cold
+    );
+    assert!(
+        phase_line(&cold, "check").ends_with(", 200 bodies inferred"),
+        "{}",
+        cold
+    );
+    assert!(
+        phase_line(&cold, "ir").ends_with(", 200 functions generated"),
+        "{}",
+        cold
+    );
+
+    // Changing a single body only checks and generates that function again
+    let (success, incremental) = rebuild(&mut daemon, &fixture("f99(a) + 42"));
+    assert!(success, "{}", incremental);
+    assert!(
+        phase_line(&incremental, "check").ends_with(", 1 body inferred"),
+        "{}",
+        incremental
+    );
+    assert!(
+        phase_line(&incremental, "ir").ends_with(", 1 function generated"),
+        "{}",
+        incremental
+    );
+
+    // Building again without changes is served from the cache entirely
+    let mut output = NoColor::new(Vec::new());
+    assert!(daemon.build(&mut output).unwrap());
+    let cached = String::from_utf8(output.into_inner()).unwrap();
+    for (phase, count) in &[
+        ("parse", "parsed"),
+        ("check", "inferred"),
+        ("ir", "generated"),
+    ] {
+        assert!(!phase_line(&cached, phase).contains(count), "{}", cached);
+    }
+}
+
+#[test]
+fn signature_change_rebuilds_callers() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut daemon = timed_daemon(
+        temp_dir.path(),
+        r#"
+fn foo(a: int): int { a }
+export fn main(): int { foo(1) }
+export fn other(): int { 3 }
+"#,
+    );
+    assert!(daemon.build(&mut NoColor::new(Vec::new())).unwrap());
+
+    // The caller of a function of which the signature changed is checked again
+    let (success, output) = rebuild(
+        &mut daemon,
+        r#"
+fn foo(a: int, b: int): int { a + b }
+export fn main(): int { foo(1) }
+export fn other(): int { 3 }
+"#,
+    );
+    assert!(!success, "{}", output);
+    assert!(
+        output.contains("this function takes 2 parameters but 1 parameters was supplied"),
+        "{}",
+        output
+    );
+
+    // Since the last successful build the IR of both functions changed, but not that of `other`
+    let (success, output) = rebuild(
+        &mut daemon,
+        r#"
+fn foo(a: int, b: int): int { a + b }
+export fn main(): int { foo(1, 2) }
+export fn other(): int { 3 }
+"#,
+    );
+    assert!(success, "{}", output);
+    assert!(
+        phase_line(&output, "check").ends_with(", 1 body inferred"),
+        "{}",
+        output
+    );
+    assert!(
+        phase_line(&output, "ir").ends_with(", 2 functions generated"),
+        "{}",
+        output
+    );
+}
@@ -2,16 +2,40 @@ mod location;
 
 pub use crate::location::Location;
 
-/// Defines the severity of diagnostics.
-/// TODO: Contains only Error, for now, maybe add some more?
+/// Defines the severity of diagnostics. Only errors prevent a module from being compiled.
 #[derive(Clone, Copy, Debug, PartialEq, Hash)]
 pub enum Level {
     Error,
+    Warning,
+    Note,
 }
 
 #[derive(Clone, Debug, PartialEq, Hash)]
 pub struct Diagnostic {
     pub level: Level,
+    /// The code that identifies the kind of diagnostic, e.g. `E0005`. Syntax errors have no code.
+    pub code: Option<&'static str>,
     pub loc: location::Location,
     pub message: String,
+    /// Secondary messages at related locations in the same file
+    pub labels: Vec<Label>,
+    /// Changes to the source that resolve the diagnostic
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// A secondary message of a `Diagnostic` that points out a related location, e.g. the declaration
+/// that caused an error.
+#[derive(Clone, Debug, PartialEq, Hash)]
+pub struct Label {
+    pub loc: location::Location,
+    pub message: String,
+}
+
+/// A change to the source that resolves a `Diagnostic`, e.g. renaming an unused variable. The
+/// source at `loc` is replaced by `replacement`.
+#[derive(Clone, Debug, PartialEq, Hash)]
+pub struct Suggestion {
+    pub loc: location::Location,
+    pub message: String,
+    pub replacement: String,
 }
@@ -0,0 +1,329 @@
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use crate::const_eval::eval_int_syntax;
+use crate::type_ref::{TypeRefBuilder, TypeRefId, TypeRefMap, TypeRefSourceMap};
+use crate::{
+    arena::{Arena, RawId},
+    code_model::{attr_name, src::HasSource},
+    AsName, DefDatabase, Enum, IntTy, Name, Struct, Visibility,
+};
+use mun_syntax::ast::{self, AttrsOwner, NameOwner, TypeAscriptionOwner};
+use rustc_hash::FxHashMap;
+
+/// A single field of a record struct, e.g. `a: int` in `struct Foo { a: int }`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct StructFieldData {
+    pub name: Name,
+    pub type_ref: TypeRefId,
+}
+
+/// An identifier for a struct's field, local to the struct that declares it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LocalStructFieldId(RawId);
+impl_arena_id!(LocalStructFieldId);
+
+/// The shape of a struct definition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructKind {
+    /// A struct with named fields, e.g. `struct Foo { a: int }`.
+    Record,
+    /// A struct without fields, e.g. `struct Foo;`. Its name can also be used as a value.
+    Unit,
+}
+
+/// Where the values of a struct are stored, e.g. `value` in `struct(value) Foo`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StructMemoryKind {
+    /// The struct is allocated on the heap by the runtime and referred to by a pointer. This is
+    /// the default.
+    GC,
+    /// The struct is stored in place and passed by value, which suits small types like vectors.
+    Value,
+}
+
+impl Default for StructMemoryKind {
+    fn default() -> Self {
+        StructMemoryKind::GC
+    }
+}
+
+/// How the fields of a struct are laid out in memory, as requested with `repr` attributes, e.g.
+/// `#[repr(packed)]`. By default the fields are laid out in declaration order with their natural
+/// alignment, like `#[repr(C)]` structs in Rust, which is also what `#[repr(C)]` requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct StructRepr {
+    /// `#[repr(packed)]`, the fields are laid out without padding and the struct has an alignment
+    /// of one byte
+    pub packed: bool,
+    /// `#[repr(align(N))]`, the minimum alignment of the struct in bytes
+    pub align: Option<u32>,
+}
+
+impl StructRepr {
+    /// The forms of the `repr` attribute, used in diagnostics.
+    pub(crate) const EXPECTED: &'static str =
+        "`#[repr(C)]`, `#[repr(packed)]` or `#[repr(align(N))]` with a power of two `N` up to 128";
+
+    /// The largest alignment that can be requested, which is the largest alignment that the ABI
+    /// can describe.
+    const MAX_ALIGN: u32 = 128;
+
+    /// Parses the arguments of a `repr` attribute. Returns `None` if they are malformed, which
+    /// includes a struct that is both packed and aligned.
+    pub(crate) fn from_args(args: Option<ast::AttrArgList>) -> Option<StructRepr> {
+        let mut repr = StructRepr::default();
+        for arg in args?.args() {
+            match (arg.name_ref()?.text().as_str(), arg.attr_arg_list()) {
+                ("C", None) => {}
+                ("packed", None) => repr.packed = true,
+                ("align", Some(args)) => repr.align = Some(Self::align_from_args(args)?),
+                _ => return None,
+            }
+        }
+        repr.validate()
+    }
+
+    /// Parses the argument of `align`, e.g. `16` in `align(16)`.
+    fn align_from_args(args: ast::AttrArgList) -> Option<u32> {
+        let mut args = args.args();
+        let literal = args.next()?.literal()?;
+        if args.next().is_some() {
+            return None;
+        }
+        match literal.kind() {
+            ast::LiteralKind::IntNumber { suffix: None } => {}
+            _ => return None,
+        }
+        let align = u32::try_from(literal.int_value().ok()?).ok()?;
+        if align.is_power_of_two() && align <= Self::MAX_ALIGN {
+            Some(align)
+        } else {
+            None
+        }
+    }
+
+    /// Combines the representations requested by two `repr` attributes of the same struct.
+    pub(crate) fn merge(self, other: StructRepr) -> Option<StructRepr> {
+        StructRepr {
+            packed: self.packed || other.packed,
+            align: self.align.max(other.align),
+        }
+        .validate()
+    }
+
+    fn validate(self) -> Option<StructRepr> {
+        // A packed struct has an alignment of one byte
+        if self.packed && self.align.is_some() {
+            None
+        } else {
+            Some(self)
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct StructData {
+    pub name: Name,
+    pub visibility: Visibility,
+    pub fields: Arena<LocalStructFieldId, StructFieldData>,
+    pub kind: StructKind,
+    pub memory_kind: StructMemoryKind,
+    pub repr: StructRepr,
+    type_ref_map: TypeRefMap,
+    type_ref_source_map: TypeRefSourceMap,
+}
+
+impl StructData {
+    pub(crate) fn struct_data_query(db: &impl DefDatabase, id: Struct) -> Arc<StructData> {
+        let src = id.source(db);
+        let name = src
+            .ast
+            .name()
+            .map(|n| n.as_name())
+            .unwrap_or_else(Name::missing);
+
+        let memory_kind = src
+            .ast
+            .memory_type_specifier()
+            .and_then(|s| s.kind())
+            .map(|kind| match kind {
+                ast::MemoryTypeSpecifierKind::Gc => StructMemoryKind::GC,
+                ast::MemoryTypeSpecifierKind::Value => StructMemoryKind::Value,
+            })
+            .unwrap_or_default();
+
+        // Malformed attributes are reported by `Struct::diagnostics`
+        let repr = src
+            .ast
+            .attrs()
+            .filter(|attr| attr_name(attr).map_or(false, |name| name == "repr"))
+            .filter_map(|attr| StructRepr::from_args(attr.attr_arg_list()))
+            .fold(StructRepr::default(), |repr, other| {
+                repr.merge(other).unwrap_or(repr)
+            });
+
+        let mut type_ref_builder = TypeRefBuilder::default();
+        let mut fields = Arena::default();
+        let kind = match src.ast.kind() {
+            ast::StructKind::Record(r) => {
+                for field in r.fields() {
+                    fields.alloc(StructFieldData {
+                        name: field
+                            .name()
+                            .map(|n| n.as_name())
+                            .unwrap_or_else(Name::missing),
+                        type_ref: type_ref_builder
+                            .alloc_from_node_opt(field.ascribed_type().as_ref()),
+                    });
+                }
+                StructKind::Record
+            }
+            ast::StructKind::Unit => StructKind::Unit,
+        };
+
+        let (type_ref_map, type_ref_source_map) = type_ref_builder.finish();
+        Arc::new(StructData {
+            name,
+            visibility: Visibility::from_ast(&src.ast),
+            fields,
+            kind,
+            memory_kind,
+            repr,
+            type_ref_map,
+            type_ref_source_map,
+        })
+    }
+
+    pub fn type_ref_source_map(&self) -> &TypeRefSourceMap {
+        &self.type_ref_source_map
+    }
+
+    pub fn type_ref_map(&self) -> &TypeRefMap {
+        &self.type_ref_map
+    }
+}
+
+/// A variant of an enum, e.g. `Idle` in `enum State { Idle, Walking }`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct EnumVariantData {
+    pub name: Name,
+    /// The value of the variant. It is either specified explicitly, e.g. `Walking = 2`, or one more
+    /// than the value of the previous variant. The value of the first variant defaults to `0`.
+    pub discriminant: i128,
+}
+
+/// An identifier for an enum's variant, local to the enum that declares it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LocalEnumVariantId(RawId);
+impl_arena_id!(LocalEnumVariantId);
+
+/// An error in the discriminants of an enum. The errors are stored without source locations, which
+/// are looked up when the diagnostics of the enum are reported.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum EnumDiagnostic {
+    /// The discriminant of the variant could not be evaluated.
+    InvalidDiscriminant(LocalEnumVariantId),
+    /// The discriminant of the variant does not fit in the integer type of the enum.
+    DiscriminantOverflow(LocalEnumVariantId),
+    /// The variant has the same discriminant as a previous variant.
+    DuplicateDiscriminant {
+        variant: LocalEnumVariantId,
+        first: LocalEnumVariantId,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct EnumData {
+    pub name: Name,
+    pub visibility: Visibility,
+    pub variants: Arena<LocalEnumVariantId, EnumVariantData>,
+    /// The integer type that represents values of the enum. This is the smallest unsigned type
+    /// that fits all discriminants, or the smallest signed type if a discriminant is negative.
+    pub repr: IntTy,
+    pub(crate) diagnostics: Vec<EnumDiagnostic>,
+}
+
+impl EnumData {
+    pub(crate) fn enum_data_query(db: &impl DefDatabase, id: Enum) -> Arc<EnumData> {
+        let src = id.source(db);
+        let name = src
+            .ast
+            .name()
+            .map(|n| n.as_name())
+            .unwrap_or_else(Name::missing);
+
+        let mut variants = Arena::default();
+        let mut diagnostics = Vec::new();
+        let mut next_discriminant = 0;
+        for variant in src
+            .ast
+            .enum_variant_list()
+            .into_iter()
+            .flat_map(|list| list.variants())
+        {
+            let explicit = variant.expr().map(|expr| eval_int_syntax(&expr));
+            let discriminant = match explicit {
+                Some(Ok(value)) => value,
+                _ => next_discriminant,
+            };
+            let id = variants.alloc(EnumVariantData {
+                name: variant
+                    .name()
+                    .map(|n| n.as_name())
+                    .unwrap_or_else(Name::missing),
+                discriminant,
+            });
+            if let Some(Err(_)) = explicit {
+                diagnostics.push(EnumDiagnostic::InvalidDiscriminant(id));
+            }
+            next_discriminant = discriminant.saturating_add(1);
+        }
+
+        let min = variants.iter().map(|(_, v)| v.discriminant).min();
+        let max = variants.iter().map(|(_, v)| v.discriminant).max();
+        let repr = repr_for_range(min.unwrap_or(0), max.unwrap_or(0));
+
+        let mut first_by_discriminant = FxHashMap::default();
+        for (id, variant) in variants.iter() {
+            if !fits_in(repr, variant.discriminant) {
+                diagnostics.push(EnumDiagnostic::DiscriminantOverflow(id));
+            } else if let Some(first) = first_by_discriminant.get(&variant.discriminant) {
+                diagnostics.push(EnumDiagnostic::DuplicateDiscriminant {
+                    variant: id,
+                    first: *first,
+                });
+            } else {
+                first_by_discriminant.insert(variant.discriminant, id);
+            }
+        }
+
+        Arc::new(EnumData {
+            name,
+            visibility: Visibility::from_ast(&src.ast),
+            variants,
+            repr,
+            diagnostics,
+        })
+    }
+}
+
+/// Returns the smallest integer type that fits all values from `min` to `max`. Values that do not
+/// fit in 64 bits are represented by 64-bit integers, and reported as overflows.
+fn repr_for_range(min: i128, max: i128) -> IntTy {
+    let candidates = if min < 0 {
+        [IntTy::i8(), IntTy::i16(), IntTy::i32(), IntTy::i64()]
+    } else {
+        [IntTy::u8(), IntTy::u16(), IntTy::u32(), IntTy::u64()]
+    };
+    candidates
+        .iter()
+        .copied()
+        .find(|ty| fits_in(*ty, min) && fits_in(*ty, max))
+        .unwrap_or(candidates[3])
+}
+
+/// Returns true if `value` can be represented by values of the integer type `ty`.
+fn fits_in(ty: IntTy, value: i128) -> bool {
+    value >= ty.min_value() && value <= ty.max_value() as i128
+}
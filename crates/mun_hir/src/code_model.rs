@@ -1,18 +1,38 @@
 pub(crate) mod src;
 
-use self::src::HasSource;
-use crate::diagnostics::DiagnosticSink;
+use self::src::{HasSource, Source};
+use crate::adt::{
+    EnumData, EnumDiagnostic, LocalEnumVariantId, LocalStructFieldId, StructData, StructMemoryKind,
+    StructRepr,
+};
+use crate::arena::ArenaId;
+use crate::const_eval::{eval_int_syntax, ConstEvalError, ConstValue, IntConstError};
+use crate::diagnostics::{
+    ConstDivisionByZero, ConstOverflow, CyclicConst, CyclicTypeAlias, Diagnostic, DiagnosticSink,
+    DuplicateDefinition, DuplicateEnumDiscriminant, EnumDiscriminantOverflow,
+    InvalidEnumDiscriminant, InvalidImplTarget, InvalidTestFunction, LiteralOutOfRange,
+    MalformedAttribute, MethodFieldCollision, NonConstExpr, PrivateItem, RecursiveStruct,
+    SelfParamOutsideImpl, UnknownAttribute, UnusedFunction,
+};
+use crate::expr::validator::ExprValidator;
 use crate::expr::{Body, BodySourceMap};
 use crate::ids::AstItemDef;
 use crate::ids::LocationCtx;
+use crate::lint::{lint_attr_diagnostics, LintLevel};
 use crate::name_resolution::Namespace;
 use crate::raw::{DefKind, RawFileItem};
 use crate::resolve::{Resolution, Resolver};
-use crate::ty::InferenceResult;
-use crate::type_ref::{TypeRefBuilder, TypeRefId, TypeRefMap, TypeRefSourceMap};
-use crate::{ids::FunctionId, AsName, DefDatabase, FileId, HirDatabase, Name, Ty};
-use mun_syntax::ast::{NameOwner, TypeAscriptionOwner};
-use rustc_hash::FxHashMap;
+use crate::ty::{FloatTy, InferenceResult, IntTy};
+use crate::type_ref::{TypeRef, TypeRefBuilder, TypeRefId, TypeRefMap, TypeRefSourceMap};
+use crate::{
+    ids::{ConstId, EnumId, FunctionId, ImplBlockId, StaticId, StructId, TypeAliasId},
+    AsName, DefDatabase, FileId, HirDatabase, Name, Ty, TypeCtor,
+};
+use mun_syntax::ast::{
+    AttrsOwner, DocCommentsOwner, NameOwner, TypeAscriptionOwner, VisibilityOwner,
+};
+use mun_syntax::{ast, AstNode, SyntaxNodePtr};
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -31,6 +51,23 @@ impl Module {
         self.file_id
     }
 
+    /// Returns the path of the module within its package, e.g. `shapes::square` for
+    /// `shapes/square.mun`. The path of the root module is empty.
+    pub fn path(self, db: &impl DefDatabase) -> Vec<Name> {
+        db.module_tree(db.file_source_root(self.file_id))
+            .path(self.file_id)
+            .to_vec()
+    }
+
+    /// Returns `name` qualified with the path of the module, e.g. `shapes::square::area`.
+    pub fn qualify(self, db: &impl DefDatabase, name: &str) -> String {
+        self.path(db)
+            .iter()
+            .map(|component| format!("{}::", component))
+            .chain(std::iter::once(name.to_string()))
+            .collect()
+    }
+
     /// Returns all the definitions declared in this module.
     pub fn declarations(self, db: &impl HirDatabase) -> Vec<ModuleDef> {
         db.module_data(self.file_id).definitions.clone()
@@ -41,22 +78,64 @@ impl Module {
     }
 
     pub fn diagnostics(self, db: &impl HirDatabase, sink: &mut DiagnosticSink) {
+        // Only lint attributes apply to a whole file
+        for attr in db.parse(self.file_id).tree().attrs() {
+            let name = match attr_name(&attr) {
+                Some(name) => name,
+                None => continue,
+            };
+            match LintLevel::from_attr_name(&name) {
+                Some(level) => lint_attr_diagnostics(self.file_id, &attr, name, level, sink),
+                None => sink.push(UnknownAttribute {
+                    file: self.file_id,
+                    attr: SyntaxNodePtr::new(attr.syntax()),
+                    name,
+                }),
+            }
+        }
         for diag in db.module_data(self.file_id).diagnostics.iter() {
             diag.add_to(db, self, sink);
         }
         for decl in self.declarations(db) {
-            #[allow(clippy::single_match)]
             match decl {
                 ModuleDef::Function(f) => f.diagnostics(db, sink),
+                ModuleDef::Struct(s) => s.diagnostics(db, sink),
+                ModuleDef::Enum(e) => e.diagnostics(db, sink),
+                ModuleDef::Const(c) => c.diagnostics(db, sink),
+                ModuleDef::Static(s) => s.diagnostics(db, sink),
+                ModuleDef::TypeAlias(t) => t.diagnostics(db, sink),
                 _ => (),
             }
         }
+        for impl_block in db.module_data(self.file_id).impls.iter() {
+            impl_block.diagnostics(db, sink);
+        }
+        db.imports(self.file_id)
+            .add_diagnostics(db, self.file_id, sink);
+    }
+}
+
+/// Returns the namespaces in which the definition `kind` of the file `file_id` defines its name. A
+/// struct without fields can also be used as a value.
+fn definition_namespaces(
+    db: &impl DefDatabase,
+    file_id: FileId,
+    kind: DefKind,
+) -> &'static [Namespace] {
+    match kind {
+        DefKind::Function(_) | DefKind::Const(_) | DefKind::Static(_) => &[Namespace::Values],
+        DefKind::Enum(_) | DefKind::TypeAlias(_) => &[Namespace::Types],
+        DefKind::Struct(id) => match id.with_file_id(file_id).to_node(db).kind() {
+            ast::StructKind::Record(_) => &[Namespace::Types],
+            ast::StructKind::Unit => &[Namespace::Types, Namespace::Values],
+        },
     }
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, Default)]
 pub struct ModuleData {
     definitions: Vec<ModuleDef>,
+    impls: Vec<ImplBlock>,
     diagnostics: Vec<ModuleDefinitionDiagnostic>,
 }
 
@@ -74,16 +153,28 @@ impl ModuleData {
         for item in items.items().iter() {
             match item {
                 RawFileItem::Definition(def) => {
-                    if let Some(prev_definition) = definition_by_name.get(&items[*def].name) {
+                    // A name can be defined once in each namespace, e.g. a function and a struct
+                    // with fields can have the same name
+                    let name = &items[*def].name;
+                    let namespaces = definition_namespaces(db, file_id, items[*def].kind);
+                    let first_definition = namespaces.iter().find_map(|namespace| {
+                        definition_by_name
+                            .get(&(name.clone(), *namespace))
+                            .map(|first| (*namespace, *first))
+                    });
+                    if let Some((namespace, first_definition)) = first_definition {
                         data.diagnostics.push(
                             diagnostics::ModuleDefinitionDiagnostic::DuplicateName {
-                                name: items[*def].name.clone(),
+                                name: name.clone(),
+                                namespace,
                                 definition: *def,
-                                first_definition: *prev_definition,
+                                first_definition,
                             },
                         )
                     } else {
-                        definition_by_name.insert(items[*def].name.clone(), *def);
+                        for namespace in namespaces {
+                            definition_by_name.insert((name.clone(), *namespace), *def);
+                        }
                     }
                     match items[*def].kind {
                         DefKind::Function(ast_id) => {
@@ -91,8 +182,43 @@ impl ModuleData {
                                 id: FunctionId::from_ast_id(loc_ctx, ast_id),
                             }))
                         }
+                        DefKind::Struct(ast_id) => {
+                            data.definitions.push(ModuleDef::Struct(Struct {
+                                id: StructId::from_ast_id(loc_ctx, ast_id),
+                            }))
+                        }
+                        DefKind::Enum(ast_id) => data.definitions.push(ModuleDef::Enum(Enum {
+                            id: EnumId::from_ast_id(loc_ctx, ast_id),
+                        })),
+                        DefKind::Const(ast_id) => data.definitions.push(ModuleDef::Const(Const {
+                            id: ConstId::from_ast_id(loc_ctx, ast_id),
+                        })),
+                        DefKind::Static(ast_id) => {
+                            data.definitions.push(ModuleDef::Static(Static {
+                                id: StaticId::from_ast_id(loc_ctx, ast_id),
+                            }))
+                        }
+                        DefKind::TypeAlias(ast_id) => {
+                            data.definitions.push(ModuleDef::TypeAlias(TypeAlias {
+                                id: TypeAliasId::from_ast_id(loc_ctx, ast_id),
+                            }))
+                        }
                     }
                 }
+                RawFileItem::Impl(ast_id) => {
+                    let impl_block = ImplBlock {
+                        id: ImplBlockId::from_ast_id(loc_ctx, *ast_id),
+                    };
+                    // Methods are generated like the other functions of the module, but they are
+                    // not in its scope
+                    let methods = db.impl_block_data(impl_block).functions.clone();
+                    data.definitions
+                        .extend(methods.into_iter().map(ModuleDef::Function));
+                    data.impls.push(impl_block);
+                }
+                // Imports are resolved by the `imports` query, once the definitions of all
+                // modules are known
+                RawFileItem::Import(_) => {}
             };
         }
         Arc::new(data)
@@ -107,6 +233,13 @@ impl ModuleData {
 pub enum ModuleDef {
     Function(Function),
     BuiltinType(BuiltinType),
+    BuiltinFunction(BuiltinFunction),
+    Struct(Struct),
+    Enum(Enum),
+    EnumVariant(EnumVariant),
+    Const(Const),
+    Static(Static),
+    TypeAlias(TypeAlias),
 }
 
 impl From<Function> for ModuleDef {
@@ -115,16 +248,117 @@ impl From<Function> for ModuleDef {
     }
 }
 
+impl From<Struct> for ModuleDef {
+    fn from(t: Struct) -> Self {
+        ModuleDef::Struct(t)
+    }
+}
+
+impl From<Enum> for ModuleDef {
+    fn from(t: Enum) -> Self {
+        ModuleDef::Enum(t)
+    }
+}
+
+impl From<EnumVariant> for ModuleDef {
+    fn from(t: EnumVariant) -> Self {
+        ModuleDef::EnumVariant(t)
+    }
+}
+
 impl From<BuiltinType> for ModuleDef {
     fn from(t: BuiltinType) -> Self {
         ModuleDef::BuiltinType(t)
     }
 }
 
+impl From<BuiltinFunction> for ModuleDef {
+    fn from(t: BuiltinFunction) -> Self {
+        ModuleDef::BuiltinFunction(t)
+    }
+}
+
+impl From<Const> for ModuleDef {
+    fn from(t: Const) -> Self {
+        ModuleDef::Const(t)
+    }
+}
+
+impl From<Static> for ModuleDef {
+    fn from(t: Static) -> Self {
+        ModuleDef::Static(t)
+    }
+}
+
+impl From<TypeAlias> for ModuleDef {
+    fn from(t: TypeAlias) -> Self {
+        ModuleDef::TypeAlias(t)
+    }
+}
+
+impl ModuleDef {
+    /// Returns true if the item can be named by the module of `file_id`. Items that are not
+    /// declared `export` can only be named by the module that defines them. An enum variant has
+    /// the visibility of its enum and builtins are visible everywhere.
+    pub(crate) fn is_visible_from(self, db: &impl HirDatabase, file_id: FileId) -> bool {
+        let (module, visibility) = match self {
+            ModuleDef::Function(f) => (f.module(db), f.visibility(db)),
+            ModuleDef::Struct(s) => (s.module(db), s.visibility(db)),
+            ModuleDef::Enum(e) => (e.module(db), e.visibility(db)),
+            ModuleDef::EnumVariant(v) => (v.parent.module(db), v.parent.visibility(db)),
+            ModuleDef::Const(c) => (c.module(db), c.visibility(db)),
+            ModuleDef::Static(s) => (s.module(db), s.visibility(db)),
+            ModuleDef::TypeAlias(t) => (t.module(db), t.visibility(db)),
+            ModuleDef::BuiltinType(_) | ModuleDef::BuiltinFunction(_) => return true,
+        };
+        visibility == Visibility::Public || module.file_id == file_id
+    }
+
+    /// Returns a diagnostic for the private item that is named by `path` in another module.
+    pub(crate) fn private_item_diagnostic(
+        self,
+        db: &impl HirDatabase,
+        file: FileId,
+        path: SyntaxNodePtr,
+    ) -> PrivateItem {
+        fn definition<N: AstNode>(src: Source<N>) -> (FileId, SyntaxNodePtr) {
+            (src.file_id, SyntaxNodePtr::new(src.ast.syntax()))
+        }
+        let (kind, name, (definition_file, definition)) = match self {
+            ModuleDef::Function(f) => ("function", f.full_name(db), definition(f.source(db))),
+            ModuleDef::Struct(s) => ("struct", s.name(db).to_string(), definition(s.source(db))),
+            ModuleDef::Enum(e) => ("enum", e.name(db).to_string(), definition(e.source(db))),
+            ModuleDef::EnumVariant(v) => {
+                return ModuleDef::Enum(v.parent).private_item_diagnostic(db, file, path)
+            }
+            ModuleDef::Const(c) => ("constant", c.name(db).to_string(), definition(c.source(db))),
+            ModuleDef::Static(s) => ("static", s.name(db).to_string(), definition(s.source(db))),
+            ModuleDef::TypeAlias(t) => (
+                "type alias",
+                t.name(db).to_string(),
+                definition(t.source(db)),
+            ),
+            ModuleDef::BuiltinType(_) | ModuleDef::BuiltinFunction(_) => {
+                unreachable!("builtins are visible in every module")
+            }
+        };
+        PrivateItem {
+            file,
+            path,
+            kind,
+            name,
+            definition_file,
+            definition,
+        }
+    }
+}
+
 /// The definitions that have a body.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DefWithBody {
     Function(Function),
+    Const(Const),
+    Static(Static),
 }
 
 impl DefWithBody {
@@ -144,6 +378,17 @@ impl DefWithBody {
     pub(crate) fn resolver(self, db: &impl HirDatabase) -> Resolver {
         match self {
             DefWithBody::Function(f) => f.resolver(db),
+            DefWithBody::Const(c) => c.resolver(db),
+            DefWithBody::Static(s) => s.resolver(db),
+        }
+    }
+
+    /// Returns the file in which this item is defined.
+    pub(crate) fn file_id(self, db: &impl DefDatabase) -> FileId {
+        match self {
+            DefWithBody::Function(f) => f.id.file_id(db),
+            DefWithBody::Const(c) => c.id.file_id(db),
+            DefWithBody::Static(s) => s.id.file_id(db),
         }
     }
 }
@@ -154,6 +399,18 @@ impl From<Function> for DefWithBody {
     }
 }
 
+impl From<Const> for DefWithBody {
+    fn from(c: Const) -> Self {
+        DefWithBody::Const(c)
+    }
+}
+
+impl From<Static> for DefWithBody {
+    fn from(s: Static) -> Self {
+        DefWithBody::Static(s)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Function {
     pub(crate) id: FunctionId,
@@ -165,7 +422,68 @@ pub struct FnData {
     params: Vec<TypeRefId>,
     ret_type: TypeRefId,
     type_ref_map: TypeRefMap,
-    type_ref_source_map: TypeRefSourceMap,
+    is_extern: bool,
+    visibility: Visibility,
+    inline: Option<InlineAttr>,
+    is_test: bool,
+    impl_block: Option<ImplBlock>,
+    has_self_param: bool,
+}
+
+/// Specifies whether an item is visible outside of its module and assembly, as declared with the
+/// `export` keyword.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Visibility {
+    /// `export`, the item can be named by other modules and is part of the public interface of the
+    /// assembly
+    Public,
+    /// The item can only be named by the module that defines it
+    Private,
+}
+
+impl Visibility {
+    /// Returns the visibility that is declared for the item `node`.
+    pub(crate) fn from_ast(node: &impl VisibilityOwner) -> Visibility {
+        if node.visibility().is_some() {
+            Visibility::Public
+        } else {
+            Visibility::Private
+        }
+    }
+}
+
+/// Specifies whether calls to a function should be inlined, as requested with the `inline`
+/// attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InlineAttr {
+    /// `#[inline]`, suggests that calls to the function are inlined
+    Hint,
+    /// `#[inline(always)]`, requests that calls to the function are always inlined
+    Always,
+    /// `#[inline(never)]`, requests that calls to the function are never inlined
+    Never,
+}
+
+impl InlineAttr {
+    /// The forms of the `inline` attribute, used in diagnostics.
+    const EXPECTED: &'static str = "`#[inline]`, `#[inline(always)]` or `#[inline(never)]`";
+
+    /// Parses the arguments of an `inline` attribute. Returns `None` if they are malformed.
+    fn from_args(args: Option<ast::AttrArgList>) -> Option<InlineAttr> {
+        let mut args = match args {
+            Some(args) => args.args(),
+            None => return Some(InlineAttr::Hint),
+        };
+        let inline = match attr_arg_name(&args.next()?)?.as_str() {
+            "always" => InlineAttr::Always,
+            "never" => InlineAttr::Never,
+            _ => return None,
+        };
+        if args.next().is_some() {
+            return None;
+        }
+        Some(inline)
+    }
 }
 
 impl FnData {
@@ -178,8 +496,18 @@ impl FnData {
             .map(|n| n.as_name())
             .unwrap_or_else(Name::missing);
 
+        let impl_block = src.ast.syntax().parent().and_then(ast::ImplBlock::cast);
+
         let mut params = Vec::new();
+        let mut has_self_param = false;
         if let Some(param_list) = src.ast.param_list() {
+            // The type of `self` is the type of the impl block. A `self` parameter outside of an
+            // impl block is reported by `Function::diagnostics`.
+            if param_list.self_param().is_some() {
+                has_self_param = true;
+                let impl_type = impl_block.as_ref().and_then(|it| it.type_ref());
+                params.push(type_ref_builder.alloc_self_type(impl_type.as_ref()));
+            }
             for param in param_list.params() {
                 let type_ref = type_ref_builder.alloc_from_node_opt(param.ascribed_type().as_ref());
                 params.push(type_ref);
@@ -192,14 +520,36 @@ impl FnData {
             type_ref_builder.unit()
         };
 
-        let (type_ref_map, type_ref_source_map) = type_ref_builder.finish();
+        // The source map is not kept, so the data of a function doesn't change when only its
+        // position in the file changes. Unresolved types in the signature are reported through the
+        // source map of the body.
+        let (type_ref_map, _) = type_ref_builder.finish();
+
+        // Malformed attributes are reported by `Function::diagnostics`
+        let inline = src
+            .ast
+            .attrs()
+            .filter(|attr| attr_name(attr).map_or(false, |name| name == "inline"))
+            .filter_map(|attr| InlineAttr::from_args(attr.attr_arg_list()))
+            .last();
+        let is_test = src
+            .ast
+            .attrs()
+            .any(|attr| attr_name(&attr).map_or(false, |name| name == "test"));
 
         Arc::new(FnData {
             name,
             params,
             ret_type,
             type_ref_map,
-            type_ref_source_map,
+            is_extern: src.ast.is_extern(),
+            visibility: Visibility::from_ast(&src.ast),
+            inline,
+            is_test,
+            impl_block: impl_block.map(|it| ImplBlock {
+                id: ImplBlockId::from_ast(LocationCtx::new(db, src.file_id), &it),
+            }),
+            has_self_param,
         })
     }
 
@@ -215,13 +565,39 @@ impl FnData {
         &self.ret_type
     }
 
-    pub fn type_ref_source_map(&self) -> &TypeRefSourceMap {
-        &self.type_ref_source_map
-    }
-
     pub fn type_ref_map(&self) -> &TypeRefMap {
         &self.type_ref_map
     }
+
+    /// Returns true if the function is declared `extern`, i.e. it is implemented by the host.
+    pub fn is_extern(&self) -> bool {
+        self.is_extern
+    }
+
+    /// Returns whether the function is declared `export`.
+    pub fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+
+    /// Returns the inlining that was requested with an `inline` attribute, if any.
+    pub fn inline(&self) -> Option<InlineAttr> {
+        self.inline
+    }
+
+    /// Returns true if the function is marked with the `test` attribute.
+    pub fn is_test(&self) -> bool {
+        self.is_test
+    }
+
+    /// Returns the impl block that defines the function, if it is a method.
+    pub fn impl_block(&self) -> Option<ImplBlock> {
+        self.impl_block
+    }
+
+    /// Returns true if the first parameter of the function is `self`.
+    pub fn has_self_param(&self) -> bool {
+        self.has_self_param
+    }
 }
 
 impl Function {
@@ -235,10 +611,58 @@ impl Function {
         self.data(db).name.clone()
     }
 
+    /// Returns the name of the function as it is exported, which is qualified with the name of
+    /// its struct if it is a method, e.g. `Vector::length`.
+    pub fn full_name(self, db: &impl HirDatabase) -> String {
+        match self.impl_block(db).and_then(|it| it.target_struct(db)) {
+            Some(s) => format!("{}::{}", s.name(db), self.name(db)),
+            None => self.name(db).to_string(),
+        }
+    }
+
+    /// Returns the full name of the function qualified with the path of its module, by which the
+    /// ABI refers to it, e.g. `shapes::Square::area` for a method in `shapes.mun`.
+    pub fn qualified_name(self, db: &impl HirDatabase) -> String {
+        self.module(db).qualify(db, &self.full_name(db))
+    }
+
+    /// Returns whether the function is part of the public interface of its assembly.
+    pub fn visibility(self, db: &impl HirDatabase) -> Visibility {
+        self.data(db).visibility
+    }
+
+    /// Returns true if the function is a test, marked with `#[test]`. Tests are only compiled in
+    /// test builds, unless other functions use them, and are invoked by the test runner.
+    pub fn is_test(self, db: &impl HirDatabase) -> bool {
+        self.data(db).is_test
+    }
+
+    /// Returns the impl block that defines this function, if it is a method.
+    pub fn impl_block(self, db: &impl HirDatabase) -> Option<ImplBlock> {
+        self.data(db).impl_block
+    }
+
+    /// Returns true if the function is a method that takes `self`, so it can be called on a value
+    /// of its struct, e.g. `v.length()`.
+    pub fn has_self_param(self, db: &impl HirDatabase) -> bool {
+        self.data(db).has_self_param
+    }
+
+    /// Returns the text of the doc comments attached to this function, if any.
+    pub fn documentation(self, db: &impl DefDatabase) -> Option<String> {
+        self.source(db).ast.doc_comment_text()
+    }
+
     pub fn data(self, db: &impl HirDatabase) -> Arc<FnData> {
         db.fn_data(self)
     }
 
+    /// Returns true if the function has no body in Mun and is instead provided by the host
+    /// through the runtime.
+    pub fn is_extern(self, db: &impl HirDatabase) -> bool {
+        self.data(db).is_extern()
+    }
+
     pub fn body(self, db: &impl HirDatabase) -> Arc<Body> {
         db.body_hir(self.into())
     }
@@ -251,7 +675,7 @@ impl Function {
         db.infer(self.into())
     }
 
-    pub(crate) fn body_source_map(self, db: &impl HirDatabase) -> Arc<BodySourceMap> {
+    pub fn body_source_map(self, db: &impl HirDatabase) -> Arc<BodySourceMap> {
         db.body_with_source_map(self.into()).1
     }
 
@@ -261,51 +685,1286 @@ impl Function {
     }
 
     pub fn diagnostics(self, db: &impl HirDatabase, sink: &mut DiagnosticSink) {
+        let src = self.source(db);
+        for attr in src.ast.attrs() {
+            // Attributes without a name are reported by the parser
+            let name = match attr_name(&attr) {
+                Some(name) => name,
+                None => continue,
+            };
+            let attr_ptr = SyntaxNodePtr::new(attr.syntax());
+            match name.as_str() {
+                "inline" => {
+                    if InlineAttr::from_args(attr.attr_arg_list()).is_none() {
+                        sink.push(MalformedAttribute {
+                            file: src.file_id,
+                            attr: attr_ptr,
+                            name,
+                            expected: InlineAttr::EXPECTED,
+                        })
+                    }
+                }
+                "test" => {
+                    if attr.attr_arg_list().is_some() {
+                        sink.push(MalformedAttribute {
+                            file: src.file_id,
+                            attr: attr_ptr,
+                            name,
+                            expected: "`#[test]`",
+                        })
+                    }
+                }
+                _ => match LintLevel::from_attr_name(&name) {
+                    Some(level) => lint_attr_diagnostics(src.file_id, &attr, name, level, sink),
+                    None => sink.push(UnknownAttribute {
+                        file: src.file_id,
+                        attr: attr_ptr,
+                        name,
+                    }),
+                },
+            }
+        }
+
+        if let Some(self_param) = src.ast.param_list().and_then(|it| it.self_param()) {
+            if self.impl_block(db).is_none() {
+                sink.push(SelfParamOutsideImpl {
+                    file: src.file_id,
+                    param: SyntaxNodePtr::new(self_param.syntax()),
+                })
+            }
+        }
+
+        if self.is_test(db) {
+            let data = self.data(db);
+            let reason = if data.is_extern() {
+                Some("be `extern`")
+            } else if data.visibility() == Visibility::Public {
+                Some("be `export`")
+            } else if data.impl_block().is_some() {
+                Some("be methods")
+            } else if !data.params().is_empty() {
+                Some("take parameters")
+            } else if src.ast.ret_type().is_some() {
+                Some("return a value")
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                sink.push(InvalidTestFunction {
+                    file: src.file_id,
+                    def: SyntaxNodePtr::new(src.ast.syntax()),
+                    name: self.name(db),
+                    reason,
+                });
+            }
+        }
+
+        let name = self.name(db);
+        if !self.is_extern(db)
+            && !self.is_test(db)
+            && !name.to_string().starts_with('_')
+            && !db
+                .used_functions(db.file_source_root(src.file_id))
+                .contains(&self)
+        {
+            sink.push(UnusedFunction {
+                file: src.file_id,
+                def: SyntaxNodePtr::new(src.ast.syntax()),
+                name: self.full_name(db),
+                fn_name: name,
+            });
+        }
+
         let infer = self.infer(db);
-        infer.add_diagnostics(db, self, sink);
-        //        let mut validator = ExprValidator::new(self, infer, sink);
-        //        validator.validate_body(db);
+        infer.add_diagnostics(db, self.into(), sink);
+        let mut validator = ExprValidator::new(self, infer, sink);
+        validator.validate_body(db);
+    }
+}
+
+/// Returns the name of an attribute, e.g. `inline` for `#[inline(always)]`.
+pub(crate) fn attr_name(attr: &ast::Attr) -> Option<String> {
+    attr.name_ref().map(|name| name.text().to_string())
+}
+
+/// Returns the name of an attribute argument without arguments of its own, e.g. `always` in
+/// `#[inline(always)]`.
+pub(crate) fn attr_arg_name(arg: &ast::AttrArg) -> Option<String> {
+    match arg.attr_arg_list() {
+        Some(_) => None,
+        None => arg.name_ref().map(|name| name.text().to_string()),
     }
 }
 
+/// A constant item, e.g. `const GRAVITY: float = -9.81;`. The value of a constant is evaluated at
+/// compile time and substituted wherever the constant is used.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum BuiltinType {
-    Float,
-    Int,
-    Boolean,
+pub struct Const {
+    pub(crate) id: ConstId,
 }
 
-use crate::code_model::diagnostics::ModuleDefinitionDiagnostic;
-use crate::name::*;
+#[derive(Debug, PartialEq, Eq)]
+pub struct ConstData {
+    name: Name,
+    visibility: Visibility,
+    type_ref: TypeRefId,
+    type_ref_map: TypeRefMap,
+}
 
-impl BuiltinType {
-    #[rustfmt::skip]
-    pub(crate) const ALL: &'static [(Name, BuiltinType)] = &[
-        (FLOAT, BuiltinType::Float),
-        (INT, BuiltinType::Int),
-        (BOOLEAN, BuiltinType::Boolean),
-    ];
+impl ConstData {
+    pub(crate) fn const_data_query(db: &impl DefDatabase, c: Const) -> Arc<ConstData> {
+        let src = c.source(db);
+        let name = src
+            .ast
+            .name()
+            .map(|n| n.as_name())
+            .unwrap_or_else(Name::missing);
+
+        let mut type_ref_builder = TypeRefBuilder::default();
+        let type_ref = type_ref_builder.alloc_from_node_opt(src.ast.ascribed_type().as_ref());
+        let (type_ref_map, _) = type_ref_builder.finish();
+
+        Arc::new(ConstData {
+            name,
+            visibility: Visibility::from_ast(&src.ast),
+            type_ref,
+            type_ref_map,
+        })
+    }
+
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    pub fn type_ref(&self) -> &TypeRefId {
+        &self.type_ref
+    }
+
+    pub fn type_ref_map(&self) -> &TypeRefMap {
+        &self.type_ref_map
+    }
 }
 
-mod diagnostics {
-    use super::Module;
-    use crate::diagnostics::{DiagnosticSink, DuplicateDefinition};
-    use crate::raw::{DefId, DefKind};
-    use crate::{DefDatabase, Name};
-    use mun_syntax::{AstNode, SyntaxNodePtr};
+impl Const {
+    pub fn module(self, db: &impl DefDatabase) -> Module {
+        Module {
+            file_id: self.id.file_id(db),
+        }
+    }
 
-    #[derive(Debug, PartialEq, Eq, Clone, Hash)]
-    pub(super) enum ModuleDefinitionDiagnostic {
-        DuplicateName {
-            name: Name,
-            definition: DefId,
-            first_definition: DefId,
-        },
+    pub fn name(self, db: &impl HirDatabase) -> Name {
+        self.data(db).name.clone()
     }
 
-    fn syntax_ptr_from_def(db: &impl DefDatabase, owner: Module, kind: DefKind) -> SyntaxNodePtr {
-        match kind {
-            DefKind::Function(id) => {
+    /// Returns whether the constant is declared `export`, which makes it visible outside of its
+    /// module.
+    pub fn visibility(self, db: &impl HirDatabase) -> Visibility {
+        self.data(db).visibility
+    }
+
+    /// Returns the text of the doc comments attached to this constant, if any.
+    pub fn documentation(self, db: &impl DefDatabase) -> Option<String> {
+        self.source(db).ast.doc_comment_text()
+    }
+
+    pub fn data(self, db: &impl HirDatabase) -> Arc<ConstData> {
+        db.const_data(self)
+    }
+
+    /// Returns the initializer of the constant as a body without parameters.
+    pub fn body(self, db: &impl HirDatabase) -> Arc<Body> {
+        db.body_hir(self.into())
+    }
+
+    /// Returns the declared type of the constant.
+    pub fn ty(self, db: &impl HirDatabase) -> Ty {
+        db.type_for_def(self.into(), Namespace::Values)
+    }
+
+    pub fn infer(self, db: &impl HirDatabase) -> Arc<InferenceResult> {
+        db.infer(self.into())
+    }
+
+    /// Returns the value of the constant, or an error if it cannot be evaluated at compile time.
+    pub fn value(self, db: &impl HirDatabase) -> Result<ConstValue, ConstEvalError> {
+        db.const_eval(self)
+    }
+
+    pub(crate) fn body_source_map(self, db: &impl HirDatabase) -> Arc<BodySourceMap> {
+        db.body_with_source_map(self.into()).1
+    }
+
+    pub(crate) fn resolver(self, db: &impl HirDatabase) -> Resolver {
+        // take the outer scope...
+        self.module(db).resolver(db)
+    }
+
+    pub fn diagnostics(self, db: &impl HirDatabase, sink: &mut DiagnosticSink) {
+        let infer = self.infer(db);
+        infer.add_diagnostics(db, self.into(), sink);
+
+        match self.value(db) {
+            Ok(_) => {}
+            Err(ConstEvalError::Cycle) => {
+                let src = self.source(db);
+                sink.push(CyclicConst {
+                    file: src.file_id,
+                    const_def: SyntaxNodePtr::new(src.ast.syntax()),
+                    name: self.name(db).to_string(),
+                })
+            }
+            Err(err) => add_const_eval_diagnostic(db, self.into(), err, sink),
+        }
+    }
+}
+
+/// A static item, e.g. `static mut SCORE: int = 0;`. A static is a global variable that is stored
+/// in the assembly, which is shared by all functions that refer to it.
+///
+/// The initial value of a static is evaluated at compile time, so statics are initialized when
+/// their assembly is loaded and the order in which they are declared doesn't matter. When the
+/// assembly is hot reloaded, mutable statics keep their values if their types didn't change.
+///
+/// Access to statics is not synchronized: invoking functions that modify a static from multiple
+/// threads at the same time is a data race.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Static {
+    pub(crate) id: StaticId,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct StaticData {
+    name: Name,
+    visibility: Visibility,
+    type_ref: TypeRefId,
+    type_ref_map: TypeRefMap,
+    is_mutable: bool,
+}
+
+impl StaticData {
+    pub(crate) fn static_data_query(db: &impl DefDatabase, s: Static) -> Arc<StaticData> {
+        let src = s.source(db);
+        let name = src
+            .ast
+            .name()
+            .map(|n| n.as_name())
+            .unwrap_or_else(Name::missing);
+
+        let mut type_ref_builder = TypeRefBuilder::default();
+        let type_ref = type_ref_builder.alloc_from_node_opt(src.ast.ascribed_type().as_ref());
+        let (type_ref_map, _) = type_ref_builder.finish();
+
+        Arc::new(StaticData {
+            name,
+            visibility: Visibility::from_ast(&src.ast),
+            type_ref,
+            type_ref_map,
+            is_mutable: src.ast.is_mutable(),
+        })
+    }
+
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    pub fn type_ref(&self) -> &TypeRefId {
+        &self.type_ref
+    }
+
+    pub fn type_ref_map(&self) -> &TypeRefMap {
+        &self.type_ref_map
+    }
+
+    pub fn is_mutable(&self) -> bool {
+        self.is_mutable
+    }
+}
+
+impl Static {
+    pub fn module(self, db: &impl DefDatabase) -> Module {
+        Module {
+            file_id: self.id.file_id(db),
+        }
+    }
+
+    pub fn name(self, db: &impl HirDatabase) -> Name {
+        self.data(db).name.clone()
+    }
+
+    /// Returns whether the static is declared `export`, which makes it visible outside of its
+    /// module.
+    pub fn visibility(self, db: &impl HirDatabase) -> Visibility {
+        self.data(db).visibility
+    }
+
+    /// Returns the name of the static qualified with the path of its module, e.g. `enemy::COUNT`.
+    pub fn qualified_name(self, db: &impl HirDatabase) -> String {
+        self.module(db).qualify(db, &self.name(db).to_string())
+    }
+
+    /// Returns the text of the doc comments attached to this static, if any.
+    pub fn documentation(self, db: &impl DefDatabase) -> Option<String> {
+        self.source(db).ast.doc_comment_text()
+    }
+
+    pub fn data(self, db: &impl HirDatabase) -> Arc<StaticData> {
+        db.static_data(self)
+    }
+
+    /// Returns true if the static is declared `mut`, i.e. functions can assign to it.
+    pub fn is_mutable(self, db: &impl HirDatabase) -> bool {
+        self.data(db).is_mutable
+    }
+
+    /// Returns the initializer of the static as a body without parameters.
+    pub fn body(self, db: &impl HirDatabase) -> Arc<Body> {
+        db.body_hir(self.into())
+    }
+
+    /// Returns the declared type of the static.
+    pub fn ty(self, db: &impl HirDatabase) -> Ty {
+        db.type_for_def(self.into(), Namespace::Values)
+    }
+
+    pub fn infer(self, db: &impl HirDatabase) -> Arc<InferenceResult> {
+        db.infer(self.into())
+    }
+
+    /// Returns the initial value of the static, or an error if it cannot be evaluated at compile
+    /// time.
+    pub fn value(self, db: &impl HirDatabase) -> Result<ConstValue, ConstEvalError> {
+        db.static_eval(self)
+    }
+
+    pub(crate) fn body_source_map(self, db: &impl HirDatabase) -> Arc<BodySourceMap> {
+        db.body_with_source_map(self.into()).1
+    }
+
+    pub(crate) fn resolver(self, db: &impl HirDatabase) -> Resolver {
+        // take the outer scope...
+        self.module(db).resolver(db)
+    }
+
+    pub fn diagnostics(self, db: &impl HirDatabase, sink: &mut DiagnosticSink) {
+        let infer = self.infer(db);
+        infer.add_diagnostics(db, self.into(), sink);
+
+        if let Err(err) = self.value(db) {
+            add_const_eval_diagnostic(db, self.into(), err, sink);
+        }
+    }
+}
+
+/// Reports an error that occurred while evaluating the initializer of `owner` at compile time.
+fn add_const_eval_diagnostic(
+    db: &impl HirDatabase,
+    owner: DefWithBody,
+    err: ConstEvalError,
+    sink: &mut DiagnosticSink,
+) {
+    let file = owner.file_id(db);
+    let expr_ptr = |expr| {
+        owner
+            .body_source_map(db)
+            .expr_syntax(expr)
+            .map(|src| src.ast.syntax_node_ptr())
+    };
+    match err {
+        ConstEvalError::NotConst(expr) => {
+            if let Some(expr) = expr_ptr(expr) {
+                sink.push(NonConstExpr { file, expr })
+            }
+        }
+        ConstEvalError::Overflow(expr) => {
+            if let Some(expr) = expr_ptr(expr) {
+                sink.push(ConstOverflow { file, expr })
+            }
+        }
+        ConstEvalError::DivisionByZero(expr) => {
+            if let Some(expr) = expr_ptr(expr) {
+                sink.push(ConstDivisionByZero { file, expr })
+            }
+        }
+        // Cycles are reported by the constants that are part of them, other errors are reported
+        // where they occur
+        ConstEvalError::Cycle | ConstEvalError::Invalid => {}
+    }
+}
+
+/// Reports an error that occurred while evaluating an integer constant outside of a body, e.g.
+/// the length of an array type. A value that is not valid is reported by the diagnostic that
+/// `invalid_value` creates, because its message depends on where the constant is used.
+pub(crate) fn add_int_const_diagnostic<D: Diagnostic>(
+    file: FileId,
+    err: IntConstError,
+    invalid_value: impl FnOnce(SyntaxNodePtr) -> D,
+    sink: &mut DiagnosticSink,
+) {
+    match err {
+        IntConstError::NotConst(expr) => sink.push(NonConstExpr { file, expr }),
+        IntConstError::InvalidValue(expr) => sink.push(invalid_value(expr)),
+        IntConstError::Overflow(expr) => sink.push(ConstOverflow { file, expr }),
+        IntConstError::DivisionByZero(expr) => sink.push(ConstDivisionByZero { file, expr }),
+        IntConstError::LiteralOutOfRange(literal, literal_ty, radix) => {
+            sink.push(LiteralOutOfRange {
+                file,
+                literal,
+                literal_ty,
+                radix,
+            })
+        }
+        // Syntax errors are reported by the parser
+        IntConstError::Missing => {}
+    }
+}
+
+/// A type alias, e.g. `type Seconds = float;`. Aliases are fully transparent: wherever an alias is
+/// used, its target type is used instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TypeAlias {
+    pub(crate) id: TypeAliasId,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TypeAliasData {
+    name: Name,
+    visibility: Visibility,
+    type_ref: TypeRefId,
+    type_ref_map: TypeRefMap,
+    type_ref_source_map: TypeRefSourceMap,
+}
+
+impl TypeAliasData {
+    pub(crate) fn type_alias_data_query(db: &impl DefDatabase, t: TypeAlias) -> Arc<TypeAliasData> {
+        let src = t.source(db);
+        let name = src
+            .ast
+            .name()
+            .map(|n| n.as_name())
+            .unwrap_or_else(Name::missing);
+
+        let mut type_ref_builder = TypeRefBuilder::default();
+        let type_ref = type_ref_builder.alloc_from_node_opt(src.ast.type_ref().as_ref());
+        let (type_ref_map, type_ref_source_map) = type_ref_builder.finish();
+
+        Arc::new(TypeAliasData {
+            name,
+            visibility: Visibility::from_ast(&src.ast),
+            type_ref,
+            type_ref_map,
+            type_ref_source_map,
+        })
+    }
+
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    pub fn type_ref(&self) -> &TypeRefId {
+        &self.type_ref
+    }
+
+    pub fn type_ref_map(&self) -> &TypeRefMap {
+        &self.type_ref_map
+    }
+}
+
+impl TypeAlias {
+    pub fn module(self, db: &impl DefDatabase) -> Module {
+        Module {
+            file_id: self.id.file_id(db),
+        }
+    }
+
+    pub fn name(self, db: &impl DefDatabase) -> Name {
+        self.data(db).name.clone()
+    }
+
+    /// Returns whether the type alias is declared `export`, which makes it visible outside of its
+    /// module.
+    pub fn visibility(self, db: &impl DefDatabase) -> Visibility {
+        self.data(db).visibility
+    }
+
+    /// Returns the text of the doc comments attached to this alias, if any.
+    pub fn documentation(self, db: &impl DefDatabase) -> Option<String> {
+        self.source(db).ast.doc_comment_text()
+    }
+
+    pub fn data(self, db: &impl DefDatabase) -> Arc<TypeAliasData> {
+        db.type_alias_data(self)
+    }
+
+    /// Returns the target type of the alias, or an unknown type if the alias is cyclic.
+    pub fn ty(self, db: &impl HirDatabase) -> Ty {
+        db.type_for_def(self.into(), Namespace::Types)
+    }
+
+    pub(crate) fn resolver(self, db: &impl HirDatabase) -> Resolver {
+        // take the outer scope...
+        self.module(db).resolver(db)
+    }
+
+    /// Returns true if the target type of the alias refers to the alias itself, either directly or
+    /// through other aliases. The target type of such an alias cannot be resolved.
+    pub(crate) fn is_cyclic(self, db: &impl HirDatabase) -> bool {
+        let mut visited = FxHashSet::default();
+        let mut stack = self.referenced_aliases(db);
+        while let Some(alias) = stack.pop() {
+            if alias == self {
+                return true;
+            }
+            if visited.insert(alias) {
+                stack.extend(alias.referenced_aliases(db));
+            }
+        }
+        false
+    }
+
+    /// Returns the aliases that the target type of this alias refers to.
+    fn referenced_aliases(self, db: &impl HirDatabase) -> Vec<TypeAlias> {
+        let data = self.data(db);
+        let resolver = self.resolver(db);
+        let mut aliases = Vec::new();
+        let mut type_refs = vec![data.type_ref];
+        while let Some(type_ref) = type_refs.pop() {
+            match &data.type_ref_map[type_ref] {
+                TypeRef::Path(path) => {
+                    if let Some(Resolution::Def(ModuleDef::TypeAlias(alias))) = resolver
+                        .resolve_path_without_assoc_items(db, path)
+                        .take_types()
+                    {
+                        aliases.push(alias);
+                    }
+                }
+                TypeRef::Tuple(fields) => type_refs.extend(fields.iter().copied()),
+                TypeRef::Array(elem, _) => type_refs.push(*elem),
+                TypeRef::Fn(params, ret) => {
+                    type_refs.extend(params.iter().copied());
+                    type_refs.push(*ret);
+                }
+                TypeRef::Never | TypeRef::Empty | TypeRef::Error => {}
+            }
+        }
+        aliases
+    }
+
+    pub fn diagnostics(self, db: &impl HirDatabase, sink: &mut DiagnosticSink) {
+        let src = self.source(db);
+        if self.is_cyclic(db) {
+            sink.push(CyclicTypeAlias {
+                file: src.file_id,
+                type_alias_def: SyntaxNodePtr::new(src.ast.syntax()),
+                name: self.name(db).to_string(),
+            });
+            return;
+        }
+
+        let data = self.data(db);
+        let lower = Ty::from_hir(db, &self.resolver(db), data.type_ref_map(), data.type_ref);
+        for diag in lower.diagnostics {
+            diag.add_to(db, src.file_id, &data.type_ref_source_map, sink);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuiltinType {
+    Float(FloatTy),
+    Int(IntTy),
+    Boolean,
+    Str,
+}
+
+/// A function that is provided by the compiler, e.g. `sqrt`. Calls to builtin functions are
+/// generated inline. A builtin function is defined for every integer and/or float type: its
+/// operands and its result all have the type of its first argument, e.g. `min(1u8, 2)` is a `u8`.
+/// The assertions are the exception, which don't return a value.
+///
+/// Builtin functions are in scope in every module, but definitions in the module with the same
+/// name take precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuiltinFunction {
+    Sqrt,
+    /// The absolute value of a signed integer wraps around for the smallest value of its type,
+    /// e.g. `abs(-128i8)` is `-128i8`.
+    Abs,
+    Sin,
+    Cos,
+    Floor,
+    Min,
+    Max,
+    /// The wrapping operations wrap around on overflow, even if overflow checks are enabled, e.g.
+    /// `wrapping_add(255u8, 1)` is `0u8`. They are only defined for integers.
+    WrappingAdd,
+    WrappingSub,
+    WrappingMul,
+    /// The saturating operations result in the smallest or largest value of the type on overflow,
+    /// e.g. `saturating_sub(0u8, 1)` is `0u8`. They are only defined for integers.
+    SaturatingAdd,
+    SaturatingSub,
+    /// `assert(condition)` traps if its `bool` operand is false. The trap reports its location and
+    /// the source of the condition, e.g. `hp >= 0`.
+    Assert,
+    /// `assert_eq(a, b)` traps if its operands are not equal, like `assert(a == b)`. It is defined
+    /// for integers, floats and bools.
+    AssertEq,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Struct {
+    pub(crate) id: StructId,
+}
+
+/// A field of a `Struct`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StructField {
+    pub(crate) parent: Struct,
+    pub(crate) id: LocalStructFieldId,
+}
+
+impl Struct {
+    pub fn module(self, db: &impl DefDatabase) -> Module {
+        Module {
+            file_id: self.id.file_id(db),
+        }
+    }
+
+    pub fn name(self, db: &impl HirDatabase) -> Name {
+        self.data(db).name.clone()
+    }
+
+    /// Returns whether the struct is declared `export`, which makes it visible outside of its
+    /// module.
+    pub fn visibility(self, db: &impl HirDatabase) -> Visibility {
+        self.data(db).visibility
+    }
+
+    /// Returns the text of the doc comments attached to this struct, if any.
+    pub fn documentation(self, db: &impl DefDatabase) -> Option<String> {
+        self.source(db).ast.doc_comment_text()
+    }
+
+    pub fn data(self, db: &impl DefDatabase) -> Arc<StructData> {
+        db.struct_data(self)
+    }
+
+    /// Returns whether values of this struct are garbage collected or stored by value.
+    pub fn memory_kind(self, db: &impl HirDatabase) -> StructMemoryKind {
+        self.data(db).memory_kind
+    }
+
+    /// Returns how the fields of this struct are laid out in memory.
+    pub fn repr(self, db: &impl HirDatabase) -> StructRepr {
+        self.data(db).repr
+    }
+
+    /// Returns the fields of this struct in declaration order.
+    pub fn fields(self, db: &impl HirDatabase) -> Vec<StructField> {
+        self.data(db)
+            .fields
+            .iter()
+            .map(|(id, _)| StructField { parent: self, id })
+            .collect()
+    }
+
+    /// Returns the field called `name`, if it exists.
+    pub fn field(self, db: &impl HirDatabase, name: &Name) -> Option<StructField> {
+        self.data(db)
+            .fields
+            .iter()
+            .find(|(_, data)| data.name == *name)
+            .map(|(id, _)| StructField { parent: self, id })
+    }
+
+    /// Returns the methods that the impl blocks of the module define for this struct, in
+    /// declaration order.
+    pub fn methods(self, db: &impl HirDatabase) -> Vec<Function> {
+        db.module_data(self.id.file_id(db))
+            .impls
+            .iter()
+            .filter(|impl_block| impl_block.target_struct(db) == Some(self))
+            .flat_map(|impl_block| impl_block.functions(db))
+            .collect()
+    }
+
+    /// Returns the method called `name`, if it exists.
+    pub fn method(self, db: &impl HirDatabase, name: &Name) -> Option<Function> {
+        self.methods(db).into_iter().find(|f| f.name(db) == *name)
+    }
+
+    pub fn ty(self, db: &impl HirDatabase) -> Ty {
+        db.type_for_def(self.into(), Namespace::Types)
+    }
+
+    pub(crate) fn resolver(self, db: &impl HirDatabase) -> Resolver {
+        // take the outer scope...
+        self.module(db).resolver(db)
+    }
+
+    /// Returns the shortest chain of fields through which this struct contains itself by value, if
+    /// it does. Such a struct would be infinitely large. A struct that is garbage collected is
+    /// stored behind a pointer, so a chain can only go through value structs.
+    pub(crate) fn recursive_fields(self, db: &impl HirDatabase) -> Option<Vec<StructField>> {
+        let mut visited = FxHashSet::default();
+        let mut queue = std::collections::VecDeque::new();
+        queue.push_back((self, Vec::new()));
+        while let Some((s, chain)) = queue.pop_front() {
+            for field in s.fields(db) {
+                for inner in inline_structs(db, &field.ty(db)) {
+                    let mut chain = chain.clone();
+                    chain.push(field);
+                    if inner == self {
+                        return Some(chain);
+                    }
+                    if visited.insert(inner) {
+                        queue.push_back((inner, chain));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    pub fn diagnostics(self, db: &impl HirDatabase, sink: &mut DiagnosticSink) {
+        let src = self.source(db);
+        let mut repr = StructRepr::default();
+        for attr in src.ast.attrs() {
+            // Attributes without a name are reported by the parser
+            let name = match attr_name(&attr) {
+                Some(name) => name,
+                None => continue,
+            };
+            let attr_ptr = SyntaxNodePtr::new(attr.syntax());
+            match name.as_str() {
+                "repr" => {
+                    // A struct cannot be both packed and aligned, even by different attributes
+                    match StructRepr::from_args(attr.attr_arg_list())
+                        .and_then(|attr_repr| repr.merge(attr_repr))
+                    {
+                        Some(merged) => repr = merged,
+                        None => sink.push(MalformedAttribute {
+                            file: src.file_id,
+                            attr: attr_ptr,
+                            name,
+                            expected: StructRepr::EXPECTED,
+                        }),
+                    }
+                }
+                _ => match LintLevel::from_attr_name(&name) {
+                    Some(level) => lint_attr_diagnostics(src.file_id, &attr, name, level, sink),
+                    None => sink.push(UnknownAttribute {
+                        file: src.file_id,
+                        attr: attr_ptr,
+                        name,
+                    }),
+                },
+            }
+        }
+
+        let data = self.data(db);
+        let resolver = self.resolver(db);
+        let file = src.file_id;
+        for (_, field) in data.fields.iter() {
+            let lower = Ty::from_hir(db, &resolver, data.type_ref_map(), field.type_ref);
+            for diag in lower.diagnostics {
+                diag.add_to(db, file, data.type_ref_source_map(), sink);
+            }
+        }
+
+        // Every struct of a cycle is reported, at its own field that starts the cycle
+        if let Some(chain) = self.recursive_fields(db) {
+            let field = match src.ast.kind() {
+                ast::StructKind::Record(r) => r.fields().nth(chain[0].index() as usize),
+                ast::StructKind::Unit => None,
+            };
+            if let Some(field) = field {
+                sink.push(RecursiveStruct {
+                    file,
+                    field: SyntaxNodePtr::new(field.syntax()),
+                    name: self.name(db).to_string(),
+                    fields: chain
+                        .iter()
+                        .map(|f| format!("{}.{}", f.parent_struct().name(db), f.name(db)))
+                        .collect(),
+                });
+            }
+        }
+    }
+}
+
+/// Returns the value structs that a value of type `ty` stores inline, e.g. the elements of an array
+/// of value structs. Garbage collected structs are stored behind a pointer.
+fn inline_structs(db: &impl HirDatabase, ty: &Ty) -> Vec<Struct> {
+    match ty {
+        Ty::Apply(app) => match app.ctor {
+            TypeCtor::Struct(s) if s.memory_kind(db) == StructMemoryKind::Value => vec![s],
+            TypeCtor::Tuple { .. } | TypeCtor::Array { .. } => app
+                .parameters
+                .iter()
+                .flat_map(|ty| inline_structs(db, ty))
+                .collect(),
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+impl StructField {
+    pub fn name(self, db: &impl HirDatabase) -> Name {
+        self.parent.data(db).fields[self.id].name.clone()
+    }
+
+    /// Returns the type of the field.
+    pub fn ty(self, db: &impl HirDatabase) -> Ty {
+        let data = self.parent.data(db);
+        let type_ref = data.fields[self.id].type_ref;
+        Ty::from_hir(db, &self.parent.resolver(db), data.type_ref_map(), type_ref).ty
+    }
+
+    /// Returns the struct that declares this field.
+    pub fn parent_struct(self) -> Struct {
+        self.parent
+    }
+
+    /// Returns the index of the field in its struct's declaration order.
+    pub fn index(self) -> u32 {
+        self.id.into_raw().into()
+    }
+}
+
+/// An impl block that defines the methods of a struct, e.g.
+/// `impl Vector { fn length(self): float { ... } }`. Methods are called on a value of the struct,
+/// e.g. `v.length()`, or through the name of the struct, e.g. `Vector::length(v)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ImplBlock {
+    pub(crate) id: ImplBlockId,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ImplBlockData {
+    type_ref: TypeRefId,
+    type_ref_map: TypeRefMap,
+    type_ref_source_map: TypeRefSourceMap,
+    functions: Vec<Function>,
+}
+
+impl ImplBlockData {
+    pub(crate) fn impl_block_data_query(
+        db: &impl DefDatabase,
+        impl_block: ImplBlock,
+    ) -> Arc<ImplBlockData> {
+        let src = impl_block.source(db);
+        let mut type_ref_builder = TypeRefBuilder::default();
+        let type_ref = type_ref_builder.alloc_from_node_opt(src.ast.type_ref().as_ref());
+        let (type_ref_map, type_ref_source_map) = type_ref_builder.finish();
+
+        let loc_ctx = LocationCtx::new(db, src.file_id);
+        let functions = src
+            .ast
+            .functions()
+            .map(|it| Function {
+                id: FunctionId::from_ast(loc_ctx, &it),
+            })
+            .collect();
+
+        Arc::new(ImplBlockData {
+            type_ref,
+            type_ref_map,
+            type_ref_source_map,
+            functions,
+        })
+    }
+
+    pub fn type_ref(&self) -> &TypeRefId {
+        &self.type_ref
+    }
+
+    pub fn type_ref_map(&self) -> &TypeRefMap {
+        &self.type_ref_map
+    }
+}
+
+impl ImplBlock {
+    pub fn module(self, db: &impl DefDatabase) -> Module {
+        Module {
+            file_id: self.id.file_id(db),
+        }
+    }
+
+    pub fn data(self, db: &impl DefDatabase) -> Arc<ImplBlockData> {
+        db.impl_block_data(self)
+    }
+
+    /// Returns the methods of this impl block in declaration order.
+    pub fn functions(self, db: &impl DefDatabase) -> Vec<Function> {
+        self.data(db).functions.clone()
+    }
+
+    /// Returns the type that this impl block defines methods for.
+    pub fn target_ty(self, db: &impl HirDatabase) -> Ty {
+        let data = self.data(db);
+        let resolver = self.module(db).resolver(db);
+        Ty::from_hir(db, &resolver, &data.type_ref_map, data.type_ref).ty
+    }
+
+    /// Returns the struct that this impl block defines methods for, or `None` if its type is not a
+    /// struct.
+    pub fn target_struct(self, db: &impl HirDatabase) -> Option<Struct> {
+        match self.target_ty(db).as_simple() {
+            Some(TypeCtor::Struct(s)) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn diagnostics(self, db: &impl HirDatabase, sink: &mut DiagnosticSink) {
+        let data = self.data(db);
+        let file = self.source(db).file_id;
+        let resolver = self.module(db).resolver(db);
+        let lower = Ty::from_hir(db, &resolver, &data.type_ref_map, data.type_ref);
+        for diag in lower.diagnostics {
+            diag.add_to(db, file, &data.type_ref_source_map, sink);
+        }
+
+        let target = match lower.ty.as_simple() {
+            Some(TypeCtor::Struct(s)) => s,
+            // Unresolved types are reported above
+            _ if lower.ty == Ty::Unknown => return,
+            _ => {
+                if let Some(type_ref) = data.type_ref_source_map.type_ref_syntax(data.type_ref) {
+                    sink.push(InvalidImplTarget {
+                        file,
+                        type_ref: type_ref.syntax_node_ptr(),
+                    });
+                }
+                return;
+            }
+        };
+
+        // Methods share a namespace with the other methods of the struct, including those of
+        // other impl blocks, and with its fields
+        let methods = target.methods(db);
+        for function in data.functions.iter() {
+            let name = function.name(db);
+            let definition = function.source(db).ast.syntax().clone();
+            let first = methods.iter().find(|it| it.name(db) == name);
+            if let Some(first) = first.filter(|it| *it != function) {
+                sink.push(DuplicateDefinition {
+                    file,
+                    name: name.to_string(),
+                    namespace: Some(Namespace::Values),
+                    first_definition: SyntaxNodePtr::new(first.source(db).ast.syntax()),
+                    definition: SyntaxNodePtr::new(&definition),
+                });
+            }
+            if target.field(db, &name).is_some() {
+                let method = function.source(db).ast.name();
+                sink.push(MethodFieldCollision {
+                    file,
+                    method: method.map_or(SyntaxNodePtr::new(&definition), |name| {
+                        SyntaxNodePtr::new(name.syntax())
+                    }),
+                    name: name.to_string(),
+                    struct_name: target.name(db).to_string(),
+                });
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Enum {
+    pub(crate) id: EnumId,
+}
+
+/// A variant of an `Enum`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EnumVariant {
+    pub(crate) parent: Enum,
+    pub(crate) id: LocalEnumVariantId,
+}
+
+impl Enum {
+    pub fn module(self, db: &impl DefDatabase) -> Module {
+        Module {
+            file_id: self.id.file_id(db),
+        }
+    }
+
+    pub fn name(self, db: &impl HirDatabase) -> Name {
+        self.data(db).name.clone()
+    }
+
+    /// Returns whether the enum is declared `export`, which makes it visible outside of its
+    /// module.
+    pub fn visibility(self, db: &impl HirDatabase) -> Visibility {
+        self.data(db).visibility
+    }
+
+    /// Returns the text of the doc comments attached to this enum, if any.
+    pub fn documentation(self, db: &impl DefDatabase) -> Option<String> {
+        self.source(db).ast.doc_comment_text()
+    }
+
+    pub fn data(self, db: &impl DefDatabase) -> Arc<EnumData> {
+        db.enum_data(self)
+    }
+
+    /// Returns the integer type that represents values of this enum.
+    pub fn repr(self, db: &impl HirDatabase) -> IntTy {
+        self.data(db).repr
+    }
+
+    /// Returns the variants of this enum in declaration order.
+    pub fn variants(self, db: &impl HirDatabase) -> Vec<EnumVariant> {
+        self.data(db)
+            .variants
+            .iter()
+            .map(|(id, _)| EnumVariant { parent: self, id })
+            .collect()
+    }
+
+    /// Returns the variant called `name`, if it exists.
+    pub fn variant(self, db: &impl DefDatabase, name: &Name) -> Option<EnumVariant> {
+        self.data(db)
+            .variants
+            .iter()
+            .find(|(_, data)| data.name == *name)
+            .map(|(id, _)| EnumVariant { parent: self, id })
+    }
+
+    pub fn ty(self, db: &impl HirDatabase) -> Ty {
+        db.type_for_def(self.into(), Namespace::Types)
+    }
+
+    pub fn diagnostics(self, db: &impl HirDatabase, sink: &mut DiagnosticSink) {
+        let data = self.data(db);
+        let src = self.source(db);
+        let file = src.file_id;
+        let variants: Vec<ast::EnumVariant> = src
+            .ast
+            .enum_variant_list()
+            .into_iter()
+            .flat_map(|list| list.variants())
+            .collect();
+        let variant_ptr = |id: LocalEnumVariantId| {
+            let index: u32 = id.into_raw().into();
+            SyntaxNodePtr::new(variants[index as usize].syntax())
+        };
+        let discriminant_ptr = |id: LocalEnumVariantId| {
+            let index: u32 = id.into_raw().into();
+            match variants[index as usize].expr() {
+                Some(expr) => SyntaxNodePtr::new(expr.syntax()),
+                None => variant_ptr(id),
+            }
+        };
+        for diag in data.diagnostics.iter() {
+            match diag {
+                // The enum data is stored without source locations, so the discriminant is
+                // evaluated again to find the offending expression
+                EnumDiagnostic::InvalidDiscriminant(id) => {
+                    let index: u32 = id.into_raw().into();
+                    if let Some(Err(error)) = variants[index as usize]
+                        .expr()
+                        .map(|expr| eval_int_syntax(&expr))
+                    {
+                        let invalid_discriminant = |expr| InvalidEnumDiscriminant { file, expr };
+                        add_int_const_diagnostic(file, error, invalid_discriminant, sink);
+                    }
+                }
+                EnumDiagnostic::DiscriminantOverflow(id) => sink.push(EnumDiscriminantOverflow {
+                    file,
+                    variant: discriminant_ptr(*id),
+                    repr: data.repr,
+                }),
+                EnumDiagnostic::DuplicateDiscriminant { variant, first } => {
+                    sink.push(DuplicateEnumDiscriminant {
+                        file,
+                        variant: variant_ptr(*variant),
+                        first_variant: variant_ptr(*first),
+                        discriminant: data.variants[*variant].discriminant,
+                    })
+                }
+            }
+        }
+    }
+}
+
+impl EnumVariant {
+    pub fn name(self, db: &impl HirDatabase) -> Name {
+        self.parent.data(db).variants[self.id].name.clone()
+    }
+
+    /// Returns the value of the variant.
+    pub fn discriminant(self, db: &impl DefDatabase) -> i128 {
+        self.parent.data(db).variants[self.id].discriminant
+    }
+
+    /// Returns the enum that declares this variant.
+    pub fn parent_enum(self) -> Enum {
+        self.parent
+    }
+
+    /// Returns the index of the variant in its enum's declaration order.
+    pub fn index(self) -> u32 {
+        self.id.into_raw().into()
+    }
+}
+
+use crate::code_model::diagnostics::ModuleDefinitionDiagnostic;
+use crate::name::*;
+
+impl BuiltinType {
+    #[rustfmt::skip]
+    pub(crate) const ALL: &'static [(Name, BuiltinType)] = &[
+        (FLOAT, BuiltinType::Float(FloatTy::float())),
+        (F32, BuiltinType::Float(FloatTy::f32())),
+        (F64, BuiltinType::Float(FloatTy::f64())),
+        (INT, BuiltinType::Int(IntTy::int())),
+        (I8, BuiltinType::Int(IntTy::i8())),
+        (I16, BuiltinType::Int(IntTy::i16())),
+        (I32, BuiltinType::Int(IntTy::i32())),
+        (I64, BuiltinType::Int(IntTy::i64())),
+        (U8, BuiltinType::Int(IntTy::u8())),
+        (U16, BuiltinType::Int(IntTy::u16())),
+        (U32, BuiltinType::Int(IntTy::u32())),
+        (U64, BuiltinType::Int(IntTy::u64())),
+        (BOOLEAN, BuiltinType::Boolean),
+        (STR, BuiltinType::Str),
+    ];
+}
+
+impl BuiltinFunction {
+    pub(crate) const ALL: &'static [(Name, BuiltinFunction)] = &[
+        (SQRT, BuiltinFunction::Sqrt),
+        (ABS, BuiltinFunction::Abs),
+        (SIN, BuiltinFunction::Sin),
+        (COS, BuiltinFunction::Cos),
+        (FLOOR, BuiltinFunction::Floor),
+        (MIN, BuiltinFunction::Min),
+        (MAX, BuiltinFunction::Max),
+        (WRAPPING_ADD, BuiltinFunction::WrappingAdd),
+        (WRAPPING_SUB, BuiltinFunction::WrappingSub),
+        (WRAPPING_MUL, BuiltinFunction::WrappingMul),
+        (SATURATING_ADD, BuiltinFunction::SaturatingAdd),
+        (SATURATING_SUB, BuiltinFunction::SaturatingSub),
+        (ASSERT, BuiltinFunction::Assert),
+        (ASSERT_EQ, BuiltinFunction::AssertEq),
+    ];
+
+    pub fn name(self) -> Name {
+        match self {
+            BuiltinFunction::Sqrt => SQRT,
+            BuiltinFunction::Abs => ABS,
+            BuiltinFunction::Sin => SIN,
+            BuiltinFunction::Cos => COS,
+            BuiltinFunction::Floor => FLOOR,
+            BuiltinFunction::Min => MIN,
+            BuiltinFunction::Max => MAX,
+            BuiltinFunction::WrappingAdd => WRAPPING_ADD,
+            BuiltinFunction::WrappingSub => WRAPPING_SUB,
+            BuiltinFunction::WrappingMul => WRAPPING_MUL,
+            BuiltinFunction::SaturatingAdd => SATURATING_ADD,
+            BuiltinFunction::SaturatingSub => SATURATING_SUB,
+            BuiltinFunction::Assert => ASSERT,
+            BuiltinFunction::AssertEq => ASSERT_EQ,
+        }
+    }
+
+    /// Returns the number of arguments of the function.
+    pub fn arity(self) -> usize {
+        match self {
+            BuiltinFunction::Sqrt
+            | BuiltinFunction::Abs
+            | BuiltinFunction::Sin
+            | BuiltinFunction::Cos
+            | BuiltinFunction::Floor
+            | BuiltinFunction::Assert => 1,
+            _ => 2,
+        }
+    }
+
+    /// Returns true if the function is an assertion, which traps if it fails and doesn't return a
+    /// value.
+    pub fn is_assertion(self) -> bool {
+        match self {
+            BuiltinFunction::Assert | BuiltinFunction::AssertEq => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the function is defined for integers.
+    pub fn accepts_ints(self) -> bool {
+        match self {
+            BuiltinFunction::Sqrt
+            | BuiltinFunction::Sin
+            | BuiltinFunction::Cos
+            | BuiltinFunction::Floor
+            | BuiltinFunction::Assert => false,
+            _ => true,
+        }
+    }
+
+    /// Returns true if the function is defined for floats.
+    pub fn accepts_floats(self) -> bool {
+        match self {
+            BuiltinFunction::WrappingAdd
+            | BuiltinFunction::WrappingSub
+            | BuiltinFunction::WrappingMul
+            | BuiltinFunction::SaturatingAdd
+            | BuiltinFunction::SaturatingSub
+            | BuiltinFunction::Assert => false,
+            _ => true,
+        }
+    }
+
+    /// Returns true if the function is defined for bools.
+    pub fn accepts_bools(self) -> bool {
+        self.is_assertion()
+    }
+}
+
+pub(crate) mod diagnostics {
+    use super::Module;
+    use crate::diagnostics::{DiagnosticSink, DuplicateDefinition};
+    use crate::name_resolution::Namespace;
+    use crate::raw::{DefId, DefKind};
+    use crate::{DefDatabase, Name};
+    use mun_syntax::{AstNode, SyntaxNodePtr};
+
+    #[derive(Debug, PartialEq, Eq, Clone, Hash)]
+    pub(super) enum ModuleDefinitionDiagnostic {
+        DuplicateName {
+            name: Name,
+            namespace: Namespace,
+            definition: DefId,
+            first_definition: DefId,
+        },
+    }
+
+    /// Returns a pointer to the syntax node of the definition `kind` in the module `owner`.
+    pub(crate) fn syntax_ptr_from_def(
+        db: &impl DefDatabase,
+        owner: Module,
+        kind: DefKind,
+    ) -> SyntaxNodePtr {
+        match kind {
+            DefKind::Function(id) => {
+                SyntaxNodePtr::new(id.with_file_id(owner.file_id).to_node(db).syntax())
+            }
+            DefKind::Struct(id) => {
+                SyntaxNodePtr::new(id.with_file_id(owner.file_id).to_node(db).syntax())
+            }
+            DefKind::Enum(id) => {
+                SyntaxNodePtr::new(id.with_file_id(owner.file_id).to_node(db).syntax())
+            }
+            DefKind::Const(id) => {
+                SyntaxNodePtr::new(id.with_file_id(owner.file_id).to_node(db).syntax())
+            }
+            DefKind::Static(id) => {
+                SyntaxNodePtr::new(id.with_file_id(owner.file_id).to_node(db).syntax())
+            }
+            DefKind::TypeAlias(id) => {
                 SyntaxNodePtr::new(id.with_file_id(owner.file_id).to_node(db).syntax())
             }
         }
@@ -321,6 +1980,7 @@ mod diagnostics {
             match self {
                 ModuleDefinitionDiagnostic::DuplicateName {
                     name,
+                    namespace,
                     definition,
                     first_definition,
                 } => {
@@ -328,6 +1988,7 @@ mod diagnostics {
                     sink.push(DuplicateDefinition {
                         file: owner.file_id,
                         name: name.to_string(),
+                        namespace: Some(*namespace),
                         definition: syntax_ptr_from_def(db, owner, raw_items[*definition].kind),
                         first_definition: syntax_ptr_from_def(
                             db,
@@ -1,4 +1,4 @@
-use crate::code_model::Function;
+use crate::code_model::{Const, Enum, Function, ImplBlock, Static, Struct, TypeAlias};
 use crate::ids::AstItemDef;
 use crate::{DefDatabase, FileId, SourceDatabase};
 use mun_syntax::{ast, AstNode, SyntaxNode};
@@ -21,6 +21,48 @@ impl HasSource for Function {
     }
 }
 
+impl HasSource for Struct {
+    type Ast = ast::StructDef;
+    fn source(self, db: &impl DefDatabase) -> Source<ast::StructDef> {
+        self.id.source(db)
+    }
+}
+
+impl HasSource for Enum {
+    type Ast = ast::EnumDef;
+    fn source(self, db: &impl DefDatabase) -> Source<ast::EnumDef> {
+        self.id.source(db)
+    }
+}
+
+impl HasSource for Const {
+    type Ast = ast::ConstDef;
+    fn source(self, db: &impl DefDatabase) -> Source<ast::ConstDef> {
+        self.id.source(db)
+    }
+}
+
+impl HasSource for Static {
+    type Ast = ast::StaticDef;
+    fn source(self, db: &impl DefDatabase) -> Source<ast::StaticDef> {
+        self.id.source(db)
+    }
+}
+
+impl HasSource for TypeAlias {
+    type Ast = ast::TypeAliasDef;
+    fn source(self, db: &impl DefDatabase) -> Source<ast::TypeAliasDef> {
+        self.id.source(db)
+    }
+}
+
+impl HasSource for ImplBlock {
+    type Ast = ast::ImplBlock;
+    fn source(self, db: &impl DefDatabase) -> Source<ast::ImplBlock> {
+        self.id.source(db)
+    }
+}
+
 impl<T> Source<T> {
     pub(crate) fn map<F: FnOnce(T) -> U, U>(self, f: F) -> Source<U> {
         Source {
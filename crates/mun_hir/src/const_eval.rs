@@ -0,0 +1,521 @@
+//! Evaluation of constant expressions at compile time. This is used to compute the values of
+//! `const` items, the initial values of `static` items, and to fold expressions whose value is
+//! known at compile time. The integer constants outside of bodies, i.e. the lengths of array types
+//! and the discriminants of enum variants, are evaluated from their syntax with the same
+//! arithmetic.
+
+use crate::{
+    code_model::{Const, ModuleDef, Static},
+    expr::{resolver_for_expr, ArithOp, BinaryOp, Body, CmpOp, Expr, ExprId, Literal, LogicOp},
+    expr::{Ordering, UnaryOp},
+    resolve::Resolution,
+    ty::InferenceResult,
+    ApplicationTy, FloatBitness, FloatTy, HirDatabase, IntTy, Signedness, Ty, TypeCtor,
+};
+use mun_syntax::{ast, ast::IntLiteralError, ast::Radix, AstNode, SyntaxNodePtr};
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+#[cfg(test)]
+mod tests;
+
+/// The value of a constant expression.
+#[derive(Debug, Clone, Copy)]
+pub enum ConstValue {
+    Int(i128),
+    Float(f64),
+    Bool(bool),
+}
+
+impl PartialEq for ConstValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ConstValue::Int(a), ConstValue::Int(b)) => a == b,
+            // Floats are compared bitwise, so a value is always equal to itself
+            (ConstValue::Float(a), ConstValue::Float(b)) => a.to_bits() == b.to_bits(),
+            (ConstValue::Bool(a), ConstValue::Bool(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ConstValue {}
+
+/// The reason why a constant expression could not be evaluated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstEvalError {
+    /// The constant depends on its own value.
+    Cycle,
+    /// The expression cannot be evaluated at compile time, e.g. a function call.
+    NotConst(ExprId),
+    /// The result of the expression does not fit in its type.
+    Overflow(ExprId),
+    /// The expression divides by zero.
+    DivisionByZero(ExprId),
+    /// The expression contains an error that is reported elsewhere, e.g. a type error or an error
+    /// in another constant.
+    Invalid,
+}
+
+/// The reason why an integer constant outside of a body, e.g. the length of an array type, could
+/// not be evaluated. Every error except `Missing` points at the offending sub-expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum IntConstError {
+    /// The expression cannot be evaluated at compile time, e.g. a function call or a path.
+    NotConst(SyntaxNodePtr),
+    /// The value of the expression is not valid where it is used, e.g. a float, a comparison or a
+    /// negative array length.
+    InvalidValue(SyntaxNodePtr),
+    /// The result of the expression does not fit in a 128-bit integer, or in the type where it is
+    /// used.
+    Overflow(SyntaxNodePtr),
+    /// The expression divides by zero.
+    DivisionByZero(SyntaxNodePtr),
+    /// A literal, or a negated literal, does not fit in the type of its suffix, e.g. `256u8`. The
+    /// radix is that of the literal.
+    LiteralOutOfRange(SyntaxNodePtr, IntTy, Radix),
+    /// The expression is missing or malformed because of a syntax error, which is reported by the
+    /// parser, e.g. a literal with invalid digits.
+    Missing,
+}
+
+/// An integer operation of which the result is not defined.
+enum IntOpError {
+    Overflow,
+    DivisionByZero,
+}
+
+pub(crate) fn const_eval_query(
+    db: &impl HirDatabase,
+    c: Const,
+) -> Result<ConstValue, ConstEvalError> {
+    ConstEvaluator {
+        db,
+        stack: Vec::new(),
+    }
+    .eval_const(c)
+}
+
+pub(crate) fn static_eval_query(
+    db: &impl HirDatabase,
+    s: Static,
+) -> Result<ConstValue, ConstEvalError> {
+    let body = s.body(db);
+    let infer = s.infer(db);
+    if !infer.diagnostics.is_empty() {
+        return Err(ConstEvalError::Invalid);
+    }
+
+    ConstEvaluator {
+        db,
+        stack: Vec::new(),
+    }
+    .eval_expr(&body, &infer, body.body_expr())
+}
+
+/// Evaluates `expr` in `body` if its value is known at compile time, e.g. `60 * 60`. Returns
+/// `None` if the expression cannot be evaluated.
+pub fn fold_expr(
+    db: &impl HirDatabase,
+    body: &Arc<Body>,
+    infer: &InferenceResult,
+    expr: ExprId,
+) -> Option<ConstValue> {
+    ConstEvaluator {
+        db,
+        stack: Vec::new(),
+    }
+    .eval_expr(body, infer, expr)
+    .ok()
+}
+
+struct ConstEvaluator<'a, D> {
+    db: &'a D,
+    /// The constants that are currently being evaluated, used to detect cycles.
+    stack: Vec<Const>,
+}
+
+impl<'a, D: HirDatabase> ConstEvaluator<'a, D> {
+    fn eval_const(&mut self, c: Const) -> Result<ConstValue, ConstEvalError> {
+        // Only the constant that started the evaluation reports the cycle. Other constants on the
+        // stack that are part of the cycle report it when they are evaluated themselves.
+        if let Some(idx) = self.stack.iter().position(|it| *it == c) {
+            return Err(if idx == 0 {
+                ConstEvalError::Cycle
+            } else {
+                ConstEvalError::Invalid
+            });
+        }
+
+        let body = c.body(self.db);
+        let infer = c.infer(self.db);
+        if !infer.diagnostics.is_empty() {
+            return Err(ConstEvalError::Invalid);
+        }
+
+        self.stack.push(c);
+        let value = self.eval_expr(&body, &infer, body.body_expr());
+        self.stack.pop();
+        value
+    }
+
+    fn eval_expr(
+        &mut self,
+        body: &Arc<Body>,
+        infer: &InferenceResult,
+        expr: ExprId,
+    ) -> Result<ConstValue, ConstEvalError> {
+        match &body[expr] {
+            Expr::Literal(Literal::Bool(value)) => Ok(ConstValue::Bool(*value)),
+            // An integer literal is a floating point value if its type annotation is a float
+            Expr::Literal(Literal::Int(lit)) => match &infer[expr] {
+                Ty::Apply(ApplicationTy {
+                    ctor: TypeCtor::Float(_),
+                    ..
+                }) => self.float_result(infer, expr, lit.value as f64),
+                _ => Ok(ConstValue::Int(lit.value as i128)),
+            },
+            Expr::Literal(Literal::Float(lit)) => self.float_result(infer, expr, lit.value),
+            Expr::UnaryOp { expr: operand, op } => {
+                match (op, self.eval_expr(body, infer, *operand)?) {
+                    (UnaryOp::Neg, ConstValue::Int(value)) => self.int_result(infer, expr, -value),
+                    (UnaryOp::Neg, ConstValue::Float(value)) => {
+                        self.float_result(infer, expr, -value)
+                    }
+                    (UnaryOp::Not, ConstValue::Bool(value)) => Ok(ConstValue::Bool(!value)),
+                    _ => Err(ConstEvalError::Invalid),
+                }
+            }
+            Expr::BinaryOp {
+                lhs,
+                rhs,
+                op: Some(op),
+            } => match op {
+                BinaryOp::LogicOp(op) => {
+                    let lhs = self.eval_bool(body, infer, *lhs)?;
+                    match (op, lhs) {
+                        (LogicOp::And, false) => Ok(ConstValue::Bool(false)),
+                        (LogicOp::Or, true) => Ok(ConstValue::Bool(true)),
+                        _ => self.eval_bool(body, infer, *rhs).map(ConstValue::Bool),
+                    }
+                }
+                BinaryOp::ArithOp(op) => {
+                    match (
+                        self.eval_expr(body, infer, *lhs)?,
+                        self.eval_expr(body, infer, *rhs)?,
+                    ) {
+                        (ConstValue::Int(lhs), ConstValue::Int(rhs)) => {
+                            self.eval_int_arith(infer, expr, *op, lhs, rhs)
+                        }
+                        (ConstValue::Float(lhs), ConstValue::Float(rhs)) => {
+                            let value = match op {
+                                ArithOp::Add => lhs + rhs,
+                                ArithOp::Subtract => lhs - rhs,
+                                ArithOp::Multiply => lhs * rhs,
+                                ArithOp::Divide => lhs / rhs,
+                                ArithOp::Remainder => lhs % rhs,
+                                // Shifting a float is a type error
+                                ArithOp::LeftShift | ArithOp::RightShift => {
+                                    return Err(ConstEvalError::Invalid)
+                                }
+                            };
+                            self.float_result(infer, expr, value)
+                        }
+                        _ => Err(ConstEvalError::Invalid),
+                    }
+                }
+                BinaryOp::CmpOp(op) => {
+                    let ordering = match (
+                        self.eval_expr(body, infer, *lhs)?,
+                        self.eval_expr(body, infer, *rhs)?,
+                    ) {
+                        (ConstValue::Int(lhs), ConstValue::Int(rhs)) => lhs.partial_cmp(&rhs),
+                        (ConstValue::Float(lhs), ConstValue::Float(rhs)) => lhs.partial_cmp(&rhs),
+                        (ConstValue::Bool(lhs), ConstValue::Bool(rhs)) => lhs.partial_cmp(&rhs),
+                        _ => return Err(ConstEvalError::Invalid),
+                    };
+                    Ok(ConstValue::Bool(compare(*op, ordering)))
+                }
+                BinaryOp::Assignment { .. } => Err(ConstEvalError::NotConst(expr)),
+            },
+            Expr::Path(path) => {
+                let resolver = resolver_for_expr(body.clone(), self.db, expr);
+                match resolver
+                    .resolve_path_without_assoc_items(self.db, path)
+                    .take_values()
+                {
+                    Some(Resolution::Def(ModuleDef::Const(c))) => {
+                        if self.stack.is_empty() {
+                            self.db.const_eval(c)
+                        } else {
+                            // Errors in other constants are reported by those constants
+                            self.eval_const(c).map_err(|err| match err {
+                                ConstEvalError::Cycle => ConstEvalError::Cycle,
+                                _ => ConstEvalError::Invalid,
+                            })
+                        }
+                    }
+                    Some(Resolution::Def(ModuleDef::EnumVariant(v))) => {
+                        Ok(ConstValue::Int(v.discriminant(self.db)))
+                    }
+                    Some(_) => Err(ConstEvalError::NotConst(expr)),
+                    None => Err(ConstEvalError::Invalid),
+                }
+            }
+            Expr::Cast { expr: operand, .. } => match self.eval_expr(body, infer, *operand)? {
+                ConstValue::Int(value) => self.cast_result(infer, expr, value),
+                _ => Err(ConstEvalError::Invalid),
+            },
+            Expr::Missing | Expr::BinaryOp { op: None, .. } => Err(ConstEvalError::Invalid),
+            _ => Err(ConstEvalError::NotConst(expr)),
+        }
+    }
+
+    fn eval_bool(
+        &mut self,
+        body: &Arc<Body>,
+        infer: &InferenceResult,
+        expr: ExprId,
+    ) -> Result<bool, ConstEvalError> {
+        match self.eval_expr(body, infer, expr)? {
+            ConstValue::Bool(value) => Ok(value),
+            _ => Err(ConstEvalError::Invalid),
+        }
+    }
+
+    /// Evaluates an arithmetic operation on integers. A shift by at least the number of bits of
+    /// the type overflows, but the bits that are shifted out of a value are discarded.
+    fn eval_int_arith(
+        &self,
+        infer: &InferenceResult,
+        expr: ExprId,
+        op: ArithOp,
+        lhs: i128,
+        rhs: i128,
+    ) -> Result<ConstValue, ConstEvalError> {
+        if let ArithOp::LeftShift | ArithOp::RightShift = op {
+            let int_ty = self.int_ty(infer, expr)?;
+            if rhs < 0 || rhs >= i128::from(int_ty.bitness.bits()) {
+                return Err(ConstEvalError::Overflow(expr));
+            }
+            let value = match op {
+                ArithOp::LeftShift => truncate(int_ty, lhs << rhs),
+                _ => lhs >> rhs,
+            };
+            return Ok(ConstValue::Int(value));
+        }
+
+        match checked_int_op(op, lhs, rhs) {
+            Ok(value) => self.int_result(infer, expr, value),
+            Err(IntOpError::Overflow) => Err(ConstEvalError::Overflow(expr)),
+            Err(IntOpError::DivisionByZero) => Err(ConstEvalError::DivisionByZero(expr)),
+        }
+    }
+
+    /// Returns the integer type of `expr`.
+    fn int_ty(&self, infer: &InferenceResult, expr: ExprId) -> Result<IntTy, ConstEvalError> {
+        match &infer[expr] {
+            Ty::Apply(ApplicationTy {
+                ctor: TypeCtor::Int(int_ty),
+                ..
+            }) => Ok(*int_ty),
+            _ => Err(ConstEvalError::Invalid),
+        }
+    }
+
+    /// Verifies that `value` fits in the integer type of `expr`.
+    fn int_result(
+        &self,
+        infer: &InferenceResult,
+        expr: ExprId,
+        value: i128,
+    ) -> Result<ConstValue, ConstEvalError> {
+        let int_ty = self.int_ty(infer, expr)?;
+        if value < int_ty.min_value() || value > int_ty.max_value() as i128 {
+            Err(ConstEvalError::Overflow(expr))
+        } else {
+            Ok(ConstValue::Int(value))
+        }
+    }
+
+    /// Truncates `value` to the integer type of the cast `expr`, e.g. `300 as u8` is `44`.
+    fn cast_result(
+        &self,
+        infer: &InferenceResult,
+        expr: ExprId,
+        value: i128,
+    ) -> Result<ConstValue, ConstEvalError> {
+        let int_ty = self.int_ty(infer, expr)?;
+        Ok(ConstValue::Int(truncate(int_ty, value)))
+    }
+
+    /// Rounds `value` to the precision of the float type of `expr`.
+    fn float_result(
+        &self,
+        infer: &InferenceResult,
+        expr: ExprId,
+        value: f64,
+    ) -> Result<ConstValue, ConstEvalError> {
+        match &infer[expr] {
+            Ty::Apply(ApplicationTy {
+                ctor: TypeCtor::Float(float_ty),
+                ..
+            }) => Ok(ConstValue::Float(match float_ty.bitness {
+                FloatBitness::X32 => f64::from(value as f32),
+                FloatBitness::X64 => value,
+            })),
+            _ => Err(ConstEvalError::Invalid),
+        }
+    }
+}
+
+/// Evaluates the integer value of `expr`, which is not part of a body, e.g. the length of an array
+/// type or the discriminant of an enum variant. These are evaluated from their syntax, because
+/// they are needed to lower types before any expression is inferred. The expression may consist of
+/// integer literals, negation and arithmetic. Intermediate values are 128-bit integers; whether the
+/// result fits is checked where it is used.
+pub(crate) fn eval_int_syntax(expr: &ast::Expr) -> Result<i128, IntConstError> {
+    let ptr = SyntaxNodePtr::new(expr.syntax());
+    match expr.kind() {
+        ast::ExprKind::Literal(lit) => eval_int_literal(&lit, ptr, false),
+        ast::ExprKind::ParenExpr(e) => eval_int_syntax_opt(e.expr()),
+        ast::ExprKind::PrefixExpr(e) => {
+            // A negated literal is checked as a whole, like in a body, where parentheses are
+            // transparent
+            let mut operand = e.expr().map(|expr| expr.kind());
+            while let Some(ast::ExprKind::ParenExpr(paren)) = operand {
+                operand = paren.expr().map(|expr| expr.kind());
+            }
+            if let (Some(ast::PrefixOp::Neg), Some(ast::ExprKind::Literal(lit))) =
+                (e.op_kind(), operand)
+            {
+                return eval_int_literal(&lit, ptr, true);
+            }
+            let value = eval_int_syntax_opt(e.expr())?;
+            match e.op_kind() {
+                Some(ast::PrefixOp::Neg) => value.checked_neg().ok_or(IntConstError::Overflow(ptr)),
+                Some(ast::PrefixOp::Not) => Err(IntConstError::InvalidValue(ptr)),
+                None => Err(IntConstError::Missing),
+            }
+        }
+        ast::ExprKind::BinExpr(e) => {
+            let lhs = eval_int_syntax_opt(e.lhs())?;
+            let rhs = eval_int_syntax_opt(e.rhs())?;
+            let op = match e.op_kind() {
+                Some(ast::BinOp::Add) => ArithOp::Add,
+                Some(ast::BinOp::Subtract) => ArithOp::Subtract,
+                Some(ast::BinOp::Multiply) => ArithOp::Multiply,
+                Some(ast::BinOp::Divide) => ArithOp::Divide,
+                Some(ast::BinOp::Remainder) => ArithOp::Remainder,
+                Some(ast::BinOp::LeftShift) => ArithOp::LeftShift,
+                Some(ast::BinOp::RightShift) => ArithOp::RightShift,
+                Some(ast::BinOp::Assign)
+                | Some(ast::BinOp::AddAssign)
+                | Some(ast::BinOp::SubtractAssign)
+                | Some(ast::BinOp::DivideAssign)
+                | Some(ast::BinOp::MultiplyAssign)
+                | Some(ast::BinOp::RemainderAssign) => return Err(IntConstError::NotConst(ptr)),
+                // Comparisons and logic operators result in booleans
+                Some(_) => return Err(IntConstError::InvalidValue(ptr)),
+                None => return Err(IntConstError::Missing),
+            };
+            checked_int_op(op, lhs, rhs).map_err(|err| match err {
+                IntOpError::Overflow => IntConstError::Overflow(ptr),
+                IntOpError::DivisionByZero => IntConstError::DivisionByZero(ptr),
+            })
+        }
+        _ => Err(IntConstError::NotConst(ptr)),
+    }
+}
+
+/// Evaluates the integer literal `lit`, or its negation if `negated` is true. The value of a literal
+/// with a suffix must fit in the type of the suffix. Errors point at `ptr`.
+fn eval_int_literal(
+    lit: &ast::Literal,
+    ptr: SyntaxNodePtr,
+    negated: bool,
+) -> Result<i128, IntConstError> {
+    let suffix = match lit.kind() {
+        ast::LiteralKind::IntNumber { suffix } => suffix,
+        _ => return Err(IntConstError::InvalidValue(ptr)),
+    };
+    // A decimal integer with a floating point suffix (e.g. `1f32`) is a float. Other radixes with
+    // such a suffix are reported during validation.
+    let float_ty = suffix.as_ref().and_then(|s| FloatTy::from_suffix(s));
+    if float_ty.is_some() {
+        return match lit.radix() {
+            Radix::Decimal => Err(IntConstError::InvalidValue(ptr)),
+            _ => Err(IntConstError::Missing),
+        };
+    }
+    let value = match lit.int_value() {
+        Ok(value) => i128::try_from(value).map_err(|_| IntConstError::Overflow(ptr))?,
+        Err(IntLiteralError::Overflow) => return Err(IntConstError::Overflow(ptr)),
+        // Invalid digits are reported during validation
+        Err(_) => return Err(IntConstError::Missing),
+    };
+    let value = if negated { -value } else { value };
+    match suffix.and_then(|s| IntTy::from_suffix(&s)) {
+        Some(int_ty) if value < int_ty.min_value() || value > int_ty.max_value() as i128 => {
+            Err(IntConstError::LiteralOutOfRange(ptr, int_ty, lit.radix()))
+        }
+        _ => Ok(value),
+    }
+}
+
+fn eval_int_syntax_opt(expr: Option<ast::Expr>) -> Result<i128, IntConstError> {
+    match expr {
+        Some(expr) => eval_int_syntax(&expr),
+        None => Err(IntConstError::Missing),
+    }
+}
+
+/// Computes the arithmetic operation `op` on 128-bit integers. The remainder of a division by
+/// zero is the dividend, similar to the generated code. Shifts by at least 128 bits, and left
+/// shifts that discard bits of the value, overflow.
+fn checked_int_op(op: ArithOp, lhs: i128, rhs: i128) -> Result<i128, IntOpError> {
+    let value = match op {
+        ArithOp::Add => lhs.checked_add(rhs),
+        ArithOp::Subtract => lhs.checked_sub(rhs),
+        ArithOp::Multiply => lhs.checked_mul(rhs),
+        ArithOp::Divide if rhs == 0 => return Err(IntOpError::DivisionByZero),
+        ArithOp::Divide => lhs.checked_div(rhs),
+        ArithOp::Remainder if rhs == 0 => Some(lhs),
+        ArithOp::Remainder => lhs.checked_rem(rhs),
+        ArithOp::LeftShift | ArithOp::RightShift => {
+            let amount = match u32::try_from(rhs) {
+                Ok(amount) if amount < 128 => amount,
+                _ => return Err(IntOpError::Overflow),
+            };
+            if op == ArithOp::LeftShift {
+                Some(lhs << amount).filter(|value| value >> amount == lhs)
+            } else {
+                Some(lhs >> amount)
+            }
+        }
+    };
+    value.ok_or(IntOpError::Overflow)
+}
+
+/// Truncates `value` to the bits of `int_ty`, e.g. `300` is `44` as a `u8`.
+fn truncate(int_ty: IntTy, value: i128) -> i128 {
+    let unused_bits = 128 - int_ty.bitness.bits();
+    match int_ty.signedness {
+        Signedness::Signed => (value << unused_bits) >> unused_bits,
+        Signedness::Unsigned => ((value as u128) << unused_bits >> unused_bits) as i128,
+    }
+}
+
+/// Returns the result of the comparison `op` given the ordering of its operands, or `None` if the
+/// operands are unordered.
+fn compare(op: CmpOp, cmp: Option<std::cmp::Ordering>) -> bool {
+    use std::cmp::Ordering::{Equal, Greater, Less};
+    match op {
+        CmpOp::Eq { negated } => (cmp == Some(Equal)) != negated,
+        CmpOp::Ord { ordering, strict } => match (ordering, cmp) {
+            (Ordering::Less, Some(Less)) | (Ordering::Greater, Some(Greater)) => true,
+            (_, Some(Equal)) => !strict,
+            _ => false,
+        },
+    }
+}
@@ -0,0 +1,110 @@
+use crate::const_eval::{eval_int_syntax, IntConstError};
+use mun_syntax::{ast, AstNode, SourceFile};
+
+#[test]
+fn int_syntax_arithmetic() {
+    assert_eq!(eval("16"), Ok(16));
+    assert_eq!(eval("1_000"), Ok(1000));
+    assert_eq!(eval("4 * 4"), Ok(16));
+    assert_eq!(eval("(1 + 2) * -3"), Ok(-9));
+    assert_eq!(eval("7 / 2 + 7 % 2"), Ok(4));
+    assert_eq!(eval("7 % 0"), Ok(7));
+}
+
+#[test]
+fn int_syntax_shifts() {
+    assert_eq!(eval("1 << 4"), Ok(16));
+    assert_eq!(eval("1 << 2 + 2"), Ok(16));
+    assert_eq!(eval("256 >> 2 % 3"), Ok(64));
+    assert_eq!(eval("-16 >> 2"), Ok(-4));
+    assert_eq!(eval("1 << 128"), Err("Overflow(1 << 128)".to_string()));
+    assert_eq!(eval("1 << -1"), Err("Overflow(1 << -1)".to_string()));
+    assert_eq!(
+        eval("(1 << 127) - 1"),
+        Err("Overflow(1 << 127)".to_string())
+    );
+}
+
+#[test]
+fn int_syntax_suffixes() {
+    assert_eq!(eval("255u8"), Ok(255));
+    assert_eq!(eval("-128i8"), Ok(-128));
+    assert_eq!(eval("-(128i8)"), Ok(-128));
+    assert_eq!(eval("18446744073709551615u64"), Ok(18446744073709551615));
+    assert_eq!(eval("2 * 200u8"), Ok(400));
+    assert_eq!(eval("256u8"), Err("LiteralOutOfRange(256u8)".to_string()));
+    assert_eq!(eval("128i8"), Err("LiteralOutOfRange(128i8)".to_string()));
+    assert_eq!(eval("-129i8"), Err("LiteralOutOfRange(-129i8)".to_string()));
+    assert_eq!(
+        eval("1 + -1u32"),
+        Err("LiteralOutOfRange(-1u32)".to_string())
+    );
+}
+
+#[test]
+fn int_syntax_radixes() {
+    assert_eq!(eval("0xFF"), Ok(255));
+    assert_eq!(eval("0b1010_0001"), Ok(161));
+    assert_eq!(eval("0o755"), Ok(493));
+    assert_eq!(eval("0xFFu8"), Ok(255));
+    assert_eq!(eval("-0x80i8"), Ok(-128));
+    assert_eq!(
+        eval("0x1FFu8"),
+        Err("LiteralOutOfRange(0x1FFu8)".to_string())
+    );
+    assert_eq!(eval("0b102"), Err("Missing".to_string()));
+}
+
+#[test]
+fn int_syntax_errors() {
+    // Errors point at the sub-expression that causes them
+    assert_eq!(eval("2 * foo()"), Err("NotConst(foo())".to_string()));
+    assert_eq!(eval("N + 1"), Err("NotConst(N)".to_string()));
+    assert_eq!(eval("1 + 1.5"), Err("InvalidValue(1.5)".to_string()));
+    assert_eq!(eval("2f32"), Err("InvalidValue(2f32)".to_string()));
+    assert_eq!(eval("(1 < 2) * 2"), Err("InvalidValue(1 < 2)".to_string()));
+    assert_eq!(eval("!1"), Err("InvalidValue(!1)".to_string()));
+    assert_eq!(
+        eval("1 + 4 / (2 - 2)"),
+        Err("DivisionByZero(4 / (2 - 2))".to_string())
+    );
+    assert_eq!(
+        eval("(1 << 126) * 4 + 1"),
+        Err("Overflow((1 << 126) * 4)".to_string())
+    );
+    assert_eq!(
+        eval("170141183460469231731687303715884105728"),
+        Err("Overflow(170141183460469231731687303715884105728)".to_string())
+    );
+    assert_eq!(eval("1 +"), Err("Missing".to_string()));
+}
+
+/// Evaluates `len` as the length of an array type. Errors are formatted with the text of the
+/// expression that they point at, e.g. `NotConst(foo())`.
+fn eval(len: &str) -> Result<i128, String> {
+    let text = format!("type T = [int; {}];", len);
+    let file = SourceFile::parse(&text);
+    let expr = file
+        .tree()
+        .syntax()
+        .descendants()
+        .find_map(ast::ArrayType::cast)
+        .and_then(|array| array.expr())
+        .unwrap();
+    eval_int_syntax(&expr).map_err(|err| {
+        let (kind, ptr) = match err {
+            IntConstError::NotConst(ptr) => ("NotConst", ptr),
+            IntConstError::InvalidValue(ptr) => ("InvalidValue", ptr),
+            IntConstError::Overflow(ptr) => ("Overflow", ptr),
+            IntConstError::DivisionByZero(ptr) => ("DivisionByZero", ptr),
+            IntConstError::LiteralOutOfRange(ptr, _, _) => ("LiteralOutOfRange", ptr),
+            IntConstError::Missing => return "Missing".to_string(),
+        };
+        let range = ptr.range();
+        format!(
+            "{}({})",
+            kind,
+            &text[range.start().to_usize()..range.end().to_usize()]
+        )
+    })
+}
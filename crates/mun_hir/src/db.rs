@@ -4,16 +4,23 @@ use crate::input::{SourceRoot, SourceRootId};
 use crate::name_resolution::Namespace;
 use crate::ty::{FnSig, Ty, TypableDef};
 use crate::{
-    code_model::{DefWithBody, FnData, Function, ModuleData},
+    adt::{EnumData, StructData},
+    code_model::{
+        Const, ConstData, DefWithBody, Enum, FnData, Function, ImplBlock, ImplBlockData,
+        ModuleData, Static, StaticData, Struct, TypeAlias, TypeAliasData,
+    },
+    const_eval::{ConstEvalError, ConstValue},
     ids,
     line_index::LineIndex,
-    name_resolution::ModuleScope,
+    module_tree::ModuleTree,
+    name_resolution::{Imports, ModuleScope},
     source_id::ErasedFileAstId,
     ty::InferenceResult,
     AstIdMap, ExprScopes, FileId, RawItems,
 };
 use mun_syntax::{ast, Parse, SourceFile, SyntaxNode};
 pub use relative_path::RelativePathBuf;
+use rustc_hash::FxHashSet;
 use std::sync::Arc;
 
 /// Database which stores all significant input facts: source code and project model. Everything
@@ -59,9 +66,54 @@ pub trait DefDatabase: SourceDatabase {
     #[salsa::invoke(RawItems::raw_file_items_query)]
     fn raw_items(&self, file_id: FileId) -> Arc<RawItems>;
 
+    /// Returns the modules of the package in a source root. The tree only depends on the paths of
+    /// the files, so it is unchanged when the contents of a file change.
+    #[salsa::invoke(ModuleTree::module_tree_query)]
+    fn module_tree(&self, source_root: SourceRootId) -> Arc<ModuleTree>;
+
     /// Interns a function definition
     #[salsa::interned]
     fn intern_function(&self, loc: ids::ItemLoc<ast::FunctionDef>) -> ids::FunctionId;
+
+    /// Interns a struct definition
+    #[salsa::interned]
+    fn intern_struct(&self, loc: ids::ItemLoc<ast::StructDef>) -> ids::StructId;
+
+    /// Interns an enum definition
+    #[salsa::interned]
+    fn intern_enum(&self, loc: ids::ItemLoc<ast::EnumDef>) -> ids::EnumId;
+
+    /// Interns a const definition
+    #[salsa::interned]
+    fn intern_const(&self, loc: ids::ItemLoc<ast::ConstDef>) -> ids::ConstId;
+
+    /// Interns a static definition
+    #[salsa::interned]
+    fn intern_static(&self, loc: ids::ItemLoc<ast::StaticDef>) -> ids::StaticId;
+
+    /// Interns a type alias definition
+    #[salsa::interned]
+    fn intern_type_alias(&self, loc: ids::ItemLoc<ast::TypeAliasDef>) -> ids::TypeAliasId;
+
+    /// Interns an impl block
+    #[salsa::interned]
+    fn intern_impl_block(&self, loc: ids::ItemLoc<ast::ImplBlock>) -> ids::ImplBlockId;
+
+    /// Returns the fields of a struct
+    #[salsa::invoke(crate::adt::StructData::struct_data_query)]
+    fn struct_data(&self, id: Struct) -> Arc<StructData>;
+
+    /// Returns the variants of an enum
+    #[salsa::invoke(crate::adt::EnumData::enum_data_query)]
+    fn enum_data(&self, id: Enum) -> Arc<EnumData>;
+
+    /// Returns the target type and the methods of an impl block
+    #[salsa::invoke(crate::code_model::ImplBlockData::impl_block_data_query)]
+    fn impl_block_data(&self, id: ImplBlock) -> Arc<ImplBlockData>;
+
+    /// Returns the target type of a type alias
+    #[salsa::invoke(crate::code_model::TypeAliasData::type_alias_data_query)]
+    fn type_alias_data(&self, id: TypeAlias) -> Arc<TypeAliasData>;
 }
 
 #[salsa::query_group(HirDatabaseStorage)]
@@ -72,12 +124,30 @@ pub trait HirDatabase: DefDatabase {
     #[salsa::invoke(crate::name_resolution::module_scope_query)]
     fn module_scope(&self, file_id: FileId) -> Arc<ModuleScope>;
 
+    /// Returns the items that are imported by the `use` declarations of a file
+    #[salsa::invoke(crate::name_resolution::imports_query)]
+    fn imports(&self, file_id: FileId) -> Arc<Imports>;
+
     #[salsa::invoke(crate::ty::infer_query)]
     fn infer(&self, def: DefWithBody) -> Arc<InferenceResult>;
 
     #[salsa::invoke(crate::FnData::fn_data_query)]
     fn fn_data(&self, func: Function) -> Arc<FnData>;
 
+    #[salsa::invoke(crate::ConstData::const_data_query)]
+    fn const_data(&self, c: Const) -> Arc<ConstData>;
+
+    /// Evaluates the value of a constant at compile time.
+    #[salsa::invoke(crate::const_eval::const_eval_query)]
+    fn const_eval(&self, c: Const) -> Result<ConstValue, ConstEvalError>;
+
+    #[salsa::invoke(crate::StaticData::static_data_query)]
+    fn static_data(&self, s: Static) -> Arc<StaticData>;
+
+    /// Evaluates the initial value of a static at compile time.
+    #[salsa::invoke(crate::const_eval::static_eval_query)]
+    fn static_eval(&self, s: Static) -> Result<ConstValue, ConstEvalError>;
+
     #[salsa::invoke(crate::ty::fn_sig_for_fn)]
     fn fn_signature(&self, func: Function) -> FnSig;
 
@@ -96,6 +166,11 @@ pub trait HirDatabase: DefDatabase {
         &self,
         def: DefWithBody,
     ) -> (Arc<crate::expr::Body>, Arc<crate::expr::BodySourceMap>);
+
+    /// Returns the functions of the package in a source root that are used by its `export`
+    /// functions
+    #[salsa::invoke(crate::reachable::used_functions_query)]
+    fn used_functions(&self, source_root: SourceRootId) -> Arc<FxHashSet<Function>>;
 }
 
 fn parse_query(db: &impl SourceDatabase, file_id: FileId) -> Parse<SourceFile> {
@@ -1,5 +1,8 @@
-use crate::{FileId, HirDatabase, Ty};
-use mun_syntax::{ast, AstPtr, SyntaxNode, SyntaxNodePtr, TextRange};
+use crate::lint::Lint;
+use crate::{FileId, FloatTy, HirDatabase, IntTy, Name, Namespace, Ty};
+use mun_syntax::{
+    ast, ast::Radix, AstPtr, SyntaxKind, SyntaxNode, SyntaxNodePtr, TextRange, TextUnit,
+};
 use std::{any::Any, fmt};
 
 /// Diagnostic defines hir API for errors and warnings.
@@ -12,12 +15,20 @@ use std::{any::Any, fmt};
 /// an `enum`), which are safe to store in salsa but do not include source locations. Such internal
 /// diagnostics are transformed into an instance of `Diagnostic` on demand.
 pub trait Diagnostic: Any + Send + Sync + fmt::Debug + 'static {
+    /// A stable code that identifies the kind of diagnostic, e.g. `E0005`. The codes of errors
+    /// start with an `E`, those of warnings with a `W` and those of notes with an `N`.
+    fn code(&self) -> &'static str;
     fn message(&self) -> String;
     fn file(&self) -> FileId;
     fn syntax_node_ptr(&self) -> SyntaxNodePtr;
     fn highlight_range(&self) -> TextRange {
         self.syntax_node_ptr().range()
     }
+    /// The lint that the diagnostic is reported for, if it is a warning of which the level can be
+    /// configured. See `lint::lint_level`.
+    fn lint(&self) -> Option<Lint> {
+        None
+    }
     fn as_any(&self) -> &(dyn Any + Send + 'static);
 }
 
@@ -64,7 +75,7 @@ impl<'a> DiagnosticSink<'a> {
         self
     }
 
-    pub(crate) fn push(&mut self, d: impl Diagnostic) {
+    pub fn push(&mut self, d: impl Diagnostic) {
         let d: &dyn Diagnostic = &d;
         for cb in self.callbacks.iter_mut() {
             match cb(d) {
@@ -83,6 +94,10 @@ pub struct UnresolvedValue {
 }
 
 impl Diagnostic for UnresolvedValue {
+    fn code(&self) -> &'static str {
+        "E0001"
+    }
+
     fn message(&self) -> String {
         "undefined value".to_string()
     }
@@ -107,6 +122,10 @@ pub struct UnresolvedType {
 }
 
 impl Diagnostic for UnresolvedType {
+    fn code(&self) -> &'static str {
+        "E0002"
+    }
+
     fn message(&self) -> String {
         "undefined type".to_string()
     }
@@ -132,6 +151,10 @@ pub struct ExpectedFunction {
 }
 
 impl Diagnostic for ExpectedFunction {
+    fn code(&self) -> &'static str {
+        "E0003"
+    }
+
     fn message(&self) -> String {
         "expected function type".to_string()
     }
@@ -158,6 +181,10 @@ pub struct ParameterCountMismatch {
 }
 
 impl Diagnostic for ParameterCountMismatch {
+    fn code(&self) -> &'static str {
+        "E0004"
+    }
+
     fn message(&self) -> String {
         format!(
             "this function takes {} parameters but {} parameters was supplied",
@@ -184,9 +211,25 @@ pub struct MismatchedType {
     pub expr: SyntaxNodePtr,
     pub expected: Ty,
     pub found: Ty,
+    /// The type annotation that imposed the expected type, if it is known
+    pub expected_source: Option<ExpectedTypeSource>,
+}
+
+/// The type annotation that imposed the type an expression is expected to have, e.g. the return
+/// type of a function or the type of a parameter of a called function.
+#[derive(Debug)]
+pub struct ExpectedTypeSource {
+    pub file: FileId,
+    pub type_ref: AstPtr<ast::TypeRef>,
+    /// Whether the annotation is the type of a parameter of a called function
+    pub is_param: bool,
 }
 
 impl Diagnostic for MismatchedType {
+    fn code(&self) -> &'static str {
+        "E0005"
+    }
+
     fn message(&self) -> String {
         "mismatched type".to_string()
     }
@@ -204,6 +247,39 @@ impl Diagnostic for MismatchedType {
     }
 }
 
+/// A use of the value of an unsuffixed literal expects the type `expected`, but an earlier use
+/// already inferred the type `found` for the literal, e.g. `let x = 0; take_u8(x); take_i32(x);`.
+#[derive(Debug)]
+pub struct ConflictingLiteralTypes {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+    pub literal: SyntaxNodePtr,
+    pub expected: Ty,
+    pub found: Ty,
+}
+
+impl Diagnostic for ConflictingLiteralTypes {
+    fn code(&self) -> &'static str {
+        "E0055"
+    }
+
+    fn message(&self) -> String {
+        "conflicting types inferred for literal".to_string()
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
 #[derive(Debug)]
 pub struct IncompatibleBranch {
     pub file: FileId,
@@ -213,6 +289,10 @@ pub struct IncompatibleBranch {
 }
 
 impl Diagnostic for IncompatibleBranch {
+    fn code(&self) -> &'static str {
+        "E0006"
+    }
+
     fn message(&self) -> String {
         "mismatched branches".to_string()
     }
@@ -243,6 +323,10 @@ pub struct InvalidLHS {
 }
 
 impl Diagnostic for InvalidLHS {
+    fn code(&self) -> &'static str {
+        "E0007"
+    }
+
     fn message(&self) -> String {
         "invalid left hand side of expression".to_string()
     }
@@ -268,6 +352,10 @@ pub struct MissingElseBranch {
 }
 
 impl Diagnostic for MissingElseBranch {
+    fn code(&self) -> &'static str {
+        "E0008"
+    }
+
     fn message(&self) -> String {
         "missing else branch".to_string()
     }
@@ -285,17 +373,150 @@ impl Diagnostic for MissingElseBranch {
     }
 }
 
+/// An exit of the body of a function or closure that doesn't return a value of the return type,
+/// i.e. an `if` without an `else`, a block without a trailing expression, a `break` without a value
+/// or a `while` or `for` loop, in the position of the return value.
+#[derive(Debug)]
+pub struct MissingReturnValue {
+    pub file: FileId,
+    /// The `if`, `break`, `while` or `for` expression or the block
+    pub exit: SyntaxNodePtr,
+    pub expected: Ty,
+    /// The return type annotation, if it is known
+    pub expected_source: Option<ExpectedTypeSource>,
+}
+
+impl Diagnostic for MissingReturnValue {
+    fn code(&self) -> &'static str {
+        "E0052"
+    }
+
+    fn message(&self) -> String {
+        match self.exit.kind() {
+            SyntaxKind::IF_EXPR => "missing else branch that returns a value".to_string(),
+            SyntaxKind::BREAK_EXPR => "missing value in break".to_string(),
+            _ => "missing return value".to_string(),
+        }
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.exit
+    }
+
+    fn highlight_range(&self) -> TextRange {
+        // A block is pointed out at its closing brace, where it ends without a value
+        let range = self.exit.range();
+        if self.exit.kind() == SyntaxKind::BLOCK_EXPR && range.len() > TextUnit::from(0) {
+            TextRange::from_to(range.end() - TextUnit::of_char('}'), range.end())
+        } else {
+            range
+        }
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A binary operator that cannot be applied to the type of its left operand, e.g. `true + 1`, or
+/// of which the operands have different types, e.g. `1 + 1.5`. The operator is highlighted.
 #[derive(Debug)]
 pub struct CannotApplyBinaryOp {
     pub file: FileId,
     pub expr: SyntaxNodePtr,
+    /// The operator as it is written, e.g. `+=`
+    pub op: String,
+    pub op_range: TextRange,
     pub lhs: Ty,
     pub rhs: Ty,
+    /// Whether the operator can be applied to the type of the left operand, but the right operand
+    /// has a different type
+    pub mismatched_operands: bool,
 }
 
 impl Diagnostic for CannotApplyBinaryOp {
+    fn code(&self) -> &'static str {
+        "E0009"
+    }
+
+    fn message(&self) -> String {
+        format!("cannot apply binary operator `{}`", self.op)
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+
+    fn highlight_range(&self) -> TextRange {
+        self.op_range
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A unary operator that cannot be applied to the type of its operand, e.g. `!1`. The operator is
+/// highlighted.
+#[derive(Debug)]
+pub struct CannotApplyUnaryOp {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+    /// The operator as it is written, e.g. `-`
+    pub op: String,
+    pub op_range: TextRange,
+    pub ty: Ty,
+}
+
+impl Diagnostic for CannotApplyUnaryOp {
+    fn code(&self) -> &'static str {
+        "E0010"
+    }
+
+    fn message(&self) -> String {
+        format!("cannot apply unary operator `{}`", self.op)
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+
+    fn highlight_range(&self) -> TextRange {
+        self.op_range
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct CannotApplyBuiltinFunction {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+    pub name: Name,
+    pub ty: Ty,
+}
+
+impl Diagnostic for CannotApplyBuiltinFunction {
+    fn code(&self) -> &'static str {
+        "E0011"
+    }
+
     fn message(&self) -> String {
-        "cannot apply binary operator".to_string()
+        format!("cannot apply builtin function `{}`", self.name)
     }
 
     fn file(&self) -> FileId {
@@ -311,15 +532,24 @@ impl Diagnostic for CannotApplyBinaryOp {
     }
 }
 
+/// A name that is defined more than once in a module, or a method that has the same name as
+/// another method of its struct.
 #[derive(Debug)]
 pub struct DuplicateDefinition {
     pub file: FileId,
     pub name: String,
+    /// The namespace in which the name is defined twice, or `None` if an import conflicts with a
+    /// definition
+    pub namespace: Option<Namespace>,
     pub first_definition: SyntaxNodePtr,
     pub definition: SyntaxNodePtr,
 }
 
 impl Diagnostic for DuplicateDefinition {
+    fn code(&self) -> &'static str {
+        "E0012"
+    }
+
     fn message(&self) -> String {
         format!("the name `{}` is defined multiple times", self.name)
     }
@@ -344,6 +574,10 @@ pub struct ReturnMissingExpression {
 }
 
 impl Diagnostic for ReturnMissingExpression {
+    fn code(&self) -> &'static str {
+        "E0013"
+    }
+
     fn message(&self) -> String {
         "`return;` in a function whose return type is not `()`".to_owned()
     }
@@ -368,6 +602,10 @@ pub struct BreakOutsideLoop {
 }
 
 impl Diagnostic for BreakOutsideLoop {
+    fn code(&self) -> &'static str {
+        "E0014"
+    }
+
     fn message(&self) -> String {
         "`break` outside of a loop".to_owned()
     }
@@ -392,6 +630,10 @@ pub struct BreakWithValueOutsideLoop {
 }
 
 impl Diagnostic for BreakWithValueOutsideLoop {
+    fn code(&self) -> &'static str {
+        "E0015"
+    }
+
     fn message(&self) -> String {
         "`break` with value can only appear in a `loop`".to_owned()
     }
@@ -408,3 +650,1498 @@ impl Diagnostic for BreakWithValueOutsideLoop {
         self
     }
 }
+
+#[derive(Debug)]
+pub struct ContinueOutsideLoop {
+    pub file: FileId,
+    pub continue_expr: SyntaxNodePtr,
+}
+
+impl Diagnostic for ContinueOutsideLoop {
+    fn code(&self) -> &'static str {
+        "E0016"
+    }
+
+    fn message(&self) -> String {
+        "`continue` outside of a loop".to_owned()
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.continue_expr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// An integer literal whose value cannot be represented by its type. A negated literal, e.g. `-1`,
+/// is checked as a whole, so `-128i8` fits but `-1u8` does not. The range of the type is shown in
+/// the radix of the literal, e.g. `0x0..=0xff` for `0x1ffu8`.
+#[derive(Debug)]
+pub struct LiteralOutOfRange {
+    pub file: FileId,
+    pub literal: SyntaxNodePtr,
+    pub literal_ty: IntTy,
+    pub radix: Radix,
+}
+
+impl Diagnostic for LiteralOutOfRange {
+    fn code(&self) -> &'static str {
+        "E0017"
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "literal out of range for `{}`, whose range is `{}..={}`",
+            self.literal_ty,
+            self.radix.format(self.literal_ty.min_value()),
+            self.radix.format(self.literal_ty.max_value() as i128)
+        )
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.literal
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A floating point literal that cannot be represented by its type, because it is rounded to
+/// infinity or, although it is not zero, to zero. This is a warning.
+#[derive(Debug)]
+pub struct FloatLiteralOutOfRange {
+    pub file: FileId,
+    pub literal: SyntaxNodePtr,
+    pub float_ty: FloatTy,
+    /// Whether the literal is rounded to zero instead of to infinity
+    pub rounded_to_zero: bool,
+}
+
+impl Diagnostic for FloatLiteralOutOfRange {
+    fn code(&self) -> &'static str {
+        "W0005"
+    }
+
+    fn message(&self) -> String {
+        let rounded = if self.rounded_to_zero {
+            "zero"
+        } else {
+            "infinity"
+        };
+        format!(
+            "literal out of range for `{}`; it is rounded to {}",
+            self.float_ty, rounded
+        )
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.literal
+    }
+
+    fn lint(&self) -> Option<Lint> {
+        Some(Lint::OverflowingLiterals)
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct AccessUnknownField {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+    pub receiver_ty: Ty,
+    pub name: Name,
+}
+
+impl Diagnostic for AccessUnknownField {
+    fn code(&self) -> &'static str {
+        "E0018"
+    }
+
+    fn message(&self) -> String {
+        format!("no field `{}`", self.name)
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct UnresolvedStruct {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+}
+
+impl Diagnostic for UnresolvedStruct {
+    fn code(&self) -> &'static str {
+        "E0019"
+    }
+
+    fn message(&self) -> String {
+        "could not find struct".to_string()
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct NoSuchField {
+    pub file: FileId,
+    pub field: SyntaxNodePtr,
+}
+
+impl Diagnostic for NoSuchField {
+    fn code(&self) -> &'static str {
+        "E0020"
+    }
+
+    fn message(&self) -> String {
+        "no such field".to_string()
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.field
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct DuplicateField {
+    pub file: FileId,
+    pub field: SyntaxNodePtr,
+    pub name: Name,
+}
+
+impl Diagnostic for DuplicateField {
+    fn code(&self) -> &'static str {
+        "E0021"
+    }
+
+    fn message(&self) -> String {
+        format!("field `{}` specified more than once", self.name)
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.field
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct MissingFields {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+    pub struct_ty: Ty,
+    pub field_names: Vec<Name>,
+}
+
+impl Diagnostic for MissingFields {
+    fn code(&self) -> &'static str {
+        "E0022"
+    }
+
+    fn message(&self) -> String {
+        let names = self
+            .field_names
+            .iter()
+            .map(|name| format!("`{}`", name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("missing fields {}", names)
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct CannotInferArrayType {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+}
+
+impl Diagnostic for CannotInferArrayType {
+    fn code(&self) -> &'static str {
+        "E0023"
+    }
+
+    fn message(&self) -> String {
+        "cannot infer the element type of an empty array".to_string()
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct CannotIndex {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+    pub ty: Ty,
+}
+
+impl Diagnostic for CannotIndex {
+    fn code(&self) -> &'static str {
+        "E0024"
+    }
+
+    fn message(&self) -> String {
+        "cannot index into a value of this type".to_string()
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct IndexOutOfBounds {
+    pub file: FileId,
+    pub index: SyntaxNodePtr,
+    pub value: u128,
+    pub len: u64,
+}
+
+impl Diagnostic for IndexOutOfBounds {
+    fn code(&self) -> &'static str {
+        "E0025"
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "index out of bounds: the length is {} but the index is {}",
+            self.len, self.value
+        )
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.index
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownAttribute {
+    pub file: FileId,
+    pub attr: SyntaxNodePtr,
+    pub name: String,
+}
+
+impl Diagnostic for UnknownAttribute {
+    fn code(&self) -> &'static str {
+        "E0026"
+    }
+
+    fn message(&self) -> String {
+        format!("unknown attribute `{}`", self.name)
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.attr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A name in a lint attribute, e.g. `#[allow(unused_variable)]`, that is not the name of a lint.
+/// This is a warning.
+#[derive(Debug)]
+pub struct UnknownLint {
+    pub file: FileId,
+    pub arg: SyntaxNodePtr,
+    pub name: String,
+}
+
+impl Diagnostic for UnknownLint {
+    fn code(&self) -> &'static str {
+        "W0006"
+    }
+
+    fn message(&self) -> String {
+        format!("unknown lint `{}`", self.name)
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.arg
+    }
+
+    fn lint(&self) -> Option<Lint> {
+        Some(Lint::UnknownLints)
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct MalformedAttribute {
+    pub file: FileId,
+    pub attr: SyntaxNodePtr,
+    pub name: String,
+    pub expected: &'static str,
+}
+
+impl Diagnostic for MalformedAttribute {
+    fn code(&self) -> &'static str {
+        "E0027"
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "malformed `{}` attribute, expected {}",
+            self.name, self.expected
+        )
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.attr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A `#[test]` function with a signature that the test runner cannot invoke. Tests are private free
+/// functions without parameters and without a return value.
+#[derive(Debug)]
+pub struct InvalidTestFunction {
+    pub file: FileId,
+    pub def: SyntaxNodePtr,
+    pub name: Name,
+    /// What test functions cannot do, e.g. `take parameters`
+    pub reason: &'static str,
+}
+
+impl Diagnostic for InvalidTestFunction {
+    fn code(&self) -> &'static str {
+        "E0056"
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "`{}` is marked `#[test]`, but test functions cannot {}",
+            self.name, self.reason
+        )
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.def
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct CyclicConst {
+    pub file: FileId,
+    pub const_def: SyntaxNodePtr,
+    pub name: String,
+}
+
+impl Diagnostic for CyclicConst {
+    fn code(&self) -> &'static str {
+        "E0028"
+    }
+
+    fn message(&self) -> String {
+        format!("cycle detected when evaluating constant `{}`", self.name)
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.const_def
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct CyclicTypeAlias {
+    pub file: FileId,
+    pub type_alias_def: SyntaxNodePtr,
+    pub name: String,
+}
+
+impl Diagnostic for CyclicTypeAlias {
+    fn code(&self) -> &'static str {
+        "E0029"
+    }
+
+    fn message(&self) -> String {
+        format!("cycle detected when resolving type alias `{}`", self.name)
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.type_alias_def
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A struct that contains itself by value, through the fields in `fields`, e.g. `A.b` and `B.a`.
+/// The struct would be infinitely large. The diagnostic points at the field of the struct through
+/// which it contains itself.
+#[derive(Debug)]
+pub struct RecursiveStruct {
+    pub file: FileId,
+    pub field: SyntaxNodePtr,
+    pub name: String,
+    pub fields: Vec<String>,
+}
+
+impl Diagnostic for RecursiveStruct {
+    fn code(&self) -> &'static str {
+        "E0054"
+    }
+
+    fn message(&self) -> String {
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|field| format!("`{}`", field))
+            .collect();
+        format!(
+            "recursive struct `{}` has infinite size, it contains itself by value through {}",
+            self.name,
+            fields.join(" -> ")
+        )
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.field
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct NonConstExpr {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+}
+
+impl Diagnostic for NonConstExpr {
+    fn code(&self) -> &'static str {
+        "E0030"
+    }
+
+    fn message(&self) -> String {
+        "expression cannot be evaluated at compile time".to_string()
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct ConstOverflow {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+}
+
+impl Diagnostic for ConstOverflow {
+    fn code(&self) -> &'static str {
+        "E0031"
+    }
+
+    fn message(&self) -> String {
+        "attempt to compute a constant value that overflows".to_string()
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct ConstDivisionByZero {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+}
+
+impl Diagnostic for ConstDivisionByZero {
+    fn code(&self) -> &'static str {
+        "E0032"
+    }
+
+    fn message(&self) -> String {
+        "attempt to divide by zero in a constant expression".to_string()
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidEnumDiscriminant {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+}
+
+impl Diagnostic for InvalidEnumDiscriminant {
+    fn code(&self) -> &'static str {
+        "E0033"
+    }
+
+    fn message(&self) -> String {
+        "enum discriminant must be an integer".to_string()
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidArrayLength {
+    pub file: FileId,
+    pub len: SyntaxNodePtr,
+}
+
+impl Diagnostic for InvalidArrayLength {
+    fn code(&self) -> &'static str {
+        "E0057"
+    }
+
+    fn message(&self) -> String {
+        "array length must be a non-negative integer".to_string()
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.len
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct EnumDiscriminantOverflow {
+    pub file: FileId,
+    pub variant: SyntaxNodePtr,
+    pub repr: IntTy,
+}
+
+impl Diagnostic for EnumDiscriminantOverflow {
+    fn code(&self) -> &'static str {
+        "E0034"
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "enum discriminant out of range for `{}`, whose range is `{}..={}`",
+            self.repr,
+            self.repr.min_value(),
+            self.repr.max_value()
+        )
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.variant
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct DuplicateEnumDiscriminant {
+    pub file: FileId,
+    pub variant: SyntaxNodePtr,
+    pub first_variant: SyntaxNodePtr,
+    pub discriminant: i128,
+}
+
+impl Diagnostic for DuplicateEnumDiscriminant {
+    fn code(&self) -> &'static str {
+        "E0035"
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "discriminant value `{}` is assigned more than once",
+            self.discriminant
+        )
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.variant
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidCast {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+    pub from_ty: Ty,
+    pub to_ty: Ty,
+}
+
+impl Diagnostic for InvalidCast {
+    fn code(&self) -> &'static str {
+        "E0036"
+    }
+
+    fn message(&self) -> String {
+        "invalid cast".to_string()
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct UnsupportedPattern {
+    pub file: FileId,
+    pub pat: SyntaxNodePtr,
+}
+
+impl Diagnostic for UnsupportedPattern {
+    fn code(&self) -> &'static str {
+        "E0037"
+    }
+
+    fn message(&self) -> String {
+        "unsupported pattern, expected an integer or boolean literal, an enum variant or `_`"
+            .to_string()
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.pat
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct NonExhaustiveMatch {
+    pub file: FileId,
+    /// The matched expression
+    pub expr: SyntaxNodePtr,
+    /// The values that are not matched by any arm, e.g. `State::Idle` or `_`
+    pub missing: Vec<String>,
+}
+
+impl Diagnostic for NonExhaustiveMatch {
+    fn code(&self) -> &'static str {
+        "E0038"
+    }
+
+    fn message(&self) -> String {
+        let missing: Vec<String> = self
+            .missing
+            .iter()
+            .map(|value| format!("`{}`", value))
+            .collect();
+        format!(
+            "non-exhaustive patterns: {} not covered",
+            missing.join(", ")
+        )
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A match arm that can never be reached, because the arms before it already match every value
+/// that its pattern matches. This is a warning.
+#[derive(Debug)]
+pub struct UnreachablePattern {
+    pub file: FileId,
+    pub pat: SyntaxNodePtr,
+}
+
+impl Diagnostic for UnreachablePattern {
+    fn code(&self) -> &'static str {
+        "W0001"
+    }
+
+    fn message(&self) -> String {
+        "unreachable pattern".to_string()
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.pat
+    }
+
+    fn lint(&self) -> Option<Lint> {
+        Some(Lint::UnreachablePatterns)
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// Code that is never executed, because it follows an expression that never returns, e.g. a
+/// `return`, or because it is the branch of an `if` with a literal condition that is never taken.
+/// This is a warning.
+#[derive(Debug)]
+pub struct UnreachableCode {
+    pub file: FileId,
+    /// The first statement or expression that is never executed
+    pub code: SyntaxNodePtr,
+    /// The range of all the code that is never executed
+    pub range: TextRange,
+}
+
+impl Diagnostic for UnreachableCode {
+    fn code(&self) -> &'static str {
+        "W0002"
+    }
+
+    fn message(&self) -> String {
+        "unreachable code".to_string()
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.code
+    }
+
+    fn highlight_range(&self) -> TextRange {
+        self.range
+    }
+
+    fn lint(&self) -> Option<Lint> {
+        Some(Lint::UnreachableCode)
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidImplTarget {
+    pub file: FileId,
+    pub type_ref: SyntaxNodePtr,
+}
+
+impl Diagnostic for InvalidImplTarget {
+    fn code(&self) -> &'static str {
+        "E0039"
+    }
+
+    fn message(&self) -> String {
+        "impl blocks can only define methods of structs".to_string()
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.type_ref
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A method that has the same name as a field of its struct, which would make `a.x` ambiguous
+/// once methods can be referred to without calling them.
+#[derive(Debug)]
+pub struct MethodFieldCollision {
+    pub file: FileId,
+    pub method: SyntaxNodePtr,
+    pub name: String,
+    pub struct_name: String,
+}
+
+impl Diagnostic for MethodFieldCollision {
+    fn code(&self) -> &'static str {
+        "E0040"
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "method `{}` has the same name as a field of `{}`",
+            self.name, self.struct_name
+        )
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.method
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct SelfParamOutsideImpl {
+    pub file: FileId,
+    pub param: SyntaxNodePtr,
+}
+
+impl Diagnostic for SelfParamOutsideImpl {
+    fn code(&self) -> &'static str {
+        "E0041"
+    }
+
+    fn message(&self) -> String {
+        "`self` parameter is only allowed in the methods of an impl block".to_string()
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.param
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct UnresolvedMethod {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+    pub receiver_ty: Ty,
+    pub name: Name,
+}
+
+impl Diagnostic for UnresolvedMethod {
+    fn code(&self) -> &'static str {
+        "E0042"
+    }
+
+    fn message(&self) -> String {
+        format!("no method `{}`", self.name)
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// An assignment to a local binding, or to a field or an element of one, that is not declared
+/// `mut`. A binding that is declared without a value may be assigned once.
+#[derive(Debug)]
+pub struct CannotAssignToImmutable {
+    pub file: FileId,
+
+    /// The left-hand side of the assignment
+    pub lhs: SyntaxNodePtr,
+
+    /// The definition of the binding, e.g. the pattern of a `let` statement
+    pub binding: SyntaxNodePtr,
+
+    pub name: Name,
+}
+
+impl Diagnostic for CannotAssignToImmutable {
+    fn code(&self) -> &'static str {
+        "E0043"
+    }
+
+    fn message(&self) -> String {
+        format!("cannot assign to immutable variable `{}`", self.name)
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.lhs
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A use of a local binding that was declared without a value, e.g. `let a: int;`, and that isn't
+/// assigned a value on every path to the use.
+#[derive(Debug)]
+pub struct PossiblyUninitializedVariable {
+    pub file: FileId,
+
+    /// The use of the binding
+    pub expr: SyntaxNodePtr,
+
+    /// The definition of the binding, i.e. the pattern of the `let` statement
+    pub binding: SyntaxNodePtr,
+
+    pub name: Name,
+}
+
+impl Diagnostic for PossiblyUninitializedVariable {
+    fn code(&self) -> &'static str {
+        "E0053"
+    }
+
+    fn message(&self) -> String {
+        format!("`{}` may be uninitialized", self.name)
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A use of a local binding of the enclosing function in a closure. Closures are function
+/// pointers, so they cannot capture variables.
+#[derive(Debug)]
+pub struct CapturedVariable {
+    pub file: FileId,
+
+    /// The use of the binding in the closure
+    pub expr: SyntaxNodePtr,
+
+    pub name: Name,
+}
+
+impl Diagnostic for CapturedVariable {
+    fn code(&self) -> &'static str {
+        "E0044"
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "closures cannot capture variables, `{}` is defined outside of the closure",
+            self.name
+        )
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct CannotInferClosureParamType {
+    pub file: FileId,
+    pub pat: SyntaxNodePtr,
+}
+
+impl Diagnostic for CannotInferClosureParamType {
+    fn code(&self) -> &'static str {
+        "E0045"
+    }
+
+    fn message(&self) -> String {
+        "cannot infer the type of the closure parameter, consider annotating it".to_string()
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.pat
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A local binding or a parameter that is never used. Bindings with a name that starts with an
+/// underscore are intentionally unused and are not reported. This is a warning.
+#[derive(Debug)]
+pub struct UnusedVariable {
+    pub file: FileId,
+
+    /// The definition of the binding, e.g. the pattern of a `let` statement
+    pub binding: SyntaxNodePtr,
+
+    pub name: Name,
+
+    /// Whether the binding is a parameter of the function
+    pub is_param: bool,
+}
+
+impl UnusedVariable {
+    /// Returns the name that marks the binding as intentionally unused
+    pub fn suggested_name(&self) -> String {
+        format!("_{}", self.name)
+    }
+}
+
+impl Diagnostic for UnusedVariable {
+    fn code(&self) -> &'static str {
+        "W0003"
+    }
+
+    fn message(&self) -> String {
+        let kind = if self.is_param {
+            "parameter"
+        } else {
+            "variable"
+        };
+        format!("unused {} `{}`", kind, self.name)
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.binding
+    }
+
+    fn lint(&self) -> Option<Lint> {
+        Some(Lint::UnusedVariables)
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A private function that is never used by the `export` functions of its file, so no code is
+/// generated for it unless dead code elimination is disabled. Functions with a name that starts
+/// with an underscore are intentionally unused and are not reported. This is a warning.
+#[derive(Debug)]
+pub struct UnusedFunction {
+    pub file: FileId,
+    pub def: SyntaxNodePtr,
+
+    /// The name of the function, including the type of an impl block for a method
+    pub name: String,
+
+    /// The name of the function without the type of an impl block
+    pub fn_name: Name,
+}
+
+impl UnusedFunction {
+    /// Returns the name that marks the function as intentionally unused
+    pub fn suggested_name(&self) -> String {
+        format!("_{}", self.fn_name)
+    }
+}
+
+impl Diagnostic for UnusedFunction {
+    fn code(&self) -> &'static str {
+        "W0004"
+    }
+
+    fn message(&self) -> String {
+        format!("function `{}` is never used", self.name)
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.def
+    }
+
+    fn lint(&self) -> Option<Lint> {
+        Some(Lint::DeadCode)
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// An import whose path doesn't refer to an item of another module. The uses of the imported
+/// name are not reported again.
+#[derive(Debug)]
+pub struct UnresolvedImport {
+    pub file: FileId,
+    pub use_decl: SyntaxNodePtr,
+    pub path: String,
+}
+
+impl Diagnostic for UnresolvedImport {
+    fn code(&self) -> &'static str {
+        "E0046"
+    }
+
+    fn message(&self) -> String {
+        format!("unresolved import `{}`", self.path)
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.use_decl
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct DuplicateImport {
+    pub file: FileId,
+    pub name: String,
+    pub first_use_decl: SyntaxNodePtr,
+    pub use_decl: SyntaxNodePtr,
+}
+
+impl Diagnostic for DuplicateImport {
+    fn code(&self) -> &'static str {
+        "E0047"
+    }
+
+    fn message(&self) -> String {
+        format!("the name `{}` is imported multiple times", self.name)
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.use_decl
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A glob import, e.g. `use shapes::*;`. Every imported name has to be listed, so which module a
+/// name refers to is always visible in the importing module.
+#[derive(Debug)]
+pub struct GlobImport {
+    pub file: FileId,
+    pub use_decl: SyntaxNodePtr,
+}
+
+impl Diagnostic for GlobImport {
+    fn code(&self) -> &'static str {
+        "E0048"
+    }
+
+    fn message(&self) -> String {
+        "glob imports are not supported".to_owned()
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.use_decl
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A path that names an item of another module that is not declared `export`. Items are private
+/// to the module that defines them unless they are declared `export`.
+#[derive(Debug)]
+pub struct PrivateItem {
+    pub file: FileId,
+    pub path: SyntaxNodePtr,
+
+    /// The kind of the item, e.g. `function`
+    pub kind: &'static str,
+    pub name: String,
+
+    /// The private definition, which is in another file than the path
+    pub definition_file: FileId,
+    pub definition: SyntaxNodePtr,
+}
+
+impl Diagnostic for PrivateItem {
+    fn code(&self) -> &'static str {
+        "E0049"
+    }
+
+    fn message(&self) -> String {
+        format!("{} `{}` is private", self.kind, self.name)
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.path
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// An access of a field of a struct of another module that is not declared `export`. The fields
+/// of a struct have the visibility of the struct.
+#[derive(Debug)]
+pub struct PrivateField {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+    pub struct_name: String,
+    pub field: Name,
+
+    /// The definition of the private struct, which is in another file than the access
+    pub definition_file: FileId,
+    pub definition: SyntaxNodePtr,
+}
+
+impl Diagnostic for PrivateField {
+    fn code(&self) -> &'static str {
+        "E0050"
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "field `{}` of struct `{}` is private",
+            self.field, self.struct_name
+        )
+    }
+
+    fn file(&self) -> FileId {
+        self.file
+    }
+
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
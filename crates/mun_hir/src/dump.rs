@@ -0,0 +1,319 @@
+//! Renders the lowered bodies of the items of a file as readable trees, optionally annotated with
+//! the inferred type of every expression and pattern. The output is meant for debugging the
+//! compiler, and is stable enough to be used in snapshot tests.
+
+use crate::code_model::src::HasSource;
+use crate::code_model::DefWithBody;
+use crate::expr::{ArithOp, BinaryOp, CmpOp, Expr, Literal, LogicOp, Ordering, Pat, Statement};
+use crate::expr::{Body, BodySourceMap, ExprId, PatId, UnaryOp};
+use crate::{FileId, HirDatabase, HirDisplay, InferenceResult, Module, ModuleDef, Path, PathKind};
+use mun_syntax::{AstNode, TextRange};
+use std::fmt::Write;
+use std::sync::Arc;
+
+#[cfg(test)]
+mod tests;
+
+/// The label of nodes that are missing from the syntax tree, because of a syntax error.
+const ERROR: &str = "ERROR";
+
+/// Returns the lowered bodies of the items of the file `file_id`, in source order.
+pub fn dump_bodies(db: &impl HirDatabase, file_id: FileId) -> String {
+    dump(db, file_id, false)
+}
+
+/// Returns the lowered bodies of the items of the file `file_id`, in source order, with the
+/// inferred type of every expression and pattern. A type that could not be inferred is shown as
+/// `{unknown}`.
+pub fn dump_types(db: &impl HirDatabase, file_id: FileId) -> String {
+    dump(db, file_id, true)
+}
+
+fn dump(db: &impl HirDatabase, file_id: FileId, with_types: bool) -> String {
+    let mut items = Vec::new();
+    for def in Module::from(file_id).declarations(db) {
+        let (header, range, def): (_, _, DefWithBody) = match def {
+            ModuleDef::Function(f) if !f.is_extern(db) => (
+                format!("fn {}", f.full_name(db)),
+                f.source(db).ast.syntax().text_range(),
+                f.into(),
+            ),
+            ModuleDef::Const(c) => (
+                format!("const {}", c.name(db)),
+                c.source(db).ast.syntax().text_range(),
+                c.into(),
+            ),
+            ModuleDef::Static(s) => (
+                format!("static {}", s.name(db)),
+                s.source(db).ast.syntax().text_range(),
+                s.into(),
+            ),
+            _ => continue,
+        };
+        items.push((range, header, def));
+    }
+    items.sort_by_key(|(range, _, _)| range.start());
+
+    let mut buf = String::new();
+    for (range, header, def) in items {
+        writeln!(buf, "{} {}", header, range).unwrap();
+        let body = def.body(db);
+        let mut dumper = BodyDumper {
+            db,
+            body: body.clone(),
+            source_map: def.body_source_map(db),
+            infer: if with_types {
+                Some(def.infer(db))
+            } else {
+                None
+            },
+            buf: &mut buf,
+        };
+        for &(pat, _) in body.params() {
+            dumper.pat(pat, 1);
+        }
+        dumper.expr(body.body_expr(), 1);
+    }
+    buf
+}
+
+/// Writes the expressions and patterns of a body to `buf`, one node per line. Children are
+/// indented below their parent.
+struct BodyDumper<'a, DB> {
+    db: &'a DB,
+    body: Arc<Body>,
+    source_map: Arc<BodySourceMap>,
+    /// The result of type inference, if the types are dumped
+    infer: Option<Arc<InferenceResult>>,
+    buf: &'a mut String,
+}
+
+impl<'a, DB: HirDatabase> BodyDumper<'a, DB> {
+    /// Writes a line with `label`, and the range and type of a node if it has them.
+    fn line(&mut self, depth: usize, label: &str, range: Option<TextRange>, ty: Option<String>) {
+        write!(self.buf, "{:indent$}{}", "", label, indent = depth * 2).unwrap();
+        if let Some(range) = range {
+            write!(self.buf, " {}", range).unwrap();
+        }
+        if let Some(ty) = ty {
+            write!(self.buf, ": {}", ty).unwrap();
+        }
+        self.buf.push('\n');
+    }
+
+    fn pat(&mut self, pat: PatId, depth: usize) {
+        let label = match &self.body[pat] {
+            Pat::Missing => ERROR.to_string(),
+            Pat::Wild => "Wild".to_string(),
+            Pat::Path(path) => format!("Path {}", path_to_string(path)),
+            Pat::Bind { name, is_mutable } => {
+                format!("Bind {}{}", if *is_mutable { "mut " } else { "" }, name)
+            }
+            Pat::Lit(_) => "Lit".to_string(),
+        };
+        let range = self
+            .source_map
+            .pat_syntax(pat)
+            .map(|src| src.ast.syntax_node_ptr().range());
+        let ty = self
+            .infer
+            .as_ref()
+            .map(|infer| infer[pat].display(self.db).to_string());
+        self.line(depth, &label, range, ty);
+
+        if let Pat::Lit(expr) = self.body[pat] {
+            self.expr(expr, depth + 1);
+        }
+    }
+
+    fn expr(&mut self, expr: ExprId, depth: usize) {
+        let body = self.body.clone();
+        let label = match &body[expr] {
+            Expr::Missing => ERROR.to_string(),
+            Expr::Call { .. } => "Call".to_string(),
+            Expr::MethodCall { method_name, .. } => format!("MethodCall {}", method_name),
+            Expr::Path(path) => format!("Path {}", path_to_string(path)),
+            Expr::Field { name, .. } => format!("Field {}", name),
+            Expr::RecordLit { path, .. } => match path {
+                Some(path) => format!("RecordLit {}", path_to_string(path)),
+                None => format!("RecordLit {}", ERROR),
+            },
+            Expr::Tuple { .. } => "Tuple".to_string(),
+            Expr::Array { .. } => "Array".to_string(),
+            Expr::Index { .. } => "Index".to_string(),
+            Expr::If { .. } => "If".to_string(),
+            Expr::UnaryOp { op, .. } => format!("UnaryOp {}", unary_op_to_str(*op)),
+            Expr::Cast { .. } => "Cast".to_string(),
+            Expr::BinaryOp { op, .. } => match op {
+                Some(op) => format!("BinaryOp {}", binary_op_to_string(*op)),
+                None => format!("BinaryOp {}", ERROR),
+            },
+            Expr::Block { .. } => "Block".to_string(),
+            Expr::Return { .. } => "Return".to_string(),
+            Expr::Break { .. } => "Break".to_string(),
+            Expr::Continue => "Continue".to_string(),
+            Expr::Loop { .. } => "Loop".to_string(),
+            Expr::While { .. } => "While".to_string(),
+            Expr::For { inclusive, .. } => {
+                format!("For {}", if *inclusive { "..=" } else { ".." })
+            }
+            Expr::Match { .. } => "Match".to_string(),
+            Expr::Closure { .. } => "Closure".to_string(),
+            Expr::Literal(literal) => format!("Literal {}", literal_to_string(literal)),
+        };
+        let range = self
+            .source_map
+            .expr_syntax(expr)
+            .map(|src| src.ast.syntax_node_ptr().range());
+        let ty = self
+            .infer
+            .as_ref()
+            .map(|infer| infer[expr].display(self.db).to_string());
+        self.line(depth, &label, range, ty);
+
+        let depth = depth + 1;
+        match &body[expr] {
+            Expr::Block { statements, tail } => {
+                for statement in statements {
+                    match statement {
+                        Statement::Let {
+                            pat, initializer, ..
+                        } => {
+                            self.line(depth, "Let", None, None);
+                            self.pat(*pat, depth + 1);
+                            if let Some(initializer) = initializer {
+                                self.expr(*initializer, depth + 1);
+                            }
+                        }
+                        Statement::Expr(expr) => self.expr(*expr, depth),
+                    }
+                }
+                if let Some(tail) = tail {
+                    self.expr(*tail, depth);
+                }
+            }
+            Expr::RecordLit { fields, .. } => {
+                for field in fields {
+                    self.line(depth, &format!("RecordField {}", field.name), None, None);
+                    self.expr(field.expr, depth + 1);
+                }
+            }
+            Expr::For {
+                pat,
+                start,
+                end,
+                body,
+                ..
+            } => {
+                self.pat(*pat, depth);
+                self.expr(*start, depth);
+                self.expr(*end, depth);
+                self.expr(*body, depth);
+            }
+            Expr::Match { expr, arms } => {
+                self.expr(*expr, depth);
+                for arm in arms {
+                    self.line(depth, "MatchArm", None, None);
+                    self.pat(arm.pat, depth + 1);
+                    self.expr(arm.expr, depth + 1);
+                }
+            }
+            Expr::Closure { args, body, .. } => {
+                for arg in args {
+                    self.pat(*arg, depth);
+                }
+                self.expr(*body, depth);
+            }
+            expr => expr.walk_child_exprs(|child| self.expr(child, depth)),
+        }
+    }
+}
+
+fn path_to_string(path: &Path) -> String {
+    let prefix = match path.kind {
+        PathKind::Plain => "",
+        PathKind::Self_ => "self::",
+        PathKind::Super => "super::",
+        PathKind::Abs => "::",
+    };
+    let segments: Vec<String> = path
+        .segments
+        .iter()
+        .map(|segment| segment.name.to_string())
+        .collect();
+    format!("{}{}", prefix, segments.join("::"))
+}
+
+fn literal_to_string(literal: &Literal) -> String {
+    match literal {
+        Literal::String(value) => format!("{:?}", value),
+        Literal::Bool(value) => value.to_string(),
+        Literal::Int(int) => match int.suffix {
+            Some(suffix) => format!("{}{}", int.value, suffix),
+            None => int.value.to_string(),
+        },
+        Literal::Float(float) => match float.suffix {
+            Some(suffix) => format!("{:?}{}", float.value, suffix),
+            None => format!("{:?}", float.value),
+        },
+    }
+}
+
+fn unary_op_to_str(op: UnaryOp) -> &'static str {
+    match op {
+        UnaryOp::Not => "!",
+        UnaryOp::Neg => "-",
+    }
+}
+
+fn binary_op_to_string(op: BinaryOp) -> String {
+    match op {
+        BinaryOp::LogicOp(op) => logic_op_to_str(op).to_string(),
+        BinaryOp::ArithOp(op) => arith_op_to_str(op).to_string(),
+        BinaryOp::CmpOp(op) => cmp_op_to_str(op).to_string(),
+        BinaryOp::Assignment { op: None } => "=".to_string(),
+        BinaryOp::Assignment { op: Some(op) } => format!("{}=", arith_op_to_str(op)),
+    }
+}
+
+fn logic_op_to_str(op: LogicOp) -> &'static str {
+    match op {
+        LogicOp::And => "&&",
+        LogicOp::Or => "||",
+    }
+}
+
+fn arith_op_to_str(op: ArithOp) -> &'static str {
+    match op {
+        ArithOp::Add => "+",
+        ArithOp::Multiply => "*",
+        ArithOp::Subtract => "-",
+        ArithOp::Divide => "/",
+        ArithOp::Remainder => "%",
+        ArithOp::LeftShift => "<<",
+        ArithOp::RightShift => ">>",
+    }
+}
+
+fn cmp_op_to_str(op: CmpOp) -> &'static str {
+    match op {
+        CmpOp::Eq { negated: false } => "==",
+        CmpOp::Eq { negated: true } => "!=",
+        CmpOp::Ord {
+            ordering: Ordering::Less,
+            strict: true,
+        } => "<",
+        CmpOp::Ord {
+            ordering: Ordering::Less,
+            strict: false,
+        } => "<=",
+        CmpOp::Ord {
+            ordering: Ordering::Greater,
+            strict: true,
+        } => ">",
+        CmpOp::Ord {
+            ordering: Ordering::Greater,
+            strict: false,
+        } => ">=",
+    }
+}
@@ -0,0 +1,56 @@
+use crate::dump::{dump_bodies, dump_types};
+use crate::mock::MockDatabase;
+
+#[test]
+fn dump_bodies_with_errors() {
+    dump_bodies_snapshot(
+        r#"
+    fn add(a: int, b: int): int {
+        let c = a + b;
+        c * 2
+    }
+
+    fn main(): int { 1 + }
+    "#,
+    )
+}
+
+#[test]
+fn dump_types_of_items() {
+    dump_types_snapshot(
+        r#"
+    const TWO: int = 2;
+
+    struct Foo;
+
+    impl Foo {
+        fn get(self, a: int): int { a * TWO }
+    }
+    "#,
+    )
+}
+
+#[test]
+fn dump_unknown_types() {
+    dump_types_snapshot("fn main() { let a = foo; }")
+}
+
+fn dump_bodies_snapshot(text: &str) {
+    let text = text.trim().replace("\n    ", "\n");
+    let (db, file_id) = MockDatabase::with_single_file(&text);
+    insta::assert_snapshot!(
+        insta::_macro_support::AutoName,
+        dump_bodies(&db, file_id).trim_end(),
+        &text
+    );
+}
+
+fn dump_types_snapshot(text: &str) {
+    let text = text.trim().replace("\n    ", "\n");
+    let (db, file_id) = MockDatabase::with_single_file(&text);
+    insta::assert_snapshot!(
+        insta::_macro_support::AutoName,
+        dump_types(&db, file_id).trim_end(),
+        &text
+    );
+}
@@ -2,15 +2,17 @@ use crate::{
     arena::map::ArenaMap,
     arena::{Arena, RawId},
     code_model::DefWithBody,
-    FileId, HirDatabase, Name, Path,
+    FileId, FloatTy, HirDatabase, IntTy, Name, Path,
 };
 
 //pub use mun_syntax::ast::PrefixOp as UnaryOp;
 use crate::code_model::src::{HasSource, Source};
-use crate::name::AsName;
+use crate::name::{AsName, SELF_PARAM};
 use crate::type_ref::{TypeRef, TypeRefBuilder, TypeRefId, TypeRefMap, TypeRefSourceMap};
 pub use mun_syntax::ast::PrefixOp as UnaryOp;
-use mun_syntax::ast::{ArgListOwner, BinOp, LoopBodyOwner, NameOwner, TypeAscriptionOwner};
+use mun_syntax::ast::{
+    ArgListOwner, BinOp, IntLiteralError, LoopBodyOwner, NameOwner, Radix, TypeAscriptionOwner,
+};
 use mun_syntax::{ast, AstNode, AstPtr, T};
 use rustc_hash::FxHashMap;
 use std::ops::Index;
@@ -20,7 +22,9 @@ pub use self::scope::ExprScopes;
 use crate::resolve::Resolver;
 use std::mem;
 
+pub(crate) mod initialization;
 pub(crate) mod scope;
+pub(crate) mod validator;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ExprId(RawId);
@@ -118,11 +122,12 @@ pub struct BodySourceMap {
     expr_map_back: ArenaMap<ExprId, ExprSource>,
     pat_map: FxHashMap<PatPtr, PatId>,
     pat_map_back: ArenaMap<PatId, PatSource>,
+    field_map: FxHashMap<(ExprId, usize), AstPtr<ast::RecordField>>,
     type_refs: TypeRefSourceMap,
 }
 
 impl BodySourceMap {
-    pub(crate) fn expr_syntax(&self, expr: ExprId) -> Option<ExprSource> {
+    pub fn expr_syntax(&self, expr: ExprId) -> Option<ExprSource> {
         self.expr_map_back.get(expr).cloned()
     }
 
@@ -146,6 +151,11 @@ impl BodySourceMap {
         self.pat_map.get(&AstPtr::new(node)).cloned()
     }
 
+    /// Returns the syntax of the `field`th field initializer of the record literal `expr`.
+    pub(crate) fn field_syntax(&self, expr: ExprId, field: usize) -> AstPtr<ast::RecordField> {
+        self.field_map[&(expr, field)]
+    }
+
     pub fn type_refs(&self) -> &TypeRefSourceMap {
         &self.type_refs
     }
@@ -165,12 +175,30 @@ pub enum Statement {
 pub enum Literal {
     String(String),
     Bool(bool),
-    Int(i64),
-    Float(f64),
+    Int(LiteralInt),
+    Float(LiteralFloat),
 }
 
 impl Eq for Literal {}
 
+/// An integer literal, e.g. `3`, `255u8` or `0xff`. Without a suffix the type of the literal is
+/// inferred from its context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LiteralInt {
+    pub value: u128,
+    pub suffix: Option<IntTy>,
+    /// The radix in which the literal is written, in which diagnostics show its values
+    pub radix: Radix,
+}
+
+/// A floating point literal, e.g. `1.5` or `1.5f32`. Without a suffix the type of the literal is
+/// inferred from its context.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LiteralFloat {
+    pub value: f64,
+    pub suffix: Option<FloatTy>,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Expr {
     /// Used if the syntax tree does not have a required expression piece
@@ -179,7 +207,32 @@ pub enum Expr {
         callee: ExprId,
         args: Vec<ExprId>,
     },
+    /// A call of a method on a receiver, e.g. `v.length()`. The method is called with the
+    /// receiver as its `self` argument.
+    MethodCall {
+        receiver: ExprId,
+        method_name: Name,
+        args: Vec<ExprId>,
+    },
     Path(Path),
+    Field {
+        expr: ExprId,
+        name: Name,
+    },
+    RecordLit {
+        path: Option<Path>,
+        fields: Vec<RecordLitField>,
+    },
+    Tuple {
+        exprs: Vec<ExprId>,
+    },
+    Array {
+        exprs: Vec<ExprId>,
+    },
+    Index {
+        base: ExprId,
+        index: ExprId,
+    },
     If {
         condition: ExprId,
         then_branch: ExprId,
@@ -189,6 +242,11 @@ pub enum Expr {
         expr: ExprId,
         op: UnaryOp,
     },
+    /// A cast of a value to another type, e.g. `State::Idle as u8`.
+    Cast {
+        expr: ExprId,
+        type_ref: TypeRefId,
+    },
     BinaryOp {
         lhs: ExprId,
         rhs: ExprId,
@@ -204,6 +262,7 @@ pub enum Expr {
     Break {
         expr: Option<ExprId>,
     },
+    Continue,
     Loop {
         body: ExprId,
     },
@@ -211,9 +270,46 @@ pub enum Expr {
         condition: ExprId,
         body: ExprId,
     },
+    /// A `for` loop over a range, e.g. `for i in start..end {}`, desugared to a counted loop.
+    For {
+        pat: PatId,
+        start: ExprId,
+        end: ExprId,
+        /// Whether the range includes its upper bound, e.g. `start..=end`
+        inclusive: bool,
+        body: ExprId,
+    },
+    /// A `match` expression, e.g. `match state { State::Idle => 0, _ => 1 }`. The arms are
+    /// checked in order, the first arm whose pattern matches the value of `expr` is evaluated.
+    Match {
+        expr: ExprId,
+        arms: Vec<MatchArm>,
+    },
+    /// A closure, e.g. `|a, b| a < b`. Closures cannot capture variables, so a closure is a
+    /// function pointer to its body, which is generated as a separate function.
+    Closure {
+        args: Vec<PatId>,
+        arg_types: Vec<Option<TypeRefId>>,
+        ret_type: Option<TypeRefId>,
+        body: ExprId,
+    },
     Literal(Literal),
 }
 
+/// An arm of a match expression, e.g. `State::Idle => 0`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MatchArm {
+    pub pat: PatId,
+    pub expr: ExprId,
+}
+
+/// A field initializer of a record literal, e.g. `a: 1` in `Foo { a: 1 }`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RecordLitField {
+    pub name: Name,
+    pub expr: ExprId,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum BinaryOp {
     LogicOp(LogicOp),
@@ -246,7 +342,9 @@ pub enum ArithOp {
     Multiply,
     Subtract,
     Divide,
-    //Remainder,
+    Remainder,
+    LeftShift,
+    RightShift,
     //Power,
 }
 
@@ -276,13 +374,36 @@ impl Expr {
                     f(*arg);
                 }
             }
+            Expr::MethodCall { receiver, args, .. } => {
+                f(*receiver);
+                for arg in args {
+                    f(*arg);
+                }
+            }
             Expr::BinaryOp { lhs, rhs, .. } => {
                 f(*lhs);
                 f(*rhs);
             }
-            Expr::UnaryOp { expr, .. } => {
+            Expr::UnaryOp { expr, .. } | Expr::Cast { expr, .. } => {
                 f(*expr);
             }
+            Expr::Field { expr, .. } => {
+                f(*expr);
+            }
+            Expr::RecordLit { fields, .. } => {
+                for field in fields {
+                    f(field.expr);
+                }
+            }
+            Expr::Tuple { exprs } | Expr::Array { exprs } => {
+                for expr in exprs {
+                    f(*expr);
+                }
+            }
+            Expr::Index { base, index } => {
+                f(*base);
+                f(*index);
+            }
             Expr::Literal(_) => {}
             Expr::If {
                 condition,
@@ -305,6 +426,14 @@ impl Expr {
                     f(*expr);
                 }
             }
+            Expr::Continue => {}
+            Expr::For {
+                start, end, body, ..
+            } => {
+                f(*start);
+                f(*end);
+                f(*body);
+            }
             Expr::Loop { body } => {
                 f(*body);
             }
@@ -312,6 +441,15 @@ impl Expr {
                 f(*condition);
                 f(*body);
             }
+            Expr::Match { expr, arms } => {
+                f(*expr);
+                for arm in arms {
+                    f(arm.expr);
+                }
+            }
+            Expr::Closure { body, .. } => {
+                f(*body);
+            }
         }
     }
 }
@@ -319,15 +457,20 @@ impl Expr {
 /// Similar to `ast::PatKind`
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Pat {
-    Missing,             // Indicates an error
-    Wild,                // `_`
-    Path(Path),          // E.g. `foo::bar`
-    Bind { name: Name }, // E.g. `a`
+    Missing,                               // Indicates an error
+    Wild,                                  // `_`
+    Path(Path),                            // E.g. `foo::bar`
+    Bind { name: Name, is_mutable: bool }, // E.g. `a` or `mut a`
+    Lit(ExprId),                           // E.g. `1`, `-1` or `true`
 }
 
 impl Pat {
-    pub fn walk_child_pats(&self, mut _f: impl FnMut(PatId)) {
-        unreachable!()
+    /// Calls `f` for every sub-pattern of the pattern. None of the patterns have sub-patterns, so
+    /// e.g. `_` in `for _ in 0..n` binds nothing.
+    pub fn walk_child_pats(&self, _f: impl FnMut(PatId)) {
+        match self {
+            Pat::Missing | Pat::Wild | Pat::Path(_) | Pat::Bind { .. } | Pat::Lit(_) => {}
+        }
     }
 }
 
@@ -397,6 +540,19 @@ where
 
     fn collect_fn_body(&mut self, node: &ast::FunctionDef) {
         if let Some(param_list) = node.param_list() {
+            // The `self` parameter is bound to the receiver of a method and has the type of the
+            // impl block, which doesn't have a source in the function
+            if let Some(self_param) = param_list.self_param() {
+                let impl_block = node.syntax().parent().and_then(ast::ImplBlock::cast);
+                let param_pat = self.pats.alloc(Pat::Bind {
+                    name: SELF_PARAM,
+                    is_mutable: self_param.is_mutable(),
+                });
+                let param_type = self
+                    .type_ref_builder
+                    .alloc_self_type(impl_block.and_then(|it| it.type_ref()).as_ref());
+                self.params.push((param_pat, param_type));
+            }
             for param in param_list.params() {
                 let pat = if let Some(pat) = param.pat() {
                     pat
@@ -422,6 +578,16 @@ where
         self.ret_type = Some(ret_type);
     }
 
+    /// Collects the initializer of a const or static item, which is a body without parameters
+    /// that returns a value of the declared type.
+    fn collect_initializer_body(&mut self, body: Option<ast::Expr>, ty: Option<ast::TypeRef>) {
+        let body = self.collect_expr_opt(body);
+        self.body_expr = Some(body);
+
+        let ty = self.type_ref_builder.alloc_from_node_opt(ty.as_ref());
+        self.ret_type = Some(ty);
+    }
+
     fn collect_block_opt(&mut self, block: Option<ast::BlockExpr>) -> ExprId {
         if let Some(block) = block {
             self.collect_block(block)
@@ -477,19 +643,51 @@ where
         match expr.kind() {
             ast::ExprKind::LoopExpr(expr) => self.collect_loop(expr),
             ast::ExprKind::WhileExpr(expr) => self.collect_while(expr),
+            ast::ExprKind::ForExpr(expr) => self.collect_for(expr),
+            ast::ExprKind::MatchExpr(expr) => self.collect_match(expr),
+            ast::ExprKind::ClosureExpr(expr) => self.collect_closure(expr),
+            // Ranges outside of `for` loops are reported during validation
+            ast::ExprKind::RangeExpr(_) => self.alloc_expr(Expr::Missing, syntax_ptr),
             ast::ExprKind::ReturnExpr(r) => self.collect_return(r),
             ast::ExprKind::BreakExpr(r) => self.collect_break(r),
+            ast::ExprKind::ContinueExpr(_) => self.alloc_expr(Expr::Continue, syntax_ptr),
             ast::ExprKind::BlockExpr(b) => self.collect_block(b),
             ast::ExprKind::Literal(e) => {
                 let lit = match e.kind() {
                     ast::LiteralKind::Bool => Literal::Bool(e.token().kind() == T![true]),
-                    ast::LiteralKind::IntNumber => {
-                        Literal::Int(e.syntax().text().to_string().parse().unwrap())
+                    ast::LiteralKind::IntNumber { suffix } => {
+                        let radix = e.radix();
+                        match suffix.as_ref().and_then(|s| FloatTy::from_suffix(s)) {
+                            // A decimal integer with a floating point suffix (e.g. `1f32`) is a
+                            // float. Other radixes with such a suffix are reported during
+                            // validation.
+                            Some(float_ty) if radix == Radix::Decimal => {
+                                Literal::Float(LiteralFloat {
+                                    value: e.value_text().replace('_', "").parse().unwrap(),
+                                    suffix: Some(float_ty),
+                                })
+                            }
+                            // Values that do not fit are reported during type inference, and
+                            // invalid digits during validation
+                            _ => Literal::Int(LiteralInt {
+                                value: match e.int_value() {
+                                    Ok(value) => value,
+                                    Err(IntLiteralError::Overflow) => u128::max_value(),
+                                    Err(_) => 0,
+                                },
+                                suffix: suffix.and_then(|s| IntTy::from_suffix(&s)),
+                                radix,
+                            }),
+                        }
                     }
-                    ast::LiteralKind::FloatNumber => {
-                        Literal::Float(e.syntax().text().to_string().parse().unwrap())
+                    ast::LiteralKind::FloatNumber { suffix } => Literal::Float(LiteralFloat {
+                        value: e.value_text().replace('_', "").parse().unwrap(),
+                        suffix: suffix.and_then(|s| FloatTy::from_suffix(&s)),
+                    }),
+                    // Malformed strings are reported during validation
+                    ast::LiteralKind::String => {
+                        Literal::String(e.string_value().unwrap_or_default())
                     }
-                    ast::LiteralKind::String => Literal::String(Default::default()),
                 };
                 self.alloc_expr(Expr::Literal(lit), syntax_ptr)
             }
@@ -515,7 +713,11 @@ where
                         | op @ BinOp::LessEqual
                         | op @ BinOp::Greater
                         | op @ BinOp::GreatEqual
-                        //| op @ BinOp::Remainder
+                        | op @ BinOp::BooleanAnd
+                        | op @ BinOp::BooleanOr
+                        | op @ BinOp::Remainder
+                        | op @ BinOp::LeftShift
+                        | op @ BinOp::RightShift
                         //| op @ BinOp::Power
                         => {
                             let op = match op {
@@ -529,7 +731,11 @@ where
                                 BinOp::LessEqual => BinaryOp::CmpOp(CmpOp::Ord { ordering: Ordering::Less, strict: false } ),
                                 BinOp::Greater => BinaryOp::CmpOp(CmpOp::Ord { ordering: Ordering::Greater, strict: true } ),
                                 BinOp::GreatEqual => BinaryOp::CmpOp(CmpOp::Ord { ordering: Ordering::Greater, strict: false } ),
-                                //BinOp::Remainder => BinaryOp::ArithOp(ArithOp::Remainder),
+                                BinOp::BooleanAnd => BinaryOp::LogicOp(LogicOp::And),
+                                BinOp::BooleanOr => BinaryOp::LogicOp(LogicOp::Or),
+                                BinOp::Remainder => BinaryOp::ArithOp(ArithOp::Remainder),
+                                BinOp::LeftShift => BinaryOp::ArithOp(ArithOp::LeftShift),
+                                BinOp::RightShift => BinaryOp::ArithOp(ArithOp::RightShift),
                                 //BinOp::Power => BinaryOp::ArithOp(ArithOp::Power),
                                 _ => unreachable!(),
                             };
@@ -548,7 +754,8 @@ where
                         | op @ BinOp::AddAssign
                         | op @ BinOp::SubtractAssign
                         | op @ BinOp::MultiplyAssign
-                        | op @ BinOp::DivideAssign => {
+                        | op @ BinOp::DivideAssign
+                        | op @ BinOp::RemainderAssign => {
 
                             let assign_op = match op {
                                 BinOp::Assign => None,
@@ -556,6 +763,7 @@ where
                                 BinOp::SubtractAssign => Some(ArithOp::Subtract),
                                 BinOp::MultiplyAssign => Some(ArithOp::Multiply),
                                 BinOp::DivideAssign => Some(ArithOp::Divide),
+                                BinOp::RemainderAssign => Some(ArithOp::Remainder),
                                 _ => unreachable!("invalid assignment operator")
                             } ;
 
@@ -613,6 +821,26 @@ where
                 self.source_map.expr_map.insert(syntax_ptr, inner);
                 inner
             }
+            ast::ExprKind::TupleExpr(e) => {
+                let exprs = e.exprs().map(|expr| self.collect_expr(expr)).collect();
+                self.alloc_expr(Expr::Tuple { exprs }, syntax_ptr)
+            }
+            ast::ExprKind::ArrayExpr(e) => {
+                let exprs = e.exprs().map(|expr| self.collect_expr(expr)).collect();
+                self.alloc_expr(Expr::Array { exprs }, syntax_ptr)
+            }
+            ast::ExprKind::IndexExpr(e) => {
+                let base = self.collect_expr_opt(e.base());
+                let index = self.collect_expr_opt(e.index());
+                self.alloc_expr(Expr::Index { base, index }, syntax_ptr)
+            }
+            ast::ExprKind::CastExpr(e) => {
+                let expr = self.collect_expr_opt(e.expr());
+                let type_ref = self
+                    .type_ref_builder
+                    .alloc_from_node_opt(e.type_ref().as_ref());
+                self.alloc_expr(Expr::Cast { expr, type_ref }, syntax_ptr)
+            }
             ast::ExprKind::CallExpr(e) => {
                 let callee = self.collect_expr_opt(e.expr());
                 let args = if let Some(arg_list) = e.arg_list() {
@@ -622,6 +850,65 @@ where
                 };
                 self.alloc_expr(Expr::Call { callee, args }, syntax_ptr)
             }
+            ast::ExprKind::MethodCallExpr(e) => {
+                let receiver = self.collect_expr_opt(e.expr());
+                let method_name = e
+                    .name_ref()
+                    .map(|nr| nr.as_name())
+                    .unwrap_or_else(Name::missing);
+                let args = if let Some(arg_list) = e.arg_list() {
+                    arg_list.args().map(|e| self.collect_expr(e)).collect()
+                } else {
+                    Vec::new()
+                };
+                self.alloc_expr(
+                    Expr::MethodCall {
+                        receiver,
+                        method_name,
+                        args,
+                    },
+                    syntax_ptr,
+                )
+            }
+            ast::ExprKind::FieldExpr(e) => {
+                let expr = self.collect_expr_opt(e.expr());
+                let name = match e.field_access() {
+                    Some(ast::FieldKind::Name(nr)) => nr.as_name(),
+                    Some(ast::FieldKind::Index(idx)) => idx
+                        .text()
+                        .parse()
+                        .map(Name::new_tuple_field)
+                        .unwrap_or_else(|_| Name::missing()),
+                    None => Name::missing(),
+                };
+                self.alloc_expr(Expr::Field { expr, name }, syntax_ptr)
+            }
+            ast::ExprKind::RecordLit(e) => {
+                let path = e.path().and_then(Path::from_ast);
+                let mut field_ptrs = Vec::new();
+                let fields = if let Some(field_list) = e.record_field_list() {
+                    field_list
+                        .fields()
+                        .map(|field| {
+                            field_ptrs.push(AstPtr::new(&field));
+                            RecordLitField {
+                                name: field
+                                    .name_ref()
+                                    .map(|nr| nr.as_name())
+                                    .unwrap_or_else(Name::missing),
+                                expr: self.collect_expr_opt(field.expr()),
+                            }
+                        })
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                let res = self.alloc_expr(Expr::RecordLit { path, fields }, syntax_ptr);
+                for (i, ptr) in field_ptrs.into_iter().enumerate() {
+                    self.source_map.field_map.insert((res, i), ptr);
+                }
+                res
+            }
         }
     }
 
@@ -647,9 +934,18 @@ where
                     .name()
                     .map(|nr| nr.as_name())
                     .unwrap_or_else(Name::missing);
-                Pat::Bind { name }
+                Pat::Bind {
+                    name,
+                    is_mutable: bp.is_mutable(),
+                }
             }
             ast::PatKind::PlaceholderPat(_) => Pat::Wild,
+            ast::PatKind::LiteralPat(lp) => Pat::Lit(self.collect_expr_opt(lp.expr())),
+            ast::PatKind::PathPat(pp) => pp
+                .path()
+                .and_then(Path::from_ast)
+                .map(Pat::Path)
+                .unwrap_or(Pat::Missing),
         };
         let ptr = AstPtr::new(&pat);
         self.alloc_pat(pattern, ptr)
@@ -680,6 +976,90 @@ where
         self.alloc_expr(Expr::While { condition, body }, syntax_node_ptr)
     }
 
+    fn collect_for(&mut self, expr: ast::ForExpr) -> ExprId {
+        let syntax_node_ptr = AstPtr::new(&expr.clone().into());
+        let pat = self.collect_pat_opt(expr.pat());
+
+        // Iterables other than ranges are reported during validation
+        let range = expr.iterable().and_then(|iterable| match iterable.kind() {
+            ast::ExprKind::RangeExpr(range) => Some(range),
+            _ => None,
+        });
+        let (start, end, inclusive) = match range {
+            Some(range) => (
+                self.collect_expr_opt(range.start()),
+                self.collect_expr_opt(range.end()),
+                range.is_inclusive(),
+            ),
+            None => (self.missing_expr(), self.missing_expr(), false),
+        };
+
+        let body = self.collect_block_opt(expr.loop_body());
+        self.alloc_expr(
+            Expr::For {
+                pat,
+                start,
+                end,
+                inclusive,
+                body,
+            },
+            syntax_node_ptr,
+        )
+    }
+
+    fn collect_match(&mut self, expr: ast::MatchExpr) -> ExprId {
+        let syntax_node_ptr = AstPtr::new(&expr.clone().into());
+        let scrutinee = self.collect_expr_opt(expr.expr());
+        let arms = if let Some(arm_list) = expr.match_arm_list() {
+            arm_list
+                .arms()
+                .map(|arm| MatchArm {
+                    pat: self.collect_pat_opt(arm.pat()),
+                    expr: self.collect_expr_opt(arm.expr()),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        self.alloc_expr(
+            Expr::Match {
+                expr: scrutinee,
+                arms,
+            },
+            syntax_node_ptr,
+        )
+    }
+
+    fn collect_closure(&mut self, expr: ast::ClosureExpr) -> ExprId {
+        let syntax_node_ptr = AstPtr::new(&expr.clone().into());
+        let mut args = Vec::new();
+        let mut arg_types = Vec::new();
+        if let Some(param_list) = expr.param_list() {
+            for param in param_list.params() {
+                args.push(self.collect_pat_opt(param.pat()));
+                arg_types.push(
+                    param
+                        .ascribed_type()
+                        .map(|t| self.type_ref_builder.alloc_from_node(&t)),
+                );
+            }
+        }
+        let ret_type = expr
+            .ret_type()
+            .and_then(|rt| rt.type_ref())
+            .map(|t| self.type_ref_builder.alloc_from_node(&t));
+        let body = self.collect_expr_opt(expr.body());
+        self.alloc_expr(
+            Expr::Closure {
+                args,
+                arg_types,
+                ret_type,
+                body,
+            },
+            syntax_node_ptr,
+        )
+    }
+
     fn finish(mut self) -> (Body, BodySourceMap) {
         let (type_refs, type_ref_source_map) = self.type_ref_builder.finish();
         let body = Body {
@@ -710,6 +1090,16 @@ pub(crate) fn body_with_source_map_query(
             collector = ExprCollector::new(def, src.file_id, db);
             collector.collect_fn_body(&src.ast)
         }
+        DefWithBody::Const(ref c) => {
+            let src = c.source(db);
+            collector = ExprCollector::new(def, src.file_id, db);
+            collector.collect_initializer_body(src.ast.body(), src.ast.ascribed_type())
+        }
+        DefWithBody::Static(ref s) => {
+            let src = s.source(db);
+            collector = ExprCollector::new(def, src.file_id, db);
+            collector.collect_initializer_body(src.ast.body(), src.ast.ascribed_type())
+        }
     }
 
     let (body, source_map) = collector.finish();
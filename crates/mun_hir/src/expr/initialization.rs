@@ -0,0 +1,358 @@
+//! Definite initialization analysis, which checks that a local binding that is declared without a
+//! value, e.g. `let a: int;`, is assigned a value on every path through the function before it is
+//! used. Code generation relies on this, as the memory of such a binding is not initialized.
+
+use crate::{
+    code_model::{DefWithBody, Function},
+    diagnostics::{CannotAssignToImmutable, DiagnosticSink, PossiblyUninitializedVariable},
+    expr::{resolver_for_expr, BinaryOp, Body, BodySourceMap, Expr, ExprId, Pat, PatId, Statement},
+    resolve::Resolution,
+    ty::InferenceResult,
+    HirDatabase, Name,
+};
+use rustc_hash::FxHashSet;
+use std::mem;
+use std::sync::Arc;
+
+/// The bindings that are declared without a value that may be uninitialized and that may be
+/// initialized at a point in a function. The state of a point that is never reached is `None`.
+#[derive(Clone, Default, PartialEq, Eq)]
+struct State {
+    maybe_uninit: FxHashSet<PatId>,
+    maybe_init: FxHashSet<PatId>,
+}
+
+impl State {
+    /// Returns the state of the point where two paths join.
+    fn join(a: Option<State>, b: Option<State>) -> Option<State> {
+        match (a, b) {
+            (Some(mut a), Some(b)) => {
+                a.maybe_uninit.extend(b.maybe_uninit);
+                a.maybe_init.extend(b.maybe_init);
+                Some(a)
+            }
+            (a, None) => a,
+            (None, b) => b,
+        }
+    }
+}
+
+/// The states in which the loop that is being checked is left by a `break` and continued by a
+/// `continue`.
+#[derive(Default)]
+struct ActiveLoop {
+    breaks: Option<State>,
+    continues: Option<State>,
+}
+
+pub(crate) struct InitializationChecker<'a, 'b, D: HirDatabase> {
+    db: &'a D,
+    body: Arc<Body>,
+    source_map: Arc<BodySourceMap>,
+    infer: Arc<InferenceResult>,
+    sink: &'a mut DiagnosticSink<'b>,
+
+    /// The state at the point of the function that is being checked
+    state: Option<State>,
+
+    /// The loops that contain the point that is being checked, the innermost loop is last
+    loops: Vec<ActiveLoop>,
+
+    /// The bindings that are declared without a value
+    deferred_bindings: FxHashSet<PatId>,
+
+    /// The bindings of which a use is reported, which isn't reported again for its other uses
+    reported: FxHashSet<PatId>,
+
+    /// Whether diagnostics are reported. They are not while the state at the start of a loop is
+    /// determined, during which the body of the loop is checked multiple times.
+    report: bool,
+}
+
+impl<'a, 'b, D: HirDatabase> InitializationChecker<'a, 'b, D> {
+    pub(crate) fn new(
+        db: &'a D,
+        func: Function,
+        infer: Arc<InferenceResult>,
+        sink: &'a mut DiagnosticSink<'b>,
+    ) -> Self {
+        let owner = DefWithBody::from(func);
+        InitializationChecker {
+            db,
+            body: owner.body(db),
+            source_map: owner.body_source_map(db),
+            infer,
+            sink,
+            state: Some(State::default()),
+            loops: Vec::new(),
+            deferred_bindings: FxHashSet::default(),
+            reported: FxHashSet::default(),
+            report: true,
+        }
+    }
+
+    pub(crate) fn check_body(&mut self) {
+        let body_expr = self.body.body_expr();
+        self.check_expr(body_expr);
+    }
+
+    /// Checks the uses of bindings in `expr` and updates the state to the point after it.
+    fn check_expr(&mut self, expr: ExprId) {
+        let body = Arc::clone(&self.body); // avoid borrow checker problem
+        match &body[expr] {
+            Expr::Path(_) => {
+                if let Some(binding) = self.local_binding(expr) {
+                    self.check_use(expr, binding);
+                }
+            }
+            Expr::BinaryOp {
+                lhs,
+                rhs,
+                op: Some(BinaryOp::Assignment { op: None }),
+            } => {
+                self.check_expr(*rhs);
+                match self.local_binding(*lhs) {
+                    Some(binding) => self.assign(*lhs, binding),
+                    None => self.check_expr(*lhs),
+                }
+            }
+            Expr::BinaryOp {
+                lhs,
+                rhs,
+                op: Some(BinaryOp::LogicOp(_)),
+            } => {
+                // The right-hand side is only evaluated depending on the value of the left-hand
+                // side
+                self.check_expr(*lhs);
+                let state = self.state.clone();
+                self.check_expr(*rhs);
+                self.state = State::join(state, self.state.take());
+            }
+            Expr::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.check_expr(*condition);
+                let state = self.state.clone();
+                self.check_expr(*then_branch);
+                let then_state = mem::replace(&mut self.state, state);
+                if let Some(else_branch) = else_branch {
+                    self.check_expr(*else_branch);
+                }
+                self.state = State::join(then_state, self.state.take());
+            }
+            Expr::Match { expr, arms } => {
+                self.check_expr(*expr);
+                let state = self.state.take();
+                for arm in arms {
+                    let arm_state = mem::replace(&mut self.state, state.clone());
+                    self.check_expr(arm.expr);
+                    self.state = State::join(arm_state, self.state.take());
+                }
+            }
+            Expr::Block { statements, tail } => {
+                for statement in statements {
+                    match statement {
+                        Statement::Let {
+                            initializer: Some(initializer),
+                            ..
+                        } => self.check_expr(*initializer),
+                        Statement::Let {
+                            pat,
+                            initializer: None,
+                            ..
+                        } => self.declare(*pat),
+                        Statement::Expr(expr) => self.check_expr(*expr),
+                    }
+                }
+                if let Some(tail) = tail {
+                    self.check_expr(*tail);
+                }
+            }
+            Expr::Return { expr } => {
+                if let Some(expr) = expr {
+                    self.check_expr(*expr);
+                }
+                self.state = None;
+            }
+            Expr::Break { expr } => {
+                if let Some(expr) = expr {
+                    self.check_expr(*expr);
+                }
+                let state = self.state.take();
+                if let Some(active_loop) = self.loops.last_mut() {
+                    active_loop.breaks = State::join(active_loop.breaks.take(), state);
+                }
+            }
+            Expr::Continue => {
+                let state = self.state.take();
+                if let Some(active_loop) = self.loops.last_mut() {
+                    active_loop.continues = State::join(active_loop.continues.take(), state);
+                }
+            }
+            Expr::Loop { body } => self.check_loop(None, *body, false),
+            Expr::While { condition, body } => self.check_loop(Some(*condition), *body, true),
+            Expr::For {
+                start, end, body, ..
+            } => {
+                self.check_expr(*start);
+                self.check_expr(*end);
+                self.check_loop(None, *body, true);
+            }
+            Expr::Closure { body, .. } => {
+                // The body of a closure is a function of its own, which is checked separately as
+                // it cannot use the bindings of the enclosing function
+                let state = self.state.replace(State::default());
+                let loops = mem::replace(&mut self.loops, Vec::new());
+                self.check_expr(*body);
+                self.state = state;
+                self.loops = loops;
+            }
+            other => other.walk_child_exprs(|child| self.check_expr(child)),
+        }
+
+        // The code after an expression that never returns is never reached
+        if self.infer[expr].is_never() {
+            self.state = None;
+        }
+    }
+
+    /// Checks a loop, of which the `body` is executed repeatedly. The `condition` of a `while` loop
+    /// is evaluated before every execution of the body. A `while` or `for` loop also ends without
+    /// a `break`, which is indicated by `ends_without_break`.
+    fn check_loop(&mut self, condition: Option<ExprId>, body: ExprId, ends_without_break: bool) {
+        // The start of an iteration is reached from before the loop and from the end of every
+        // iteration. Its state is determined by checking the body until the state doesn't change
+        // anymore, which happens as the state only grows.
+        let report = mem::replace(&mut self.report, false);
+        let entry = self.state.take();
+        let mut start = entry.clone();
+        loop {
+            self.state = start.clone();
+            let (_, active_loop) = self.check_iteration(condition, body);
+            let next = State::join(
+                State::join(entry.clone(), self.state.take()),
+                active_loop.continues,
+            );
+            if next == start {
+                break;
+            }
+            start = next;
+        }
+        self.report = report;
+
+        // Check the loop again from the final start state, in which diagnostics are reported
+        self.state = start;
+        let (condition_state, active_loop) = self.check_iteration(condition, body);
+        self.state = if ends_without_break {
+            State::join(condition_state, active_loop.breaks)
+        } else {
+            active_loop.breaks
+        };
+    }
+
+    /// Checks a single iteration of a loop. Returns the state after the `condition`, if any, and
+    /// the states in which the loop is left and continued.
+    fn check_iteration(
+        &mut self,
+        condition: Option<ExprId>,
+        body: ExprId,
+    ) -> (Option<State>, ActiveLoop) {
+        if let Some(condition) = condition {
+            self.check_expr(condition);
+        }
+        let condition_state = self.state.clone();
+        self.loops.push(ActiveLoop::default());
+        self.check_expr(body);
+        (condition_state, self.loops.pop().unwrap())
+    }
+
+    /// Returns the local binding that `expr` refers to, if it is a path to one.
+    fn local_binding(&self, expr: ExprId) -> Option<PatId> {
+        let path = match &self.body[expr] {
+            Expr::Path(path) => path,
+            _ => return None,
+        };
+        match resolver_for_expr(self.body.clone(), self.db, expr)
+            .resolve_path_without_assoc_items(self.db, path)
+            .take_values()
+        {
+            Some(Resolution::LocalBinding(pat)) => Some(pat),
+            _ => None,
+        }
+    }
+
+    /// Declares the bindings of a `let` statement without a value, which are uninitialized.
+    fn declare(&mut self, pat: PatId) {
+        if let Pat::Bind { .. } = &self.body[pat] {
+            self.deferred_bindings.insert(pat);
+            if let Some(state) = &mut self.state {
+                state.maybe_uninit.insert(pat);
+                state.maybe_init.remove(&pat);
+            }
+        }
+    }
+
+    /// Assigns a value to `binding` by the assignment to `lhs`. An immutable binding may only be
+    /// assigned a value once.
+    fn assign(&mut self, lhs: ExprId, binding: PatId) {
+        if !self.deferred_bindings.contains(&binding) {
+            return;
+        }
+        let state = match &mut self.state {
+            Some(state) => state,
+            None => return,
+        };
+        let is_mutable = match &self.body[binding] {
+            Pat::Bind { is_mutable, .. } => *is_mutable,
+            _ => true,
+        };
+        let reassigned = !is_mutable && state.maybe_init.contains(&binding);
+        state.maybe_uninit.remove(&binding);
+        state.maybe_init.insert(binding);
+
+        if reassigned && self.report {
+            if let (Some(lhs), Some(pat)) = (
+                self.source_map.expr_syntax(lhs),
+                self.source_map.pat_syntax(binding),
+            ) {
+                self.sink.push(CannotAssignToImmutable {
+                    file: lhs.file_id,
+                    lhs: lhs.ast.syntax_node_ptr(),
+                    binding: pat.ast.syntax_node_ptr(),
+                    name: self.binding_name(binding),
+                });
+            }
+        }
+    }
+
+    /// Reports the use of `binding` by `expr` if the binding may be uninitialized.
+    fn check_use(&mut self, expr: ExprId, binding: PatId) {
+        let maybe_uninit = match &self.state {
+            Some(state) => state.maybe_uninit.contains(&binding),
+            None => false,
+        };
+        if !maybe_uninit || !self.report || !self.reported.insert(binding) {
+            return;
+        }
+        if let (Some(use_ptr), Some(pat)) = (
+            self.source_map.expr_syntax(expr),
+            self.source_map.pat_syntax(binding),
+        ) {
+            self.sink.push(PossiblyUninitializedVariable {
+                file: use_ptr.file_id,
+                expr: use_ptr.ast.syntax_node_ptr(),
+                binding: pat.ast.syntax_node_ptr(),
+                name: self.binding_name(binding),
+            });
+        }
+    }
+
+    fn binding_name(&self, binding: PatId) -> Name {
+        match &self.body[binding] {
+            Pat::Bind { name, .. } => name.clone(),
+            _ => Name::missing(),
+        }
+    }
+}
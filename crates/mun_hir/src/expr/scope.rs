@@ -154,6 +154,30 @@ fn compute_expr_scopes(expr: ExprId, body: &Body, scopes: &mut ExprScopes, scope
         Expr::Block { statements, tail } => {
             compute_block_scopes(&statements, *tail, body, scopes, scope);
         }
+        Expr::For {
+            pat,
+            start,
+            end,
+            body: loop_body,
+            ..
+        } => {
+            compute_expr_scopes(*start, body, scopes, scope);
+            compute_expr_scopes(*end, body, scopes, scope);
+            // The loop variable is only visible in the body of the loop
+            let scope = scopes.new_scope(scope);
+            scopes.add_bindings(body, scope, *pat);
+            compute_expr_scopes(*loop_body, body, scopes, scope);
+        }
+        Expr::Closure {
+            args,
+            body: closure_body,
+            ..
+        } => {
+            // The parameters of a closure are only visible in its body
+            let scope = scopes.new_scope(scope);
+            scopes.add_params_bindings(scope, args.iter());
+            compute_expr_scopes(*closure_body, body, scopes, scope);
+        }
         e => e.walk_child_exprs(|e| compute_expr_scopes(e, body, scopes, scope)),
     };
 }
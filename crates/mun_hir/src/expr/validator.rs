@@ -0,0 +1,305 @@
+//! Validation of function bodies that requires the result of type inference, e.g. checking that
+//! match expressions are exhaustive.
+
+use crate::{
+    code_model::{DefWithBody, Function},
+    diagnostics::{
+        DiagnosticSink, NonExhaustiveMatch, UnreachableCode, UnreachablePattern, UnusedVariable,
+    },
+    expr::{
+        initialization::InitializationChecker, resolver_for_expr, Body, BodySourceMap, Expr,
+        ExprId, Literal, MatchArm, Pat, PatId, Statement, UnaryOp,
+    },
+    name::SELF_PARAM,
+    resolve::Resolution,
+    ty::InferenceResult,
+    HirDatabase, Ty, TypeCtor,
+};
+use mun_syntax::{ast, AstNode, SyntaxNodePtr, TextRange};
+use rustc_hash::FxHashSet;
+use std::sync::Arc;
+
+pub(crate) struct ExprValidator<'a, 'b: 'a> {
+    func: Function,
+    infer: Arc<InferenceResult>,
+    sink: &'a mut DiagnosticSink<'b>,
+}
+
+impl<'a, 'b> ExprValidator<'a, 'b> {
+    pub(crate) fn new(
+        func: Function,
+        infer: Arc<InferenceResult>,
+        sink: &'a mut DiagnosticSink<'b>,
+    ) -> ExprValidator<'a, 'b> {
+        ExprValidator { func, infer, sink }
+    }
+
+    pub(crate) fn validate_body(&mut self, db: &impl HirDatabase) {
+        let owner = DefWithBody::from(self.func);
+        let body = owner.body(db);
+        let source_map = owner.body_source_map(db);
+        let mut unreachable_code = Vec::new();
+        let mut used_bindings = FxHashSet::default();
+        let mut params: FxHashSet<PatId> = body.params().iter().map(|(pat, _)| *pat).collect();
+        for (id, expr) in body.exprs() {
+            match expr {
+                Expr::Path(path) => {
+                    let resolution = resolver_for_expr(body.clone(), db, id)
+                        .resolve_path_without_assoc_items(db, path)
+                        .take_values();
+                    if let Some(Resolution::LocalBinding(pat)) = resolution {
+                        used_bindings.insert(pat);
+                    }
+                }
+                Expr::Closure { args, .. } => params.extend(args.iter().copied()),
+                Expr::Match { expr, arms } => {
+                    self.validate_match(db, &body, &source_map, id, *expr, arms)
+                }
+                Expr::Block { statements, .. } => {
+                    if let Some(first) = self.first_unreachable_statement(statements) {
+                        unreachable_code.extend(unreachable_statements(db, &source_map, id, first));
+                    }
+                }
+                Expr::If {
+                    condition,
+                    then_branch,
+                    else_branch,
+                } => {
+                    let untaken_branch = match &body[*condition] {
+                        Expr::Literal(Literal::Bool(true)) => *else_branch,
+                        Expr::Literal(Literal::Bool(false)) => Some(*then_branch),
+                        _ => None,
+                    };
+                    if let Some(ptr) = untaken_branch.and_then(|e| source_map.expr_syntax(e)) {
+                        let ptr = ptr.ast.syntax_node_ptr();
+                        unreachable_code.push((ptr.range(), ptr));
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.report_unreachable_code(db, unreachable_code);
+        self.report_unused_variables(db, &body, &source_map, &used_bindings, &params);
+        InitializationChecker::new(db, self.func, self.infer.clone(), self.sink).check_body();
+    }
+
+    /// Warns about the bindings of local variables and parameters that are never used. Bindings
+    /// with a name that starts with an underscore and the `self` parameter are not reported, nor
+    /// are the parameters of extern functions, which don't have a body.
+    fn report_unused_variables(
+        &mut self,
+        db: &impl HirDatabase,
+        body: &Body,
+        source_map: &BodySourceMap,
+        used_bindings: &FxHashSet<PatId>,
+        params: &FxHashSet<PatId>,
+    ) {
+        if self.func.is_extern(db) {
+            return;
+        }
+        for (pat, binding) in body.pats() {
+            let name = match binding {
+                Pat::Bind { name, .. } => name,
+                _ => continue,
+            };
+            if used_bindings.contains(&pat)
+                || *name == SELF_PARAM
+                || name.to_string().starts_with('_')
+            {
+                continue;
+            }
+            if let Some(ptr) = source_map.pat_syntax(pat) {
+                self.sink.push(UnusedVariable {
+                    file: ptr.file_id,
+                    binding: ptr.ast.syntax_node_ptr(),
+                    name: name.clone(),
+                    is_param: params.contains(&pat),
+                });
+            }
+        }
+    }
+
+    /// Returns the index of the first statement of a block that is never executed, because a
+    /// statement before it never returns, e.g. a `return` or a `break`. If only the trailing
+    /// expression is never executed, the index is the number of statements. Type inference
+    /// identifies such statements by the never type, so code that is only dead after optimization
+    /// is not considered.
+    fn first_unreachable_statement(&self, statements: &[Statement]) -> Option<usize> {
+        statements
+            .iter()
+            .position(|statement| {
+                let expr = match statement {
+                    Statement::Let { initializer, .. } => *initializer,
+                    Statement::Expr(expr) => Some(*expr),
+                };
+                expr.map_or(false, |expr| self.infer[expr].is_never())
+            })
+            .map(|idx| idx + 1)
+    }
+
+    /// Warns about code that is never executed: the statements after a statement that never
+    /// returns and the branch of an `if` that is never taken, because its condition is the literal
+    /// `true` or `false`. Unreachable code within code that is already reported is not reported
+    /// again.
+    fn report_unreachable_code(
+        &mut self,
+        db: &impl HirDatabase,
+        unreachable_code: Vec<(TextRange, SyntaxNodePtr)>,
+    ) {
+        let file = DefWithBody::from(self.func).file_id(db);
+        let mut reported: Vec<(TextRange, SyntaxNodePtr)> = Vec::new();
+        for (range, code) in unreachable_code {
+            let is_nested = |other: &TextRange| other.is_subrange(&range) && *other != range;
+            reported.retain(|(other, _)| !is_nested(other));
+            if reported.iter().all(|(other, _)| !range.is_subrange(other)) {
+                reported.push((range, code));
+            }
+        }
+        reported.sort_by_key(|(range, _)| range.start());
+        for (range, code) in reported {
+            self.sink.push(UnreachableCode { file, code, range });
+        }
+    }
+
+    /// Verifies that every value of the matched expression is matched by an arm, and warns about
+    /// arms that can never be reached. Only the variants of enums and booleans can be matched
+    /// exhaustively without a wildcard, other types always require one.
+    fn validate_match(
+        &mut self,
+        db: &impl HirDatabase,
+        body: &Body,
+        source_map: &BodySourceMap,
+        match_expr: ExprId,
+        expr: ExprId,
+        arms: &[MatchArm],
+    ) {
+        let file = DefWithBody::from(self.func).file_id(db);
+        let ty = &self.infer[expr];
+
+        // The values that can be matched and how they are displayed, if they can be enumerated
+        let domain: Option<Vec<(i128, String)>> = match ty.as_simple() {
+            Some(TypeCtor::Bool) => Some(vec![(0, "false".to_string()), (1, "true".to_string())]),
+            Some(TypeCtor::Enum(e)) => Some(
+                e.variants(db)
+                    .into_iter()
+                    .map(|v| {
+                        let name = format!("{}::{}", e.name(db), v.name(db));
+                        (v.discriminant(db), name)
+                    })
+                    .collect(),
+            ),
+            // A value that cannot exist doesn't need to be matched, and the type of the matched
+            // expression could not be inferred if it is unknown, which is reported elsewhere
+            _ if *ty == Ty::Unknown || ty.is_never() => return,
+            _ => None,
+        };
+
+        let mut covered = FxHashSet::default();
+        let mut exhaustive = false;
+        let mut has_invalid_pattern = false;
+        for arm in arms {
+            // The value that the pattern matches, or `None` if it matches any value
+            let value = match &body[arm.pat] {
+                Pat::Wild => None,
+                Pat::Lit(lit) if self.infer[*lit] == *ty => literal_value(body, *lit),
+                Pat::Path(_) => self
+                    .infer
+                    .variant_resolution(arm.pat)
+                    .map(|variant| variant.discriminant(db)),
+                _ => None,
+            };
+            if value.is_none() && body[arm.pat] != Pat::Wild {
+                // Invalid patterns are reported during type inference
+                has_invalid_pattern = true;
+                continue;
+            }
+
+            let is_reachable = !exhaustive && value.map_or(true, |value| covered.insert(value));
+            if !is_reachable {
+                if let Some(pat) = source_map.pat_syntax(arm.pat) {
+                    self.sink.push(UnreachablePattern {
+                        file,
+                        pat: pat.ast.syntax_node_ptr(),
+                    });
+                }
+                continue;
+            }
+
+            exhaustive = match (value, &domain) {
+                (None, _) => true,
+                (Some(_), Some(domain)) => domain.iter().all(|(value, _)| covered.contains(value)),
+                (Some(_), None) => false,
+            };
+        }
+
+        if exhaustive || has_invalid_pattern {
+            return;
+        }
+        let missing = match domain {
+            Some(domain) => domain
+                .into_iter()
+                .filter(|(value, _)| !covered.contains(value))
+                .map(|(_, name)| name)
+                .collect(),
+            None => vec!["_".to_string()],
+        };
+        let expr = source_map
+            .expr_syntax(expr)
+            .or_else(|| source_map.expr_syntax(match_expr))
+            .unwrap()
+            .ast
+            .syntax_node_ptr();
+        self.sink.push(NonExhaustiveMatch {
+            file,
+            expr,
+            missing,
+        });
+    }
+}
+
+/// Returns the value of the literal of a literal pattern, e.g. `-1` or `true`.
+fn literal_value(body: &Body, expr: ExprId) -> Option<i128> {
+    match &body[expr] {
+        Expr::Literal(Literal::Int(lit)) => Some(lit.value as i128),
+        Expr::Literal(Literal::Bool(value)) => Some(*value as i128),
+        Expr::UnaryOp {
+            expr,
+            op: UnaryOp::Neg,
+        } => match &body[*expr] {
+            Expr::Literal(Literal::Int(lit)) => Some(-(lit.value as i128)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the range of the statements of `block` from the statement with index `first` onwards,
+/// including its trailing expression, and the first of these statements. Returns `None` if there
+/// are no such statements.
+fn unreachable_statements(
+    db: &impl HirDatabase,
+    source_map: &BodySourceMap,
+    block: ExprId,
+    first: usize,
+) -> Option<(TextRange, SyntaxNodePtr)> {
+    let ptr = source_map.expr_syntax(block)?;
+    let block = match ptr
+        .ast
+        .to_node(db.parse(ptr.file_id).tree().syntax())
+        .kind()
+    {
+        ast::ExprKind::BlockExpr(block) => block,
+        _ => return None,
+    };
+    let mut code = block
+        .statements()
+        .skip(first)
+        .map(|statement| statement.syntax().clone())
+        .chain(block.expr().map(|tail| tail.syntax().clone()));
+    let start = code.next()?;
+    let end = code.last().unwrap_or_else(|| start.clone());
+    Some((
+        TextRange::from_to(start.text_range().start(), end.text_range().end()),
+        SyntaxNodePtr::new(&start),
+    ))
+}
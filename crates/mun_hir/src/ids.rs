@@ -104,3 +104,81 @@ impl AstItemDef<ast::FunctionDef> for FunctionId {
         db.lookup_intern_function(self)
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StructId(salsa::InternId);
+impl_intern_key!(StructId);
+
+impl AstItemDef<ast::StructDef> for StructId {
+    fn intern(db: &impl DefDatabase, loc: ItemLoc<ast::StructDef>) -> Self {
+        db.intern_struct(loc)
+    }
+    fn lookup_intern(self, db: &impl DefDatabase) -> ItemLoc<ast::StructDef> {
+        db.lookup_intern_struct(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EnumId(salsa::InternId);
+impl_intern_key!(EnumId);
+
+impl AstItemDef<ast::EnumDef> for EnumId {
+    fn intern(db: &impl DefDatabase, loc: ItemLoc<ast::EnumDef>) -> Self {
+        db.intern_enum(loc)
+    }
+    fn lookup_intern(self, db: &impl DefDatabase) -> ItemLoc<ast::EnumDef> {
+        db.lookup_intern_enum(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConstId(salsa::InternId);
+impl_intern_key!(ConstId);
+
+impl AstItemDef<ast::ConstDef> for ConstId {
+    fn intern(db: &impl DefDatabase, loc: ItemLoc<ast::ConstDef>) -> Self {
+        db.intern_const(loc)
+    }
+    fn lookup_intern(self, db: &impl DefDatabase) -> ItemLoc<ast::ConstDef> {
+        db.lookup_intern_const(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StaticId(salsa::InternId);
+impl_intern_key!(StaticId);
+
+impl AstItemDef<ast::StaticDef> for StaticId {
+    fn intern(db: &impl DefDatabase, loc: ItemLoc<ast::StaticDef>) -> Self {
+        db.intern_static(loc)
+    }
+    fn lookup_intern(self, db: &impl DefDatabase) -> ItemLoc<ast::StaticDef> {
+        db.lookup_intern_static(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TypeAliasId(salsa::InternId);
+impl_intern_key!(TypeAliasId);
+
+impl AstItemDef<ast::TypeAliasDef> for TypeAliasId {
+    fn intern(db: &impl DefDatabase, loc: ItemLoc<ast::TypeAliasDef>) -> Self {
+        db.intern_type_alias(loc)
+    }
+    fn lookup_intern(self, db: &impl DefDatabase) -> ItemLoc<ast::TypeAliasDef> {
+        db.lookup_intern_type_alias(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ImplBlockId(salsa::InternId);
+impl_intern_key!(ImplBlockId);
+
+impl AstItemDef<ast::ImplBlock> for ImplBlockId {
+    fn intern(db: &impl DefDatabase, loc: ItemLoc<ast::ImplBlock>) -> Self {
+        db.intern_impl_block(loc)
+    }
+    fn lookup_intern(self, db: &impl DefDatabase) -> ItemLoc<ast::ImplBlock> {
+        db.lookup_intern_impl_block(self)
+    }
+}
@@ -7,19 +7,25 @@
 
 #[macro_use]
 mod arena;
+mod adt;
 mod code_model;
+mod const_eval;
 mod db;
 pub mod diagnostics;
 mod display;
+pub mod dump;
 mod expr;
 mod ids;
 mod input;
 pub mod line_index;
+pub mod lint;
 mod model;
+mod module_tree;
 mod name;
 mod name_resolution;
 mod path;
 mod raw;
+mod reachable;
 mod resolve;
 mod source_id;
 mod ty;
@@ -35,6 +41,7 @@ pub use salsa;
 pub use relative_path::{RelativePath, RelativePathBuf};
 
 pub use crate::{
+    const_eval::{fold_expr, ConstEvalError, ConstValue},
     db::{
         DefDatabase, DefDatabaseStorage, HirDatabase, HirDatabaseStorage, SourceDatabase,
         SourceDatabaseStorage,
@@ -42,16 +49,22 @@ pub use crate::{
     display::HirDisplay,
     expr::{
         resolver_for_expr, ArithOp, BinaryOp, Body, CmpOp, Expr, ExprId, ExprScopes, Literal,
-        LogicOp, Ordering, Pat, PatId, Statement,
+        LiteralFloat, LiteralInt, LogicOp, MatchArm, Ordering, Pat, PatId, RecordLitField,
+        Statement,
     },
     ids::ItemLoc,
     input::{FileId, SourceRoot, SourceRootId},
+    module_tree::ModuleTree,
     name::Name,
-    name_resolution::PerNs,
+    name_resolution::{Namespace, PerNs},
     path::{Path, PathKind},
     raw::RawItems,
+    reachable::functions_reachable_from,
     resolve::{Resolution, Resolver},
-    ty::{ApplicationTy, InferenceResult, Ty, TypeCtor},
+    ty::{
+        ApplicationTy, FloatBitness, FloatTy, FnSig, InferenceResult, IntBitness, IntTy,
+        Signedness, Substs, Ty, TypeCtor,
+    },
 };
 
 use crate::{
@@ -60,4 +73,12 @@ use crate::{
     source_id::{AstIdMap, FileAstId},
 };
 
-pub use self::code_model::{FnData, Function, Module, ModuleDef};
+pub use self::adt::{
+    EnumData, EnumVariantData, StructData, StructFieldData, StructKind, StructMemoryKind,
+    StructRepr,
+};
+pub use self::code_model::{
+    src::HasSource, BuiltinFunction, Const, ConstData, Enum, EnumVariant, FnData, Function,
+    ImplBlock, ImplBlockData, InlineAttr, Module, ModuleDef, Static, StaticData, Struct,
+    StructField, TypeAlias, TypeAliasData, Visibility,
+};
@@ -0,0 +1,210 @@
+//! Lints are the warnings of the compiler, each of which has a stable name by which its level can
+//! be configured: `#[allow(unused_variables)]` silences a lint for an item, `#[deny(dead_code)]`
+//! turns it into an error, and `#[warn(...)]` restores the warning. An attribute at the start of a
+//! file, e.g. `#![deny(warnings)]`, applies to the whole file. The attributes of an item override
+//! those of the file that contains it, which override the levels set on the command line.
+
+use crate::code_model::{attr_arg_name, attr_name};
+use crate::diagnostics::{Diagnostic, DiagnosticSink, MalformedAttribute, UnknownLint};
+use crate::{FileId, HirDatabase};
+use mun_syntax::{ast, ast::AttrsOwner, AstNode, SyntaxNodePtr};
+use rustc_hash::FxHashMap;
+
+/// The name of the group that contains every lint, e.g. in `#![deny(warnings)]`.
+pub const WARNINGS: &str = "warnings";
+
+/// A kind of warning, of which the level can be configured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+    /// Variables that are never used
+    UnusedVariables,
+    /// Private functions that are never called
+    DeadCode,
+    /// Code that is never executed
+    UnreachableCode,
+    /// Match arms that never match, because the arms before them match every value
+    UnreachablePatterns,
+    /// Floating point literals that are rounded to infinity or zero
+    OverflowingLiterals,
+    /// Names in lint attributes that are not lints
+    UnknownLints,
+}
+
+impl Lint {
+    /// All lints, in the order in which they are documented.
+    pub const ALL: &'static [Lint] = &[
+        Lint::UnusedVariables,
+        Lint::DeadCode,
+        Lint::UnreachableCode,
+        Lint::UnreachablePatterns,
+        Lint::OverflowingLiterals,
+        Lint::UnknownLints,
+    ];
+
+    /// Returns the name by which the level of the lint is configured, e.g. `dead_code`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::UnusedVariables => "unused_variables",
+            Lint::DeadCode => "dead_code",
+            Lint::UnreachableCode => "unreachable_code",
+            Lint::UnreachablePatterns => "unreachable_patterns",
+            Lint::OverflowingLiterals => "overflowing_literals",
+            Lint::UnknownLints => "unknown_lints",
+        }
+    }
+
+    /// Returns the lint with the name `name`, if there is one.
+    pub fn from_name(name: &str) -> Option<Lint> {
+        Lint::ALL.iter().copied().find(|lint| lint.name() == name)
+    }
+}
+
+/// How a lint is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintLevel {
+    /// The lint is not reported
+    Allow,
+    /// The lint is reported as a warning, which is the default
+    Warn,
+    /// The lint is reported as an error, so the build fails
+    Deny,
+}
+
+impl LintLevel {
+    /// Returns the level that the attribute `name` sets, e.g. `Deny` for `#[deny(dead_code)]`.
+    pub fn from_attr_name(name: &str) -> Option<LintLevel> {
+        match name {
+            "allow" => Some(LintLevel::Allow),
+            "warn" => Some(LintLevel::Warn),
+            "deny" => Some(LintLevel::Deny),
+            _ => None,
+        }
+    }
+
+    /// The forms of the lint attributes, used in diagnostics.
+    fn expected(self) -> &'static str {
+        match self {
+            LintLevel::Allow => "`#[allow(lint, ...)]`",
+            LintLevel::Warn => "`#[warn(lint, ...)]`",
+            LintLevel::Deny => "`#[deny(lint, ...)]`",
+        }
+    }
+}
+
+/// The levels of the lints in a scope. Lints of which the level is not set are warnings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintLevels {
+    levels: FxHashMap<Lint, LintLevel>,
+}
+
+impl LintLevels {
+    /// Returns the level of `lint`.
+    pub fn level(&self, lint: Lint) -> LintLevel {
+        self.levels.get(&lint).copied().unwrap_or(LintLevel::Warn)
+    }
+
+    /// Sets the level of `lint`.
+    pub fn set(&mut self, lint: Lint, level: LintLevel) {
+        self.levels.insert(lint, level);
+    }
+
+    /// Sets the level of the lint `name`, or of all lints if `name` is `warnings`. Returns false if
+    /// there is no lint with the name, in which case no level is changed.
+    pub fn set_by_name(&mut self, name: &str, level: LintLevel) -> bool {
+        if name == WARNINGS {
+            for &lint in Lint::ALL {
+                self.set(lint, level);
+            }
+            true
+        } else if let Some(lint) = Lint::from_name(name) {
+            self.set(lint, level);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Sets the levels of the lint attributes in `attrs`, in order. Other attributes, malformed
+    /// arguments and unknown lints are ignored; they are reported by `lint_attr_diagnostics`.
+    fn apply_attrs(&mut self, attrs: impl Iterator<Item = ast::Attr>) {
+        for attr in attrs {
+            let level = match attr_name(&attr).and_then(|name| LintLevel::from_attr_name(&name)) {
+                Some(level) => level,
+                None => continue,
+            };
+            let args = attr
+                .attr_arg_list()
+                .into_iter()
+                .flat_map(|args| args.args());
+            for name in args.filter_map(|arg| attr_arg_name(&arg)) {
+                self.set_by_name(&name, level);
+            }
+        }
+    }
+}
+
+/// Returns the level of the lint that `diagnostic` is reported for, in the scope of its location,
+/// or `None` if it isn't a lint. The levels of `command_line` are overridden by the lint
+/// attributes of the file of the diagnostic, which are overridden by those of the items that
+/// contain it, the innermost item last.
+pub fn lint_level(
+    db: &impl HirDatabase,
+    command_line: &LintLevels,
+    diagnostic: &dyn Diagnostic,
+) -> Option<LintLevel> {
+    let lint = diagnostic.lint()?;
+    let file = db.parse(diagnostic.file()).tree();
+    let mut levels = command_line.clone();
+    levels.apply_attrs(file.attrs());
+
+    let node = diagnostic.syntax_node_ptr().to_node(file.syntax());
+    let mut items: Vec<_> = node
+        .ancestors()
+        .filter_map(|node| match ast::FunctionDef::cast(node.clone()) {
+            Some(f) => Some(f.attrs()),
+            None => ast::StructDef::cast(node).map(|s| s.attrs()),
+        })
+        .collect();
+    while let Some(attrs) = items.pop() {
+        levels.apply_attrs(attrs);
+    }
+    Some(levels.level(lint))
+}
+
+/// Reports the lint attribute `attr`, which sets `level`, if it is malformed, and every name in it
+/// that is not a lint.
+pub(crate) fn lint_attr_diagnostics(
+    file: FileId,
+    attr: &ast::Attr,
+    name: String,
+    level: LintLevel,
+    sink: &mut DiagnosticSink,
+) {
+    let args: Vec<_> = attr
+        .attr_arg_list()
+        .into_iter()
+        .flat_map(|args| args.args())
+        .collect();
+    let names: Option<Vec<_>> = args.iter().map(attr_arg_name).collect();
+    let names = match names {
+        Some(names) if attr.attr_arg_list().is_some() => names,
+        _ => {
+            sink.push(MalformedAttribute {
+                file,
+                attr: SyntaxNodePtr::new(attr.syntax()),
+                name,
+                expected: level.expected(),
+            });
+            return;
+        }
+    };
+    for (arg, lint) in args.iter().zip(names) {
+        if lint != WARNINGS && Lint::from_name(&lint).is_none() {
+            sink.push(UnknownLint {
+                file,
+                arg: SyntaxNodePtr::new(arg.syntax()),
+                name: lint,
+            })
+        }
+    }
+}
@@ -31,21 +31,30 @@ impl salsa::Database for MockDatabase {
 impl MockDatabase {
     /// Creates a database from the given text.
     pub fn with_single_file(text: &str) -> (MockDatabase, FileId) {
+        let (db, file_ids) = MockDatabase::with_files(&[("main.mun", text)]);
+        (db, file_ids[0])
+    }
+
+    /// Creates a database with a file for every pair of relative path and text.
+    pub fn with_files(files: &[(&str, &str)]) -> (MockDatabase, Vec<FileId>) {
         let mut db: MockDatabase = Default::default();
 
         let mut source_root = SourceRoot::default();
         let source_root_id = SourceRootId(0);
 
-        let text = Arc::new(text.to_owned());
-        let rel_path = RelativePathBuf::from("main.mun");
-        let file_id = FileId(0);
-        db.set_file_relative_path(file_id, rel_path.clone());
-        db.set_file_text(file_id, Arc::new(text.to_string()));
-        db.set_file_source_root(file_id, source_root_id);
-        source_root.insert_file(rel_path, file_id);
+        let mut file_ids = Vec::new();
+        for (idx, (path, text)) in files.iter().enumerate() {
+            let rel_path = RelativePathBuf::from(*path);
+            let file_id = FileId(idx as u32);
+            db.set_file_relative_path(file_id, rel_path.clone());
+            db.set_file_text(file_id, Arc::new(text.to_string()));
+            db.set_file_source_root(file_id, source_root_id);
+            source_root.insert_file(rel_path, file_id);
+            file_ids.push(file_id);
+        }
 
         db.set_source_root(source_root_id, Arc::new(source_root));
-        (db, file_id)
+        (db, file_ids)
     }
 }
 
@@ -0,0 +1,104 @@
+//! Every file of a package is a module. The path of a module is derived from the path of its file
+//! relative to the source root of the package: `math.mun` is the module `math` and
+//! `shapes/square.mun` is the module `shapes::square`. A `mod.mun` file is the module of its
+//! directory, so `shapes/mod.mun` is the module `shapes`. The root module of a package is
+//! `main.mun`, or the only file of a package that consists of a single file. The items of the root
+//! module have no module path.
+
+use crate::name::AsName;
+use crate::{DefDatabase, FileId, Name, RelativePath, SourceRootId};
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+/// The file name of the root module of a package.
+const ROOT_FILE_NAME: &str = "main.mun";
+
+/// The file name of the module of a directory.
+const DIRECTORY_FILE_NAME: &str = "mod";
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ModuleTree {
+    /// The file of the root module, if the package has one
+    root: Option<FileId>,
+    /// The files of the package, ordered by their module paths
+    files: Vec<FileId>,
+    /// The module path of every file
+    paths: FxHashMap<FileId, Vec<Name>>,
+    /// The file of every module path. If multiple files have the same module path, e.g. `foo.mun`
+    /// and `foo/mod.mun`, the first one in path order defines the module.
+    modules: FxHashMap<Vec<Name>, FileId>,
+}
+
+impl ModuleTree {
+    pub(crate) fn module_tree_query(
+        db: &impl DefDatabase,
+        source_root: SourceRootId,
+    ) -> Arc<ModuleTree> {
+        let files: Vec<FileId> = db.source_root(source_root).files().collect();
+        let is_single_file = files.len() == 1;
+        let mut files: Vec<(FileId, Vec<Name>)> = files
+            .into_iter()
+            .map(|file_id| {
+                let rel_path = db.file_relative_path(file_id);
+                let path = if is_single_file || rel_path.as_str() == ROOT_FILE_NAME {
+                    Vec::new()
+                } else {
+                    module_path(&rel_path)
+                };
+                (file_id, path)
+            })
+            .collect();
+        files.sort_by(|(a_file, a_path), (b_file, b_path)| {
+            a_path.cmp(b_path).then_with(|| {
+                db.file_relative_path(*a_file)
+                    .cmp(&db.file_relative_path(*b_file))
+            })
+        });
+
+        let mut tree = ModuleTree::default();
+        for (file_id, path) in files {
+            if path.is_empty() && tree.root.is_none() {
+                tree.root = Some(file_id);
+            }
+            tree.modules.entry(path.clone()).or_insert(file_id);
+            tree.paths.insert(file_id, path);
+            tree.files.push(file_id);
+        }
+        Arc::new(tree)
+    }
+
+    /// Returns the file of the root module of the package, if it has one.
+    pub fn root(&self) -> Option<FileId> {
+        self.root
+    }
+
+    /// Returns the files of the package, ordered by their module paths.
+    pub fn files(&self) -> &[FileId] {
+        &self.files
+    }
+
+    /// Returns the path of the module of `file_id`, which is empty for the root module.
+    pub fn path(&self, file_id: FileId) -> &[Name] {
+        self.paths.get(&file_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the file of the module with the specified path.
+    pub fn file(&self, path: &[Name]) -> Option<FileId> {
+        self.modules.get(path).copied()
+    }
+}
+
+/// Returns the module path of the file at `rel_path`, e.g. `shapes::square` for
+/// `shapes/square.mun` and `shapes` for `shapes/mod.mun`.
+fn module_path(rel_path: &RelativePath) -> Vec<Name> {
+    let mut components: Vec<&str> = rel_path
+        .as_str()
+        .trim_end_matches(".mun")
+        .split('/')
+        .filter(|component| !component.is_empty())
+        .collect();
+    if components.len() > 1 && components.last() == Some(&DIRECTORY_FILE_NAME) {
+        components.pop();
+    }
+    components.into_iter().map(AsName::as_name).collect()
+}
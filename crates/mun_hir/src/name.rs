@@ -28,6 +28,16 @@ impl Name {
     pub(crate) fn missing() -> Name {
         Name::new("[missing name]".into())
     }
+
+    /// Constructs the name of the `idx`th field of a tuple, e.g. `0` in `a.0`.
+    pub(crate) fn new_tuple_field(idx: usize) -> Name {
+        Name::new(idx.to_string().into())
+    }
+
+    /// Returns the index of the tuple field that this name refers to, if it is one.
+    pub fn as_tuple_index(&self) -> Option<usize> {
+        self.text.parse().ok()
+    }
 }
 
 pub(crate) trait AsName {
@@ -46,6 +56,46 @@ impl AsName for ast::Name {
     }
 }
 
+impl AsName for str {
+    fn as_name(&self) -> Name {
+        Name::new(self.into())
+    }
+}
+
 pub(crate) const FLOAT: Name = Name::new(SmolStr::new_inline_from_ascii(5, b"float"));
+pub(crate) const F32: Name = Name::new(SmolStr::new_inline_from_ascii(3, b"f32"));
+pub(crate) const F64: Name = Name::new(SmolStr::new_inline_from_ascii(3, b"f64"));
 pub(crate) const INT: Name = Name::new(SmolStr::new_inline_from_ascii(3, b"int"));
+pub(crate) const I8: Name = Name::new(SmolStr::new_inline_from_ascii(2, b"i8"));
+pub(crate) const I16: Name = Name::new(SmolStr::new_inline_from_ascii(3, b"i16"));
+pub(crate) const I32: Name = Name::new(SmolStr::new_inline_from_ascii(3, b"i32"));
+pub(crate) const I64: Name = Name::new(SmolStr::new_inline_from_ascii(3, b"i64"));
+pub(crate) const U8: Name = Name::new(SmolStr::new_inline_from_ascii(2, b"u8"));
+pub(crate) const U16: Name = Name::new(SmolStr::new_inline_from_ascii(3, b"u16"));
+pub(crate) const U32: Name = Name::new(SmolStr::new_inline_from_ascii(3, b"u32"));
+pub(crate) const U64: Name = Name::new(SmolStr::new_inline_from_ascii(3, b"u64"));
 pub(crate) const BOOLEAN: Name = Name::new(SmolStr::new_inline_from_ascii(4, b"bool"));
+pub(crate) const STR: Name = Name::new(SmolStr::new_inline_from_ascii(3, b"str"));
+
+pub(crate) const SQRT: Name = Name::new(SmolStr::new_inline_from_ascii(4, b"sqrt"));
+pub(crate) const ABS: Name = Name::new(SmolStr::new_inline_from_ascii(3, b"abs"));
+pub(crate) const SIN: Name = Name::new(SmolStr::new_inline_from_ascii(3, b"sin"));
+pub(crate) const COS: Name = Name::new(SmolStr::new_inline_from_ascii(3, b"cos"));
+pub(crate) const FLOOR: Name = Name::new(SmolStr::new_inline_from_ascii(5, b"floor"));
+pub(crate) const MIN: Name = Name::new(SmolStr::new_inline_from_ascii(3, b"min"));
+pub(crate) const MAX: Name = Name::new(SmolStr::new_inline_from_ascii(3, b"max"));
+pub(crate) const WRAPPING_ADD: Name =
+    Name::new(SmolStr::new_inline_from_ascii(12, b"wrapping_add"));
+pub(crate) const WRAPPING_SUB: Name =
+    Name::new(SmolStr::new_inline_from_ascii(12, b"wrapping_sub"));
+pub(crate) const WRAPPING_MUL: Name =
+    Name::new(SmolStr::new_inline_from_ascii(12, b"wrapping_mul"));
+pub(crate) const SATURATING_ADD: Name =
+    Name::new(SmolStr::new_inline_from_ascii(14, b"saturating_add"));
+pub(crate) const SATURATING_SUB: Name =
+    Name::new(SmolStr::new_inline_from_ascii(14, b"saturating_sub"));
+pub(crate) const ASSERT: Name = Name::new(SmolStr::new_inline_from_ascii(6, b"assert"));
+pub(crate) const ASSERT_EQ: Name = Name::new(SmolStr::new_inline_from_ascii(9, b"assert_eq"));
+
+/// The name of the `self` parameter of a method.
+pub(crate) const SELF_PARAM: Name = Name::new(SmolStr::new_inline_from_ascii(4, b"self"));
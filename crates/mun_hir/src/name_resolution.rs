@@ -1,7 +1,13 @@
+mod imports;
 mod per_ns;
 
+pub(crate) use self::imports::imports_query;
+pub use self::imports::Imports;
 pub use self::per_ns::{Namespace, PerNs};
-use crate::{code_model::BuiltinType, FileId, HirDatabase, ModuleDef, Name};
+use crate::{
+    code_model::{BuiltinFunction, BuiltinType},
+    FileId, HirDatabase, ModuleDef, Name, StructKind,
+};
 use once_cell::sync::Lazy;
 use rustc_hash::FxHashMap;
 use std::sync::Arc;
@@ -30,6 +36,14 @@ static BUILTIN_SCOPE: Lazy<FxHashMap<Name, Resolution>> = Lazy::new(|| {
                 },
             )
         })
+        .chain(BuiltinFunction::ALL.iter().map(|(name, function)| {
+            (
+                name.clone(),
+                Resolution {
+                    def: PerNs::values((*function).into()),
+                },
+            )
+        }))
         .collect()
 });
 
@@ -41,24 +55,41 @@ impl ModuleScope {
     pub fn get(&self, name: &Name) -> Option<&Resolution> {
         self.items.get(name).or_else(|| BUILTIN_SCOPE.get(name))
     }
+
+    /// Returns the item called `name` that is defined in the module. Builtins are not defined in
+    /// a module, so they are not found by a path that is qualified with a module.
+    pub fn get_item(&self, name: &Name) -> Option<&Resolution> {
+        self.items.get(name)
+    }
 }
 
 pub(crate) fn module_scope_query(db: &impl HirDatabase, file_id: FileId) -> Arc<ModuleScope> {
     let mut scope = ModuleScope::default();
     let defs = db.module_data(file_id);
     for def in defs.definitions() {
-        #[allow(clippy::single_match)]
-        match def {
-            ModuleDef::Function(f) => {
-                scope.items.insert(
-                    f.name(db),
-                    Resolution {
-                        def: PerNs::values(*def),
-                    },
-                );
+        let (name, per_ns) = match def {
+            // Methods are only in scope through the name of their struct, e.g. `Vector::new`
+            ModuleDef::Function(f) if f.impl_block(db).is_some() => continue,
+            ModuleDef::Function(f) => (f.name(db), PerNs::values(*def)),
+            ModuleDef::Struct(s) => {
+                // Unit structs can also be used as values, e.g. `let a = Foo;`
+                let per_ns = match s.data(db).kind {
+                    StructKind::Record => PerNs::types(*def),
+                    StructKind::Unit => PerNs::both(*def, *def),
+                };
+                (s.name(db), per_ns)
             }
-            _ => {}
-        }
+            ModuleDef::Enum(e) => (e.name(db), PerNs::types(*def)),
+            ModuleDef::Const(c) => (c.name(db), PerNs::values(*def)),
+            ModuleDef::Static(s) => (s.name(db), PerNs::values(*def)),
+            ModuleDef::TypeAlias(t) => (t.name(db), PerNs::types(*def)),
+            _ => continue,
+        };
+
+        // A name refers to its first definition in each namespace, a duplicate definition is
+        // reported by the module
+        let resolution = scope.items.entry(name).or_default();
+        resolution.def = resolution.def.or(per_ns);
     }
     Arc::new(scope)
 }
@@ -0,0 +1,214 @@
+//! A `use` declaration brings an item of another module into the scope of a module, e.g.
+//! `use shapes::square::area;` or `use shapes::square::area as square_area;`. Imports only refer to
+//! the items that are defined in a module, so an import cannot be re-exported by another import.
+//! The items that are defined in a module shadow its imports, which in turn shadow the builtins.
+//! Only the items that are declared `export` can be imported from another module.
+
+use super::PerNs;
+use crate::code_model::diagnostics::syntax_ptr_from_def;
+use crate::diagnostics::{
+    DiagnosticSink, DuplicateDefinition, DuplicateImport, GlobImport, UnresolvedImport,
+};
+use crate::raw::{DefId, ImportId, RawFileItem};
+use crate::resolve::resolve_module_prefix;
+use crate::{FileId, HirDatabase, ModuleDef, Name, Path};
+use mun_syntax::{AstNode, SyntaxNodePtr};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::sync::Arc;
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Imports {
+    /// The items that are imported by name
+    items: FxHashMap<Name, PerNs<ModuleDef>>,
+    /// The names of the imports that could not be resolved
+    unresolved: FxHashSet<Name>,
+    diagnostics: Vec<ImportDiagnostic>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ImportDiagnostic {
+    Unresolved {
+        import: ImportId,
+    },
+    Glob {
+        import: ImportId,
+    },
+    DuplicateImport {
+        import: ImportId,
+        first_import: ImportId,
+    },
+    /// An import that has the same name as an item defined in the module
+    DuplicateDefinition {
+        import: ImportId,
+        definition: DefId,
+    },
+    /// An import of an item that is private to another module
+    Private {
+        import: ImportId,
+        def: ModuleDef,
+    },
+}
+
+impl Imports {
+    /// Returns the item that is imported as `name`.
+    pub fn get(&self, name: &Name) -> Option<PerNs<ModuleDef>> {
+        self.items.get(name).copied()
+    }
+
+    /// Returns true if `name` is imported by an import that could not be resolved.
+    pub(crate) fn is_unresolved(&self, name: &Name) -> bool {
+        self.unresolved.contains(name)
+    }
+
+    pub(crate) fn add_diagnostics(
+        &self,
+        db: &impl HirDatabase,
+        file_id: FileId,
+        sink: &mut DiagnosticSink,
+    ) {
+        let raw_items = db.raw_items(file_id);
+        let use_decl =
+            |import: ImportId| raw_items[import].ast_id.with_file_id(file_id).to_node(db);
+        let use_decl_ptr = |import: ImportId| SyntaxNodePtr::new(use_decl(import).syntax());
+        for diagnostic in self.diagnostics.iter() {
+            match diagnostic {
+                ImportDiagnostic::Unresolved { import } => sink.push(UnresolvedImport {
+                    file: file_id,
+                    use_decl: use_decl_ptr(*import),
+                    path: use_decl(*import)
+                        .path()
+                        .map(|path| path.syntax().text().to_string())
+                        .unwrap_or_default(),
+                }),
+                ImportDiagnostic::Glob { import } => sink.push(GlobImport {
+                    file: file_id,
+                    use_decl: use_decl_ptr(*import),
+                }),
+                ImportDiagnostic::DuplicateImport {
+                    import,
+                    first_import,
+                } => sink.push(DuplicateImport {
+                    file: file_id,
+                    name: raw_items[*import]
+                        .name()
+                        .map(|name| name.to_string())
+                        .unwrap_or_default(),
+                    first_use_decl: use_decl_ptr(*first_import),
+                    use_decl: use_decl_ptr(*import),
+                }),
+                ImportDiagnostic::DuplicateDefinition { import, definition } => {
+                    sink.push(DuplicateDefinition {
+                        file: file_id,
+                        name: raw_items[*definition].name.to_string(),
+                        namespace: None,
+                        first_definition: syntax_ptr_from_def(
+                            db,
+                            file_id.into(),
+                            raw_items[*definition].kind,
+                        ),
+                        definition: use_decl_ptr(*import),
+                    })
+                }
+                ImportDiagnostic::Private { import, def } => {
+                    let path = use_decl(*import)
+                        .path()
+                        .map(|path| SyntaxNodePtr::new(path.syntax()))
+                        .unwrap_or_else(|| use_decl_ptr(*import));
+                    sink.push(def.private_item_diagnostic(db, file_id, path))
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn imports_query(db: &impl HirDatabase, file_id: FileId) -> Arc<Imports> {
+    let raw_items = db.raw_items(file_id);
+    let definitions: FxHashMap<&Name, DefId> = raw_items
+        .items()
+        .iter()
+        .rev()
+        .filter_map(|item| match item {
+            RawFileItem::Definition(def) => Some((&raw_items[*def].name, *def)),
+            _ => None,
+        })
+        .collect();
+
+    let mut imports = Imports::default();
+    let mut import_by_name = FxHashMap::default();
+    for (import, data) in raw_items.imports() {
+        let name = match data.name() {
+            Some(name) => name,
+            None => {
+                imports.diagnostics.push(ImportDiagnostic::Glob { import });
+                continue;
+            }
+        };
+        if let Some(definition) = definitions.get(name) {
+            imports
+                .diagnostics
+                .push(ImportDiagnostic::DuplicateDefinition {
+                    import,
+                    definition: *definition,
+                });
+            continue;
+        }
+        if let Some(first_import) = import_by_name.get(name) {
+            imports.diagnostics.push(ImportDiagnostic::DuplicateImport {
+                import,
+                first_import: *first_import,
+            });
+            continue;
+        }
+        import_by_name.insert(name.clone(), import);
+
+        let def = resolve_import(db, file_id, &data.path);
+        if def.is_none() {
+            imports
+                .diagnostics
+                .push(ImportDiagnostic::Unresolved { import });
+            imports.unresolved.insert(name.clone());
+        } else {
+            // A private item is still imported, so its uses are not reported as well
+            if let Some(private) = def
+                .types
+                .or(def.values)
+                .filter(|def| !def.is_visible_from(db, file_id))
+            {
+                imports.diagnostics.push(ImportDiagnostic::Private {
+                    import,
+                    def: private,
+                });
+            }
+            imports.items.insert(name.clone(), def);
+        }
+    }
+    Arc::new(imports)
+}
+
+/// Resolves the path of an import in the module of `file_id` to an item that is defined in a
+/// module or to an enum variant. Methods are resolved through the impl blocks of a module, which
+/// can refer to its imports in turn, so an import cannot refer to a method.
+fn resolve_import(db: &impl HirDatabase, file_id: FileId, path: &Path) -> PerNs<ModuleDef> {
+    let (module, segments) = match resolve_module_prefix(db, file_id, path) {
+        Some(prefix) => prefix,
+        None => return PerNs::none(),
+    };
+    let scope = db.module_scope(module);
+    match segments {
+        [item_segment] => scope
+            .get_item(&item_segment.name)
+            .map(|r| r.def)
+            .unwrap_or_else(PerNs::none),
+        [type_segment, variant_segment] => match scope
+            .get_item(&type_segment.name)
+            .and_then(|r| r.def.take_types())
+        {
+            Some(ModuleDef::Enum(e)) => e
+                .variant(db, &variant_segment.name)
+                .map(|variant| PerNs::values(variant.into()))
+                .unwrap_or_else(PerNs::none),
+            _ => PerNs::none(),
+        },
+        _ => PerNs::none(),
+    }
+}
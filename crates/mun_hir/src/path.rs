@@ -22,34 +22,40 @@ pub enum PathKind {
 
 impl Path {
     /// Converts an `ast::Path` to `Path`.
-    pub fn from_ast(path: ast::Path) -> Option<Path> {
+    pub fn from_ast(mut path: ast::Path) -> Option<Path> {
         let mut kind = PathKind::Plain;
         let mut segments = Vec::new();
-        //        loop {
-        let segment = path.segment()?;
+        loop {
+            let segment = path.segment()?;
 
-        if segment.has_colon_colon() {
-            kind = PathKind::Abs;
-        }
-
-        match segment.kind()? {
-            ast::PathSegmentKind::Name(name) => {
-                let segment = PathSegment {
-                    name: name.as_name(),
-                };
-                segments.push(segment);
-            }
-            ast::PathSegmentKind::SelfKw => {
-                kind = PathKind::Self_;
-                //                    break;
+            if segment.has_colon_colon() {
+                kind = PathKind::Abs;
             }
-            ast::PathSegmentKind::SuperKw => {
-                kind = PathKind::Super;
-                //                    break;
+
+            match segment.kind()? {
+                ast::PathSegmentKind::Name(name) => {
+                    let segment = PathSegment {
+                        name: name.as_name(),
+                    };
+                    segments.push(segment);
+                }
+                ast::PathSegmentKind::SelfKw => {
+                    kind = PathKind::Self_;
+                    break;
+                }
+                ast::PathSegmentKind::SuperKw => {
+                    kind = PathKind::Super;
+                    break;
+                }
             }
+
+            // The segments are visited from last to first, e.g. `Idle` before `State` in
+            // `State::Idle`
+            path = match path.qualifier() {
+                Some(qualifier) => qualifier,
+                None => break,
+            };
         }
-        //            break;
-        //        }
         segments.reverse();
         Some(Path { kind, segments })
     }
@@ -1,7 +1,7 @@
 use mun_syntax::ast::{self, ModuleItemOwner, NameOwner};
 
 use crate::name::AsName;
-use crate::{Arena, DefDatabase, FileAstId, FileId, Name, RawId};
+use crate::{Arena, DefDatabase, FileAstId, FileId, Name, Path, RawId};
 use std::ops::Index;
 use std::sync::Arc;
 
@@ -9,6 +9,7 @@ use std::sync::Arc;
 #[derive(Debug, Default, PartialEq, Eq)]
 pub struct RawItems {
     definitions: Arena<DefId, DefData>,
+    imports: Arena<ImportId, ImportData>,
     items: Vec<RawFileItem>,
 }
 
@@ -26,11 +27,48 @@ pub(super) struct DefData {
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub(super) enum DefKind {
     Function(FileAstId<ast::FunctionDef>),
+    Struct(FileAstId<ast::StructDef>),
+    Enum(FileAstId<ast::EnumDef>),
+    Const(FileAstId<ast::ConstDef>),
+    Static(FileAstId<ast::StaticDef>),
+    TypeAlias(FileAstId<ast::TypeAliasDef>),
+}
+
+/// Id for an import of a module
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct ImportId(RawId);
+impl_arena_id!(ImportId);
+
+/// An import of an item from another module, e.g. `use shapes::square::area as square_area;`.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct ImportData {
+    pub(crate) path: Path,
+    pub(crate) alias: Option<Name>,
+    pub(crate) is_glob: bool,
+    pub(crate) ast_id: FileAstId<ast::UseDecl>,
+}
+
+impl ImportData {
+    /// Returns the name by which the import is in scope, i.e. its alias or else the last segment of
+    /// its path. A glob import doesn't have a name.
+    pub(crate) fn name(&self) -> Option<&Name> {
+        if self.is_glob {
+            None
+        } else {
+            self.alias
+                .as_ref()
+                .or_else(|| self.path.segments.last().map(|segment| &segment.name))
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub(super) enum RawFileItem {
     Definition(DefId),
+    /// An impl block is not a definition itself, but it defines the methods of a type
+    Impl(FileAstId<ast::ImplBlock>),
+    /// An import is not a definition either, it brings an item of another module into scope
+    Import(ImportId),
 }
 
 impl Index<DefId> for RawItems {
@@ -41,6 +79,14 @@ impl Index<DefId> for RawItems {
     }
 }
 
+impl Index<ImportId> for RawItems {
+    type Output = ImportData;
+
+    fn index(&self, index: ImportId) -> &Self::Output {
+        &self.imports[index]
+    }
+}
+
 impl RawItems {
     pub(crate) fn raw_file_items_query(db: &impl DefDatabase, file_id: FileId) -> Arc<RawItems> {
         let mut items = RawItems::default();
@@ -54,6 +100,43 @@ impl RawItems {
                 ast::ModuleItemKind::FunctionDef(it) => {
                     (DefKind::Function((*ast_id_map).ast_id(&it)), it.name())
                 }
+                ast::ModuleItemKind::StructDef(it) => {
+                    (DefKind::Struct((*ast_id_map).ast_id(&it)), it.name())
+                }
+                ast::ModuleItemKind::EnumDef(it) => {
+                    (DefKind::Enum((*ast_id_map).ast_id(&it)), it.name())
+                }
+                ast::ModuleItemKind::ConstDef(it) => {
+                    (DefKind::Const((*ast_id_map).ast_id(&it)), it.name())
+                }
+                ast::ModuleItemKind::StaticDef(it) => {
+                    (DefKind::Static((*ast_id_map).ast_id(&it)), it.name())
+                }
+                ast::ModuleItemKind::TypeAliasDef(it) => {
+                    (DefKind::TypeAlias((*ast_id_map).ast_id(&it)), it.name())
+                }
+                ast::ModuleItemKind::ImplBlock(it) => {
+                    items
+                        .items
+                        .push(RawFileItem::Impl((*ast_id_map).ast_id(&it)));
+                    continue;
+                }
+                ast::ModuleItemKind::UseDecl(it) => {
+                    // If no path is provided an error is already emitted
+                    if let Some(path) = it.path().and_then(Path::from_ast) {
+                        let id = items.imports.alloc(ImportData {
+                            path,
+                            alias: it
+                                .alias()
+                                .and_then(|alias| alias.name())
+                                .map(|name| name.as_name()),
+                            is_glob: it.is_glob(),
+                            ast_id: (*ast_id_map).ast_id(&it),
+                        });
+                        items.items.push(RawFileItem::Import(id));
+                    }
+                    continue;
+                }
             };
 
             // If no name is provided an error is already emitted
@@ -71,4 +154,9 @@ impl RawItems {
     pub(super) fn items(&self) -> &[RawFileItem] {
         &self.items
     }
+
+    /// Returns the imports of the file in the order in which they are declared.
+    pub(crate) fn imports(&self) -> impl Iterator<Item = (ImportId, &ImportData)> {
+        self.imports.iter()
+    }
 }
@@ -0,0 +1,83 @@
+use crate::{
+    expr::resolver_for_expr, Body, Expr, ExprId, Function, HirDatabase, InferenceResult, ModuleDef,
+    Resolution, SourceRootId, Visibility,
+};
+use rustc_hash::FxHashSet;
+use std::sync::Arc;
+
+/// Returns the functions of the package that are used: the `export` functions and the private
+/// functions they use, directly or indirectly, from any module of the package. A package without
+/// `export` functions has no declared interface, so all of its functions are used. `#[test]`
+/// functions are used by the test runner, so they and the functions they use are used as well.
+pub(crate) fn used_functions_query(
+    db: &impl HirDatabase,
+    source_root: SourceRootId,
+) -> Arc<FxHashSet<Function>> {
+    let functions: Vec<Function> = db
+        .module_tree(source_root)
+        .files()
+        .iter()
+        .flat_map(|file_id| db.module_data(*file_id).definitions().to_vec())
+        .filter_map(|def| match def {
+            ModuleDef::Function(f) => Some(f),
+            _ => None,
+        })
+        .collect();
+
+    let has_exports = functions
+        .iter()
+        .any(|f| f.visibility(db) == Visibility::Public);
+    let roots = functions
+        .into_iter()
+        .filter(|f| !has_exports || f.visibility(db) == Visibility::Public || f.is_test(db))
+        .collect();
+    Arc::new(functions_reachable_from(db, roots))
+}
+
+/// Returns the functions `roots` and the functions they use, directly or indirectly.
+pub fn functions_reachable_from(
+    db: &impl HirDatabase,
+    roots: Vec<Function>,
+) -> FxHashSet<Function> {
+    let mut used = FxHashSet::default();
+    let mut worklist = roots;
+    while let Some(f) = worklist.pop() {
+        if !used.insert(f) {
+            continue;
+        }
+
+        if !f.is_extern(db) {
+            let body = f.body(db);
+            let infer = f.infer(db);
+            collect_callees(db, body.body_expr(), &body, &infer, &mut worklist);
+        }
+    }
+    used
+}
+
+/// Collects the functions that are called by the expression `expr_id` and its sub expressions, or
+/// that are used as function pointers, which can be called as well.
+fn collect_callees(
+    db: &impl HirDatabase,
+    expr_id: ExprId,
+    body: &Arc<Body>,
+    infer: &InferenceResult,
+    callees: &mut Vec<Function>,
+) {
+    let expr = &body[expr_id];
+    match expr {
+        // Calls to builtin functions are generated inline, so they are not function definitions
+        Expr::Call { callee, .. } => callees.extend(infer[*callee].as_function_def()),
+        Expr::MethodCall { .. } => callees.extend(infer.method_resolution(expr_id)),
+        Expr::Path(path) => {
+            let resolution = resolver_for_expr(body.clone(), db, expr_id)
+                .resolve_path_without_assoc_items(db, path)
+                .take_values();
+            if let Some(Resolution::Def(ModuleDef::Function(f))) = resolution {
+                callees.push(f);
+            }
+        }
+        _ => {}
+    }
+    expr.walk_child_exprs(|expr_id| collect_callees(db, expr_id, body, infer, callees))
+}
@@ -1,6 +1,6 @@
 use crate::{
-    expr::scope::ScopeId, expr::PatId, ExprScopes, FileId, HirDatabase, ModuleDef, Name, Path,
-    PerNs,
+    expr::scope::ScopeId, expr::PatId, name::SELF_PARAM, path::PathSegment, ExprScopes, FileId,
+    HirDatabase, ModuleDef, Name, Path, PathKind, PerNs,
 };
 use std::sync::Arc;
 
@@ -79,22 +79,170 @@ impl Resolver {
         path: &Path,
     ) -> PerNs<Resolution> {
         if let Some(name) = path.as_ident() {
-            self.resolve_name(db, name)
-        } else {
-            PerNs::none()
+            return self.resolve_name(db, name);
+        }
+        if let (PathKind::Self_, []) = (&path.kind, path.segments.as_slice()) {
+            return self.resolve_name(db, &SELF_PARAM);
+        }
+
+        // A path starts with the modules that contain the item, e.g. `shapes::square` in
+        // `shapes::square::area`. The rest of the path is an item, e.g. `area`, an enum variant,
+        // e.g. `State::Idle`, or a method, e.g. `Vector::new`.
+        let file_id = match self.module_file() {
+            Some(file_id) => file_id,
+            None => return PerNs::none(),
+        };
+        let (module, segments) = match resolve_module_prefix(db, file_id, path) {
+            Some(prefix) => prefix,
+            None => return PerNs::none(),
+        };
+        let item = |name: &Name| module_item(db, file_id, module, name);
+        match segments {
+            [item_segment] => item(&item_segment.name).map(Resolution::Def),
+            [type_segment, item_segment] => match item(&type_segment.name).take_types() {
+                Some(ModuleDef::Enum(e)) => e
+                    .variant(db, &item_segment.name)
+                    .map(|variant| PerNs::values(Resolution::Def(variant.into())))
+                    .unwrap_or_else(PerNs::none),
+                Some(ModuleDef::Struct(s)) => s
+                    .method(db, &item_segment.name)
+                    .map(|method| PerNs::values(Resolution::Def(method.into())))
+                    .unwrap_or_else(PerNs::none),
+                _ => PerNs::none(),
+            },
+            _ => PerNs::none(),
         }
     }
+
+    /// Returns the item that `path` names if it is private to another module, e.g. a function of
+    /// another module that is not declared `export`. A name that is imported by a `use`
+    /// declaration is checked by the declaration instead.
+    pub(crate) fn private_item(&self, db: &impl HirDatabase, path: &Path) -> Option<ModuleDef> {
+        if path.as_ident().is_some() {
+            return None;
+        }
+        let file_id = self.module_file()?;
+        let (module, segments) = resolve_module_prefix(db, file_id, path)?;
+        let is_private = |def: &ModuleDef| !def.is_visible_from(db, file_id);
+        let item = |name: &Name| {
+            let def = module_item(db, file_id, module, name);
+            def.types.or(def.values)
+        };
+        match segments {
+            [item_segment] if module != file_id => item(&item_segment.name).filter(is_private),
+            [type_segment, item_segment] => {
+                let ty = item(&type_segment.name)?;
+                if module != file_id && is_private(&ty) {
+                    return Some(ty);
+                }
+                match ty {
+                    ModuleDef::Struct(s) => s
+                        .method(db, &item_segment.name)
+                        .map(ModuleDef::Function)
+                        .filter(is_private),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns true if the first segment of `path` is the name of an import of the current module
+    /// that could not be resolved. The import is already reported, so the path isn't reported
+    /// again.
+    pub(crate) fn is_unresolved_import(&self, db: &impl HirDatabase, path: &Path) -> bool {
+        match (&path.kind, path.segments.first(), self.module_file()) {
+            (PathKind::Plain, Some(segment), Some(file_id)) => {
+                db.imports(file_id).is_unresolved(&segment.name)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the file of the innermost module scope.
+    pub(crate) fn module_file(&self) -> Option<FileId> {
+        self.scopes.iter().rev().find_map(|scope| match scope {
+            Scope::ModuleScope(m) => Some(m.file_id),
+            Scope::ExprScope(_) => None,
+        })
+    }
+}
+
+/// Returns the item called `name` in `module` for a path in the module of `file_id`. Imports are
+/// only visible in the module that declares them, they are not re-exported.
+fn module_item(
+    db: &impl HirDatabase,
+    file_id: FileId,
+    module: FileId,
+    name: &Name,
+) -> PerNs<ModuleDef> {
+    let def = db.module_scope(module).get_item(name).map(|r| r.def);
+    let def = match def {
+        None if module == file_id => db.imports(module).get(name),
+        def => def,
+    };
+    def.unwrap_or_else(PerNs::none)
+}
+
+/// Resolves the modules at the start of `path` in the module of `file_id` and returns the file of
+/// the module that contains the rest of the path, together with the rest of its segments. Plain
+/// paths are relative to the current module or else to the root module, e.g. `square::area` in the
+/// module `shapes` refers to `shapes::square::area` if it exists. Paths that start with `::` are
+/// relative to the root module and paths that start with `self` or `super` are relative to the
+/// current module or its parent. The longest prefix of the path that names a module is used.
+pub(crate) fn resolve_module_prefix<'p>(
+    db: &impl HirDatabase,
+    file_id: FileId,
+    path: &'p Path,
+) -> Option<(FileId, &'p [PathSegment])> {
+    let tree = db.module_tree(db.file_source_root(file_id));
+    let current = tree.path(file_id);
+    let base: &[Name] = match path.kind {
+        PathKind::Plain | PathKind::Self_ => current,
+        PathKind::Super => current.split_last()?.1,
+        PathKind::Abs => &[],
+    };
+    let mut bases = vec![base];
+    if path.kind == PathKind::Plain && !base.is_empty() {
+        bases.push(&[]);
+    }
+
+    // At least one segment remains for the item
+    for len in (1..path.segments.len()).rev() {
+        for base in bases.iter() {
+            let module_path: Vec<Name> = base
+                .iter()
+                .cloned()
+                .chain(path.segments[..len].iter().map(|s| s.name.clone()))
+                .collect();
+            if let Some(module) = tree.file(&module_path) {
+                return Some((module, &path.segments[len..]));
+            }
+        }
+    }
+    match path.kind {
+        PathKind::Plain => Some((file_id, &path.segments)),
+        _ => tree
+            .file(base)
+            .map(|module| (module, path.segments.as_slice())),
+    }
 }
 
 impl Scope {
     fn resolve_name(&self, db: &impl HirDatabase, name: &Name) -> PerNs<Resolution> {
         match self {
-            Scope::ModuleScope(m) => db
-                .module_scope(m.file_id)
-                .get(name)
-                .map(|r| r.def)
-                .unwrap_or_else(PerNs::none)
-                .map(Resolution::Def),
+            // The items that are defined in the module shadow its imports, which shadow the
+            // builtins
+            Scope::ModuleScope(m) => {
+                let scope = db.module_scope(m.file_id);
+                scope
+                    .get_item(name)
+                    .map(|r| r.def)
+                    .or_else(|| db.imports(m.file_id).get(name))
+                    .or_else(|| scope.get(name).map(|r| r.def))
+                    .unwrap_or_else(PerNs::none)
+                    .map(Resolution::Def)
+            }
             Scope::ExprScope(e) => {
                 let entry = e
                     .expr_scopes
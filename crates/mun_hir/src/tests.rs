@@ -1,6 +1,8 @@
-use crate::db::HirDatabase;
 use crate::db::SourceDatabase;
+use crate::db::{DefDatabase, HirDatabase};
+use crate::diagnostics::DiagnosticSink;
 use crate::mock::MockDatabase;
+use crate::{FileId, Module};
 use std::sync::Arc;
 
 /// This function tests that the ModuleData of a module does not change if the contents of a function
@@ -47,3 +49,391 @@ fn check_module_data_does_not_change() {
         )
     }
 }
+
+/// Returns the messages of the diagnostics of the module of `file_id`.
+fn diagnostic_messages(db: &MockDatabase, file_id: FileId) -> Vec<String> {
+    let mut messages = Vec::new();
+    let mut sink = DiagnosticSink::new(|diag| messages.push(diag.message()));
+    Module::from(file_id).diagnostics(db, &mut sink);
+    drop(sink);
+    messages
+}
+
+#[test]
+fn module_paths() {
+    let (db, file_ids) = MockDatabase::with_files(&[
+        ("main.mun", ""),
+        ("math.mun", ""),
+        ("shapes/mod.mun", ""),
+        ("shapes/square.mun", ""),
+    ]);
+
+    let module_tree = db.module_tree(db.file_source_root(file_ids[0]));
+    assert_eq!(module_tree.root(), Some(file_ids[0]));
+    assert_eq!(module_tree.files(), &file_ids[..]);
+
+    let names: Vec<String> = file_ids
+        .iter()
+        .map(|file_id| Module::from(*file_id).qualify(&db, "foo"))
+        .collect();
+    assert_eq!(
+        names,
+        vec!["foo", "math::foo", "shapes::foo", "shapes::square::foo"]
+    );
+}
+
+#[test]
+fn resolve_paths_across_modules() {
+    let (db, file_ids) = MockDatabase::with_files(&[
+        (
+            "main.mun",
+            r#"
+    export fn main(a: int): int {
+        math::add(a, 2) + ::math::add(a, 3) + shapes::square::area(a)
+    }
+    "#,
+        ),
+        ("math.mun", "export fn add(a: int, b: int): int { a + b }"),
+        (
+            "shapes/square.mun",
+            r#"
+    struct Square { side: int }
+    export fn area(side: int): int { self::new(side).side * super::circle::unit() }
+    fn new(side: int): Square { Square { side: side } }
+    "#,
+        ),
+        ("shapes/circle.mun", "export fn unit(): int { 1 }"),
+    ]);
+
+    for file_id in file_ids {
+        assert_eq!(diagnostic_messages(&db, file_id), Vec::<String>::new());
+    }
+}
+
+#[test]
+fn unresolved_path_in_other_module() {
+    let (db, file_ids) = MockDatabase::with_files(&[
+        ("main.mun", "fn main(): int { math::missing() }"),
+        ("math.mun", "fn add(a: int, b: int): int { a + b }"),
+    ]);
+
+    let messages = diagnostic_messages(&db, file_ids[0]);
+    assert!(
+        messages.contains(&"undefined value".to_string()),
+        "{:?}",
+        messages
+    );
+}
+
+#[test]
+fn resolve_imports() {
+    let (db, file_ids) = MockDatabase::with_files(&[
+        (
+            "main.mun",
+            r#"
+    use math::add;
+    use shapes::square::area as square_area;
+    use shapes::square::Square;
+    use shapes::State;
+    use shapes::State::Idle;
+
+    export fn main(a: int, s: State): int {
+        let square: Square = Square { side: add(a, 1) };
+        if s == Idle { 0 } else if s == State::Moving { square_area(square.side) } else { 1 }
+    }
+    "#,
+        ),
+        ("math.mun", "export fn add(a: int, b: int): int { a + b }"),
+        ("shapes/mod.mun", "export enum State { Idle, Moving }"),
+        (
+            "shapes/square.mun",
+            r#"
+    use ::math::add;
+    export struct Square { side: int }
+    export fn area(side: int): int { add(side, 0) * side }
+    "#,
+        ),
+    ]);
+
+    for file_id in file_ids {
+        assert_eq!(diagnostic_messages(&db, file_id), Vec::<String>::new());
+    }
+}
+
+#[test]
+fn local_bindings_shadow_imports() {
+    let (db, file_ids) = MockDatabase::with_files(&[
+        (
+            "main.mun",
+            r#"
+    use math::unit;
+    export fn main(): int {
+        let unit = 2;
+        unit
+    }
+    "#,
+        ),
+        ("math.mun", "export fn unit(): int { 1 }"),
+    ]);
+
+    assert_eq!(diagnostic_messages(&db, file_ids[0]), Vec::<String>::new());
+}
+
+#[test]
+fn unresolved_import_is_reported_at_use_decl() {
+    let (db, file_ids) = MockDatabase::with_files(&[
+        (
+            "main.mun",
+            r#"
+    use math::missing;
+    use geometry::Shape;
+    fn main(): int {
+        let shape: Shape = Shape { side: 2 };
+        missing(shape) + missing(2)
+    }
+    "#,
+        ),
+        ("math.mun", "fn add(a: int, b: int): int { a + b }"),
+    ]);
+
+    assert_eq!(
+        diagnostic_messages(&db, file_ids[0]),
+        vec![
+            "unresolved import `math::missing`".to_string(),
+            "unresolved import `geometry::Shape`".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn duplicate_imports() {
+    let (db, file_ids) = MockDatabase::with_files(&[
+        (
+            "main.mun",
+            r#"
+    use math::add;
+    use math::sub as add;
+    use math::sub;
+    fn sub(): int { add(1, 2) }
+    export fn main(): int { sub() }
+    "#,
+        ),
+        (
+            "math.mun",
+            r#"
+    export fn add(a: int, b: int): int { a + b }
+    export fn sub(a: int, b: int): int { a - b }
+    "#,
+        ),
+    ]);
+
+    // The function that is defined in the module takes precedence over the import
+    assert_eq!(
+        diagnostic_messages(&db, file_ids[0]),
+        vec![
+            "the name `add` is imported multiple times".to_string(),
+            "the name `sub` is defined multiple times".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn duplicate_definitions() {
+    let (db, file_ids) = MockDatabase::with_files(&[(
+        "main.mun",
+        r#"
+    export fn foo(): int { 1 }
+    export fn foo(): int { 2 }
+    export struct Bar { a: int }
+    export struct Bar { b: float }
+    export struct Baz;
+    export fn Baz() {}
+    "#,
+    )]);
+
+    // A struct without fields is also a value
+    assert_eq!(
+        diagnostic_messages(&db, file_ids[0]),
+        vec![
+            "the name `foo` is defined multiple times".to_string(),
+            "the name `Bar` is defined multiple times".to_string(),
+            "the name `Baz` is defined multiple times".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn definitions_in_different_namespaces() {
+    let (db, file_ids) = MockDatabase::with_files(&[(
+        "main.mun",
+        r#"
+    export struct Foo { a: int }
+    export fn Foo(a: int): Foo { Foo { a: a } }
+    export fn main(): int { Foo(1).a }
+    export fn shadow(main: int): int {
+        let main = main + 1;
+        main
+    }
+    "#,
+    )]);
+
+    // A function and a struct with fields can have the same name, and parameters and local
+    // bindings can shadow definitions
+    assert!(diagnostic_messages(&db, file_ids[0]).is_empty());
+}
+
+#[test]
+fn glob_imports_are_not_supported() {
+    let (db, file_ids) = MockDatabase::with_files(&[
+        ("main.mun", "use math::*;\nfn main(): int { 1 }"),
+        ("math.mun", "fn add(a: int, b: int): int { a + b }"),
+    ]);
+
+    assert_eq!(
+        diagnostic_messages(&db, file_ids[0]),
+        vec!["glob imports are not supported".to_string()]
+    );
+}
+
+/// An import is only visible in its own module, so another module cannot import it in turn.
+#[test]
+fn imports_are_not_reexported() {
+    let (db, file_ids) = MockDatabase::with_files(&[
+        (
+            "main.mun",
+            r#"
+    use math::add;
+    export fn main(): int { math::add(1, 2) + add(1, 2) }
+    "#,
+        ),
+        ("math.mun", "use ops::add;"),
+        ("ops.mun", "export fn add(a: int, b: int): int { a + b }"),
+    ]);
+
+    assert_eq!(
+        diagnostic_messages(&db, file_ids[0]),
+        vec![
+            "undefined value".to_string(),
+            "unresolved import `math::add`".to_string(),
+        ]
+    );
+    assert_eq!(diagnostic_messages(&db, file_ids[1]), Vec::<String>::new());
+}
+
+#[test]
+fn private_items_of_other_modules() {
+    let (db, file_ids) = MockDatabase::with_files(&[
+        (
+            "main.mun",
+            r#"
+    export fn main(s: shapes::Shape): int {
+        let square: shapes::Square = shapes::new(2);
+        if s == shapes::Shape::Circle { shapes::UNIT } else { shapes::area(square) }
+    }
+    "#,
+        ),
+        (
+            "shapes.mun",
+            r#"
+    export fn new(side: int): Square { Square { side: side } }
+    fn area(square: Square): int { square.side * square.side }
+    struct Square { side: int }
+    enum Shape { Circle, Rectangle }
+    const UNIT: int = 1;
+    "#,
+        ),
+    ]);
+
+    assert_eq!(
+        diagnostic_messages(&db, file_ids[0]),
+        vec![
+            "enum `Shape` is private".to_string(),
+            "struct `Square` is private".to_string(),
+            "enum `Shape` is private".to_string(),
+            "constant `UNIT` is private".to_string(),
+            "function `area` is private".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn private_imports() {
+    let (db, file_ids) = MockDatabase::with_files(&[
+        (
+            "main.mun",
+            r#"
+    use math::add;
+    use math::sub;
+    export fn main(): int { add(1, 2) + sub(1, 2) + sub(2, 1) }
+    "#,
+        ),
+        (
+            "math.mun",
+            r#"
+    export fn add(a: int, b: int): int { a + b }
+    fn sub(a: int, b: int): int { a - b }
+    "#,
+        ),
+    ]);
+
+    // The uses of a private import are not reported in addition to the import itself
+    assert_eq!(
+        diagnostic_messages(&db, file_ids[0]),
+        vec!["function `sub` is private".to_string()]
+    );
+}
+
+#[test]
+fn private_fields_and_methods_of_other_modules() {
+    let (db, file_ids) = MockDatabase::with_files(&[
+        (
+            "main.mun",
+            r#"
+    export fn main(): int {
+        let square = shapes::new(2);
+        square.side + square.area()
+    }
+    "#,
+        ),
+        (
+            "shapes.mun",
+            r#"
+    export fn new(side: int): Square { Square { side: side } }
+    struct Square { side: int }
+    impl Square {
+        fn area(self): int { self.side * self.side }
+    }
+    "#,
+        ),
+    ]);
+
+    assert_eq!(
+        diagnostic_messages(&db, file_ids[0]),
+        vec![
+            "field `side` of struct `Square` is private".to_string(),
+            "function `Square::area` is private".to_string(),
+        ]
+    );
+}
+
+/// Changing a module of a package does not parse the other modules again.
+#[test]
+fn check_other_modules_are_not_parsed_again() {
+    let (mut db, file_ids) = MockDatabase::with_files(&[
+        ("main.mun", "fn main(): int { math::add(1, 2) }"),
+        ("math.mun", "export fn add(a: int, b: int): int { a + b }"),
+    ]);
+    let source_root = db.file_source_root(file_ids[0]);
+    db.used_functions(source_root);
+
+    db.set_file_text(
+        file_ids[1],
+        Arc::new("export fn add(a: int, b: int): int { a * b }".to_owned()),
+    );
+    let events = db.log_executed(|| {
+        db.used_functions(source_root);
+    });
+    let events = format!("{:?}", events);
+    assert!(events.contains("parse(FileId(1))"), "{}", events);
+    assert!(!events.contains("parse(FileId(0))"), "{}", events);
+}
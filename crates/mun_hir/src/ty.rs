@@ -2,8 +2,8 @@ mod infer;
 mod lower;
 
 use crate::display::{HirDisplay, HirFormatter};
-use crate::ty::infer::TypeVarId;
-use crate::{Function, HirDatabase};
+use crate::ty::infer::InferTy;
+use crate::{BuiltinFunction, Enum, Function, HirDatabase, Struct};
 pub(crate) use infer::infer_query;
 pub use infer::InferenceResult;
 pub(crate) use lower::{fn_sig_for_fn, type_for_def, TypableDef};
@@ -11,6 +11,9 @@ use std::fmt;
 use std::sync::Arc;
 
 mod op;
+mod primitive;
+
+pub use primitive::{FloatBitness, FloatTy, IntBitness, IntTy, Signedness};
 
 #[cfg(test)]
 mod tests;
@@ -23,7 +26,7 @@ pub enum Ty {
     Apply(ApplicationTy),
 
     /// A type variable used during type checking. Not to be confused with a type parameter.
-    Infer(TypeVarId),
+    Infer(InferTy),
 
     /// A placeholder for a type which could not be computed; this is propagated to avoid useless
     /// error messages. Doubles as a placeholder where type variables are inserted before type
@@ -46,15 +49,21 @@ pub struct ApplicationTy {
 /// tuples.
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
 pub enum TypeCtor {
-    /// The primitive floating point type. Written as `float`.
-    Float,
+    /// The primitive floating point types. Written as `float` (an alias of `f64`), `f32`, and
+    /// `f64`.
+    Float(FloatTy),
 
-    /// The primitive integral type. Written as `int`.
-    Int,
+    /// The primitive integral types. Written as `int` (an alias of `i64`), `i8` to `i64`, and `u8`
+    /// to `u64`.
+    Int(IntTy),
 
     /// The primitive boolean type. Written as `bool`.
     Bool,
 
+    /// The primitive string type. Written as `str`. A string is an immutable sequence of UTF-8
+    /// encoded bytes, represented by a pointer to the bytes and their length.
+    Str,
+
     /// The never type `never`.
     Never,
 
@@ -71,6 +80,35 @@ pub enum TypeCtor {
     /// let bar = foo; // bar: function() -> number {foo}
     /// ```
     FnDef(Function),
+
+    /// The type of a builtin function, e.g. `sqrt`. The single parameter of the type is the type
+    /// of the operands and the result of the function, which is the type of the first argument of
+    /// the call.
+    BuiltinFn(BuiltinFunction),
+
+    /// A user defined struct type. Written as the name of the struct, e.g. `Foo`.
+    Struct(Struct),
+
+    /// A user defined enum type. Written as the name of the enum, e.g. `State`. Values of the enum
+    /// are represented by the integer type of the enum.
+    Enum(Enum),
+
+    /// A tuple type with at least one element, e.g. `(int,)` or `(int, float)`. The types of the
+    /// elements are the parameters of the type. The empty tuple `()` is `Ty::Empty`.
+    Tuple { cardinality: u16 },
+
+    /// A fixed-size array type, e.g. `[float; 16]`. The element type is the single parameter of
+    /// the type.
+    Array { len: u64 },
+
+    /// A function pointer type, e.g. `fn(int, float): bool`. The parameters of the type are the
+    /// types of the `num_args` parameters of the function, followed by its return type. A function
+    /// definition is coerced to a function pointer with the same signature.
+    ///
+    /// A function pointer is the address of the code of the function. The address is not updated
+    /// when the function is hot reloaded, so a stored function pointer keeps referring to the code
+    /// of the assembly it was taken from.
+    FnPtr { num_args: u16 },
 }
 
 impl Ty {
@@ -81,6 +119,72 @@ impl Ty {
         })
     }
 
+    /// Constructs a tuple type with the specified element types. A tuple without elements is
+    /// `Ty::Empty`.
+    pub fn tuple(types: Vec<Ty>) -> Ty {
+        if types.is_empty() {
+            Ty::Empty
+        } else {
+            Ty::Apply(ApplicationTy {
+                ctor: TypeCtor::Tuple {
+                    cardinality: types.len() as u16,
+                },
+                parameters: types.into(),
+            })
+        }
+    }
+
+    /// Returns the types of the elements if this is a tuple type.
+    pub fn as_tuple(&self) -> Option<&Substs> {
+        match self {
+            Ty::Apply(ApplicationTy {
+                ctor: TypeCtor::Tuple { .. },
+                parameters,
+            }) => Some(parameters),
+            _ => None,
+        }
+    }
+
+    /// Constructs a fixed-size array type of `len` elements of type `elem`.
+    pub fn array(elem: Ty, len: u64) -> Ty {
+        Ty::Apply(ApplicationTy {
+            ctor: TypeCtor::Array { len },
+            parameters: Substs::single(elem),
+        })
+    }
+
+    /// Returns the type of the elements and the length if this is an array type.
+    pub fn as_array(&self) -> Option<(&Ty, u64)> {
+        match self {
+            Ty::Apply(ApplicationTy {
+                ctor: TypeCtor::Array { len },
+                parameters,
+            }) => Some((&parameters[0], *len)),
+            _ => None,
+        }
+    }
+
+    /// Constructs a function pointer type with the specified signature.
+    pub fn fn_ptr(sig: FnSig) -> Ty {
+        Ty::Apply(ApplicationTy {
+            ctor: TypeCtor::FnPtr {
+                num_args: sig.params().len() as u16,
+            },
+            parameters: Substs(sig.params_and_return),
+        })
+    }
+
+    /// Returns true if this is a function pointer type.
+    pub fn is_fn_ptr(&self) -> bool {
+        match self {
+            Ty::Apply(ApplicationTy {
+                ctor: TypeCtor::FnPtr { .. },
+                ..
+            }) => true,
+            _ => false,
+        }
+    }
+
     pub fn as_simple(&self) -> Option<TypeCtor> {
         match self {
             Ty::Apply(ApplicationTy { ctor, parameters }) if parameters.0.is_empty() => Some(*ctor),
@@ -111,10 +215,25 @@ impl Ty {
         }
     }
 
+    /// Returns the builtin function for the given expression or `None` if the type does not
+    /// represent a builtin function.
+    pub fn as_builtin_function(&self) -> Option<BuiltinFunction> {
+        match self {
+            Ty::Apply(a_ty) => match a_ty.ctor {
+                TypeCtor::BuiltinFn(function) => Some(function),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     pub fn callable_sig(&self, db: &impl HirDatabase) -> Option<FnSig> {
         match self {
             Ty::Apply(a_ty) => match a_ty.ctor {
                 TypeCtor::FnDef(def) => Some(db.fn_signature(def)),
+                TypeCtor::FnPtr { .. } => Some(FnSig {
+                    params_and_return: a_ty.parameters.0.clone(),
+                }),
                 _ => None,
             },
             _ => None,
@@ -136,6 +255,20 @@ impl Substs {
     }
 }
 
+impl From<Vec<Ty>> for Substs {
+    fn from(tys: Vec<Ty>) -> Self {
+        Substs(tys.into())
+    }
+}
+
+impl std::ops::Deref for Substs {
+    type Target = [Ty];
+
+    fn deref(&self) -> &[Ty] {
+        &self.0
+    }
+}
+
 /// A function signature as seen by type inference: Several parameter types and
 /// one return type.
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -166,7 +299,9 @@ impl HirDisplay for Ty {
             Ty::Apply(a_ty) => a_ty.hir_fmt(f),
             Ty::Unknown => write!(f, "{{unknown}}"),
             Ty::Empty => write!(f, "nothing"),
-            Ty::Infer(tv) => write!(f, "'{}", tv.0),
+            Ty::Infer(InferTy::TypeVar(tv)) => write!(f, "'{}", tv.0),
+            Ty::Infer(InferTy::IntVar(_)) => write!(f, "{{integer}}"),
+            Ty::Infer(InferTy::FloatVar(_)) => write!(f, "{{float}}"),
         }
     }
 }
@@ -174,18 +309,50 @@ impl HirDisplay for Ty {
 impl HirDisplay for ApplicationTy {
     fn hir_fmt(&self, f: &mut HirFormatter<impl HirDatabase>) -> fmt::Result {
         match self.ctor {
-            TypeCtor::Float => write!(f, "float"),
-            TypeCtor::Int => write!(f, "int"),
+            TypeCtor::Float(ty) => write!(f, "{}", ty),
+            TypeCtor::Int(ty) => write!(f, "{}", ty),
             TypeCtor::Bool => write!(f, "bool"),
+            TypeCtor::Str => write!(f, "str"),
             TypeCtor::Never => write!(f, "never"),
+            TypeCtor::Struct(def) => write!(f, "{}", def.name(f.db)),
+            TypeCtor::Enum(def) => write!(f, "{}", def.name(f.db)),
+            TypeCtor::Tuple { .. } => {
+                write!(f, "(")?;
+                f.write_joined(&*self.parameters, ", ")?;
+                if self.parameters.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+            TypeCtor::Array { len } => write!(f, "[{}; {}]", self.parameters[0].display(f.db), len),
             TypeCtor::FnDef(def) => {
                 let sig = f.db.fn_signature(def);
-                let name = def.name(f.db);
+                let name = def.full_name(f.db);
                 write!(f, "function {}", name)?;
                 write!(f, "(")?;
                 f.write_joined(sig.params(), ", ")?;
                 write!(f, ") -> {}", sig.ret().display(f.db))
             }
+            TypeCtor::FnPtr { num_args } => {
+                let (params, ret) = self.parameters.split_at(num_args as usize);
+                write!(f, "fn(")?;
+                f.write_joined(params, ", ")?;
+                write!(f, ")")?;
+                if !ret[0].is_empty() {
+                    write!(f, ": {}", ret[0].display(f.db))?;
+                }
+                Ok(())
+            }
+            TypeCtor::BuiltinFn(function) => {
+                let operand = &self.parameters[0];
+                write!(f, "function {}(", function.name())?;
+                f.write_joined(std::iter::repeat(operand).take(function.arity()), ", ")?;
+                write!(f, ")")?;
+                if !function.is_assertion() {
+                    write!(f, " -> {}", operand.display(f.db))?;
+                }
+                Ok(())
+            }
         }
     }
 }
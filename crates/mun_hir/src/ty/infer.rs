@@ -3,17 +3,19 @@ use crate::{
     code_model::DefWithBody,
     diagnostics::DiagnosticSink,
     expr,
-    expr::{Body, Expr, ExprId, Literal, Pat, PatId, Statement},
+    expr::{Body, Expr, ExprId, Literal, MatchArm, Pat, PatId, RecordLitField, Statement, UnaryOp},
     name_resolution::Namespace,
     resolve::{Resolution, Resolver},
     ty::infer::diagnostics::InferenceDiagnostic,
     ty::infer::type_variable::TypeVariableTable,
     ty::lower::LowerDiagnostic,
     ty::op,
-    ty::{Ty, TypableDef},
+    ty::{ApplicationTy, FnSig, Substs, Ty, TypableDef},
     type_ref::TypeRefId,
-    BinaryOp, Function, HirDatabase, Path, TypeCtor,
+    BinaryOp, BuiltinFunction, EnumVariant, FloatBitness, FloatTy, Function, HirDatabase, IntTy,
+    ModuleDef, Name, Path, Signedness, StructField, TypeCtor,
 };
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::mem;
 use std::ops::Index;
 use std::sync::Arc;
@@ -21,8 +23,33 @@ use std::sync::Arc;
 mod place_expr;
 mod type_variable;
 
+use place_expr::PlaceError;
 pub use type_variable::TypeVarId;
 
+/// A type variable that is introduced during type inference.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum InferTy {
+    /// A variable that can represent any type.
+    TypeVar(TypeVarId),
+
+    /// The type of an unsuffixed integer literal. Can only represent integer types and defaults
+    /// to `int` if it is not constrained by its context.
+    IntVar(TypeVarId),
+
+    /// The type of an unsuffixed floating point literal. Can only represent floating point types
+    /// and defaults to `float` if it is not constrained by its context.
+    FloatVar(TypeVarId),
+}
+
+impl InferTy {
+    /// Returns the id of the underlying type variable.
+    fn to_inner(self) -> TypeVarId {
+        match self {
+            InferTy::TypeVar(tv) | InferTy::IntVar(tv) | InferTy::FloatVar(tv) => tv,
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! ty_app {
     ($ctor:pat, $param:pat) => {
@@ -44,6 +71,12 @@ mod coerce;
 /// The result of type inference: A mapping from expressions and patterns to types.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct InferenceResult {
+    /// For each field access expression, records the field it resolves to.
+    field_resolutions: FxHashMap<ExprId, StructField>,
+    /// For each path pattern, records the enum variant it resolves to.
+    variant_resolutions: FxHashMap<PatId, EnumVariant>,
+    /// For each method call expression, records the method it resolves to.
+    method_resolutions: FxHashMap<ExprId, Function>,
     pub(crate) type_of_expr: ArenaMap<ExprId, Ty>,
     pub(crate) type_of_pat: ArenaMap<PatId, Ty>,
     pub(crate) diagnostics: Vec<diagnostics::InferenceDiagnostic>,
@@ -64,11 +97,26 @@ impl Index<PatId> for InferenceResult {
 }
 
 impl InferenceResult {
+    /// Returns the field that the field access expression `expr` resolves to, if any.
+    pub fn field_resolution(&self, expr: ExprId) -> Option<StructField> {
+        self.field_resolutions.get(&expr).copied()
+    }
+
+    /// Returns the enum variant that the path pattern `pat` resolves to, if any.
+    pub fn variant_resolution(&self, pat: PatId) -> Option<EnumVariant> {
+        self.variant_resolutions.get(&pat).copied()
+    }
+
+    /// Returns the method that the method call expression `expr` resolves to, if any.
+    pub fn method_resolution(&self, expr: ExprId) -> Option<Function> {
+        self.method_resolutions.get(&expr).copied()
+    }
+
     /// Adds all the `InferenceDiagnostic`s of the result to the `DiagnosticSink`.
     pub(crate) fn add_diagnostics(
         &self,
         db: &impl HirDatabase,
-        owner: Function,
+        owner: DefWithBody,
         sink: &mut DiagnosticSink,
     ) {
         self.diagnostics
@@ -85,7 +133,9 @@ pub fn infer_query(db: &impl HirDatabase, def: DefWithBody) -> Arc<InferenceResu
     let mut ctx = InferenceResultBuilder::new(db, body, resolver);
 
     match def {
-        DefWithBody::Function(_) => ctx.infer_signature(),
+        DefWithBody::Function(_) | DefWithBody::Const(_) | DefWithBody::Static(_) => {
+            ctx.infer_signature()
+        }
     }
 
     ctx.infer_body();
@@ -99,12 +149,25 @@ enum ActiveLoop {
     For,
 }
 
+/// A closure whose body is being inferred.
+struct ActiveClosure {
+    /// The parameters of the closure and the bindings in its body. Any other local binding that is
+    /// used in the body is captured by the closure.
+    bindings: FxHashSet<PatId>,
+    /// The `return` expressions in the body, if the return type of the closure is inferred from
+    /// its body
+    returns: Option<Vec<ExprId>>,
+}
+
 /// The inference context contains all information needed during type inference.
 struct InferenceResultBuilder<'a, D: HirDatabase> {
     db: &'a D,
     body: Arc<Body>,
     resolver: Resolver,
 
+    field_resolutions: FxHashMap<ExprId, StructField>,
+    variant_resolutions: FxHashMap<PatId, EnumVariant>,
+    method_resolutions: FxHashMap<ExprId, Function>,
     type_of_expr: ArenaMap<ExprId, Ty>,
     type_of_pat: ArenaMap<PatId, Ty>,
     diagnostics: Vec<InferenceDiagnostic>,
@@ -117,23 +180,45 @@ struct InferenceResultBuilder<'a, D: HirDatabase> {
     /// encountered.
     active_loop: Option<ActiveLoop>,
 
-    /// The return type of the function being inferred.
+    /// The closures whose bodies are being inferred, the innermost closure is last.
+    active_closures: Vec<ActiveClosure>,
+
+    /// The bindings of `let` statements without an initializer, e.g. `let a: int;`. An immutable
+    /// binding like that is assigned its value later, which is checked to happen only once by the
+    /// definite initialization analysis.
+    deferred_bindings: FxHashSet<PatId>,
+
+    /// The return type of the function or closure being inferred.
     return_ty: Ty,
+
+    /// The annotation of the return type of the function or closure being inferred, if any.
+    return_type_ref: Option<TypeRefId>,
+
+    /// The type variables of unsuffixed literals and the literals they belong to, in the order in
+    /// which the literals are inferred.
+    literal_vars: Vec<(TypeVarId, ExprId)>,
 }
 
 impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
     /// Construct a new `InferenceContext` from a `Body` and a `Resolver` for that body.
     fn new(db: &'a D, body: Arc<Body>, resolver: Resolver) -> Self {
         InferenceResultBuilder {
+            field_resolutions: FxHashMap::default(),
+            variant_resolutions: FxHashMap::default(),
+            method_resolutions: FxHashMap::default(),
             type_of_expr: ArenaMap::default(),
             type_of_pat: ArenaMap::default(),
             diagnostics: Vec::default(),
             active_loop: None,
+            active_closures: Vec::new(),
+            deferred_bindings: FxHashSet::default(),
             type_variables: TypeVariableTable::default(),
             db,
             body,
             resolver,
             return_ty: Ty::Unknown, // set in collect_fn_signature
+            return_type_ref: None,
+            literal_vars: Vec::new(),
         }
     }
 
@@ -165,6 +250,12 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
                 LowerDiagnostic::UnresolvedType { id } => {
                     InferenceDiagnostic::UnresolvedType { id }
                 }
+                LowerDiagnostic::PrivateItem { id, def } => {
+                    InferenceDiagnostic::PrivateType { id, def }
+                }
+                LowerDiagnostic::InvalidArrayLength { id } => {
+                    InferenceDiagnostic::InvalidArrayLength { id }
+                }
             };
             self.diagnostics.push(diag);
         }
@@ -180,16 +271,140 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
             return true;
         }
 
-        self.unify_inner_trivial(&ty1, &ty2)
+        let ty1 = self.type_variables.replace_if_possible(ty1).into_owned();
+        let ty2 = self.type_variables.replace_if_possible(ty2).into_owned();
+        if ty1 == ty2 {
+            return true;
+        }
+
+        match (&ty1, &ty2) {
+            (Ty::Apply(a_ty1), Ty::Apply(a_ty2)) if a_ty1.ctor == a_ty2.ctor => {
+                self.unify_substs(&a_ty1.parameters, &a_ty2.parameters)
+            }
+            _ => self.unify_inner_trivial(&ty1, &ty2),
+        }
+    }
+
+    /// Unifies the parameters of two types with the same type constructor, e.g. the element
+    /// types of two tuples.
+    fn unify_substs(&mut self, substs1: &Substs, substs2: &Substs) -> bool {
+        substs1.len() == substs2.len()
+            && substs1
+                .iter()
+                .zip(substs2.iter())
+                .all(|(ty1, ty2)| self.unify(ty1, ty2))
     }
 
     /// This function performs trivial unifications. Returns true if a unification took place;
     fn unify_inner_trivial(&mut self, ty1: &Ty, ty2: &Ty) -> bool {
         match (ty1, ty2) {
             (Ty::Unknown, _) | (_, Ty::Unknown) => true,
+            (Ty::Infer(InferTy::TypeVar(tv1)), Ty::Infer(InferTy::TypeVar(tv2)))
+            | (Ty::Infer(InferTy::IntVar(tv1)), Ty::Infer(InferTy::IntVar(tv2)))
+            | (Ty::Infer(InferTy::FloatVar(tv1)), Ty::Infer(InferTy::FloatVar(tv2))) => {
+                self.type_variables.equate(*tv1, *tv2);
+                true
+            }
+            (Ty::Infer(InferTy::IntVar(tv)), other @ ty_app!(TypeCtor::Int(_)))
+            | (other @ ty_app!(TypeCtor::Int(_)), Ty::Infer(InferTy::IntVar(tv)))
+            | (Ty::Infer(InferTy::FloatVar(tv)), other @ ty_app!(TypeCtor::Float(_)))
+            | (other @ ty_app!(TypeCtor::Float(_)), Ty::Infer(InferTy::FloatVar(tv))) => {
+                self.type_variables.instantiate(*tv, other.clone());
+                true
+            }
             _ => false,
         }
     }
+
+    /// Creates a new type variable for the type of an unsuffixed integer literal.
+    fn new_int_var(&mut self) -> Ty {
+        Ty::Infer(InferTy::IntVar(self.type_variables.new_type_var()))
+    }
+
+    /// Creates a new type variable for the type of an unsuffixed floating point literal.
+    fn new_float_var(&mut self) -> Ty {
+        Ty::Infer(InferTy::FloatVar(self.type_variables.new_type_var()))
+    }
+
+    /// Creates a new type variable for the type of the unsuffixed literal `expr`, which is either
+    /// an integer or a floating point variable.
+    fn new_literal_var(&mut self, expr: ExprId, var: fn(TypeVarId) -> InferTy) -> Ty {
+        let tv = self.type_variables.new_type_var();
+        self.literal_vars.push((tv, expr));
+        Ty::Infer(var(tv))
+    }
+
+    /// Returns the unsuffixed literal whose type is the type variable `tv`, if any.
+    fn literal_of_var(&mut self, tv: TypeVarId) -> Option<ExprId> {
+        let root = self.type_variables.find(tv);
+        for (literal_tv, literal) in self.literal_vars.iter() {
+            if self.type_variables.find(*literal_tv) == root {
+                return Some(*literal);
+            }
+        }
+        None
+    }
+
+    /// Reports that the type `found` of `expr` doesn't meet the `expected` type. While the type of
+    /// an unsuffixed literal is not constrained, a conflicting constraint is reported at the
+    /// literal, rather than at the use of its value. Once an earlier use constrained the type of
+    /// the literal, the use is reported as a conflict with that earlier use.
+    fn report_mismatch(&mut self, expr: ExprId, found: &Ty, expected: &Expectation) {
+        let id = match self.type_variables.replace_if_possible(found).into_owned() {
+            Ty::Infer(InferTy::IntVar(tv)) | Ty::Infer(InferTy::FloatVar(tv)) => {
+                self.literal_of_var(tv).unwrap_or(expr)
+            }
+            resolved => {
+                if let Ty::Infer(InferTy::IntVar(tv)) | Ty::Infer(InferTy::FloatVar(tv)) = found {
+                    if let Some(literal) = self.literal_of_var(*tv) {
+                        let expected = self.type_variables.replace_if_possible(&expected.ty);
+                        self.diagnostics
+                            .push(InferenceDiagnostic::ConflictingLiteralTypes {
+                                id: expr,
+                                literal,
+                                expected: expected.into_owned(),
+                                found: resolved,
+                            });
+                        return;
+                    }
+                }
+                expr
+            }
+        };
+        self.diagnostics.push(InferenceDiagnostic::MismatchedTypes {
+            expected: expected.ty.clone(),
+            found: found.clone(),
+            id,
+            origin: expected.origin,
+        });
+    }
+
+    /// Resolves the type variables in `ty`. Integer and floating point variables that were not
+    /// constrained by their context get their default type.
+    fn resolve_ty_completely(&mut self, ty: Ty) -> Ty {
+        match self.type_variables.replace_if_possible(&ty).into_owned() {
+            Ty::Infer(InferTy::IntVar(_)) => Ty::simple(TypeCtor::Int(IntTy::int())),
+            Ty::Infer(InferTy::FloatVar(_)) => Ty::simple(TypeCtor::Float(FloatTy::float())),
+            Ty::Infer(InferTy::TypeVar(_)) => Ty::Unknown,
+            Ty::Apply(a_ty) => {
+                if a_ty.parameters.is_empty() {
+                    Ty::Apply(a_ty)
+                } else {
+                    // Also resolve the type variables in the parameters, e.g. for tuples
+                    Ty::Apply(ApplicationTy {
+                        ctor: a_ty.ctor,
+                        parameters: a_ty
+                            .parameters
+                            .iter()
+                            .map(|ty| self.resolve_ty_completely(ty.clone()))
+                            .collect::<Vec<_>>()
+                            .into(),
+                    })
+                }
+            }
+            ty => ty,
+        }
+    }
 }
 
 impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
@@ -206,7 +421,25 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
         }
 
         // Resolve the return type
-        self.return_ty = self.resolve_type(body.ret_type())
+        self.return_ty = self.resolve_type(body.ret_type());
+        self.return_type_ref = Some(body.ret_type());
+    }
+
+    /// The expectation that an expression has the return type of the function or closure being
+    /// inferred.
+    fn return_expectation(&self) -> Expectation {
+        Expectation::has_type_from(
+            self.return_ty.clone(),
+            self.return_type_ref.map(ExpectationOrigin::TypeRef),
+        )
+    }
+
+    /// Returns true if `expected` is the expectation of the return value and an expression without
+    /// a value doesn't meet it, i.e. the function or closure returns a value that is missing.
+    fn is_missing_return_value(&mut self, expected: &Expectation) -> bool {
+        expected.origin.is_some()
+            && expected.origin == self.return_type_ref.map(ExpectationOrigin::TypeRef)
+            && !self.coerce(&Ty::Empty, &expected.ty)
     }
 
     /// Record the type of the specified pattern and all sub-patterns.
@@ -223,21 +456,14 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
 
     /// Infer the types of all the expressions and sub-expressions in the body.
     fn infer_body(&mut self) {
-        self.infer_expr_coerce(
-            self.body.body_expr(),
-            &Expectation::has_type(self.return_ty.clone()),
-        );
+        self.infer_expr_coerce(self.body.body_expr(), &self.return_expectation());
     }
 
     /// Infers the type of the `tgt_expr`
     fn infer_expr(&mut self, tgt_expr: ExprId, expected: &Expectation) -> Ty {
         let ty = self.infer_expr_inner(tgt_expr, expected);
-        if !expected.is_none() && ty != expected.ty {
-            self.diagnostics.push(InferenceDiagnostic::MismatchedTypes {
-                expected: expected.ty.clone(),
-                found: ty.clone(),
-                id: tgt_expr,
-            });
+        if !expected.is_none() && !self.unify(&ty, &expected.ty) {
+            self.report_mismatch(tgt_expr, &ty, expected);
         };
 
         ty
@@ -254,15 +480,16 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
     /// possible coercion. Adds a diagnostic message if coercion failed.
     fn coerce_expr_ty(&mut self, expr: ExprId, ty: Ty, expected: &Expectation) -> Ty {
         if !self.coerce(&ty, &expected.ty) {
-            self.diagnostics.push(InferenceDiagnostic::MismatchedTypes {
-                expected: expected.ty.clone(),
-                found: ty.clone(),
-                id: expr,
-            });
+            self.report_mismatch(expr, &ty, expected);
             ty
         } else if expected.ty == Ty::Unknown {
             ty
         } else {
+            // A function that is coerced to a function pointer is used as a value, which code
+            // generation needs to know
+            if ty.as_function_def().is_some() {
+                self.set_expr_type(expr, expected.ty.clone());
+            }
             expected.ty.clone()
         }
     }
@@ -285,34 +512,102 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
             } => self.infer_if(tgt_expr, &expected, *condition, *then_branch, *else_branch),
             Expr::BinaryOp { lhs, rhs, op } => match op {
                 Some(op) => {
-                    let lhs_ty = self.infer_expr(*lhs, &Expectation::none());
-                    if let BinaryOp::Assignment { op: _op } = op {
+                    let lhs_expected = op::binary_op_lhs_expectation(*op)
+                        .map_or_else(Expectation::none, Expectation::has_type);
+                    let lhs_ty = self.infer_expr(*lhs, &lhs_expected);
+                    if let BinaryOp::Assignment { op: assign_op } = op {
                         let resolver =
                             expr::resolver_for_expr(self.body.clone(), self.db, tgt_expr);
-                        if !self.check_place_expression(&resolver, *lhs) {
-                            self.diagnostics.push(InferenceDiagnostic::InvalidLHS {
-                                id: tgt_expr,
-                                lhs: *lhs,
-                            })
+                        match self.check_place_expression(&resolver, *lhs) {
+                            Ok(()) => {}
+                            // The initial value of a binding that was declared without one
+                            Err(PlaceError::ImmutableBinding(binding))
+                                if assign_op.is_none()
+                                    && self.deferred_bindings.contains(&binding)
+                                    && match self.body[*lhs] {
+                                        Expr::Path(_) => true,
+                                        _ => false,
+                                    } => {}
+                            Err(PlaceError::NotAPlace) => {
+                                self.diagnostics.push(InferenceDiagnostic::InvalidLHS {
+                                    id: tgt_expr,
+                                    lhs: *lhs,
+                                })
+                            }
+                            Err(PlaceError::ImmutableBinding(binding)) => self.diagnostics.push(
+                                InferenceDiagnostic::CannotAssignToImmutable { lhs: *lhs, binding },
+                            ),
                         }
                     };
-                    let rhs_expected = op::binary_op_rhs_expectation(*op, lhs_ty);
-                    let rhs_ty = self.infer_expr(*rhs, &Expectation::has_type(rhs_expected));
-                    op::binary_op_return_ty(*op, rhs_ty)
+                    let lhs_ty = self
+                        .type_variables
+                        .replace_if_possible(&lhs_ty)
+                        .into_owned();
+                    let rhs_expected =
+                        Expectation::has_type(op::binary_op_rhs_expectation(*op, lhs_ty.clone()));
+                    if !op::checks_operand_types(*op) {
+                        let rhs_ty = self.infer_expr(*rhs, &rhs_expected);
+                        op::binary_op_return_ty(*op, rhs_ty)
+                    } else {
+                        // An operand of another type is reported as part of the operator, instead
+                        // of as a mismatched type
+                        let rhs_ty = self.infer_expr_inner(*rhs, &rhs_expected);
+                        let operands_match = self.unify(&rhs_ty, &lhs_ty);
+                        let is_valid = op::is_valid_binary_op(*op, &lhs_ty);
+                        if is_valid && operands_match {
+                            op::binary_op_return_ty(*op, lhs_ty)
+                        } else {
+                            let rhs_ty = self.type_variables.replace_if_possible(&rhs_ty);
+                            self.diagnostics
+                                .push(InferenceDiagnostic::CannotApplyBinaryOp {
+                                    id: tgt_expr,
+                                    lhs: lhs_ty,
+                                    rhs: rhs_ty.into_owned(),
+                                    mismatched_operands: is_valid,
+                                });
+                            op::binary_op_return_ty(*op, Ty::Unknown)
+                        }
+                    }
                 }
                 _ => Ty::Unknown,
             },
-            Expr::Block { statements, tail } => self.infer_block(statements, *tail, expected),
+            Expr::Block { statements, tail } => {
+                self.infer_block(tgt_expr, statements, *tail, expected)
+            }
             Expr::Call { callee: call, args } => self.infer_call(tgt_expr, *call, args, expected),
+            Expr::MethodCall {
+                receiver,
+                method_name,
+                args,
+            } => self.infer_method_call(tgt_expr, *receiver, method_name, args),
             Expr::Literal(lit) => match lit {
-                Literal::String(_) => Ty::Unknown,
+                Literal::String(_) => Ty::simple(TypeCtor::Str),
                 Literal::Bool(_) => Ty::simple(TypeCtor::Bool),
-                Literal::Int(_) => Ty::simple(TypeCtor::Int),
-                Literal::Float(_) => Ty::simple(TypeCtor::Float),
+                Literal::Int(lit) => match lit.suffix {
+                    Some(ty) => Ty::simple(TypeCtor::Int(ty)),
+                    None if expected.is_float_annotation() => {
+                        self.new_literal_var(tgt_expr, InferTy::FloatVar)
+                    }
+                    None => self.new_literal_var(tgt_expr, InferTy::IntVar),
+                },
+                Literal::Float(lit) => match lit.suffix {
+                    Some(ty) => Ty::simple(TypeCtor::Float(ty)),
+                    None => self.new_literal_var(tgt_expr, InferTy::FloatVar),
+                },
             },
             Expr::Return { expr } => {
-                if let Some(expr) = expr {
-                    self.infer_expr(*expr, &Expectation::has_type(self.return_ty.clone()));
+                if let Some(returns) = self
+                    .active_closures
+                    .last_mut()
+                    .and_then(|closure| closure.returns.as_mut())
+                {
+                    // The return type of the closure is inferred once its body is inferred
+                    returns.push(tgt_expr);
+                    if let Some(expr) = expr {
+                        self.infer_expr(*expr, &Expectation::none());
+                    }
+                } else if let Some(expr) = expr {
+                    self.infer_expr(*expr, &self.return_expectation());
                 } else if self.return_ty != Ty::Empty {
                     self.diagnostics
                         .push(InferenceDiagnostic::ReturnMissingExpression { id: tgt_expr });
@@ -321,12 +616,41 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
                 Ty::simple(TypeCtor::Never)
             }
             Expr::Break { expr } => self.infer_break(tgt_expr, *expr),
+            Expr::Continue => {
+                if self.active_loop.is_none() {
+                    self.diagnostics
+                        .push(InferenceDiagnostic::ContinueOutsideLoop { id: tgt_expr });
+                }
+                Ty::simple(TypeCtor::Never)
+            }
             Expr::Loop { body } => self.infer_loop_expr(tgt_expr, *body, expected),
             Expr::While { condition, body } => {
                 self.infer_while_expr(tgt_expr, *condition, *body, expected)
             }
+            Expr::For {
+                pat,
+                start,
+                end,
+                body,
+                ..
+            } => self.infer_for_expr(tgt_expr, *pat, *start, *end, *body, expected),
+            Expr::Match { expr, arms } => self.infer_match(tgt_expr, *expr, arms, expected),
+            Expr::Closure {
+                args,
+                arg_types,
+                ret_type,
+                body,
+            } => self.infer_closure(args, arg_types, *ret_type, *body, expected),
+            Expr::Field { expr, name } => self.infer_field(tgt_expr, *expr, name),
+            Expr::RecordLit { path, fields } => {
+                self.infer_record_lit(tgt_expr, path.as_ref(), fields)
+            }
+            Expr::Tuple { exprs } => self.infer_tuple(exprs, expected),
+            Expr::Array { exprs } => self.infer_array(tgt_expr, exprs, expected),
+            Expr::Index { base, index } => self.infer_index(*base, *index),
+            Expr::UnaryOp { expr, op } => self.infer_unary_op(tgt_expr, *expr, *op),
+            Expr::Cast { expr, type_ref } => self.infer_cast(tgt_expr, *expr, *type_ref),
             _ => Ty::Unknown,
-            //            Expr::UnaryOp { expr: _, op: _ } => {}
             //            Expr::Block { statements: _, tail: _ } => {}
         };
 
@@ -365,18 +689,129 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
                 }
             }
             None => {
+                // In the position of the return value, the function returns without a value if
+                // the condition is false
+                if self.is_missing_return_value(expected) {
+                    self.diagnostics
+                        .push(InferenceDiagnostic::MissingReturnValue {
+                            id: tgt_expr,
+                            expected: expected.ty.clone(),
+                            origin: expected.origin,
+                        });
+                    return expected.ty.clone();
+                }
+
+                // Without an else branch the if expression doesn't evaluate to a value. The type
+                // of the then branch is kept to prevent follow-up errors in value positions.
                 if !self.coerce(&then_ty, &Ty::Empty) {
                     self.diagnostics
                         .push(InferenceDiagnostic::MissingElseBranch {
                             id: tgt_expr,
-                            then_ty,
-                        })
+                            then_ty: then_ty.clone(),
+                        });
+                    return then_ty;
                 }
                 Ty::Empty
             }
         }
     }
 
+    /// Infers the type of a match expression. The patterns of the arms must have the type of the
+    /// matched value. The expressions of the arms must all have the same type, which is the type
+    /// of the match expression, or the type of the first arm that doesn't diverge if no type is
+    /// expected.
+    fn infer_match(
+        &mut self,
+        tgt_expr: ExprId,
+        expr: ExprId,
+        arms: &[MatchArm],
+        expected: &Expectation,
+    ) -> Ty {
+        let input_ty = self.infer_expr(expr, &Expectation::none());
+
+        let mut expected = expected.clone();
+        let mut diverges = true;
+        for arm in arms {
+            self.infer_match_pat(tgt_expr, arm.pat, &input_ty);
+            let arm_ty = self.infer_expr_coerce(arm.expr, &expected);
+            if !arm_ty.is_never() {
+                diverges = false;
+                if expected.is_none() {
+                    expected = Expectation::has_type(arm_ty);
+                }
+            }
+        }
+
+        // A match without arms can only match a value that cannot exist, which is reported if the
+        // match is not exhaustive
+        if diverges {
+            Ty::simple(TypeCtor::Never)
+        } else {
+            expected.ty
+        }
+    }
+
+    /// Infers the type of the pattern of a match arm, which is the type `ty` of the matched value.
+    /// Only literals, enum variants and wildcards are supported.
+    fn infer_match_pat(&mut self, match_expr: ExprId, pat: PatId, ty: &Ty) {
+        let body = Arc::clone(&self.body); // avoid borrow checker problem
+        match &body[pat] {
+            Pat::Lit(expr) => {
+                self.infer_expr(*expr, &Expectation::has_type(ty.clone()));
+                let is_supported = match &body[*expr] {
+                    Expr::Literal(Literal::Int(_)) | Expr::Literal(Literal::Bool(_)) => true,
+                    Expr::UnaryOp {
+                        expr,
+                        op: UnaryOp::Neg,
+                    } => match &body[*expr] {
+                        Expr::Literal(Literal::Int(_)) => true,
+                        _ => false,
+                    },
+                    _ => false,
+                };
+                if !is_supported {
+                    self.diagnostics
+                        .push(InferenceDiagnostic::UnsupportedPattern { id: pat });
+                }
+            }
+            Pat::Path(path) => {
+                let resolver = expr::resolver_for_expr(self.body.clone(), self.db, match_expr);
+                match resolver
+                    .resolve_path_without_assoc_items(self.db, path)
+                    .take_values()
+                {
+                    Some(Resolution::Def(ModuleDef::EnumVariant(variant))) => {
+                        self.check_path_visibility(&resolver, path, pat.into());
+                        let variant_ty = self.db.type_for_def(variant.into(), Namespace::Values);
+                        if self.unify(&variant_ty, ty) {
+                            self.variant_resolutions.insert(pat, variant);
+                        } else {
+                            self.diagnostics
+                                .push(InferenceDiagnostic::MismatchedPatternType {
+                                    id: pat,
+                                    expected: ty.clone(),
+                                    found: variant_ty,
+                                });
+                        }
+                    }
+                    Some(_) => self
+                        .diagnostics
+                        .push(InferenceDiagnostic::UnsupportedPattern { id: pat }),
+                    None if resolver.is_unresolved_import(self.db, path) => {}
+                    None => self
+                        .diagnostics
+                        .push(InferenceDiagnostic::UnresolvedValue { id: pat.into() }),
+                }
+            }
+            // Bindings in match arms are not supported yet
+            Pat::Bind { .. } => self
+                .diagnostics
+                .push(InferenceDiagnostic::UnsupportedPattern { id: pat }),
+            Pat::Wild | Pat::Missing => {}
+        }
+        self.set_pat_type(pat, ty.clone());
+    }
+
     /// Inferences the type of a call expression.
     fn infer_call(
         &mut self,
@@ -386,8 +821,20 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
         _expected: &Expectation,
     ) -> Ty {
         let callee_ty = self.infer_expr(callee, &Expectation::none());
+        if let Some(function) = callee_ty.as_builtin_function() {
+            return self.infer_builtin_call(tgt_expr, callee, function, args);
+        }
+        let callee_fn = callee_ty.as_function_def().map(|f| (f, 0));
         let (param_tys, ret_ty) = match callee_ty.callable_sig(self.db) {
             Some(sig) => (sig.params().to_vec(), sig.ret().clone()),
+            // The callee is already reported, e.g. as an undefined value or an unresolved import,
+            // so its parameters are unknown and the arguments can't be checked against them
+            None if callee_ty == Ty::Unknown => {
+                for &arg in args {
+                    self.infer_expr(arg, &Expectation::none());
+                }
+                return Ty::Unknown;
+            }
             None => {
                 self.diagnostics
                     .push(InferenceDiagnostic::ExpectedFunction {
@@ -397,12 +844,125 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
                 (Vec::new(), Ty::Unknown)
             }
         };
-        self.check_call_arguments(tgt_expr, args, &param_tys);
+        self.check_call_arguments(tgt_expr, args, &param_tys, callee_fn);
         ret_ty
     }
 
+    /// Infers the type of a call to a builtin function. The operands and the result of the function
+    /// have the type of the first argument, which is recorded as the parameter of the type of the
+    /// callee. Whether the function is defined for that type is verified once the types of all
+    /// literals are known. Assertions don't return a value, and the condition of `assert` is a
+    /// `bool`.
+    fn infer_builtin_call(
+        &mut self,
+        tgt_expr: ExprId,
+        callee: ExprId,
+        function: BuiltinFunction,
+        args: &[ExprId],
+    ) -> Ty {
+        if args.len() != function.arity() {
+            self.diagnostics
+                .push(InferenceDiagnostic::ParameterCountMismatch {
+                    id: tgt_expr,
+                    found: args.len(),
+                    expected: function.arity(),
+                })
+        }
+        let operand_ty = match args.split_first() {
+            Some((&first, rest)) if function == BuiltinFunction::Assert => {
+                let bool_ty = Ty::simple(TypeCtor::Bool);
+                self.infer_expr_coerce(first, &Expectation::has_type(bool_ty.clone()));
+                for &arg in rest {
+                    self.infer_expr(arg, &Expectation::none());
+                }
+                bool_ty
+            }
+            Some((&first, rest)) => {
+                let operand_ty = self.infer_expr(first, &Expectation::none());
+                for &arg in rest {
+                    self.infer_expr_coerce(arg, &Expectation::has_type(operand_ty.clone()));
+                }
+                operand_ty
+            }
+            None => Ty::Unknown,
+        };
+        self.set_expr_type(
+            callee,
+            Ty::Apply(ApplicationTy {
+                ctor: TypeCtor::BuiltinFn(function),
+                parameters: Substs::single(operand_ty.clone()),
+            }),
+        );
+        if function.is_assertion() {
+            Ty::Empty
+        } else {
+            operand_ty
+        }
+    }
+
+    /// Inferences the type of a method call expression and records the method it resolves to.
+    /// The receiver is passed as the `self` argument, the other arguments are checked against the
+    /// remaining parameters of the method.
+    fn infer_method_call(
+        &mut self,
+        tgt_expr: ExprId,
+        receiver: ExprId,
+        method_name: &Name,
+        args: &[ExprId],
+    ) -> Ty {
+        let receiver_ty = self.infer_expr(receiver, &Expectation::none());
+        let receiver_ty = self
+            .type_variables
+            .replace_if_possible(&receiver_ty)
+            .into_owned();
+        let method = match &receiver_ty {
+            Ty::Unknown => None,
+            ty_app!(TypeCtor::Struct(s)) => s
+                .method(self.db, method_name)
+                .filter(|method| method.has_self_param(self.db)),
+            _ => None,
+        };
+        let method = match method {
+            Some(method) => method,
+            None => {
+                if receiver_ty != Ty::Unknown {
+                    self.diagnostics
+                        .push(InferenceDiagnostic::UnresolvedMethod {
+                            id: tgt_expr,
+                            receiver_ty,
+                            name: method_name.clone(),
+                        });
+                }
+                // Infer the arguments anyway, so errors in them are reported
+                for &arg in args {
+                    self.infer_expr(arg, &Expectation::none());
+                }
+                return Ty::Unknown;
+            }
+        };
+
+        if !self.is_visible(method.into()) {
+            self.diagnostics.push(InferenceDiagnostic::PrivateItem {
+                id: tgt_expr.into(),
+                def: method.into(),
+            });
+        }
+        self.method_resolutions.insert(tgt_expr, method);
+        let sig = self.db.fn_signature(method);
+        self.check_call_arguments(tgt_expr, args, &sig.params()[1..], Some((method, 1)));
+        sig.ret().clone()
+    }
+
     /// Checks whether the specified passed arguments match the parameters of a callable definition.
-    fn check_call_arguments(&mut self, tgt_expr: ExprId, args: &[ExprId], param_tys: &[Ty]) {
+    /// If the callee is a function, `callee_fn` holds it and the index of the parameter that is
+    /// passed the first argument.
+    fn check_call_arguments(
+        &mut self,
+        tgt_expr: ExprId,
+        args: &[ExprId],
+        param_tys: &[Ty],
+        callee_fn: Option<(Function, usize)>,
+    ) {
         if args.len() != param_tys.len() {
             self.diagnostics
                 .push(InferenceDiagnostic::ParameterCountMismatch {
@@ -411,11 +971,219 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
                     expected: param_tys.len(),
                 })
         }
-        for (&arg, param_ty) in args.iter().zip(param_tys.iter()) {
-            self.infer_expr_coerce(arg, &Expectation::has_type(param_ty.clone()));
+        for (idx, (&arg, param_ty)) in args.iter().zip(param_tys.iter()).enumerate() {
+            let origin = callee_fn.map(|(f, first)| ExpectationOrigin::Param(f, first + idx));
+            self.infer_expr_coerce(arg, &Expectation::has_type_from(param_ty.clone(), origin));
+        }
+    }
+
+    /// Inferences the type of a field access expression and records the field it resolves to.
+    fn infer_field(&mut self, tgt_expr: ExprId, receiver: ExprId, name: &Name) -> Ty {
+        let receiver_ty = self.infer_expr(receiver, &Expectation::none());
+        let receiver_ty = self
+            .type_variables
+            .replace_if_possible(&receiver_ty)
+            .into_owned();
+        let field = match &receiver_ty {
+            Ty::Unknown => return Ty::Unknown,
+            ty_app!(TypeCtor::Struct(s)) => s.field(self.db, name),
+            ty_app!(TypeCtor::Tuple { .. }, elements) => {
+                if let Some(ty) = name.as_tuple_index().and_then(|idx| elements.get(idx)) {
+                    return ty.clone();
+                }
+                None
+            }
+            _ => None,
+        };
+        match field {
+            Some(field) => {
+                if !self.is_visible(field.parent_struct().into()) {
+                    self.diagnostics.push(InferenceDiagnostic::PrivateField {
+                        id: tgt_expr,
+                        field,
+                    });
+                }
+                self.field_resolutions.insert(tgt_expr, field);
+                field.ty(self.db)
+            }
+            None => {
+                self.diagnostics
+                    .push(InferenceDiagnostic::AccessUnknownField {
+                        id: tgt_expr,
+                        receiver_ty,
+                        name: name.clone(),
+                    });
+                Ty::Unknown
+            }
+        }
+    }
+
+    /// Inferences the type of a tuple expression. The elements are checked against the element
+    /// types of the expected tuple type, if any.
+    fn infer_tuple(&mut self, exprs: &[ExprId], expected: &Expectation) -> Ty {
+        let expected_tys = expected
+            .ty
+            .as_tuple()
+            .filter(|tys| tys.len() == exprs.len())
+            .map(|tys| tys.to_vec())
+            .unwrap_or_else(|| vec![Ty::Unknown; exprs.len()]);
+        let tys = exprs
+            .iter()
+            .zip(expected_tys)
+            .map(|(expr, ty)| self.infer_expr_coerce(*expr, &Expectation::has_type(ty)))
+            .collect();
+        Ty::tuple(tys)
+    }
+
+    /// Inferences the type of an array literal. The elements are checked against the element type
+    /// of the expected array type or, if there is none, against the type of the first element.
+    fn infer_array(&mut self, tgt_expr: ExprId, exprs: &[ExprId], expected: &Expectation) -> Ty {
+        let mut elem_ty = expected
+            .ty
+            .as_array()
+            .map(|(elem_ty, _)| elem_ty.clone())
+            .unwrap_or(Ty::Unknown);
+        for expr in exprs {
+            let ty = self.infer_expr_coerce(*expr, &Expectation::has_type(elem_ty.clone()));
+            if elem_ty == Ty::Unknown {
+                elem_ty = ty;
+            }
+        }
+        if exprs.is_empty() && elem_ty == Ty::Unknown {
+            self.diagnostics
+                .push(InferenceDiagnostic::CannotInferArrayType { id: tgt_expr });
+        }
+        Ty::array(elem_ty, exprs.len() as u64)
+    }
+
+    /// Inferences the type of an index expression, e.g. `a[i]`. The index must be an integer and,
+    /// if it is a literal, must lie within the bounds of the array.
+    fn infer_index(&mut self, base: ExprId, index: ExprId) -> Ty {
+        let base_ty = self.infer_expr(base, &Expectation::none());
+        let base_ty = self
+            .type_variables
+            .replace_if_possible(&base_ty)
+            .into_owned();
+        let index_ty = self.new_int_var();
+        self.infer_expr(index, &Expectation::has_type(index_ty));
+
+        let (elem_ty, len) = match base_ty.as_array() {
+            Some((elem_ty, len)) => (elem_ty.clone(), len),
+            None => {
+                if base_ty != Ty::Unknown {
+                    self.diagnostics.push(InferenceDiagnostic::CannotIndex {
+                        id: base,
+                        ty: base_ty.clone(),
+                    });
+                }
+                return Ty::Unknown;
+            }
+        };
+
+        // Dynamic indices are not checked at compile time
+        if let Expr::Literal(Literal::Int(lit)) = &self.body[index] {
+            if lit.value >= u128::from(len) {
+                self.diagnostics
+                    .push(InferenceDiagnostic::IndexOutOfBounds {
+                        id: index,
+                        index: lit.value,
+                        len,
+                    });
+            }
+        }
+        elem_ty
+    }
+
+    /// Inferences the type of a record literal and checks that every field of the struct is
+    /// initialized exactly once.
+    fn infer_record_lit(
+        &mut self,
+        tgt_expr: ExprId,
+        path: Option<&Path>,
+        fields: &[RecordLitField],
+    ) -> Ty {
+        let resolver = expr::resolver_for_expr(self.body.clone(), self.db, tgt_expr);
+        let ty = path
+            .and_then(|p| Ty::from_hir_path(self.db, &resolver, p))
+            .unwrap_or(Ty::Unknown);
+        let strukt = match &ty {
+            ty_app!(TypeCtor::Struct(s)) => {
+                if let Some(path) = path {
+                    self.check_path_visibility(&resolver, path, tgt_expr.into());
+                }
+                Some(*s)
+            }
+            _ if path.map_or(false, |p| resolver.is_unresolved_import(self.db, p)) => None,
+            _ => {
+                self.diagnostics
+                    .push(InferenceDiagnostic::UnresolvedStruct { id: tgt_expr });
+                None
+            }
+        };
+
+        let mut initialized_fields = FxHashSet::default();
+        for (idx, field) in fields.iter().enumerate() {
+            let field_def = strukt.and_then(|s| s.field(self.db, &field.name));
+            let expected = match field_def {
+                Some(field_def) => {
+                    if !initialized_fields.insert(field_def) {
+                        self.diagnostics.push(InferenceDiagnostic::DuplicateField {
+                            id: tgt_expr,
+                            field: idx,
+                            name: field.name.clone(),
+                        });
+                    }
+                    Expectation::has_type(field_def.ty(self.db))
+                }
+                None => {
+                    if strukt.is_some() {
+                        self.diagnostics.push(InferenceDiagnostic::NoSuchField {
+                            id: tgt_expr,
+                            field: idx,
+                        });
+                    }
+                    Expectation::none()
+                }
+            };
+            self.infer_expr_coerce(field.expr, &expected);
+        }
+
+        if let Some(s) = strukt {
+            let missing_fields: Vec<Name> = s
+                .fields(self.db)
+                .into_iter()
+                .filter(|f| !initialized_fields.contains(f))
+                .map(|f| f.name(self.db))
+                .collect();
+            if !missing_fields.is_empty() {
+                self.diagnostics.push(InferenceDiagnostic::MissingFields {
+                    id: tgt_expr,
+                    struct_ty: ty.clone(),
+                    field_names: missing_fields,
+                });
+            }
+            ty
+        } else {
+            Ty::Unknown
+        }
+    }
+
+    /// Reports the item that `path` names if it is private to another module.
+    fn check_path_visibility(&mut self, resolver: &Resolver, path: &Path, id: ExprOrPatId) {
+        if let Some(def) = resolver.private_item(self.db, path) {
+            self.diagnostics
+                .push(InferenceDiagnostic::PrivateItem { id, def });
         }
     }
 
+    /// Returns true if `def` can be named by the module of the body, which is always the case for
+    /// the items of the module itself.
+    fn is_visible(&self, def: ModuleDef) -> bool {
+        self.resolver
+            .module_file()
+            .map_or(true, |file_id| def.is_visible_from(self.db, file_id))
+    }
+
     fn infer_path_expr(&mut self, resolver: &Resolver, path: &Path, id: ExprOrPatId) -> Option<Ty> {
         let resolution = match resolver
             .resolve_path_without_assoc_items(self.db, path)
@@ -423,19 +1191,40 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
         {
             Some(resolution) => resolution,
             None => {
-                self.diagnostics
-                    .push(InferenceDiagnostic::UnresolvedValue { id });
+                if !resolver.is_unresolved_import(self.db, path) {
+                    self.diagnostics
+                        .push(InferenceDiagnostic::UnresolvedValue { id });
+                }
                 return None;
             }
         };
 
         match resolution {
             Resolution::LocalBinding(pat) => {
+                if let (Some(closure), ExprOrPatId::ExprId(expr)) =
+                    (self.active_closures.last(), id)
+                {
+                    if !closure.bindings.contains(&pat) {
+                        self.diagnostics
+                            .push(InferenceDiagnostic::CapturedVariable {
+                                id: expr,
+                                binding: pat,
+                            });
+                    }
+                }
                 let ty = self.type_of_pat.get(pat)?.clone();
                 //let ty = self.resolve_ty_as_possible(&mut vec![], ty);
                 Some(ty)
             }
+            // The operands of a builtin function are only known when it is called
+            Resolution::Def(ModuleDef::BuiltinFunction(function)) => {
+                Some(Ty::Apply(ApplicationTy {
+                    ctor: TypeCtor::BuiltinFn(function),
+                    parameters: Substs::single(Ty::Unknown),
+                }))
+            }
             Resolution::Def(def) => {
+                self.check_path_visibility(resolver, path, id);
                 let typable: Option<TypableDef> = def.into();
                 let typable = typable?;
                 let ty = self.db.type_for_def(typable, Namespace::Values);
@@ -446,47 +1235,205 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
 
     fn resolve_all(mut self) -> InferenceResult {
         // FIXME resolve obligations as well (use Guidance if necessary)
-        //let mut tv_stack = Vec::new();
         let mut expr_types = mem::replace(&mut self.type_of_expr, ArenaMap::default());
         for (expr, ty) in expr_types.iter_mut() {
-            //let resolved = self.resolve_ty_completely(&mut tv_stack, mem::replace(ty, Ty::Unknown));
-            if *ty == Ty::Unknown {
+            let resolved = self.resolve_ty_completely(mem::replace(ty, Ty::Unknown));
+            if resolved == Ty::Unknown {
                 self.report_expr_inference_failure(expr);
             }
-            //*ty = resolved;
+            *ty = resolved;
         }
         let mut pat_types = mem::replace(&mut self.type_of_pat, ArenaMap::default());
         for (pat, ty) in pat_types.iter_mut() {
-            //let resolved = self.resolve_ty_completely(&mut tv_stack, mem::replace(ty, Ty::Unknown));
-            if *ty == Ty::Unknown {
+            let resolved = self.resolve_ty_completely(mem::replace(ty, Ty::Unknown));
+            if resolved == Ty::Unknown {
                 self.report_pat_inference_failure(pat);
             }
-            //*ty = resolved;
-        }
-        InferenceResult {
-            //            method_resolutions: self.method_resolutions,
-            //            field_resolutions: self.field_resolutions,
-            //            variant_resolutions: self.variant_resolutions,
-            //            assoc_resolutions: self.assoc_resolutions,
-            type_of_expr: expr_types,
-            type_of_pat: pat_types,
-            diagnostics: self.diagnostics,
+            *ty = resolved;
         }
-    }
 
-    fn infer_block(
-        &mut self,
-        statements: &[Statement],
-        tail: Option<ExprId>,
-        expected: &Expectation,
-    ) -> Ty {
-        let mut diverges = false;
-        for stmt in statements {
-            match stmt {
-                Statement::Let {
-                    pat,
-                    type_ref,
-                    initializer,
+        // A negated literal is checked as a whole, so it can hold the magnitude of the smallest
+        // value of a signed type. Maps the literals to their negations.
+        let negated_literals: FxHashMap<ExprId, ExprId> = expr_types
+            .iter()
+            .filter_map(|(neg_expr, _)| match &self.body[neg_expr] {
+                Expr::UnaryOp {
+                    expr,
+                    op: UnaryOp::Neg,
+                } => Some((*expr, neg_expr)),
+                _ => None,
+            })
+            .collect();
+
+        // Now that the types of all literals are known, verify that their values fit
+        let mut negative_unsigned_literals = FxHashSet::default();
+        for (expr, ty) in expr_types.iter() {
+            match (&self.body[expr], ty) {
+                (Expr::Literal(Literal::Int(lit)), ty_app!(TypeCtor::Int(int_ty))) => {
+                    let (id, is_valid) = match negated_literals.get(&expr) {
+                        Some(&neg_expr) if int_ty.is_signed() => {
+                            (neg_expr, lit.value <= int_ty.max_value() + 1)
+                        }
+                        // `-0` fits, but the negation of an unsigned value is reported below
+                        Some(&neg_expr) => {
+                            if lit.value != 0 {
+                                negative_unsigned_literals.insert(neg_expr);
+                            }
+                            (neg_expr, lit.value == 0)
+                        }
+                        None => (expr, lit.value <= int_ty.max_value()),
+                    };
+                    if !is_valid {
+                        self.diagnostics
+                            .push(InferenceDiagnostic::LiteralOutOfRange {
+                                id,
+                                literal_ty: *int_ty,
+                                radix: lit.radix,
+                            });
+                    }
+                }
+                (Expr::Literal(Literal::Float(lit)), ty_app!(TypeCtor::Float(float_ty))) => {
+                    let rounded = match float_ty.bitness {
+                        FloatBitness::X32 => f64::from(lit.value as f32),
+                        FloatBitness::X64 => lit.value,
+                    };
+                    let rounded_to_zero = rounded == 0.0 && lit.value != 0.0;
+                    if rounded.is_infinite() || rounded_to_zero {
+                        self.diagnostics
+                            .push(InferenceDiagnostic::FloatLiteralOutOfRange {
+                                id: expr,
+                                float_ty: *float_ty,
+                                rounded_to_zero,
+                            });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Negation is only defined for signed integers and floating point values
+        for (expr, ty) in expr_types.iter() {
+            if let Expr::UnaryOp {
+                op: UnaryOp::Neg, ..
+            } = &self.body[expr]
+            {
+                if negative_unsigned_literals.contains(&expr) {
+                    continue;
+                }
+                let is_valid = match ty {
+                    ty_app!(TypeCtor::Int(int_ty)) => int_ty.signedness == Signedness::Signed,
+                    ty_app!(TypeCtor::Float(_)) | ty_app!(TypeCtor::Never) | Ty::Unknown => true,
+                    _ => false,
+                };
+                if !is_valid {
+                    self.diagnostics
+                        .push(InferenceDiagnostic::CannotApplyUnaryOp {
+                            id: expr,
+                            ty: ty.clone(),
+                        });
+                }
+            }
+        }
+
+        // Builtin functions are defined for integers and/or floats, and assertions for bools
+        for (expr, _) in expr_types.iter() {
+            if let Expr::Call { callee, .. } = &self.body[expr] {
+                if let Some(ty_app!(TypeCtor::BuiltinFn(function), parameters)) =
+                    expr_types.get(*callee)
+                {
+                    let operand_ty = &parameters[0];
+                    let is_valid = match operand_ty {
+                        ty_app!(TypeCtor::Int(_)) => function.accepts_ints(),
+                        ty_app!(TypeCtor::Float(_)) => function.accepts_floats(),
+                        ty_app!(TypeCtor::Bool) => function.accepts_bools(),
+                        ty_app!(TypeCtor::Never) | Ty::Unknown => true,
+                        _ => false,
+                    };
+                    if !is_valid {
+                        self.diagnostics
+                            .push(InferenceDiagnostic::CannotApplyBuiltinFunction {
+                                id: expr,
+                                function: *function,
+                                ty: operand_ty.clone(),
+                            });
+                    }
+                }
+            }
+        }
+
+        InferenceResult {
+            //            method_resolutions: self.method_resolutions,
+            field_resolutions: self.field_resolutions,
+            variant_resolutions: self.variant_resolutions,
+            method_resolutions: self.method_resolutions,
+            //            assoc_resolutions: self.assoc_resolutions,
+            type_of_expr: expr_types,
+            type_of_pat: pat_types,
+            diagnostics: self.diagnostics,
+        }
+    }
+
+    /// Infers the type of a unary operation. Whether the operand of a negation has a signed type
+    /// is verified once the types of all literals are known.
+    fn infer_unary_op(&mut self, tgt_expr: ExprId, expr: ExprId, op: UnaryOp) -> Ty {
+        match op {
+            UnaryOp::Neg => self.infer_expr(expr, &Expectation::none()),
+            UnaryOp::Not => {
+                let bool_ty = Ty::simple(TypeCtor::Bool);
+                let ty = self.infer_expr_inner(expr, &Expectation::has_type(bool_ty.clone()));
+                if !self.unify(&ty, &bool_ty) {
+                    let ty = self.type_variables.replace_if_possible(&ty).into_owned();
+                    self.diagnostics
+                        .push(InferenceDiagnostic::CannotApplyUnaryOp { id: tgt_expr, ty });
+                }
+                bool_ty
+            }
+        }
+    }
+
+    /// Infers the type of a cast, e.g. `a as u8`, which is the target type of the cast. Only
+    /// enums and integers can be cast, to integer types.
+    fn infer_cast(&mut self, tgt_expr: ExprId, expr: ExprId, type_ref: TypeRefId) -> Ty {
+        let ty = self.infer_expr(expr, &Expectation::none());
+        let ty = self.type_variables.replace_if_possible(&ty).into_owned();
+        let cast_ty = self.resolve_type(type_ref);
+
+        let is_valid_source = match &ty {
+            Ty::Apply(a_ty) => match a_ty.ctor {
+                TypeCtor::Enum(_) | TypeCtor::Int(_) | TypeCtor::Never => true,
+                _ => false,
+            },
+            Ty::Infer(InferTy::IntVar(_)) | Ty::Unknown => true,
+            _ => false,
+        };
+        let is_valid_target = match cast_ty.as_simple() {
+            Some(TypeCtor::Int(_)) => true,
+            _ => cast_ty == Ty::Unknown,
+        };
+        if !is_valid_source || !is_valid_target {
+            self.diagnostics.push(InferenceDiagnostic::InvalidCast {
+                id: tgt_expr,
+                from_ty: ty,
+                to_ty: cast_ty.clone(),
+            });
+        }
+        cast_ty
+    }
+
+    fn infer_block(
+        &mut self,
+        tgt_expr: ExprId,
+        statements: &[Statement],
+        tail: Option<ExprId>,
+        expected: &Expectation,
+    ) -> Ty {
+        let mut diverges = false;
+        for stmt in statements {
+            match stmt {
+                Statement::Let {
+                    pat,
+                    type_ref,
+                    initializer,
                 } => {
                     let decl_ty = type_ref
                         .as_ref()
@@ -494,8 +1441,22 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
                         .unwrap_or(Ty::Unknown);
                     //let decl_ty = self.insert_type_vars(decl_ty);
                     let ty = if let Some(expr) = initializer {
-                        self.infer_expr_coerce(*expr, &Expectation::has_type(decl_ty))
+                        let expected = Expectation::has_type_from(
+                            decl_ty,
+                            type_ref.map(ExpectationOrigin::TypeRef),
+                        );
+                        let mut ty = self.infer_expr_coerce(*expr, &expected);
+                        // A function that is bound to a variable is a function pointer
+                        if let Some(def) = ty.as_function_def() {
+                            ty = Ty::fn_ptr(self.db.fn_signature(def));
+                            self.set_expr_type(*expr, ty.clone());
+                        }
+                        if self.type_of_expr[*expr].is_never() {
+                            diverges = true;
+                        }
+                        ty
                     } else {
+                        self.deferred_bindings.insert(*pat);
                         decl_ty
                     };
 
@@ -515,8 +1476,25 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
             if let ty_app!(TypeCtor::Never) = ty {
                 Ty::simple(TypeCtor::Never)
             } else {
-                self.coerce_expr_ty(expr, ty, expected)
+                let ty = self.coerce_expr_ty(expr, ty, expected);
+                // A trailing expression that doesn't have the expected type is reported where it
+                // is, rather than again for the whole block
+                if expected.is_none() {
+                    ty
+                } else {
+                    expected.ty.clone()
+                }
             }
+        } else if !diverges && self.is_missing_return_value(expected) {
+            // The function returns without a value at the end of the block, which is reported
+            // here rather than for the whole body
+            self.diagnostics
+                .push(InferenceDiagnostic::MissingReturnValue {
+                    id: tgt_expr,
+                    expected: expected.ty.clone(),
+                    origin: expected.origin,
+                });
+            expected.ty.clone()
         } else {
             Ty::Empty
         };
@@ -552,20 +1530,35 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
             Ty::Empty
         };
 
-        // Verify that it matches what we expected
-        let ty = if !expected.is_none() && ty != expected.ty {
+        // Verify that it matches what we expected. A `break` without a value from a loop in the
+        // position of the return value is reported as an exit without a return value.
+        let origin = expected.origin;
+        let ty = if expr.is_none() && self.is_missing_return_value(&expected) {
+            self.diagnostics
+                .push(InferenceDiagnostic::MissingReturnValue {
+                    id: tgt_expr,
+                    expected: expected.ty.clone(),
+                    origin: expected.origin,
+                });
+            expected.ty
+        } else if !expected.is_none() && !self.unify(&ty, &expected.ty) {
             self.diagnostics.push(InferenceDiagnostic::MismatchedTypes {
                 expected: expected.ty.clone(),
                 found: ty,
                 id: tgt_expr,
+                origin: expected.origin,
             });
             expected.ty
         } else {
             ty
         };
 
-        // Update the expected type for the rest of the loop
-        self.active_loop = Some(ActiveLoop::Loop(ty.clone(), Expectation::has_type(ty)));
+        // Update the expected type for the rest of the loop, which is still imposed by the same
+        // declaration
+        self.active_loop = Some(ActiveLoop::Loop(
+            ty.clone(),
+            Expectation::has_type_from(ty, origin),
+        ));
 
         Ty::simple(TypeCtor::Never)
     }
@@ -593,10 +1586,10 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
 
     fn infer_while_expr(
         &mut self,
-        _tgt_expr: ExprId,
+        tgt_expr: ExprId,
         condition: ExprId,
         body: ExprId,
-        _expected: &Expectation,
+        expected: &Expectation,
     ) -> Ty {
         self.infer_expr(
             condition,
@@ -604,7 +1597,139 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
         );
 
         self.infer_loop_block(body, ActiveLoop::While);
-        Ty::Empty
+        self.loop_without_value_ty(tgt_expr, expected)
+    }
+
+    fn infer_for_expr(
+        &mut self,
+        tgt_expr: ExprId,
+        pat: PatId,
+        start: ExprId,
+        end: ExprId,
+        body: ExprId,
+        expected: &Expectation,
+    ) -> Ty {
+        // Both bounds of the range must be integers of the same type, which is also the type of
+        // the loop variable
+        let elem_ty = self.new_int_var();
+        self.infer_expr(start, &Expectation::has_type(elem_ty.clone()));
+        self.infer_expr(end, &Expectation::has_type(elem_ty.clone()));
+        self.infer_pat(pat, elem_ty);
+
+        self.infer_loop_block(body, ActiveLoop::For);
+        self.loop_without_value_ty(tgt_expr, expected)
+    }
+
+    /// Returns the type of a `while` or `for` loop, which doesn't evaluate to a value. In the
+    /// position of the return value, the function returns without a value when the loop ends.
+    fn loop_without_value_ty(&mut self, tgt_expr: ExprId, expected: &Expectation) -> Ty {
+        if self.is_missing_return_value(expected) {
+            self.diagnostics
+                .push(InferenceDiagnostic::MissingReturnValue {
+                    id: tgt_expr,
+                    expected: expected.ty.clone(),
+                    origin: expected.origin,
+                });
+            expected.ty.clone()
+        } else {
+            Ty::Empty
+        }
+    }
+
+    /// Infers the type of a closure, which is a function pointer. The types of the parameters and
+    /// the return type that are not annotated are taken from the expected function pointer type.
+    /// Without an expected type, the return type is inferred from the body.
+    fn infer_closure(
+        &mut self,
+        args: &[PatId],
+        arg_types: &[Option<TypeRefId>],
+        ret_type: Option<TypeRefId>,
+        body: ExprId,
+        expected: &Expectation,
+    ) -> Ty {
+        let expected_ty = self
+            .type_variables
+            .replace_if_possible(&expected.ty)
+            .into_owned();
+        let expected_sig = expected_ty
+            .callable_sig(self.db)
+            .filter(|sig| expected_ty.is_fn_ptr() && sig.params().len() == args.len());
+
+        let mut param_tys = Vec::with_capacity(args.len());
+        for (idx, (pat, type_ref)) in args.iter().zip(arg_types).enumerate() {
+            let ty = match (type_ref, &expected_sig) {
+                (Some(type_ref), _) => self.resolve_type(*type_ref),
+                (None, Some(sig)) => sig.params()[idx].clone(),
+                (None, None) => {
+                    self.diagnostics
+                        .push(InferenceDiagnostic::CannotInferClosureParamType { id: *pat });
+                    Ty::Unknown
+                }
+            };
+            self.infer_pat(*pat, ty.clone());
+            param_tys.push(ty);
+        }
+        let ret_ty = match (ret_type, &expected_sig) {
+            (Some(type_ref), _) => Some(self.resolve_type(type_ref)),
+            (None, Some(sig)) => Some(sig.ret().clone()),
+            (None, None) => None,
+        };
+
+        // The body is inferred like the body of a function, `break` and `return` expressions in it
+        // don't refer to the enclosing function
+        self.active_closures.push(ActiveClosure {
+            bindings: closure_bindings(&self.body, args, body),
+            returns: if ret_ty.is_none() {
+                Some(Vec::new())
+            } else {
+                None
+            },
+        });
+        let outer_loop = self.active_loop.take();
+        let outer_return_ty =
+            mem::replace(&mut self.return_ty, ret_ty.clone().unwrap_or(Ty::Unknown));
+        let outer_return_type_ref = mem::replace(&mut self.return_type_ref, ret_type);
+        let body_ty = match &ret_ty {
+            Some(_) => self.infer_expr_coerce(body, &self.return_expectation()),
+            None => self.infer_expr(body, &Expectation::none()),
+        };
+        self.return_ty = outer_return_ty;
+        self.return_type_ref = outer_return_type_ref;
+        self.active_loop = outer_loop;
+        let closure = self.active_closures.pop().unwrap();
+
+        let ret_ty = match ret_ty {
+            Some(ty) => ty,
+            None => self.infer_closure_return_ty(body_ty, closure.returns.unwrap_or_default()),
+        };
+        Ty::fn_ptr(FnSig::from_params_and_return(param_tys, ret_ty))
+    }
+
+    /// Infers the return type of a closure from the type of its body and the values of its
+    /// `return` expressions, which must all have the same type.
+    fn infer_closure_return_ty(&mut self, body_ty: Ty, returns: Vec<ExprId>) -> Ty {
+        let body = Arc::clone(&self.body); // avoid borrow checker problem
+        let mut ret_ty = self
+            .type_variables
+            .replace_if_possible(&body_ty)
+            .into_owned();
+        for ret in returns {
+            let (value, ty) = match &body[ret] {
+                Expr::Return { expr: Some(expr) } => (*expr, self.type_of_expr[*expr].clone()),
+                _ => (ret, Ty::Empty),
+            };
+            if ret_ty.is_never() {
+                ret_ty = ty;
+            } else if !self.unify(&ty, &ret_ty) {
+                self.diagnostics.push(InferenceDiagnostic::MismatchedTypes {
+                    id: value,
+                    expected: ret_ty.clone(),
+                    found: ty,
+                    origin: None,
+                });
+            }
+        }
+        ret_ty
     }
 
     pub fn report_pat_inference_failure(&mut self, _pat: PatId) {
@@ -620,11 +1745,41 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
     }
 }
 
+/// Returns the bindings that are declared by a closure, which are its parameters and the bindings in
+/// its body.
+fn closure_bindings(body: &Body, args: &[PatId], closure_body: ExprId) -> FxHashSet<PatId> {
+    fn collect(body: &Body, expr: ExprId, bindings: &mut FxHashSet<PatId>) {
+        match &body[expr] {
+            Expr::Block { statements, .. } => {
+                for statement in statements {
+                    if let Statement::Let { pat, .. } = statement {
+                        bindings.insert(*pat);
+                    }
+                }
+            }
+            Expr::For { pat, .. } => {
+                bindings.insert(*pat);
+            }
+            Expr::Match { arms, .. } => bindings.extend(arms.iter().map(|arm| arm.pat)),
+            Expr::Closure { args, .. } => bindings.extend(args.iter().copied()),
+            _ => {}
+        }
+        body[expr].walk_child_exprs(|expr| collect(body, expr, bindings));
+    }
+
+    let mut bindings = args.iter().copied().collect();
+    collect(body, closure_body, &mut bindings);
+    bindings
+}
+
 /// When inferring an expression, we propagate downward whatever type hint we
 /// are able in the form of an `Expectation`.
 #[derive(Clone, PartialEq, Eq, Debug)]
 struct Expectation {
     ty: Ty,
+    /// The declaration that imposed the expected type, which is pointed out when the expectation
+    /// is not met.
+    origin: Option<ExpectationOrigin>,
     // FIXME: In some cases, we need to be aware whether the expectation is that
     // the type match exactly what we passed, or whether it just needs to be
     // coercible to the expected type. See Expectation::rvalue_hint in rustc.
@@ -634,17 +1789,45 @@ impl Expectation {
     /// The expectation that the type of the expression needs to equal the given
     /// type.
     fn has_type(ty: Ty) -> Self {
-        Expectation { ty }
+        Expectation { ty, origin: None }
+    }
+
+    /// The expectation that the type of the expression needs to equal the given type, which is
+    /// imposed by `origin`.
+    fn has_type_from(ty: Ty, origin: Option<ExpectationOrigin>) -> Self {
+        Expectation { ty, origin }
     }
 
     /// This expresses no expectation on the type.
     fn none() -> Self {
-        Expectation { ty: Ty::Unknown }
+        Expectation {
+            ty: Ty::Unknown,
+            origin: None,
+        }
     }
 
     fn is_none(&self) -> bool {
         self.ty == Ty::Unknown
     }
+
+    /// Returns true if a type annotation expects a floating point type. An unsuffixed integer
+    /// literal is a floating point value if an annotation expects one, e.g. the initializer of
+    /// `let health: float = 100`.
+    fn is_float_annotation(&self) -> bool {
+        match (self.origin, &self.ty) {
+            (Some(ExpectationOrigin::TypeRef(_)), ty_app!(TypeCtor::Float(_))) => true,
+            _ => false,
+        }
+    }
+}
+
+/// The declaration that imposed the type of an `Expectation`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum ExpectationOrigin {
+    /// A type annotation in the body, e.g. its return type or the type of a `let` binding
+    TypeRef(TypeRefId),
+    /// The parameter at the given index of the signature of a called function
+    Param(Function, usize),
 }
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
@@ -667,16 +1850,27 @@ impl From<PatId> for ExprOrPatId {
 
 mod diagnostics {
     use crate::diagnostics::{
-        BreakOutsideLoop, BreakWithValueOutsideLoop, CannotApplyBinaryOp, ExpectedFunction,
-        IncompatibleBranch, InvalidLHS, MismatchedType, MissingElseBranch, ParameterCountMismatch,
-        ReturnMissingExpression,
+        AccessUnknownField, BreakOutsideLoop, BreakWithValueOutsideLoop, CannotApplyBinaryOp,
+        CannotApplyBuiltinFunction, CannotApplyUnaryOp, CannotAssignToImmutable, CannotIndex,
+        CannotInferArrayType, CannotInferClosureParamType, CapturedVariable,
+        ConflictingLiteralTypes, ContinueOutsideLoop, DuplicateField, ExpectedFunction,
+        ExpectedTypeSource, FloatLiteralOutOfRange, IncompatibleBranch, IndexOutOfBounds,
+        InvalidCast, InvalidLHS, LiteralOutOfRange, MismatchedType, MissingElseBranch,
+        MissingFields, MissingReturnValue, NoSuchField, ParameterCountMismatch, PrivateField,
+        ReturnMissingExpression, UnresolvedMethod, UnresolvedStruct, UnsupportedPattern,
     };
     use crate::{
-        code_model::src::HasSource,
+        code_model::{src::HasSource, DefWithBody},
         diagnostics::{DiagnosticSink, UnresolvedType, UnresolvedValue},
-        ty::infer::ExprOrPatId,
+        expr::BodySourceMap,
+        ty::infer::{ExpectationOrigin, ExprOrPatId},
+        ty::lower::LowerDiagnostic,
         type_ref::TypeRefId,
-        ExprId, Function, HirDatabase, Ty,
+        BuiltinFunction, ExprId, FileId, FloatTy, Function, HirDatabase, IntTy, ModuleDef, Name,
+        Pat, PatId, StructField, Ty,
+    };
+    use mun_syntax::{
+        ast, ast::Radix, ast::TypeAscriptionOwner, AstNode, AstPtr, SyntaxNodePtr, TextRange,
     };
 
     #[derive(Debug, PartialEq, Eq, Clone)]
@@ -700,6 +1894,15 @@ mod diagnostics {
             id: ExprId,
             expected: Ty,
             found: Ty,
+            origin: Option<ExpectationOrigin>,
+        },
+        /// The use `id` of the value of the unsuffixed `literal` expects a type that conflicts with
+        /// the type `found` that an earlier use inferred for the literal.
+        ConflictingLiteralTypes {
+            id: ExprId,
+            literal: ExprId,
+            expected: Ty,
+            found: Ty,
         },
         IncompatibleBranches {
             id: ExprId,
@@ -710,10 +1913,25 @@ mod diagnostics {
             id: ExprId,
             then_ty: Ty,
         },
+        MissingReturnValue {
+            id: ExprId,
+            expected: Ty,
+            origin: Option<ExpectationOrigin>,
+        },
         CannotApplyBinaryOp {
             id: ExprId,
             lhs: Ty,
             rhs: Ty,
+            mismatched_operands: bool,
+        },
+        CannotApplyUnaryOp {
+            id: ExprId,
+            ty: Ty,
+        },
+        CannotApplyBuiltinFunction {
+            id: ExprId,
+            function: BuiltinFunction,
+            ty: Ty,
         },
         InvalidLHS {
             id: ExprId,
@@ -728,16 +1946,107 @@ mod diagnostics {
         BreakWithValueOutsideLoop {
             id: ExprId,
         },
+        ContinueOutsideLoop {
+            id: ExprId,
+        },
+        LiteralOutOfRange {
+            id: ExprId,
+            literal_ty: IntTy,
+            radix: Radix,
+        },
+        FloatLiteralOutOfRange {
+            id: ExprId,
+            float_ty: FloatTy,
+            rounded_to_zero: bool,
+        },
+        AccessUnknownField {
+            id: ExprId,
+            receiver_ty: Ty,
+            name: Name,
+        },
+        UnresolvedStruct {
+            id: ExprId,
+        },
+        NoSuchField {
+            id: ExprId,
+            field: usize,
+        },
+        DuplicateField {
+            id: ExprId,
+            field: usize,
+            name: Name,
+        },
+        MissingFields {
+            id: ExprId,
+            struct_ty: Ty,
+            field_names: Vec<Name>,
+        },
+        CannotInferArrayType {
+            id: ExprId,
+        },
+        CannotIndex {
+            id: ExprId,
+            ty: Ty,
+        },
+        IndexOutOfBounds {
+            id: ExprId,
+            index: u128,
+            len: u64,
+        },
+        InvalidCast {
+            id: ExprId,
+            from_ty: Ty,
+            to_ty: Ty,
+        },
+        MismatchedPatternType {
+            id: PatId,
+            expected: Ty,
+            found: Ty,
+        },
+        UnsupportedPattern {
+            id: PatId,
+        },
+        UnresolvedMethod {
+            id: ExprId,
+            receiver_ty: Ty,
+            name: Name,
+        },
+        CannotAssignToImmutable {
+            lhs: ExprId,
+            binding: PatId,
+        },
+        CapturedVariable {
+            id: ExprId,
+            binding: PatId,
+        },
+        CannotInferClosureParamType {
+            id: PatId,
+        },
+        PrivateItem {
+            id: ExprOrPatId,
+            def: ModuleDef,
+        },
+        PrivateType {
+            id: TypeRefId,
+            def: ModuleDef,
+        },
+        InvalidArrayLength {
+            id: TypeRefId,
+        },
+        PrivateField {
+            id: ExprId,
+            field: StructField,
+        },
     }
 
     impl InferenceDiagnostic {
         pub(crate) fn add_to(
             &self,
             db: &impl HirDatabase,
-            owner: Function,
+            owner: DefWithBody,
             sink: &mut DiagnosticSink,
         ) {
-            let file = owner.source(db).file_id;
+            let file = owner.file_id(db);
             let body = owner.body_source_map(db);
             match self {
                 InferenceDiagnostic::UnresolvedValue { id } => {
@@ -754,8 +2063,11 @@ mod diagnostics {
                     sink.push(UnresolvedValue { file, expr });
                 }
                 InferenceDiagnostic::UnresolvedType { id } => {
-                    let type_ref = body.type_ref_syntax(*id).expect("If this is not found, it must be a type ref generated by the library which should never be unresolved.");
-                    sink.push(UnresolvedType { file, type_ref });
+                    // Type refs without a source, like the type of `self`, are reported where
+                    // they are declared
+                    if let Some(type_ref) = body.type_ref_syntax(*id) {
+                        sink.push(UnresolvedType { file, type_ref });
+                    }
                 }
                 InferenceDiagnostic::ParameterCountMismatch {
                     id,
@@ -782,6 +2094,7 @@ mod diagnostics {
                     id,
                     found,
                     expected,
+                    origin,
                 } => {
                     let expr = body.expr_syntax(*id).unwrap().ast.syntax_node_ptr();
                     sink.push(MismatchedType {
@@ -789,6 +2102,23 @@ mod diagnostics {
                         expr,
                         found: found.clone(),
                         expected: expected.clone(),
+                        expected_source: expected_type_source(db, file, &body, *origin),
+                    });
+                }
+                InferenceDiagnostic::ConflictingLiteralTypes {
+                    id,
+                    literal,
+                    expected,
+                    found,
+                } => {
+                    let expr = body.expr_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    let literal = body.expr_syntax(*literal).unwrap().ast.syntax_node_ptr();
+                    sink.push(ConflictingLiteralTypes {
+                        file,
+                        expr,
+                        literal,
+                        expected: expected.clone(),
+                        found: found.clone(),
                     });
                 }
                 InferenceDiagnostic::IncompatibleBranches {
@@ -812,13 +2142,55 @@ mod diagnostics {
                         found: then_ty.clone(),
                     });
                 }
-                InferenceDiagnostic::CannotApplyBinaryOp { id, lhs, rhs } => {
+                InferenceDiagnostic::MissingReturnValue {
+                    id,
+                    expected,
+                    origin,
+                } => {
+                    let exit = body.expr_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    sink.push(MissingReturnValue {
+                        file,
+                        exit,
+                        expected: expected.clone(),
+                        expected_source: expected_type_source(db, file, &body, *origin),
+                    });
+                }
+                InferenceDiagnostic::CannotApplyBinaryOp {
+                    id,
+                    lhs,
+                    rhs,
+                    mismatched_operands,
+                } => {
                     let expr = body.expr_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    let (op, op_range) = operator(db, file, expr);
                     sink.push(CannotApplyBinaryOp {
                         file,
                         expr,
+                        op,
+                        op_range,
                         lhs: lhs.clone(),
                         rhs: rhs.clone(),
+                        mismatched_operands: *mismatched_operands,
+                    });
+                }
+                InferenceDiagnostic::CannotApplyUnaryOp { id, ty } => {
+                    let expr = body.expr_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    let (op, op_range) = operator(db, file, expr);
+                    sink.push(CannotApplyUnaryOp {
+                        file,
+                        expr,
+                        op,
+                        op_range,
+                        ty: ty.clone(),
+                    });
+                }
+                InferenceDiagnostic::CannotApplyBuiltinFunction { id, function, ty } => {
+                    let expr = body.expr_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    sink.push(CannotApplyBuiltinFunction {
+                        file,
+                        expr,
+                        name: function.name(),
+                        ty: ty.clone(),
                     });
                 }
                 InferenceDiagnostic::InvalidLHS { id, lhs } => {
@@ -851,7 +2223,276 @@ mod diagnostics {
                         break_expr: id,
                     });
                 }
+                InferenceDiagnostic::ContinueOutsideLoop { id } => {
+                    let id = body.expr_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    sink.push(ContinueOutsideLoop {
+                        file,
+                        continue_expr: id,
+                    });
+                }
+                InferenceDiagnostic::LiteralOutOfRange {
+                    id,
+                    literal_ty,
+                    radix,
+                } => {
+                    let literal = body.expr_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    sink.push(LiteralOutOfRange {
+                        file,
+                        literal,
+                        literal_ty: *literal_ty,
+                        radix: *radix,
+                    });
+                }
+                InferenceDiagnostic::FloatLiteralOutOfRange {
+                    id,
+                    float_ty,
+                    rounded_to_zero,
+                } => {
+                    let literal = body.expr_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    sink.push(FloatLiteralOutOfRange {
+                        file,
+                        literal,
+                        float_ty: *float_ty,
+                        rounded_to_zero: *rounded_to_zero,
+                    });
+                }
+                InferenceDiagnostic::AccessUnknownField {
+                    id,
+                    receiver_ty,
+                    name,
+                } => {
+                    let expr = body.expr_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    sink.push(AccessUnknownField {
+                        file,
+                        expr,
+                        receiver_ty: receiver_ty.clone(),
+                        name: name.clone(),
+                    });
+                }
+                InferenceDiagnostic::UnresolvedStruct { id } => {
+                    let expr = body.expr_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    sink.push(UnresolvedStruct { file, expr });
+                }
+                InferenceDiagnostic::NoSuchField { id, field } => {
+                    let field = body.field_syntax(*id, *field).syntax_node_ptr();
+                    sink.push(NoSuchField { file, field });
+                }
+                InferenceDiagnostic::DuplicateField { id, field, name } => {
+                    let field = body.field_syntax(*id, *field).syntax_node_ptr();
+                    sink.push(DuplicateField {
+                        file,
+                        field,
+                        name: name.clone(),
+                    });
+                }
+                InferenceDiagnostic::MissingFields {
+                    id,
+                    struct_ty,
+                    field_names,
+                } => {
+                    let expr = body.expr_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    sink.push(MissingFields {
+                        file,
+                        expr,
+                        struct_ty: struct_ty.clone(),
+                        field_names: field_names.clone(),
+                    });
+                }
+                InferenceDiagnostic::CannotInferArrayType { id } => {
+                    let expr = body.expr_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    sink.push(CannotInferArrayType { file, expr });
+                }
+                InferenceDiagnostic::CannotIndex { id, ty } => {
+                    let expr = body.expr_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    sink.push(CannotIndex {
+                        file,
+                        expr,
+                        ty: ty.clone(),
+                    });
+                }
+                InferenceDiagnostic::IndexOutOfBounds { id, index, len } => {
+                    let index_ptr = body.expr_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    sink.push(IndexOutOfBounds {
+                        file,
+                        index: index_ptr,
+                        value: *index,
+                        len: *len,
+                    });
+                }
+                InferenceDiagnostic::InvalidCast { id, from_ty, to_ty } => {
+                    let expr = body.expr_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    sink.push(InvalidCast {
+                        file,
+                        expr,
+                        from_ty: from_ty.clone(),
+                        to_ty: to_ty.clone(),
+                    });
+                }
+                InferenceDiagnostic::MismatchedPatternType {
+                    id,
+                    expected,
+                    found,
+                } => {
+                    let pat = body.pat_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    sink.push(MismatchedType {
+                        file,
+                        expr: pat,
+                        expected: expected.clone(),
+                        found: found.clone(),
+                        expected_source: None,
+                    });
+                }
+                InferenceDiagnostic::UnsupportedPattern { id } => {
+                    let pat = body.pat_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    sink.push(UnsupportedPattern { file, pat });
+                }
+                InferenceDiagnostic::UnresolvedMethod {
+                    id,
+                    receiver_ty,
+                    name,
+                } => {
+                    let expr = body.expr_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    sink.push(UnresolvedMethod {
+                        file,
+                        expr,
+                        receiver_ty: receiver_ty.clone(),
+                        name: name.clone(),
+                    });
+                }
+                InferenceDiagnostic::CannotAssignToImmutable { lhs, binding } => {
+                    let lhs = body.expr_syntax(*lhs).unwrap().ast.syntax_node_ptr();
+                    // The `self` parameter of a method is the only binding without a source
+                    let binding_ptr = body
+                        .pat_syntax(*binding)
+                        .map(|ptr| ptr.ast.syntax_node_ptr())
+                        .or_else(|| match owner {
+                            DefWithBody::Function(f) => f
+                                .source(db)
+                                .ast
+                                .param_list()
+                                .and_then(|params| params.self_param())
+                                .map(|param| SyntaxNodePtr::new(param.syntax())),
+                            _ => None,
+                        })
+                        .unwrap();
+                    let name = match &owner.body(db)[*binding] {
+                        Pat::Bind { name, .. } => name.clone(),
+                        _ => Name::missing(),
+                    };
+                    sink.push(CannotAssignToImmutable {
+                        file,
+                        lhs,
+                        binding: binding_ptr,
+                        name,
+                    });
+                }
+                InferenceDiagnostic::CapturedVariable { id, binding } => {
+                    let expr = body.expr_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    let name = match &owner.body(db)[*binding] {
+                        Pat::Bind { name, .. } => name.clone(),
+                        _ => Name::missing(),
+                    };
+                    sink.push(CapturedVariable { file, expr, name });
+                }
+                InferenceDiagnostic::CannotInferClosureParamType { id } => {
+                    let pat = body.pat_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    sink.push(CannotInferClosureParamType { file, pat });
+                }
+                InferenceDiagnostic::PrivateItem { id, def } => {
+                    let path = match id {
+                        ExprOrPatId::ExprId(id) => {
+                            body.expr_syntax(*id).map(|ptr| ptr.ast.syntax_node_ptr())
+                        }
+                        ExprOrPatId::PatId(id) => {
+                            body.pat_syntax(*id).map(|ptr| ptr.ast.syntax_node_ptr())
+                        }
+                    }
+                    .unwrap();
+                    sink.push(def.private_item_diagnostic(db, file, path));
+                }
+                InferenceDiagnostic::PrivateType { id, def } => {
+                    if let Some(type_ref) = body.type_ref_syntax(*id) {
+                        sink.push(def.private_item_diagnostic(
+                            db,
+                            file,
+                            type_ref.syntax_node_ptr(),
+                        ));
+                    }
+                }
+                InferenceDiagnostic::InvalidArrayLength { id } => {
+                    LowerDiagnostic::InvalidArrayLength { id: *id }.add_to(
+                        db,
+                        file,
+                        body.type_refs(),
+                        sink,
+                    );
+                }
+                InferenceDiagnostic::PrivateField { id, field } => {
+                    let expr = body.expr_syntax(*id).unwrap().ast.syntax_node_ptr();
+                    let strukt = field.parent_struct();
+                    let definition = strukt.source(db);
+                    sink.push(PrivateField {
+                        file,
+                        expr,
+                        struct_name: strukt.name(db).to_string(),
+                        field: field.name(db),
+                        definition_file: definition.file_id,
+                        definition: SyntaxNodePtr::new(definition.ast.syntax()),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Returns the type annotation of `origin`, which imposed an expected type in the body of
+    /// `source_map`.
+    fn expected_type_source(
+        db: &impl HirDatabase,
+        file: FileId,
+        source_map: &BodySourceMap,
+        origin: Option<ExpectationOrigin>,
+    ) -> Option<ExpectedTypeSource> {
+        match origin? {
+            ExpectationOrigin::TypeRef(type_ref) => {
+                source_map
+                    .type_ref_syntax(type_ref)
+                    .map(|type_ref| ExpectedTypeSource {
+                        file,
+                        type_ref,
+                        is_param: false,
+                    })
             }
+            ExpectationOrigin::Param(function, idx) => param_type_source(db, function, idx),
+        }
+    }
+
+    /// Returns the type annotation of the parameter at index `idx` of the signature of `function`.
+    /// The `self` parameter has no annotation.
+    fn param_type_source(
+        db: &impl HirDatabase,
+        function: Function,
+        idx: usize,
+    ) -> Option<ExpectedTypeSource> {
+        let idx = idx.checked_sub(function.has_self_param(db) as usize)?;
+        let src = function.source(db);
+        let type_ref = src.ast.param_list()?.params().nth(idx)?.ascribed_type()?;
+        Some(ExpectedTypeSource {
+            file: src.file_id,
+            type_ref: AstPtr::new(&type_ref),
+            is_param: true,
+        })
+    }
+
+    /// Returns the text and the range of the operator of the binary or unary operation `expr`.
+    fn operator(db: &impl HirDatabase, file: FileId, expr: SyntaxNodePtr) -> (String, TextRange) {
+        let node = expr.to_node(db.parse(file).tree().syntax());
+        let token = match ast::BinExpr::cast(node.clone()) {
+            Some(bin_expr) => bin_expr.op_token(),
+            None => ast::PrefixExpr::cast(node).and_then(|prefix| prefix.op_token()),
+        };
+        match token {
+            Some(token) => (token.text().to_string(), token.text_range()),
+            None => (String::new(), expr.range()),
         }
     }
 }
@@ -20,14 +20,17 @@ impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
     }
 
     fn coerce_inner(&mut self, from_ty: Ty, to_ty: &Ty) -> bool {
-        match (&from_ty, to_ty) {
-            (ty_app!(TypeCtor::Never), ..) => return true,
-            _ => {
-                if self.unify_inner_trivial(&from_ty, &to_ty) {
-                    return true;
-                }
+        if let ty_app!(TypeCtor::Never) = from_ty {
+            return true;
+        }
+
+        // A function definition coerces to a function pointer with the same signature
+        if let ty_app!(TypeCtor::FnDef(def)) = from_ty {
+            let to_ty = self.type_variables.replace_if_possible(to_ty).into_owned();
+            if to_ty.is_fn_ptr() {
+                return self.unify(&Ty::fn_ptr(self.db.fn_signature(def)), &to_ty);
             }
-        };
+        }
 
         self.unify(&from_ty, to_ty)
     }
@@ -1,32 +1,52 @@
 use crate::{
-    ty::infer::InferenceResultBuilder, Expr, ExprId, HirDatabase, Path, Resolution, Resolver,
+    expr::PatId, ty::infer::InferenceResultBuilder, Expr, ExprId, HirDatabase, ModuleDef, Pat,
+    Path, Resolution, Resolver,
 };
 use std::sync::Arc;
 
+/// The reason why an expression cannot be assigned to.
+pub(super) enum PlaceError {
+    /// The expression doesn't represent a memory location.
+    NotAPlace,
+
+    /// The memory location belongs to a local binding that is not declared `mut`.
+    ImmutableBinding(PatId),
+}
+
 impl<'a, D: HirDatabase> InferenceResultBuilder<'a, D> {
-    /// Checks if the specified expression is a place-expression. A place expression represents a
-    /// memory location.
-    pub(super) fn check_place_expression(&mut self, resolver: &Resolver, expr: ExprId) -> bool {
+    /// Checks if the specified expression is a place-expression that can be assigned to. A place
+    /// expression represents a memory location. Assigning to a field or an element of a local
+    /// binding requires the binding itself to be mutable.
+    pub(super) fn check_place_expression(
+        &mut self,
+        resolver: &Resolver,
+        expr: ExprId,
+    ) -> Result<(), PlaceError> {
         let body = Arc::clone(&self.body); // avoid borrow checker problem
         match &body[expr] {
             Expr::Path(p) => self.check_place_path(resolver, p),
-            _ => false,
+            Expr::Field { expr, .. } => self.check_place_expression(resolver, *expr),
+            Expr::Index { base, .. } => self.check_place_expression(resolver, *base),
+            _ => Err(PlaceError::NotAPlace),
         }
     }
 
-    /// Checks if the specified path references a memory location.
-    fn check_place_path(&mut self, resolver: &Resolver, path: &Path) -> bool {
-        let resolution = match resolver
+    /// Checks if the specified path references a memory location that can be assigned to.
+    fn check_place_path(&mut self, resolver: &Resolver, path: &Path) -> Result<(), PlaceError> {
+        let resolution = resolver
             .resolve_path_without_assoc_items(self.db, path)
             .take_values()
-        {
-            Some(resolution) => resolution,
-            None => return false,
-        };
+            .ok_or(PlaceError::NotAPlace)?;
 
         match resolution {
-            Resolution::LocalBinding(_) => true,
-            Resolution::Def(_) => false,
+            Resolution::LocalBinding(pat) => match &self.body[pat] {
+                Pat::Bind {
+                    is_mutable: true, ..
+                } => Ok(()),
+                _ => Err(PlaceError::ImmutableBinding(pat)),
+            },
+            Resolution::Def(ModuleDef::Static(s)) if s.is_mutable(self.db) => Ok(()),
+            Resolution::Def(_) => Err(PlaceError::NotAPlace),
         }
     }
 }
@@ -109,6 +109,11 @@ impl TypeVariableTable {
         self.eq_relations.union(a, b);
     }
 
+    /// Returns the variable that represents all variables that are equal to `tv`.
+    pub fn find(&mut self, tv: TypeVarId) -> TypeVarId {
+        self.eq_relations.find(tv)
+    }
+
     /// Instantiates `tv` with the type `ty`.
     pub fn instantiate(&mut self, tv: TypeVarId, ty: Ty) {
         debug_assert!(
@@ -126,7 +131,7 @@ impl TypeVariableTable {
     pub fn replace_if_possible<'t>(&mut self, ty: &'t Ty) -> Cow<'t, Ty> {
         let ty = Cow::Borrowed(ty);
         match &*ty {
-            Ty::Infer(tv) => match self.eq_relations.probe_value(*tv).known() {
+            Ty::Infer(tv) => match self.eq_relations.probe_value(tv.to_inner()).known() {
                 Some(known_ty) => Cow::Owned(known_ty.clone()),
                 _ => ty,
             },
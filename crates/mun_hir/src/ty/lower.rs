@@ -4,7 +4,10 @@ use crate::name_resolution::Namespace;
 use crate::resolve::{Resolution, Resolver};
 use crate::ty::{FnSig, Ty, TypeCtor};
 use crate::type_ref::{TypeRef, TypeRefId, TypeRefMap};
-use crate::{Function, HirDatabase, ModuleDef, Path};
+use crate::{
+    Const, Enum, EnumVariant, Function, HirDatabase, ModuleDef, Path, Static, Struct, StructKind,
+    TypeAlias,
+};
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub(crate) struct LowerResult {
@@ -34,15 +37,56 @@ impl Ty {
     ) -> Ty {
         let res = match &type_ref_map[type_ref] {
             TypeRef::Path(path) => Ty::from_hir_path(db, resolver, path),
+            TypeRef::Tuple(fields) => Some(Ty::tuple(
+                fields
+                    .iter()
+                    .map(|field| {
+                        Ty::from_hir_with_diagnostics(
+                            db,
+                            resolver,
+                            type_ref_map,
+                            diagnostics,
+                            *field,
+                        )
+                    })
+                    .collect(),
+            )),
+            TypeRef::Array(elem, len) => {
+                let elem =
+                    Ty::from_hir_with_diagnostics(db, resolver, type_ref_map, diagnostics, *elem);
+                match len {
+                    Some(len) => Some(Ty::array(elem, *len)),
+                    None => {
+                        diagnostics.push(LowerDiagnostic::InvalidArrayLength { id: type_ref });
+                        Some(Ty::Unknown)
+                    }
+                }
+            }
+            TypeRef::Fn(params, ret) => {
+                let mut lower = |type_ref: TypeRefId| {
+                    Ty::from_hir_with_diagnostics(db, resolver, type_ref_map, diagnostics, type_ref)
+                };
+                let params = params.iter().map(|param| lower(*param)).collect();
+                let ret = lower(*ret);
+                Some(Ty::fn_ptr(FnSig::from_params_and_return(params, ret)))
+            }
             TypeRef::Error => Some(Ty::Unknown),
             TypeRef::Empty => Some(Ty::Empty),
             TypeRef::Never => Some(Ty::simple(TypeCtor::Never)),
         };
-        if let Some(ty) = res {
-            ty
-        } else {
-            diagnostics.push(LowerDiagnostic::UnresolvedType { id: type_ref });
-            Ty::Unknown
+        match (res, &type_ref_map[type_ref]) {
+            (Some(ty), TypeRef::Path(path)) => {
+                if let Some(def) = resolver.private_item(db, path) {
+                    diagnostics.push(LowerDiagnostic::PrivateItem { id: type_ref, def });
+                }
+                ty
+            }
+            (Some(ty), _) => ty,
+            (None, TypeRef::Path(path)) if resolver.is_unresolved_import(db, path) => Ty::Unknown,
+            (None, _) => {
+                diagnostics.push(LowerDiagnostic::UnresolvedType { id: type_ref });
+                Ty::Unknown
+            }
         }
     }
 
@@ -78,6 +122,12 @@ impl Ty {
 pub enum TypableDef {
     Function(Function),
     BuiltinType(BuiltinType),
+    Struct(Struct),
+    Enum(Enum),
+    EnumVariant(EnumVariant),
+    Const(Const),
+    Static(Static),
+    TypeAlias(TypeAlias),
 }
 
 impl From<Function> for TypableDef {
@@ -92,11 +142,55 @@ impl From<BuiltinType> for TypableDef {
     }
 }
 
+impl From<Struct> for TypableDef {
+    fn from(f: Struct) -> Self {
+        TypableDef::Struct(f)
+    }
+}
+
+impl From<Enum> for TypableDef {
+    fn from(f: Enum) -> Self {
+        TypableDef::Enum(f)
+    }
+}
+
+impl From<EnumVariant> for TypableDef {
+    fn from(f: EnumVariant) -> Self {
+        TypableDef::EnumVariant(f)
+    }
+}
+
+impl From<Const> for TypableDef {
+    fn from(f: Const) -> Self {
+        TypableDef::Const(f)
+    }
+}
+
+impl From<Static> for TypableDef {
+    fn from(f: Static) -> Self {
+        TypableDef::Static(f)
+    }
+}
+
+impl From<TypeAlias> for TypableDef {
+    fn from(f: TypeAlias) -> Self {
+        TypableDef::TypeAlias(f)
+    }
+}
+
 impl From<ModuleDef> for Option<TypableDef> {
     fn from(d: ModuleDef) -> Self {
         match d {
             ModuleDef::Function(f) => Some(TypableDef::Function(f)),
             ModuleDef::BuiltinType(t) => Some(TypableDef::BuiltinType(t)),
+            // The type of a builtin function depends on the arguments of the call
+            ModuleDef::BuiltinFunction(_) => None,
+            ModuleDef::Struct(t) => Some(TypableDef::Struct(t)),
+            ModuleDef::Enum(e) => Some(TypableDef::Enum(e)),
+            ModuleDef::EnumVariant(v) => Some(TypableDef::EnumVariant(v)),
+            ModuleDef::Const(c) => Some(TypableDef::Const(c)),
+            ModuleDef::Static(s) => Some(TypableDef::Static(s)),
+            ModuleDef::TypeAlias(t) => Some(TypableDef::TypeAlias(t)),
         }
     }
 }
@@ -109,19 +203,32 @@ pub(crate) fn type_for_def(db: &impl HirDatabase, def: TypableDef, ns: Namespace
     match (def, ns) {
         (TypableDef::Function(f), Namespace::Values) => type_for_fn(db, f),
         (TypableDef::BuiltinType(t), Namespace::Types) => type_for_builtin(t),
+        (TypableDef::Struct(s), Namespace::Types) => type_for_struct(s),
+        (TypableDef::Struct(s), Namespace::Values) => type_for_struct_value(db, s),
+        (TypableDef::Enum(e), Namespace::Types) => type_for_enum(e),
+        (TypableDef::EnumVariant(v), Namespace::Values) => type_for_enum(v.parent_enum()),
+        (TypableDef::Const(c), Namespace::Values) => type_for_const(db, c),
+        (TypableDef::Static(s), Namespace::Values) => type_for_static(db, s),
+        (TypableDef::TypeAlias(t), Namespace::Types) => type_for_type_alias(db, t),
 
         // 'error' cases:
         (TypableDef::Function(_), Namespace::Types) => Ty::Unknown,
         (TypableDef::BuiltinType(_), Namespace::Values) => Ty::Unknown,
+        (TypableDef::Enum(_), Namespace::Values) => Ty::Unknown,
+        (TypableDef::EnumVariant(_), Namespace::Types) => Ty::Unknown,
+        (TypableDef::Const(_), Namespace::Types) => Ty::Unknown,
+        (TypableDef::Static(_), Namespace::Types) => Ty::Unknown,
+        (TypableDef::TypeAlias(_), Namespace::Values) => Ty::Unknown,
     }
 }
 
 /// Build the declared type of a static.
 fn type_for_builtin(def: BuiltinType) -> Ty {
     Ty::simple(match def {
-        BuiltinType::Float => TypeCtor::Float,
-        BuiltinType::Int => TypeCtor::Int,
+        BuiltinType::Float(ty) => TypeCtor::Float(ty),
+        BuiltinType::Int(ty) => TypeCtor::Int(ty),
         BuiltinType::Boolean => TypeCtor::Bool,
+        BuiltinType::Str => TypeCtor::Str,
     })
 }
 
@@ -131,6 +238,49 @@ fn type_for_fn(_db: &impl HirDatabase, def: Function) -> Ty {
     Ty::simple(TypeCtor::FnDef(def))
 }
 
+/// Build the type of a struct.
+fn type_for_struct(def: Struct) -> Ty {
+    Ty::simple(TypeCtor::Struct(def))
+}
+
+/// Build the type of a struct used as a value. Only unit structs can be used as values.
+fn type_for_struct_value(db: &impl HirDatabase, def: Struct) -> Ty {
+    match def.data(db).kind {
+        StructKind::Unit => type_for_struct(def),
+        StructKind::Record => Ty::Unknown,
+    }
+}
+
+/// Build the type of an enum, which is also the type of its variants.
+fn type_for_enum(def: Enum) -> Ty {
+    Ty::simple(TypeCtor::Enum(def))
+}
+
+/// Build the declared type of a constant.
+fn type_for_const(db: &impl HirDatabase, def: Const) -> Ty {
+    let data = def.data(db);
+    let resolver = def.resolver(db);
+    Ty::from_hir(db, &resolver, data.type_ref_map(), *data.type_ref()).ty
+}
+
+/// Build the declared type of a static.
+fn type_for_static(db: &impl HirDatabase, def: Static) -> Ty {
+    let data = def.data(db);
+    let resolver = def.resolver(db);
+    Ty::from_hir(db, &resolver, data.type_ref_map(), *data.type_ref()).ty
+}
+
+/// Build the type of a type alias, which is the type of its target. Cyclic aliases are reported
+/// during validation.
+fn type_for_type_alias(db: &impl HirDatabase, def: TypeAlias) -> Ty {
+    if def.is_cyclic(db) {
+        return Ty::Unknown;
+    }
+    let data = def.data(db);
+    let resolver = def.resolver(db);
+    Ty::from_hir(db, &resolver, data.type_ref_map(), *data.type_ref()).ty
+}
+
 pub fn fn_sig_for_fn(db: &impl HirDatabase, def: Function) -> FnSig {
     let data = def.data(db);
     let resolver = def.resolver(db);
@@ -144,10 +294,59 @@ pub fn fn_sig_for_fn(db: &impl HirDatabase, def: Function) -> FnSig {
 }
 
 pub mod diagnostics {
-    use crate::type_ref::TypeRefId;
+    use crate::code_model::add_int_const_diagnostic;
+    use crate::diagnostics::{DiagnosticSink, InvalidArrayLength, UnresolvedType};
+    use crate::type_ref::{array_len, TypeRefId, TypeRefSourceMap};
+    use crate::{FileId, HirDatabase, ModuleDef};
+    use mun_syntax::{ast, AstNode};
 
     #[derive(Debug, PartialEq, Eq, Clone)]
     pub(crate) enum LowerDiagnostic {
         UnresolvedType { id: TypeRefId },
+        PrivateItem { id: TypeRefId, def: ModuleDef },
+        InvalidArrayLength { id: TypeRefId },
+    }
+
+    impl LowerDiagnostic {
+        /// Adds the diagnostic to `sink` for a type reference in `file` of which the syntax is
+        /// found in `source_map`.
+        pub(crate) fn add_to(
+            &self,
+            db: &impl HirDatabase,
+            file: FileId,
+            source_map: &TypeRefSourceMap,
+            sink: &mut DiagnosticSink,
+        ) {
+            match self {
+                LowerDiagnostic::UnresolvedType { id } => {
+                    if let Some(type_ref) = source_map.type_ref_syntax(*id) {
+                        sink.push(UnresolvedType { file, type_ref });
+                    }
+                }
+                LowerDiagnostic::PrivateItem { id, def } => {
+                    if let Some(type_ref) = source_map.type_ref_syntax(*id) {
+                        sink.push(def.private_item_diagnostic(
+                            db,
+                            file,
+                            type_ref.syntax_node_ptr(),
+                        ));
+                    }
+                }
+                LowerDiagnostic::InvalidArrayLength { id } => {
+                    // Type references are stored without source locations, so the length is
+                    // evaluated again to find the offending expression
+                    let type_ref = match source_map.type_ref_syntax(*id) {
+                        Some(type_ref) => type_ref.to_node(db.parse(file).tree().syntax()),
+                        None => return,
+                    };
+                    if let ast::TypeRefKind::ArrayType(array) = type_ref.kind() {
+                        if let Err(error) = array_len(&array) {
+                            let invalid_len = |len| InvalidArrayLength { file, len };
+                            add_int_const_diagnostic(file, error, invalid_len, sink);
+                        }
+                    }
+                }
+            }
+        }
     }
 }
@@ -1,7 +1,62 @@
-use crate::{BinaryOp, Ty, TypeCtor};
+use crate::{ty::infer::InferTy, ArithOp, BinaryOp, CmpOp, Ty, TypeCtor};
 
-pub(super) fn binary_op_rhs_expectation(_op: BinaryOp, lhs_ty: Ty) -> Ty {
-    lhs_ty
+/// Returns true if the binary operator `op` can be applied to a left-hand side of type `lhs_ty`.
+/// Types that are not known yet are accepted to avoid cascading errors. The right-hand side must
+/// have the same type.
+pub(super) fn is_valid_binary_op(op: BinaryOp, lhs_ty: &Ty) -> bool {
+    let (is_numeric, is_integer) = match lhs_ty {
+        Ty::Apply(a_ty) => match a_ty.ctor {
+            TypeCtor::Int(_) => (true, true),
+            TypeCtor::Float(_) => (true, false),
+            TypeCtor::Never => return true,
+            _ => (false, false),
+        },
+        Ty::Infer(InferTy::IntVar(_)) => (true, true),
+        Ty::Infer(InferTy::FloatVar(_)) => (true, false),
+        Ty::Infer(InferTy::TypeVar(_)) | Ty::Unknown => return true,
+        Ty::Empty => (false, false),
+    };
+    match op {
+        // Shifts only apply to the bits of integers
+        BinaryOp::ArithOp(ArithOp::LeftShift) | BinaryOp::ArithOp(ArithOp::RightShift) => {
+            is_integer
+        }
+        BinaryOp::ArithOp(_)
+        | BinaryOp::CmpOp(CmpOp::Ord { .. })
+        | BinaryOp::Assignment { op: Some(_) } => is_numeric,
+        BinaryOp::CmpOp(CmpOp::Eq { .. }) => {
+            is_numeric
+                || match lhs_ty.as_simple() {
+                    Some(TypeCtor::Bool) | Some(TypeCtor::Enum(_)) => true,
+                    _ => false,
+                }
+        }
+        BinaryOp::LogicOp(_) | BinaryOp::Assignment { op: None } => true,
+    }
+}
+
+/// Returns true if the types of the operands of `op` are verified by `is_valid_binary_op`, instead
+/// of by the types that are expected of them. The operands of logic operators must be booleans,
+/// and an assigned value must have the type of the place that it is assigned to.
+pub(super) fn checks_operand_types(op: BinaryOp) -> bool {
+    match op {
+        BinaryOp::LogicOp(_) | BinaryOp::Assignment { op: None } => false,
+        BinaryOp::ArithOp(_) | BinaryOp::CmpOp(_) | BinaryOp::Assignment { op: Some(_) } => true,
+    }
+}
+
+pub(super) fn binary_op_lhs_expectation(op: BinaryOp) -> Option<Ty> {
+    match op {
+        BinaryOp::LogicOp(_) => Some(Ty::simple(TypeCtor::Bool)),
+        _ => None,
+    }
+}
+
+pub(super) fn binary_op_rhs_expectation(op: BinaryOp, lhs_ty: Ty) -> Ty {
+    match op {
+        BinaryOp::LogicOp(_) => Ty::simple(TypeCtor::Bool),
+        _ => lhs_ty,
+    }
 }
 
 pub(super) fn binary_op_return_ty(op: BinaryOp, rhs_ty: Ty) -> Ty {
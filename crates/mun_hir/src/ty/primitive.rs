@@ -0,0 +1,235 @@
+use std::fmt;
+
+/// Whether an integer type can represent negative values.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Signedness {
+    Signed,
+    Unsigned,
+}
+
+/// The number of bits of an integer type.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum IntBitness {
+    X8,
+    X16,
+    X32,
+    X64,
+}
+
+impl IntBitness {
+    /// Returns the number of bits.
+    pub fn bits(self) -> u32 {
+        match self {
+            IntBitness::X8 => 8,
+            IntBitness::X16 => 16,
+            IntBitness::X32 => 32,
+            IntBitness::X64 => 64,
+        }
+    }
+}
+
+/// A primitive integer type. Arithmetic on integers wraps around on overflow.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct IntTy {
+    pub signedness: Signedness,
+    pub bitness: IntBitness,
+}
+
+impl fmt::Debug for IntTy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::Display for IntTy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.ty_to_string())
+    }
+}
+
+impl IntTy {
+    /// The default integer type `int`, which is an alias of `i64`.
+    pub const fn int() -> IntTy {
+        IntTy::i64()
+    }
+
+    pub const fn i8() -> IntTy {
+        IntTy {
+            signedness: Signedness::Signed,
+            bitness: IntBitness::X8,
+        }
+    }
+
+    pub const fn i16() -> IntTy {
+        IntTy {
+            signedness: Signedness::Signed,
+            bitness: IntBitness::X16,
+        }
+    }
+
+    pub const fn i32() -> IntTy {
+        IntTy {
+            signedness: Signedness::Signed,
+            bitness: IntBitness::X32,
+        }
+    }
+
+    pub const fn i64() -> IntTy {
+        IntTy {
+            signedness: Signedness::Signed,
+            bitness: IntBitness::X64,
+        }
+    }
+
+    pub const fn u8() -> IntTy {
+        IntTy {
+            signedness: Signedness::Unsigned,
+            bitness: IntBitness::X8,
+        }
+    }
+
+    pub const fn u16() -> IntTy {
+        IntTy {
+            signedness: Signedness::Unsigned,
+            bitness: IntBitness::X16,
+        }
+    }
+
+    pub const fn u32() -> IntTy {
+        IntTy {
+            signedness: Signedness::Unsigned,
+            bitness: IntBitness::X32,
+        }
+    }
+
+    pub const fn u64() -> IntTy {
+        IntTy {
+            signedness: Signedness::Unsigned,
+            bitness: IntBitness::X64,
+        }
+    }
+
+    /// Returns the integer type corresponding to the suffix of a literal, e.g. `u8` for `255u8`.
+    pub fn from_suffix(suffix: &str) -> Option<IntTy> {
+        match suffix {
+            "i8" => Some(IntTy::i8()),
+            "i16" => Some(IntTy::i16()),
+            "i32" => Some(IntTy::i32()),
+            "i64" => Some(IntTy::i64()),
+            "u8" => Some(IntTy::u8()),
+            "u16" => Some(IntTy::u16()),
+            "u32" => Some(IntTy::u32()),
+            "u64" => Some(IntTy::u64()),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the type can represent negative values.
+    pub fn is_signed(self) -> bool {
+        self.signedness == Signedness::Signed
+    }
+
+    /// Returns the largest value that can be represented by the type.
+    pub fn max_value(self) -> u128 {
+        let bits = match self.signedness {
+            Signedness::Signed => self.bitness.bits() - 1,
+            Signedness::Unsigned => self.bitness.bits(),
+        };
+        (1u128 << bits) - 1
+    }
+
+    /// Returns the smallest value that can be represented by the type.
+    pub fn min_value(self) -> i128 {
+        match self.signedness {
+            Signedness::Signed => -(self.max_value() as i128) - 1,
+            Signedness::Unsigned => 0,
+        }
+    }
+
+    /// Returns the name of the type as it is written in Mun code. `i64` is written as `int`.
+    pub fn ty_to_string(self) -> &'static str {
+        match (self.signedness, self.bitness) {
+            (Signedness::Signed, IntBitness::X8) => "i8",
+            (Signedness::Signed, IntBitness::X16) => "i16",
+            (Signedness::Signed, IntBitness::X32) => "i32",
+            (Signedness::Signed, IntBitness::X64) => "int",
+            (Signedness::Unsigned, IntBitness::X8) => "u8",
+            (Signedness::Unsigned, IntBitness::X16) => "u16",
+            (Signedness::Unsigned, IntBitness::X32) => "u32",
+            (Signedness::Unsigned, IntBitness::X64) => "u64",
+        }
+    }
+}
+
+/// The number of bits of a floating point type.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum FloatBitness {
+    X32,
+    X64,
+}
+
+impl FloatBitness {
+    /// Returns the number of bits.
+    pub fn bits(self) -> u32 {
+        match self {
+            FloatBitness::X32 => 32,
+            FloatBitness::X64 => 64,
+        }
+    }
+}
+
+/// A primitive floating point type. Floating point types of different widths are never converted
+/// implicitly.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FloatTy {
+    pub bitness: FloatBitness,
+}
+
+impl fmt::Debug for FloatTy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::Display for FloatTy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.ty_to_string())
+    }
+}
+
+impl FloatTy {
+    /// The default floating point type `float`, which is an alias of `f64`.
+    pub const fn float() -> FloatTy {
+        FloatTy::f64()
+    }
+
+    pub const fn f32() -> FloatTy {
+        FloatTy {
+            bitness: FloatBitness::X32,
+        }
+    }
+
+    pub const fn f64() -> FloatTy {
+        FloatTy {
+            bitness: FloatBitness::X64,
+        }
+    }
+
+    /// Returns the floating point type corresponding to the suffix of a literal, e.g. `f32` for
+    /// `1.5f32`.
+    pub fn from_suffix(suffix: &str) -> Option<FloatTy> {
+        match suffix {
+            "f32" => Some(FloatTy::f32()),
+            "f64" => Some(FloatTy::f64()),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the type as it is written in Mun code. `f64` is written as `float`.
+    pub fn ty_to_string(self) -> &'static str {
+        match self.bitness {
+            FloatBitness::X32 => "f32",
+            FloatBitness::X64 => "float",
+        }
+    }
+}
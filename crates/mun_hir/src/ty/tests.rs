@@ -1,9 +1,10 @@
+use crate::code_model::DefWithBody;
 use crate::db::SourceDatabase;
 use crate::diagnostics::DiagnosticSink;
 use crate::expr::BodySourceMap;
 use crate::ids::LocationCtx;
 use crate::mock::MockDatabase;
-use crate::{Function, HirDisplay, InferenceResult};
+use crate::{Const, Function, HirDisplay, InferenceResult, Static};
 use mun_syntax::{ast, AstNode};
 use std::fmt::Write;
 use std::sync::Arc;
@@ -71,6 +72,170 @@ fn infer_branching() {
     )
 }
 
+#[test]
+fn infer_if_value() {
+    infer_snapshot(
+        r#"
+    fn foo(a:bool):int {
+        let b = if a { return 1; } else { 2 }
+        let c:int = if a { 3 } // Missing else branch
+        if a { b } else { c }
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_logic_ops() {
+    infer_snapshot(
+        r#"
+    fn foo(a:bool, b:bool, c:int):bool {
+        let d = a && b || a
+        let e = c < 3 && b
+        a || c
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_comparisons() {
+    infer_snapshot(
+        r#"
+    struct Foo;
+
+    fn foo(a:i32, b:u8, c:bool, d:Foo) {
+        let e = a < 1
+        let f = b >= 255
+        let g = c == true
+        let h = c < false
+        let i = a == b
+        let j = d == d
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_invalid_operators() {
+    infer_snapshot(
+        r#"
+    struct Foo;
+
+    fn foo(a: int, b: u8, c: bool, d: float, e: f32) {
+        let f = Foo;
+        let g = c + 1;
+        let h = f < f;
+        let i = d << 1;
+        let j = d % 2.0;
+        let k = a + d;
+        let l = a == b;
+        let m = d == e;
+        let n = c == 1.5;
+        let o = f == f;
+        let p = !a;
+        let q = -c;
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_unary_ops() {
+    infer_snapshot(
+        r#"
+    fn foo(a:int, b:u8, c:bool, d:f32) {
+        let e = -a
+        let f = -b
+        let g = !c
+        let h = -d
+        let i:i8 = -128
+        let j = !a
+        let k = --a
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_never() {
+    infer_snapshot(
+        r#"
+    fn forever():never {
+        loop {}
+    }
+
+    fn foo(a:bool):int {
+        let b = if a { forever() } else { 1 };
+        let c = return b;
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_extern() {
+    infer_snapshot(
+        r#"
+    extern fn log_value(v:float);
+    extern fn add(a:int, b:int):int;
+
+    fn main() {
+        log_value(1.0);
+        let a = add(3, 4);
+        log_value(a);
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_const() {
+    infer_snapshot(
+        r#"
+    const SECONDS_PER_MINUTE: int = 60;
+    const SECONDS_PER_HOUR: int = SECONDS_PER_MINUTE * 60;
+    const GRAVITY: float = -9.81;
+    const ENABLED: bool = true && SECONDS_PER_HOUR > 0;
+
+    fn main() {
+        let a = SECONDS_PER_HOUR * 2;
+        let b = GRAVITY;
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_static() {
+    infer_snapshot(
+        r#"
+    static mut SCORE: int = 10;
+    static LIMIT: float = 1.5;
+
+    fn main() {
+        SCORE += 1;
+        LIMIT = 2.0; // error: invalid left hand side of expression
+        let a = SCORE;
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_string_literals() {
+    infer_snapshot(
+        r#"
+    fn message():str { "game_over" }
+
+    fn main() {
+        let a = message();
+        let b: str = 'quote\'';
+    }
+    "#,
+    )
+}
+
 #[test]
 fn void_return() {
     infer_snapshot(
@@ -89,7 +254,7 @@ fn void_return() {
 fn place_expressions() {
     infer_snapshot(
         r#"
-    fn foo(a:int) {
+    fn foo(mut a:int) {
         a += 3;
         3 = 5; // error: invalid left hand side of expression
     }
@@ -101,7 +266,7 @@ fn place_expressions() {
 fn update_operators() {
     infer_snapshot(
         r#"
-    fn foo(a:int, b:float) {
+    fn foo(mut a:int, mut b:float) {
         a += 3;
         a -= 3;
         a *= 3;
@@ -117,6 +282,409 @@ fn update_operators() {
     )
 }
 
+#[test]
+fn infer_mutability() {
+    infer_snapshot(
+        r#"
+    struct Foo { a: int }
+
+    impl Foo {
+        fn set(self) {
+            self.a = 1; // error: immutable `self`
+        }
+    }
+
+    fn foo(a: int, mut b: int, f: Foo) {
+        let c = 1;
+        let mut d = 2;
+        a = 3; // error: immutable parameter
+        b = 4;
+        c += 5; // error: immutable local
+        d += 6;
+        f.a = 7; // error: field of an immutable parameter
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_shadowing() {
+    infer_snapshot(
+        r#"
+    fn foo(): bool {
+        let x = 1;
+        let x = x * 2;
+        let x = x > 3;
+        x
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_type_aliases() {
+    infer_snapshot(
+        r#"
+    type Seconds = float;
+    type Pair = (int, Seconds);
+    type Cycle = Cycle;
+
+    fn scale(t: Seconds, factor: float): Seconds {
+        t * factor
+    }
+
+    fn pair(a: int, b: Seconds): Pair {
+        let p: Pair = (a, b);
+        p
+    }
+
+    fn cyclic(c: Cycle) {
+        c;
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_builtin_functions() {
+    infer_snapshot(
+        r#"
+    fn foo(a: f32, b: int, c: u8) {
+        let d = sqrt(2.0);
+        let e = min(c, 3);
+        let f = max(1.5, a);
+        abs(b);
+        sqrt(b); // error: not defined for integers
+        min(b); // error: missing argument
+    }
+
+    fn floor(x: int): bool {
+        x > 0
+    }
+
+    fn bar(): bool {
+        floor(1)
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_integer_arithmetic_builtins() {
+    infer_snapshot(
+        r#"
+    fn foo(a: u8, b: i32, c: float) {
+        let d = wrapping_add(a, 1);
+        let e = saturating_sub(b, 5);
+        wrapping_mul(c, 2.0); // error: not defined for floats
+        saturating_add(a); // error: missing argument
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_assertion_builtins() {
+    infer_snapshot(
+        r#"
+    fn foo(hp: int, speed: float) {
+        assert(hp >= 0);
+        assert_eq(speed, 1.0);
+        assert(hp); // error: mismatched type
+        assert_eq("a", "b"); // error: not defined for strings
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_sized_ints() {
+    infer_snapshot(
+        r#"
+    fn add(a:u8, b:u8):u8 {
+        a + b + 1
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_literals() {
+    infer_snapshot(
+        r#"
+    fn takes_u8(a:u8) {}
+
+    fn foo() {
+        let a = 255u8;
+        let b = 1.5f32;
+        let c = 1f64;
+        takes_u8(3);
+        let d = 3;
+        takes_u8(d);
+        let e = 3;
+        let f: i16 = e + 1;
+        let g = 1.5;
+        let h = 4;
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_literal_out_of_range() {
+    infer_snapshot(
+        r#"
+    fn foo() {
+        let a = 300u8;
+        let b: i8 = 128;
+        let c: u64 = 18446744073709551615;
+        let d = 9223372036854775808;
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_literal_width_boundaries() {
+    infer_snapshot(
+        r#"
+    fn foo() {
+        let a: u8 = 255;
+        let b: u8 = 256;
+        let c: i8 = -128;
+        let d: i8 = -129;
+        let e: u16 = 65536;
+        let f: i16 = 32768;
+        let g: u32 = 4294967296;
+        let h: i32 = -2147483649;
+        let i = 18446744073709551615u64;
+        let j = -9223372036854775809;
+        let k: u8 = -1;
+        let l = -0u8;
+        let m = 256;
+        let n: u8 = m;
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_radix_literal_widths() {
+    infer_snapshot(
+        r#"
+    fn foo() {
+        let a = 0xFFu8;
+        let b = 0x1FFu8;
+        let c: i8 = -0x80;
+        let d: i8 = 0b1000_0000;
+        let e = 0o777;
+        let f: u8 = e;
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_float_literal_out_of_range() {
+    infer_snapshot(
+        r#"
+    fn foo() {
+        let a: f32 = 3.4e38;
+        let b: f32 = 1e39;
+        let c: f32 = -1e39;
+        let d: f32 = 1e-50;
+        let e = 1e39f32;
+        let f: float = 1e39;
+        let g: f32 = 0.0;
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_let_annotations() {
+    infer_snapshot(
+        r#"
+    const SPEED: f32 = 2;
+
+    fn foo(): float {
+        let health: float = 100;
+        let count: u8 = 3;
+        let active: bool = 1; // error: mismatched type
+        let later: f32;
+        later = 2.5;
+        10
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_literals_from_later_uses() {
+    infer_snapshot(
+        r#"
+    fn takes_u8(a: u8) {}
+    fn takes_i32(a: i32) {}
+
+    fn foo() {
+        let a = 0;
+        takes_u8(a);
+        let b = 1;
+        let c = b + 2;
+        takes_i32(c);
+        let d = 3;
+        takes_u8(d);
+        takes_i32(d); // error: conflicting types
+        let e = 4;
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_literal_fallback() {
+    infer_snapshot(
+        r#"
+    fn takes_f32(a:f32) {}
+    fn takes_u8(a:u8) {}
+
+    fn foo() {
+        let a: u8 = 7;
+        takes_f32(1.5);
+        let b = 300;
+        let c = 2.5;
+        let d = 7;
+        takes_f32(d);
+        let e = 1.5;
+        takes_u8(e + 1.0);
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_field_access() {
+    infer_snapshot(
+        r#"
+    struct Foo {
+        a: int,
+        b: float,
+    }
+    struct Bar {
+        foo: Foo,
+    }
+
+    fn main(mut bar:Bar):float {
+        bar.foo.a = 3;
+        bar.c; // error: no field
+        bar.foo.b
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_record_lit() {
+    infer_snapshot(
+        r#"
+    struct Foo {
+        a: int,
+        b: float,
+    }
+    struct Unit;
+
+    fn main() {
+        let a = Foo { a: 1, b: 2.0 };
+        let b = Foo { b: 1.0, a: 3 };
+        let c = Unit;
+        let d = Unit {};
+        Foo { a: 1 }; // missing field
+        Foo { a: 1, b: 2.0, a: 3 }; // duplicate field
+        Foo { a: 1, b: 2.0, c: true }; // no such field
+        Baz { a: 1 }; // unresolved struct
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_tuples() {
+    infer_snapshot(
+        r#"
+    fn foo(a:(int, float)):(float, int) {
+        let b = (1, 2.0);
+        let c:(u8,) = (3,);
+        let d = ();
+        b.0;
+        a.2; // error: no field
+        (a.1, b.0)
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_arrays() {
+    infer_snapshot(
+        r#"
+    fn foo(mut a:[float; 3]):float {
+        let b = [1, 2, 3];
+        let c:[u8; 2] = [4, 5, 6]; // mismatched length
+        let i = 1;
+        a[i] = 2.0;
+        a[3]; // index out of bounds
+        i[0]; // cannot index
+        let e = []; // cannot infer element type
+        b[0] + b[i];
+        a[0]
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_fn_pointers() {
+    infer_snapshot(
+        r#"
+    fn double(a:int):int { a * 2 }
+    fn is_zero(a:int):bool { a == 0 }
+    fn apply(f:fn(int):int, v:int):int { f(v) }
+    fn foo() {
+        let f = double;
+        apply(f, 1);
+        apply(double, 2);
+        apply(is_zero, 3); // mismatched signature
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_closures() {
+    infer_snapshot(
+        r#"
+    fn apply(f:fn(int):int, v:int):int { f(v) }
+    fn foo(y:int) {
+        apply(|x| x * 2, 1);
+        let add = |a:int, b:int| a + b;
+        add(y, 2);
+        apply(|x| x + y, 3); // captures `y`
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_mixed_floats() {
+    infer_snapshot(
+        r#"
+    fn mix(a:f32, b:float) {
+        a + b; // mismatched type
+    }
+    "#,
+    )
+}
+
 #[test]
 fn infer_loop() {
     infer_snapshot(
@@ -144,12 +712,44 @@ fn infer_break() {
     )
 }
 
+#[test]
+fn infer_loop_break_unification() {
+    infer_snapshot(
+        r#"
+    fn foo(a: u8, c: bool): u8 {
+        let b = loop {
+            if c { break a; }
+            break 1;
+        };
+        b
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_loop_without_break() {
+    infer_snapshot(
+        r#"
+    fn foo(): int {
+        loop {}
+    }
+
+    fn bar(c: bool): int {
+        loop {
+            if c { continue; }
+        }
+    }
+    "#,
+    )
+}
+
 #[test]
 fn infer_while() {
     infer_snapshot(
         r#"
     fn foo() {
-        let n = 0;
+        let mut n = 0;
         while n < 3 { n += 1; };
         while n < 3 { n += 1; break; };
         while n < 3 { break 3; };   // error: break with value can only appear in a loop
@@ -159,6 +759,103 @@ fn infer_while() {
     )
 }
 
+#[test]
+fn infer_continue() {
+    infer_snapshot(
+        r#"
+    fn foo() {
+        continue; // error: not in a loop
+        let mut n = 0;
+        while n < 3 { n += 1; continue; };
+        let a:int = loop { if n > 5 { break n; } n += 1; continue; }
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_for() {
+    infer_snapshot(
+        r#"
+    fn foo(n:u8) {
+        for i in 0..n { let a = i; }
+        for i in 0..=10 { i; }
+        for _ in 0..1.5 {} // error: mismatched type
+        i; // error: not in scope
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_enums() {
+    infer_snapshot(
+        r#"
+    enum State { Idle, Walking = 2, Attacking }
+
+    fn foo(a: State): u8 {
+        let b = State::Walking;
+        let c = a == b;
+        let d: u8 = a; // error: mismatched type
+        let e = 1.5 as u8; // error: invalid cast
+        let f = State::Running; // error: undefined value
+        a as u8
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_methods() {
+    infer_snapshot(
+        r#"
+    struct Vector { x: float, y: float }
+
+    impl Vector {
+        fn new(x: float, y: float): Vector { Vector { x: x, y: y } }
+        fn dot(self, other: Vector): float { self.x * other.x + self.y * other.y }
+    }
+
+    fn foo(a: Vector): float {
+        let b = Vector::new(1.0, 2.0);
+        a.dot(b);
+        a.dot(true); // error: mismatched type
+        a.len(); // error: no method
+        Vector::dot(a, b)
+    }
+    "#,
+    )
+}
+
+#[test]
+fn infer_match() {
+    infer_snapshot(
+        r#"
+    enum State { Idle, Walking, Attacking }
+
+    fn foo(a: i32, b: bool, c: State): i32 {
+        let d = match a {
+            0 => 1,
+            -1 => { 2 }
+            _ => 3,
+        };
+        let e = match b { true => 1u8, false => return 0 };
+        let f = match a {
+            x => 1, // error: unsupported pattern
+            true => 2, // error: mismatched type
+            State::Idle => 3, // error: mismatched type
+            _ => 4,
+        };
+        match c {
+            State::Idle => d,
+            State::Running => 2, // error: undefined value
+            _ => 3,
+        }
+    }
+    "#,
+    )
+}
+
 fn infer_snapshot(text: &str) {
     let text = text.trim().replace("\n    ", "\n");
     insta::assert_snapshot!(insta::_macro_support::AutoName, infer(&text), &text);
@@ -218,19 +915,32 @@ fn infer(content: &str) -> String {
 
     let ctx = LocationCtx::new(&db, file_id);
     for node in source_file.syntax().descendants() {
-        if let Some(def) = ast::FunctionDef::cast(node.clone()) {
-            let fun = Function {
+        let owner: DefWithBody = if let Some(def) = ast::FunctionDef::cast(node.clone()) {
+            Function {
                 id: ctx.to_def(&def),
-            };
-            let source_map = fun.body_source_map(&db);
-            let infer_result = fun.infer(&db);
-
-            for diag in infer_result.diagnostics.iter() {
-                diag.add_to(&db, fun, &mut diag_sink);
             }
+            .into()
+        } else if let Some(def) = ast::ConstDef::cast(node.clone()) {
+            Const {
+                id: ctx.to_def(&def),
+            }
+            .into()
+        } else if let Some(def) = ast::StaticDef::cast(node.clone()) {
+            Static {
+                id: ctx.to_def(&def),
+            }
+            .into()
+        } else {
+            continue;
+        };
+        let source_map = owner.body_source_map(&db);
+        let infer_result = owner.infer(&db);
 
-            infer_def(infer_result, source_map);
+        for diag in infer_result.diagnostics.iter() {
+            diag.add_to(&db, owner, &mut diag_sink);
         }
+
+        infer_def(infer_result, source_map);
     }
 
     drop(diag_sink);
@@ -2,10 +2,12 @@ use crate::arena::map::ArenaMap;
 use crate::arena::{Arena, RawId};
 ///! HIR for references to types. These paths are not yet resolved. They can be directly created
 /// from an `ast::TypeRef`, without further queries.
+use crate::const_eval::{eval_int_syntax, IntConstError};
 use crate::Path;
 use mun_syntax::ast;
-use mun_syntax::AstPtr;
+use mun_syntax::{AstNode, AstPtr, SyntaxNodePtr};
 use rustc_hash::FxHashMap;
+use std::convert::TryFrom;
 use std::ops::Index;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -16,6 +18,12 @@ impl_arena_id!(TypeRefId);
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub enum TypeRef {
     Path(Path),
+    Tuple(Vec<TypeRefId>),
+    /// An array type with the type of its elements and its length. The length is `None` if it
+    /// could not be evaluated, which is reported when the type is lowered.
+    Array(TypeRefId, Option<u64>),
+    /// A function pointer type with the types of its parameters and its return type
+    Fn(Vec<TypeRefId>, TypeRefId),
     Never,
     Empty,
     Error,
@@ -82,10 +90,52 @@ impl TypeRefBuilder {
                 .map(TypeRef::Path)
                 .unwrap_or(TypeRef::Error),
             NeverType(_) => TypeRef::Never,
+            ParenType(inner) => return self.alloc_from_node_opt(inner.type_ref().as_ref()),
+            TupleType(tuple) => {
+                let fields: Vec<TypeRefId> = tuple
+                    .fields()
+                    .map(|field| self.alloc_from_node(&field))
+                    .collect();
+                if fields.is_empty() {
+                    TypeRef::Empty
+                } else {
+                    TypeRef::Tuple(fields)
+                }
+            }
+            ArrayType(array) => {
+                let elem = self.alloc_from_node_opt(array.type_ref().as_ref());
+                TypeRef::Array(elem, array_len(&array).ok())
+            }
+            FnPointerType(fn_ptr) => {
+                let params = fn_ptr
+                    .params()
+                    .map(|param| self.alloc_from_node(&param))
+                    .collect();
+                // A function pointer without a return type returns nothing
+                let ret = match fn_ptr.ret_type() {
+                    Some(ret_type) => self.alloc_from_node_opt(ret_type.type_ref().as_ref()),
+                    None => self.unit(),
+                };
+                TypeRef::Fn(params, ret)
+            }
         };
         self.alloc_type_ref(type_ref, ptr)
     }
 
+    /// Allocates the type of the `self` parameter of a method, which is the type `node` of its
+    /// impl block. The type has no source, so errors in it are only reported on the impl block.
+    pub fn alloc_self_type(&mut self, node: Option<&ast::TypeRef>) -> TypeRefId {
+        let type_ref = match node.map(|node| node.kind()) {
+            Some(ast::TypeRefKind::PathType(path)) => path
+                .path()
+                .and_then(Path::from_ast)
+                .map(TypeRef::Path)
+                .unwrap_or(TypeRef::Error),
+            _ => TypeRef::Error,
+        };
+        self.map.type_refs.alloc(type_ref)
+    }
+
     pub fn unit(&mut self) -> TypeRefId {
         self.map.type_refs.alloc(TypeRef::Empty)
     }
@@ -98,3 +148,16 @@ impl TypeRefBuilder {
         (self.map, self.source_map)
     }
 }
+
+/// Evaluates the length of an array type, which must be a non-negative integer that fits in 64
+/// bits, e.g. `4 * 4`.
+pub(crate) fn array_len(array: &ast::ArrayType) -> Result<u64, IntConstError> {
+    let expr = array.expr().ok_or(IntConstError::Missing)?;
+    let value = eval_int_syntax(&expr)?;
+    let ptr = SyntaxNodePtr::new(expr.syntax());
+    if value < 0 {
+        Err(IntConstError::InvalidValue(ptr))
+    } else {
+        u64::try_from(value).map_err(|_| IntConstError::Overflow(ptr))
+    }
+}
@@ -0,0 +1,75 @@
+//! Conversions of the positions and diagnostics of the compiler to those of LSP.
+
+use mun_compiler::Driver;
+use mun_errors::{Diagnostic, Level, Location};
+use mun_hir::{line_index::LineIndex, FileId};
+use mun_syntax::{TextRange, TextUnit};
+use serde_json::{json, Value};
+use url::Url;
+
+/// Converts `offset` in `text` to an LSP position. Lines and characters start at 0, characters
+/// count UTF-16 code units.
+pub(crate) fn position(line_index: &LineIndex, text: &str, offset: TextUnit) -> Value {
+    let line_col = line_index.line_col(offset);
+    let line = line_index.line_str(line_col.line, text).unwrap_or("");
+    let character = line
+        .get(..line_col.col as usize)
+        .unwrap_or(line)
+        .encode_utf16()
+        .count();
+    json!({ "line": line_col.line, "character": character })
+}
+
+/// Converts `range` in `text` to an LSP range.
+pub(crate) fn range(line_index: &LineIndex, text: &str, range: TextRange) -> Value {
+    json!({
+        "start": position(line_index, text, range.start()),
+        "end": position(line_index, text, range.end()),
+    })
+}
+
+/// Converts the diagnostic `diagnostic` of the file `file_id`, which the client knows as `uri`, to
+/// an LSP diagnostic. Its labels become related information in the same file.
+pub(crate) fn diagnostic(
+    driver: &Driver,
+    file_id: FileId,
+    uri: &Url,
+    diagnostic: &Diagnostic,
+) -> Value {
+    let text = driver.file_text(file_id);
+    let line_index = driver.line_index(file_id);
+    let location_range = |loc: &Location| {
+        range(
+            &line_index,
+            &text,
+            TextRange::from_to(loc.offset(), loc.end_offset()),
+        )
+    };
+
+    let mut result = json!({
+        "range": location_range(&diagnostic.loc),
+        "severity": match diagnostic.level {
+            Level::Error => 1,
+            Level::Warning => 2,
+            Level::Note => 3,
+        },
+        "source": "mun",
+        "message": diagnostic.message,
+    });
+    if let Some(code) = diagnostic.code {
+        result["code"] = json!(code);
+    }
+    if !diagnostic.labels.is_empty() {
+        result["relatedInformation"] = diagnostic
+            .labels
+            .iter()
+            .map(|label| {
+                json!({
+                    "location": { "uri": uri.as_str(), "range": location_range(&label.loc) },
+                    "message": label.message,
+                })
+            })
+            .collect();
+    }
+    result
+}
@@ -0,0 +1,365 @@
+//! The language server of Mun, which speaks the Language Server Protocol (LSP) with an editor. The
+//! files of the package of the workspace are kept in the database of a compiler driver, so after a
+//! change of a document only what depends on the change is checked again.
+//!
+//! The server supports:
+//! - synchronizing the full text of open documents,
+//! - publishing the diagnostics of all files of the package after every change, and
+//! - the symbols of a document, which editors show in their outline.
+
+use failure::Error;
+use mun_compiler::{Config, Driver, FileId, Manifest, RelativePathBuf, MANIFEST_FILE_NAME};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use url::Url;
+
+mod conversion;
+mod symbols;
+#[cfg(test)]
+mod tests;
+mod transport;
+
+pub use crate::transport::{read_message, write_message};
+
+/// The error codes of JSON-RPC and LSP that are used in responses.
+const INVALID_PARAMS: i64 = -32602;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INTERNAL_ERROR: i64 = -32603;
+const SERVER_NOT_INITIALIZED: i64 = -32002;
+
+/// The extension of Mun source files.
+const SOURCE_FILE_EXTENSION: &str = "mun";
+
+/// Runs a language server that handles the messages of `reader` and writes its own messages to
+/// `writer`, until the client sends the `exit` notification or closes `reader`. Returns whether the
+/// client asked the server to shut down before it exited.
+pub fn run(mut reader: impl BufRead, mut writer: impl Write) -> Result<bool, Error> {
+    let mut server = LanguageServer::new();
+    while let Some(message) = read_message(&mut reader)? {
+        for message in server.handle_message(&message) {
+            write_message(&mut writer, &message)?;
+        }
+        if server.exited {
+            break;
+        }
+    }
+    Ok(server.shut_down)
+}
+
+/// The error of a request, which is sent to the client instead of its result.
+#[derive(Debug)]
+struct ResponseError {
+    code: i64,
+    message: String,
+}
+
+impl ResponseError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        ResponseError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// The package of the workspace of the client.
+struct Package {
+    driver: Driver,
+    /// The directory of the source files of the package
+    source_dir: PathBuf,
+}
+
+/// The state of a language server, which handles the messages of a single client.
+pub struct LanguageServer {
+    /// The package of the workspace, once the server is initialized
+    package: Option<Package>,
+    /// The diagnostics that were last published for every document, if there were any
+    published_diagnostics: HashMap<String, Value>,
+    shut_down: bool,
+    exited: bool,
+}
+
+impl Default for LanguageServer {
+    fn default() -> Self {
+        LanguageServer::new()
+    }
+}
+
+impl LanguageServer {
+    /// Constructs a server that still has to be initialized by the client.
+    pub fn new() -> Self {
+        LanguageServer {
+            package: None,
+            published_diagnostics: HashMap::new(),
+            shut_down: false,
+            exited: false,
+        }
+    }
+
+    /// Handles the request, notification or response `message` of the client, and returns the
+    /// messages that the server sends in reply.
+    pub fn handle_message(&mut self, message: &Value) -> Vec<Value> {
+        let params = &message["params"];
+        match (message.get("id"), message["method"].as_str()) {
+            (Some(id), Some(method)) => {
+                let response = match self.handle_request(method, params) {
+                    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                    Err(e) => json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": { "code": e.code, "message": e.message },
+                    }),
+                };
+                vec![response]
+            }
+            (None, Some(method)) => self.handle_notification(method, params),
+            // The server sends no requests, so it expects no responses
+            (_, None) => Vec::new(),
+        }
+    }
+
+    /// Returns the result of the request `method`.
+    fn handle_request(&mut self, method: &str, params: &Value) -> Result<Value, ResponseError> {
+        if method == "initialize" {
+            return self.initialize(params);
+        }
+        let package = self.package.as_ref().ok_or_else(|| {
+            ResponseError::new(SERVER_NOT_INITIALIZED, "the server is not initialized")
+        })?;
+
+        match method {
+            "shutdown" => {
+                self.shut_down = true;
+                Ok(Value::Null)
+            }
+            "textDocument/documentSymbol" => {
+                document_uri(params).map(|uri| match package.file_id(uri) {
+                    Some(file_id) => json!(symbols::document_symbols(
+                        &package.driver.parse(file_id).tree(),
+                        &package.driver.line_index(file_id),
+                        &package.driver.file_text(file_id),
+                    )),
+                    None => Value::Null,
+                })
+            }
+            _ => Err(ResponseError::new(
+                METHOD_NOT_FOUND,
+                format!("unknown request '{}'", method),
+            )),
+        }
+    }
+
+    /// Handles the notification `method`, and returns the notifications it causes.
+    fn handle_notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        if method == "exit" {
+            self.exited = true;
+            return Vec::new();
+        }
+        // Notifications other than `exit` are dropped until the server is initialized
+        let package = match &mut self.package {
+            Some(package) => package,
+            None => return Vec::new(),
+        };
+
+        let changed = match method {
+            "initialized" => true,
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                match (document["uri"].as_str(), document["text"].as_str()) {
+                    (Some(uri), Some(text)) => package.set_document_text(uri, text.to_string()),
+                    _ => false,
+                }
+            }
+            // The server synchronizes full documents, so the last change contains the whole text
+            "textDocument/didChange" => {
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+                match (params["textDocument"]["uri"].as_str(), text) {
+                    (Some(uri), Some(text)) => package.set_document_text(uri, text.to_string()),
+                    _ => false,
+                }
+            }
+            "textDocument/didClose" => match params["textDocument"]["uri"].as_str() {
+                Some(uri) => package.revert_document(uri),
+                None => false,
+            },
+            // Other notifications, e.g. `$/cancelRequest`, have no effect
+            _ => false,
+        };
+        if changed {
+            self.publish_diagnostics()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Initializes the server with the package at the root of the workspace of the client, of
+    /// which the source files are loaded from disk. Returns the capabilities of the server.
+    fn initialize(&mut self, params: &Value) -> Result<Value, ResponseError> {
+        if self.package.is_some() {
+            return Err(ResponseError::new(
+                INVALID_PARAMS,
+                "the server is already initialized",
+            ));
+        }
+
+        let root = params["rootUri"]
+            .as_str()
+            .and_then(|uri| Url::parse(uri).ok())
+            .and_then(|uri| uri.to_file_path().ok())
+            .ok_or_else(|| {
+                ResponseError::new(
+                    INVALID_PARAMS,
+                    "the root of the workspace is not a file path",
+                )
+            })?;
+        let source_dir = package_source_dir(&root)
+            .map_err(|e| ResponseError::new(INTERNAL_ERROR, e.to_string()))?;
+
+        let mut driver = Driver::with_config(Config::default());
+        if source_dir.is_dir() {
+            driver
+                .add_source_files(&source_dir)
+                .map_err(|e| ResponseError::new(INTERNAL_ERROR, e.to_string()))?;
+        }
+        self.package = Some(Package { driver, source_dir });
+
+        Ok(json!({
+            "capabilities": {
+                // Documents are synchronized by sending their full text
+                "textDocumentSync": 1,
+                "documentSymbolProvider": true,
+            },
+            "serverInfo": { "name": "mun-lsp", "version": env!("CARGO_PKG_VERSION") },
+        }))
+    }
+
+    /// Returns the `textDocument/publishDiagnostics` notifications of the files of which the
+    /// diagnostics changed since they were last published. A file that was removed from the
+    /// package has no diagnostics anymore.
+    fn publish_diagnostics(&mut self) -> Vec<Value> {
+        let package = match &self.package {
+            Some(package) => package,
+            None => return Vec::new(),
+        };
+
+        let mut diagnostics = BTreeMap::new();
+        for file_id in package.driver.files() {
+            let uri = package.uri(file_id);
+            let file_diagnostics: Vec<Value> = package
+                .driver
+                .file_diagnostics(file_id)
+                .iter()
+                .map(|diagnostic| {
+                    conversion::diagnostic(&package.driver, file_id, &uri, diagnostic)
+                })
+                .collect();
+            diagnostics.insert(uri.to_string(), Value::from(file_diagnostics));
+        }
+        for uri in self.published_diagnostics.keys() {
+            diagnostics
+                .entry(uri.clone())
+                .or_insert_with(|| Value::Array(Vec::new()));
+        }
+
+        let mut notifications = Vec::new();
+        for (uri, file_diagnostics) in diagnostics {
+            let previous = self
+                .published_diagnostics
+                .remove(&uri)
+                .unwrap_or_else(|| Value::Array(Vec::new()));
+            if previous != file_diagnostics {
+                notifications.push(json!({
+                    "jsonrpc": "2.0",
+                    "method": "textDocument/publishDiagnostics",
+                    "params": { "uri": uri, "diagnostics": file_diagnostics },
+                }));
+            }
+            if file_diagnostics.as_array().map_or(false, |d| !d.is_empty()) {
+                self.published_diagnostics.insert(uri, file_diagnostics);
+            }
+        }
+        notifications
+    }
+}
+
+impl Package {
+    /// Returns the path of the document `uri` relative to the source directory, if it is a Mun
+    /// file in the source directory.
+    fn relative_path(&self, uri: &str) -> Option<RelativePathBuf> {
+        let path = Url::parse(uri).ok()?.to_file_path().ok()?;
+        if path.extension().and_then(|ext| ext.to_str()) != Some(SOURCE_FILE_EXTENSION) {
+            return None;
+        }
+        RelativePathBuf::from_path(path.strip_prefix(&self.source_dir).ok()?).ok()
+    }
+
+    /// Returns the file of the document `uri`, if the package contains it.
+    fn file_id(&self, uri: &str) -> Option<FileId> {
+        self.driver.file_id(&self.relative_path(uri)?)
+    }
+
+    /// Returns the URI of the file `file_id`.
+    fn uri(&self, file_id: FileId) -> Url {
+        let path = self
+            .driver
+            .file_relative_path(file_id)
+            .to_path(&self.source_dir);
+        Url::from_file_path(path).expect("the source directory is an absolute path")
+    }
+
+    /// Sets the contents of the document `uri`, which is added to the package if it is a new file
+    /// of the package. Returns whether the package changed.
+    fn set_document_text(&mut self, uri: &str, text: String) -> bool {
+        let rel_path = match self.relative_path(uri) {
+            Some(rel_path) => rel_path,
+            None => return false,
+        };
+        match self.driver.file_id(&rel_path) {
+            Some(file_id) => self.driver.set_file_text(file_id, text),
+            None => {
+                self.driver.add_file(rel_path, text);
+            }
+        }
+        true
+    }
+
+    /// Reverts the closed document `uri` to the contents of its file on disk, or removes it from
+    /// the package if it was never saved. Returns whether the package changed.
+    fn revert_document(&mut self, uri: &str) -> bool {
+        let rel_path = match self.relative_path(uri) {
+            Some(rel_path) => rel_path,
+            None => return false,
+        };
+        let file_id = match self.driver.file_id(&rel_path) {
+            Some(file_id) => file_id,
+            None => return false,
+        };
+        match std::fs::read_to_string(rel_path.to_path(&self.source_dir)) {
+            Ok(text) => self.driver.set_file_text(file_id, text),
+            Err(_) => self.driver.remove_file(&rel_path),
+        }
+        true
+    }
+}
+
+/// Returns the URI of the text document of a request.
+fn document_uri(params: &Value) -> Result<&str, ResponseError> {
+    params["textDocument"]["uri"]
+        .as_str()
+        .ok_or_else(|| ResponseError::new(INVALID_PARAMS, "missing text document"))
+}
+
+/// Returns the directory of the source files of the package at `root`, which is given by its
+/// manifest. A directory without a manifest is a package of its own source files.
+fn package_source_dir(root: &Path) -> Result<PathBuf, Error> {
+    if root.join(MANIFEST_FILE_NAME).is_file() {
+        Ok(Manifest::from_dir(root)?.source_dir())
+    } else {
+        Ok(root.to_path_buf())
+    }
+}
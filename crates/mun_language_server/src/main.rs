@@ -0,0 +1,10 @@
+//! Runs the language server of Mun, which an editor talks to over stdin and stdout.
+
+fn main() -> Result<(), failure::Error> {
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    let shut_down = mun_language_server::run(stdin.lock(), stdout.lock())?;
+
+    // An exit without a shutdown request is an error, as LSP prescribes
+    std::process::exit(if shut_down { 0 } else { 1 })
+}
@@ -0,0 +1,115 @@
+//! The symbols of a document, which editors show in their outline.
+
+use crate::conversion;
+use mun_hir::line_index::LineIndex;
+use mun_syntax::{
+    ast::{self, ModuleItemOwner, NameOwner, StructKind},
+    AstNode, SourceFile, TextRange,
+};
+use serde_json::{json, Value};
+
+/// The kinds of symbols of LSP that are used for Mun items.
+const METHOD: u32 = 6;
+const FIELD: u32 = 8;
+const ENUM: u32 = 10;
+const FUNCTION: u32 = 12;
+const VARIABLE: u32 = 13;
+const CONSTANT: u32 = 14;
+const OBJECT: u32 = 19;
+const ENUM_MEMBER: u32 = 22;
+const STRUCT: u32 = 23;
+const TYPE_PARAMETER: u32 = 26;
+
+/// Returns the LSP document symbols of the items of `file`, of which the text is `text`. The
+/// fields of structs, the variants of enums and the functions of impl blocks are the children of
+/// their items. Items without a name, which only occur in code with syntax errors, are skipped.
+pub(crate) fn document_symbols(
+    file: &SourceFile,
+    line_index: &LineIndex,
+    text: &str,
+) -> Vec<Value> {
+    let symbols = Symbols { line_index, text };
+    file.items()
+        .filter_map(|item| match item.kind() {
+            ast::ModuleItemKind::FunctionDef(def) => symbols.named(&def, FUNCTION, Vec::new()),
+            ast::ModuleItemKind::StructDef(def) => {
+                let fields = match def.kind() {
+                    StructKind::Record(fields) => fields
+                        .fields()
+                        .filter_map(|field| symbols.named(&field, FIELD, Vec::new()))
+                        .collect(),
+                    StructKind::Unit => Vec::new(),
+                };
+                symbols.named(&def, STRUCT, fields)
+            }
+            ast::ModuleItemKind::EnumDef(def) => {
+                let variants = def
+                    .enum_variant_list()
+                    .into_iter()
+                    .flat_map(|list| list.variants())
+                    .filter_map(|variant| symbols.named(&variant, ENUM_MEMBER, Vec::new()))
+                    .collect();
+                symbols.named(&def, ENUM, variants)
+            }
+            ast::ModuleItemKind::ConstDef(def) => symbols.named(&def, CONSTANT, Vec::new()),
+            ast::ModuleItemKind::StaticDef(def) => symbols.named(&def, VARIABLE, Vec::new()),
+            ast::ModuleItemKind::TypeAliasDef(def) => {
+                symbols.named(&def, TYPE_PARAMETER, Vec::new())
+            }
+            ast::ModuleItemKind::ImplBlock(block) => {
+                let type_ref = block.type_ref()?;
+                let methods = block
+                    .functions()
+                    .filter_map(|def| symbols.named(&def, METHOD, Vec::new()))
+                    .collect();
+                Some(symbols.symbol(
+                    format!("impl {}", type_ref.syntax().text()),
+                    OBJECT,
+                    block.syntax().text_range(),
+                    type_ref.syntax().text_range(),
+                    methods,
+                ))
+            }
+            ast::ModuleItemKind::UseDecl(_) => None,
+        })
+        .collect()
+}
+
+/// Constructs the symbols of a single file.
+struct Symbols<'a> {
+    line_index: &'a LineIndex,
+    text: &'a str,
+}
+
+impl Symbols<'_> {
+    /// Returns the symbol of the node `node`, which is selected by its name, if it has one.
+    fn named(&self, node: &impl NameOwner, kind: u32, children: Vec<Value>) -> Option<Value> {
+        let name = node.name()?;
+        Some(self.symbol(
+            name.syntax().text().to_string(),
+            kind,
+            node.syntax().text_range(),
+            name.syntax().text_range(),
+            children,
+        ))
+    }
+
+    /// Returns a symbol that spans `range`, of which `selection_range` is selected when the symbol
+    /// is picked.
+    fn symbol(
+        &self,
+        name: String,
+        kind: u32,
+        range: TextRange,
+        selection_range: TextRange,
+        children: Vec<Value>,
+    ) -> Value {
+        json!({
+            "name": name,
+            "kind": kind,
+            "range": conversion::range(self.line_index, self.text, range),
+            "selectionRange": conversion::range(self.line_index, self.text, selection_range),
+            "children": children,
+        })
+    }
+}
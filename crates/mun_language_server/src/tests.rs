@@ -0,0 +1,266 @@
+use crate::{conversion, read_message, run, write_message};
+use mun_hir::line_index::LineIndex;
+use serde_json::{json, Value};
+use std::io::Cursor;
+use std::path::Path;
+use url::Url;
+
+/// Runs a language server on the raw LSP messages `messages`. Returns the messages that the server
+/// sent and whether it was shut down.
+fn run_server(messages: &[Value]) -> (Vec<Value>, bool) {
+    let mut input = Vec::new();
+    for message in messages {
+        write_message(&mut input, message).unwrap();
+    }
+
+    let mut output = Vec::new();
+    let shut_down = run(Cursor::new(input), &mut output).unwrap();
+    let mut output = Cursor::new(output);
+    let mut sent = Vec::new();
+    while let Some(message) = read_message(&mut output).unwrap() {
+        sent.push(message);
+    }
+    (sent, shut_down)
+}
+
+/// Returns the `initialize` request and `initialized` notification for the workspace `root`.
+fn initialize(root: &Path) -> Vec<Value> {
+    vec![
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "rootUri": Url::from_directory_path(root).unwrap().as_str(),
+                "capabilities": {},
+            },
+        }),
+        json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+    ]
+}
+
+/// Returns the notifications of `messages` that publish diagnostics.
+fn published_diagnostics(messages: &[Value]) -> Vec<&Value> {
+    messages
+        .iter()
+        .filter(|message| message["method"] == "textDocument/publishDiagnostics")
+        .map(|message| &message["params"])
+        .collect()
+}
+
+#[test]
+fn publish_diagnostics_of_broken_document() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    std::fs::write(root.join("main.mun"), "export fn main(): int { 1 }").unwrap();
+    let uri = Url::from_file_path(root.join("main.mun")).unwrap();
+
+    let mut messages = initialize(&root);
+    messages.push(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": {
+            "uri": uri.as_str(),
+            "languageId": "mun",
+            "version": 1,
+            "text": "export fn main(): int { 1 }",
+        }},
+    }));
+    messages.push(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didChange",
+        "params": {
+            "textDocument": { "uri": uri.as_str(), "version": 2 },
+            "contentChanges": [{ "text": "export fn main(): int {\n    true\n}" }],
+        },
+    }));
+    messages.push(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didChange",
+        "params": {
+            "textDocument": { "uri": uri.as_str(), "version": 3 },
+            "contentChanges": [{ "text": "export fn main(): int {\n    2\n}" }],
+        },
+    }));
+    let (sent, _) = run_server(&messages);
+
+    assert_eq!(sent[0]["id"], 1);
+    assert_eq!(sent[0]["result"]["capabilities"]["textDocumentSync"], 1);
+
+    // Only changes of the diagnostics are published, so the valid file is published when it
+    // breaks and when it is fixed
+    assert_eq!(
+        published_diagnostics(&sent),
+        vec![
+            &json!({
+                "uri": uri.as_str(),
+                "diagnostics": [{
+                    "range": {
+                        "start": { "line": 1, "character": 4 },
+                        "end": { "line": 1, "character": 8 },
+                    },
+                    "severity": 1,
+                    "code": "E0005",
+                    "source": "mun",
+                    "message": "expected `int`, found `bool`",
+                    "relatedInformation": [{
+                        "location": {
+                            "uri": uri.as_str(),
+                            "range": {
+                                "start": { "line": 0, "character": 18 },
+                                "end": { "line": 0, "character": 21 },
+                            },
+                        },
+                        "message": "expected due to this type",
+                    }],
+                }],
+            }),
+            &json!({ "uri": uri.as_str(), "diagnostics": [] }),
+        ]
+    );
+}
+
+#[test]
+fn clear_diagnostics_of_closed_unsaved_document() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    std::fs::write(root.join("main.mun"), "export fn main(): int { 1 }").unwrap();
+    let uri = Url::from_file_path(root.join("new.mun")).unwrap();
+
+    let mut messages = initialize(&root);
+    messages.push(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": {
+            "uri": uri.as_str(),
+            "languageId": "mun",
+            "version": 1,
+            "text": "export fn broken(): int { let }",
+        }},
+    }));
+    messages.push(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didClose",
+        "params": { "textDocument": { "uri": uri.as_str() } },
+    }));
+    let (sent, _) = run_server(&messages);
+
+    let published = published_diagnostics(&sent);
+    assert_eq!(published.len(), 2);
+    assert_eq!(published[0]["uri"], uri.as_str());
+    assert!(!published[0]["diagnostics"].as_array().unwrap().is_empty());
+    assert_eq!(
+        published[1],
+        &json!({ "uri": uri.as_str(), "diagnostics": [] })
+    );
+}
+
+#[test]
+fn document_symbols() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    std::fs::write(
+        root.join("main.mun"),
+        "struct Vector { x: float }\nimpl Vector {\n    fn len(self): float { self.x }\n}",
+    )
+    .unwrap();
+    let uri = Url::from_file_path(root.join("main.mun")).unwrap();
+
+    let mut messages = initialize(&root);
+    messages.push(json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "textDocument/documentSymbol",
+        "params": { "textDocument": { "uri": uri.as_str() } },
+    }));
+    let (sent, _) = run_server(&messages);
+
+    let range = |start: (u32, u32), end: (u32, u32)| {
+        json!({
+            "start": { "line": start.0, "character": start.1 },
+            "end": { "line": end.0, "character": end.1 },
+        })
+    };
+    let response = sent.iter().find(|message| message["id"] == 2).unwrap();
+    assert_eq!(
+        response["result"],
+        json!([
+            {
+                "name": "Vector",
+                "kind": 23,
+                "range": range((0, 0), (0, 26)),
+                "selectionRange": range((0, 7), (0, 13)),
+                "children": [{
+                    "name": "x",
+                    "kind": 8,
+                    "range": range((0, 16), (0, 24)),
+                    "selectionRange": range((0, 16), (0, 17)),
+                    "children": [],
+                }],
+            },
+            {
+                "name": "impl Vector",
+                "kind": 19,
+                "range": range((1, 0), (3, 1)),
+                "selectionRange": range((1, 5), (1, 11)),
+                "children": [{
+                    "name": "len",
+                    "kind": 6,
+                    "range": range((2, 4), (2, 34)),
+                    "selectionRange": range((2, 7), (2, 10)),
+                    "children": [],
+                }],
+            },
+        ])
+    );
+}
+
+#[test]
+fn requests_before_initialize_and_shutdown() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut messages = vec![json!({ "jsonrpc": "2.0", "id": 1, "method": "shutdown" })];
+    messages.extend(initialize(temp_dir.path()));
+    messages.push(json!({ "jsonrpc": "2.0", "id": 2, "method": "textDocument/hover" }));
+    messages.push(json!({ "jsonrpc": "2.0", "id": 3, "method": "shutdown" }));
+    messages.push(json!({ "jsonrpc": "2.0", "method": "exit" }));
+    // Messages after `exit` are not handled
+    messages.push(json!({ "jsonrpc": "2.0", "id": 4, "method": "shutdown" }));
+    let (sent, shut_down) = run_server(&messages);
+
+    assert!(shut_down);
+    assert_eq!(sent.len(), 4);
+    assert_eq!(sent[0]["error"]["code"], -32002);
+    assert_eq!(sent[1]["id"], 1);
+    assert_eq!(sent[2]["error"]["code"], -32601);
+    assert_eq!(
+        sent[3],
+        json!({ "jsonrpc": "2.0", "id": 3, "result": null })
+    );
+}
+
+#[test]
+fn raw_messages() {
+    let input =
+        "Content-Length: 52\r\n\r\n{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"textDocument/foo\"}";
+    let mut output = Vec::new();
+    assert!(!run(Cursor::new(input), &mut output).unwrap());
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Content-Length: 90\r\n\r\n{\"error\":{\"code\":-32002,\"message\":\"the server is not initialized\"},\"id\":1,\"jsonrpc\":\"2.0\"}"
+    );
+}
+
+#[test]
+fn utf16_positions() {
+    // `ö` is two UTF-8 bytes but one UTF-16 code unit, `𝄞` is four bytes and two code units
+    let text = "let a = \"ö\";\nlet 𝄞 = 1;";
+    let line_index = LineIndex::new(text);
+    assert_eq!(
+        conversion::position(&line_index, text, 12.into()),
+        json!({ "line": 0, "character": 11 })
+    );
+    assert_eq!(
+        conversion::position(&line_index, text, 22.into()),
+        json!({ "line": 1, "character": 6 })
+    );
+}
@@ -0,0 +1,64 @@
+//! The base protocol of LSP: every message is a JSON-RPC object, preceded by a header that
+//! contains the length of the object in bytes, e.g.:
+//!
+//! ```text
+//! Content-Length: 58\r\n
+//! \r\n
+//! {"jsonrpc":"2.0","id":1,"method":"shutdown","params":null}
+//! ```
+
+use failure::{err_msg, Error};
+use serde_json::Value;
+use std::io::{BufRead, Read, Write};
+
+/// Reads the next message from `reader`. Returns `None` if the reader reached its end before the
+/// start of a message.
+pub fn read_message(reader: &mut impl BufRead) -> Result<Option<Value>, Error> {
+    let mut content_length = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return if content_length.is_none() {
+                Ok(None)
+            } else {
+                Err(err_msg(
+                    "unexpected end of input in the header of a message",
+                ))
+            };
+        }
+
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let mut parts = header.splitn(2, ':');
+        match (parts.next(), parts.next()) {
+            (Some(name), Some(value)) if name.eq_ignore_ascii_case("Content-Length") => {
+                content_length = Some(value.trim().parse::<usize>()?)
+            }
+            // Other headers, e.g. `Content-Type`, are ignored
+            (Some(_), Some(_)) => {}
+            _ => return Err(err_msg(format!("invalid header '{}'", header))),
+        }
+    }
+
+    let content_length =
+        content_length.ok_or_else(|| err_msg("missing Content-Length header in a message"))?;
+    let mut content = vec![0; content_length];
+    reader.read_exact(&mut content)?;
+    Ok(Some(serde_json::from_slice(&content)?))
+}
+
+/// Writes `message` with its header to `writer`.
+pub fn write_message(writer: &mut impl Write, message: &Value) -> Result<(), Error> {
+    let content = serde_json::to_string(message)?;
+    write!(
+        writer,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,192 @@
+//! A session that evaluates Mun expressions, e.g. `damage(12.0, 0.5)`, and keeps the items that
+//! were defined before available to later inputs. Every expression is wrapped in a function that is
+//! compiled into an assembly, which is loaded by a runtime that invokes the function.
+
+use failure::{err_msg, Error};
+use mun_abi::Reflection;
+use mun_compiler::{Config, Driver, FileId, PathOrInline, RelativePathBuf};
+use mun_runtime::{invoke_fn, RuntimeBuilder};
+use mun_syntax::{ast::ModuleItemOwner, tokenize, SourceFile, SyntaxKind};
+use termcolor::WriteColor;
+
+#[cfg(test)]
+mod tests;
+
+/// The name of the function that an expression is wrapped in.
+const EVAL_FN_NAME: &str = "__repl_eval";
+
+/// The name of the variable that binds the value of an expression, of which the type is inferred.
+const VALUE_NAME: &str = "__repl_value";
+
+/// The outcome of evaluating an input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Evaluation {
+    /// The input defined items, which are available to later inputs
+    Defined,
+    /// The input is an expression that evaluated to `value`, of type `ty`
+    Value { value: String, ty: String },
+    /// The input is an expression without a value
+    Empty,
+    /// The input did not compile; its errors were emitted
+    Failed,
+}
+
+/// A session of inputs, which keeps the items that were defined.
+pub struct Session {
+    driver: Driver,
+    file_id: FileId,
+    /// The source of the items that were defined
+    items: String,
+    /// The directory of the assemblies of the evaluated expressions
+    _out_dir: tempfile::TempDir,
+}
+
+impl Session {
+    /// Constructs a session in which no items are defined yet.
+    pub fn new() -> Result<Session, Error> {
+        let out_dir = tempfile::tempdir()?;
+        let config = Config {
+            out_dir: Some(out_dir.path().to_path_buf()),
+            ..Config::default()
+        };
+        let (driver, file_id) = Driver::with_file(
+            config,
+            PathOrInline::Inline {
+                rel_path: RelativePathBuf::from("main.mun"),
+                contents: String::new(),
+            },
+        )?;
+        Ok(Session {
+            driver,
+            file_id,
+            items: String::new(),
+            _out_dir: out_dir,
+        })
+    }
+
+    /// Returns the source of the items that were defined in the session.
+    pub fn items(&self) -> &str {
+        &self.items
+    }
+
+    /// Evaluates `input`, which either defines items or is an expression. Compile errors are
+    /// emitted to `writer` and leave the session unchanged, so it can continue with the next
+    /// input. An error is returned if the expression could not be invoked, e.g. because it
+    /// trapped.
+    pub fn eval(&mut self, input: &str, writer: &mut impl WriteColor) -> Result<Evaluation, Error> {
+        let result = if is_items(input) {
+            self.define(input, writer)
+        } else {
+            self.evaluate(input, writer)
+        };
+
+        // Only the items of the session are kept, whether the input compiled or not
+        self.driver.set_file_text(self.file_id, &self.items);
+        result
+    }
+
+    /// Adds the items of `input` to the session, if they compile along with the other items.
+    fn define(&mut self, input: &str, writer: &mut impl WriteColor) -> Result<Evaluation, Error> {
+        let items = format!("{}{}\n", self.items, input);
+        self.driver.set_file_text(self.file_id, &items);
+        if self.driver.emit_errors(writer)? {
+            return Ok(Evaluation::Failed);
+        }
+
+        self.items = items;
+        Ok(Evaluation::Defined)
+    }
+
+    /// Compiles the expression `input` in a function that returns its value, and invokes it.
+    fn evaluate(&mut self, input: &str, writer: &mut impl WriteColor) -> Result<Evaluation, Error> {
+        // The type of the expression is inferred from a variable that binds it, because a
+        // variable without a type doesn't expect a type of its value
+        self.driver.set_file_text(
+            self.file_id,
+            format!(
+                "{}fn {}() {{\n    let {} = {{\n{}\n}};\n}}\n",
+                self.items, EVAL_FN_NAME, VALUE_NAME, input
+            ),
+        );
+        if self.driver.emit_errors(writer)? {
+            return Ok(Evaluation::Failed);
+        }
+        let ty = self
+            .driver
+            .local_type(self.file_id, EVAL_FN_NAME, VALUE_NAME)
+            .ok_or_else(|| err_msg("the type of the expression could not be inferred"))?;
+
+        let has_value = ty != "nothing" && ty != "never";
+        let return_type = if has_value {
+            format!(": {}", ty)
+        } else {
+            String::new()
+        };
+        self.driver.set_file_text(
+            self.file_id,
+            format!(
+                "{}export fn {}(){} {{\n{}\n}}\n",
+                self.items, EVAL_FN_NAME, return_type, input
+            ),
+        );
+        if self.driver.emit_errors(writer)? {
+            return Ok(Evaluation::Failed);
+        }
+
+        let assembly_path = self
+            .driver
+            .write_assembly(self.file_id)?
+            .ok_or_else(|| err_msg("no assembly was written for the expression"))?;
+        let mut runtime = RuntimeBuilder::new(assembly_path).spawn()?;
+        if !has_value {
+            return invoke_fn!(runtime, EVAL_FN_NAME)
+                .map(|()| Evaluation::Empty)
+                .map_err(|e| err_msg(format!("{}", e)));
+        }
+
+        let type_guid = runtime
+            .get_function_info(EVAL_FN_NAME)
+            .and_then(|info| info.signature.return_type())
+            .map(|ret_type| ret_type.guid);
+
+        // Invokes the function and returns its value if it returns a `$T`
+        macro_rules! invoke_and_display {
+            ($($T:ty),+) => {
+                $(
+                    if type_guid == Some(<$T>::type_guid()) {
+                        let value: $T = invoke_fn!(runtime, EVAL_FN_NAME)
+                            .map_err(|e| err_msg(format!("{}", e)))?;
+
+                        return Ok(Evaluation::Value { value: value.to_string(), ty });
+                    }
+                )+
+            };
+        }
+        invoke_and_display!(bool, f32, f64, i8, i16, i32, i64, u8, u16, u32, u64);
+
+        Err(err_msg(format!(
+            "Only values of native Mun types can be displayed. Found: {}",
+            ty
+        )))
+    }
+}
+
+/// Returns whether `input` consists of items, rather than an expression.
+fn is_items(input: &str) -> bool {
+    let parse = SourceFile::parse(input);
+    parse.errors().is_empty() && parse.tree().items().next().is_some()
+}
+
+/// Returns whether all delimiters that `input` opens are closed again, i.e. whether an input that
+/// is entered line by line is complete.
+pub fn is_complete(input: &str) -> bool {
+    let mut depth = 0i32;
+    for token in tokenize(input) {
+        match token.kind {
+            SyntaxKind::L_CURLY | SyntaxKind::L_PAREN | SyntaxKind::L_BRACKET => depth += 1,
+            SyntaxKind::R_CURLY | SyntaxKind::R_PAREN | SyntaxKind::R_BRACKET => depth -= 1,
+            _ => {}
+        }
+    }
+    depth <= 0
+}
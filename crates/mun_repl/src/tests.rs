@@ -0,0 +1,85 @@
+use crate::{is_complete, Evaluation, Session};
+use termcolor::NoColor;
+
+/// Returns the evaluation of the value `value` of type `ty`.
+fn value(value: &str, ty: &str) -> Evaluation {
+    Evaluation::Value {
+        value: value.to_string(),
+        ty: ty.to_string(),
+    }
+}
+
+#[test]
+fn define_and_call_function() {
+    let mut session = Session::new().unwrap();
+    let mut output = NoColor::new(Vec::new());
+
+    assert_eq!(
+        session
+            .eval(
+                "fn damage(base: float, factor: float): float { base * factor }",
+                &mut output
+            )
+            .unwrap(),
+        Evaluation::Defined
+    );
+    assert_eq!(
+        session.eval("damage(12.0, 0.5)", &mut output).unwrap(),
+        value("6", "float")
+    );
+    assert_eq!(
+        session
+            .eval("damage(12.0, 0.5) > 5.0", &mut output)
+            .unwrap(),
+        value("true", "bool")
+    );
+    assert_eq!(
+        session.eval("let a = 2;\na * 3", &mut output).unwrap(),
+        value("6", "int")
+    );
+    assert_eq!(
+        session.eval("damage(1.0, 1.0);", &mut output).unwrap(),
+        Evaluation::Empty
+    );
+    assert!(output.into_inner().is_empty());
+}
+
+#[test]
+fn compile_errors_keep_session() {
+    let mut session = Session::new().unwrap();
+    let mut output = NoColor::new(Vec::new());
+
+    session
+        .eval("fn double(a: int): int { a * 2 }", &mut output)
+        .unwrap();
+    let items = session.items().to_string();
+
+    // Neither a definition nor an expression with errors changes the items of the session
+    assert_eq!(
+        session
+            .eval("fn broken(): int { true }", &mut output)
+            .unwrap(),
+        Evaluation::Failed
+    );
+    assert_eq!(session.items(), items);
+    assert_eq!(
+        session.eval("double(true)", &mut output).unwrap(),
+        Evaluation::Failed
+    );
+    let errors = String::from_utf8(output.into_inner()).unwrap();
+    assert!(errors.contains("expected `int`, found `bool`"));
+
+    let mut output = NoColor::new(Vec::new());
+    assert_eq!(
+        session.eval("double(21)", &mut output).unwrap(),
+        value("42", "int")
+    );
+}
+
+#[test]
+fn complete_inputs() {
+    assert!(is_complete("damage(12.0, 0.5)"));
+    assert!(is_complete("fn double(a: int): int {\n    a * 2\n}"));
+    assert!(!is_complete("fn double(a: int): int {"));
+    assert!(!is_complete("damage(\n    12.0,"));
+}
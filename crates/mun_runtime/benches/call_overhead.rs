@@ -0,0 +1,63 @@
+//! Compares the cost of calls between the functions of an assembly that go through the dispatch
+//! table with calls that go directly to the callee. Run with
+//! `cargo bench -p mun_runtime --bench call_overhead`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use mun_compiler::{CallMode, Config, Driver, PathOrInline, RelativePathBuf};
+use mun_runtime::{invoke_fn, Runtime, RuntimeBuilder};
+use std::path::Path;
+
+/// Every call of `fibonacci` makes two more calls, so the time of a call to `fibonacci` is
+/// dominated by the calls between the functions of the assembly.
+const FIXTURE: &str = r"
+fn fibonacci(n: int): int {
+    if n <= 1 {
+        n
+    } else {
+        fibonacci(n - 1) + fibonacci(n - 2)
+    }
+}
+";
+
+/// The argument to `fibonacci`, for which it makes 21891 calls.
+const FIBONACCI_N: i64 = 20;
+
+/// Compiles the fixture with `call_mode` into `out_dir` and loads the assembly into a runtime.
+fn runtime(call_mode: CallMode, out_dir: &Path) -> Runtime {
+    let config = Config {
+        call_mode,
+        out_dir: Some(out_dir.to_path_buf()),
+        ..Config::default()
+    };
+    let (driver, file_id) = Driver::with_file(
+        config,
+        PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("main.mun"),
+            contents: FIXTURE.to_owned(),
+        },
+    )
+    .unwrap();
+    let out_path = driver.write_assembly(file_id).unwrap().unwrap();
+    RuntimeBuilder::new(out_path).spawn().unwrap()
+}
+
+fn dispatched_and_direct_calls(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fibonacci");
+    for (name, call_mode) in &[
+        ("dispatch", CallMode::Dispatch),
+        ("direct", CallMode::Direct),
+    ] {
+        let out_dir = tempfile::tempdir().unwrap();
+        let mut runtime = runtime(*call_mode, out_dir.path());
+        group.bench_function(*name, |b| {
+            b.iter(|| {
+                let result: i64 = invoke_fn!(runtime, "fibonacci", black_box(FIBONACCI_N)).unwrap();
+                result
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, dispatched_and_direct_calls);
+criterion_main!(benches);
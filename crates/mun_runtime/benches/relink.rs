@@ -0,0 +1,75 @@
+//! Measures how long it takes to link an assembly with a few hundred dispatch table slots, both
+//! by the names of the functions of its slots and by the indices that the slots were previously
+//! resolved to. Run with `cargo bench -p mun_runtime --bench relink`.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use mun_compiler::{Config, Driver, PathOrInline, RelativePathBuf};
+use mun_runtime::{Assembly, DispatchTable};
+use std::path::{Path, PathBuf};
+use std::ptr;
+
+/// The number of functions of the fixture, every one of which occupies a dispatch table slot.
+const FIXTURE_FUNCTIONS: usize = 300;
+
+/// Generates a file of `functions` functions, of which every function calls the previous one.
+fn many_functions_fixture(functions: usize) -> String {
+    let mut text = "fn f0(a: int): int { a }\n".to_owned();
+    for i in 1..functions {
+        text.push_str(&format!("fn f{}(a: int): int {{ f{}(a) }}\n", i, i - 1));
+    }
+    text
+}
+
+/// Compiles the fixture into `out_dir` and returns the path of the assembly.
+fn compile_fixture(out_dir: &Path) -> PathBuf {
+    let config = Config {
+        out_dir: Some(out_dir.to_path_buf()),
+        ..Config::default()
+    };
+    let (driver, file_id) = Driver::with_file(
+        config,
+        PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("main.mun"),
+            contents: many_functions_fixture(FIXTURE_FUNCTIONS),
+        },
+    )
+    .unwrap();
+    driver.write_assembly(file_id).unwrap().unwrap()
+}
+
+fn link_by_name_and_by_index(c: &mut Criterion) {
+    let out_dir = tempfile::tempdir().unwrap();
+    let library_path = compile_fixture(out_dir.path());
+
+    // Loading the assembly inserts its functions into the dispatch table that it is linked against
+    let mut dispatch_table = DispatchTable::default();
+    let mut assembly = Assembly::load(&library_path, &mut dispatch_table, ptr::null_mut()).unwrap();
+
+    let mut group = c.benchmark_group("relink");
+
+    // A freshly loaded assembly has not been linked before, so it resolves every slot by name
+    group.bench_function("by_name", |b| {
+        b.iter_batched(
+            || {
+                let mut scratch_table = DispatchTable::default();
+                Assembly::load(&library_path, &mut scratch_table, ptr::null_mut()).unwrap()
+            },
+            |mut assembly| {
+                assembly.link(&dispatch_table).unwrap();
+                // Unloading the library is not part of the measurement
+                assembly
+            },
+            BatchSize::PerIteration,
+        )
+    });
+
+    // Once linked, an assembly with an unchanged dispatch table patches its slots by index
+    assembly.link(&dispatch_table).unwrap();
+    group.bench_function("by_index", |b| {
+        b.iter(|| assembly.link(&dispatch_table).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, link_by_name_and_by_index);
+criterion_main!(benches);
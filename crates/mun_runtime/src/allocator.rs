@@ -0,0 +1,66 @@
+use std::alloc::{self, Layout};
+use std::ffi::c_void;
+
+use mun_abi::{FunctionInfoBuilder, FunctionInfoStorage};
+use parking_lot::Mutex;
+
+/// The name under which the allocation function is registered in the runtime's dispatch table.
+/// The `@core::` prefix prevents collisions with functions defined in Mun.
+pub const NEW_FN_NAME: &str = "@core::new";
+
+/// Allocates the memory of garbage collected structs on behalf of Mun assemblies.
+///
+/// Memory is not collected yet; all allocations are released when the allocator is dropped.
+#[derive(Default)]
+pub struct Allocator {
+    allocations: Mutex<Vec<(*mut u8, Layout)>>,
+}
+
+impl Allocator {
+    /// Allocates zero-initialized memory of `size` bytes with the specified `alignment`.
+    pub fn allocate(&self, size: usize, alignment: usize) -> *mut u8 {
+        // Allocating zero bytes is undefined behavior, so unit structs occupy a single byte.
+        let layout =
+            Layout::from_size_align(size.max(1), alignment).expect("invalid allocation layout");
+        let ptr = unsafe { alloc::alloc_zeroed(layout) };
+        if ptr.is_null() {
+            alloc::handle_alloc_error(layout);
+        }
+        self.allocations.lock().push((ptr, layout));
+        ptr
+    }
+
+    /// Returns the handle that assemblies pass to the allocation function.
+    pub fn handle(&self) -> *mut c_void {
+        self as *const Allocator as *mut c_void
+    }
+
+    /// Constructs the information of the allocation function that allocates memory through the
+    /// allocator identified by the passed handle.
+    pub fn new_fn_info() -> FunctionInfoStorage {
+        FunctionInfoBuilder::new(NEW_FN_NAME)
+            .params::<(u64, u32, &mut u8)>()
+            .param_names(&["size", "alignment", "alloc_handle"])
+            .returns::<*mut u8>()
+            .fn_ptr(new as *const c_void)
+            .build()
+    }
+}
+
+impl Drop for Allocator {
+    fn drop(&mut self) {
+        for (ptr, layout) in self.allocations.get_mut().drain(..) {
+            unsafe { alloc::dealloc(ptr, layout) };
+        }
+    }
+}
+
+unsafe impl Send for Allocator {}
+unsafe impl Sync for Allocator {}
+
+/// Allocates `size` bytes with the specified `alignment` using the allocator identified by
+/// `alloc_handle`. Mun assemblies call this function to construct garbage collected structs.
+extern "C" fn new(size: u64, alignment: u32, alloc_handle: *mut c_void) -> *mut u8 {
+    let allocator = unsafe { &*(alloc_handle as *const Allocator) };
+    allocator.allocate(size as usize, alignment as usize)
+}
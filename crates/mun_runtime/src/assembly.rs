@@ -1,13 +1,18 @@
+use std::ffi::c_void;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::{ptr, slice};
 
 use crate::DispatchTable;
 use failure::Error;
 use libloading::Symbol;
-use mun_abi::AssemblyInfo;
+use mun_abi::metadata::AssemblyMetadata;
+use mun_abi::{AssemblyInfo, Guid, ModuleInfo, Str, TypeDesc, TypeInfo};
 
+pub(crate) mod library_format;
 mod temp_library;
 
+use self::library_format::{check_library_format, check_target};
 use self::temp_library::TempLibrary;
 
 /// An assembly is a hot reloadable compilation unit, consisting of one or more Mun modules.
@@ -15,14 +20,34 @@ pub struct Assembly {
     library_path: PathBuf,
     library: Option<TempLibrary>,
     info: AssemblyInfo,
+    linked_slots: LinkedSlots,
+}
+
+/// The runtime dispatch table indices that the slots of an assembly's dispatch table were last
+/// resolved to, and the hash of the dispatch table they were resolved for.
+#[derive(Default)]
+struct LinkedSlots {
+    hash: Option<u64>,
+    indices: Vec<usize>,
+}
+
+/// The value of a global of an assembly, which is restored in the reloaded assembly if it still
+/// contains a global with the same path and type.
+struct GlobalValue {
+    path: String,
+    type_guid: Guid,
+    bytes: Vec<u8>,
 }
 
 impl Assembly {
-    /// Loads an assembly and its information for the shared library at `library_path`.
+    /// Loads an assembly and its information for the shared library at `library_path`. Memory
+    /// that the assembly allocates is provided by the allocator identified by `allocator_handle`.
     pub fn load(
         library_path: &Path,
         runtime_dispatch_table: &mut DispatchTable,
+        allocator_handle: *mut c_void,
     ) -> Result<Self, Error> {
+        check_library_format(library_path)?;
         let library = TempLibrary::new(library_path)?;
 
         // Check whether the library has a symbols function
@@ -30,24 +55,64 @@ impl Assembly {
             unsafe { library.library().get(b"get_info") }?;
 
         let info = get_info();
+        check_target(library_path, info.target())?;
+        check_type_layouts(library_path, &info)?;
+        info.set_allocator_handle(allocator_handle);
 
-        for function in info.symbols.functions() {
-            runtime_dispatch_table.insert(function.signature.name(), function.clone());
-        }
+        register_functions(&info.symbols, runtime_dispatch_table);
 
         Ok(Assembly {
             library_path: library_path.to_path_buf(),
             library: Some(library),
             info,
+            linked_slots: LinkedSlots::default(),
         })
     }
 
     /// Links the assembly using the runtime's dispatch table.
+    ///
+    /// If the assembly's dispatch table has the same hash as when it was last linked, its slots
+    /// are patched using the previously resolved indices. Otherwise, or if the assembly does not
+    /// provide a hash, every slot is resolved by name.
     pub fn link(&mut self, runtime_dispatch_table: &DispatchTable) -> Result<(), Error> {
+        if !self.link_by_index(runtime_dispatch_table) {
+            self.link_by_name(runtime_dispatch_table)?;
+        }
+        Ok(())
+    }
+
+    /// Tries to link the assembly using the previously resolved slot indices. Returns whether all
+    /// slots could be resolved.
+    fn link_by_index(&mut self, runtime_dispatch_table: &DispatchTable) -> bool {
+        let hash = self.info.dispatch_table.hash();
+        let fn_ptrs = self.info.dispatch_table.ptrs_mut();
+        if hash.is_none()
+            || hash != self.linked_slots.hash
+            || fn_ptrs.len() != self.linked_slots.indices.len()
+        {
+            return false;
+        }
+
+        for (dispatch_ptr, idx) in fn_ptrs.iter_mut().zip(self.linked_slots.indices.iter()) {
+            match runtime_dispatch_table.get_by_index(*idx) {
+                Some(fn_info) => *dispatch_ptr = fn_info.fn_ptr,
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// Links the assembly by resolving every slot by the name of its function.
+    fn link_by_name(&mut self, runtime_dispatch_table: &DispatchTable) -> Result<(), Error> {
+        let mut indices = Vec::with_capacity(self.info.dispatch_table.signatures().len());
         for (dispatch_ptr, fn_signature) in self.info.dispatch_table.iter_mut() {
-            let fn_ptr = runtime_dispatch_table
-                .get(fn_signature.name())
-                .map(|f| f.fn_ptr)
+            let (idx, fn_ptr) = runtime_dispatch_table
+                .get_index(fn_signature.name())
+                .and_then(|idx| {
+                    runtime_dispatch_table
+                        .get_by_index(idx)
+                        .map(|f| (idx, f.fn_ptr))
+                })
                 .ok_or_else(|| {
                     io::Error::new(
                         io::ErrorKind::NotFound,
@@ -59,29 +124,84 @@ impl Assembly {
                 })?;
 
             *dispatch_ptr = fn_ptr;
+            indices.push(idx);
         }
+
+        self.linked_slots = LinkedSlots {
+            hash: self.info.dispatch_table.hash(),
+            indices,
+        };
         Ok(())
     }
 
     /// Swaps the assembly's shared library and its information for the library at `library_path`.
+    ///
+    /// The values of globals are preserved if the new library contains a global with the same path
+    /// and type. Other globals are initialized with their initial values.
     pub fn swap(
         &mut self,
         library_path: &Path,
         runtime_dispatch_table: &mut DispatchTable,
+        allocator_handle: *mut c_void,
     ) -> Result<(), Error> {
         // let library_path = library_path.canonicalize()?;
 
-        for function in self.info.symbols.functions() {
-            runtime_dispatch_table.remove(function.signature.name());
+        for (path, _) in self.info.symbols.qualified_functions() {
+            runtime_dispatch_table.remove(&path);
         }
 
+        // Copy the values of the globals before their memory is unloaded
+        let global_values = self.global_values();
+
         // Drop the old library, as some operating systems don't allow editing of in-use shared
         // libraries
         self.library.take();
 
         // TODO: Partial hot reload of an assembly
-        *self = Assembly::load(library_path, runtime_dispatch_table)?;
-        Ok(())
+        let linked_slots = std::mem::replace(&mut self.linked_slots, LinkedSlots::default());
+        *self = Assembly::load(library_path, runtime_dispatch_table, allocator_handle)?;
+        self.linked_slots = linked_slots;
+        self.restore_global_values(&global_values);
+        self.link(runtime_dispatch_table)
+    }
+
+    /// Copies the current values of the assembly's globals.
+    fn global_values(&self) -> Vec<GlobalValue> {
+        self.info
+            .symbols
+            .qualified_globals()
+            .into_iter()
+            .map(|(path, global)| {
+                let type_info = global.type_info();
+                let bytes = unsafe {
+                    slice::from_raw_parts(global.ptr as *const u8, type_info.size_in_bytes())
+                };
+                GlobalValue {
+                    path,
+                    type_guid: type_info.guid,
+                    bytes: bytes.to_vec(),
+                }
+            })
+            .collect()
+    }
+
+    /// Overwrites the values of the assembly's globals with the matching `values`.
+    fn restore_global_values(&self, values: &[GlobalValue]) {
+        for (path, global) in self.info.symbols.qualified_globals() {
+            let type_info = global.type_info();
+            let value = values
+                .iter()
+                .find(|value| value.path == path && value.type_guid == type_info.guid);
+            if let Some(value) = value {
+                unsafe {
+                    ptr::copy_nonoverlapping(
+                        value.bytes.as_ptr(),
+                        global.ptr as *mut u8,
+                        value.bytes.len(),
+                    )
+                };
+            }
+        }
     }
 
     /// Returns the assembly's information.
@@ -89,8 +209,81 @@ impl Assembly {
         &self.info
     }
 
+    /// Returns the metadata of the assembly, in the format of the metadata that the compiler
+    /// writes next to the assembly.
+    pub fn metadata(&self) -> AssemblyMetadata {
+        AssemblyMetadata::from_assembly_info(&self.info)
+    }
+
+    /// Returns the root module of the assembly, from which the module tree can be enumerated.
+    pub fn root_module(&self) -> &ModuleInfo {
+        &self.info.symbols
+    }
+
     /// Returns the path corresponding to the assembly's library.
     pub fn library_path(&self) -> &Path {
         self.library_path.as_path()
     }
 }
+
+/// Registers the functions of `module` and its descendants in the dispatch table under their fully
+/// qualified paths, e.g. `foo::bar::baz`, so functions with the same name in different modules
+/// don't collide.
+pub(crate) fn register_functions(module: &ModuleInfo, dispatch_table: &mut DispatchTable) {
+    for (path, function) in module.qualified_functions() {
+        dispatch_table.insert(&path, function.clone());
+    }
+}
+
+/// Verifies that the layouts of the primitive types that are referenced by the functions and
+/// globals of an assembly match the layouts of their host counterparts.
+fn check_type_layouts(library_path: &Path, info: &AssemblyInfo) -> Result<(), io::Error> {
+    let primitives = [
+        TypeDesc::of::<f32>(),
+        TypeDesc::of::<f64>(),
+        TypeDesc::of::<i8>(),
+        TypeDesc::of::<i16>(),
+        TypeDesc::of::<i32>(),
+        TypeDesc::of::<i64>(),
+        TypeDesc::of::<u8>(),
+        TypeDesc::of::<u16>(),
+        TypeDesc::of::<u32>(),
+        TypeDesc::of::<u64>(),
+        TypeDesc::of::<bool>(),
+        TypeDesc::of::<Str>(),
+    ];
+
+    let check = |type_info: &TypeInfo| {
+        if let Some(expected) = primitives.iter().find(|t| t.guid == type_info.guid) {
+            if expected.size_in_bits != type_info.size_in_bits
+                || expected.alignment != type_info.alignment
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "Failed to load '{}': type '{}' has size {} bits and alignment {}, \
+                         but the runtime expects size {} bits and alignment {}.",
+                        library_path.display(),
+                        type_info.name(),
+                        type_info.size_in_bits,
+                        type_info.alignment,
+                        expected.size_in_bits,
+                        expected.alignment
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    };
+
+    for (_, function) in info.symbols.qualified_functions() {
+        let signature = &function.signature;
+        for type_info in signature.arg_types().iter().chain(signature.return_type()) {
+            check(type_info)?;
+        }
+    }
+    for (_, global) in info.symbols.qualified_globals() {
+        check(global.type_info())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,250 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// The object file format and architecture of a shared library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LibraryFormat {
+    pub kind: ObjectKind,
+    pub arch: Arch,
+}
+
+/// An object file format for shared libraries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectKind {
+    Elf,
+    MachO,
+    Pe,
+}
+
+/// A machine architecture that shared libraries are compiled for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arch {
+    X86,
+    X86_64,
+    Arm,
+    AArch64,
+    Other(u32),
+}
+
+impl Arch {
+    /// Returns the architecture of the host, or `None` if the runtime does not know it.
+    pub fn host() -> Option<Self> {
+        if cfg!(target_arch = "x86") {
+            Some(Arch::X86)
+        } else if cfg!(target_arch = "x86_64") {
+            Some(Arch::X86_64)
+        } else if cfg!(target_arch = "arm") {
+            Some(Arch::Arm)
+        } else if cfg!(target_arch = "aarch64") {
+            Some(Arch::AArch64)
+        } else {
+            None
+        }
+    }
+
+    /// Determines the architecture from the first component of a target triple, or `None` if the
+    /// architecture is not known.
+    pub fn from_triple(triple: &str) -> Option<Self> {
+        match triple.split('-').next()? {
+            "x86" | "i386" | "i586" | "i686" => Some(Arch::X86),
+            "x86_64" => Some(Arch::X86_64),
+            "aarch64" | "arm64" => Some(Arch::AArch64),
+            arch if arch.starts_with("arm") || arch.starts_with("thumb") => Some(Arch::Arm),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Arch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Arch::X86 => write!(f, "x86"),
+            Arch::X86_64 => write!(f, "x86_64"),
+            Arch::Arm => write!(f, "arm"),
+            Arch::AArch64 => write!(f, "aarch64"),
+            Arch::Other(machine) => write!(f, "(machine type {:#x})", machine),
+        }
+    }
+}
+
+/// Returns the operating system of the host, or `None` if the runtime does not know it.
+fn host_os() -> Option<&'static str> {
+    if cfg!(target_os = "windows") {
+        Some("windows")
+    } else if cfg!(target_os = "macos") {
+        Some("macos")
+    } else if cfg!(target_os = "linux") {
+        Some("linux")
+    } else {
+        None
+    }
+}
+
+/// Determines the operating system from the components of a target triple, or `None` if the
+/// operating system is not known.
+fn os_from_triple(triple: &str) -> Option<&'static str> {
+    triple
+        .split('-')
+        .skip(1)
+        .find_map(|component| match component {
+            "windows" => Some("windows"),
+            "linux" => Some("linux"),
+            c if c.starts_with("darwin") || c.starts_with("macos") => Some("macos"),
+            _ => None,
+        })
+}
+
+impl LibraryFormat {
+    /// Returns the format of shared libraries that can be loaded on the host, or `None` if the
+    /// architecture of the host is not known.
+    pub fn host() -> Option<Self> {
+        let kind = if cfg!(target_os = "windows") {
+            ObjectKind::Pe
+        } else if cfg!(target_os = "macos") {
+            ObjectKind::MachO
+        } else {
+            ObjectKind::Elf
+        };
+        Arch::host().map(|arch| LibraryFormat { kind, arch })
+    }
+
+    /// Determines the format of the shared library at `path` from its header, or `None` if the
+    /// file is not a recognized object file.
+    pub fn of_file(path: &Path) -> Result<Option<Self>, io::Error> {
+        let mut header = Vec::new();
+        File::open(path)?.take(4096).read_to_end(&mut header)?;
+        Ok(Self::of_header(&header))
+    }
+
+    fn of_header(header: &[u8]) -> Option<Self> {
+        let read_u16 = |offset: usize| {
+            header
+                .get(offset..offset + 2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        };
+        let read_u32 = |offset: usize| {
+            header
+                .get(offset..offset + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        };
+
+        if header.starts_with(b"\x7fELF") {
+            let arch = match read_u16(18)? {
+                0x03 => Arch::X86,
+                0x3E => Arch::X86_64,
+                0x28 => Arch::Arm,
+                0xB7 => Arch::AArch64,
+                machine => Arch::Other(u32::from(machine)),
+            };
+            Some(LibraryFormat {
+                kind: ObjectKind::Elf,
+                arch,
+            })
+        } else if header.starts_with(&[0xCF, 0xFA, 0xED, 0xFE])
+            || header.starts_with(&[0xCE, 0xFA, 0xED, 0xFE])
+        {
+            let arch = match read_u32(4)? {
+                0x0000_0007 => Arch::X86,
+                0x0100_0007 => Arch::X86_64,
+                0x0000_000C => Arch::Arm,
+                0x0100_000C => Arch::AArch64,
+                cpu_type => Arch::Other(cpu_type),
+            };
+            Some(LibraryFormat {
+                kind: ObjectKind::MachO,
+                arch,
+            })
+        } else if header.starts_with(b"MZ") {
+            // The DOS header points to the PE header, which is followed by the machine type
+            let pe_offset = read_u32(0x3C)? as usize;
+            if header.get(pe_offset..pe_offset + 4)? != b"PE\0\0" {
+                return None;
+            }
+            let arch = match read_u16(pe_offset + 4)? {
+                0x014C => Arch::X86,
+                0x8664 => Arch::X86_64,
+                0x01C0 | 0x01C4 => Arch::Arm,
+                0xAA64 => Arch::AArch64,
+                machine => Arch::Other(u32::from(machine)),
+            };
+            Some(LibraryFormat {
+                kind: ObjectKind::Pe,
+                arch,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for LibraryFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            ObjectKind::Elf => "ELF",
+            ObjectKind::MachO => "Mach-O",
+            ObjectKind::Pe => "PE",
+        };
+        write!(f, "{} {}", kind, self.arch)
+    }
+}
+
+/// Checks that the shared library at `library_path` was compiled for the host, to report a
+/// library that was compiled for another target instead of failing to load it.
+pub fn check_library_format(library_path: &Path) -> Result<(), io::Error> {
+    // Libraries cannot be checked on a host of an unknown architecture
+    let host = match LibraryFormat::host() {
+        Some(host) => host,
+        None => return Ok(()),
+    };
+    match LibraryFormat::of_file(library_path)? {
+        Some(format) if format != host => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "Failed to load '{}': the library is a {} library, but the runtime requires a {} \
+                 library. Recompile the library for the host target.",
+                library_path.display(),
+                format,
+                host
+            ),
+        )),
+        // Unrecognized files are left to the dynamic loader to report
+        _ => Ok(()),
+    }
+}
+
+/// Checks that the `target` triple that the shared library at `library_path` was compiled for
+/// matches the architecture and operating system of the host. Unlike `check_library_format`, this
+/// also tells apart targets that share an object file format, e.g. two operating systems that both
+/// use ELF.
+pub fn check_target(library_path: &Path, target: &str) -> Result<(), io::Error> {
+    let arch_matches = match (Arch::from_triple(target), Arch::host()) {
+        (Some(arch), Some(host)) => arch == host,
+        _ => true,
+    };
+    let os_matches = match (os_from_triple(target), host_os()) {
+        (Some(os), Some(host)) => os == host,
+        _ => true,
+    };
+    if arch_matches && os_matches {
+        return Ok(());
+    }
+
+    let host = match (Arch::host(), host_os()) {
+        (Some(arch), Some(os)) => format!("{} {}", arch, os),
+        (Some(arch), None) => arch.to_string(),
+        (None, Some(os)) => os.to_owned(),
+        (None, None) => "host".to_owned(),
+    };
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!(
+            "Failed to load '{}': the library was compiled for target '{}', but the runtime \
+             requires a library for {}. Recompile the library for the host target.",
+            library_path.display(),
+            target,
+            host
+        ),
+    ))
+}
@@ -4,9 +4,11 @@
 //! compliant shared libraries.
 #![warn(missing_docs)]
 
+mod allocator;
 mod assembly;
 #[macro_use]
 mod macros;
+mod trap;
 
 #[cfg(test)]
 mod test;
@@ -18,10 +20,14 @@ use std::sync::mpsc::{channel, Receiver};
 use std::time::Duration;
 
 use failure::Error;
-use mun_abi::{FunctionInfo, Reflection};
+use mun_abi::{ArgumentReflection, FunctionInfo, FunctionInfoStorage, Privacy, Reflection};
 use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
 
 pub use crate::assembly::Assembly;
+pub use crate::trap::ScriptTrap;
+pub use mun_abi::TrapKind;
+
+use crate::allocator::Allocator;
 
 /// Options for the construction of a [`Runtime`].
 #[derive(Clone, Debug)]
@@ -30,11 +36,15 @@ pub struct RuntimeOptions {
     pub library_path: PathBuf,
     /// Delay during which filesystem events are collected, deduplicated, and after which emitted.
     pub delay: Duration,
+    /// Host functions that are made available to Mun libraries through the dispatch table.
+    pub user_functions: Vec<FunctionInfoStorage>,
 }
 
 /// A builder for the [`Runtime`].
 pub struct RuntimeBuilder {
     options: RuntimeOptions,
+    /// The temporary directory of a library that was loaded from memory
+    library_dir: Option<tempfile::TempDir>,
 }
 
 impl RuntimeBuilder {
@@ -44,32 +54,78 @@ impl RuntimeBuilder {
             options: RuntimeOptions {
                 library_path: library_path.into(),
                 delay: Duration::from_millis(10),
+                user_functions: Vec::new(),
             },
+            library_dir: None,
         }
     }
 
+    /// Constructs a new `RuntimeBuilder` for a shared library that is given by its contents, e.g.
+    /// an assembly that was compiled in memory. The library cannot have dependencies.
+    ///
+    /// # Limitations
+    ///
+    /// The operating system only loads shared libraries from files, so the library is written to
+    /// a temporary directory that exists as long as the runtime.
+    pub fn from_bytes(library: &[u8]) -> Result<Self, Error> {
+        let library_dir = tempfile::tempdir()?;
+        let library_path = library_dir
+            .path()
+            .join(format!("assembly{}", std::env::consts::DLL_SUFFIX));
+        std::fs::write(&library_path, library)?;
+
+        let mut builder = RuntimeBuilder::new(library_path);
+        builder.library_dir = Some(library_dir);
+        Ok(builder)
+    }
+
     /// Sets the `delay`.
     pub fn set_delay(&mut self, delay: Duration) -> &mut Self {
         self.options.delay = delay;
         self
     }
 
+    /// Adds a host function to the runtime. The function is available to all loaded
+    /// assemblies and is retained when assemblies are reloaded.
+    pub fn insert_fn(&mut self, function: FunctionInfoStorage) -> &mut Self {
+        self.options.user_functions.push(function);
+        self
+    }
+
     /// Spawns a [`Runtime`] with the builder's options.
     pub fn spawn(self) -> Result<Runtime, Error> {
-        Runtime::new(self.options)
+        let mut runtime = Runtime::new(self.options)?;
+        runtime._library_dir = self.library_dir;
+        Ok(runtime)
     }
 }
 
 /// A runtime dispatch table that maps full function paths to function information.
+///
+/// Every function path is assigned a stable index the first time it is inserted. The index remains
+/// valid when the function is removed and reinserted, e.g. during hot reloading, which allows
+/// assemblies to relink by index rather than by name.
 #[derive(Default)]
 pub struct DispatchTable {
-    functions: HashMap<String, FunctionInfo>,
+    functions: Vec<Option<FunctionInfo>>,
+    indices: HashMap<String, usize>,
 }
 
 impl DispatchTable {
     /// Retrieves the [`FunctionInfo`] corresponding to `fn_path`, if it exists.
     pub fn get(&self, fn_path: &str) -> Option<&FunctionInfo> {
-        self.functions.get(fn_path)
+        self.get_index(fn_path)
+            .and_then(|idx| self.functions[idx].as_ref())
+    }
+
+    /// Retrieves the index of `fn_path`, if it was ever inserted.
+    pub fn get_index(&self, fn_path: &str) -> Option<usize> {
+        self.indices.get(fn_path).cloned()
+    }
+
+    /// Retrieves the [`FunctionInfo`] at `idx`, if it exists.
+    pub fn get_by_index(&self, idx: usize) -> Option<&FunctionInfo> {
+        self.functions.get(idx).and_then(Option::as_ref)
     }
 
     /// Inserts the `fn_info` for `fn_path` into the dispatch table.
@@ -77,12 +133,20 @@ impl DispatchTable {
     /// If the dispatch table already contained this `fn_path`, the value is updated, and the old
     /// value is returned.
     pub fn insert(&mut self, fn_path: &str, fn_info: FunctionInfo) -> Option<FunctionInfo> {
-        self.functions.insert(fn_path.to_string(), fn_info)
+        if let Some(idx) = self.get_index(fn_path) {
+            self.functions[idx].replace(fn_info)
+        } else {
+            self.indices
+                .insert(fn_path.to_string(), self.functions.len());
+            self.functions.push(Some(fn_info));
+            None
+        }
     }
 
     /// Removes and returns the `fn_info` corresponding to `fn_path`, if it exists.
     pub fn remove(&mut self, fn_path: &str) -> Option<FunctionInfo> {
-        self.functions.remove(fn_path)
+        self.get_index(fn_path)
+            .and_then(|idx| self.functions[idx].take())
     }
 }
 
@@ -92,6 +156,10 @@ pub struct Runtime {
     dispatch_table: DispatchTable,
     watcher: RecommendedWatcher,
     watcher_rx: Receiver<DebouncedEvent>,
+    allocator: Box<Allocator>,
+    _intrinsics: Vec<FunctionInfoStorage>,
+    _user_functions: Vec<FunctionInfoStorage>,
+    _library_dir: Option<tempfile::TempDir>,
 }
 
 impl Runtime {
@@ -101,19 +169,38 @@ impl Runtime {
     pub fn new(options: RuntimeOptions) -> Result<Runtime, Error> {
         let (tx, rx) = channel();
 
+        // Intrinsics are functions that the runtime provides to every assembly
+        let intrinsics = vec![
+            Allocator::new_fn_info(),
+            trap::trap_fn_info(),
+            trap::assert_failed_fn_info(),
+        ];
+
+        let mut dispatch_table = DispatchTable::default();
+        for function in intrinsics.iter().chain(options.user_functions.iter()) {
+            let info = function.info();
+            dispatch_table.insert(info.signature.name(), info.clone());
+        }
+
         let watcher: RecommendedWatcher = Watcher::new(tx, options.delay)?;
         let mut runtime = Runtime {
             assemblies: HashMap::new(),
-            dispatch_table: DispatchTable::default(),
+            dispatch_table,
             watcher,
             watcher_rx: rx,
+            allocator: Box::new(Allocator::default()),
+            _intrinsics: intrinsics,
+            _user_functions: options.user_functions,
+            _library_dir: None,
         };
 
         runtime.add_assembly(&options.library_path)?;
         Ok(runtime)
     }
 
-    /// Adds an assembly corresponding to the library at `library_path`.
+    /// Adds an assembly corresponding to the library at `library_path`, after its dependencies. The
+    /// paths of the dependencies are relative to the directory of the library. A dependency that
+    /// is shared by multiple assemblies is only loaded once.
     fn add_assembly(&mut self, library_path: &Path) -> Result<(), Error> {
         let library_path = library_path.canonicalize()?;
         if self.assemblies.contains_key(&library_path) {
@@ -124,22 +211,42 @@ impl Runtime {
             .into());
         }
 
-        let mut assembly = Assembly::load(&library_path, &mut self.dispatch_table)?;
+        let mut assembly = Assembly::load(
+            &library_path,
+            &mut self.dispatch_table,
+            self.allocator.handle(),
+        )?;
+        let library_dir = library_path.parent().unwrap();
         for dependency in assembly.info().dependencies() {
-            self.add_assembly(Path::new(dependency))?;
+            let dependency_path = library_dir.join(dependency).canonicalize()?;
+            if !self.assemblies.contains_key(&dependency_path) {
+                self.add_assembly(&dependency_path)?;
+            }
         }
         assembly.link(&self.dispatch_table)?;
 
         self.watcher
-            .watch(library_path.parent().unwrap(), RecursiveMode::NonRecursive)?;
+            .watch(library_dir, RecursiveMode::NonRecursive)?;
 
         self.assemblies.insert(library_path, assembly);
         Ok(())
     }
 
     /// Retrieves the function information corresponding to `function_name`, if available.
+    ///
+    /// The runtime has the access of the root module of an assembly: it can retrieve every function
+    /// of the root module, but only the functions of other modules that are declared `export`,
+    /// e.g. `math::add`. The private functions of other modules are still part of the dispatch
+    /// table, because other assemblies are linked against it.
     pub fn get_function_info(&self, function_name: &str) -> Option<&FunctionInfo> {
-        self.dispatch_table.get(function_name)
+        self.dispatch_table.get(function_name).filter(|info| {
+            !info.signature.name().contains("::") || info.signature.privacy() == Privacy::Public
+        })
+    }
+
+    /// Returns an iterator over the loaded assemblies.
+    pub fn assemblies(&self) -> impl Iterator<Item = &Assembly> {
+        self.assemblies.values()
     }
 
     /// Updates the state of the runtime. This includes checking for file changes, and reloading
@@ -150,7 +257,9 @@ impl Runtime {
             match event {
                 Write(ref path) | Rename(_, ref path) | Create(ref path) => {
                     if let Some(assembly) = self.assemblies.get_mut(path) {
-                        if let Err(e) = assembly.swap(path, &mut self.dispatch_table) {
+                        if let Err(e) =
+                            assembly.swap(path, &mut self.dispatch_table, self.allocator.handle())
+                        {
                             println!(
                                 "An error occured while reloading assembly '{}': {:?}",
                                 path.to_string_lossy(),
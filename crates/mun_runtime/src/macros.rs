@@ -6,47 +6,54 @@ macro_rules! invoke_fn_impl {
             /// An invocation error that contains the function name, a mutable reference to the
             /// runtime, passed arguments, and the output type. This allows the caller to retry
             /// the function invocation using the `Retriable` trait.
-            pub struct $ErrName<'r, 's, $($T: Reflection,)* Output:Reflection> {
+            pub struct $ErrName<'r, 's, $($T: ArgumentReflection,)* Output:Reflection> {
                 msg: String,
+                trap: Option<$crate::ScriptTrap>,
                 runtime: &'r mut Runtime,
                 function_name: &'s str,
                 $($Arg: $T,)*
                 output: core::marker::PhantomData<Output>,
             }
 
-            impl<'r, 's, $($T: Reflection,)* Output: Reflection> core::fmt::Debug for $ErrName<'r, 's, $($T,)* Output> {
+            impl<'r, 's, $($T: ArgumentReflection,)* Output: Reflection> core::fmt::Debug for $ErrName<'r, 's, $($T,)* Output> {
                 fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
                     write!(f, "{}", &self.msg)
                 }
             }
 
-            impl<'r, 's, $($T: Reflection,)* Output: Reflection> core::fmt::Display for $ErrName<'r, 's, $($T,)* Output> {
+            impl<'r, 's, $($T: ArgumentReflection,)* Output: Reflection> core::fmt::Display for $ErrName<'r, 's, $($T,)* Output> {
                 fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
                     write!(f, "{}", &self.msg)
                 }
             }
 
-            impl<'r, 's, $($T: Reflection,)* Output: Reflection> std::error::Error for $ErrName<'r, 's, $($T,)* Output> {
+            impl<'r, 's, $($T: ArgumentReflection,)* Output: Reflection> std::error::Error for $ErrName<'r, 's, $($T,)* Output> {
                 fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
                     None
                 }
             }
 
-            impl<'r, 's, $($T: Reflection,)* Output: Reflection> $ErrName<'r, 's, $($T,)* Output> {
+            impl<'r, 's, $($T: ArgumentReflection,)* Output: Reflection> $ErrName<'r, 's, $($T,)* Output> {
                 /// Constructs a new invocation error.
                 #[allow(clippy::too_many_arguments)]
                 pub fn new(err_msg: String, runtime: &'r mut Runtime, function_name: &'s str, $($Arg: $T),*) -> Self {
                     Self {
                         msg: err_msg,
+                        trap: None,
                         runtime,
                         function_name,
                         $($Arg,)*
                         output: core::marker::PhantomData,
                     }
                 }
+
+                /// Returns the trap that the Mun code raised during the invocation, if it trapped.
+                pub fn trap(&self) -> Option<&$crate::ScriptTrap> {
+                    self.trap.as_ref()
+                }
             }
 
-            impl<'r, 's, $($T: Reflection,)* Output: Reflection> $crate::RetryResultExt for core::result::Result<Output, $ErrName<'r, 's, $($T,)* Output>> {
+            impl<'r, 's, $($T: ArgumentReflection,)* Output: Reflection> $crate::RetryResultExt for core::result::Result<Output, $ErrName<'r, 's, $($T,)* Output>> {
                 type Output = Output;
 
                 fn retry(self) -> Self {
@@ -77,20 +84,57 @@ macro_rules! invoke_fn_impl {
                 /// based on the manifest at `manifest_path`.
                 ///
                 /// If an error occurs when invoking the method, an error message is logged. The
-                /// runtime continues looping until the cause of the error has been resolved.
+                /// runtime continues looping until the cause of the error has been resolved. An
+                /// invocation during which the Mun code traps, e.g. on an integer overflow, results
+                /// in an error that holds the `ScriptTrap` with the location of the failed check.
                 #[allow(clippy::too_many_arguments)]
-                pub fn $FnName<'r, 's, $($T: Reflection,)* Output: Reflection>(
+                pub fn $FnName<'r, 's, $($T: ArgumentReflection,)* Output: Reflection>(
                     runtime: &'r mut Runtime,
                     function_name: &'s str,
                     $($Arg: $T,)*
                 ) -> core::result::Result<Output, $ErrName<'r, 's, $($T,)* Output>> {
-                    let function: core::result::Result<fn($($T),*) -> Output, String> = runtime
+                    let function: core::result::Result<(extern "C" fn($($T),*) -> Output, mun_abi::ReturnConvention), String> = runtime
                         .get_function_info(function_name)
                         .ok_or(format!("Failed to obtain function '{}'", function_name))
-                        .and_then(|function| mun_abi::downcast_fn!(function, fn($($T),*) -> Output));
+                        .and_then(|function| {
+                            let return_convention = function.signature.return_convention();
+                            mun_abi::downcast_fn!(function, fn($($T),*) -> Output)
+                                .map(|function| (function, return_convention))
+                        });
 
                     match function {
-                        Ok(function) => Ok(function($($Arg),*)),
+                        Ok((function, return_convention)) => {
+                            // Arguments are primitives or references, which can be copied
+                            // bitwise, so they are retained to allow retrying after a trap.
+                            crate::trap::take_trap();
+                            let output = if return_convention == mun_abi::ReturnConvention::Pointer {
+                                // The return value is stored in a buffer that is passed as a
+                                // hidden first argument
+                                let function: extern "C" fn(*mut Output, $($T),*) = unsafe { core::mem::transmute(function) };
+                                let mut output = core::mem::MaybeUninit::<Output>::uninit();
+                                function(output.as_mut_ptr(), $(unsafe { core::ptr::read(&$Arg) }),*);
+                                Ok(unsafe { output.assume_init() })
+                            } else if Output::type_guid() == <bool as Reflection>::type_guid() {
+                                // A returned bool is read as a byte first, because a byte
+                                // other than 0 or 1 is not a valid `bool`.
+                                let function: extern "C" fn($($T),*) -> u8 = unsafe { core::mem::transmute(function) };
+                                let byte = function($(unsafe { core::ptr::read(&$Arg) }),*);
+                                match byte {
+                                    0 | 1 => Ok(unsafe { core::mem::transmute_copy::<u8, Output>(&byte) }),
+                                    _ => Err(format!("Invalid bool value {} returned by '{}'.", byte, function_name)),
+                                }
+                            } else {
+                                Ok(function($(unsafe { core::ptr::read(&$Arg) }),*))
+                            };
+                            if let Some(trap) = crate::trap::take_trap() {
+                                let msg = format!("Script trapped while invoking '{}': {}", function_name, trap);
+                                let mut err = $ErrName::new(msg, runtime, function_name, $($Arg),*);
+                                err.trap = Some(trap);
+                                Err(err)
+                            } else {
+                                output.map_err(|msg| $ErrName::new(msg, runtime, function_name, $($Arg),*))
+                            }
+                        }
                         Err(e) => Err($ErrName::new(e, runtime, function_name, $($Arg),*)),
                     }
                 }
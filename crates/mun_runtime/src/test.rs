@@ -1,6 +1,18 @@
-use crate::{Runtime, RuntimeBuilder};
-use mun_compiler::{ColorChoice, Config, Driver, FileId, PathOrInline, RelativePathBuf};
+use crate::assembly::register_functions;
+use crate::{DispatchTable, Runtime, RuntimeBuilder, ScriptTrap, TrapKind};
+use mun_abi::metadata::AssemblyMetadata;
+use mun_abi::{
+    ArgumentReflection, FunctionInfoBuilder, FunctionInfoStorage, MemoryKind, ModuleInfo, Privacy,
+    Reflection, Str,
+};
+use mun_compiler::{
+    Artifact, ColorChoice, Config, Driver, FileId, FloatSemantics, Manifest, OptimizationLevel,
+    PathOrInline, RelativePathBuf, Target,
+};
+use std::cell::RefCell;
+use std::ffi::{c_void, CString};
 use std::path::PathBuf;
+use std::ptr;
 use std::thread::sleep;
 use std::time::Duration;
 
@@ -17,29 +29,98 @@ struct TestDriver {
 impl TestDriver {
     /// Construct a new TestDriver from a single Mun source
     fn new(text: &str) -> Self {
+        Self::with_functions(text, Vec::new())
+    }
+
+    /// Construct a new TestDriver from a single Mun source and a set of host functions that are
+    /// inserted into the runtime.
+    fn with_functions(text: &str, functions: Vec<FunctionInfoStorage>) -> Self {
+        Self::try_with_functions(text, functions).unwrap()
+    }
+
+    /// Construct a new TestDriver from a single Mun source that is compiled with the specified
+    /// optimization level.
+    fn with_optimization_lvl(text: &str, optimization_lvl: OptimizationLevel) -> Self {
+        Self::with_config(
+            text,
+            Config {
+                optimization_lvl,
+                ..Config::default()
+            },
+        )
+    }
+
+    /// Construct a new TestDriver from a single Mun source that is compiled with the specified
+    /// configuration. The output directory of the configuration is ignored.
+    fn with_config(text: &str, config: Config) -> Self {
+        Self::try_with_config(text, config, Vec::new()).unwrap()
+    }
+
+    /// Like `with_functions`, but returns the error if the runtime fails to load the assembly.
+    fn try_with_functions(
+        text: &str,
+        functions: Vec<FunctionInfoStorage>,
+    ) -> Result<Self, failure::Error> {
+        Self::try_with_config(text, Config::default(), functions)
+    }
+
+    fn try_with_config(
+        text: &str,
+        config: Config,
+        functions: Vec<FunctionInfoStorage>,
+    ) -> Result<Self, failure::Error> {
         let temp_dir = tempfile::TempDir::new().unwrap();
         let config = Config {
             out_dir: Some(temp_dir.path().to_path_buf()),
-            ..Config::default()
+            ..config
         };
         let input = PathOrInline::Inline {
             rel_path: RelativePathBuf::from("main.mun"),
             contents: text.to_owned(),
         };
         let (driver, file_id) = Driver::with_file(config, input).unwrap();
+        Self::try_with_driver(temp_dir, driver, file_id, functions)
+    }
+
+    /// Construct a new TestDriver from a package with a Mun source for every relative path.
+    fn with_files(files: &[(&str, &str)]) -> Self {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = Config {
+            out_dir: Some(temp_dir.path().to_path_buf()),
+            ..Config::default()
+        };
+        let files = files
+            .iter()
+            .map(|(rel_path, text)| (RelativePathBuf::from(*rel_path), text.to_string()))
+            .collect();
+        let (driver, file_id) = Driver::with_files(config, files).unwrap();
+        Self::try_with_driver(temp_dir, driver, file_id, Vec::new()).unwrap()
+    }
+
+    /// Compiles the package of `driver` into `temp_dir` and loads the assembly of `file_id`.
+    fn try_with_driver(
+        temp_dir: tempfile::TempDir,
+        driver: Driver,
+        file_id: FileId,
+        functions: Vec<FunctionInfoStorage>,
+    ) -> Result<Self, failure::Error> {
         let mut err_stream = mun_compiler::StandardStream::stderr(ColorChoice::Auto);
         if driver.emit_diagnostics(&mut err_stream).unwrap() {
             panic!("compiler errors..")
         }
         let out_path = driver.write_assembly(file_id).unwrap().unwrap();
-        let runtime = RuntimeBuilder::new(&out_path).spawn().unwrap();
-        TestDriver {
+        let mut builder = RuntimeBuilder::new(&out_path);
+        for function in functions {
+            builder.insert_fn(function);
+        }
+        let runtime = builder.spawn()?;
+        Ok(TestDriver {
             _temp_dir: temp_dir,
             driver,
             out_path,
             file_id,
             runtime,
-        }
+        })
     }
 
     /// Updates the text of the Mun source and ensures that the generated assembly has been reloaded.
@@ -168,6 +249,227 @@ fn booleans() {
     assert_invoke_eq!(bool, true, driver, "greater_equalf", 64f64, 64f64);
 }
 
+#[test]
+fn if_expressions() {
+    let mut driver = TestDriver::new(
+        r#"
+    fn sign(a:int):int {
+        if a > 0 { 1 } else if a < 0 { 0 - 1 } else { 0 }
+    }
+
+    fn clamp(a:int, min:int, max:int):int {
+        let b = if a < min {
+            min
+        } else {
+            if a > max { max } else { a }
+        };
+        b
+    }
+
+    fn checked_half(a:int):int {
+        let b = if a < 0 {
+            return 0 - 1;
+        } else {
+            a / 2
+        };
+        b
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(i64, 1, driver, "sign", 5i64);
+    assert_invoke_eq!(i64, -1, driver, "sign", -5i64);
+    assert_invoke_eq!(i64, 0, driver, "sign", 0i64);
+    assert_invoke_eq!(i64, 2, driver, "clamp", 1i64, 2i64, 4i64);
+    assert_invoke_eq!(i64, 3, driver, "clamp", 3i64, 2i64, 4i64);
+    assert_invoke_eq!(i64, 4, driver, "clamp", 5i64, 2i64, 4i64);
+    assert_invoke_eq!(i64, 4, driver, "checked_half", 8i64);
+    assert_invoke_eq!(i64, -1, driver, "checked_half", -8i64);
+}
+
+#[test]
+fn logic_operators() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct Counter {
+        count: int,
+    }
+
+    fn tick(mut c:Counter, result:bool):bool {
+        c.count += 1;
+        result
+    }
+
+    fn and(a:bool, b:bool):bool { a && b }
+    fn or(a:bool, b:bool):bool { a || b }
+    fn nested(a:bool, b:bool, c:bool):bool { a && (b || c) }
+
+    fn and_calls(a:bool, b:bool):int {
+        let c = Counter { count: 0 };
+        let _ = tick(c, a) && tick(c, b);
+        c.count
+    }
+
+    fn or_calls(a:bool, b:bool):int {
+        let c = Counter { count: 0 };
+        let _ = tick(c, a) || tick(c, b);
+        c.count
+    }
+
+    fn if_calls(a:bool):int {
+        let c = Counter { count: 0 };
+        if a || tick(c, false) { c.count + 10 } else { c.count }
+    }
+
+    fn while_calls(mut n:int):int {
+        let c = Counter { count: 0 };
+        while n > 0 && tick(c, true) {
+            n -= 1;
+        }
+        c.count
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(bool, true, driver, "and", true, true);
+    assert_invoke_eq!(bool, false, driver, "and", true, false);
+    assert_invoke_eq!(bool, false, driver, "and", false, true);
+    assert_invoke_eq!(bool, true, driver, "or", false, true);
+    assert_invoke_eq!(bool, true, driver, "or", true, false);
+    assert_invoke_eq!(bool, false, driver, "or", false, false);
+    assert_invoke_eq!(bool, true, driver, "nested", true, false, true);
+    assert_invoke_eq!(bool, false, driver, "nested", true, false, false);
+    assert_invoke_eq!(bool, false, driver, "nested", false, true, true);
+    assert_invoke_eq!(i64, 1, driver, "and_calls", false, true);
+    assert_invoke_eq!(i64, 2, driver, "and_calls", true, false);
+    assert_invoke_eq!(i64, 1, driver, "or_calls", true, false);
+    assert_invoke_eq!(i64, 2, driver, "or_calls", false, true);
+    assert_invoke_eq!(i64, 10, driver, "if_calls", true);
+    assert_invoke_eq!(i64, 1, driver, "if_calls", false);
+    assert_invoke_eq!(i64, 3, driver, "while_calls", 3i64);
+    assert_invoke_eq!(i64, 0, driver, "while_calls", 0i64);
+}
+
+#[test]
+fn typed_comparisons() {
+    let mut driver = TestDriver::new(
+        r#"
+        fn less_i32(a:i32, b:i32):bool              { a<b }
+        fn greater_u8(a:u8, b:u8):bool              { a>b }
+        fn less_eq_u64(a:u64, b:u64):bool           { a<=b }
+        fn greater_equal_i8(a:i8, b:i8):bool        { a>=b }
+        fn less_f32(a:f32, b:f32):bool              { a<b }
+        fn equal_bool(a:bool, b:bool):bool          { a==b }
+        fn not_equal_bool(a:bool, b:bool):bool      { a!=b }
+    "#,
+    );
+    assert_invoke_eq!(bool, true, driver, "less_i32", -1i32, 1i32);
+    assert_invoke_eq!(bool, false, driver, "less_i32", 1i32, -1i32);
+    assert_invoke_eq!(bool, true, driver, "greater_u8", 0xFFu8, 0x01u8);
+    assert_invoke_eq!(bool, false, driver, "greater_u8", 0x01u8, 0xFFu8);
+    assert_invoke_eq!(bool, false, driver, "less_eq_u64", u64::max_value(), 0u64);
+    assert_invoke_eq!(bool, true, driver, "less_eq_u64", 0u64, u64::max_value());
+    assert_invoke_eq!(bool, false, driver, "greater_equal_i8", -128i8, 127i8);
+    assert_invoke_eq!(bool, true, driver, "greater_equal_i8", -1i8, -1i8);
+    assert_invoke_eq!(bool, true, driver, "less_f32", -1.5f32, 1.0f32);
+    assert_invoke_eq!(bool, false, driver, "less_f32", 1.0f32, 1.0f32);
+    assert_invoke_eq!(bool, true, driver, "equal_bool", true, true);
+    assert_invoke_eq!(bool, false, driver, "equal_bool", true, false);
+    assert_invoke_eq!(bool, true, driver, "not_equal_bool", true, false);
+    assert_invoke_eq!(bool, false, driver, "not_equal_bool", false, false);
+}
+
+#[test]
+fn remainder() {
+    // Without division checks the remainder of a division by zero is the dividend
+    let mut driver = TestDriver::with_config(
+        r#"
+        fn rem(a:int, b:int):int                    { a%b }
+        fn rem_i8(a:i8, b:i8):i8                    { a%b }
+        fn rem_u8(a:u8, b:u8):u8                    { a%b }
+        fn remf(a:float, b:float):float             { a%b }
+        fn rem_assign(a:int, b:int):int             { a %= b; a }
+    "#,
+        Config {
+            division_checks: false,
+            ..Config::default()
+        },
+    );
+    assert_invoke_eq!(i64, 1, driver, "rem", 7i64, 3i64);
+    assert_invoke_eq!(i64, -1, driver, "rem", -7i64, 3i64);
+    assert_invoke_eq!(i64, 1, driver, "rem", 7i64, -3i64);
+    assert_invoke_eq!(i64, -1, driver, "rem", -7i64, -3i64);
+    assert_invoke_eq!(i64, 7, driver, "rem", 7i64, 0i64);
+    assert_invoke_eq!(i64, 0, driver, "rem", std::i64::MIN, -1i64);
+    assert_invoke_eq!(i8, 0, driver, "rem_i8", -128i8, -1i8);
+    assert_invoke_eq!(i8, -2, driver, "rem_i8", -128i8, 7i8);
+    assert_invoke_eq!(u8, 3, driver, "rem_u8", 255u8, 4u8);
+    assert_invoke_eq!(u8, 255, driver, "rem_u8", 255u8, 0u8);
+    assert_invoke_eq!(f64, -1.5, driver, "remf", -7.5f64, 2.0f64);
+    assert_invoke_eq!(f64, 1.5, driver, "remf", 7.5f64, -2.0f64);
+    assert_invoke_eq!(i64, 2, driver, "rem_assign", 11i64, 3i64);
+}
+
+#[test]
+fn unary_operators() {
+    let mut driver = TestDriver::new(
+        r#"
+        fn neg(a:int):int                           { -a }
+        fn neg_i8(a:i8):i8                          { -a }
+        fn negf(a:float):float                      { -a }
+        fn not(a:bool):bool                         { !a }
+        fn double_neg(a:int):int                    { --a }
+        fn min_i8():i8                              { -128 }
+        fn literal():int                            { -5 + 2 }
+    "#,
+    );
+    assert_invoke_eq!(i64, -3, driver, "neg", 3i64);
+    assert_invoke_eq!(i64, 3, driver, "neg", -3i64);
+    assert_invoke_eq!(i8, -127, driver, "neg_i8", 127i8);
+    assert_invoke_eq!(i8, -128, driver, "neg_i8", -128i8);
+    assert_invoke_eq!(f64, -1.5, driver, "negf", 1.5f64);
+    assert_invoke_eq!(bool, false, driver, "not", true);
+    assert_invoke_eq!(bool, true, driver, "not", false);
+    assert_invoke_eq!(i64, 7, driver, "double_neg", 7i64);
+    assert_invoke_eq!(i8, -128, driver, "min_i8");
+    assert_invoke_eq!(i64, -3, driver, "literal");
+}
+
+#[test]
+fn never_type() {
+    let mut driver = TestDriver::new(
+        r#"
+    fn forever():never {
+        loop {}
+    }
+
+    fn checked(a:int):int {
+        if a < 0 { forever() } else { a }
+    }
+
+    fn clamp_positive(a:int):int {
+        let b = if a > 0 { a } else { return 0; };
+        b * 2
+    }
+
+    fn isqrt(n:int):int {
+        let mut i = 0;
+        loop {
+            if (i + 1) * (i + 1) > n {
+                return i;
+            }
+            i += 1;
+        }
+    }
+    "#,
+    );
+    assert_invoke_eq!(i64, 5, driver, "checked", 5i64);
+    assert_invoke_eq!(i64, 6, driver, "clamp_positive", 3i64);
+    assert_invoke_eq!(i64, 0, driver, "clamp_positive", -3i64);
+    assert_invoke_eq!(i64, 4, driver, "isqrt", 24i64);
+    assert_invoke_eq!(i64, 5, driver, "isqrt", 25i64);
+}
+
 #[test]
 fn fibonacci() {
     let mut driver = TestDriver::new(
@@ -192,9 +494,9 @@ fn fibonacci_loop() {
     let mut driver = TestDriver::new(
         r#"
     fn fibonacci(n:int):int {
-        let a = 0;
-        let b = 1;
-        let i = 1;
+        let mut a = 0;
+        let mut b = 1;
+        let mut i = 1;
         loop {
             if i > n {
                 return a
@@ -219,9 +521,9 @@ fn fibonacci_loop_break() {
     let mut driver = TestDriver::new(
         r#"
     fn fibonacci(n:int):int {
-        let a = 0;
-        let b = 1;
-        let i = 1;
+        let mut a = 0;
+        let mut b = 1;
+        let mut i = 1;
         loop {
             if i > n {
                 break a;
@@ -246,9 +548,9 @@ fn fibonacci_while() {
     let mut driver = TestDriver::new(
         r#"
     fn fibonacci(n:int):int {
-        let a = 0;
-        let b = 1;
-        let i = 1;
+        let mut a = 0;
+        let mut b = 1;
+        let mut i = 1;
         while i <= n {
             let sum = a + b;
             a = b;
@@ -266,6 +568,281 @@ fn fibonacci_while() {
     assert_invoke_eq!(i64, 46368, driver, "fibonacci", 24i64);
 }
 
+#[test]
+fn loop_break_without_value() {
+    let mut driver = TestDriver::new(
+        r#"
+    fn count_to(n:int):int {
+        let mut i = 0;
+        loop {
+            i += 1;
+            if i >= n {
+                break;
+            }
+        }
+        i
+    }
+    "#,
+    );
+
+    // Code after the loop is reached when it is exited with a `break` without a value
+    assert_invoke_eq!(i64, 1, driver, "count_to", 0i64);
+    assert_invoke_eq!(i64, 5, driver, "count_to", 5i64);
+}
+
+#[test]
+fn continue_expr() {
+    let mut driver = TestDriver::new(
+        r#"
+    fn sum_skip(n:int, skip:int):int {
+        let mut sum = 0;
+        let mut i = 0;
+        while i < n {
+            i += 1;
+            if i == skip {
+                continue;
+            }
+            sum += i;
+        }
+        sum
+    }
+
+    fn first_square_above(n:int):int {
+        let mut i = 0;
+        loop {
+            i += 1;
+            if i * i <= n {
+                continue;
+            }
+            break i;
+        }
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(i64, 12, driver, "sum_skip", 5i64, 3i64);
+    assert_invoke_eq!(i64, 15, driver, "sum_skip", 5i64, 0i64);
+    assert_invoke_eq!(i64, 4, driver, "first_square_above", 10i64);
+    assert_invoke_eq!(i64, 1, driver, "first_square_above", 0i64);
+}
+
+#[test]
+fn for_range() {
+    let mut driver = TestDriver::new(
+        r#"
+    fn sum(n:int):int {
+        let mut total = 0;
+        for i in 0..n {
+            total += i;
+        }
+        total
+    }
+
+    fn sum_inclusive(n:int):int {
+        let mut total = 0;
+        for i in 0..=n {
+            total += i;
+        }
+        total
+    }
+
+    fn count(a:int, b:int):int {
+        let mut count = 0;
+        for _ in a..b {
+            count += 1;
+        }
+        count
+    }
+
+    fn count_inclusive(a:int, b:int):int {
+        let mut count = 0;
+        for _ in a..=b {
+            count += 1;
+        }
+        count
+    }
+
+    fn count_to_max():int {
+        let mut count = 0;
+        for _ in 250u8..=255u8 {
+            count += 1;
+        }
+        count
+    }
+
+    fn sum_skip(n:int, skip:int):int {
+        let mut total = 0;
+        for mut i in 0..n {
+            if i == skip {
+                continue;
+            }
+            i += 100; // Doesn't affect the iteration
+            total += i;
+        }
+        total
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(i64, 45, driver, "sum", 10i64);
+    assert_invoke_eq!(i64, 55, driver, "sum_inclusive", 10i64);
+    assert_invoke_eq!(i64, 10, driver, "count", 0i64, 10i64);
+    assert_invoke_eq!(i64, 11, driver, "count_inclusive", 0i64, 10i64);
+    assert_invoke_eq!(i64, 0, driver, "count", 5i64, 5i64);
+    assert_invoke_eq!(i64, 1, driver, "count_inclusive", 5i64, 5i64);
+    assert_invoke_eq!(i64, 0, driver, "count_inclusive", 5i64, 4i64);
+    assert_invoke_eq!(i64, 6, driver, "count_to_max");
+    assert_invoke_eq!(i64, 940, driver, "sum_skip", 10i64, 5i64);
+}
+
+#[test]
+fn mutable_bindings() {
+    let mut driver = TestDriver::new(
+        r#"
+    fn shadowing(a:int):float {
+        let x = a * 2;
+        let x = x as float;
+        x / 4.0
+    }
+
+    fn add_doubled(mut a:int, n:int):int {
+        let mut total = 0;
+        for mut i in 0..n {
+            i *= 2;
+            total += i;
+        }
+        a += total;
+        a
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(f64, 2.5, driver, "shadowing", 5i64);
+    assert_invoke_eq!(i64, 13, driver, "add_doubled", 1i64, 4i64);
+}
+
+#[test]
+fn type_aliases() {
+    let mut driver = TestDriver::new(
+        r#"
+    type Seconds = float;
+    type Frames = int;
+    type Duration = Seconds;
+
+    fn duration(frames: Frames, frame_time: Seconds): Duration {
+        frames as float * frame_time
+    }
+
+    fn frame_count(d: Duration, frame_time: Seconds): Frames {
+        (d / frame_time) as int
+    }
+    "#,
+    );
+
+    assert_invoke_eq!(f64, 1.5, driver, "duration", 3i64, 0.5f64);
+    assert_invoke_eq!(i64, 4, driver, "frame_count", 2.0f64, 0.5f64);
+}
+
+#[test]
+fn builtin_functions() {
+    let mut driver = TestDriver::new(
+        r#"
+    fn length(x: float, y: float): float {
+        sqrt(x * x + y * y)
+    }
+
+    fn sqrt_of(x: float): float { sqrt(x) }
+    fn abs_int(x: int): int { abs(x) }
+    fn abs_f32(x: f32): f32 { abs(x) }
+    fn sin_cos(x: float): float { sin(x) + cos(x) }
+    fn floor_of(x: float): float { floor(x) }
+    fn clamp(x: int, lo: int, hi: int): int { min(max(x, lo), hi) }
+    fn smallest(a: u8, b: u8): u8 { min(a, b) }
+    fn largest(a: float, b: float): float { max(a, b) }
+    "#,
+    );
+
+    assert_invoke_eq!(f64, 5.0, driver, "length", 3.0f64, 4.0f64);
+    let result: f64 = invoke_fn!(driver.runtime_mut(), "sqrt_of", -1.0f64).unwrap();
+    assert!(result.is_nan());
+    assert_invoke_eq!(i64, 5, driver, "abs_int", -5i64);
+    assert_invoke_eq!(i64, std::i64::MIN, driver, "abs_int", std::i64::MIN);
+    assert_invoke_eq!(f32, 2.5, driver, "abs_f32", -2.5f32);
+    assert_invoke_eq!(f64, 1.0, driver, "sin_cos", 0.0f64);
+    assert_invoke_eq!(f64, -2.0, driver, "floor_of", -1.5f64);
+    assert_invoke_eq!(i64, 10, driver, "clamp", 15i64, 0i64, 10i64);
+    assert_invoke_eq!(i64, 0, driver, "clamp", -3i64, 0i64, 10i64);
+    assert_invoke_eq!(u8, 100, driver, "smallest", 200u8, 100u8);
+    assert_invoke_eq!(f64, 2.5, driver, "largest", 1.5f64, 2.5f64);
+}
+
+#[test]
+fn wrapping_and_saturating_builtins() {
+    let text = r#"
+    fn wrapping_add_u8(a: u8, b: u8): u8 { wrapping_add(a, b) }
+    fn wrapping_sub_u8(a: u8, b: u8): u8 { wrapping_sub(a, b) }
+    fn wrapping_mul_i64(a: int, b: int): int { wrapping_mul(a, b) }
+    fn wrapping_add_i8(a: i8, b: i8): i8 { wrapping_add(a, b) }
+    fn saturating_add_u8(a: u8, b: u8): u8 { saturating_add(a, b) }
+    fn saturating_sub_u32(a: u32, b: u32): u32 { saturating_sub(a, b) }
+    fn saturating_add_i8(a: i8, b: i8): i8 { saturating_add(a, b) }
+    fn saturating_sub_i64(a: int, b: int): int { saturating_sub(a, b) }
+    "#;
+
+    // The results are the same with and without overflow checks
+    for overflow_checks in &[true, false] {
+        let mut driver = TestDriver::with_config(
+            text,
+            Config {
+                overflow_checks: *overflow_checks,
+                ..Config::default()
+            },
+        );
+
+        assert_invoke_eq!(u8, 0, driver, "wrapping_add_u8", 255u8, 1u8);
+        assert_invoke_eq!(u8, 255, driver, "wrapping_sub_u8", 0u8, 1u8);
+        assert_invoke_eq!(i64, -2, driver, "wrapping_mul_i64", std::i64::MAX, 2i64);
+        assert_invoke_eq!(i8, -128, driver, "wrapping_add_i8", 127i8, 1i8);
+        assert_invoke_eq!(i8, 3, driver, "wrapping_add_i8", 1i8, 2i8);
+
+        assert_invoke_eq!(u8, 255, driver, "saturating_add_u8", 200u8, 100u8);
+        assert_invoke_eq!(u8, 254, driver, "saturating_add_u8", 253u8, 1u8);
+        assert_invoke_eq!(u32, 0, driver, "saturating_sub_u32", 1u32, 2u32);
+        assert_invoke_eq!(u32, 1, driver, "saturating_sub_u32", 3u32, 2u32);
+        assert_invoke_eq!(i8, 127, driver, "saturating_add_i8", 100i8, 100i8);
+        assert_invoke_eq!(i8, -128, driver, "saturating_add_i8", -100i8, -100i8);
+        assert_invoke_eq!(i8, -1, driver, "saturating_add_i8", 127i8, -128i8);
+        assert_invoke_eq!(
+            i64,
+            std::i64::MIN,
+            driver,
+            "saturating_sub_i64",
+            std::i64::MIN,
+            1i64
+        );
+        assert_invoke_eq!(
+            i64,
+            std::i64::MAX,
+            driver,
+            "saturating_sub_i64",
+            0i64,
+            std::i64::MIN
+        );
+    }
+}
+
+#[test]
+fn builtin_functions_are_shadowed() {
+    let mut driver = TestDriver::new(
+        r#"
+    fn min(a: int, b: int): int { a + b }
+    fn sum(a: int, b: int): int { min(a, b) }
+    "#,
+    );
+
+    assert_invoke_eq!(i64, 5, driver, "sum", 2i64, 3i64);
+}
+
 #[test]
 fn true_is_true() {
     let mut driver = TestDriver::new(
@@ -298,3 +875,2176 @@ fn hotreloadable() {
     );
     assert_invoke_eq!(i64, 10, driver, "main");
 }
+
+#[test]
+fn host_functions() {
+    extern "C" fn add(a: f64, b: i64) -> f64 {
+        a + b as f64
+    }
+
+    let add_fn = FunctionInfoBuilder::new("add")
+        .params::<(f64, i64)>()
+        .returns::<f64>()
+        .fn_ptr(add as *const std::ffi::c_void)
+        .build();
+
+    let mut driver = TestDriver::with_functions(
+        r"
+    fn main():int { 5 }
+    ",
+        vec![add_fn],
+    );
+    assert_invoke_eq!(i64, 5, driver, "main");
+    assert_invoke_eq!(f64, 5.5, driver, "add", 1.5f64, 4i64);
+
+    // Host functions must survive hot reloading
+    driver.update(
+        r"
+    fn main():int { 10 }
+    ",
+    );
+    assert_invoke_eq!(i64, 10, driver, "main");
+    assert_invoke_eq!(f64, 7.5, driver, "add", 3.5f64, 4i64);
+}
+
+#[test]
+fn optimization_levels() {
+    let text = r"
+    struct Counter { value: int }
+
+    fn fibonacci(n:int):int {
+        let mut a = 0;
+        let mut b = 1;
+        let mut i = 1;
+        while i <= n {
+            let sum = a + b;
+            a = b;
+            b = sum;
+            i += 1;
+        }
+        a
+    }
+
+    fn count(n:int):int {
+        let mut counter = Counter { value: 0 };
+        for i in 0..n {
+            counter.value += i % 3;
+        }
+        counter.value
+    }
+    ";
+
+    for optimization_lvl in &[OptimizationLevel::None, OptimizationLevel::Default] {
+        let mut driver = TestDriver::with_optimization_lvl(text, *optimization_lvl);
+        assert_invoke_eq!(i64, 0, driver, "fibonacci", 0i64);
+        assert_invoke_eq!(i64, 55, driver, "fibonacci", 10i64);
+        assert_invoke_eq!(i64, 9, driver, "count", 10i64);
+        assert!(driver.runtime_mut().get_function_info("count").is_some());
+    }
+}
+
+#[test]
+fn overflow_checks() {
+    let text = r"
+    fn overflow():u8 { 255u8 + 1 }
+
+    fn multiply(a:int, b:int):int { a * b }
+    ";
+
+    let mut driver = TestDriver::with_config(
+        text,
+        Config {
+            overflow_checks: true,
+            ..Config::default()
+        },
+    );
+    let result: Result<u8, _> = invoke_fn!(driver.runtime_mut(), "overflow");
+    let message = result.unwrap_err().to_string();
+    assert!(message.contains("trapped"), "unexpected error: {}", message);
+    let result: Result<i64, _> = invoke_fn!(driver.runtime_mut(), "multiply", std::i64::MAX, 2i64);
+    assert!(result.is_err());
+
+    // A trap does not affect subsequent invocations
+    assert_invoke_eq!(i64, 6, driver, "multiply", 2i64, 3i64);
+
+    // Without overflow checks arithmetic wraps around
+    let mut driver = TestDriver::new(text);
+    assert_invoke_eq!(u8, 0, driver, "overflow");
+    assert_invoke_eq!(i64, -2, driver, "multiply", std::i64::MAX, 2i64);
+}
+
+#[test]
+fn division_checks() {
+    let text = r"
+    fn divide(a:int, b:int):int { a / b }
+    fn remainder(a:u8, b:u8):u8 { a % b }
+    fn divide_float(a:float, b:float):float { a / b }
+    ";
+
+    let mut driver = TestDriver::new(text);
+    let result: Result<i64, _> = invoke_fn!(driver.runtime_mut(), "divide", 7i64, 0i64);
+    let message = result.unwrap_err().to_string();
+    assert!(message.contains("trapped"), "unexpected error: {}", message);
+    let result: Result<u8, _> = invoke_fn!(driver.runtime_mut(), "remainder", 7u8, 0u8);
+    let message = result.unwrap_err().to_string();
+    assert!(message.contains("trapped"), "unexpected error: {}", message);
+
+    // Dividing the smallest value by -1 overflows
+    let result: Result<i64, _> = invoke_fn!(driver.runtime_mut(), "divide", std::i64::MIN, -1i64);
+    let trap = result.unwrap_err().trap().cloned().unwrap();
+    assert_eq!(trap.kind, TrapKind::Overflow);
+
+    // A trap does not affect subsequent invocations
+    assert_invoke_eq!(i64, -3, driver, "divide", -7i64, 2i64);
+    assert_invoke_eq!(u8, 1, driver, "remainder", 7u8, 3u8);
+
+    // Float division follows IEEE 754
+    assert_invoke_eq!(
+        f64,
+        std::f64::INFINITY,
+        driver,
+        "divide_float",
+        1.0f64,
+        0.0f64
+    );
+    let result: Result<f64, _> = invoke_fn!(driver.runtime_mut(), "divide_float", 0.0f64, 0.0f64);
+    assert!(result.unwrap().is_nan());
+
+    // Without division checks the quotient is still defined
+    let mut driver = TestDriver::with_config(
+        text,
+        Config {
+            division_checks: false,
+            ..Config::default()
+        },
+    );
+    assert_invoke_eq!(i64, 7, driver, "divide", 7i64, 0i64);
+    assert_invoke_eq!(i64, std::i64::MIN, driver, "divide", std::i64::MIN, -1i64);
+    assert_invoke_eq!(i64, -3, driver, "divide", -7i64, 2i64);
+}
+
+#[test]
+fn bounds_checks() {
+    let text = r"
+    fn get(i:int):int {
+        let arr = [1, 2, 3];
+        arr[i]
+    }
+    fn set(i:u8, value:int):int {
+        let mut arr = [1, 2, 3];
+        arr[i] = value;
+        arr[0] + arr[1] + arr[2]
+    }
+    ";
+
+    let mut driver = TestDriver::with_config(
+        text,
+        Config {
+            bounds_checks: true,
+            ..Config::default()
+        },
+    );
+    let result: Result<i64, _> = invoke_fn!(driver.runtime_mut(), "get", 3i64);
+    let message = result.unwrap_err().to_string();
+    assert!(message.contains("trapped"), "unexpected error: {}", message);
+    let result: Result<i64, _> = invoke_fn!(driver.runtime_mut(), "get", -1i64);
+    assert!(result.is_err());
+    let result: Result<i64, _> = invoke_fn!(driver.runtime_mut(), "set", 3u8, 10i64);
+    let message = result.unwrap_err().to_string();
+    assert!(message.contains("trapped"), "unexpected error: {}", message);
+
+    // A trap does not affect subsequent invocations
+    assert_invoke_eq!(i64, 3, driver, "get", 2i64);
+    assert_invoke_eq!(i64, 13, driver, "set", 1u8, 10i64);
+
+    // In-bounds accesses behave the same without bounds checks
+    let mut driver = TestDriver::new(text);
+    assert_invoke_eq!(i64, 1, driver, "get", 0i64);
+    assert_invoke_eq!(i64, 3, driver, "get", 2i64);
+    assert_invoke_eq!(i64, 13, driver, "set", 1u8, 10i64);
+}
+
+#[test]
+fn bounds_checked_writes() {
+    thread_local! {
+        static OBSERVED: RefCell<Vec<i64>> = RefCell::new(Vec::new());
+    }
+
+    extern "C" fn observe(value: i64) {
+        OBSERVED.with(|observed| observed.borrow_mut().push(value));
+    }
+
+    let observe_fn = FunctionInfoBuilder::new("observe")
+        .params::<(i64,)>()
+        .fn_ptr(observe as *const std::ffi::c_void)
+        .build();
+    let mut driver = TestDriver::try_with_config(
+        r"
+    extern fn observe(value:int);
+
+    struct(value) Slots {
+        values: [int; 3],
+        guard: int,
+    }
+
+    fn write(i:u8) {
+        let mut slots = Slots { values: [1, 2, 3], guard: 42 };
+        slots.values[i] = 7;
+        observe(slots.values[2]);
+        observe(slots.guard);
+    }
+    ",
+        Config {
+            bounds_checks: true,
+            ..Config::default()
+        },
+        vec![observe_fn],
+    )
+    .unwrap();
+
+    // A write one past the end traps once
+    let result: Result<(), _> = invoke_fn!(driver.runtime_mut(), "write", 3u8);
+    let trap = result.unwrap_err().trap().cloned().unwrap();
+    assert_eq!((trap.line, trap.kind), (11, TrapKind::IndexOutOfBounds));
+
+    // After the trap the write doesn't store to the memory that follows the array
+    OBSERVED.with(|observed| assert_eq!(*observed.borrow(), vec![3, 42]));
+}
+
+#[test]
+fn assertions() {
+    let text = r"
+    fn check_hp(hp: int) {
+        assert(hp >= 0);
+    }
+    fn check_eq(a: int, b: int) {
+        assert_eq(a, b * 2);
+    }
+    ";
+
+    let mut driver = TestDriver::new(text);
+    let result: Result<(), _> = invoke_fn!(driver.runtime_mut(), "check_hp", -1i64);
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Script trapped while invoking 'check_hp': assertion failed in 'check_hp' at main.mun:3: hp >= 0"
+    );
+    let result: Result<(), _> = invoke_fn!(driver.runtime_mut(), "check_eq", 1i64, 1i64);
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Script trapped while invoking 'check_eq': assertion failed in 'check_eq' at main.mun:6: a == b * 2"
+    );
+
+    // Assertions that hold don't trap, and a failed assertion doesn't affect later invocations
+    let result: Result<(), _> = invoke_fn!(driver.runtime_mut(), "check_hp", 0i64);
+    assert!(result.is_ok());
+    let result: Result<(), _> = invoke_fn!(driver.runtime_mut(), "check_eq", 4i64, 2i64);
+    assert!(result.is_ok());
+
+    // Without assertions they are compiled out
+    let mut driver = TestDriver::with_config(
+        text,
+        Config {
+            assertions: false,
+            ..Config::default()
+        },
+    );
+    let result: Result<(), _> = invoke_fn!(driver.runtime_mut(), "check_hp", -1i64);
+    assert!(result.is_ok());
+}
+
+#[test]
+fn trap_locations() {
+    let text = r"
+    fn add(a: u8, b: u8): u8 {
+        a + b
+    }
+    fn divide(a: int, b: int): int {
+        a / b
+    }
+    fn get(i: int): int {
+        let arr = [1, 2, 3];
+        arr[i]
+    }
+    fn check(hp: int) {
+        assert(hp >= 0);
+    }
+    fn double(a: u8): u8 {
+        add(a, a)
+    }
+    ";
+    let trap = |function: &str, line: u32, kind: TrapKind| ScriptTrap {
+        function: function.to_string(),
+        file: "main.mun".to_string(),
+        line,
+        kind,
+        message: None,
+    };
+
+    let mut driver = TestDriver::with_config(
+        text,
+        Config {
+            overflow_checks: true,
+            bounds_checks: true,
+            ..Config::default()
+        },
+    );
+    let result: Result<u8, _> = invoke_fn!(driver.runtime_mut(), "add", 200u8, 100u8);
+    assert_eq!(
+        result.unwrap_err().trap(),
+        Some(&trap("add", 3, TrapKind::Overflow))
+    );
+    let result: Result<i64, _> = invoke_fn!(driver.runtime_mut(), "divide", 1i64, 0i64);
+    assert_eq!(
+        result.unwrap_err().trap(),
+        Some(&trap("divide", 6, TrapKind::DivisionByZero))
+    );
+    let result: Result<i64, _> = invoke_fn!(driver.runtime_mut(), "get", 3i64);
+    assert_eq!(
+        result.unwrap_err().trap(),
+        Some(&trap("get", 10, TrapKind::IndexOutOfBounds))
+    );
+    let result: Result<(), _> = invoke_fn!(driver.runtime_mut(), "check", -1i64);
+    assert_eq!(
+        result.unwrap_err().trap(),
+        Some(&ScriptTrap {
+            message: Some("hp >= 0".to_string()),
+            ..trap("check", 13, TrapKind::AssertionFailed)
+        })
+    );
+
+    // The location is that of the function that trapped, rather than the one that was invoked
+    let result: Result<u8, _> = invoke_fn!(driver.runtime_mut(), "double", 200u8);
+    let err = result.unwrap_err();
+    assert_eq!(err.trap(), Some(&trap("add", 3, TrapKind::Overflow)));
+    assert_eq!(
+        err.to_string(),
+        "Script trapped while invoking 'double': integer overflow in 'add' at main.mun:3"
+    );
+
+    // Errors other than traps have no trap
+    let result: Result<i64, _> = invoke_fn!(driver.runtime_mut(), "missing");
+    assert_eq!(result.unwrap_err().trap(), None);
+}
+
+#[test]
+fn trap_locations_in_modules() {
+    let mut driver = TestDriver::with_files(&[
+        (
+            "main.mun",
+            "export fn main(hp: int): int { enemies::damage(hp, 10) }",
+        ),
+        (
+            "enemies.mun",
+            "export fn damage(hp: int, amount: int): int {\n    assert(hp > amount);\n    hp / (hp - amount)\n}",
+        ),
+    ]);
+    let result: Result<i64, _> = invoke_fn!(driver.runtime_mut(), "main", 5i64);
+    assert_eq!(
+        result.unwrap_err().trap(),
+        Some(&ScriptTrap {
+            function: "enemies::damage".to_string(),
+            file: "enemies.mun".to_string(),
+            line: 2,
+            kind: TrapKind::AssertionFailed,
+            message: Some("hp > amount".to_string()),
+        })
+    );
+
+    // After the failed assertion execution continues, so only the first trap is reported
+    let result: Result<i64, _> = invoke_fn!(driver.runtime_mut(), "main", 10i64);
+    let trap = result.unwrap_err().trap().cloned().unwrap();
+    assert_eq!((trap.line, trap.kind), (2, TrapKind::AssertionFailed));
+}
+
+#[test]
+fn float_semantics() {
+    let text = r"
+    fn equal(a: float, b: float): bool { a == b }
+    fn not_equal(a: float, b: float): bool { a != b }
+    fn less(a: float, b: float): bool { a < b }
+    fn less_equal(a: float, b: float): bool { a <= b }
+    fn greater(a: f32, b: f32): bool { a > b }
+    fn greater_equal(a: f32, b: f32): bool { a >= b }
+    fn add(a: float, b: float): float { a + b }
+    ";
+
+    // The semantics are recorded in the assembly
+    let float_semantics = |driver: &mut TestDriver| {
+        driver
+            .runtime_mut()
+            .assemblies()
+            .next()
+            .unwrap()
+            .info()
+            .float_semantics
+    };
+
+    // In strict mode comparisons with NaN are false, except `!=`
+    let mut driver = TestDriver::new(text);
+    let nan = std::f64::NAN;
+    let nan32 = std::f32::NAN;
+    assert_invoke_eq!(bool, false, driver, "equal", nan, nan);
+    assert_invoke_eq!(bool, false, driver, "equal", nan, 1.0f64);
+    assert_invoke_eq!(bool, true, driver, "not_equal", nan, nan);
+    assert_invoke_eq!(bool, true, driver, "not_equal", 1.0f64, nan);
+    assert_invoke_eq!(bool, false, driver, "not_equal", 1.0f64, 1.0f64);
+    assert_invoke_eq!(bool, false, driver, "less", nan, 1.0f64);
+    assert_invoke_eq!(bool, false, driver, "less_equal", 1.0f64, nan);
+    assert_invoke_eq!(bool, false, driver, "greater", nan32, 1.0f32);
+    assert_invoke_eq!(bool, false, driver, "greater_equal", nan32, nan32);
+    let strict = mun_abi::FloatSemantics::Strict;
+    assert_eq!(float_semantics(&mut driver), strict);
+
+    let mut driver = TestDriver::with_config(
+        text,
+        Config {
+            float_semantics: FloatSemantics::Fast,
+            ..Config::default()
+        },
+    );
+    assert_invoke_eq!(f64, 4.0, driver, "add", 1.5f64, 2.5f64);
+    let fast = mun_abi::FloatSemantics::Fast;
+    assert_eq!(float_semantics(&mut driver), fast);
+}
+
+#[test]
+fn mutual_recursion() {
+    let mut driver = TestDriver::new(
+        r"
+    fn is_even(n:int):bool {
+        if n == 0 { true } else { is_odd(n - 1) }
+    }
+
+    fn is_odd(n:int):bool {
+        if n == 0 { false } else { is_even(n - 1) }
+    }
+    ",
+    );
+    assert_invoke_eq!(bool, true, driver, "is_even", 10i64);
+    assert_invoke_eq!(bool, true, driver, "is_odd", 7i64);
+    assert_invoke_eq!(bool, false, driver, "is_odd", 4i64);
+
+    // Both functions must pick up the reloaded definition of `is_even`
+    driver.update(
+        r"
+    fn is_even(n:int):bool {
+        if n <= 0 { n == -2 } else { is_odd(n - 1) }
+    }
+
+    fn is_odd(n:int):bool {
+        if n == 0 { false } else { is_even(n - 1) }
+    }
+    ",
+    );
+    assert_invoke_eq!(bool, false, driver, "is_even", 10i64);
+    assert_invoke_eq!(bool, false, driver, "is_odd", 7i64);
+    assert_invoke_eq!(bool, true, driver, "is_even", -2i64);
+}
+
+#[test]
+fn extern_functions() {
+    thread_local! {
+        static LOGGED: RefCell<Vec<f64>> = RefCell::new(Vec::new());
+    }
+
+    extern "C" fn log_value(v: f64) {
+        LOGGED.with(|logged| logged.borrow_mut().push(v));
+    }
+
+    extern "C" fn add(a: i64, b: i64) -> i64 {
+        a + b
+    }
+
+    let log_value_fn = FunctionInfoBuilder::new("log_value")
+        .params::<(f64,)>()
+        .fn_ptr(log_value as *const std::ffi::c_void)
+        .build();
+    let add_fn = FunctionInfoBuilder::new("add")
+        .params::<(i64, i64)>()
+        .returns::<i64>()
+        .fn_ptr(add as *const std::ffi::c_void)
+        .build();
+
+    let mut driver = TestDriver::with_functions(
+        r"
+    extern fn log_value(v:float);
+    extern fn add(a:int, b:int):int;
+
+    fn main(a:int):int {
+        log_value(1.5);
+        let b = add(a, 3);
+        log_value(2.0 * 1.5);
+        b
+    }
+    ",
+        vec![log_value_fn, add_fn],
+    );
+    assert_invoke_eq!(i64, 7, driver, "main", 4i64);
+    LOGGED.with(|logged| assert_eq!(*logged.borrow(), vec![1.5, 3.0]));
+}
+
+#[test]
+fn extern_function_missing() {
+    let err = TestDriver::try_with_functions(
+        r"
+    extern fn log_value(v:float);
+
+    fn main() {
+        log_value(1.5)
+    }
+    ",
+        Vec::new(),
+    )
+    .err()
+    .expect("linking should fail without host function");
+    assert_eq!(
+        err.to_string(),
+        "Failed to link: function 'log_value' is missing."
+    );
+}
+
+#[test]
+fn foreign_target() {
+    let (foreign_triple, extension) = if cfg!(target_os = "windows") {
+        ("x86_64-unknown-linux-gnu", "so")
+    } else {
+        ("x86_64-pc-windows-msvc", "dll")
+    };
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let config = Config {
+        target: Target::search(foreign_triple).unwrap(),
+        out_dir: Some(temp_dir.path().to_path_buf()),
+        ..Config::default()
+    };
+    let input = PathOrInline::Inline {
+        rel_path: RelativePathBuf::from("main.mun"),
+        contents: "fn main():int { 5 }".to_owned(),
+    };
+    let (driver, file_id) = Driver::with_file(config, input).unwrap();
+    let out_path = driver.write_assembly(file_id).unwrap().unwrap();
+    assert_eq!(out_path.extension().unwrap(), extension);
+
+    // The runtime must refuse the library before trying to load it
+    let err = RuntimeBuilder::new(&out_path)
+        .spawn()
+        .err()
+        .expect("a library for a foreign target should not load");
+    let message = err.to_string();
+    assert!(
+        message.contains("Recompile the library for the host target"),
+        "unexpected error: {}",
+        message
+    );
+}
+
+#[test]
+fn foreign_target_triple() {
+    use crate::assembly::library_format::check_target;
+
+    let driver = TestDriver::new("fn main():int { 5 }");
+    let assembly = driver.runtime.assemblies().next().unwrap();
+    let host_triple = assembly.info().target().to_owned();
+    assert_eq!(
+        host_triple,
+        Target::search(mun_compiler::host_triple())
+            .unwrap()
+            .llvm_target
+    );
+    assert!(check_target(&driver.out_path, &host_triple).is_ok());
+
+    // Libraries that share the object file format of the host are told apart by their triple
+    let foreign_arch = if cfg!(target_arch = "x86_64") {
+        "aarch64"
+    } else {
+        "x86_64"
+    };
+    let foreign_triple =
+        host_triple.replacen(host_triple.split('-').next().unwrap(), foreign_arch, 1);
+    let message = check_target(&driver.out_path, &foreign_triple)
+        .err()
+        .expect("a library for a foreign architecture should be rejected")
+        .to_string();
+    assert!(
+        message.contains(&foreign_triple)
+            && message.contains("Recompile the library for the host target"),
+        "unexpected error: {}",
+        message
+    );
+}
+
+#[test]
+fn documentation() {
+    let mut driver = TestDriver::new(
+        r"
+    /// Deals damage to the target.
+    ///
+    /// Returns the remaining health.
+    fn main():int { 5 }
+
+    // Not a doc comment
+    fn undocumented() {}
+    ",
+    );
+
+    let runtime = driver.runtime_mut();
+    assert_eq!(
+        runtime.get_function_info("main").unwrap().docs(),
+        "Deals damage to the target.\n\nReturns the remaining health."
+    );
+    assert_eq!(
+        runtime.get_function_info("undocumented").unwrap().docs(),
+        ""
+    );
+}
+
+#[test]
+fn argument_names() {
+    let mut driver = TestDriver::new(
+        r"
+    fn attack(target:int, damage:float):float { damage }
+    ",
+    );
+
+    let signature = &driver
+        .runtime_mut()
+        .get_function_info("attack")
+        .unwrap()
+        .signature;
+    assert_eq!(signature.arg_name(0), Some("target"));
+    assert_eq!(signature.arg_name(1), Some("damage"));
+    assert_eq!(signature.arg_name(2), None);
+    assert_eq!(
+        signature.to_string(),
+        "fn attack(target: @core::int, damage: @core::float): @core::float"
+    );
+
+    let result: Result<f64, _> = invoke_fn!(driver.runtime_mut(), "attack", 1i64, 2i64);
+    let message = format!("{}", result.unwrap_err());
+    assert!(message.contains("('damage')"), "{}", message);
+}
+
+#[test]
+fn type_layouts() {
+    let mut driver = TestDriver::new(
+        r"
+    fn layout(a:int, b:bool):float { 1.0 }
+    ",
+    );
+
+    let signature = &driver
+        .runtime_mut()
+        .get_function_info("layout")
+        .unwrap()
+        .signature;
+    let arg_types = signature.arg_types();
+    assert_eq!(arg_types[0].size_in_bits, 64);
+    assert_eq!(arg_types[0].alignment, 8);
+    assert_eq!(arg_types[1].size_in_bits, 8);
+    assert_eq!(arg_types[1].alignment, 1);
+
+    let return_type = signature.return_type().unwrap();
+    assert_eq!(return_type.size_in_bits, 64);
+    assert_eq!(return_type.alignment, 8);
+}
+
+#[test]
+fn same_function_name_in_two_modules() {
+    extern "C" fn foo_a() -> i64 {
+        1
+    }
+    extern "C" fn foo_b() -> i64 {
+        2
+    }
+
+    let foo_a_fn = FunctionInfoBuilder::new("foo")
+        .returns::<i64>()
+        .fn_ptr(foo_a as *const c_void)
+        .build();
+    let foo_b_fn = FunctionInfoBuilder::new("foo")
+        .returns::<i64>()
+        .fn_ptr(foo_b as *const c_void)
+        .build();
+
+    // The modules `a` and `b` both define a function called `foo`
+    let a_functions = [foo_a_fn.info().clone()];
+    let b_functions = [foo_b_fn.info().clone()];
+    let a_path = CString::new("a").unwrap();
+    let b_path = CString::new("b").unwrap();
+    let root_path = CString::new("").unwrap();
+    let modules = [
+        ModuleInfo {
+            path: a_path.as_ptr(),
+            functions: a_functions.as_ptr(),
+            modules: ptr::null(),
+            globals: ptr::null(),
+            num_functions: a_functions.len() as u32,
+            num_modules: 0,
+            num_globals: 0,
+        },
+        ModuleInfo {
+            path: b_path.as_ptr(),
+            functions: b_functions.as_ptr(),
+            modules: ptr::null(),
+            globals: ptr::null(),
+            num_functions: b_functions.len() as u32,
+            num_modules: 0,
+            num_globals: 0,
+        },
+    ];
+    let root_module = ModuleInfo {
+        path: root_path.as_ptr(),
+        functions: ptr::null(),
+        modules: modules.as_ptr(),
+        globals: ptr::null(),
+        num_functions: 0,
+        num_modules: modules.len() as u32,
+        num_globals: 0,
+    };
+
+    let mut dispatch_table = DispatchTable::default();
+    register_functions(&root_module, &mut dispatch_table);
+    assert!(dispatch_table.get("foo").is_none());
+    assert_eq!(
+        dispatch_table.get("a::foo").unwrap().fn_ptr,
+        foo_a as *const c_void
+    );
+    assert_eq!(
+        dispatch_table.get("b::foo").unwrap().fn_ptr,
+        foo_b as *const c_void
+    );
+}
+
+#[test]
+fn module_tree() {
+    let mut driver = TestDriver::new(
+        r"
+    fn foo():int { 1 }
+    fn bar():int { 2 }
+    ",
+    );
+
+    let runtime = driver.runtime_mut();
+    let assembly = runtime.assemblies().next().unwrap();
+    let root_module = assembly.root_module();
+    assert_eq!(root_module.path(), "");
+    assert!(root_module.modules().is_empty());
+
+    let mut paths: Vec<String> = root_module
+        .qualified_functions()
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect();
+    paths.sort();
+    assert_eq!(paths, vec!["bar".to_string(), "foo".to_string()]);
+    for path in paths {
+        assert!(runtime.get_function_info(&path).is_some());
+    }
+}
+
+#[test]
+fn metadata_matches_compiler_metadata() {
+    let mut driver = TestDriver::with_config(
+        r"
+    static mut SCORE: int = 10;
+    fn add(a: int, _: int): int { a }
+    export fn main(): int { add(SCORE, 1) }
+    ",
+        Config {
+            emit: vec![Artifact::Lib, Artifact::Metadata],
+            ..Config::default()
+        },
+    );
+
+    let json = std::fs::read_to_string(driver.out_path.with_extension("mun.json")).unwrap();
+    let mut compiled = AssemblyMetadata::from_json(&json).unwrap();
+    assert!(compiled.source_hash.is_some());
+    let runtime = driver.runtime_mut();
+    let loaded = runtime.assemblies().next().unwrap().metadata();
+    assert_eq!(loaded.root.functions[0].params[1].name, None);
+    assert_eq!(loaded.root.globals[0].ty, "@core::int");
+
+    // The information of a loaded assembly doesn't contain the hash of its sources
+    compiled.source_hash = None;
+    assert_eq!(compiled, loaded);
+}
+
+#[test]
+fn package_modules() {
+    let mut driver = TestDriver::with_files(&[
+        (
+            "main.mun",
+            "export fn main(a: int): int { math::add(a, 2) + shapes::square::area(a) }",
+        ),
+        ("math.mun", "export fn add(a: int, b: int): int { a + b }"),
+        (
+            "shapes/square.mun",
+            "export fn area(side: int): int { side * side }",
+        ),
+    ]);
+    assert_invoke_eq!(i64, 5 + 9, driver, "main", 3i64);
+    assert_invoke_eq!(i64, 7, driver, "math::add", 3i64, 4i64);
+
+    // The modules of the package are child modules of the root module, a directory without a
+    // module of its own is an empty module
+    let runtime = driver.runtime_mut();
+    let root_module = runtime.assemblies().next().unwrap().root_module();
+    let paths: Vec<&str> = root_module.modules().iter().map(|m| m.path()).collect();
+    assert_eq!(paths, vec!["math", "shapes"]);
+    let shapes = &root_module.modules()[1];
+    assert!(shapes.functions().is_empty());
+    assert_eq!(shapes.modules()[0].path(), "shapes::square");
+}
+
+#[test]
+fn manifest_dependencies() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let write = |rel_path: &str, text: &str| {
+        let path = temp_dir.path().join(rel_path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, text).unwrap();
+    };
+    write(
+        "game/mun.toml",
+        "[package]\nname = \"game\"\n\n[dependencies]\nphysics = { path = \"../physics\" }\n",
+    );
+    write("game/src/main.mun", "export fn main(): int { 1 }");
+    write("physics/mun.toml", "[package]\nname = \"physics\"\n");
+    write("physics/src/main.mun", "export fn gravity(): int { -10 }");
+
+    // The dependency is built into the target directory of the package that depends on it
+    let manifest = Manifest::from_dir(&temp_dir.path().join("game")).unwrap();
+    let result = mun_compiler::build_package(&manifest, Config::default()).unwrap();
+    let assembly_path = result.library_path().unwrap().to_path_buf();
+    let target_dir = temp_dir.path().join("game").join("target");
+    let file_name = assembly_path.file_name().unwrap();
+    assert_eq!(assembly_path, target_dir.join("game").join(file_name));
+    assert!(target_dir.join("physics").join(file_name).is_file());
+
+    // Loading the package also loads its dependency
+    let mut runtime = RuntimeBuilder::new(&assembly_path).spawn().unwrap();
+    assert_eq!(runtime.assemblies().count(), 2);
+    let result: i64 = invoke_fn!(runtime, "main").unwrap();
+    assert_eq!(result, 1);
+    let result: i64 = invoke_fn!(runtime, "gravity").unwrap();
+    assert_eq!(result, -10);
+}
+
+#[test]
+fn private_functions_of_modules_are_not_retrievable() {
+    let mut driver = TestDriver::with_files(&[
+        ("main.mun", "fn main(a: int): int { math::add(a, 2) }"),
+        (
+            "math.mun",
+            "export fn add(a: int, b: int): int { helper(a) + b }\nfn helper(a: int): int { a * 2 }",
+        ),
+    ]);
+
+    // Every function of the root module is retrievable, but only the exported functions of other
+    // modules
+    assert_invoke_eq!(i64, 8, driver, "main", 3i64);
+    assert_invoke_eq!(i64, 10, driver, "math::add", 3i64, 4i64);
+    let runtime = driver.runtime_mut();
+    assert!(runtime.get_function_info("math::helper").is_none());
+    let add = runtime.get_function_info("math::add").unwrap();
+    assert_eq!(add.signature.privacy(), Privacy::Public);
+}
+
+#[test]
+fn hotreload_relinks_dispatch_table() {
+    let mut driver = TestDriver::new(
+        r"
+    fn add(a:int, b:int):int { a+b }
+    fn main():int { add(2, 3) }
+    ",
+    );
+    assert_invoke_eq!(i64, 5, driver, "main");
+
+    // The dispatch table layout is unchanged, so its slots are relinked by index
+    driver.update(
+        r"
+    fn add(a:int, b:int):int { a*b }
+    fn main():int { add(2, 3) }
+    ",
+    );
+    assert_invoke_eq!(i64, 6, driver, "main");
+
+    // The dispatch table layout changed, so its slots are relinked by name
+    driver.update(
+        r"
+    fn sub(a:int, b:int):int { a-b }
+    fn add(a:int, b:int):int { a+b }
+    fn main():int { sub(add(2, 3), 1) }
+    ",
+    );
+    assert_invoke_eq!(i64, 4, driver, "main");
+}
+
+#[test]
+fn reference_arguments() {
+    extern "C" fn increment(value: *mut i64) {
+        unsafe { *value += 1 };
+    }
+
+    let increment_fn = FunctionInfoBuilder::new("increment")
+        .params::<(&mut i64,)>()
+        .fn_ptr(increment as *const std::ffi::c_void)
+        .build();
+
+    let mut driver = TestDriver::with_functions(
+        r"
+    fn main() {}
+    ",
+        vec![increment_fn],
+    );
+
+    // Mutations through a `&mut` argument are visible to the caller
+    let mut value = 1i64;
+    {
+        let result: Result<(), _> = invoke_fn!(driver.runtime_mut(), "increment", &mut value);
+        assert!(result.is_ok());
+    }
+    assert_eq!(value, 2);
+
+    // A shared reference must not be passed where a mutable one is expected
+    let result: Result<(), _> = invoke_fn!(driver.runtime_mut(), "increment", &value);
+    let message = format!("{}", result.unwrap_err());
+    assert!(
+        message.contains("Expected: *const @core::int. Found: *mut @core::int."),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn sized_integers() {
+    let mut driver = TestDriver::new(
+        r"
+    fn add_u8(a:u8, b:u8):u8 { a + b }
+    fn mul_i32(a:i32, b:i32):i32 { a * b }
+    fn div_u32(a:u32, b:u32):u32 { a / b }
+    fn less_u16(a:u16, b:u16):bool { a < b }
+    fn increment(a:i8):i8 { a + 1 }
+    ",
+    );
+    assert_invoke_eq!(u8, 255, driver, "add_u8", 200u8, 55u8);
+    // Integer arithmetic wraps around on overflow
+    assert_invoke_eq!(u8, 4, driver, "add_u8", 200u8, 60u8);
+    assert_invoke_eq!(i32, -42, driver, "mul_i32", 6i32, -7i32);
+    assert_invoke_eq!(
+        u32,
+        2_147_483_647,
+        driver,
+        "div_u32",
+        4_294_967_294u32,
+        2u32
+    );
+    assert_invoke_eq!(bool, false, driver, "less_u16", 65_535u16, 1u16);
+    assert_invoke_eq!(i8, -128, driver, "increment", 127i8);
+
+    // Sized integers are distinct types
+    let result: Result<u8, _> = invoke_fn!(driver.runtime_mut(), "add_u8", 1i64, 2u8);
+    assert!(result.is_err());
+}
+
+#[test]
+fn f32_arithmetic() {
+    let mut driver = TestDriver::new(
+        r"
+    fn add_f32(a:f32, b:f32):f32 { a + b }
+    fn half(a:f32):f32 { a / 2.0 }
+    fn less_f32(a:f32, b:f32):bool { a < b }
+    ",
+    );
+    assert_invoke_eq!(f32, 3.5, driver, "add_f32", 1.25f32, 2.25f32);
+    assert_invoke_eq!(f32, 0.75, driver, "half", 1.5f32);
+    assert_invoke_eq!(bool, true, driver, "less_f32", 1.0f32, 2.0f32);
+
+    // `f32` and `float` are distinct types
+    let result: Result<f32, _> = invoke_fn!(driver.runtime_mut(), "add_f32", 1.0f64, 2.0f32);
+    assert!(result.is_err());
+}
+
+#[test]
+fn typed_literals() {
+    let mut driver = TestDriver::new(
+        r"
+    fn add_u8(a:u8):u8 { a + 250 }
+    fn max_u64():u64 { 18446744073709551615 }
+    fn suffixed():f32 { let a = 1.5f32; a * 2f32 }
+    fn defaulted():int { let a = 3; a * 2 }
+    fn widths(a:u8, b:int):int {
+        let x = a + 200;
+        let y = b + 200;
+        (x as int) + y
+    }
+    ",
+    );
+    assert_invoke_eq!(u8, 255, driver, "add_u8", 5u8);
+    assert_invoke_eq!(u64, 18_446_744_073_709_551_615, driver, "max_u64");
+    assert_invoke_eq!(f32, 3.0, driver, "suffixed");
+    assert_invoke_eq!(i64, 6, driver, "defaulted");
+    // The same numeral is a `u8` that wraps around and an `int` that doesn't
+    assert_invoke_eq!(i64, 500, driver, "widths", 50u8, 50i64);
+    assert_invoke_eq!(i64, 44 + 300, driver, "widths", 100u8, 100i64);
+}
+
+#[test]
+fn struct_literals() {
+    let mut driver = TestDriver::new(
+        r"
+    struct Vector2 {
+        x: float,
+        y: float,
+    }
+    struct Unit;
+
+    fn vector2_new(x:float, y:float):Vector2 { Vector2 { y: y, x: x } }
+    fn vector2_add(a:Vector2, b:Vector2):Vector2 { Vector2 { x: a.x + b.x, y: a.y + b.y } }
+    fn unit():Unit { Unit }
+
+    fn main(x:float, y:float):float {
+        let a = vector2_new(x, y);
+        let mut b = vector2_add(a, Vector2 { x: 1.0, y: 2.0 });
+        b.x = b.x * 10.0;
+        let _ = unit();
+        b.x + b.y
+    }
+    ",
+    );
+    assert_invoke_eq!(f64, 40.5, driver, "main", 2.5f64, 3.5f64);
+}
+
+#[test]
+fn value_structs() {
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Vector2 {
+        x: f64,
+        y: f64,
+    }
+
+    impl Reflection for Vector2 {
+        fn type_name() -> &'static str {
+            "main::Vector2"
+        }
+    }
+
+    impl ArgumentReflection for Vector2 {
+        fn type_name() -> &'static str {
+            <Self as Reflection>::type_name()
+        }
+    }
+
+    let mut driver = TestDriver::new(
+        r"
+    struct(value) Vector2 {
+        x: float,
+        y: float,
+    }
+
+    fn vector2_new(x:float, y:float):Vector2 { Vector2 { x: x, y: y } }
+    fn vector2_sum(a:Vector2):float { a.x + a.y }
+    ",
+    );
+    let a = Vector2 { x: 1.5, y: 2.0 };
+    assert_invoke_eq!(Vector2, a, driver, "vector2_new", 1.5f64, 2.0f64);
+    assert_invoke_eq!(f64, 3.5, driver, "vector2_sum", a);
+}
+
+#[test]
+fn gc_structs() {
+    /// A handle to a GC struct that is allocated by the runtime
+    #[repr(transparent)]
+    #[derive(Clone, Copy)]
+    struct Vector2Handle(*mut [f64; 2]);
+
+    impl Reflection for Vector2Handle {
+        fn type_name() -> &'static str {
+            "main::Vector2"
+        }
+
+        fn memory_kind() -> MemoryKind {
+            MemoryKind::GC
+        }
+    }
+
+    impl ArgumentReflection for Vector2Handle {
+        fn type_name() -> &'static str {
+            <Self as Reflection>::type_name()
+        }
+
+        fn memory_kind() -> MemoryKind {
+            <Self as Reflection>::memory_kind()
+        }
+    }
+
+    #[repr(C)]
+    struct Vector2 {
+        x: f64,
+        y: f64,
+    }
+
+    impl Reflection for Vector2 {
+        fn type_name() -> &'static str {
+            "main::Vector2"
+        }
+    }
+
+    let mut driver = TestDriver::new(
+        r"
+    struct Vector2 {
+        x: float,
+        y: float,
+    }
+
+    fn vector2_new(x:float, y:float):Vector2 { Vector2 { x: x, y: y } }
+    fn vector2_sum(a:Vector2):float { a.x + a.y }
+    ",
+    );
+    let handle: Vector2Handle =
+        invoke_fn!(driver.runtime_mut(), "vector2_new", 1.5f64, 2.0f64).unwrap();
+    assert_eq!(unsafe { *handle.0 }, [1.5, 2.0]);
+
+    // Memory that is allocated by the runtime is shared with the host
+    unsafe { (*handle.0)[1] = 4.0 };
+    assert_invoke_eq!(f64, 5.5, driver, "vector2_sum", handle);
+
+    // A GC struct cannot be returned by value
+    let result: Result<Vector2, _> =
+        invoke_fn!(driver.runtime_mut(), "vector2_new", 1.5f64, 2.0f64);
+    let message = format!("{}", result.unwrap_err());
+    assert!(
+        message.contains("Invalid memory kind of return type"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn tuples() {
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Pair(i64, f64);
+
+    impl Reflection for Pair {
+        fn type_name() -> &'static str {
+            "(@core::int, @core::float)"
+        }
+    }
+
+    impl ArgumentReflection for Pair {
+        fn type_name() -> &'static str {
+            <Self as Reflection>::type_name()
+        }
+    }
+
+    let mut driver = TestDriver::new(
+        r"
+    fn pair(a:int, b:float):(int, float) { (a, b) }
+    fn min_max(a:int, b:int):int {
+        let t = if a < b { (a, b) } else { (b, a) };
+        t.1 - t.0
+    }
+    fn sum(t:(int, float)):float { let x = t.1; x + 1.0 }
+    ",
+    );
+    let pair = Pair(3, 2.5);
+    assert_invoke_eq!(Pair, pair, driver, "pair", 3i64, 2.5f64);
+    assert_invoke_eq!(i64, 4, driver, "min_max", 7i64, 3i64);
+    assert_invoke_eq!(f64, 3.5, driver, "sum", pair);
+}
+
+#[test]
+fn arrays() {
+    let mut driver = TestDriver::new(
+        r"
+    fn fill(mut a:float):[float; 4] {
+        let mut arr = [0.0, 0.0, 0.0, 0.0];
+        let mut i = 0;
+        while i < 4 {
+            arr[i] = a * 2.0;
+            a += 1.0;
+            i += 1;
+        };
+        arr
+    }
+    fn sum(arr:[float; 4]):float {
+        let mut total = 0.0;
+        let mut i = 0;
+        while i < 4 {
+            total += arr[i];
+            i += 1;
+        };
+        total
+    }
+    fn main(a:float):float { sum(fill(a)) }
+    fn clear(mut arr:[float; 4]):float { arr[0] = 0.0; arr[0] }
+    fn copy(a:float):float {
+        let arr = [a, 2.0, 3.0, 4.0];
+        clear(arr);
+        arr[0]
+    }
+    ",
+    );
+    assert_invoke_eq!(f64, 24.0, driver, "main", 1.5f64);
+    assert_invoke_eq!(f64, 1.5, driver, "copy", 1.5f64);
+}
+
+#[test]
+fn array_params() {
+    let mut driver = TestDriver::new(
+        r"
+    fn sum(values:[float; 16]):float {
+        let mut total = 0.0;
+        let mut i = 0;
+        while i < 16 {
+            total += values[i];
+            i += 1;
+        };
+        total
+    }
+    fn reset(mut values:[float; 16]):float {
+        values[0] = 0.0;
+        sum(values)
+    }
+    ",
+    );
+
+    // The element type and the length of array parameters are part of the ABI
+    let arg_type = &driver
+        .runtime_mut()
+        .get_function_info("sum")
+        .unwrap()
+        .signature
+        .arg_types()[0];
+    let array_info = arg_type.array_info().expect("expected an array");
+    assert_eq!(array_info.element_type().name(), "@core::float");
+    assert_eq!(array_info.length(), 16);
+
+    let mut values = [0.0f64; 16];
+    for (i, value) in values.iter_mut().enumerate() {
+        *value = i as f64 + 1.0;
+    }
+    assert_invoke_eq!(f64, 136.0, driver, "sum", &values);
+
+    // A mutable parameter is a copy of the array
+    assert_invoke_eq!(f64, 135.0, driver, "reset", &values);
+    assert_invoke_eq!(f64, 136.0, driver, "sum", &values);
+
+    // An array of another length is rejected
+    let short = [0.0f64; 15];
+    let result: Result<f64, _> = invoke_fn!(driver.runtime_mut(), "sum", &short);
+    let message = result.unwrap_err().to_string();
+    assert!(
+        message.contains("Invalid length of array argument"),
+        "unexpected error: {}",
+        message
+    );
+}
+
+#[test]
+fn const_contexts() {
+    let mut driver = TestDriver::new(
+        r"
+    enum Flags { Visible = 1 << 4, Solid = 2 * (1 << 4) }
+
+    fn sum(values: [f64; 4 * 4]): f64 {
+        let mut total = 0.0;
+        let mut i = 0;
+        while i < 4 * 4 {
+            total += values[i];
+            i += 1;
+        };
+        total
+    }
+    fn visible(): u8 { Flags::Visible as u8 }
+    fn solid(): u8 { Flags::Solid as u8 }
+    fn shl(a: int, b: int): int { a << b }
+    fn shr(a: int, b: int): int { a >> b }
+    fn shr_unsigned(a: u8, b: u8): u8 { a >> b }
+    ",
+    );
+
+    // The evaluated length is part of the ABI
+    let arg_type = &driver
+        .runtime_mut()
+        .get_function_info("sum")
+        .unwrap()
+        .signature
+        .arg_types()[0];
+    assert_eq!(
+        arg_type.array_info().expect("expected an array").length(),
+        16
+    );
+    let mut values = [0.0f64; 16];
+    for (i, value) in values.iter_mut().enumerate() {
+        *value = i as f64 + 1.0;
+    }
+    assert_invoke_eq!(f64, 136.0, driver, "sum", &values);
+
+    assert_invoke_eq!(u8, 16, driver, "visible");
+    assert_invoke_eq!(u8, 32, driver, "solid");
+
+    assert_invoke_eq!(i64, 16, driver, "shl", 1i64, 4i64);
+    assert_invoke_eq!(i64, -4, driver, "shr", -16i64, 2i64);
+    assert_invoke_eq!(u8, 1, driver, "shr_unsigned", 128u8, 7u8);
+    // The shift amount wraps around at the number of bits of the type
+    assert_invoke_eq!(i64, 2, driver, "shl", 1i64, 65i64);
+}
+
+#[test]
+fn enums() {
+    #[repr(u8)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum State {
+        Idle,
+        Walking,
+        Attacking = 5,
+    }
+
+    impl Reflection for State {
+        fn type_name() -> &'static str {
+            "main::State"
+        }
+    }
+
+    impl ArgumentReflection for State {
+        fn type_name() -> &'static str {
+            <Self as Reflection>::type_name()
+        }
+    }
+
+    /// A Rust enum whose size does not match the Mun enum
+    #[allow(dead_code)]
+    #[repr(u16)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum WideState {
+        Idle,
+    }
+
+    impl Reflection for WideState {
+        fn type_name() -> &'static str {
+            "main::State"
+        }
+    }
+
+    let mut driver = TestDriver::new(
+        r"
+    enum State { Idle, Walking, Attacking = 5 }
+
+    fn next(s: State): State {
+        if s == State::Idle {
+            State::Walking
+        } else if s == State::Walking {
+            State::Attacking
+        } else {
+            State::Idle
+        }
+    }
+    fn code(s: State): u8 { s as u8 }
+    fn start(): State { State::Idle }
+    ",
+    );
+    assert_invoke_eq!(State, State::Walking, driver, "next", State::Idle);
+    assert_invoke_eq!(State, State::Attacking, driver, "next", State::Walking);
+    assert_invoke_eq!(State, State::Idle, driver, "next", State::Attacking);
+    assert_invoke_eq!(u8, 5, driver, "code", State::Attacking);
+
+    // The variants are exposed so values can be displayed
+    let return_type = driver
+        .runtime_mut()
+        .get_function_info("next")
+        .unwrap()
+        .signature
+        .return_type()
+        .unwrap();
+    let enum_info = return_type.enum_info().expect("expected an enum");
+    assert_eq!(enum_info.repr().name(), "@core::u8");
+    assert_eq!(enum_info.variant_values(), &[0, 1, 5]);
+    assert_eq!(enum_info.variant_name_of(5), Some("Attacking"));
+
+    let result: Result<WideState, _> = invoke_fn!(driver.runtime_mut(), "start");
+    let message = format!("{}", result.unwrap_err());
+    assert!(
+        message.contains("Invalid size of enum return type"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn match_expressions() {
+    #[repr(u8)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum State {
+        Idle,
+        Walking,
+        Attacking = 5,
+    }
+
+    impl Reflection for State {
+        fn type_name() -> &'static str {
+            "main::State"
+        }
+    }
+
+    impl ArgumentReflection for State {
+        fn type_name() -> &'static str {
+            <Self as Reflection>::type_name()
+        }
+    }
+
+    let mut driver = TestDriver::new(
+        r"
+    enum State { Idle, Walking, Attacking = 5 }
+
+    fn next(s: State): State {
+        match s {
+            State::Idle => State::Walking,
+            State::Walking => State::Attacking,
+            State::Attacking => State::Idle,
+        }
+    }
+    fn speed(s: State): int {
+        match s {
+            State::Walking => 2,
+            _ => 0,
+        }
+    }
+    fn sign(a: int): int {
+        match a {
+            0 => 0,
+            -1 => { return -1; }
+            1 => 1,
+            1 => 2, // warning: unreachable pattern
+            _ => 100,
+        }
+    }
+    fn toggle(b: bool): bool {
+        match b { true => false, false => true }
+    }
+    ",
+    );
+    assert_invoke_eq!(State, State::Walking, driver, "next", State::Idle);
+    assert_invoke_eq!(State, State::Attacking, driver, "next", State::Walking);
+    assert_invoke_eq!(State, State::Idle, driver, "next", State::Attacking);
+    assert_invoke_eq!(i64, 2, driver, "speed", State::Walking);
+    assert_invoke_eq!(i64, 0, driver, "speed", State::Attacking);
+    assert_invoke_eq!(i64, 0, driver, "sign", 0i64);
+    assert_invoke_eq!(i64, -1, driver, "sign", -1i64);
+    assert_invoke_eq!(i64, 1, driver, "sign", 1i64);
+    assert_invoke_eq!(i64, 100, driver, "sign", 7i64);
+    assert_invoke_eq!(bool, false, driver, "toggle", true);
+    assert_invoke_eq!(bool, true, driver, "toggle", false);
+}
+
+#[test]
+fn consts() {
+    let mut driver = TestDriver::new(
+        r"
+    const SECONDS_PER_MINUTE: int = 60;
+    const SECONDS_PER_HOUR: int = SECONDS_PER_MINUTE * 60;
+    const GRAVITY: float = -9.81;
+    const MAX: u8 = 255;
+    const ENABLED: bool = SECONDS_PER_HOUR > 3000 && MAX == 255;
+
+    fn hours(seconds:int):int { seconds / SECONDS_PER_HOUR }
+    fn fall_speed(t:float):float { GRAVITY * t }
+    fn max():u8 { MAX }
+    fn enabled():bool { ENABLED }
+    fn remainder():int { -7 % 0 }
+    ",
+    );
+    assert_invoke_eq!(i64, 2, driver, "hours", 7200i64);
+    assert_invoke_eq!(f64, -19.62, driver, "fall_speed", 2.0f64);
+    assert_invoke_eq!(u8, 255, driver, "max");
+    assert_invoke_eq!(bool, true, driver, "enabled");
+    assert_invoke_eq!(i64, -7, driver, "remainder");
+}
+
+#[test]
+fn statics() {
+    let mut driver = TestDriver::new(
+        r"
+    static mut SCORE: int = 10;
+    static BONUS: int = 5;
+
+    fn increment() { SCORE += BONUS; }
+    fn score():int { SCORE }
+    ",
+    );
+    assert_invoke_eq!(i64, 10, driver, "score");
+    assert_invoke_eq!((), (), driver, "increment");
+    assert_invoke_eq!((), (), driver, "increment");
+    assert_invoke_eq!(i64, 20, driver, "score");
+
+    // The value of a static is preserved across hot reloads if its type doesn't change
+    driver.update(
+        r"
+    static mut SCORE: int = 10;
+    static mut LIVES: u8 = 3;
+
+    fn increment() { SCORE += 1; }
+    fn score():int { SCORE }
+    fn lives():u8 { LIVES }
+    ",
+    );
+    assert_invoke_eq!(i64, 20, driver, "score");
+    assert_invoke_eq!((), (), driver, "increment");
+    assert_invoke_eq!(i64, 21, driver, "score");
+    assert_invoke_eq!(u8, 3, driver, "lives");
+
+    // Otherwise the static is initialized with its new initial value
+    driver.update(
+        r"
+    static mut SCORE: float = 1.5;
+
+    fn score():float { SCORE }
+    ",
+    );
+    assert_invoke_eq!(f64, 1.5, driver, "score");
+}
+
+#[test]
+fn assignment_places() {
+    let mut driver = TestDriver::new(
+        r"
+    static mut CALLS: int = 0;
+
+    fn next():int {
+        CALLS += 1;
+        CALLS - 1
+    }
+    fn assign():int {
+        let mut arr = [0, 0, 0];
+        arr[next()] = next() + 10;
+        arr[next()] += 5;
+        arr[0] * 100 + arr[1] * 10 + arr[2]
+    }
+    fn calls():int { CALLS }
+    ",
+    );
+
+    // The index of the place is evaluated once, before the right-hand side
+    assert_invoke_eq!(i64, 1105, driver, "assign");
+    assert_invoke_eq!(i64, 3, driver, "calls");
+}
+
+#[test]
+fn string_literals() {
+    extern "C" fn is_game_over(message: Str) -> bool {
+        message.as_bytes() == b"game_over"
+    }
+
+    let is_game_over_fn = FunctionInfoBuilder::new("is_game_over")
+        .params::<(Str,)>()
+        .returns::<bool>()
+        .fn_ptr(is_game_over as *const std::ffi::c_void)
+        .build();
+
+    let mut driver = TestDriver::with_functions(
+        r#"
+    extern fn is_game_over(message: str): bool;
+
+    fn game_over():bool { is_game_over("game_over") }
+    fn still_playing():bool { is_game_over("game_on") }
+    fn message():str { "tab\tquote\"" }
+    "#,
+        vec![is_game_over_fn],
+    );
+    assert_invoke_eq!(bool, true, driver, "game_over");
+    assert_invoke_eq!(bool, false, driver, "still_playing");
+
+    // The returned string refers to the memory of the library, so it is only valid until the
+    // library is reloaded
+    let message: Str = invoke_fn!(driver.runtime_mut(), "message").unwrap();
+    assert_eq!(message.to_string(), "tab\tquote\"");
+}
+
+#[test]
+fn methods() {
+    /// A handle to a GC struct that is allocated by the runtime
+    #[repr(transparent)]
+    #[derive(Clone, Copy)]
+    struct Vector2Handle(*mut [f64; 2]);
+
+    impl Reflection for Vector2Handle {
+        fn type_name() -> &'static str {
+            "main::Vector2"
+        }
+
+        fn memory_kind() -> MemoryKind {
+            MemoryKind::GC
+        }
+    }
+
+    impl ArgumentReflection for Vector2Handle {
+        fn type_name() -> &'static str {
+            <Self as Reflection>::type_name()
+        }
+
+        fn memory_kind() -> MemoryKind {
+            <Self as Reflection>::memory_kind()
+        }
+    }
+
+    let mut driver = TestDriver::new(
+        r"
+    struct Vector2 {
+        x: float,
+        y: float,
+    }
+
+    impl Vector2 {
+        fn new(x: float, y: float): Vector2 { Vector2 { x: x, y: y } }
+        fn length_squared(self): float { self.x * self.x + self.y * self.y }
+        fn scale(mut self, factor: float) {
+            self.x *= factor;
+            self.y *= factor;
+        }
+    }
+
+    fn main(): float {
+        let a = Vector2::new(3.0, 4.0);
+        a.scale(2.0);
+        a.length_squared()
+    }
+    ",
+    );
+    assert_invoke_eq!(f64, 100.0, driver, "main");
+
+    // Methods are exported under the name of their struct
+    let handle: Vector2Handle =
+        invoke_fn!(driver.runtime_mut(), "Vector2::new", 3.0f64, 4.0f64).unwrap();
+    assert_eq!(unsafe { *handle.0 }, [3.0, 4.0]);
+    assert_invoke_eq!(f64, 25.0, driver, "Vector2::length_squared", handle);
+}
+
+#[test]
+fn codegen_units() {
+    let mut driver = TestDriver::with_config(
+        r#"
+    struct Vector2 {
+        x: float,
+        y: float,
+    }
+
+    static mut CALLS: int = 0;
+
+    fn vector2_new(x:float, y:float):Vector2 {
+        CALLS += 1;
+        Vector2 { x: x, y: y }
+    }
+    fn length_squared(v:Vector2):float { v.x * v.x + v.y * v.y }
+    fn fibonacci(n:int):int { if n <= 1 { n } else { fibonacci(n - 1) + fibonacci(n - 2) } }
+    fn main():float { length_squared(vector2_new(3.0, 4.0)) + fibonacci(10) as float }
+    fn calls():int { CALLS }
+    fn message():str { "split" }
+    "#,
+        Config {
+            codegen_units: 4,
+            ..Config::default()
+        },
+    );
+
+    // Functions and globals are spread over the units, but behave as if they were emitted together
+    assert_invoke_eq!(f64, 80.0, driver, "main");
+    assert_invoke_eq!(i64, 55, driver, "fibonacci", 10i64);
+    assert_invoke_eq!(i64, 1, driver, "calls");
+    let message: Str = invoke_fn!(driver.runtime_mut(), "message").unwrap();
+    assert_eq!(message.to_string(), "split");
+}
+
+#[test]
+fn exported_symbols() {
+    let mut driver = TestDriver::new(
+        r"
+    struct Counter {
+        count: int,
+    }
+
+    static mut TOTAL: int = 0;
+
+    fn add(a:int, b:int):int { TOTAL += 1; a + b }
+    export fn counter():Counter { Counter { count: add(1, 2) } }
+    ",
+    );
+
+    // Only `get_info` and the `export` functions are exported, the runtime finds all other symbols
+    // through `get_info`
+    let library = libloading::Library::new(&driver.out_path).unwrap();
+    unsafe {
+        assert!(library.get::<*const u8>(b"get_info").is_ok());
+        assert!(library.get::<*const u8>(b"_MN4main7counterE").is_ok());
+        for symbol in [
+            &b"_MN4main3addE"[..],
+            b"TOTAL",
+            b"dispatchTable",
+            b"allocatorHandle",
+        ]
+        .iter()
+        {
+            assert!(
+                library.get::<*const u8>(symbol).is_err(),
+                "`{}` is exported",
+                String::from_utf8_lossy(symbol)
+            );
+        }
+    }
+
+    // Private functions can still be invoked by the runtime
+    let runtime = driver.runtime_mut();
+    let counter = runtime.get_function_info("counter").unwrap();
+    assert_eq!(counter.signature.privacy(), Privacy::Public);
+    let add = runtime.get_function_info("add").unwrap();
+    assert_eq!(add.signature.privacy(), Privacy::Private);
+    assert_invoke_eq!(i64, 3, driver, "add", 1i64, 2i64);
+}
+
+#[test]
+fn tail_calls() {
+    let mut driver = TestDriver::new(
+        r#"
+    fn countdown(n:int):int {
+        if n == 0 {
+            0
+        } else {
+            countdown(n - 1)
+        }
+    }
+    fn sum(n:int, acc:int):int {
+        if n == 0 {
+            return acc
+        }
+        return sum(n - 1, acc + n)
+    }
+    fn sum_non_tail(n:int):int {
+        if n == 0 {
+            0
+        } else {
+            n + sum_non_tail(n - 1)
+        }
+    }
+    "#,
+    );
+
+    // Tail recursion runs in constant stack space
+    assert_invoke_eq!(i64, 0, driver, "countdown", 1_000_000i64);
+    assert_invoke_eq!(i64, 500_000_500_000, driver, "sum", 1_000_000i64, 0i64);
+
+    // Recursion that is not in tail position still calls the function
+    assert_invoke_eq!(i64, 55, driver, "sum_non_tail", 10i64);
+}
+
+#[test]
+fn struct_repr() {
+    #[repr(C, packed)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Record {
+        tag: u8,
+        value: i64,
+        count: u16,
+    }
+
+    #[repr(C, align(16))]
+    struct Aligned {
+        _x: f64,
+    }
+
+    /// A handle to a packed GC struct that is allocated by the runtime
+    #[repr(transparent)]
+    #[derive(Clone, Copy)]
+    struct RecordHandle(*mut Record);
+
+    impl Reflection for RecordHandle {
+        fn type_name() -> &'static str {
+            "main::Record"
+        }
+
+        fn memory_kind() -> MemoryKind {
+            MemoryKind::GC
+        }
+    }
+
+    impl ArgumentReflection for RecordHandle {
+        fn type_name() -> &'static str {
+            <Self as Reflection>::type_name()
+        }
+
+        fn memory_kind() -> MemoryKind {
+            <Self as Reflection>::memory_kind()
+        }
+    }
+
+    extern "C" fn bump(record: RecordHandle) {
+        unsafe {
+            (*record.0).tag += 1;
+            (*record.0).value += 100;
+            (*record.0).count += 10;
+        }
+    }
+
+    let bump_fn = FunctionInfoBuilder::new("bump")
+        .params::<(RecordHandle,)>()
+        .fn_ptr(bump as *const std::ffi::c_void)
+        .build();
+
+    let mut driver = TestDriver::with_functions(
+        r"
+    #[repr(packed)]
+    struct Record {
+        tag: u8,
+        value: int,
+        count: u16,
+    }
+
+    #[repr(packed)]
+    struct(value) Header {
+        tag: u8,
+        value: int,
+        count: u16,
+    }
+
+    #[repr(align(16))]
+    struct(value) Aligned {
+        x: float,
+    }
+
+    extern fn bump(record: Record);
+
+    fn new_record(tag: u8, value: int, count: u16): Record {
+        Record { tag: tag, value: value, count: count }
+    }
+    fn bumped(tag: u8, value: int, count: u16): int {
+        let record = new_record(tag, value, count);
+        bump(record);
+        record.tag as int + record.value + record.count as int
+    }
+    fn header(): Header { Header { tag: 1, value: 2, count: 3 } }
+    fn header_sum(): int {
+        let mut h = header();
+        h.value += 10;
+        h.tag as int + h.value + h.count as int
+    }
+    fn aligned(): Aligned { Aligned { x: 1.0 } }
+    ",
+        vec![bump_fn],
+    );
+
+    // The reported layouts of value structs match the equivalent Rust structs
+    let runtime = driver.runtime_mut();
+    let header = runtime.get_function_info("header").unwrap();
+    let header_ty = header.signature.return_type().unwrap();
+    assert_eq!(
+        header_ty.size_in_bits as usize,
+        std::mem::size_of::<Record>() * 8
+    );
+    assert_eq!(header_ty.alignment as usize, std::mem::align_of::<Record>());
+    let aligned = runtime.get_function_info("aligned").unwrap();
+    let aligned_ty = aligned.signature.return_type().unwrap();
+    assert_eq!(
+        aligned_ty.size_in_bits as usize,
+        std::mem::size_of::<Aligned>() * 8
+    );
+    assert_eq!(
+        aligned_ty.alignment as usize,
+        std::mem::align_of::<Aligned>()
+    );
+    assert_invoke_eq!(i64, 16, driver, "header_sum");
+
+    // Packed GC structs are shared with the host
+    let handle: RecordHandle =
+        invoke_fn!(driver.runtime_mut(), "new_record", 1u8, 2i64, 3u16).unwrap();
+    assert_eq!(
+        unsafe { *handle.0 },
+        Record {
+            tag: 1,
+            value: 2,
+            count: 3
+        }
+    );
+    assert_invoke_eq!(i64, 117, driver, "bumped", 1u8, 2i64, 3u16);
+}
+
+#[test]
+fn bool_abi() {
+    #[repr(C)]
+    struct Flags {
+        a: bool,
+        b: bool,
+        value: i64,
+    }
+
+    /// A handle to a GC struct with bool fields that is allocated by the runtime
+    #[repr(transparent)]
+    #[derive(Clone, Copy)]
+    struct FlagsHandle(*mut Flags);
+
+    impl Reflection for FlagsHandle {
+        fn type_name() -> &'static str {
+            "main::Flags"
+        }
+
+        fn memory_kind() -> MemoryKind {
+            MemoryKind::GC
+        }
+    }
+
+    impl ArgumentReflection for FlagsHandle {
+        fn type_name() -> &'static str {
+            <Self as Reflection>::type_name()
+        }
+
+        fn memory_kind() -> MemoryKind {
+            <Self as Reflection>::memory_kind()
+        }
+    }
+
+    extern "C" fn negate(value: bool) -> bool {
+        !value
+    }
+
+    // Returns a byte that is not a valid bool
+    extern "C" fn invalid() -> u8 {
+        2
+    }
+
+    let negate_fn = FunctionInfoBuilder::new("negate")
+        .params::<(bool,)>()
+        .returns::<bool>()
+        .fn_ptr(negate as *const std::ffi::c_void)
+        .build();
+    let invalid_fn = FunctionInfoBuilder::new("invalid")
+        .returns::<bool>()
+        .fn_ptr(invalid as *const std::ffi::c_void)
+        .build();
+
+    let mut driver = TestDriver::with_functions(
+        r"
+    struct Flags {
+        a: bool,
+        b: bool,
+        value: int,
+    }
+
+    extern fn negate(value: bool): bool;
+    extern fn invalid(): bool;
+
+    fn identity(value: bool): bool { value }
+    fn negated(value: bool): bool { negate(value) }
+    fn flags(a: bool, b: bool): Flags { Flags { a: a, b: b, value: 1 } }
+    fn count(f: Flags): int {
+        let a = if f.a { 1 } else { 0 };
+        let b = if f.b { 2 } else { 0 };
+        a + b
+    }
+    fn both(f: Flags): bool { f.a && f.b }
+    fn invalid_bool(): bool { invalid() }
+    ",
+        vec![negate_fn, invalid_fn],
+    );
+
+    // Bools are passed to and returned from Mun and host functions
+    assert_invoke_eq!(bool, true, driver, "identity", true);
+    assert_invoke_eq!(bool, false, driver, "identity", false);
+    assert_invoke_eq!(bool, false, driver, "negated", true);
+    assert_invoke_eq!(bool, true, driver, "negated", false);
+
+    // Bools are stored in struct fields as bytes that the host can read
+    let handle: FlagsHandle = invoke_fn!(driver.runtime_mut(), "flags", true, false).unwrap();
+    unsafe {
+        assert!((*handle.0).a);
+        assert!(!(*handle.0).b);
+        assert_eq!((*handle.0).value, 1);
+    }
+    assert_invoke_eq!(i64, 1, driver, "count", handle);
+    assert_invoke_eq!(bool, false, driver, "both", handle);
+    let handle: FlagsHandle = invoke_fn!(driver.runtime_mut(), "flags", true, true).unwrap();
+    assert_invoke_eq!(i64, 3, driver, "count", handle);
+    assert_invoke_eq!(bool, true, driver, "both", handle);
+
+    // A returned byte other than 0 or 1 is rejected
+    let result: Result<bool, _> = invoke_fn!(driver.runtime_mut(), "invalid_bool");
+    let message = format!("{}", result.unwrap_err());
+    assert!(message.contains("Invalid bool value 2"), "{}", message);
+}
+
+#[test]
+fn sret_returns() {
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Eight {
+        a: i64,
+        b: f64,
+        c: i32,
+        d: u8,
+        e: bool,
+        f: f32,
+        g: i16,
+        h: u64,
+    }
+
+    impl Reflection for Eight {
+        fn type_name() -> &'static str {
+            "main::Eight"
+        }
+    }
+
+    extern "C" fn host_eight(seed: i64) -> Eight {
+        Eight {
+            a: seed,
+            b: 2.5,
+            c: 3,
+            d: 4,
+            e: true,
+            f: 6.5,
+            g: -7,
+            h: 8,
+        }
+    }
+
+    let host_eight_fn = FunctionInfoBuilder::new("host_eight")
+        .params::<(i64,)>()
+        .returns::<Eight>()
+        .fn_ptr(host_eight as *const std::ffi::c_void)
+        .build();
+
+    let mut driver = TestDriver::with_functions(
+        r"
+    struct(value) Eight {
+        a: int,
+        b: float,
+        c: i32,
+        d: u8,
+        e: bool,
+        f: f32,
+        g: i16,
+        h: u64,
+    }
+
+    extern fn host_eight(seed: int): Eight;
+
+    fn eight(seed: int): Eight {
+        Eight { a: seed, b: 2.5, c: 3, d: 4, e: true, f: 6.5, g: -7, h: 8 }
+    }
+    fn sum(seed: int): int {
+        let e = eight(seed);
+        e.a + e.h as int
+    }
+    fn from_host(seed: int): Eight { host_eight(seed) }
+    ",
+        vec![host_eight_fn],
+    );
+
+    // The struct is larger than two registers, so it is returned through a pointer on x86-64
+    assert_eq!(
+        driver
+            .runtime_mut()
+            .get_function_info("eight")
+            .unwrap()
+            .signature
+            .return_convention(),
+        mun_abi::ReturnConvention::Pointer
+    );
+
+    // Every field is stored in the buffer of the caller
+    let eight: Eight = invoke_fn!(driver.runtime_mut(), "eight", 5i64).unwrap();
+    assert_eq!(
+        eight,
+        Eight {
+            a: 5,
+            b: 2.5,
+            c: 3,
+            d: 4,
+            e: true,
+            f: 6.5,
+            g: -7,
+            h: 8,
+        }
+    );
+
+    // Mun functions return the struct through a pointer to each other and to the host, and
+    // receive it from host functions
+    assert_invoke_eq!(i64, 13, driver, "sum", 5i64);
+    assert_invoke_eq!(Eight, host_eight(9), driver, "from_host", 9i64);
+}
+
+#[test]
+fn zero_sized_params() {
+    let mut driver = TestDriver::new(
+        r"
+    fn nothing() {}
+    fn five(x: ()): int { 5 }
+    fn call_five(): int { five(nothing()) }
+    ",
+    );
+
+    // Zero-sized parameters are not part of the signature, so they are not passed by the host
+    assert!(driver
+        .runtime_mut()
+        .get_function_info("five")
+        .unwrap()
+        .signature
+        .arg_types()
+        .is_empty());
+    assert_invoke_eq!(i64, 5, driver, "five");
+    assert_invoke_eq!(i64, 5, driver, "call_five");
+}
+
+#[test]
+fn fn_pointers() {
+    let mut driver = TestDriver::new(
+        r"
+    fn double(a: int): int { a * 2 }
+    fn square(a: int): int { a * a }
+    fn apply(f: fn(int): int, v: int): int { f(v) }
+    fn strategy(square_it: bool): fn(int): int {
+        if square_it { square } else { double }
+    }
+    fn main(v: int): int {
+        let f = double;
+        apply(f, v) + apply(strategy(true), v)
+    }
+    ",
+    );
+    assert_invoke_eq!(i64, 6 + 9, driver, "main", 3i64);
+}
+
+#[test]
+fn closures() {
+    let mut driver = TestDriver::new(
+        r"
+    fn apply(f: fn(int): int, v: int): int { f(v) }
+    fn max_by(a: int, b: int, less: fn(int, int): bool): int {
+        if less(a, b) { b } else { a }
+    }
+    fn main(v: int): int {
+        let add_one = |x: int| x + 1;
+        let clamp = |x: int|: int {
+            if x > 5 { return 5; }
+            x
+        };
+        max_by(v, 10, |a, b| a < b) + apply(|x| x * x, v) + add_one(v) + apply(clamp, v * 4)
+    }
+    ",
+    );
+    assert_invoke_eq!(i64, 10 + 9 + 4 + 5, driver, "main", 3i64);
+}
+
+#[test]
+fn compile_and_load_in_memory() {
+    let artifact = mun_compiler::compile_str(
+        "export fn add(a: int, b: int): int { a + b }",
+        Config::default(),
+    )
+    .unwrap();
+    assert!(artifact.ir.is_none());
+
+    let mut runtime = RuntimeBuilder::from_bytes(&artifact.library)
+        .unwrap()
+        .spawn()
+        .unwrap();
+    let result: i64 = invoke_fn!(runtime, "add", 2i64, 3i64).unwrap();
+    assert_eq!(result, 5);
+}
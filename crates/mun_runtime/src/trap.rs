@@ -0,0 +1,126 @@
+use std::cell::RefCell;
+use std::ffi::{c_void, CStr};
+use std::fmt;
+use std::os::raw::c_char;
+
+use mun_abi::{FunctionInfoBuilder, FunctionInfoStorage, TrapKind, TrapSite};
+
+/// The name under which the trap handler is registered in the runtime's dispatch table. The
+/// `@core::` prefix prevents collisions with functions defined in Mun.
+pub const TRAP_FN_NAME: &str = "@core::trap";
+
+/// The name under which the handler of failed assertions is registered in the runtime's dispatch
+/// table.
+pub const ASSERT_FAILED_FN_NAME: &str = "@core::assert_failed";
+
+/// A trap that Mun code raised, e.g. because an integer overflowed while overflow checks were
+/// enabled, at the location in the source of its assembly at which the check failed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScriptTrap {
+    /// The qualified name of the function that trapped, e.g. `enemies::update`
+    pub function: String,
+    /// The path of the source file of the function relative to its source root
+    pub file: String,
+    /// The one-based line number of the check that failed
+    pub line: u32,
+    /// The check that failed
+    pub kind: TrapKind,
+    /// The source of the condition of a failed assertion, e.g. `hp >= 0`
+    pub message: Option<String>,
+}
+
+impl ScriptTrap {
+    /// Constructs the trap of the entry `site` in the table of trap sites `sites`.
+    ///
+    /// # Safety
+    ///
+    /// `sites` must point to a table of trap sites generated by the compiler that has an entry
+    /// `site`.
+    unsafe fn from_site(sites: *const TrapSite, site: u32, message: Option<String>) -> Self {
+        let site = &*sites.add(site as usize);
+        ScriptTrap {
+            function: site.function().to_owned(),
+            file: site.file().to_owned(),
+            line: site.line,
+            kind: site.kind,
+            message,
+        }
+    }
+}
+
+/// Formats the trap as its kind and location, followed by the message of a failed assertion,
+/// e.g. `assertion failed in 'check_hp' at main.mun:3: hp >= 0`.
+impl fmt::Display for ScriptTrap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match self.kind {
+            TrapKind::Overflow => "integer overflow",
+            TrapKind::DivisionByZero => "division by zero",
+            TrapKind::IndexOutOfBounds => "index out of bounds",
+            TrapKind::AssertionFailed => "assertion failed",
+        };
+        write!(
+            f,
+            "{} in '{}' at {}:{}",
+            kind, self.function, self.file, self.line
+        )?;
+        if let Some(message) = &self.message {
+            write!(f, ": {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+thread_local! {
+    /// The first trap that Mun code raised on this thread since it was last taken.
+    static TRAP: RefCell<Option<ScriptTrap>> = RefCell::new(None);
+}
+
+/// Constructs the information of the trap handler that Mun assemblies call when they trap.
+pub fn trap_fn_info() -> FunctionInfoStorage {
+    FunctionInfoBuilder::new(TRAP_FN_NAME)
+        .params::<(&u8, u32)>()
+        .param_names(&["sites", "site"])
+        .fn_ptr(trap as *const c_void)
+        .build()
+}
+
+/// Constructs the information of the handler that Mun assemblies call when an assertion fails.
+pub fn assert_failed_fn_info() -> FunctionInfoStorage {
+    FunctionInfoBuilder::new(ASSERT_FAILED_FN_NAME)
+        .params::<(&u8, u32, &u8)>()
+        .param_names(&["sites", "site", "message"])
+        .fn_ptr(assert_failed as *const c_void)
+        .build()
+}
+
+/// Returns the first trap that Mun code raised on the current thread since the last call, if any.
+pub fn take_trap() -> Option<ScriptTrap> {
+    TRAP.with(|trap| trap.borrow_mut().take())
+}
+
+/// Records `trap`, unless Mun code already raised a trap. Only the first trap is kept, because
+/// the traps that follow it are often caused by the values that the first one produced.
+fn record_trap(trap: ScriptTrap) {
+    TRAP.with(|current| {
+        let mut current = current.borrow_mut();
+        if current.is_none() {
+            *current = Some(trap);
+        }
+    });
+}
+
+/// Records that Mun code raised a trap at the entry `site` of the table of trap sites `sites`. The
+/// runtime reports the trap after the invoked function returns, rather than aborting the host
+/// process.
+extern "C" fn trap(sites: *const TrapSite, site: u32) {
+    record_trap(unsafe { ScriptTrap::from_site(sites, site, None) });
+}
+
+/// Records that an assertion failed at the entry `site` of the table of trap sites `sites`, with
+/// the nul-terminated `message` that describes it, e.g. `hp >= 0`.
+extern "C" fn assert_failed(sites: *const TrapSite, site: u32, message: *const c_char) {
+    let message = unsafe { CStr::from_ptr(message) }
+        .to_string_lossy()
+        .into_owned();
+    record_trap(unsafe { ScriptTrap::from_site(sites, site, Some(message)) });
+}
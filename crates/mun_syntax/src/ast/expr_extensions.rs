@@ -1,10 +1,11 @@
 use super::{children, BinExpr};
 use crate::ast::{child_opt, AstChildren, Literal};
 use crate::{
-    ast, AstNode,
+    ast, AstNode, SmolStr,
     SyntaxKind::{self, *},
     SyntaxToken,
 };
+use std::fmt;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum PrefixOp {
@@ -34,14 +35,16 @@ pub enum BinOp {
     Subtract,
     Divide,
     Multiply,
-    //    Remainder,
+    Remainder,
+    LeftShift,
+    RightShift,
     //    Power,
     Assign,
     AddAssign,
     SubtractAssign,
     DivideAssign,
     MultiplyAssign,
-    //    RemainderAssign,
+    RemainderAssign,
     //    PowerAssign,
     Equals,
     NotEquals,
@@ -49,6 +52,8 @@ pub enum BinOp {
     Less,
     GreatEqual,
     Greater,
+    BooleanAnd,
+    BooleanOr,
 }
 
 impl BinExpr {
@@ -62,14 +67,16 @@ impl BinExpr {
                 MINUS => Some((c, BinOp::Subtract)),
                 SLASH => Some((c, BinOp::Divide)),
                 STAR => Some((c, BinOp::Multiply)),
-                //                PERCENT => Some((c, BinOp::Remainder)),
+                PERCENT => Some((c, BinOp::Remainder)),
+                SHL => Some((c, BinOp::LeftShift)),
+                SHR => Some((c, BinOp::RightShift)),
                 //                CARET => Some((c, BinOp::Power)),
                 T![=] => Some((c, BinOp::Assign)),
                 PLUSEQ => Some((c, BinOp::AddAssign)),
                 MINUSEQ => Some((c, BinOp::SubtractAssign)),
                 SLASHEQ => Some((c, BinOp::DivideAssign)),
                 STAREQ => Some((c, BinOp::MultiplyAssign)),
-                //                PERCENTEQ => Some((c, BinOp::RemainderAssign)),
+                PERCENTEQ => Some((c, BinOp::RemainderAssign)),
                 //                CARETEQ => Some((c, BinOp::PowerAssign)),
                 EQEQ => Some((c, BinOp::Equals)),
                 NEQ => Some((c, BinOp::NotEquals)),
@@ -77,6 +84,8 @@ impl BinExpr {
                 LTEQ => Some((c, BinOp::LessEqual)),
                 GT => Some((c, BinOp::Greater)),
                 GTEQ => Some((c, BinOp::GreatEqual)),
+                AMPAMP => Some((c, BinOp::BooleanAnd)),
+                PIPEPIPE => Some((c, BinOp::BooleanOr)),
                 _ => None,
             })
     }
@@ -105,11 +114,28 @@ impl BinExpr {
     }
 }
 
+impl ast::RangeExpr {
+    pub fn start(&self) -> Option<ast::Expr> {
+        children(self).nth(0)
+    }
+
+    pub fn end(&self) -> Option<ast::Expr> {
+        children(self).nth(1)
+    }
+
+    /// Returns true if the range includes its upper bound, e.g. `a..=b`.
+    pub fn is_inclusive(&self) -> bool {
+        self.syntax()
+            .children_with_tokens()
+            .any(|it| it.kind() == DOTDOTEQ)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum LiteralKind {
     String,
-    IntNumber,
-    FloatNumber,
+    IntNumber { suffix: Option<SmolStr> },
+    FloatNumber { suffix: Option<SmolStr> },
     Bool,
 }
 
@@ -123,14 +149,236 @@ impl Literal {
     }
 
     pub fn kind(&self) -> LiteralKind {
-        match self.token().kind() {
+        let token = self.token();
+        match token.kind() {
             STRING => LiteralKind::String,
-            FLOAT_NUMBER => LiteralKind::FloatNumber,
-            INT_NUMBER => LiteralKind::IntNumber,
+            FLOAT_NUMBER => LiteralKind::FloatNumber {
+                suffix: float_suffix(token.text()).map(SmolStr::new),
+            },
+            INT_NUMBER => LiteralKind::IntNumber {
+                suffix: int_suffix(token.text()).map(SmolStr::new),
+            },
             T![true] | T![false] => LiteralKind::Bool,
             _ => unreachable!(),
         }
     }
+
+    /// Returns the text of a numeric literal without its suffix, e.g. `255` for `255u8`.
+    pub fn value_text(&self) -> SmolStr {
+        let token = self.token();
+        let text = token.text().as_str();
+        let suffix_len = match token.kind() {
+            FLOAT_NUMBER => float_suffix(text).map_or(0, str::len),
+            INT_NUMBER => int_suffix(text).map_or(0, str::len),
+            _ => 0,
+        };
+        SmolStr::new(&text[..text.len() - suffix_len])
+    }
+
+    /// Returns the radix of an integer literal, e.g. `Radix::Hexadecimal` for `0xff`.
+    pub fn radix(&self) -> Radix {
+        Radix::of(self.token().text())
+    }
+
+    /// Returns the value of an integer literal, e.g. `255` for `0xffu8`. Invalid digits and
+    /// missing digits are reported during validation, and values that don't fit in their type
+    /// during type inference.
+    pub fn int_value(&self) -> Result<u128, IntLiteralError> {
+        let radix = self.radix();
+        let text = self.value_text();
+        parse_int(&text[radix.prefix().len()..], radix)
+    }
+
+    /// Returns the value of a string literal with its escape sequences resolved, e.g. `a"b` for
+    /// `"a\"b"`. Returns `None` if the literal is not a valid string literal, which is reported
+    /// during validation.
+    pub fn string_value(&self) -> Option<String> {
+        let token = self.token();
+        match token.kind() {
+            STRING => unescape_string(token.text()).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// The base in which an integer literal is written, which is indicated by its prefix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Radix {
+    /// A literal with the prefix `0b`, e.g. `0b1010`
+    Binary,
+    /// A literal with the prefix `0o`, e.g. `0o755`
+    Octal,
+    /// A literal without a prefix, e.g. `255`
+    Decimal,
+    /// A literal with the prefix `0x`, e.g. `0xff`
+    Hexadecimal,
+}
+
+impl Radix {
+    /// Returns the radix of the text of an integer literal.
+    fn of(text: &str) -> Radix {
+        if text.starts_with("0b") {
+            Radix::Binary
+        } else if text.starts_with("0o") {
+            Radix::Octal
+        } else if text.starts_with("0x") {
+            Radix::Hexadecimal
+        } else {
+            Radix::Decimal
+        }
+    }
+
+    /// Returns the base of the radix, e.g. `16` for `Hexadecimal`.
+    pub fn base(self) -> u32 {
+        match self {
+            Radix::Binary => 2,
+            Radix::Octal => 8,
+            Radix::Decimal => 10,
+            Radix::Hexadecimal => 16,
+        }
+    }
+
+    /// Returns the prefix of literals that are written in the radix, e.g. `0x` for
+    /// `Hexadecimal`.
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Radix::Binary => "0b",
+            Radix::Octal => "0o",
+            Radix::Decimal => "",
+            Radix::Hexadecimal => "0x",
+        }
+    }
+
+    /// Formats `value` as a literal in the radix, e.g. `-0x80` for `-128` in `Hexadecimal`.
+    pub fn format(self, value: i128) -> String {
+        let sign = if value < 0 { "-" } else { "" };
+        // The absolute value of `i128::min_value()` wraps to itself, which is correct as a `u128`
+        let magnitude = value.wrapping_abs() as u128;
+        match self {
+            Radix::Binary => format!("{}0b{:b}", sign, magnitude),
+            Radix::Octal => format!("{}0o{:o}", sign, magnitude),
+            Radix::Decimal => format!("{}{}", sign, magnitude),
+            Radix::Hexadecimal => format!("{}0x{:x}", sign, magnitude),
+        }
+    }
+}
+
+impl fmt::Display for Radix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Radix::Binary => "binary",
+            Radix::Octal => "octal",
+            Radix::Decimal => "decimal",
+            Radix::Hexadecimal => "hexadecimal",
+        })
+    }
+}
+
+/// An error in the digits of an integer literal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IntLiteralError {
+    /// The literal has a prefix but no digits, e.g. `0x`.
+    Empty,
+    /// The literal contains a digit that is not valid in its radix, e.g. `2` in `0b102`.
+    InvalidDigit(char),
+    /// The value of the literal doesn't fit in 128 bits.
+    Overflow,
+}
+
+/// Parses the digits of an integer literal, without its prefix and suffix, in `radix`. Digits may
+/// be separated by underscores.
+pub(crate) fn parse_int(digits: &str, radix: Radix) -> Result<u128, IntLiteralError> {
+    let mut value: Option<u128> = None;
+    for c in digits.chars().filter(|&c| c != '_') {
+        let digit = c
+            .to_digit(radix.base())
+            .ok_or(IntLiteralError::InvalidDigit(c))?;
+        value = Some(
+            value
+                .unwrap_or(0)
+                .checked_mul(u128::from(radix.base()))
+                .and_then(|value| value.checked_add(u128::from(digit)))
+                .ok_or(IntLiteralError::Overflow)?,
+        );
+    }
+    value.ok_or(IntLiteralError::Empty)
+}
+
+/// An error in the text of a string literal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum StringLiteralError {
+    /// The literal is missing its closing quote.
+    Unterminated,
+    /// The literal contains an unknown escape sequence, e.g. `\q`.
+    UnknownEscape(char),
+}
+
+/// Resolves the escape sequences in the text of a string literal, including its quotes. The
+/// supported escape sequences are `\n`, `\r`, `\t`, `\0`, `\\`, `\'` and `\"`.
+pub(crate) fn unescape_string(text: &str) -> Result<String, StringLiteralError> {
+    let mut chars = text.chars();
+    let quote = chars.next().ok_or(StringLiteralError::Unterminated)?;
+
+    let mut value = String::with_capacity(text.len());
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') => value.push('\n'),
+                Some('r') => value.push('\r'),
+                Some('t') => value.push('\t'),
+                Some('0') => value.push('\0'),
+                Some(c @ '\\') | Some(c @ '\'') | Some(c @ '"') => value.push(c),
+                Some(c) => return Err(StringLiteralError::UnknownEscape(c)),
+                None => return Err(StringLiteralError::Unterminated),
+            },
+            c if c == quote => return Ok(value),
+            c => value.push(c),
+        }
+    }
+    Err(StringLiteralError::Unterminated)
+}
+
+/// Returns the suffix of an integer literal, e.g. `u8` for `0xffu8`.
+fn int_suffix(text: &str) -> Option<&str> {
+    let (prefix_len, radix) = if text.starts_with("0x") {
+        (2, 16)
+    } else if text.starts_with("0b") || text.starts_with("0o") {
+        (2, 10)
+    } else {
+        (0, 10)
+    };
+    text[prefix_len..]
+        .find(|c: char| !(c.is_digit(radix) || c == '_'))
+        .map(|idx| &text[prefix_len + idx..])
+}
+
+/// Returns the suffix of a floating point literal, e.g. `f32` for `1.5e3f32`.
+fn float_suffix(text: &str) -> Option<&str> {
+    let bytes = text.as_bytes();
+    let skip_digits = |mut idx: usize| {
+        while idx < bytes.len() && (bytes[idx].is_ascii_digit() || bytes[idx] == b'_') {
+            idx += 1;
+        }
+        idx
+    };
+
+    let mut idx = skip_digits(0);
+    if idx < bytes.len() && bytes[idx] == b'.' {
+        idx = skip_digits(idx + 1);
+    }
+    if idx < bytes.len() && (bytes[idx] == b'e' || bytes[idx] == b'E') {
+        idx += 1;
+        if idx < bytes.len() && (bytes[idx] == b'-' || bytes[idx] == b'+') {
+            idx += 1;
+        }
+        idx = skip_digits(idx);
+    }
+
+    if idx < bytes.len() {
+        Some(&text[idx..])
+    } else {
+        None
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -158,3 +406,48 @@ impl ast::IfExpr {
         children(self)
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldKind {
+    /// A named field, e.g. `a.b`
+    Name(ast::NameRef),
+    /// The index of a tuple field, e.g. `a.0`
+    Index(SyntaxToken),
+}
+
+impl ast::FieldExpr {
+    pub fn index_token(&self) -> Option<SyntaxToken> {
+        self.syntax()
+            .children_with_tokens()
+            .filter_map(|it| it.into_token())
+            .find(|t| t.kind() == INT_NUMBER)
+    }
+
+    pub fn field_access(&self) -> Option<FieldKind> {
+        if let Some(name_ref) = self.name_ref() {
+            Some(FieldKind::Name(name_ref))
+        } else {
+            self.index_token().map(FieldKind::Index)
+        }
+    }
+}
+
+impl ast::IndexExpr {
+    pub fn base(&self) -> Option<ast::Expr> {
+        children(self).nth(0)
+    }
+
+    pub fn index(&self) -> Option<ast::Expr> {
+        children(self).nth(1)
+    }
+}
+
+impl ast::ForExpr {
+    /// Returns the expression that is iterated over, e.g. `0..n` in `for i in 0..n {}`.
+    pub fn iterable(&self) -> Option<ast::Expr> {
+        let mut exprs = children(self);
+        let first = exprs.next();
+        // If there is only a single expression, it is the body of the loop
+        exprs.next().and(first)
+    }
+}
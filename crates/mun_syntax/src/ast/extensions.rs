@@ -3,7 +3,7 @@ use crate::{
     ast::{self, AstNode},
     T,
 };
-use crate::{SmolStr, SyntaxNode};
+use crate::{SmolStr, SyntaxKind::IDENT, SyntaxNode};
 use text_unit::TextRange;
 
 impl ast::Name {
@@ -42,6 +42,94 @@ impl ast::FunctionDef {
 
         TextRange::from_to(start, end)
     }
+
+    /// Returns true if the function is declared `extern`, i.e. its implementation is provided by
+    /// the host.
+    pub fn is_extern(&self) -> bool {
+        self.syntax()
+            .children_with_tokens()
+            .any(|p| p.kind() == T![extern])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StructKind {
+    Record(ast::RecordFieldDefList),
+    Unit,
+}
+
+impl StructKind {
+    fn from_node<N: AstNode>(node: &N) -> StructKind {
+        if let Some(r) = ast::child_opt::<_, ast::RecordFieldDefList>(node) {
+            StructKind::Record(r)
+        } else {
+            StructKind::Unit
+        }
+    }
+}
+
+impl ast::StaticDef {
+    /// Returns true if the static is declared `mut`, i.e. functions can assign to it.
+    pub fn is_mutable(&self) -> bool {
+        self.syntax()
+            .children_with_tokens()
+            .any(|p| p.kind() == T![mut])
+    }
+}
+
+impl ast::UseDecl {
+    /// Returns true if the declaration imports all items of a module, e.g. `use shapes::*;`.
+    pub fn is_glob(&self) -> bool {
+        self.syntax()
+            .children_with_tokens()
+            .any(|p| p.kind() == T![*])
+    }
+}
+
+impl ast::BindPat {
+    /// Returns true if the binding is declared `mut`, i.e. it can be assigned to.
+    pub fn is_mutable(&self) -> bool {
+        self.syntax()
+            .children_with_tokens()
+            .any(|p| p.kind() == T![mut])
+    }
+}
+
+impl ast::SelfParam {
+    /// Returns true if the `self` parameter is declared `mut`, i.e. it can be assigned to.
+    pub fn is_mutable(&self) -> bool {
+        self.syntax()
+            .children_with_tokens()
+            .any(|p| p.kind() == T![mut])
+    }
+}
+
+impl ast::StructDef {
+    pub fn kind(&self) -> StructKind {
+        StructKind::from_node(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryTypeSpecifierKind {
+    Gc,
+    Value,
+}
+
+impl ast::MemoryTypeSpecifier {
+    /// Returns the specified memory type, or `None` if it is missing or unknown.
+    pub fn kind(&self) -> Option<MemoryTypeSpecifierKind> {
+        let specifier = self
+            .syntax()
+            .children_with_tokens()
+            .filter_map(|it| it.into_token())
+            .find(|t| t.kind() == IDENT)?;
+        match specifier.text().as_str() {
+            "gc" => Some(MemoryTypeSpecifierKind::Gc),
+            "value" => Some(MemoryTypeSpecifierKind::Value),
+            _ => None,
+        }
+    }
 }
 
 fn text_of_first_token(node: &SyntaxNode) -> &SmolStr {
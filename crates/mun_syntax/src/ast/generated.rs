@@ -15,23 +15,23 @@ use crate::{
     SyntaxNode,
 };
 
-// ArgList
+// Alias
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct ArgList {
+pub struct Alias {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for ArgList {
+impl AstNode for Alias {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            ARG_LIST => true,
+            ALIAS => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(ArgList { syntax })
+            Some(Alias { syntax })
         } else {
             None
         }
@@ -40,29 +40,26 @@ impl AstNode for ArgList {
         &self.syntax
     }
 }
-impl ArgList {
-    pub fn args(&self) -> impl Iterator<Item = Expr> {
-        super::children(self)
-    }
-}
+impl ast::NameOwner for Alias {}
+impl Alias {}
 
-// BinExpr
+// ArgList
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct BinExpr {
+pub struct ArgList {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for BinExpr {
+impl AstNode for ArgList {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            BIN_EXPR => true,
+            ARG_LIST => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(BinExpr { syntax })
+            Some(ArgList { syntax })
         } else {
             None
         }
@@ -71,25 +68,29 @@ impl AstNode for BinExpr {
         &self.syntax
     }
 }
-impl BinExpr {}
+impl ArgList {
+    pub fn args(&self) -> impl Iterator<Item = Expr> {
+        super::children(self)
+    }
+}
 
-// BindPat
+// ArrayExpr
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct BindPat {
+pub struct ArrayExpr {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for BindPat {
+impl AstNode for ArrayExpr {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            BIND_PAT => true,
+            ARRAY_EXPR => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(BindPat { syntax })
+            Some(ArrayExpr { syntax })
         } else {
             None
         }
@@ -98,30 +99,29 @@ impl AstNode for BindPat {
         &self.syntax
     }
 }
-impl ast::NameOwner for BindPat {}
-impl BindPat {
-    pub fn pat(&self) -> Option<Pat> {
-        super::child_opt(self)
+impl ArrayExpr {
+    pub fn exprs(&self) -> impl Iterator<Item = Expr> {
+        super::children(self)
     }
 }
 
-// BlockExpr
+// ArrayType
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct BlockExpr {
+pub struct ArrayType {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for BlockExpr {
+impl AstNode for ArrayType {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            BLOCK_EXPR => true,
+            ARRAY_TYPE => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(BlockExpr { syntax })
+            Some(ArrayType { syntax })
         } else {
             None
         }
@@ -130,9 +130,9 @@ impl AstNode for BlockExpr {
         &self.syntax
     }
 }
-impl BlockExpr {
-    pub fn statements(&self) -> impl Iterator<Item = Stmt> {
-        super::children(self)
+impl ArrayType {
+    pub fn type_ref(&self) -> Option<TypeRef> {
+        super::child_opt(self)
     }
 
     pub fn expr(&self) -> Option<Expr> {
@@ -140,23 +140,23 @@ impl BlockExpr {
     }
 }
 
-// BreakExpr
+// Attr
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct BreakExpr {
+pub struct Attr {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for BreakExpr {
+impl AstNode for Attr {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            BREAK_EXPR => true,
+            ATTR => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(BreakExpr { syntax })
+            Some(Attr { syntax })
         } else {
             None
         }
@@ -165,29 +165,33 @@ impl AstNode for BreakExpr {
         &self.syntax
     }
 }
-impl BreakExpr {
-    pub fn expr(&self) -> Option<Expr> {
+impl Attr {
+    pub fn name_ref(&self) -> Option<NameRef> {
+        super::child_opt(self)
+    }
+
+    pub fn attr_arg_list(&self) -> Option<AttrArgList> {
         super::child_opt(self)
     }
 }
 
-// CallExpr
+// AttrArg
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct CallExpr {
+pub struct AttrArg {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for CallExpr {
+impl AstNode for AttrArg {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            CALL_EXPR => true,
+            ATTR_ARG => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(CallExpr { syntax })
+            Some(AttrArg { syntax })
         } else {
             None
         }
@@ -196,30 +200,37 @@ impl AstNode for CallExpr {
         &self.syntax
     }
 }
-impl ast::ArgListOwner for CallExpr {}
-impl CallExpr {
-    pub fn expr(&self) -> Option<Expr> {
+impl AttrArg {
+    pub fn name_ref(&self) -> Option<NameRef> {
+        super::child_opt(self)
+    }
+
+    pub fn attr_arg_list(&self) -> Option<AttrArgList> {
+        super::child_opt(self)
+    }
+
+    pub fn literal(&self) -> Option<Literal> {
         super::child_opt(self)
     }
 }
 
-// Condition
+// AttrArgList
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct Condition {
+pub struct AttrArgList {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for Condition {
+impl AstNode for AttrArgList {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            CONDITION => true,
+            ATTR_ARG_LIST => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(Condition { syntax })
+            Some(AttrArgList { syntax })
         } else {
             None
         }
@@ -228,34 +239,29 @@ impl AstNode for Condition {
         &self.syntax
     }
 }
-impl Condition {
-    pub fn pat(&self) -> Option<Pat> {
-        super::child_opt(self)
-    }
-
-    pub fn expr(&self) -> Option<Expr> {
-        super::child_opt(self)
+impl AttrArgList {
+    pub fn args(&self) -> impl Iterator<Item = AttrArg> {
+        super::children(self)
     }
 }
 
-// Expr
+// BinExpr
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct Expr {
+pub struct BinExpr {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for Expr {
+impl AstNode for BinExpr {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            LITERAL | PREFIX_EXPR | PATH_EXPR | BIN_EXPR | PAREN_EXPR | CALL_EXPR | IF_EXPR
-            | LOOP_EXPR | WHILE_EXPR | RETURN_EXPR | BREAK_EXPR | BLOCK_EXPR => true,
+            BIN_EXPR => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(Expr { syntax })
+            Some(BinExpr { syntax })
         } else {
             None
         }
@@ -264,121 +270,25 @@ impl AstNode for Expr {
         &self.syntax
     }
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum ExprKind {
-    Literal(Literal),
-    PrefixExpr(PrefixExpr),
-    PathExpr(PathExpr),
-    BinExpr(BinExpr),
-    ParenExpr(ParenExpr),
-    CallExpr(CallExpr),
-    IfExpr(IfExpr),
-    LoopExpr(LoopExpr),
-    WhileExpr(WhileExpr),
-    ReturnExpr(ReturnExpr),
-    BreakExpr(BreakExpr),
-    BlockExpr(BlockExpr),
-}
-impl From<Literal> for Expr {
-    fn from(n: Literal) -> Expr {
-        Expr { syntax: n.syntax }
-    }
-}
-impl From<PrefixExpr> for Expr {
-    fn from(n: PrefixExpr) -> Expr {
-        Expr { syntax: n.syntax }
-    }
-}
-impl From<PathExpr> for Expr {
-    fn from(n: PathExpr) -> Expr {
-        Expr { syntax: n.syntax }
-    }
-}
-impl From<BinExpr> for Expr {
-    fn from(n: BinExpr) -> Expr {
-        Expr { syntax: n.syntax }
-    }
-}
-impl From<ParenExpr> for Expr {
-    fn from(n: ParenExpr) -> Expr {
-        Expr { syntax: n.syntax }
-    }
-}
-impl From<CallExpr> for Expr {
-    fn from(n: CallExpr) -> Expr {
-        Expr { syntax: n.syntax }
-    }
-}
-impl From<IfExpr> for Expr {
-    fn from(n: IfExpr) -> Expr {
-        Expr { syntax: n.syntax }
-    }
-}
-impl From<LoopExpr> for Expr {
-    fn from(n: LoopExpr) -> Expr {
-        Expr { syntax: n.syntax }
-    }
-}
-impl From<WhileExpr> for Expr {
-    fn from(n: WhileExpr) -> Expr {
-        Expr { syntax: n.syntax }
-    }
-}
-impl From<ReturnExpr> for Expr {
-    fn from(n: ReturnExpr) -> Expr {
-        Expr { syntax: n.syntax }
-    }
-}
-impl From<BreakExpr> for Expr {
-    fn from(n: BreakExpr) -> Expr {
-        Expr { syntax: n.syntax }
-    }
-}
-impl From<BlockExpr> for Expr {
-    fn from(n: BlockExpr) -> Expr {
-        Expr { syntax: n.syntax }
-    }
-}
-
-impl Expr {
-    pub fn kind(&self) -> ExprKind {
-        match self.syntax.kind() {
-            LITERAL => ExprKind::Literal(Literal::cast(self.syntax.clone()).unwrap()),
-            PREFIX_EXPR => ExprKind::PrefixExpr(PrefixExpr::cast(self.syntax.clone()).unwrap()),
-            PATH_EXPR => ExprKind::PathExpr(PathExpr::cast(self.syntax.clone()).unwrap()),
-            BIN_EXPR => ExprKind::BinExpr(BinExpr::cast(self.syntax.clone()).unwrap()),
-            PAREN_EXPR => ExprKind::ParenExpr(ParenExpr::cast(self.syntax.clone()).unwrap()),
-            CALL_EXPR => ExprKind::CallExpr(CallExpr::cast(self.syntax.clone()).unwrap()),
-            IF_EXPR => ExprKind::IfExpr(IfExpr::cast(self.syntax.clone()).unwrap()),
-            LOOP_EXPR => ExprKind::LoopExpr(LoopExpr::cast(self.syntax.clone()).unwrap()),
-            WHILE_EXPR => ExprKind::WhileExpr(WhileExpr::cast(self.syntax.clone()).unwrap()),
-            RETURN_EXPR => ExprKind::ReturnExpr(ReturnExpr::cast(self.syntax.clone()).unwrap()),
-            BREAK_EXPR => ExprKind::BreakExpr(BreakExpr::cast(self.syntax.clone()).unwrap()),
-            BLOCK_EXPR => ExprKind::BlockExpr(BlockExpr::cast(self.syntax.clone()).unwrap()),
-            _ => unreachable!(),
-        }
-    }
-}
-
-impl Expr {}
+impl BinExpr {}
 
-// ExprStmt
+// BindPat
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct ExprStmt {
+pub struct BindPat {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for ExprStmt {
+impl AstNode for BindPat {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            EXPR_STMT => true,
+            BIND_PAT => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(ExprStmt { syntax })
+            Some(BindPat { syntax })
         } else {
             None
         }
@@ -387,29 +297,30 @@ impl AstNode for ExprStmt {
         &self.syntax
     }
 }
-impl ExprStmt {
-    pub fn expr(&self) -> Option<Expr> {
+impl ast::NameOwner for BindPat {}
+impl BindPat {
+    pub fn pat(&self) -> Option<Pat> {
         super::child_opt(self)
     }
 }
 
-// FunctionDef
+// BlockExpr
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct FunctionDef {
+pub struct BlockExpr {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for FunctionDef {
+impl AstNode for BlockExpr {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            FUNCTION_DEF => true,
+            BLOCK_EXPR => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(FunctionDef { syntax })
+            Some(BlockExpr { syntax })
         } else {
             None
         }
@@ -418,40 +329,33 @@ impl AstNode for FunctionDef {
         &self.syntax
     }
 }
-impl ast::NameOwner for FunctionDef {}
-impl ast::VisibilityOwner for FunctionDef {}
-impl ast::DocCommentsOwner for FunctionDef {}
-impl FunctionDef {
-    pub fn param_list(&self) -> Option<ParamList> {
-        super::child_opt(self)
-    }
-
-    pub fn body(&self) -> Option<BlockExpr> {
-        super::child_opt(self)
+impl BlockExpr {
+    pub fn statements(&self) -> impl Iterator<Item = Stmt> {
+        super::children(self)
     }
 
-    pub fn ret_type(&self) -> Option<RetType> {
+    pub fn expr(&self) -> Option<Expr> {
         super::child_opt(self)
     }
 }
 
-// IfExpr
+// BreakExpr
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct IfExpr {
+pub struct BreakExpr {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for IfExpr {
+impl AstNode for BreakExpr {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            IF_EXPR => true,
+            BREAK_EXPR => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(IfExpr { syntax })
+            Some(BreakExpr { syntax })
         } else {
             None
         }
@@ -460,29 +364,813 @@ impl AstNode for IfExpr {
         &self.syntax
     }
 }
-impl IfExpr {
-    pub fn condition(&self) -> Option<Condition> {
+impl BreakExpr {
+    pub fn expr(&self) -> Option<Expr> {
         super::child_opt(self)
     }
 }
 
-// LetStmt
+// CallExpr
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct LetStmt {
+pub struct CallExpr {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for LetStmt {
+impl AstNode for CallExpr {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            LET_STMT => true,
+            CALL_EXPR => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(LetStmt { syntax })
+            Some(CallExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ast::ArgListOwner for CallExpr {}
+impl CallExpr {
+    pub fn expr(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+}
+
+// CastExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CastExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for CastExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            CAST_EXPR => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(CastExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl CastExpr {
+    pub fn expr(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+
+    pub fn type_ref(&self) -> Option<TypeRef> {
+        super::child_opt(self)
+    }
+}
+
+// ClosureExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClosureExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for ClosureExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            CLOSURE_EXPR => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(ClosureExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ClosureExpr {
+    pub fn param_list(&self) -> Option<ParamList> {
+        super::child_opt(self)
+    }
+
+    pub fn ret_type(&self) -> Option<RetType> {
+        super::child_opt(self)
+    }
+
+    pub fn body(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+}
+
+// Condition
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Condition {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for Condition {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            CONDITION => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Condition { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl Condition {
+    pub fn pat(&self) -> Option<Pat> {
+        super::child_opt(self)
+    }
+
+    pub fn expr(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+}
+
+// ConstDef
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConstDef {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for ConstDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            CONST_DEF => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(ConstDef { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ast::NameOwner for ConstDef {}
+impl ast::VisibilityOwner for ConstDef {}
+impl ast::DocCommentsOwner for ConstDef {}
+impl ast::TypeAscriptionOwner for ConstDef {}
+impl ConstDef {
+    pub fn body(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+}
+
+// ContinueExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ContinueExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for ContinueExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            CONTINUE_EXPR => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(ContinueExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ContinueExpr {}
+
+// EnumDef
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EnumDef {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for EnumDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            ENUM_DEF => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(EnumDef { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ast::NameOwner for EnumDef {}
+impl ast::VisibilityOwner for EnumDef {}
+impl ast::DocCommentsOwner for EnumDef {}
+impl EnumDef {
+    pub fn enum_variant_list(&self) -> Option<EnumVariantList> {
+        super::child_opt(self)
+    }
+}
+
+// EnumVariant
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EnumVariant {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for EnumVariant {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            ENUM_VARIANT => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(EnumVariant { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ast::NameOwner for EnumVariant {}
+impl ast::DocCommentsOwner for EnumVariant {}
+impl EnumVariant {
+    pub fn expr(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+}
+
+// EnumVariantList
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EnumVariantList {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for EnumVariantList {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            ENUM_VARIANT_LIST => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(EnumVariantList { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl EnumVariantList {
+    pub fn variants(&self) -> impl Iterator<Item = EnumVariant> {
+        super::children(self)
+    }
+}
+
+// Expr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Expr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for Expr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            LITERAL | PREFIX_EXPR | PATH_EXPR | BIN_EXPR | RANGE_EXPR | PAREN_EXPR | TUPLE_EXPR
+            | ARRAY_EXPR | CALL_EXPR | METHOD_CALL_EXPR | CAST_EXPR | FIELD_EXPR | INDEX_EXPR
+            | RECORD_LIT | IF_EXPR | LOOP_EXPR | WHILE_EXPR | FOR_EXPR | RETURN_EXPR
+            | BREAK_EXPR | CONTINUE_EXPR | BLOCK_EXPR | MATCH_EXPR | CLOSURE_EXPR => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Expr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExprKind {
+    Literal(Literal),
+    PrefixExpr(PrefixExpr),
+    PathExpr(PathExpr),
+    BinExpr(BinExpr),
+    RangeExpr(RangeExpr),
+    ParenExpr(ParenExpr),
+    TupleExpr(TupleExpr),
+    ArrayExpr(ArrayExpr),
+    CallExpr(CallExpr),
+    MethodCallExpr(MethodCallExpr),
+    CastExpr(CastExpr),
+    FieldExpr(FieldExpr),
+    IndexExpr(IndexExpr),
+    RecordLit(RecordLit),
+    IfExpr(IfExpr),
+    LoopExpr(LoopExpr),
+    WhileExpr(WhileExpr),
+    ForExpr(ForExpr),
+    ReturnExpr(ReturnExpr),
+    BreakExpr(BreakExpr),
+    ContinueExpr(ContinueExpr),
+    BlockExpr(BlockExpr),
+    MatchExpr(MatchExpr),
+    ClosureExpr(ClosureExpr),
+}
+impl From<Literal> for Expr {
+    fn from(n: Literal) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<PrefixExpr> for Expr {
+    fn from(n: PrefixExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<PathExpr> for Expr {
+    fn from(n: PathExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<BinExpr> for Expr {
+    fn from(n: BinExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<RangeExpr> for Expr {
+    fn from(n: RangeExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<ParenExpr> for Expr {
+    fn from(n: ParenExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<TupleExpr> for Expr {
+    fn from(n: TupleExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<ArrayExpr> for Expr {
+    fn from(n: ArrayExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<CallExpr> for Expr {
+    fn from(n: CallExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<MethodCallExpr> for Expr {
+    fn from(n: MethodCallExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<CastExpr> for Expr {
+    fn from(n: CastExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<FieldExpr> for Expr {
+    fn from(n: FieldExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<IndexExpr> for Expr {
+    fn from(n: IndexExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<RecordLit> for Expr {
+    fn from(n: RecordLit) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<IfExpr> for Expr {
+    fn from(n: IfExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<LoopExpr> for Expr {
+    fn from(n: LoopExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<WhileExpr> for Expr {
+    fn from(n: WhileExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<ForExpr> for Expr {
+    fn from(n: ForExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<ReturnExpr> for Expr {
+    fn from(n: ReturnExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<BreakExpr> for Expr {
+    fn from(n: BreakExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<ContinueExpr> for Expr {
+    fn from(n: ContinueExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<BlockExpr> for Expr {
+    fn from(n: BlockExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<MatchExpr> for Expr {
+    fn from(n: MatchExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<ClosureExpr> for Expr {
+    fn from(n: ClosureExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+
+impl Expr {
+    pub fn kind(&self) -> ExprKind {
+        match self.syntax.kind() {
+            LITERAL => ExprKind::Literal(Literal::cast(self.syntax.clone()).unwrap()),
+            PREFIX_EXPR => ExprKind::PrefixExpr(PrefixExpr::cast(self.syntax.clone()).unwrap()),
+            PATH_EXPR => ExprKind::PathExpr(PathExpr::cast(self.syntax.clone()).unwrap()),
+            BIN_EXPR => ExprKind::BinExpr(BinExpr::cast(self.syntax.clone()).unwrap()),
+            RANGE_EXPR => ExprKind::RangeExpr(RangeExpr::cast(self.syntax.clone()).unwrap()),
+            PAREN_EXPR => ExprKind::ParenExpr(ParenExpr::cast(self.syntax.clone()).unwrap()),
+            TUPLE_EXPR => ExprKind::TupleExpr(TupleExpr::cast(self.syntax.clone()).unwrap()),
+            ARRAY_EXPR => ExprKind::ArrayExpr(ArrayExpr::cast(self.syntax.clone()).unwrap()),
+            CALL_EXPR => ExprKind::CallExpr(CallExpr::cast(self.syntax.clone()).unwrap()),
+            METHOD_CALL_EXPR => {
+                ExprKind::MethodCallExpr(MethodCallExpr::cast(self.syntax.clone()).unwrap())
+            }
+            CAST_EXPR => ExprKind::CastExpr(CastExpr::cast(self.syntax.clone()).unwrap()),
+            FIELD_EXPR => ExprKind::FieldExpr(FieldExpr::cast(self.syntax.clone()).unwrap()),
+            INDEX_EXPR => ExprKind::IndexExpr(IndexExpr::cast(self.syntax.clone()).unwrap()),
+            RECORD_LIT => ExprKind::RecordLit(RecordLit::cast(self.syntax.clone()).unwrap()),
+            IF_EXPR => ExprKind::IfExpr(IfExpr::cast(self.syntax.clone()).unwrap()),
+            LOOP_EXPR => ExprKind::LoopExpr(LoopExpr::cast(self.syntax.clone()).unwrap()),
+            WHILE_EXPR => ExprKind::WhileExpr(WhileExpr::cast(self.syntax.clone()).unwrap()),
+            FOR_EXPR => ExprKind::ForExpr(ForExpr::cast(self.syntax.clone()).unwrap()),
+            RETURN_EXPR => ExprKind::ReturnExpr(ReturnExpr::cast(self.syntax.clone()).unwrap()),
+            BREAK_EXPR => ExprKind::BreakExpr(BreakExpr::cast(self.syntax.clone()).unwrap()),
+            CONTINUE_EXPR => {
+                ExprKind::ContinueExpr(ContinueExpr::cast(self.syntax.clone()).unwrap())
+            }
+            BLOCK_EXPR => ExprKind::BlockExpr(BlockExpr::cast(self.syntax.clone()).unwrap()),
+            MATCH_EXPR => ExprKind::MatchExpr(MatchExpr::cast(self.syntax.clone()).unwrap()),
+            CLOSURE_EXPR => ExprKind::ClosureExpr(ClosureExpr::cast(self.syntax.clone()).unwrap()),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Expr {}
+
+// ExprStmt
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExprStmt {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for ExprStmt {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            EXPR_STMT => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(ExprStmt { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ExprStmt {
+    pub fn expr(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+}
+
+// FieldExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FieldExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for FieldExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            FIELD_EXPR => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(FieldExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl FieldExpr {
+    pub fn expr(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+
+    pub fn name_ref(&self) -> Option<NameRef> {
+        super::child_opt(self)
+    }
+}
+
+// FnPointerType
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FnPointerType {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for FnPointerType {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            FN_POINTER_TYPE => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(FnPointerType { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl FnPointerType {
+    pub fn params(&self) -> impl Iterator<Item = TypeRef> {
+        super::children(self)
+    }
+
+    pub fn ret_type(&self) -> Option<RetType> {
+        super::child_opt(self)
+    }
+}
+
+// ForExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ForExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for ForExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            FOR_EXPR => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(ForExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ast::LoopBodyOwner for ForExpr {}
+impl ForExpr {
+    pub fn pat(&self) -> Option<Pat> {
+        super::child_opt(self)
+    }
+}
+
+// FunctionDef
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FunctionDef {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for FunctionDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            FUNCTION_DEF => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(FunctionDef { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ast::NameOwner for FunctionDef {}
+impl ast::VisibilityOwner for FunctionDef {}
+impl ast::DocCommentsOwner for FunctionDef {}
+impl ast::AttrsOwner for FunctionDef {}
+impl FunctionDef {
+    pub fn param_list(&self) -> Option<ParamList> {
+        super::child_opt(self)
+    }
+
+    pub fn body(&self) -> Option<BlockExpr> {
+        super::child_opt(self)
+    }
+
+    pub fn ret_type(&self) -> Option<RetType> {
+        super::child_opt(self)
+    }
+}
+
+// IfExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IfExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for IfExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            IF_EXPR => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(IfExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl IfExpr {
+    pub fn condition(&self) -> Option<Condition> {
+        super::child_opt(self)
+    }
+}
+
+// ImplBlock
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImplBlock {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for ImplBlock {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            IMPL_BLOCK => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(ImplBlock { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ImplBlock {
+    pub fn functions(&self) -> impl Iterator<Item = FunctionDef> {
+        super::children(self)
+    }
+
+    pub fn type_ref(&self) -> Option<TypeRef> {
+        super::child_opt(self)
+    }
+}
+
+// IndexExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IndexExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for IndexExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            INDEX_EXPR => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(IndexExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl IndexExpr {}
+
+// LetStmt
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LetStmt {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for LetStmt {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            LET_STMT => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(LetStmt { syntax })
         } else {
             None
         }
@@ -496,29 +1184,589 @@ impl LetStmt {
     pub fn pat(&self) -> Option<Pat> {
         super::child_opt(self)
     }
-
-    pub fn initializer(&self) -> Option<Expr> {
+
+    pub fn initializer(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+}
+
+// Literal
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Literal {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for Literal {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            LITERAL => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Literal { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl Literal {}
+
+// LiteralPat
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LiteralPat {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for LiteralPat {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            LITERAL_PAT => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(LiteralPat { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl LiteralPat {
+    pub fn expr(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+}
+
+// LoopExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LoopExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for LoopExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            LOOP_EXPR => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(LoopExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ast::LoopBodyOwner for LoopExpr {}
+impl LoopExpr {}
+
+// MatchArm
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MatchArm {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for MatchArm {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            MATCH_ARM => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(MatchArm { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl MatchArm {
+    pub fn pat(&self) -> Option<Pat> {
+        super::child_opt(self)
+    }
+
+    pub fn expr(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+}
+
+// MatchArmList
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MatchArmList {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for MatchArmList {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            MATCH_ARM_LIST => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(MatchArmList { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl MatchArmList {
+    pub fn arms(&self) -> impl Iterator<Item = MatchArm> {
+        super::children(self)
+    }
+}
+
+// MatchExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MatchExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for MatchExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            MATCH_EXPR => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(MatchExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl MatchExpr {
+    pub fn expr(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+
+    pub fn match_arm_list(&self) -> Option<MatchArmList> {
+        super::child_opt(self)
+    }
+}
+
+// MemoryTypeSpecifier
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MemoryTypeSpecifier {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for MemoryTypeSpecifier {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            MEMORY_TYPE_SPECIFIER => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(MemoryTypeSpecifier { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl MemoryTypeSpecifier {}
+
+// MethodCallExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MethodCallExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for MethodCallExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            METHOD_CALL_EXPR => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(MethodCallExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ast::ArgListOwner for MethodCallExpr {}
+impl MethodCallExpr {
+    pub fn expr(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+
+    pub fn name_ref(&self) -> Option<NameRef> {
+        super::child_opt(self)
+    }
+}
+
+// ModuleItem
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ModuleItem {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for ModuleItem {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            FUNCTION_DEF | STRUCT_DEF | ENUM_DEF | CONST_DEF | STATIC_DEF | TYPE_ALIAS_DEF
+            | IMPL_BLOCK | USE_DECL => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(ModuleItem { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModuleItemKind {
+    FunctionDef(FunctionDef),
+    StructDef(StructDef),
+    EnumDef(EnumDef),
+    ConstDef(ConstDef),
+    StaticDef(StaticDef),
+    TypeAliasDef(TypeAliasDef),
+    ImplBlock(ImplBlock),
+    UseDecl(UseDecl),
+}
+impl From<FunctionDef> for ModuleItem {
+    fn from(n: FunctionDef) -> ModuleItem {
+        ModuleItem { syntax: n.syntax }
+    }
+}
+impl From<StructDef> for ModuleItem {
+    fn from(n: StructDef) -> ModuleItem {
+        ModuleItem { syntax: n.syntax }
+    }
+}
+impl From<EnumDef> for ModuleItem {
+    fn from(n: EnumDef) -> ModuleItem {
+        ModuleItem { syntax: n.syntax }
+    }
+}
+impl From<ConstDef> for ModuleItem {
+    fn from(n: ConstDef) -> ModuleItem {
+        ModuleItem { syntax: n.syntax }
+    }
+}
+impl From<StaticDef> for ModuleItem {
+    fn from(n: StaticDef) -> ModuleItem {
+        ModuleItem { syntax: n.syntax }
+    }
+}
+impl From<TypeAliasDef> for ModuleItem {
+    fn from(n: TypeAliasDef) -> ModuleItem {
+        ModuleItem { syntax: n.syntax }
+    }
+}
+impl From<ImplBlock> for ModuleItem {
+    fn from(n: ImplBlock) -> ModuleItem {
+        ModuleItem { syntax: n.syntax }
+    }
+}
+impl From<UseDecl> for ModuleItem {
+    fn from(n: UseDecl) -> ModuleItem {
+        ModuleItem { syntax: n.syntax }
+    }
+}
+
+impl ModuleItem {
+    pub fn kind(&self) -> ModuleItemKind {
+        match self.syntax.kind() {
+            FUNCTION_DEF => {
+                ModuleItemKind::FunctionDef(FunctionDef::cast(self.syntax.clone()).unwrap())
+            }
+            STRUCT_DEF => ModuleItemKind::StructDef(StructDef::cast(self.syntax.clone()).unwrap()),
+            ENUM_DEF => ModuleItemKind::EnumDef(EnumDef::cast(self.syntax.clone()).unwrap()),
+            CONST_DEF => ModuleItemKind::ConstDef(ConstDef::cast(self.syntax.clone()).unwrap()),
+            STATIC_DEF => ModuleItemKind::StaticDef(StaticDef::cast(self.syntax.clone()).unwrap()),
+            TYPE_ALIAS_DEF => {
+                ModuleItemKind::TypeAliasDef(TypeAliasDef::cast(self.syntax.clone()).unwrap())
+            }
+            IMPL_BLOCK => ModuleItemKind::ImplBlock(ImplBlock::cast(self.syntax.clone()).unwrap()),
+            USE_DECL => ModuleItemKind::UseDecl(UseDecl::cast(self.syntax.clone()).unwrap()),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl ModuleItem {}
+
+// Name
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Name {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for Name {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            NAME => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Name { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl Name {}
+
+// NameRef
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NameRef {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for NameRef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            NAME_REF => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(NameRef { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl NameRef {}
+
+// NeverType
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NeverType {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for NeverType {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            NEVER_TYPE => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(NeverType { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl NeverType {}
+
+// Param
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Param {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for Param {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            PARAM => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Param { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ast::TypeAscriptionOwner for Param {}
+impl Param {
+    pub fn pat(&self) -> Option<Pat> {
+        super::child_opt(self)
+    }
+}
+
+// ParamList
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParamList {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for ParamList {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            PARAM_LIST => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(ParamList { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ParamList {
+    pub fn params(&self) -> impl Iterator<Item = Param> {
+        super::children(self)
+    }
+
+    pub fn self_param(&self) -> Option<SelfParam> {
+        super::child_opt(self)
+    }
+}
+
+// ParenExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParenExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for ParenExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            PAREN_EXPR => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(ParenExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ParenExpr {
+    pub fn expr(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+}
+
+// ParenType
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParenType {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for ParenType {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            PAREN_TYPE => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(ParenType { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ParenType {
+    pub fn type_ref(&self) -> Option<TypeRef> {
         super::child_opt(self)
     }
 }
 
-// Literal
+// Pat
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct Literal {
+pub struct Pat {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for Literal {
+impl AstNode for Pat {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            LITERAL => true,
+            BIND_PAT | PLACEHOLDER_PAT | LITERAL_PAT | PATH_PAT => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(Literal { syntax })
+            Some(Pat { syntax })
         } else {
             None
         }
@@ -527,25 +1775,67 @@ impl AstNode for Literal {
         &self.syntax
     }
 }
-impl Literal {}
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatKind {
+    BindPat(BindPat),
+    PlaceholderPat(PlaceholderPat),
+    LiteralPat(LiteralPat),
+    PathPat(PathPat),
+}
+impl From<BindPat> for Pat {
+    fn from(n: BindPat) -> Pat {
+        Pat { syntax: n.syntax }
+    }
+}
+impl From<PlaceholderPat> for Pat {
+    fn from(n: PlaceholderPat) -> Pat {
+        Pat { syntax: n.syntax }
+    }
+}
+impl From<LiteralPat> for Pat {
+    fn from(n: LiteralPat) -> Pat {
+        Pat { syntax: n.syntax }
+    }
+}
+impl From<PathPat> for Pat {
+    fn from(n: PathPat) -> Pat {
+        Pat { syntax: n.syntax }
+    }
+}
 
-// LoopExpr
+impl Pat {
+    pub fn kind(&self) -> PatKind {
+        match self.syntax.kind() {
+            BIND_PAT => PatKind::BindPat(BindPat::cast(self.syntax.clone()).unwrap()),
+            PLACEHOLDER_PAT => {
+                PatKind::PlaceholderPat(PlaceholderPat::cast(self.syntax.clone()).unwrap())
+            }
+            LITERAL_PAT => PatKind::LiteralPat(LiteralPat::cast(self.syntax.clone()).unwrap()),
+            PATH_PAT => PatKind::PathPat(PathPat::cast(self.syntax.clone()).unwrap()),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Pat {}
+
+// Path
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct LoopExpr {
+pub struct Path {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for LoopExpr {
+impl AstNode for Path {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            LOOP_EXPR => true,
+            PATH => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(LoopExpr { syntax })
+            Some(Path { syntax })
         } else {
             None
         }
@@ -554,26 +1844,33 @@ impl AstNode for LoopExpr {
         &self.syntax
     }
 }
-impl ast::LoopBodyOwner for LoopExpr {}
-impl LoopExpr {}
+impl Path {
+    pub fn segment(&self) -> Option<PathSegment> {
+        super::child_opt(self)
+    }
 
-// ModuleItem
+    pub fn qualifier(&self) -> Option<Path> {
+        super::child_opt(self)
+    }
+}
+
+// PathExpr
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct ModuleItem {
+pub struct PathExpr {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for ModuleItem {
+impl AstNode for PathExpr {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            FUNCTION_DEF => true,
+            PATH_EXPR => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(ModuleItem { syntax })
+            Some(PathExpr { syntax })
         } else {
             None
         }
@@ -582,46 +1879,122 @@ impl AstNode for ModuleItem {
         &self.syntax
     }
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum ModuleItemKind {
-    FunctionDef(FunctionDef),
+impl PathExpr {
+    pub fn path(&self) -> Option<Path> {
+        super::child_opt(self)
+    }
 }
-impl From<FunctionDef> for ModuleItem {
-    fn from(n: FunctionDef) -> ModuleItem {
-        ModuleItem { syntax: n.syntax }
+
+// PathPat
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PathPat {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for PathPat {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            PATH_PAT => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(PathPat { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl PathPat {
+    pub fn path(&self) -> Option<Path> {
+        super::child_opt(self)
     }
 }
 
-impl ModuleItem {
-    pub fn kind(&self) -> ModuleItemKind {
-        match self.syntax.kind() {
-            FUNCTION_DEF => {
-                ModuleItemKind::FunctionDef(FunctionDef::cast(self.syntax.clone()).unwrap())
-            }
-            _ => unreachable!(),
+// PathSegment
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PathSegment {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for PathSegment {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            PATH_SEGMENT => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(PathSegment { syntax })
+        } else {
+            None
         }
     }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl PathSegment {
+    pub fn name_ref(&self) -> Option<NameRef> {
+        super::child_opt(self)
+    }
 }
 
-impl ModuleItem {}
+// PathType
 
-// Name
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PathType {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for PathType {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            PATH_TYPE => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(PathType { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl PathType {
+    pub fn path(&self) -> Option<Path> {
+        super::child_opt(self)
+    }
+}
+
+// PlaceholderPat
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct Name {
+pub struct PlaceholderPat {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for Name {
+impl AstNode for PlaceholderPat {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            NAME => true,
+            PLACEHOLDER_PAT => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(Name { syntax })
+            Some(PlaceholderPat { syntax })
         } else {
             None
         }
@@ -630,25 +2003,25 @@ impl AstNode for Name {
         &self.syntax
     }
 }
-impl Name {}
+impl PlaceholderPat {}
 
-// NameRef
+// PrefixExpr
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct NameRef {
+pub struct PrefixExpr {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for NameRef {
+impl AstNode for PrefixExpr {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            NAME_REF => true,
+            PREFIX_EXPR => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(NameRef { syntax })
+            Some(PrefixExpr { syntax })
         } else {
             None
         }
@@ -657,25 +2030,29 @@ impl AstNode for NameRef {
         &self.syntax
     }
 }
-impl NameRef {}
+impl PrefixExpr {
+    pub fn expr(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+}
 
-// NeverType
+// RangeExpr
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct NeverType {
+pub struct RangeExpr {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for NeverType {
+impl AstNode for RangeExpr {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            NEVER_TYPE => true,
+            RANGE_EXPR => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(NeverType { syntax })
+            Some(RangeExpr { syntax })
         } else {
             None
         }
@@ -684,25 +2061,25 @@ impl AstNode for NeverType {
         &self.syntax
     }
 }
-impl NeverType {}
+impl RangeExpr {}
 
-// Param
+// RecordField
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct Param {
+pub struct RecordField {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for Param {
+impl AstNode for RecordField {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            PARAM => true,
+            RECORD_FIELD => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(Param { syntax })
+            Some(RecordField { syntax })
         } else {
             None
         }
@@ -711,30 +2088,63 @@ impl AstNode for Param {
         &self.syntax
     }
 }
-impl ast::TypeAscriptionOwner for Param {}
-impl Param {
-    pub fn pat(&self) -> Option<Pat> {
+impl RecordField {
+    pub fn name_ref(&self) -> Option<NameRef> {
         super::child_opt(self)
     }
+
+    pub fn expr(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+}
+
+// RecordFieldDef
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RecordFieldDef {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for RecordFieldDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            RECORD_FIELD_DEF => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(RecordFieldDef { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
 }
+impl ast::NameOwner for RecordFieldDef {}
+impl ast::TypeAscriptionOwner for RecordFieldDef {}
+impl ast::DocCommentsOwner for RecordFieldDef {}
+impl RecordFieldDef {}
 
-// ParamList
+// RecordFieldDefList
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct ParamList {
+pub struct RecordFieldDefList {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for ParamList {
+impl AstNode for RecordFieldDefList {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            PARAM_LIST => true,
+            RECORD_FIELD_DEF_LIST => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(ParamList { syntax })
+            Some(RecordFieldDefList { syntax })
         } else {
             None
         }
@@ -743,29 +2153,29 @@ impl AstNode for ParamList {
         &self.syntax
     }
 }
-impl ParamList {
-    pub fn params(&self) -> impl Iterator<Item = Param> {
+impl RecordFieldDefList {
+    pub fn fields(&self) -> impl Iterator<Item = RecordFieldDef> {
         super::children(self)
     }
 }
 
-// ParenExpr
+// RecordFieldList
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct ParenExpr {
+pub struct RecordFieldList {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for ParenExpr {
+impl AstNode for RecordFieldList {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            PAREN_EXPR => true,
+            RECORD_FIELD_LIST => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(ParenExpr { syntax })
+            Some(RecordFieldList { syntax })
         } else {
             None
         }
@@ -774,29 +2184,29 @@ impl AstNode for ParenExpr {
         &self.syntax
     }
 }
-impl ParenExpr {
-    pub fn expr(&self) -> Option<Expr> {
-        super::child_opt(self)
+impl RecordFieldList {
+    pub fn fields(&self) -> impl Iterator<Item = RecordField> {
+        super::children(self)
     }
 }
 
-// Pat
+// RecordLit
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct Pat {
+pub struct RecordLit {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for Pat {
+impl AstNode for RecordLit {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            BIND_PAT | PLACEHOLDER_PAT => true,
+            RECORD_LIT => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(Pat { syntax })
+            Some(RecordLit { syntax })
         } else {
             None
         }
@@ -805,53 +2215,33 @@ impl AstNode for Pat {
         &self.syntax
     }
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum PatKind {
-    BindPat(BindPat),
-    PlaceholderPat(PlaceholderPat),
-}
-impl From<BindPat> for Pat {
-    fn from(n: BindPat) -> Pat {
-        Pat { syntax: n.syntax }
-    }
-}
-impl From<PlaceholderPat> for Pat {
-    fn from(n: PlaceholderPat) -> Pat {
-        Pat { syntax: n.syntax }
+impl RecordLit {
+    pub fn path(&self) -> Option<Path> {
+        super::child_opt(self)
     }
-}
 
-impl Pat {
-    pub fn kind(&self) -> PatKind {
-        match self.syntax.kind() {
-            BIND_PAT => PatKind::BindPat(BindPat::cast(self.syntax.clone()).unwrap()),
-            PLACEHOLDER_PAT => {
-                PatKind::PlaceholderPat(PlaceholderPat::cast(self.syntax.clone()).unwrap())
-            }
-            _ => unreachable!(),
-        }
+    pub fn record_field_list(&self) -> Option<RecordFieldList> {
+        super::child_opt(self)
     }
 }
 
-impl Pat {}
-
-// Path
+// RetType
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct Path {
+pub struct RetType {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for Path {
+impl AstNode for RetType {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            PATH => true,
+            RET_TYPE => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(Path { syntax })
+            Some(RetType { syntax })
         } else {
             None
         }
@@ -860,33 +2250,29 @@ impl AstNode for Path {
         &self.syntax
     }
 }
-impl Path {
-    pub fn segment(&self) -> Option<PathSegment> {
-        super::child_opt(self)
-    }
-
-    pub fn qualifier(&self) -> Option<Path> {
+impl RetType {
+    pub fn type_ref(&self) -> Option<TypeRef> {
         super::child_opt(self)
     }
 }
 
-// PathExpr
+// ReturnExpr
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct PathExpr {
+pub struct ReturnExpr {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for PathExpr {
+impl AstNode for ReturnExpr {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            PATH_EXPR => true,
+            RETURN_EXPR => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(PathExpr { syntax })
+            Some(ReturnExpr { syntax })
         } else {
             None
         }
@@ -895,29 +2281,29 @@ impl AstNode for PathExpr {
         &self.syntax
     }
 }
-impl PathExpr {
-    pub fn path(&self) -> Option<Path> {
+impl ReturnExpr {
+    pub fn expr(&self) -> Option<Expr> {
         super::child_opt(self)
     }
 }
 
-// PathSegment
+// SelfParam
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct PathSegment {
+pub struct SelfParam {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for PathSegment {
+impl AstNode for SelfParam {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            PATH_SEGMENT => true,
+            SELF_PARAM => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(PathSegment { syntax })
+            Some(SelfParam { syntax })
         } else {
             None
         }
@@ -926,29 +2312,25 @@ impl AstNode for PathSegment {
         &self.syntax
     }
 }
-impl PathSegment {
-    pub fn name_ref(&self) -> Option<NameRef> {
-        super::child_opt(self)
-    }
-}
+impl SelfParam {}
 
-// PathType
+// SourceFile
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct PathType {
+pub struct SourceFile {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for PathType {
+impl AstNode for SourceFile {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            PATH_TYPE => true,
+            SOURCE_FILE => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(PathType { syntax })
+            Some(SourceFile { syntax })
         } else {
             None
         }
@@ -957,29 +2339,28 @@ impl AstNode for PathType {
         &self.syntax
     }
 }
-impl PathType {
-    pub fn path(&self) -> Option<Path> {
-        super::child_opt(self)
-    }
-}
+impl ast::ModuleItemOwner for SourceFile {}
+impl ast::FunctionDefOwner for SourceFile {}
+impl ast::AttrsOwner for SourceFile {}
+impl SourceFile {}
 
-// PlaceholderPat
+// StaticDef
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct PlaceholderPat {
+pub struct StaticDef {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for PlaceholderPat {
+impl AstNode for StaticDef {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            PLACEHOLDER_PAT => true,
+            STATIC_DEF => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(PlaceholderPat { syntax })
+            Some(StaticDef { syntax })
         } else {
             None
         }
@@ -988,25 +2369,33 @@ impl AstNode for PlaceholderPat {
         &self.syntax
     }
 }
-impl PlaceholderPat {}
+impl ast::NameOwner for StaticDef {}
+impl ast::VisibilityOwner for StaticDef {}
+impl ast::DocCommentsOwner for StaticDef {}
+impl ast::TypeAscriptionOwner for StaticDef {}
+impl StaticDef {
+    pub fn body(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+}
 
-// PrefixExpr
+// Stmt
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct PrefixExpr {
+pub struct Stmt {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for PrefixExpr {
+impl AstNode for Stmt {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            PREFIX_EXPR => true,
+            LET_STMT | EXPR_STMT => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(PrefixExpr { syntax })
+            Some(Stmt { syntax })
         } else {
             None
         }
@@ -1015,29 +2404,51 @@ impl AstNode for PrefixExpr {
         &self.syntax
     }
 }
-impl PrefixExpr {
-    pub fn expr(&self) -> Option<Expr> {
-        super::child_opt(self)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StmtKind {
+    LetStmt(LetStmt),
+    ExprStmt(ExprStmt),
+}
+impl From<LetStmt> for Stmt {
+    fn from(n: LetStmt) -> Stmt {
+        Stmt { syntax: n.syntax }
+    }
+}
+impl From<ExprStmt> for Stmt {
+    fn from(n: ExprStmt) -> Stmt {
+        Stmt { syntax: n.syntax }
     }
 }
 
-// RetType
+impl Stmt {
+    pub fn kind(&self) -> StmtKind {
+        match self.syntax.kind() {
+            LET_STMT => StmtKind::LetStmt(LetStmt::cast(self.syntax.clone()).unwrap()),
+            EXPR_STMT => StmtKind::ExprStmt(ExprStmt::cast(self.syntax.clone()).unwrap()),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Stmt {}
+
+// StructDef
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct RetType {
+pub struct StructDef {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for RetType {
+impl AstNode for StructDef {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            RET_TYPE => true,
+            STRUCT_DEF => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(RetType { syntax })
+            Some(StructDef { syntax })
         } else {
             None
         }
@@ -1046,29 +2457,33 @@ impl AstNode for RetType {
         &self.syntax
     }
 }
-impl RetType {
-    pub fn type_ref(&self) -> Option<TypeRef> {
+impl ast::NameOwner for StructDef {}
+impl ast::VisibilityOwner for StructDef {}
+impl ast::DocCommentsOwner for StructDef {}
+impl ast::AttrsOwner for StructDef {}
+impl StructDef {
+    pub fn memory_type_specifier(&self) -> Option<MemoryTypeSpecifier> {
         super::child_opt(self)
     }
 }
 
-// ReturnExpr
+// TupleExpr
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct ReturnExpr {
+pub struct TupleExpr {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for ReturnExpr {
+impl AstNode for TupleExpr {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            RETURN_EXPR => true,
+            TUPLE_EXPR => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(ReturnExpr { syntax })
+            Some(TupleExpr { syntax })
         } else {
             None
         }
@@ -1077,29 +2492,29 @@ impl AstNode for ReturnExpr {
         &self.syntax
     }
 }
-impl ReturnExpr {
-    pub fn expr(&self) -> Option<Expr> {
-        super::child_opt(self)
+impl TupleExpr {
+    pub fn exprs(&self) -> impl Iterator<Item = Expr> {
+        super::children(self)
     }
 }
 
-// SourceFile
+// TupleType
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct SourceFile {
+pub struct TupleType {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for SourceFile {
+impl AstNode for TupleType {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            SOURCE_FILE => true,
+            TUPLE_TYPE => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(SourceFile { syntax })
+            Some(TupleType { syntax })
         } else {
             None
         }
@@ -1108,27 +2523,29 @@ impl AstNode for SourceFile {
         &self.syntax
     }
 }
-impl ast::ModuleItemOwner for SourceFile {}
-impl ast::FunctionDefOwner for SourceFile {}
-impl SourceFile {}
+impl TupleType {
+    pub fn fields(&self) -> impl Iterator<Item = TypeRef> {
+        super::children(self)
+    }
+}
 
-// Stmt
+// TypeAliasDef
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct Stmt {
+pub struct TypeAliasDef {
     pub(crate) syntax: SyntaxNode,
 }
 
-impl AstNode for Stmt {
+impl AstNode for TypeAliasDef {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            LET_STMT | EXPR_STMT => true,
+            TYPE_ALIAS_DEF => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         if Self::can_cast(syntax.kind()) {
-            Some(Stmt { syntax })
+            Some(TypeAliasDef { syntax })
         } else {
             None
         }
@@ -1137,34 +2554,15 @@ impl AstNode for Stmt {
         &self.syntax
     }
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum StmtKind {
-    LetStmt(LetStmt),
-    ExprStmt(ExprStmt),
-}
-impl From<LetStmt> for Stmt {
-    fn from(n: LetStmt) -> Stmt {
-        Stmt { syntax: n.syntax }
-    }
-}
-impl From<ExprStmt> for Stmt {
-    fn from(n: ExprStmt) -> Stmt {
-        Stmt { syntax: n.syntax }
-    }
-}
-
-impl Stmt {
-    pub fn kind(&self) -> StmtKind {
-        match self.syntax.kind() {
-            LET_STMT => StmtKind::LetStmt(LetStmt::cast(self.syntax.clone()).unwrap()),
-            EXPR_STMT => StmtKind::ExprStmt(ExprStmt::cast(self.syntax.clone()).unwrap()),
-            _ => unreachable!(),
-        }
+impl ast::NameOwner for TypeAliasDef {}
+impl ast::VisibilityOwner for TypeAliasDef {}
+impl ast::DocCommentsOwner for TypeAliasDef {}
+impl TypeAliasDef {
+    pub fn type_ref(&self) -> Option<TypeRef> {
+        super::child_opt(self)
     }
 }
 
-impl Stmt {}
-
 // TypeRef
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1175,7 +2573,7 @@ pub struct TypeRef {
 impl AstNode for TypeRef {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            PATH_TYPE | NEVER_TYPE => true,
+            PATH_TYPE | NEVER_TYPE | PAREN_TYPE | TUPLE_TYPE | ARRAY_TYPE | FN_POINTER_TYPE => true,
             _ => false,
         }
     }
@@ -1194,6 +2592,10 @@ impl AstNode for TypeRef {
 pub enum TypeRefKind {
     PathType(PathType),
     NeverType(NeverType),
+    ParenType(ParenType),
+    TupleType(TupleType),
+    ArrayType(ArrayType),
+    FnPointerType(FnPointerType),
 }
 impl From<PathType> for TypeRef {
     fn from(n: PathType) -> TypeRef {
@@ -1205,12 +2607,38 @@ impl From<NeverType> for TypeRef {
         TypeRef { syntax: n.syntax }
     }
 }
+impl From<ParenType> for TypeRef {
+    fn from(n: ParenType) -> TypeRef {
+        TypeRef { syntax: n.syntax }
+    }
+}
+impl From<TupleType> for TypeRef {
+    fn from(n: TupleType) -> TypeRef {
+        TypeRef { syntax: n.syntax }
+    }
+}
+impl From<ArrayType> for TypeRef {
+    fn from(n: ArrayType) -> TypeRef {
+        TypeRef { syntax: n.syntax }
+    }
+}
+impl From<FnPointerType> for TypeRef {
+    fn from(n: FnPointerType) -> TypeRef {
+        TypeRef { syntax: n.syntax }
+    }
+}
 
 impl TypeRef {
     pub fn kind(&self) -> TypeRefKind {
         match self.syntax.kind() {
             PATH_TYPE => TypeRefKind::PathType(PathType::cast(self.syntax.clone()).unwrap()),
             NEVER_TYPE => TypeRefKind::NeverType(NeverType::cast(self.syntax.clone()).unwrap()),
+            PAREN_TYPE => TypeRefKind::ParenType(ParenType::cast(self.syntax.clone()).unwrap()),
+            TUPLE_TYPE => TypeRefKind::TupleType(TupleType::cast(self.syntax.clone()).unwrap()),
+            ARRAY_TYPE => TypeRefKind::ArrayType(ArrayType::cast(self.syntax.clone()).unwrap()),
+            FN_POINTER_TYPE => {
+                TypeRefKind::FnPointerType(FnPointerType::cast(self.syntax.clone()).unwrap())
+            }
             _ => unreachable!(),
         }
     }
@@ -1218,6 +2646,41 @@ impl TypeRef {
 
 impl TypeRef {}
 
+// UseDecl
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UseDecl {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for UseDecl {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            USE_DECL => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(UseDecl { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl UseDecl {
+    pub fn path(&self) -> Option<Path> {
+        super::child_opt(self)
+    }
+
+    pub fn alias(&self) -> Option<Alias> {
+        super::child_opt(self)
+    }
+}
+
 // Visibility
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
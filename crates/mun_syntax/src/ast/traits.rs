@@ -31,6 +31,12 @@ pub trait VisibilityOwner: AstNode {
     }
 }
 
+pub trait AttrsOwner: AstNode {
+    fn attrs(&self) -> AstChildren<ast::Attr> {
+        children(self)
+    }
+}
+
 pub trait LoopBodyOwner: AstNode {
     fn loop_body(&self) -> Option<ast::BlockExpr> {
         child_opt(self)
@@ -49,6 +55,39 @@ pub trait DocCommentsOwner: AstNode {
             iter: self.syntax().children_with_tokens(),
         }
     }
+
+    /// Returns the textual content of the outer doc comments (e.g. `///`) as a single string.
+    /// The comment prefix and a single leading whitespace character are stripped from every
+    /// line, and lines are joined with newlines.
+    fn doc_comment_text(&self) -> Option<String> {
+        let lines: Vec<String> = self
+            .doc_comments()
+            .filter(|comment| comment.kind().doc == Some(ast::CommentPlacement::Outer))
+            .map(|comment| {
+                let prefix_len = comment.prefix().len();
+                let line = comment.text().as_str();
+
+                // Determine whether only the prefix, or the prefix and a space are stripped
+                let start = if line[prefix_len..].starts_with(char::is_whitespace) {
+                    prefix_len + 1
+                } else {
+                    prefix_len
+                };
+                let end = if comment.kind().shape.is_block() && line.ends_with("*/") {
+                    line.len() - 2
+                } else {
+                    line.len()
+                };
+                line[start.min(end)..end].trim_end().to_owned()
+            })
+            .collect();
+
+        if lines.is_empty() {
+            None
+        } else {
+            Some(lines.join("\n"))
+        }
+    }
 }
 
 pub struct CommentIter {
@@ -0,0 +1,392 @@
+//! Formats Mun source code in a canonical style.
+//!
+//! The formatter works on the tokens of the syntax tree. All whitespace of the source is discarded
+//! and recreated from the structure of the tree, except for blank lines, which are kept (at most
+//! one in a row), and the line breaks after the opening delimiter of a list, which tell whether the
+//! author laid out the list over multiple lines. As the output only depends on those, formatting
+//! formatted code does not change it.
+
+use crate::{
+    syntax_node::NodeOrToken, AstNode, SourceFile, SyntaxElement, SyntaxError, SyntaxKind,
+    SyntaxKind::*, SyntaxNode, SyntaxToken,
+};
+use std::collections::HashMap;
+
+/// The whitespace that is added in front of a line for each level of nesting.
+const INDENT: &str = "    ";
+
+/// Formats the Mun source `text`. The syntax errors of `text` are returned instead if it does not
+/// parse, as its structure is unknown and formatting it could change its meaning or lose code.
+pub fn format(text: &str) -> Result<String, Vec<SyntaxError>> {
+    let parse = SourceFile::parse(text);
+    if !parse.errors().is_empty() {
+        return Err(parse.errors().to_vec());
+    }
+    Ok(Formatter::new(parse.tree().syntax()).format())
+}
+
+/// A token of the source, without the whitespace in front of it.
+struct Token {
+    token: SyntaxToken,
+    /// The number of line breaks in the whitespace in front of the token
+    newlines: usize,
+}
+
+impl Token {
+    fn kind(&self) -> SyntaxKind {
+        self.token.kind()
+    }
+}
+
+/// A node whose elements are placed between delimiters, e.g. the statements of a block or the
+/// arguments of a call. The elements of a broken container are placed on lines of their own, which
+/// are indented one level deeper than the delimiters.
+struct Container {
+    open: SyntaxToken,
+    close: SyntaxToken,
+    /// Whether the elements are separated by commas, which the last element of a broken list is
+    /// followed by as well
+    is_list: bool,
+    is_broken: bool,
+}
+
+impl Container {
+    /// Returns the container of `node`, if it is one.
+    fn of(node: &SyntaxNode) -> Option<Container> {
+        let is_list = match node.kind() {
+            BLOCK_EXPR | IMPL_BLOCK | MATCH_ARM_LIST => false,
+            RECORD_FIELD_DEF_LIST
+            | ENUM_VARIANT_LIST
+            | RECORD_FIELD_LIST
+            | PARAM_LIST
+            | ARG_LIST
+            | ATTR_ARG_LIST
+            | TUPLE_EXPR
+            | TUPLE_TYPE
+            | ARRAY_EXPR => true,
+            _ => return None,
+        };
+
+        // The parameter list of a closure is delimited by pipes and is never broken
+        let tokens: Vec<SyntaxToken> = node
+            .children_with_tokens()
+            .filter_map(|element| element.into_token())
+            .collect();
+        let open = tokens
+            .iter()
+            .find(|token| [T!['{'], T!['('], T!['[']].contains(&token.kind()))?
+            .clone();
+        let close = tokens
+            .iter()
+            .rev()
+            .find(|token| [T!['}'], T![')'], T![']']].contains(&token.kind()))?
+            .clone();
+
+        let mut elements = node.children_with_tokens().filter(|element| {
+            element.text_range().start() >= open.text_range().end()
+                && element.text_range().end() <= close.text_range().start()
+                && element.kind() != WHITESPACE
+                && element.kind() != T![,]
+        });
+        let is_broken = if is_list {
+            // A list stays on a single line, unless its author started its elements on a new line
+            // or a comment has to be placed in it
+            elements.next().is_some()
+                && (open.next_token().map_or(false, |token| {
+                    token.kind() == WHITESPACE && token.text().contains('\n')
+                }) || node
+                    .descendants_with_tokens()
+                    .any(|element| element.kind() == COMMENT))
+        } else {
+            elements.next().is_some()
+        };
+
+        Some(Container {
+            open,
+            close,
+            is_list,
+            is_broken,
+        })
+    }
+
+    /// Returns true if the comma at the end of the list is required, as the list would otherwise
+    /// be a parenthesized expression or type, e.g. `(a,)`.
+    fn requires_trailing_comma(&self) -> bool {
+        let node = self.open.parent();
+        (node.kind() == TUPLE_EXPR || node.kind() == TUPLE_TYPE) && node.children().count() == 1
+    }
+}
+
+/// Where a token is placed relative to the line structure of the code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineStart {
+    /// The token starts an item of the source file
+    Item,
+    /// The token starts an element of a broken container, e.g. a statement of a block
+    Element,
+    /// The token is the closing delimiter of a broken container
+    Close,
+}
+
+/// Prints the tokens of a syntax tree with canonical whitespace.
+struct Formatter {
+    tokens: Vec<Token>,
+    containers: HashMap<SyntaxNode, Container>,
+    output: String,
+    indent: usize,
+}
+
+impl Formatter {
+    fn new(root: &SyntaxNode) -> Formatter {
+        let mut tokens = Vec::new();
+        let mut newlines = 0;
+        for token in root
+            .descendants_with_tokens()
+            .filter_map(|element| element.into_token())
+        {
+            if token.kind() == WHITESPACE {
+                newlines += token.text().matches('\n').count();
+            } else {
+                tokens.push(Token { token, newlines });
+                newlines = 0;
+            }
+        }
+
+        let containers = root
+            .descendants()
+            .filter_map(|node| Container::of(&node).map(|container| (node, container)))
+            .collect();
+
+        Formatter {
+            tokens,
+            containers,
+            output: String::new(),
+            indent: 0,
+        }
+    }
+
+    fn format(mut self) -> String {
+        let mut prev = None;
+        for index in 0..self.tokens.len() {
+            if self.is_redundant_comma(index) {
+                continue;
+            }
+
+            let token = &self.tokens[index].token;
+            let broken = self
+                .containers
+                .get(&token.parent())
+                .filter(|container| container.is_broken);
+            let opens = broken.map_or(false, |container| container.open == *token);
+            if broken.map_or(false, |container| container.close == *token) {
+                self.indent -= 1;
+            }
+
+            match prev {
+                None => {}
+                Some(prev) => match self.newlines_between(prev, index) {
+                    0 => {
+                        if needs_space(&self.tokens[prev].token, &self.tokens[index].token) {
+                            self.output.push(' ');
+                        }
+                    }
+                    newlines => {
+                        self.output.push_str(&"\n".repeat(newlines));
+                        self.output.push_str(&INDENT.repeat(self.indent));
+                    }
+                },
+            }
+
+            let token = &self.tokens[index].token;
+            if is_line_comment(token) {
+                self.output.push_str(token.text().trim_end());
+            } else {
+                self.output.push_str(token.text());
+            }
+            if opens {
+                self.indent += 1;
+            }
+            if self.is_missing_comma(index) {
+                self.output.push(',');
+            }
+            prev = Some(index);
+        }
+
+        if !self.output.is_empty() {
+            self.output.push('\n');
+        }
+        self.output
+    }
+
+    /// Returns the number of line breaks that are placed between the tokens at `prev` and `next`.
+    fn newlines_between(&self, prev: usize, next: usize) -> usize {
+        let (prev, next) = (&self.tokens[prev], &self.tokens[next]);
+        // Code after a block comment on the same line stays there, e.g. `/* unused */ a`
+        if is_trailing_comment(&next.token)
+            || (next.newlines == 0 && prev.kind() == COMMENT && !is_line_comment(&prev.token))
+        {
+            return 0;
+        }
+
+        // At most a single blank line is kept
+        let kept = next.newlines.max(1).min(2);
+        match self.line_start(&next.token) {
+            Some(LineStart::Close) => 1,
+            Some(LineStart::Item) => {
+                // Items are separated by a blank line, except for a run of single-line items of the
+                // same kind, and the comments in front of an item
+                let grouped = match (top_level_item(&prev.token), top_level_item(&next.token)) {
+                    (Some(a), Some(b)) => {
+                        a.kind() == b.kind()
+                            && [USE_DECL, CONST_DEF, STATIC_DEF, TYPE_ALIAS_DEF, ATTR]
+                                .contains(&a.kind())
+                    }
+                    _ => false,
+                };
+                if grouped || (prev.kind() == COMMENT && !is_trailing_comment(&prev.token)) {
+                    kept
+                } else {
+                    2
+                }
+            }
+            Some(LineStart::Element) if is_opening_delimiter(&prev.token) => 1,
+            Some(LineStart::Element) => kept,
+            None if next.kind() == COMMENT && !is_opening_delimiter(&prev.token) => kept,
+            None if next.kind() == COMMENT => 1,
+            None if is_line_comment(&prev.token) => 1,
+            None if prev.kind() == T![']'] && prev.token.parent().kind() == ATTR => 1,
+            None => 0,
+        }
+    }
+
+    /// Returns how `token` is placed relative to the line structure of the code, if it starts a
+    /// line.
+    fn line_start(&self, token: &SyntaxToken) -> Option<LineStart> {
+        let mut element = SyntaxElement::Token(token.clone());
+        loop {
+            let parent = element.parent()?;
+            if parent.kind() == SOURCE_FILE {
+                return Some(LineStart::Item);
+            }
+            if let Some(container) = self
+                .containers
+                .get(&parent)
+                .filter(|container| container.is_broken)
+            {
+                let is_element = match &element {
+                    NodeOrToken::Node(_) => true,
+                    NodeOrToken::Token(token) if *token == container.close => {
+                        return Some(LineStart::Close);
+                    }
+                    NodeOrToken::Token(token) => token.kind() == COMMENT,
+                };
+                if is_element && element.text_range().start() >= container.open.text_range().end() {
+                    return Some(LineStart::Element);
+                }
+            }
+            if first_token(&parent).as_ref() != Some(token) {
+                return None;
+            }
+            element = NodeOrToken::Node(parent);
+        }
+    }
+
+    /// Returns the broken list whose closing delimiter is the first token after `index` that is
+    /// not a comment.
+    fn list_closed_after(&self, index: usize) -> Option<&Container> {
+        let next = self.tokens[index + 1..]
+            .iter()
+            .find(|token| token.kind() != COMMENT)?;
+        self.containers
+            .get(&next.token.parent())
+            .filter(|container| container.is_list && container.close == next.token)
+    }
+
+    /// Returns true if the token at `index` is the comma after the last element of a list that is
+    /// laid out on a single line.
+    fn is_redundant_comma(&self, index: usize) -> bool {
+        self.tokens[index].kind() == T![,]
+            && self.list_closed_after(index).map_or(false, |list| {
+                !list.is_broken
+                    && list.open.parent() == self.tokens[index].token.parent()
+                    && !list.requires_trailing_comma()
+            })
+    }
+
+    /// Returns true if the token at `index` ends the last element of a broken list, which is not
+    /// followed by a comma yet.
+    fn is_missing_comma(&self, index: usize) -> bool {
+        let token = &self.tokens[index].token;
+        token.kind() != COMMENT
+            && token.kind() != T![,]
+            && self
+                .list_closed_after(index)
+                .map_or(false, |list| list.is_broken && list.open != *token)
+    }
+}
+
+/// Returns the first token of `node` that is neither whitespace nor a comment placed after the
+/// code in front of the node.
+fn first_token(node: &SyntaxNode) -> Option<SyntaxToken> {
+    node.descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .find(|token| token.kind() != WHITESPACE && !is_trailing_comment(token))
+}
+
+/// Returns true if `token` is a comment on the same line as the code in front of it.
+fn is_trailing_comment(token: &SyntaxToken) -> bool {
+    token.kind() == COMMENT
+        && token.prev_token().map_or(false, |prev| {
+            prev.kind() != WHITESPACE || !prev.text().contains('\n')
+        })
+}
+
+/// Returns the item of the source file that contains `token`, if any.
+fn top_level_item(token: &SyntaxToken) -> Option<SyntaxNode> {
+    token.parent().ancestors().find(|node| {
+        node.parent()
+            .map_or(false, |parent| parent.kind() == SOURCE_FILE)
+    })
+}
+
+fn is_line_comment(token: &SyntaxToken) -> bool {
+    token.kind() == COMMENT && token.text().starts_with("//")
+}
+
+fn is_opening_delimiter(token: &SyntaxToken) -> bool {
+    [T!['{'], T!['('], T!['[']].contains(&token.kind())
+}
+
+/// Returns true if the adjacent tokens `prev` and `next` on the same line are separated by a space.
+fn needs_space(prev: &SyntaxToken, next: &SyntaxToken) -> bool {
+    let is_first = |token: &SyntaxToken| first_token(&token.parent()).as_ref() == Some(token);
+    let is_last = |token: &SyntaxToken| token.parent().last_token().as_ref() == Some(token);
+    match prev.kind() {
+        T!['('] | T!['['] | T![.] | T![#] | T![::] | T![..] | T![..=] => return false,
+        T!['{'] if next.kind() == T!['}'] => return false,
+        // Prefix operators, e.g. `-a` or `!a`
+        T![-] | T![!] if [PREFIX_EXPR, LITERAL_PAT].contains(&prev.parent().kind()) => {
+            return false
+        }
+        // The opening pipe of the parameters of a closure, e.g. `|a| a + 1`
+        T![|] if prev.parent().kind() == PARAM_LIST && is_first(prev) => return false,
+        _ => {}
+    }
+    match next.kind() {
+        T![,] | T![;] | T![:] | T![')'] | T![']'] | T![.] | T![..] | T![..=] => false,
+        // A path that starts at the root of the package keeps the space in front of it, e.g.
+        // `use ::math`
+        T![::] => next.parent().kind() == PATH_SEGMENT,
+        T!['('] => ![
+            ARG_LIST,
+            PARAM_LIST,
+            ATTR_ARG_LIST,
+            MEMORY_TYPE_SPECIFIER,
+            FN_POINTER_TYPE,
+        ]
+        .contains(&next.parent().kind()),
+        T!['['] => ![INDEX_EXPR, ATTR].contains(&next.parent().kind()),
+        T![|] => !(next.parent().kind() == PARAM_LIST && is_last(next)),
+        _ => true,
+    }
+}
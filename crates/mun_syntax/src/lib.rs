@@ -13,10 +13,12 @@
 mod syntax_kind;
 
 pub mod ast;
+mod formatting;
 mod parsing;
 mod ptr;
 mod syntax_error;
 mod syntax_node;
+mod validation;
 
 #[cfg(test)]
 mod tests;
@@ -25,6 +27,7 @@ use std::{fmt::Write, marker::PhantomData, sync::Arc};
 
 pub use crate::{
     ast::AstNode,
+    formatting::format,
     parsing::{lexer::Token, tokenize},
     ptr::{AstPtr, SyntaxNodePtr},
     syntax_error::{SyntaxError, SyntaxErrorKind},
@@ -124,8 +127,8 @@ pub use crate::ast::SourceFile;
 
 impl SourceFile {
     pub fn parse(text: &str) -> Parse<SourceFile> {
-        let (green, errors) = parsing::parse_text(text);
-        //errors.extend(validation::validate(&SourceFile::new(green.clone())));
+        let (green, mut errors) = parsing::parse_text(text);
+        errors.extend(validation::validate(&SyntaxNode::new_root(green.clone())));
         Parse {
             green,
             errors: Arc::new(errors),
@@ -160,8 +163,8 @@ fn api_walkthrough() {
     // `SourceFile` is the root of the syntax tree. We can iterate file's items:
     let mut func = None;
     for item in file.items() {
-        match item.kind() {
-            ast::ModuleItemKind::FunctionDef(f) => func = Some(f),
+        if let ast::ModuleItemKind::FunctionDef(f) = item.kind() {
+            func = Some(f)
         }
     }
 
@@ -1,3 +1,5 @@
+mod adt;
+mod attributes;
 mod declarations;
 mod expressions;
 mod params;
@@ -13,6 +15,7 @@ use super::{
 
 pub(crate) fn root(p: &mut Parser) {
     let m = p.start();
+    attributes::inner_attributes(p);
     declarations::mod_contents(p);
     m.complete(p, SOURCE_FILE);
 }
@@ -70,6 +73,6 @@ fn error_block(p: &mut Parser, message: &str) {
     p.error(message);
     p.bump(T!['{']);
     expressions::expr_block_contents(p);
-    p.eat(T!['{']);
+    p.eat(T!['}']);
     m.complete(p, ERROR);
 }
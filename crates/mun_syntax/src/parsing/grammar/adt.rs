@@ -0,0 +1,124 @@
+use super::*;
+
+pub(super) fn struct_def(p: &mut Parser, m: Marker) {
+    assert!(p.at(T![struct]));
+    p.bump(T![struct]);
+    if p.at(T!['(']) {
+        memory_type_specifier(p);
+    }
+
+    name_recovery(p, declarations::DECLARATION_RECOVERY_SET);
+    match p.current() {
+        T![;] => {
+            p.bump(T![;]);
+        }
+        T!['{'] => record_field_def_list(p),
+        _ => {
+            p.error("expected a ';', or '{'");
+        }
+    }
+    m.complete(p, STRUCT_DEF);
+}
+
+/// Parses the memory type of a struct, e.g. `(gc)` in `struct(gc) Foo`.
+fn memory_type_specifier(p: &mut Parser) {
+    assert!(p.at(T!['(']));
+    let m = p.start();
+    p.bump(T!['(']);
+    if p.at_contextual_kw("gc") || p.at_contextual_kw("value") {
+        p.bump(IDENT);
+    } else if p.at(IDENT) {
+        p.error_and_bump("expected memory type specifier `gc` or `value`");
+    } else {
+        p.error("expected memory type specifier `gc` or `value`");
+    }
+    p.expect(T![')']);
+    m.complete(p, MEMORY_TYPE_SPECIFIER);
+}
+
+pub(super) fn record_field_def_list(p: &mut Parser) {
+    assert!(p.at(T!['{']));
+    let m = p.start();
+    p.bump(T!['{']);
+    while !p.at(T!['}']) && !p.at(EOF) {
+        // A field can be exported, so `export` only starts a declaration if it isn't followed by
+        // the name of a field
+        if p.at_ts(declarations::DECLARATION_RECOVERY_SET)
+            && !(p.at(T![export]) && p.nth(1) == IDENT)
+        {
+            break;
+        }
+        if p.at(T!['{']) {
+            error_block(p, "expected a field");
+            continue;
+        }
+        record_field_def(p);
+        if !p.at(T!['}']) {
+            p.expect(T![,]);
+        }
+    }
+    p.expect(T!['}']);
+    m.complete(p, RECORD_FIELD_DEF_LIST);
+}
+
+fn record_field_def(p: &mut Parser) {
+    let m = p.start();
+    opt_visibility(p);
+    if p.at(IDENT) {
+        name(p);
+        p.expect(T![:]);
+        types::type_(p);
+        m.complete(p, RECORD_FIELD_DEF);
+    } else {
+        m.abandon(p);
+        p.error_and_bump("expected a field declaration");
+    }
+}
+
+pub(super) fn enum_def(p: &mut Parser, m: Marker) {
+    assert!(p.at(T![enum]));
+    p.bump(T![enum]);
+    name_recovery(
+        p,
+        declarations::DECLARATION_RECOVERY_SET.union(token_set![L_CURLY]),
+    );
+    if p.at(T!['{']) {
+        enum_variant_list(p);
+    } else {
+        p.error("expected a '{'");
+    }
+    m.complete(p, ENUM_DEF);
+}
+
+fn enum_variant_list(p: &mut Parser) {
+    assert!(p.at(T!['{']));
+    let m = p.start();
+    p.bump(T!['{']);
+    while !p.at(T!['}']) && !p.at(EOF) && !p.at_ts(declarations::DECLARATION_RECOVERY_SET) {
+        if p.at(T!['{']) {
+            error_block(p, "expected an enum variant");
+            continue;
+        }
+        enum_variant(p);
+        if !p.at(T!['}']) {
+            p.expect(T![,]);
+        }
+    }
+    p.expect(T!['}']);
+    m.complete(p, ENUM_VARIANT_LIST);
+}
+
+/// Parses a variant of an enum, e.g. `Idle` or `Walking = 1`.
+fn enum_variant(p: &mut Parser) {
+    let m = p.start();
+    if p.at(IDENT) {
+        name(p);
+        if p.eat(T![=]) {
+            expressions::expr(p);
+        }
+        m.complete(p, ENUM_VARIANT);
+    } else {
+        m.abandon(p);
+        p.error_and_bump("expected an enum variant");
+    }
+}
@@ -0,0 +1,71 @@
+use super::*;
+
+/// Parses the attributes at the start of a file that apply to the whole file, e.g.
+/// `#![deny(warnings)]`.
+pub(super) fn inner_attributes(p: &mut Parser) {
+    while p.at(T![#]) && p.nth(1) == T![!] {
+        attribute(p);
+    }
+}
+
+pub(super) fn outer_attributes(p: &mut Parser) {
+    while p.at(T![#]) {
+        if p.nth(1) == T![!] {
+            p.error("inner attributes are only allowed at the start of a file");
+        }
+        attribute(p);
+    }
+}
+
+fn attribute(p: &mut Parser) {
+    assert!(p.at(T![#]));
+    let m = p.start();
+    p.bump(T![#]);
+    p.eat(T![!]);
+    if p.expect(T!['[']) {
+        name_ref(p);
+        if p.at(T!['(']) {
+            arg_list(p);
+        }
+        p.expect(T![']']);
+    }
+    m.complete(p, ATTR);
+}
+
+fn arg_list(p: &mut Parser) {
+    assert!(p.at(T!['(']));
+    let m = p.start();
+    p.bump(T!['(']);
+    while !p.at(EOF) && !p.at(T![')']) {
+        if !p.at(IDENT) && !p.at(INT_NUMBER) && !p.at(T![never]) {
+            p.error("expected attribute argument");
+            break;
+        }
+        arg(p);
+        if !p.at(T![')']) {
+            p.expect(T![,]);
+        }
+    }
+    p.expect(T![')']);
+    m.complete(p, ATTR_ARG_LIST);
+}
+
+/// Parses an argument of an attribute, which is a name, e.g. `packed`, a name with arguments,
+/// e.g. `align(16)`, or an integer, e.g. `16`.
+fn arg(p: &mut Parser) {
+    let m = p.start();
+    if p.at(INT_NUMBER) {
+        expressions::literal(p);
+    } else if p.at(T![never]) {
+        // `never` is a keyword, but it is an ordinary name in `#[inline(never)]`
+        let name = p.start();
+        p.bump_remap(IDENT);
+        name.complete(p, NAME_REF);
+    } else {
+        name_ref(p);
+        if p.at(T!['(']) {
+            arg_list(p);
+        }
+    }
+    m.complete(p, ATTR_ARG);
+}
@@ -1,7 +1,13 @@
 use super::*;
 use crate::T;
 
-pub(super) const DECLARATION_RECOVERY_SET: TokenSet = token_set![FN_KW, EXPORT_KW];
+pub(super) const DECLARATION_RECOVERY_SET: TokenSet = token_set![
+    FN_KW, EXPORT_KW, STRUCT_KW, ENUM_KW, EXTERN_KW, CONST_KW, STATIC_KW, TYPE_KW, IMPL_KW, USE_KW,
+    HASH
+];
+
+/// The tokens that start a function in an impl block.
+const IMPL_ITEM_FIRST: TokenSet = token_set![FN_KW, EXPORT_KW, EXTERN_KW, HASH];
 
 pub(super) fn mod_contents(p: &mut Parser) {
     while !p.at(EOF) {
@@ -19,10 +25,10 @@ pub(super) fn declaration(p: &mut Parser) {
     m.abandon(p);
     if p.at(T!['{']) {
         error_block(p, "expected a declaration")
-    } else if p.at(T!['{']) {
+    } else if p.at(T!['}']) {
         let e = p.start();
         p.error("unmatched }");
-        p.bump(T!['{']);
+        p.bump(T!['}']);
         e.complete(p, ERROR);
     } else if !p.at(EOF) {
         p.error_and_bump("expected a declaration");
@@ -32,21 +38,38 @@ pub(super) fn declaration(p: &mut Parser) {
 }
 
 pub(super) fn maybe_declaration(p: &mut Parser, m: Marker) -> Result<(), Marker> {
+    let has_attributes = p.at(T![#]);
+    attributes::outer_attributes(p);
     opt_visibility(p);
 
     match p.current() {
-        T![fn] => {
+        T![fn] | T![extern] => {
             fn_def(p);
             m.complete(p, FUNCTION_DEF);
         }
+        T![struct] => adt::struct_def(p, m),
+        T![enum] | T![const] | T![static] | T![type] | T![impl] | T![use] => {
+            if has_attributes {
+                p.error("attributes are only supported on functions and structs");
+            }
+            match p.current() {
+                T![enum] => adt::enum_def(p, m),
+                T![const] => const_def(p, m),
+                T![type] => type_alias_def(p, m),
+                T![impl] => impl_block(p, m),
+                T![use] => use_decl(p, m),
+                _ => static_def(p, m),
+            }
+        }
         _ => return Err(m),
     }
     Ok(())
 }
 
 pub(super) fn fn_def(p: &mut Parser) {
-    assert!(p.at(T![fn]));
-    p.bump(T![fn]);
+    assert!(p.at(T![fn]) || p.at(T![extern]));
+    let is_extern = p.eat(T![extern]);
+    p.expect(T![fn]);
 
     name_recovery(p, DECLARATION_RECOVERY_SET.union(token_set![L_PAREN]));
 
@@ -58,10 +81,120 @@ pub(super) fn fn_def(p: &mut Parser) {
 
     opt_fn_ret_type(p);
 
-    expressions::block(p);
+    // Extern functions are provided by the host and therefore don't have a body
+    if is_extern {
+        p.expect(T![;]);
+    } else {
+        expressions::block(p);
+    }
+}
+
+/// Parses an impl block that defines the methods of a type, e.g. `impl Vector { fn len(self) {} }`.
+pub(super) fn impl_block(p: &mut Parser, m: Marker) {
+    assert!(p.at(T![impl]));
+    p.bump(T![impl]);
+    types::type_(p);
+
+    if p.at(T!['{']) {
+        p.bump(T!['{']);
+        while !p.at(EOF) && !p.at(T!['}']) {
+            // An impl block that isn't closed ends at the next declaration that isn't a function
+            if p.at_ts(DECLARATION_RECOVERY_SET) && !p.at_ts(IMPL_ITEM_FIRST) {
+                break;
+            }
+
+            let m = p.start();
+            attributes::outer_attributes(p);
+            opt_visibility(p);
+            if p.at(T![fn]) || p.at(T![extern]) {
+                fn_def(p);
+                m.complete(p, FUNCTION_DEF);
+            } else {
+                m.abandon(p);
+                if p.at(T!['{']) {
+                    error_block(p, "expected a function");
+                } else {
+                    p.error_and_bump("expected a function");
+                }
+            }
+        }
+        p.expect(T!['}']);
+    } else {
+        p.error("expected `{`");
+    }
+    m.complete(p, IMPL_BLOCK);
+}
+
+pub(super) fn const_def(p: &mut Parser, m: Marker) {
+    assert!(p.at(T![const]));
+    p.bump(T![const]);
+
+    name_recovery(p, DECLARATION_RECOVERY_SET.union(token_set![COLON, EQ]));
+    types::ascription(p);
+
+    if p.expect(T![=]) {
+        expressions::expr(p);
+    }
+    p.expect(T![;]);
+    m.complete(p, CONST_DEF);
+}
+
+pub(super) fn static_def(p: &mut Parser, m: Marker) {
+    assert!(p.at(T![static]));
+    p.bump(T![static]);
+    p.eat(T![mut]);
+
+    name_recovery(p, DECLARATION_RECOVERY_SET.union(token_set![COLON, EQ]));
+    types::ascription(p);
+
+    if p.expect(T![=]) {
+        expressions::expr(p);
+    }
+    p.expect(T![;]);
+    m.complete(p, STATIC_DEF);
+}
+
+/// Parses a type alias, e.g. `type Seconds = float;`.
+pub(super) fn type_alias_def(p: &mut Parser, m: Marker) {
+    assert!(p.at(T![type]));
+    p.bump(T![type]);
+
+    name_recovery(p, DECLARATION_RECOVERY_SET.union(token_set![EQ]));
+
+    if p.expect(T![=]) {
+        types::type_(p);
+    }
+    p.expect(T![;]);
+    m.complete(p, TYPE_ALIAS_DEF);
+}
+
+/// Parses an import, e.g. `use shapes::square::area;`, `use math::add as plus;` or the glob import
+/// `use shapes::*;`.
+pub(super) fn use_decl(p: &mut Parser, m: Marker) {
+    assert!(p.at(T![use]));
+    p.bump(T![use]);
+
+    if paths::is_path_start(p) || p.at(T![self]) {
+        paths::use_path(p);
+        if p.at(T![::]) && p.nth(2) == T![*] {
+            p.bump(T![::]);
+            p.bump(T![*]);
+        }
+    } else {
+        p.error("expected a path");
+    }
+
+    if p.at(T![as]) {
+        let alias = p.start();
+        p.bump(T![as]);
+        name_recovery(p, DECLARATION_RECOVERY_SET.union(token_set![SEMI]));
+        alias.complete(p, ALIAS);
+    }
+    p.expect(T![;]);
+    m.complete(p, USE_DECL);
 }
 
-fn opt_fn_ret_type(p: &mut Parser) -> bool {
+pub(super) fn opt_fn_ret_type(p: &mut Parser) -> bool {
     if p.at(T![:]) {
         let m = p.start();
         p.bump(T![:]);
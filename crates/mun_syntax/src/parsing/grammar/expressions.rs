@@ -4,17 +4,25 @@ use crate::parsing::grammar::paths::PATH_FIRST;
 pub(crate) const LITERAL_FIRST: TokenSet =
     token_set![TRUE_KW, FALSE_KW, INT_NUMBER, FLOAT_NUMBER, STRING];
 
-const EXPR_RECOVERY_SET: TokenSet = token_set![LET_KW];
+/// The tokens on which a missing expression is reported without consuming them. A statement or
+/// declaration that follows is still parsed, and a block ends at the start of a declaration.
+const EXPR_RECOVERY_SET: TokenSet =
+    token_set![LET_KW, SEMI].union(declarations::DECLARATION_RECOVERY_SET);
 
 const ATOM_EXPR_FIRST: TokenSet = LITERAL_FIRST.union(PATH_FIRST).union(token_set![
     IDENT,
     T!['('],
+    T!['['],
     T!['{'],
     T![if],
     T![loop],
     T![return],
     T![break],
+    T![continue],
     T![while],
+    T![for],
+    T![match],
+    T![|],
 ]);
 
 const LHS_FIRST: TokenSet = ATOM_EXPR_FIRST.union(token_set![EXCLAMATION, MINUS]);
@@ -35,6 +43,12 @@ struct Restrictions {
 
 pub(crate) fn expr_block_contents(p: &mut Parser) {
     while !p.at(EOF) && !p.at(T!['}']) {
+        // Declarations can't be nested in a block, so a block that isn't closed ends at the next
+        // declaration instead of swallowing it
+        if p.at_ts(declarations::DECLARATION_RECOVERY_SET) {
+            break;
+        }
+
         if p.eat(T![;]) {
             continue;
         }
@@ -131,6 +145,12 @@ fn expr_bp(p: &mut Parser, r: Restrictions, bp: u8) -> Option<CompletedMarker> {
     };
 
     loop {
+        // A cast binds tighter than any binary operator, e.g. `a + b as u8` casts `b`
+        if p.at(T![as]) && bp <= CAST_BP {
+            lhs = cast_expr(p, lhs);
+            continue;
+        }
+
         let (op_bp, op) = current_op(p);
         if op_bp < bp {
             break;
@@ -140,12 +160,27 @@ fn expr_bp(p: &mut Parser, r: Restrictions, bp: u8) -> Option<CompletedMarker> {
         p.bump(op);
 
         expr_bp(p, r, op_bp + 1);
-        lhs = m.complete(p, BIN_EXPR);
+        let kind = match op {
+            T![..] | T![..=] => RANGE_EXPR,
+            _ => BIN_EXPR,
+        };
+        lhs = m.complete(p, kind);
     }
 
     Some(lhs)
 }
 
+/// The binding power of a cast, e.g. `a as u8`.
+const CAST_BP: u8 = 12;
+
+fn cast_expr(p: &mut Parser, lhs: CompletedMarker) -> CompletedMarker {
+    assert!(p.at(T![as]));
+    let m = lhs.precede(p);
+    p.bump(T![as]);
+    types::type_(p);
+    m.complete(p, CAST_EXPR)
+}
+
 fn current_op(p: &Parser) -> (u8, SyntaxKind) {
     match p.current() {
         T![+] if p.at(T![+=]) => (1, T![+=]),
@@ -156,11 +191,19 @@ fn current_op(p: &Parser) -> (u8, SyntaxKind) {
         T![*] => (11, T![*]),
         T![/] if p.at(T![/=]) => (1, T![/=]),
         T![/] => (11, T![/]),
+        T![%] if p.at(T![%=]) => (1, T![%=]),
+        T![%] => (11, T![%]),
+        T![.] if p.at(T![..=]) => (2, T![..=]),
+        T![.] if p.at(T![..]) => (2, T![..]),
+        T![|] if p.at(T![||]) => (3, T![||]),
+        T![&] if p.at(T![&&]) => (4, T![&&]),
         T![=] if p.at(T![==]) => (5, T![==]),
         T![=] => (1, T![=]),
         T![!] if p.at(T![!=]) => (5, T![!=]),
+        T![>] if p.at(T![>>]) => (9, T![>>]),
         T![>] if p.at(T![>=]) => (5, T![>=]),
         T![>] => (5, T![>]),
+        T![<] if p.at(T![<<]) => (9, T![<<]),
         T![<] if p.at(T![<=]) => (5, T![<=]),
         T![<] => (5, T![<]),
         _ => (0, T![_]),
@@ -188,6 +231,11 @@ fn postfix_expr(p: &mut Parser, mut lhs: CompletedMarker) -> CompletedMarker {
     loop {
         lhs = match p.current() {
             T!['('] => call_expr(p, lhs),
+            T![.] if !p.at(T![..]) && p.nth(1) == IDENT && p.nth(2) == T!['('] => {
+                method_call_expr(p, lhs)
+            }
+            T![.] if !p.at(T![..]) => field_expr(p, lhs),
+            T!['['] => index_expr(p, lhs),
             _ => break,
         }
     }
@@ -201,6 +249,40 @@ fn call_expr(p: &mut Parser, lhs: CompletedMarker) -> CompletedMarker {
     m.complete(p, CALL_EXPR)
 }
 
+/// Parses a method call, e.g. `a.len()`.
+fn method_call_expr(p: &mut Parser, lhs: CompletedMarker) -> CompletedMarker {
+    assert!(p.at(T![.]) && p.nth(1) == IDENT && p.nth(2) == T!['(']);
+    let m = lhs.precede(p);
+    p.bump(T![.]);
+    name_ref(p);
+    arg_list(p);
+    m.complete(p, METHOD_CALL_EXPR)
+}
+
+fn field_expr(p: &mut Parser, lhs: CompletedMarker) -> CompletedMarker {
+    assert!(p.at(T![.]));
+    let m = lhs.precede(p);
+    p.bump(T![.]);
+    if p.at(IDENT) {
+        name_ref(p)
+    } else if p.at(INT_NUMBER) {
+        // The index of a tuple field, e.g. `a.0`
+        p.bump(INT_NUMBER)
+    } else {
+        p.error("expected field name")
+    }
+    m.complete(p, FIELD_EXPR)
+}
+
+fn index_expr(p: &mut Parser, lhs: CompletedMarker) -> CompletedMarker {
+    assert!(p.at(T!['[']));
+    let m = lhs.precede(p);
+    p.bump(T!['[']);
+    expr(p);
+    p.expect(T![']']);
+    m.complete(p, INDEX_EXPR)
+}
+
 fn arg_list(p: &mut Parser) {
     assert!(p.at(T!['(']));
     let m = p.start();
@@ -225,18 +307,24 @@ fn atom_expr(p: &mut Parser, r: Restrictions) -> Option<CompletedMarker> {
         return Some(m);
     }
 
-    if paths::is_path_start(p) {
-        return Some(path_expr(p));
+    // `self` refers to the receiver of a method
+    if paths::is_path_start(p) || p.at(T![self]) {
+        return Some(path_expr(p, r));
     }
 
     let marker = match p.current() {
-        T!['('] => paren_expr(p),
+        T!['('] => paren_or_tuple_expr(p),
+        T!['['] => array_expr(p),
         T!['{'] => block_expr(p),
         T![if] => if_expr(p),
         T![loop] => loop_expr(p),
         T![return] => ret_expr(p),
         T![while] => while_expr(p),
+        T![for] => for_expr(p),
+        T![match] => match_expr(p),
         T![break] => break_expr(p, r),
+        T![continue] => continue_expr(p),
+        T![|] => closure_expr(p),
         _ => {
             p.error_recover("expected expression", EXPR_RECOVERY_SET);
             return None;
@@ -245,13 +333,46 @@ fn atom_expr(p: &mut Parser, r: Restrictions) -> Option<CompletedMarker> {
     Some(marker)
 }
 
-fn path_expr(p: &mut Parser) -> CompletedMarker {
+fn path_expr(p: &mut Parser, r: Restrictions) -> CompletedMarker {
     let m = p.start();
     paths::expr_path(p);
-    m.complete(p, PATH_EXPR)
+    if p.at(T!['{']) && !r.forbid_structs {
+        record_field_list(p);
+        m.complete(p, RECORD_LIT)
+    } else {
+        m.complete(p, PATH_EXPR)
+    }
+}
+
+/// Parses the fields of a record literal, e.g. `{ a: 1, b: 2 }`.
+fn record_field_list(p: &mut Parser) {
+    assert!(p.at(T!['{']));
+    let m = p.start();
+    p.bump(T!['{']);
+    while !p.at(EOF) && !p.at(T!['}']) && !p.at_ts(declarations::DECLARATION_RECOVERY_SET) {
+        match p.current() {
+            IDENT => {
+                let m = p.start();
+                name_ref(p);
+                p.expect(T![:]);
+                expr(p);
+                m.complete(p, RECORD_FIELD);
+            }
+            T!['{'] => {
+                error_block(p, "expected a field");
+                continue;
+            }
+            _ => p.error_and_bump("expected identifier"),
+        }
+        if !p.at(T!['}']) {
+            p.expect(T![,]);
+        }
+    }
+    p.expect(T!['}']);
+    m.complete(p, RECORD_FIELD_LIST);
 }
 
-fn literal(p: &mut Parser) -> Option<CompletedMarker> {
+pub(super) fn literal(p: &mut Parser) -> Option<CompletedMarker> {
     if !p.at_ts(LITERAL_FIRST) {
         return None;
     }
@@ -260,13 +381,54 @@ fn literal(p: &mut Parser) -> Option<CompletedMarker> {
     Some(m.complete(p, LITERAL))
 }
 
-fn paren_expr(p: &mut Parser) -> CompletedMarker {
+/// Parses a parenthesized expression, e.g. `(a)`, or a tuple expression, e.g. `()`, `(a,)` or
+/// `(a, b)`. Only a single expression without a trailing comma is a parenthesized expression.
+fn paren_or_tuple_expr(p: &mut Parser) -> CompletedMarker {
     assert!(p.at(T!['(']));
     let m = p.start();
     p.bump(T!['(']);
-    expr(p);
+    let mut saw_comma = false;
+    let mut saw_expr = false;
+    while !p.at(EOF) && !p.at(T![')']) {
+        saw_expr = true;
+        if !p.at_ts(EXPR_FIRST) {
+            p.error("expected expression");
+            break;
+        }
+        expr(p);
+        if !p.at(T![')']) {
+            saw_comma = true;
+            p.expect(T![,]);
+        }
+    }
     p.expect(T![')']);
-    m.complete(p, PAREN_EXPR)
+    m.complete(
+        p,
+        if saw_expr && !saw_comma {
+            PAREN_EXPR
+        } else {
+            TUPLE_EXPR
+        },
+    )
+}
+
+/// Parses an array literal, e.g. `[1, 2, 3]`.
+fn array_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(T!['[']));
+    let m = p.start();
+    p.bump(T!['[']);
+    while !p.at(EOF) && !p.at(T![']']) {
+        if !p.at_ts(EXPR_FIRST) {
+            p.error("expected expression");
+            break;
+        }
+        expr(p);
+        if !p.at(T![']']) && !p.expect(T![,]) {
+            break;
+        }
+    }
+    p.expect(T![']']);
+    m.complete(p, ARRAY_EXPR)
 }
 
 fn if_expr(p: &mut Parser) -> CompletedMarker {
@@ -315,11 +477,18 @@ fn break_expr(p: &mut Parser, r: Restrictions) -> CompletedMarker {
     let m = p.start();
     p.bump(T![break]);
     if p.at_ts(EXPR_FIRST) && !(r.forbid_structs && p.at(T!['{'])) {
-        expr(p);
+        expr_bp(p, r, 1);
     }
     m.complete(p, BREAK_EXPR)
 }
 
+fn continue_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(T![continue]));
+    let m = p.start();
+    p.bump(T![continue]);
+    m.complete(p, CONTINUE_EXPR)
+}
+
 fn while_expr(p: &mut Parser) -> CompletedMarker {
     assert!(p.at(T![while]));
     let m = p.start();
@@ -328,3 +497,76 @@ fn while_expr(p: &mut Parser) -> CompletedMarker {
     block(p);
     m.complete(p, WHILE_EXPR)
 }
+
+fn for_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(T![for]));
+    let m = p.start();
+    p.bump(T![for]);
+    patterns::pattern(p);
+    p.expect(T![in]);
+    expr_no_struct(p);
+    block(p);
+    m.complete(p, FOR_EXPR)
+}
+
+/// Parses a closure, e.g. `|a, b| a < b` or `|| 5`. The body of a closure with a return type must be
+/// a block, e.g. `|a: int|: int { a * 2 }`.
+fn closure_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(T![|]));
+    let m = p.start();
+    params::closure_param_list(p);
+    if declarations::opt_fn_ret_type(p) {
+        block(p);
+    } else if p.at_ts(EXPR_FIRST) {
+        expr(p);
+    } else {
+        p.error("expected expression");
+    }
+    m.complete(p, CLOSURE_EXPR)
+}
+
+fn match_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(T![match]));
+    let m = p.start();
+    p.bump(T![match]);
+    expr_no_struct(p);
+    if p.at(T!['{']) {
+        match_arm_list(p);
+    } else {
+        p.error("expected `{`");
+    }
+    m.complete(p, MATCH_EXPR)
+}
+
+/// Parses the arms of a match expression, e.g. `{ 0 => a, _ => b }`. Arms are separated by
+/// commas, which are optional after an arm whose expression is a block.
+fn match_arm_list(p: &mut Parser) {
+    assert!(p.at(T!['{']));
+    let m = p.start();
+    p.bump(T!['{']);
+    while !p.at(EOF) && !p.at(T!['}']) && !p.at_ts(declarations::DECLARATION_RECOVERY_SET) {
+        if p.at(T!['{']) {
+            error_block(p, "expected match arm");
+            continue;
+        }
+
+        let is_block = match_arm(p);
+        if !p.at(T!['}']) && !p.eat(T![,]) && !is_block {
+            p.error("expected `,`");
+        }
+    }
+    p.expect(T!['}']);
+    m.complete(p, MATCH_ARM_LIST);
+}
+
+/// Parses a match arm, e.g. `State::Idle => 0`. Returns whether the expression of the arm is a
+/// block.
+fn match_arm(p: &mut Parser) -> bool {
+    let m = p.start();
+    patterns::match_arm_pattern(p);
+    p.expect(T![=>]);
+    let is_block = p.at(T!['{']);
+    expr(p);
+    m.complete(p, MATCH_ARM);
+    is_block
+}
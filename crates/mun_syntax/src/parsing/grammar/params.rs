@@ -8,6 +8,12 @@ fn list(p: &mut Parser) {
     assert!(p.at(T!['(']));
     let m = p.start();
     p.bump(T!['(']);
+    if p.at(T![self]) || (p.at(T![mut]) && p.nth_at(1, T![self])) {
+        self_param(p);
+        if !p.at(T![')']) {
+            p.expect(T![,]);
+        }
+    }
     while !p.at(EOF) && !p.at(T![')']) {
         if !p.at_ts(VALUE_PARAMETER_FIRST) {
             p.error("expected value parameter");
@@ -22,8 +28,44 @@ fn list(p: &mut Parser) {
     m.complete(p, PARAM_LIST);
 }
 
+/// Parses the parameters of a closure, e.g. `|a, b: int|` or `||`. The types of the parameters are
+/// optional.
+pub(super) fn closure_param_list(p: &mut Parser) {
+    assert!(p.at(T![|]));
+    let m = p.start();
+    if !p.eat(T![||]) {
+        p.bump(T![|]);
+        while !p.at(EOF) && !p.at(T![|]) {
+            if !p.at_ts(VALUE_PARAMETER_FIRST) {
+                p.error("expected value parameter");
+                break;
+            }
+            let m = p.start();
+            patterns::pattern(p);
+            if p.at(T![:]) {
+                types::ascription(p);
+            }
+            m.complete(p, PARAM);
+            if !p.at(T![|]) {
+                p.expect(T![,]);
+            }
+        }
+        p.expect(T![|]);
+    }
+    m.complete(p, PARAM_LIST);
+}
+
 const VALUE_PARAMETER_FIRST: TokenSet = patterns::PATTERN_FIRST;
 
+/// Parses the `self` parameter of a method, which has the type of the impl block, e.g. `self` or
+/// `mut self`.
+fn self_param(p: &mut Parser) {
+    let m = p.start();
+    p.eat(T![mut]);
+    p.bump(T![self]);
+    m.complete(p, SELF_PARAM);
+}
+
 fn param(p: &mut Parser) {
     let m = p.start();
     patterns::pattern(p);
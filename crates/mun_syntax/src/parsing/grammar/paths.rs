@@ -3,10 +3,7 @@ use super::*;
 pub(super) const PATH_FIRST: TokenSet = token_set![IDENT, SELF_KW, SUPER_KW, COLONCOLON];
 
 pub(super) fn is_path_start(p: &Parser) -> bool {
-    match p.current() {
-        IDENT | T![::] => true,
-        _ => false,
-    }
+    p.at(IDENT) || p.at(T![super]) || p.at(T![::])
 }
 
 pub(super) fn type_path(p: &mut Parser) {
@@ -15,11 +12,15 @@ pub(super) fn type_path(p: &mut Parser) {
 pub(super) fn expr_path(p: &mut Parser) {
     path(p, Mode::Expr)
 }
+pub(super) fn use_path(p: &mut Parser) {
+    path(p, Mode::Use)
+}
 
 #[derive(Clone, Copy, Eq, PartialEq)]
 enum Mode {
     Type,
     Expr,
+    Use,
 }
 
 fn path(p: &mut Parser, mode: Mode) {
@@ -27,7 +28,7 @@ fn path(p: &mut Parser, mode: Mode) {
     path_segment(p, mode, true);
     let mut qualifier = path.complete(p, PATH);
     loop {
-        let import_tree = match p.nth(1) {
+        let import_tree = match p.nth(2) {
             T![*] | T!['{'] => true,
             _ => false,
         };
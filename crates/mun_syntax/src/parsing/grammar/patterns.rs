@@ -2,7 +2,7 @@ use super::*;
 
 pub(super) const PATTERN_FIRST: TokenSet = expressions::LITERAL_FIRST
     .union(paths::PATH_FIRST)
-    .union(token_set![MINUS, UNDERSCORE]);
+    .union(token_set![MINUS, UNDERSCORE, MUT_KW]);
 
 pub(super) fn pattern(p: &mut Parser) {
     pattern_r(p, PATTERN_FIRST);
@@ -12,9 +12,21 @@ pub(super) fn pattern_r(p: &mut Parser, recovery_set: TokenSet) {
     atom_pat(p, recovery_set);
 }
 
+/// Parses the pattern of a match arm. Besides the patterns that are valid everywhere, a match arm
+/// can also match a literal, e.g. `1`, `-1` or `true`, or a path, e.g. `State::Idle`.
+pub(super) fn match_arm_pattern(p: &mut Parser) {
+    if p.at_ts(expressions::LITERAL_FIRST) || p.at(T![-]) {
+        literal_pat(p);
+    } else if p.at(IDENT) && p.nth_at(1, T![::]) {
+        path_pat(p);
+    } else {
+        pattern_r(p, PATTERN_FIRST);
+    }
+}
+
 fn atom_pat(p: &mut Parser, recovery_set: TokenSet) -> Option<CompletedMarker> {
     let t1 = p.nth(0);
-    if t1 == IDENT {
+    if t1 == IDENT || t1 == T![mut] {
         return Some(bind_pat(p));
     }
 
@@ -35,8 +47,33 @@ fn placeholder_pat(p: &mut Parser) -> CompletedMarker {
     m.complete(p, PLACEHOLDER_PAT)
 }
 
+/// Parses a binding, e.g. `a` or `mut a`.
 fn bind_pat(p: &mut Parser) -> CompletedMarker {
     let m = p.start();
+    p.eat(T![mut]);
     name(p);
     m.complete(p, BIND_PAT)
 }
+
+/// Parses a literal pattern, e.g. `1` or `-1`. A negative literal is parsed as a prefix
+/// expression, so the value of the pattern is an expression.
+fn literal_pat(p: &mut Parser) -> CompletedMarker {
+    let m = p.start();
+    if p.at(T![-]) {
+        let negation = p.start();
+        p.bump(T![-]);
+        if expressions::literal(p).is_none() {
+            p.error("expected a literal");
+        }
+        negation.complete(p, PREFIX_EXPR);
+    } else {
+        expressions::literal(p);
+    }
+    m.complete(p, LITERAL_PAT)
+}
+
+fn path_pat(p: &mut Parser) -> CompletedMarker {
+    let m = p.start();
+    paths::expr_path(p);
+    m.complete(p, PATH_PAT)
+}
@@ -9,6 +9,9 @@ pub(super) fn ascription(p: &mut Parser) {
 
 pub(super) fn type_(p: &mut Parser) {
     match p.current() {
+        T!['('] => paren_or_tuple_type(p),
+        T!['['] => array_type(p),
+        T![fn] => fn_pointer_type(p),
         T![never] => never_type(p),
         _ if paths::is_path_start(p) => path_type(p),
         _ => {
@@ -23,6 +26,64 @@ pub(super) fn path_type(p: &mut Parser) {
     m.complete(p, PATH_TYPE);
 }
 
+/// Parses a parenthesized type, e.g. `(int)`, or a tuple type, e.g. `()`, `(int,)` or
+/// `(int, float)`. Only a single type without a trailing comma is a parenthesized type.
+fn paren_or_tuple_type(p: &mut Parser) {
+    assert!(p.at(T!['(']));
+    let m = p.start();
+    p.bump(T!['(']);
+    let mut n_types: u32 = 0;
+    let mut trailing_comma = false;
+    while !p.at(EOF) && !p.at(T![')']) {
+        n_types += 1;
+        type_(p);
+        if p.eat(T![,]) {
+            trailing_comma = true;
+        } else {
+            trailing_comma = false;
+            break;
+        }
+    }
+    p.expect(T![')']);
+
+    let kind = if n_types == 1 && !trailing_comma {
+        PAREN_TYPE
+    } else {
+        TUPLE_TYPE
+    };
+    m.complete(p, kind);
+}
+
+/// Parses a fixed-size array type, e.g. `[float; 16]`.
+fn array_type(p: &mut Parser) {
+    assert!(p.at(T!['[']));
+    let m = p.start();
+    p.bump(T!['[']);
+    type_(p);
+    p.expect(T![;]);
+    expressions::expr(p);
+    p.expect(T![']']);
+    m.complete(p, ARRAY_TYPE);
+}
+
+/// Parses a function pointer type, e.g. `fn(int, float): bool` or `fn()`. Like the signature of a
+/// function, a function pointer without a return type returns nothing.
+fn fn_pointer_type(p: &mut Parser) {
+    assert!(p.at(T![fn]));
+    let m = p.start();
+    p.bump(T![fn]);
+    p.expect(T!['(']);
+    while !p.at(EOF) && !p.at(T![')']) {
+        type_(p);
+        if !p.eat(T![,]) {
+            break;
+        }
+    }
+    p.expect(T![')']);
+    declarations::opt_fn_ret_type(p);
+    m.complete(p, FN_POINTER_TYPE);
+}
+
 fn never_type(p: &mut Parser) {
     assert!(p.at(T![never]));
     let m = p.start();
@@ -5,18 +5,27 @@ use crate::SyntaxKind::{self, *};
 pub(crate) fn scan_number(c: char, cursor: &mut Cursor) -> SyntaxKind {
     if c == '0' {
         match cursor.current().unwrap_or('\0') {
+            // Literals with a prefix are always integers. Decimal digits that are not valid in the
+            // radix, and missing digits (e.g. `0x`), are reported during validation.
             'b' | 'o' => {
                 cursor.bump();
                 scan_digits(cursor, false);
+                scan_literal_suffix(cursor);
+                return INT_NUMBER;
             }
             'x' => {
                 cursor.bump();
                 scan_digits(cursor, true);
+                scan_literal_suffix(cursor);
+                return INT_NUMBER;
             }
             '0'..='9' | '_' | '.' | 'e' | 'E' => {
-                scan_digits(cursor, true);
+                scan_digits(cursor, false);
+            }
+            _ => {
+                scan_literal_suffix(cursor);
+                return INT_NUMBER;
             }
-            _ => return INT_NUMBER,
         }
     } else {
         scan_digits(cursor, false);
@@ -28,17 +37,29 @@ pub(crate) fn scan_number(c: char, cursor: &mut Cursor) -> SyntaxKind {
         cursor.bump();
         scan_digits(cursor, false);
         scan_float_exponent(cursor);
+        scan_literal_suffix(cursor);
         return FLOAT_NUMBER;
     }
 
     if cursor.matches('e') || cursor.matches('E') {
         scan_float_exponent(cursor);
+        scan_literal_suffix(cursor);
         return FLOAT_NUMBER;
     }
 
+    scan_literal_suffix(cursor);
     INT_NUMBER
 }
 
+/// Scans the type suffix of a numeric literal (e.g. `u8` in `255u8`). Any identifier is accepted
+/// here, invalid suffixes are reported during validation.
+fn scan_literal_suffix(cursor: &mut Cursor) {
+    if cursor.matches_nth_if(0, is_ident_start) {
+        cursor.bump();
+        cursor.bump_while(is_ident_continue);
+    }
+}
+
 fn scan_digits(cursor: &mut Cursor, allow_hex: bool) {
     while let Some(c) = cursor.current() {
         match c {
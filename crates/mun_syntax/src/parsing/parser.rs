@@ -62,21 +62,21 @@ impl<'t> Parser<'t> {
             T![..] => self.at_composite2(n, T![.], T![.]),
             T![*=] => self.at_composite2(n, T![*], T![=]),
             T![/=] => self.at_composite2(n, T![/], T![=]),
-            //T![&&] => self.at_composite2(n, T![&], T![&]),
+            T![&&] => self.at_composite2(n, T![&], T![&]),
             //T![&=] => self.at_composite2(n, T![&], T![=]),
-            //T![%=] => self.at_composite2(n, T![%], T![=]),
+            T![%=] => self.at_composite2(n, T![%], T![=]),
             //T![^=] => self.at_composite2(n, T![^], T![=]),
             T![+=] => self.at_composite2(n, T![+], T![=]),
-            //T![<<] => self.at_composite2(n, T![<], T![<]),
+            T![<<] => self.at_composite2(n, T![<], T![<]),
             T![<=] => self.at_composite2(n, T![<], T![=]),
             T![==] => self.at_composite2(n, T![=], T![=]),
-            //T![=>] => self.at_composite2(n, T![=], T![>]),
+            T![=>] => self.at_composite2(n, T![=], T![>]),
             T![>=] => self.at_composite2(n, T![>], T![=]),
-            //T![>>] => self.at_composite2(n, T![>], T![>]),
+            T![>>] => self.at_composite2(n, T![>], T![>]),
             //T![|=] => self.at_composite2(n, T![|], T![=]),
-            //T![||] => self.at_composite2(n, T![|], T![|]),
+            T![||] => self.at_composite2(n, T![|], T![|]),
             T![...] => self.at_composite3(n, T![.], T![.], T![.]),
-            //T![..=] => self.at_composite3(n, T![.], T![.], T![=]),
+            T![..=] => self.at_composite3(n, T![.], T![.], T![=]),
             //T![<<=] => self.at_composite3(n, T![<], T![<], T![=]),
             //T![>>=] => self.at_composite3(n, T![>], T![>], T![=]),
             _ => self.token_source.lookahead_nth(n).kind == kind,
@@ -103,10 +103,10 @@ impl<'t> Parser<'t> {
         kinds.contains(self.current())
     }
 
-    //    /// Checks if the current token is contextual keyword with text `t`.
-    //    pub(crate) fn at_contextual_kw(&self, kw: &str) -> bool {
-    //        self.token_source.is_keyword(kw)
-    //    }
+    /// Checks if the current token is contextual keyword with text `t`.
+    pub(crate) fn at_contextual_kw(&self, kw: &str) -> bool {
+        self.token_source.is_keyword(kw)
+    }
 
     /// Starts a new node in the syntax tree. All nodes and tokens consumed between the `start` and
     /// the corresponding `Marker::complete` belong to the same node.
@@ -130,6 +130,15 @@ impl<'t> Parser<'t> {
         self.do_bump(kind, 1)
     }
 
+    /// Advances the parser by one token, which is added to the tree as a token of `kind`. This
+    /// turns keywords into identifiers where they are used as ordinary names.
+    pub(crate) fn bump_remap(&mut self, kind: SyntaxKind) {
+        if self.nth(0) == EOF {
+            return;
+        }
+        self.do_bump(kind, 1)
+    }
+
     fn do_bump(&mut self, kind: SyntaxKind, n_raw_tokens: u8) {
         for _ in 0..n_raw_tokens {
             self.token_source.bump();
@@ -148,9 +157,10 @@ impl<'t> Parser<'t> {
         self.error_recover(message, TokenSet::empty())
     }
 
-    /// Create an error node and consume the next token.
+    /// Create an error node and consume the next token, unless it is a brace or in `recovery`. The
+    /// parser synchronizes on those tokens, so they are left for the enclosing rule.
     pub(crate) fn error_recover(&mut self, message: &str, recovery: TokenSet) {
-        if self.at(T!['{']) || self.at(T!['{']) || self.at_ts(recovery) {
+        if self.at(T!['{']) || self.at(T!['}']) || self.at_ts(recovery) {
             self.error(message);
         } else {
             let m = self.start();
@@ -177,23 +187,23 @@ impl<'t> Parser<'t> {
             | T![..]
             | T![*=]
             | T![/=]
-            //| T![&&]
+            | T![&&]
             //| T![&=]
-            //| T![%=]
+            | T![%=]
             //| T![^=]
             | T![+=]
-            //| T![<<]
+            | T![<<]
             | T![<=]
             | T![==]
-            //| T![=>]
+            | T![=>]
             | T![>=]
-            //| T![>>]
+            | T![>>]
             //| T![|=]
-            //| T![||]
+            | T![||]
             => 2,
 
             T![...]
-            //| T![..=]
+            | T![..=]
             //| T![<<=]
             //| T![>>=]
             => 3,
@@ -142,7 +142,8 @@ fn n_attached_trivias<'a>(
     trivias: impl Iterator<Item = (SyntaxKind, &'a str)>,
 ) -> usize {
     match kind {
-        FUNCTION_DEF => trivias
+        FUNCTION_DEF | STRUCT_DEF | ENUM_DEF | CONST_DEF | STATIC_DEF | TYPE_ALIAS_DEF
+        | IMPL_BLOCK | USE_DECL => trivias
             .take_while(|(kind, text)| match kind {
                 WHITESPACE => !text.contains("\n\n"),
                 COMMENT => true,
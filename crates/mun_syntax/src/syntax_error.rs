@@ -1,3 +1,4 @@
+use crate::ast::Radix;
 use crate::parsing::ParseError;
 use mun_errors::Location;
 use std::fmt;
@@ -34,6 +35,14 @@ impl fmt::Display for SyntaxError {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SyntaxErrorKind {
     ParseError(ParseError),
+    InvalidSuffix(String),
+    InvalidRangePosition,
+    InvalidForIterable,
+    UnterminatedString,
+    UnknownEscape(char),
+    EmptyIntLiteral(Radix),
+    InvalidDigit { digit: char, radix: Radix },
+    NonDecimalFloat(Radix),
 }
 
 impl fmt::Display for SyntaxErrorKind {
@@ -41,6 +50,26 @@ impl fmt::Display for SyntaxErrorKind {
         use self::SyntaxErrorKind::*;
         match self {
             ParseError(msg) => write!(f, "{}", msg.0),
+            InvalidSuffix(suffix) => write!(f, "invalid suffix `{}` for number literal", suffix),
+            InvalidRangePosition => write!(
+                f,
+                "range expressions can only be used as the iterable of a `for` loop"
+            ),
+            InvalidForIterable => write!(f, "`for` loops can only iterate over ranges"),
+            UnterminatedString => write!(f, "unterminated string literal"),
+            UnknownEscape(c) => write!(f, "unknown character escape `\\{}`", c),
+            EmptyIntLiteral(radix) => write!(
+                f,
+                "no digits after the `{}` prefix of the {} literal",
+                radix.prefix(),
+                radix
+            ),
+            InvalidDigit { digit, radix } => {
+                write!(f, "invalid digit `{}` in {} literal", digit, radix)
+            }
+            NonDecimalFloat(radix) => {
+                write!(f, "{} literals cannot have a floating point suffix", radix)
+            }
         }
     }
 }
@@ -38,6 +38,8 @@ pub enum SyntaxKind {
     COLON,
     COMMA,
     EXCLAMATION,
+    AMP,
+    PIPE,
     UNDERSCORE,
     EQEQ,
     NEQ,
@@ -53,6 +55,11 @@ pub enum SyntaxKind {
     PERCENTEQ,
     DOTDOTEQ,
     COLONCOLON,
+    AMPAMP,
+    PIPEPIPE,
+    FAT_ARROW,
+    SHL,
+    SHR,
     AND_KW,
     BREAK_KW,
     DO_KW,
@@ -69,6 +76,7 @@ pub enum SyntaxKind {
     TRUE_KW,
     WHILE_KW,
     LOOP_KW,
+    CONTINUE_KW,
     LET_KW,
     MUT_KW,
     CLASS_KW,
@@ -77,6 +85,16 @@ pub enum SyntaxKind {
     PRIVATE_KW,
     EXPORT_KW,
     NEVER_KW,
+    STRUCT_KW,
+    EXTERN_KW,
+    CONST_KW,
+    STATIC_KW,
+    ENUM_KW,
+    AS_KW,
+    MATCH_KW,
+    IMPL_KW,
+    TYPE_KW,
+    USE_KW,
     INT_NUMBER,
     FLOAT_NUMBER,
     STRING,
@@ -88,27 +106,66 @@ pub enum SyntaxKind {
     FUNCTION_DEF,
     RET_TYPE,
     VISIBILITY,
+    ATTR,
+    ATTR_ARG_LIST,
+    ATTR_ARG,
+    STRUCT_DEF,
+    MEMORY_TYPE_SPECIFIER,
+    RECORD_FIELD_DEF_LIST,
+    RECORD_FIELD_DEF,
+    ENUM_DEF,
+    ENUM_VARIANT_LIST,
+    ENUM_VARIANT,
+    CONST_DEF,
+    STATIC_DEF,
+    TYPE_ALIAS_DEF,
+    IMPL_BLOCK,
+    USE_DECL,
+    ALIAS,
     PARAM_LIST,
     PARAM,
+    SELF_PARAM,
     PATH_TYPE,
     NEVER_TYPE,
+    PAREN_TYPE,
+    TUPLE_TYPE,
+    ARRAY_TYPE,
+    FN_POINTER_TYPE,
     LET_STMT,
     EXPR_STMT,
     PATH_EXPR,
     PREFIX_EXPR,
     LITERAL,
     BIN_EXPR,
+    RANGE_EXPR,
     PAREN_EXPR,
+    TUPLE_EXPR,
+    ARRAY_EXPR,
     CALL_EXPR,
+    METHOD_CALL_EXPR,
+    CAST_EXPR,
+    FIELD_EXPR,
+    INDEX_EXPR,
+    RECORD_LIT,
+    RECORD_FIELD_LIST,
+    RECORD_FIELD,
     IF_EXPR,
     BLOCK_EXPR,
     RETURN_EXPR,
     WHILE_EXPR,
     LOOP_EXPR,
+    FOR_EXPR,
     BREAK_EXPR,
+    CONTINUE_EXPR,
+    MATCH_EXPR,
+    MATCH_ARM_LIST,
+    MATCH_ARM,
+    CLOSURE_EXPR,
     CONDITION,
     BIND_PAT,
     PLACEHOLDER_PAT,
+    LITERAL_PAT,
+    PATH_PAT,
     ARG_LIST,
     NAME,
     NAME_REF,
@@ -143,6 +200,8 @@ macro_rules! T {
     (:) => { $crate::SyntaxKind::COLON };
     (,) => { $crate::SyntaxKind::COMMA };
     (!) => { $crate::SyntaxKind::EXCLAMATION };
+    (&) => { $crate::SyntaxKind::AMP };
+    (|) => { $crate::SyntaxKind::PIPE };
     (_) => { $crate::SyntaxKind::UNDERSCORE };
     (==) => { $crate::SyntaxKind::EQEQ };
     (!=) => { $crate::SyntaxKind::NEQ };
@@ -158,6 +217,11 @@ macro_rules! T {
     (%=) => { $crate::SyntaxKind::PERCENTEQ };
     (..=) => { $crate::SyntaxKind::DOTDOTEQ };
     (::) => { $crate::SyntaxKind::COLONCOLON };
+    (&&) => { $crate::SyntaxKind::AMPAMP };
+    (||) => { $crate::SyntaxKind::PIPEPIPE };
+    (=>) => { $crate::SyntaxKind::FAT_ARROW };
+    (<<) => { $crate::SyntaxKind::SHL };
+    (>>) => { $crate::SyntaxKind::SHR };
     (and) => { $crate::SyntaxKind::AND_KW };
     (break) => { $crate::SyntaxKind::BREAK_KW };
     (do) => { $crate::SyntaxKind::DO_KW };
@@ -174,6 +238,7 @@ macro_rules! T {
     (true) => { $crate::SyntaxKind::TRUE_KW };
     (while) => { $crate::SyntaxKind::WHILE_KW };
     (loop) => { $crate::SyntaxKind::LOOP_KW };
+    (continue) => { $crate::SyntaxKind::CONTINUE_KW };
     (let) => { $crate::SyntaxKind::LET_KW };
     (mut) => { $crate::SyntaxKind::MUT_KW };
     (class) => { $crate::SyntaxKind::CLASS_KW };
@@ -182,6 +247,16 @@ macro_rules! T {
     (private) => { $crate::SyntaxKind::PRIVATE_KW };
     (export) => { $crate::SyntaxKind::EXPORT_KW };
     (never) => { $crate::SyntaxKind::NEVER_KW };
+    (struct) => { $crate::SyntaxKind::STRUCT_KW };
+    (extern) => { $crate::SyntaxKind::EXTERN_KW };
+    (const) => { $crate::SyntaxKind::CONST_KW };
+    (static) => { $crate::SyntaxKind::STATIC_KW };
+    (enum) => { $crate::SyntaxKind::ENUM_KW };
+    (as) => { $crate::SyntaxKind::AS_KW };
+    (match) => { $crate::SyntaxKind::MATCH_KW };
+    (impl) => { $crate::SyntaxKind::IMPL_KW };
+    (type) => { $crate::SyntaxKind::TYPE_KW };
+    (use) => { $crate::SyntaxKind::USE_KW };
 }
 
 impl From<u16> for SyntaxKind {
@@ -216,6 +291,7 @@ impl SyntaxKind {
             | TRUE_KW
             | WHILE_KW
             | LOOP_KW
+            | CONTINUE_KW
             | LET_KW
             | MUT_KW
             | CLASS_KW
@@ -224,6 +300,16 @@ impl SyntaxKind {
             | PRIVATE_KW
             | EXPORT_KW
             | NEVER_KW
+            | STRUCT_KW
+            | EXTERN_KW
+            | CONST_KW
+            | STATIC_KW
+            | ENUM_KW
+            | AS_KW
+            | MATCH_KW
+            | IMPL_KW
+            | TYPE_KW
+            | USE_KW
                 => true,
             _ => false
         }
@@ -252,6 +338,8 @@ impl SyntaxKind {
                 | COLON
                 | COMMA
                 | EXCLAMATION
+                | AMP
+                | PIPE
                 | UNDERSCORE
                 | EQEQ
                 | NEQ
@@ -267,6 +355,11 @@ impl SyntaxKind {
                 | PERCENTEQ
                 | DOTDOTEQ
                 | COLONCOLON
+                | AMPAMP
+                | PIPEPIPE
+                | FAT_ARROW
+                | SHL
+                | SHR
                     => true,
                 _ => false
             }
@@ -305,6 +398,8 @@ impl SyntaxKind {
                 COLON => &SyntaxInfo { name: "COLON" },
                 COMMA => &SyntaxInfo { name: "COMMA" },
                 EXCLAMATION => &SyntaxInfo { name: "EXCLAMATION" },
+                AMP => &SyntaxInfo { name: "AMP" },
+                PIPE => &SyntaxInfo { name: "PIPE" },
                 UNDERSCORE => &SyntaxInfo { name: "UNDERSCORE" },
                 EQEQ => &SyntaxInfo { name: "EQEQ" },
                 NEQ => &SyntaxInfo { name: "NEQ" },
@@ -320,6 +415,11 @@ impl SyntaxKind {
                 PERCENTEQ => &SyntaxInfo { name: "PERCENTEQ" },
                 DOTDOTEQ => &SyntaxInfo { name: "DOTDOTEQ" },
                 COLONCOLON => &SyntaxInfo { name: "COLONCOLON" },
+                AMPAMP => &SyntaxInfo { name: "AMPAMP" },
+                PIPEPIPE => &SyntaxInfo { name: "PIPEPIPE" },
+                FAT_ARROW => &SyntaxInfo { name: "FAT_ARROW" },
+                SHL => &SyntaxInfo { name: "SHL" },
+                SHR => &SyntaxInfo { name: "SHR" },
                 AND_KW => &SyntaxInfo { name: "AND_KW" },
                 BREAK_KW => &SyntaxInfo { name: "BREAK_KW" },
                 DO_KW => &SyntaxInfo { name: "DO_KW" },
@@ -336,6 +436,7 @@ impl SyntaxKind {
                 TRUE_KW => &SyntaxInfo { name: "TRUE_KW" },
                 WHILE_KW => &SyntaxInfo { name: "WHILE_KW" },
                 LOOP_KW => &SyntaxInfo { name: "LOOP_KW" },
+                CONTINUE_KW => &SyntaxInfo { name: "CONTINUE_KW" },
                 LET_KW => &SyntaxInfo { name: "LET_KW" },
                 MUT_KW => &SyntaxInfo { name: "MUT_KW" },
                 CLASS_KW => &SyntaxInfo { name: "CLASS_KW" },
@@ -344,6 +445,16 @@ impl SyntaxKind {
                 PRIVATE_KW => &SyntaxInfo { name: "PRIVATE_KW" },
                 EXPORT_KW => &SyntaxInfo { name: "EXPORT_KW" },
                 NEVER_KW => &SyntaxInfo { name: "NEVER_KW" },
+                STRUCT_KW => &SyntaxInfo { name: "STRUCT_KW" },
+                EXTERN_KW => &SyntaxInfo { name: "EXTERN_KW" },
+                CONST_KW => &SyntaxInfo { name: "CONST_KW" },
+                STATIC_KW => &SyntaxInfo { name: "STATIC_KW" },
+                ENUM_KW => &SyntaxInfo { name: "ENUM_KW" },
+                AS_KW => &SyntaxInfo { name: "AS_KW" },
+                MATCH_KW => &SyntaxInfo { name: "MATCH_KW" },
+                IMPL_KW => &SyntaxInfo { name: "IMPL_KW" },
+                TYPE_KW => &SyntaxInfo { name: "TYPE_KW" },
+                USE_KW => &SyntaxInfo { name: "USE_KW" },
                 INT_NUMBER => &SyntaxInfo { name: "INT_NUMBER" },
                 FLOAT_NUMBER => &SyntaxInfo { name: "FLOAT_NUMBER" },
                 STRING => &SyntaxInfo { name: "STRING" },
@@ -355,27 +466,66 @@ impl SyntaxKind {
                 FUNCTION_DEF => &SyntaxInfo { name: "FUNCTION_DEF" },
                 RET_TYPE => &SyntaxInfo { name: "RET_TYPE" },
                 VISIBILITY => &SyntaxInfo { name: "VISIBILITY" },
+                ATTR => &SyntaxInfo { name: "ATTR" },
+                ATTR_ARG_LIST => &SyntaxInfo { name: "ATTR_ARG_LIST" },
+                ATTR_ARG => &SyntaxInfo { name: "ATTR_ARG" },
+                STRUCT_DEF => &SyntaxInfo { name: "STRUCT_DEF" },
+                MEMORY_TYPE_SPECIFIER => &SyntaxInfo { name: "MEMORY_TYPE_SPECIFIER" },
+                RECORD_FIELD_DEF_LIST => &SyntaxInfo { name: "RECORD_FIELD_DEF_LIST" },
+                RECORD_FIELD_DEF => &SyntaxInfo { name: "RECORD_FIELD_DEF" },
+                ENUM_DEF => &SyntaxInfo { name: "ENUM_DEF" },
+                ENUM_VARIANT_LIST => &SyntaxInfo { name: "ENUM_VARIANT_LIST" },
+                ENUM_VARIANT => &SyntaxInfo { name: "ENUM_VARIANT" },
+                CONST_DEF => &SyntaxInfo { name: "CONST_DEF" },
+                STATIC_DEF => &SyntaxInfo { name: "STATIC_DEF" },
+                TYPE_ALIAS_DEF => &SyntaxInfo { name: "TYPE_ALIAS_DEF" },
+                IMPL_BLOCK => &SyntaxInfo { name: "IMPL_BLOCK" },
+                USE_DECL => &SyntaxInfo { name: "USE_DECL" },
+                ALIAS => &SyntaxInfo { name: "ALIAS" },
                 PARAM_LIST => &SyntaxInfo { name: "PARAM_LIST" },
                 PARAM => &SyntaxInfo { name: "PARAM" },
+                SELF_PARAM => &SyntaxInfo { name: "SELF_PARAM" },
                 PATH_TYPE => &SyntaxInfo { name: "PATH_TYPE" },
                 NEVER_TYPE => &SyntaxInfo { name: "NEVER_TYPE" },
+                PAREN_TYPE => &SyntaxInfo { name: "PAREN_TYPE" },
+                TUPLE_TYPE => &SyntaxInfo { name: "TUPLE_TYPE" },
+                ARRAY_TYPE => &SyntaxInfo { name: "ARRAY_TYPE" },
+                FN_POINTER_TYPE => &SyntaxInfo { name: "FN_POINTER_TYPE" },
                 LET_STMT => &SyntaxInfo { name: "LET_STMT" },
                 EXPR_STMT => &SyntaxInfo { name: "EXPR_STMT" },
                 PATH_EXPR => &SyntaxInfo { name: "PATH_EXPR" },
                 PREFIX_EXPR => &SyntaxInfo { name: "PREFIX_EXPR" },
                 LITERAL => &SyntaxInfo { name: "LITERAL" },
                 BIN_EXPR => &SyntaxInfo { name: "BIN_EXPR" },
+                RANGE_EXPR => &SyntaxInfo { name: "RANGE_EXPR" },
                 PAREN_EXPR => &SyntaxInfo { name: "PAREN_EXPR" },
+                TUPLE_EXPR => &SyntaxInfo { name: "TUPLE_EXPR" },
+                ARRAY_EXPR => &SyntaxInfo { name: "ARRAY_EXPR" },
                 CALL_EXPR => &SyntaxInfo { name: "CALL_EXPR" },
+                METHOD_CALL_EXPR => &SyntaxInfo { name: "METHOD_CALL_EXPR" },
+                CAST_EXPR => &SyntaxInfo { name: "CAST_EXPR" },
+                FIELD_EXPR => &SyntaxInfo { name: "FIELD_EXPR" },
+                INDEX_EXPR => &SyntaxInfo { name: "INDEX_EXPR" },
+                RECORD_LIT => &SyntaxInfo { name: "RECORD_LIT" },
+                RECORD_FIELD_LIST => &SyntaxInfo { name: "RECORD_FIELD_LIST" },
+                RECORD_FIELD => &SyntaxInfo { name: "RECORD_FIELD" },
                 IF_EXPR => &SyntaxInfo { name: "IF_EXPR" },
                 BLOCK_EXPR => &SyntaxInfo { name: "BLOCK_EXPR" },
                 RETURN_EXPR => &SyntaxInfo { name: "RETURN_EXPR" },
                 WHILE_EXPR => &SyntaxInfo { name: "WHILE_EXPR" },
                 LOOP_EXPR => &SyntaxInfo { name: "LOOP_EXPR" },
+                FOR_EXPR => &SyntaxInfo { name: "FOR_EXPR" },
                 BREAK_EXPR => &SyntaxInfo { name: "BREAK_EXPR" },
+                CONTINUE_EXPR => &SyntaxInfo { name: "CONTINUE_EXPR" },
+                MATCH_EXPR => &SyntaxInfo { name: "MATCH_EXPR" },
+                MATCH_ARM_LIST => &SyntaxInfo { name: "MATCH_ARM_LIST" },
+                MATCH_ARM => &SyntaxInfo { name: "MATCH_ARM" },
+                CLOSURE_EXPR => &SyntaxInfo { name: "CLOSURE_EXPR" },
                 CONDITION => &SyntaxInfo { name: "CONDITION" },
                 BIND_PAT => &SyntaxInfo { name: "BIND_PAT" },
                 PLACEHOLDER_PAT => &SyntaxInfo { name: "PLACEHOLDER_PAT" },
+                LITERAL_PAT => &SyntaxInfo { name: "LITERAL_PAT" },
+                PATH_PAT => &SyntaxInfo { name: "PATH_PAT" },
                 ARG_LIST => &SyntaxInfo { name: "ARG_LIST" },
                 NAME => &SyntaxInfo { name: "NAME" },
                 NAME_REF => &SyntaxInfo { name: "NAME_REF" },
@@ -405,6 +555,7 @@ impl SyntaxKind {
                 "true" => TRUE_KW,
                 "while" => WHILE_KW,
                 "loop" => LOOP_KW,
+                "continue" => CONTINUE_KW,
                 "let" => LET_KW,
                 "mut" => MUT_KW,
                 "class" => CLASS_KW,
@@ -413,6 +564,16 @@ impl SyntaxKind {
                 "private" => PRIVATE_KW,
                 "export" => EXPORT_KW,
                 "never" => NEVER_KW,
+                "struct" => STRUCT_KW,
+                "extern" => EXTERN_KW,
+                "const" => CONST_KW,
+                "static" => STATIC_KW,
+                "enum" => ENUM_KW,
+                "as" => AS_KW,
+                "match" => MATCH_KW,
+                "impl" => IMPL_KW,
+                "type" => TYPE_KW,
+                "use" => USE_KW,
                 _ => return None,
             };
             Some(kw)
@@ -441,6 +602,8 @@ impl SyntaxKind {
                 ':' => COLON,
                 ',' => COMMA,
                 '!' => EXCLAMATION,
+                '&' => AMP,
+                '|' => PIPE,
                 '_' => UNDERSCORE,
                 _ => return None,
             };
@@ -0,0 +1,252 @@
+use crate::{
+    tokenize,
+    SyntaxKind::{COMMA, COMMENT, WHITESPACE},
+};
+
+fn format_snapshot(text: &str) {
+    let text = text.trim().replace("\n    ", "\n");
+    let formatted = crate::format(&text).expect("the text should not contain syntax errors");
+    assert_eq!(
+        crate::format(&formatted).unwrap(),
+        formatted,
+        "formatting formatted code should not change it"
+    );
+    insta::assert_snapshot!(insta::_macro_support::AutoName, formatted, &text);
+}
+
+#[test]
+fn items() {
+    format_snapshot(
+        r#"
+    use math::add;
+    use math::sub;
+    const A:f32=1.0;
+    const B : f32 = 2.0;
+    struct Point{x:f32,y:f32}
+    struct(value)   Unit;
+    enum Direction{Up,Down,}
+    type Seconds=f32;
+
+
+
+    #[inline( always )]
+    export   fn add( a:i32 ,b :i32 ):i32{a+b}
+    fn empty( ) { }
+    impl Point { fn new():Self{ Point{x:0.0,y:0.0} }
+        fn x(self): f32 { self.x } }"#,
+    );
+}
+
+#[test]
+fn inner_attributes() {
+    format_snapshot(
+        r#"
+    #![deny( warnings )]
+    #![allow(dead_code)]
+    #[allow(unused_variables)]
+    fn a() {}"#,
+    );
+}
+
+#[test]
+fn expressions() {
+    format_snapshot(
+        r#"
+    fn main() {
+      let a=add(1,2)  ;     let b = - a*(a+1) ;
+      let c=!(a==b)&&a!=b||a>=b;
+      a+=1;c = a as f32/2.0 ;
+      let d = [1,2,3];
+      let e = (1,) ; let f: (i32,) = e;
+      let g = |x : i32 , y| x*y;
+      let h = p . x + d[ 0 ]+e.0+p.len( );
+      ::math::sub(a , b)
+    }"#,
+    );
+}
+
+#[test]
+fn radix_literals() {
+    format_snapshot(
+        r#"
+    const MASK:u8=0xFF;
+    const MODE : u16=0o755;
+    fn main(){let a=0b1010_0001u8+MASK;}"#,
+    );
+}
+
+#[test]
+fn blocks() {
+    format_snapshot(
+        r#"
+    fn main(a: i32): i32 {
+    if a<0 { return -a } else if a==0 {0} else{ a }
+    for i in 0 .. 10 { } while true { break } loop { continue }
+    let b = match a { 1 => 2, _ => { 3 } };
+      {
+              let c = { b };
+
+
+        c
+    }
+    }"#,
+    );
+}
+
+#[test]
+fn trailing_commas() {
+    format_snapshot(
+        r#"
+    struct Flat { a: f32, b: f32, }
+    struct Tall {
+        a: f32, b: f32
+    }
+    fn main(
+        a: i32, b: i32) {
+        foo(a,b,);
+        foo(
+          a, bar(
+            b
+          ));
+        let p = Point {
+            x: a,
+            y: b };
+        let t = (a,);
+    }"#,
+    );
+}
+
+#[test]
+fn comments() {
+    format_snapshot(
+        r#"
+    // The license of the file
+
+    /// Adds two numbers
+    fn add(a: i32, // the first number
+         b: i32): i32 { // adds the numbers
+         // leading comment
+      a /* inline */ + b   // trailing comment
+
+
+      // last comment
+    }
+    fn empty() {
+        // todo
+    }
+    /* unused */ fn other() {}"#,
+    );
+}
+
+#[test]
+fn syntax_errors() {
+    // Files that do not parse are not formatted, as that could lose code
+    let errors = crate::format("fn main() { let a = ; }").unwrap_err();
+    assert!(!errors.is_empty());
+}
+
+/// The code of which the whitespace is changed in `idempotent_for_any_whitespace`.
+const CORPUS: &str = r#"
+// Computes the fibonacci sequence
+export fn fibonacci(n: i64): i64 {
+    if n <= 1 {
+        n
+    } else {
+        fibonacci(n - 1) + fibonacci(n - 2) // recursive
+    }
+}
+
+struct Vector {
+    x: f32,
+    y: f32, /* the second component */
+}
+
+fn sum(
+    values: [f32; 10],
+    weight: f32,
+): f32 {
+    let total = 0.0;
+    for i in 0..10 {
+        total += values[i] * weight;
+    }
+
+    match total as i32 {
+        0 => 0.0,
+        _ => {
+            let v = Vector { x: total, y: -total };
+            v.x / v.y
+        }
+    }
+}
+"#;
+
+/// Returns the text of the tokens of `text` that are not whitespace, comments or commas.
+fn code_tokens(text: &str) -> Vec<String> {
+    let mut offset = 0;
+    let mut result = Vec::new();
+    for token in tokenize(text) {
+        let len: u32 = token.len.into();
+        let token_text = &text[offset..offset + len as usize];
+        offset += len as usize;
+        if ![WHITESPACE, COMMENT, COMMA].contains(&token.kind) {
+            result.push(token_text.to_string());
+        }
+    }
+    result
+}
+
+/// Formats the corpus with the whitespace between its tokens replaced by pseudo-random whitespace.
+/// The formatted code must contain the same tokens, and formatting it again must not change it.
+#[test]
+fn idempotent_for_any_whitespace() {
+    const WHITESPACE_OPTIONS: [&str; 6] = [" ", "   ", "\t", "\n", "\n\n\n", " \n        "];
+
+    let mut seed: u32 = 0x2545_f491;
+    for _ in 0..100 {
+        let mut text = String::new();
+        let mut offset = 0;
+        let mut after_line_comment = false;
+        for token in tokenize(CORPUS) {
+            let len: u32 = token.len.into();
+            let token_text = &CORPUS[offset..offset + len as usize];
+            offset += len as usize;
+            if token.kind == WHITESPACE {
+                // Xorshift, which is deterministic and good enough to vary the whitespace
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                let mut options = WHITESPACE_OPTIONS.iter();
+                let whitespace = if after_line_comment {
+                    // The end of a line comment is the end of its line
+                    options
+                        .filter(|ws| ws.contains('\n'))
+                        .nth(seed as usize % 3)
+                        .unwrap()
+                } else {
+                    options
+                        .nth(seed as usize % WHITESPACE_OPTIONS.len())
+                        .unwrap()
+                };
+                text.push_str(whitespace);
+            } else {
+                text.push_str(token_text);
+            }
+            after_line_comment = token.kind == COMMENT && token_text.starts_with("//");
+        }
+
+        let formatted = crate::format(&text)
+            .unwrap_or_else(|errors| panic!("{:?} in the code:\n{}", errors, text));
+        assert_eq!(
+            code_tokens(&formatted),
+            code_tokens(&text),
+            "formatting changed the code:\n{}",
+            text
+        );
+        assert_eq!(
+            crate::format(&formatted).unwrap(),
+            formatted,
+            "formatting formatted code changed it, the original code is:\n{}",
+            text
+        );
+    }
+}
@@ -34,6 +34,33 @@ fn numbers() {
     )
 }
 
+#[test]
+fn radix_numbers() {
+    lex_snapshot(
+        r#"
+    0b1010_0001
+    0o755
+    0xFFu8
+    0x1e5
+    0x
+    0b102
+    0b1.5"#,
+    )
+}
+
+#[test]
+fn number_suffixes() {
+    lex_snapshot(
+        r#"
+    255u8
+    1.5f32
+    1e3f64
+    0xffu8
+    1_000_i32
+    1..2"#,
+    )
+}
+
 #[test]
 fn comments() {
     lex_snapshot(
@@ -86,6 +113,8 @@ fn symbols() {
     ^ ^=
     % %=
     : ::
+    & &&
+    | ||
     "#,
     )
 }
@@ -110,7 +139,7 @@ fn keywords() {
         r#"
     and break do else false for fn if in nil
     return true while let mut class public protected
-    private never loop
+    private never loop continue extern const static
     "#,
     )
 }
@@ -1,4 +1,4 @@
-use crate::SourceFile;
+use crate::{ast, ast::NameOwner, AstNode, SourceFile};
 
 fn ok_snapshot_test(text: &str) {
     let text = text.trim().replace("\n    ", "\n");
@@ -32,6 +32,106 @@ fn function() {
     );
 }
 
+#[test]
+fn extern_fn() {
+    ok_snapshot_test(
+        r#"
+    extern fn log_value(v:float);
+    extern fn add(a:int, b:int):int;"#,
+    );
+}
+
+#[test]
+fn const_def() {
+    ok_snapshot_test(
+        r#"
+    const GRAVITY: float = -9.81;
+    /// Seconds per hour
+    export const SECONDS: int = 60 * 60;"#,
+    );
+}
+
+#[test]
+fn static_def() {
+    ok_snapshot_test(
+        r#"
+    static mut score: int = 0;
+    /// The speed of the player
+    export static SPEED: float = 2.5;"#,
+    );
+}
+
+#[test]
+fn type_alias_def() {
+    ok_snapshot_test(
+        r#"
+    type Seconds = float;
+    export type Pair = (int, Seconds);"#,
+    );
+}
+
+#[test]
+fn use_decl() {
+    ok_snapshot_test(
+        r#"
+    use math::add;
+    use shapes::square::area as square_area;
+    use ::math::sub;
+    use super::util::*;"#,
+    );
+}
+
+#[test]
+fn attributes() {
+    ok_snapshot_test(
+        r#"
+    #[inline]
+    fn a() {}
+    #[inline(always)]
+    export fn b() {}
+    #[inline(never, always)]
+    fn c() {}"#,
+    );
+}
+
+#[test]
+fn inner_attributes() {
+    ok_snapshot_test(
+        r#"
+    #![deny(warnings)]
+    #![allow(unused_variables, dead_code)]
+    #[allow(unreachable_code)]
+    fn a() {}"#,
+    );
+}
+
+#[test]
+fn misplaced_inner_attributes() {
+    let file = SourceFile::parse(
+        r#"
+    fn a() {}
+    #![deny(warnings)]
+    fn b() {}
+    "#,
+    );
+    let errors: Vec<String> = file.errors().iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        errors,
+        vec!["inner attributes are only allowed at the start of a file"]
+    );
+}
+
+#[test]
+fn struct_attributes() {
+    ok_snapshot_test(
+        r#"
+    #[repr(C, packed)]
+    struct(value) Foo;
+    #[repr(align(16))]
+    export struct Bar;"#,
+    );
+}
+
 #[test]
 fn block() {
     ok_snapshot_test(
@@ -83,6 +183,18 @@ fn binary_expr() {
     )
 }
 
+#[test]
+fn shift_ops() {
+    ok_snapshot_test(
+        r#"
+    fn foo() {
+        let a = 1<<4+1
+        let b = a>>2<3
+    }
+    "#,
+    )
+}
+
 #[test]
 fn expression_statement() {
     ok_snapshot_test(
@@ -123,6 +235,20 @@ fn patterns() {
     )
 }
 
+#[test]
+fn mutable_bindings() {
+    ok_snapshot_test(
+        r#"
+    impl Foo {
+        fn bar(mut self, mut a: int) {
+            let mut b = a;
+            for mut i in 0..b {}
+        }
+    }
+    "#,
+    )
+}
+
 #[test]
 fn compare_operands() {
     ok_snapshot_test(
@@ -200,6 +326,19 @@ fn break_expr() {
     )
 }
 
+#[test]
+fn continue_expr() {
+    ok_snapshot_test(
+        r#"
+    fn foo() {
+        loop {
+            continue;
+        }
+    }
+    "#,
+    )
+}
+
 #[test]
 fn while_expr() {
     ok_snapshot_test(
@@ -211,3 +350,384 @@ fn while_expr() {
     "#,
     )
 }
+
+#[test]
+fn for_expr() {
+    ok_snapshot_test(
+        r#"
+    fn foo(n:int) {
+        for i in 0..n {}
+        for _ in 0..=n + 1 {
+            i;
+        }
+    }
+    "#,
+    )
+}
+
+#[test]
+fn logic_ops() {
+    ok_snapshot_test(
+        r#"
+    fn foo() {
+        let a = a && b || c
+        let b = a < b && !c
+    }
+    "#,
+    )
+}
+
+#[test]
+fn struct_def() {
+    ok_snapshot_test(
+        r#"
+    struct Foo;
+    struct Foo {}
+    struct Foo {
+        a: float,
+        b: int,
+    }
+    "#,
+    )
+}
+
+#[test]
+fn struct_memory_type_specifier() {
+    ok_snapshot_test(
+        r#"
+    struct(gc) Foo;
+    struct(value) Foo {}
+    struct(gc) Foo {
+        a: float,
+    }
+    "#,
+    )
+}
+
+#[test]
+fn enum_def() {
+    ok_snapshot_test(
+        r#"
+    enum Foo {}
+    enum State {
+        Idle,
+        Walking = 2,
+        Attacking
+    }
+    "#,
+    )
+}
+
+#[test]
+fn cast_expr() {
+    ok_snapshot_test(
+        r#"
+    fn foo() {
+        let a = State::Idle as u8;
+        let b = -a as i16 + b as i16;
+    }
+    "#,
+    )
+}
+
+#[test]
+fn module_paths() {
+    ok_snapshot_test(
+        r#"
+    fn foo() {
+        super::bar();
+        self::baz;
+        ::root::qux;
+    }
+    "#,
+    )
+}
+
+#[test]
+fn impl_block() {
+    ok_snapshot_test(
+        r#"
+    impl Vector {
+        fn new(): Vector {}
+        export fn len(self): float { self.x }
+        fn scale(self, a: float) {}
+    }
+    "#,
+    )
+}
+
+#[test]
+fn match_expr() {
+    ok_snapshot_test(
+        r#"
+    fn foo() {
+        match a {
+            0 => 1,
+            -1 => { 2 }
+            State::Idle => b,
+            true => c,
+            _ => 3,
+        }
+    }
+    "#,
+    )
+}
+
+#[test]
+fn field_expr() {
+    ok_snapshot_test(
+        r#"
+    fn foo() {
+        a.b;
+        a.b.c;
+        foo().b;
+    }
+    "#,
+    )
+}
+
+#[test]
+fn method_call_expr() {
+    ok_snapshot_test(
+        r#"
+    fn foo() {
+        a.b();
+        a.b(1, c).d.e();
+    }
+    "#,
+    )
+}
+
+#[test]
+fn record_lit() {
+    ok_snapshot_test(
+        r#"
+    fn foo() {
+        Foo {};
+        Foo { a: 1, b: a };
+        Foo { a: Bar { b: true } }
+    }
+    "#,
+    )
+}
+
+#[test]
+fn tuples() {
+    ok_snapshot_test(
+        r#"
+    fn foo(a:(int, float), b:(int,), c:()):(int) {
+        let t = (1, 2.0);
+        let u = (t.0,);
+        let e = ();
+        (a.1)
+    }
+    "#,
+    )
+}
+
+#[test]
+fn arrays() {
+    ok_snapshot_test(
+        r#"
+    fn foo(a:[float; 4]):[int; 2] {
+        let b = [1, 2,];
+        let c = [];
+        a[0] = 1.0;
+        [b[1], 3]
+    }
+    "#,
+    )
+}
+
+#[test]
+fn fn_pointer_type() {
+    ok_snapshot_test(
+        r#"
+    extern fn apply(f:fn(int, bool):int, g:fn()):bool;"#,
+    )
+}
+
+#[test]
+fn closure_expr() {
+    ok_snapshot_test(
+        r#"
+    fn main() {
+        let f = |a, b: int| a < b;
+        let g = ||: int { 5 };
+        apply(|_| 1);
+    }
+    "#,
+    )
+}
+
+#[test]
+fn literal_suffixes() {
+    let file = SourceFile::parse(
+        r#"
+    fn foo() {
+        let a = 255u8;
+        let b = 1.5f32;
+        let c = 1f64;
+        let d = 1u7;
+        let e = 1.5u8;
+    }
+    "#,
+    );
+    let errors: Vec<String> = file.errors().iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        errors,
+        vec![
+            "invalid suffix `u7` for number literal",
+            "invalid suffix `u8` for number literal"
+        ]
+    );
+}
+
+#[test]
+fn radix_literals() {
+    let file = SourceFile::parse(
+        r#"
+    fn foo() {
+        let a = 0xFFu8;
+        let b = 0b1010_0001;
+        let c = 0o755u16;
+        let d = 0x;
+        let e = 0b102;
+        let f = 0o8_u8;
+        let g = 0b1f32;
+        let h = 0xffu7;
+    }
+    "#,
+    );
+    let errors: Vec<String> = file.errors().iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        errors,
+        vec![
+            "no digits after the `0x` prefix of the hexadecimal literal",
+            "invalid digit `2` in binary literal",
+            "invalid digit `8` in octal literal",
+            "binary literals cannot have a floating point suffix",
+            "invalid suffix `u7` for number literal"
+        ]
+    );
+}
+
+#[test]
+fn array_length() {
+    // Lengths are evaluated at compile time, so invalid lengths are reported by the compiler
+    let file = SourceFile::parse(
+        r#"
+    fn foo(a:[int; 4], b:[int; 4 * (1 << 2)], c:[int; n], d:[int; 1.5]) {}
+    "#,
+    );
+    assert_eq!(file.errors(), &[] as &[crate::SyntaxError]);
+}
+
+#[test]
+fn range_position() {
+    let file = SourceFile::parse(
+        r#"
+    fn foo(n:int) {
+        let a = 0..n;
+        for i in n {}
+    }
+    "#,
+    );
+    let errors: Vec<String> = file.errors().iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        errors,
+        vec![
+            "range expressions can only be used as the iterable of a `for` loop",
+            "`for` loops can only iterate over ranges"
+        ]
+    );
+}
+
+#[test]
+fn string_literals() {
+    let file = SourceFile::parse(
+        r#"
+    fn foo() {
+        let a = "game_over";
+        let b = "tab\tquote\"backslash\\";
+        let c = "\q";
+    }
+    "#,
+    );
+    let errors: Vec<String> = file.errors().iter().map(|e| e.to_string()).collect();
+    assert_eq!(errors, vec!["unknown character escape `\\q`"]);
+
+    let values: Vec<Option<String>> = file
+        .syntax_node()
+        .descendants()
+        .filter_map(ast::Literal::cast)
+        .map(|literal| literal.string_value())
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            Some("game_over".to_string()),
+            Some("tab\tquote\"backslash\\".to_string()),
+            None
+        ]
+    );
+
+    let file = SourceFile::parse(r#"fn foo() { let a = "unterminated; }"#);
+    let errors: Vec<String> = file.errors().iter().map(|e| e.to_string()).collect();
+    assert!(errors.contains(&"unterminated string literal".to_string()));
+}
+
+#[test]
+fn error_recovery() {
+    let file = SourceFile::parse(
+        r#"
+    fn foo() {
+        let a = ;
+        let b = 1
+    }
+
+    struct Bar { x: int y: float }
+
+    fn baz() {
+        let c = 3 +
+    }
+
+    fn qux() {
+        let d = 1;
+
+    fn ok() {
+        let e = 1;
+    }
+    }
+    fn last() {}
+    "#,
+    );
+    let errors: Vec<String> = file.errors().iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        errors,
+        vec![
+            "expected expression",
+            "expected COMMA",
+            "expected expression",
+            "expected R_CURLY",
+            "unmatched }"
+        ]
+    );
+
+    // The declarations after the errors are still parsed
+    let names: Vec<String> = file
+        .syntax_node()
+        .descendants()
+        .filter_map(ast::FunctionDef::cast)
+        .filter_map(|f| f.name())
+        .map(|name| name.text().to_string())
+        .collect();
+    assert_eq!(names, vec!["foo", "baz", "qux", "ok", "last"]);
+}
+
+#[test]
+fn error_recovery_terminates() {
+    let text = "fn { } } ) ( let ;; struct { fn ( : fn match { => } impl { export # [ enum | , ";
+    let file = SourceFile::parse(&text.repeat(100));
+    assert!(!file.errors().is_empty());
+}
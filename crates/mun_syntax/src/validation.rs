@@ -0,0 +1,112 @@
+use crate::ast::{unescape_string, IntLiteralError, Radix, StringLiteralError};
+use crate::{ast, AstNode, SyntaxError, SyntaxErrorKind, SyntaxNode};
+
+/// The suffixes that can be used to specify the type of an integer literal.
+const INT_SUFFIXES: [&str; 8] = ["i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64"];
+
+/// The suffixes that can be used to specify the type of a floating point literal.
+const FLOAT_SUFFIXES: [&str; 2] = ["f32", "f64"];
+
+/// Performs checks on the syntax tree which are not enforced by the parser.
+pub(crate) fn validate(root: &SyntaxNode) -> Vec<SyntaxError> {
+    let mut errors = Vec::new();
+    for node in root.descendants() {
+        if let Some(literal) = ast::Literal::cast(node.clone()) {
+            validate_literal(literal, &mut errors);
+        } else if let Some(range) = ast::RangeExpr::cast(node.clone()) {
+            validate_range_expr(range, &mut errors);
+        } else if let Some(for_expr) = ast::ForExpr::cast(node) {
+            validate_for_expr(for_expr, &mut errors);
+        }
+    }
+    errors
+}
+
+/// Checks that the suffix of a numeric literal is a known type. Decimal integer literals may also
+/// be suffixed with a floating point type (e.g. `1f32`). The digits of an integer literal must be
+/// valid in its radix, e.g. `0b` must be followed by binary digits. String literals must be
+/// terminated and only contain known escape sequences.
+fn validate_literal(literal: ast::Literal, errors: &mut Vec<SyntaxError>) {
+    let suffix = match literal.kind() {
+        ast::LiteralKind::String => {
+            if let Err(err) = unescape_string(literal.token().text()) {
+                let kind = match err {
+                    StringLiteralError::Unterminated => SyntaxErrorKind::UnterminatedString,
+                    StringLiteralError::UnknownEscape(c) => SyntaxErrorKind::UnknownEscape(c),
+                };
+                errors.push(SyntaxError::new(kind, literal.syntax().text_range()));
+            }
+            return;
+        }
+        ast::LiteralKind::IntNumber { suffix } => {
+            let radix = literal.radix();
+            let kind = match literal.int_value() {
+                Err(IntLiteralError::Empty) => Some(SyntaxErrorKind::EmptyIntLiteral(radix)),
+                Err(IntLiteralError::InvalidDigit(digit)) => {
+                    Some(SyntaxErrorKind::InvalidDigit { digit, radix })
+                }
+                // Values that don't fit are reported during type inference
+                Ok(_) | Err(IntLiteralError::Overflow) => None,
+            };
+            if let Some(kind) = kind {
+                errors.push(SyntaxError::new(kind, literal.syntax().text_range()));
+            }
+
+            let is_float_suffix = suffix
+                .as_ref()
+                .map_or(false, |s| FLOAT_SUFFIXES.contains(&s.as_str()));
+            if is_float_suffix && radix != Radix::Decimal {
+                errors.push(SyntaxError::new(
+                    SyntaxErrorKind::NonDecimalFloat(radix),
+                    literal.syntax().text_range(),
+                ));
+                return;
+            }
+            suffix.filter(|s| !INT_SUFFIXES.contains(&s.as_str()) && !is_float_suffix)
+        }
+        ast::LiteralKind::FloatNumber { suffix } => {
+            suffix.filter(|s| !FLOAT_SUFFIXES.contains(&s.as_str()))
+        }
+        _ => None,
+    };
+
+    if let Some(suffix) = suffix {
+        errors.push(SyntaxError::new(
+            SyntaxErrorKind::InvalidSuffix(suffix.to_string()),
+            literal.syntax().text_range(),
+        ));
+    }
+}
+
+/// Checks that a range expression is only used as the iterable of a `for` loop, e.g. `0..n` in
+/// `for i in 0..n {}`.
+fn validate_range_expr(range: ast::RangeExpr, errors: &mut Vec<SyntaxError>) {
+    let is_iterable = range
+        .syntax()
+        .parent()
+        .and_then(ast::ForExpr::cast)
+        .and_then(|for_expr| for_expr.iterable())
+        .map_or(false, |iterable| iterable.syntax() == range.syntax());
+    if !is_iterable {
+        errors.push(SyntaxError::new(
+            SyntaxErrorKind::InvalidRangePosition,
+            range.syntax().text_range(),
+        ));
+    }
+}
+
+/// Checks that a `for` loop iterates over a range.
+fn validate_for_expr(for_expr: ast::ForExpr, errors: &mut Vec<SyntaxError>) {
+    let iterable = match for_expr.iterable() {
+        Some(iterable) => iterable,
+        // A missing iterable is already reported by the parser
+        None => return,
+    };
+    if let ast::ExprKind::RangeExpr(_) = iterable.kind() {
+        return;
+    }
+    errors.push(SyntaxError::new(
+        SyntaxErrorKind::InvalidForIterable,
+        iterable.syntax().text_range(),
+    ));
+}
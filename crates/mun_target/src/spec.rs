@@ -17,6 +17,9 @@ pub struct Target {
     /// Target triple to pass to LLVM
     pub llvm_target: String,
 
+    /// Data layout to pass to LLVM
+    pub data_layout: String,
+
     /// The name of the OS
     pub target_os: String,
 
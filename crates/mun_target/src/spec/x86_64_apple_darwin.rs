@@ -12,6 +12,7 @@ pub fn target() -> TargetResult {
 
     Ok(Target {
         llvm_target,
+        data_layout: "e-m:o-i64:64-f80:128-n8:16:32:64-S128".to_string(),
         target_os: "macos".to_string(),
         target_env: String::new(),
         target_vendor: "apple".to_string(),
@@ -6,6 +6,7 @@ pub fn target() -> TargetResult {
 
     Ok(Target {
         llvm_target: "x86_64-pc-windows-msvc".to_string(),
+        data_layout: "e-m:w-i64:64-f80:128-n8:16:32:64-S128".to_string(),
         target_os: "windows".to_string(),
         target_env: "msvc".to_string(),
         target_vendor: "pc".to_string(),
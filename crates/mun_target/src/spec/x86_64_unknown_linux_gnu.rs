@@ -6,6 +6,7 @@ pub fn target() -> TargetResult {
 
     Ok(Target {
         llvm_target: "x86_64-unknown-linux-gnu".to_string(),
+        data_layout: "e-m:e-i64:64-f80:128-n8:16:32:64-S128".to_string(),
         target_os: "linux".to_string(),
         target_env: "gnu".to_string(),
         target_vendor: "unknown".to_string(),